@@ -14,9 +14,16 @@ export const CORE_PERMISSIONS = new Set([
 ]);
 
 export const OPTIONAL_PERMISSIONS = new Set([
+  'document:selection',
   'fs:pick-read',
   'fs:pick-write',
   'network:https',
+  'network:credentials',
+  'clipboard:read',
+  'clipboard:write',
+  'ui:notify',
+  'scheduler:tasks',
+  'events:bus',
   'ui:mount',
   'editor:annotations',
   'system:fonts',
@@ -73,6 +80,7 @@ const TOP_LEVEL_KEYS = new Set([
   'enabledApiProposals',
   'permissionRationales',
   'signature',
+  'defaultLocale',
 ]);
 
 const CONTRIBUTION_KEYS = [
@@ -87,6 +95,8 @@ const CONTRIBUTION_KEYS = [
   'uiControls',
   'uiPanels',
   'transforms',
+  'themes',
+  'dictionaries',
 ];
 
 const IGNORED_PACKAGE_ENTRIES = new Set(['.DS_Store']);
@@ -96,6 +106,26 @@ export function isNonEmptyString(value) {
   return typeof value === 'string' && value.trim().length > 0;
 }
 
+const ALLOWLIST_LABEL = /^[a-z0-9](?:[a-z0-9-]{0,61}[a-z0-9])?$/;
+
+export function isValidAllowlistEntry(entry) {
+  const match = /^(\*\.)?([^:]+)(?::(\d{1,5}))?$/.exec(entry.trim().toLowerCase());
+  if (!match) {
+    return false;
+  }
+
+  const [, wildcard, host, port] = match;
+  if (port && (Number(port) < 1 || Number(port) > 65535)) {
+    return false;
+  }
+  if (/^\d+(\.\d+){3}$/.test(host)) {
+    return false;
+  }
+
+  const labels = host.split('.');
+  return labels.every((label) => ALLOWLIST_LABEL.test(label)) && (!wildcard || labels.length >= 2);
+}
+
 export function isSemverLike(value) {
   return typeof value === 'string' && /^[0-9]+\.[0-9]+\.[0-9]+(?:[-+].*)?$/.test(value);
 }
@@ -121,6 +151,7 @@ export function isValidActivationEvent(value) {
     'onUIPanel:',
     'onStatusBadge:',
     'onInlineAnnotations:',
+    'onSchedule:',
   ];
 
   for (const prefix of prefixes) {
@@ -222,6 +253,8 @@ function createEmptyContributions() {
     uiControls: [],
     uiPanels: [],
     transforms: [],
+    themes: [],
+    dictionaries: [],
   };
 }
 
@@ -252,6 +285,8 @@ function validateContributes(contributes, errors) {
   validateContributedIdUniqueness(normalized.uiControls, errors, 'contributes.uiControls');
   validateContributedIdUniqueness(normalized.uiPanels, errors, 'contributes.uiPanels');
   validateContributedIdUniqueness(normalized.transforms, errors, 'contributes.transforms');
+  validateContributedIdUniqueness(normalized.themes, errors, 'contributes.themes');
+  validateContributedIdUniqueness(normalized.dictionaries, errors, 'contributes.dictionaries');
 
   for (const item of normalized.commands) {
     if (!item || typeof item !== 'object') {
@@ -449,9 +484,107 @@ function validateContributes(contributes, errors) {
     }
   }
 
+  for (const item of normalized.themes) {
+    if (!item || typeof item !== 'object') {
+      pushError(errors, 'contributes.themes entries must be objects');
+      continue;
+    }
+    if (!isValidLocalId(item.id)) {
+      pushError(errors, `Invalid contributes.themes id: ${String(item.id)}`);
+    }
+    if (!isNonEmptyString(item.label)) {
+      pushError(errors, `contributes.themes '${String(item.id)}' label is required`);
+    }
+    if (item.base !== 'light' && item.base !== 'dark') {
+      pushError(errors, `contributes.themes '${String(item.id)}' base must be light or dark`);
+    }
+    if (
+      !isNonEmptyString(item.path)
+      || item.path.includes('..')
+      || item.path.startsWith('/')
+      || !item.path.endsWith('.json')
+    ) {
+      pushError(errors, `contributes.themes '${String(item.id)}' path must be a relative .json file`);
+    }
+  }
+
+  for (const item of normalized.dictionaries) {
+    if (!item || typeof item !== 'object') {
+      pushError(errors, 'contributes.dictionaries entries must be objects');
+      continue;
+    }
+    if (!isValidLocalId(item.id)) {
+      pushError(errors, `Invalid contributes.dictionaries id: ${String(item.id)}`);
+    }
+    if (!isNonEmptyString(item.label)) {
+      pushError(errors, `contributes.dictionaries '${String(item.id)}' label is required`);
+    }
+    if (typeof item.language !== 'string' || !/^[a-z]{2,3}(-[A-Za-z0-9]{2,8})*$/.test(item.language)) {
+      pushError(errors, `contributes.dictionaries '${String(item.id)}' has invalid language`);
+    }
+    if (item.kind !== 'language' && item.kind !== 'wordlist') {
+      pushError(errors, `contributes.dictionaries '${String(item.id)}' kind must be language or wordlist`);
+    }
+    if (
+      !isNonEmptyString(item.path)
+      || item.path.includes('..')
+      || item.path.startsWith('/')
+      || !item.path.endsWith('.txt')
+    ) {
+      pushError(errors, `contributes.dictionaries '${String(item.id)}' path must be a relative .txt file`);
+    }
+  }
+
   return normalized;
 }
 
+const PLACEHOLDER_RE = /^%([a-zA-Z0-9._-]+)%$/;
+
+function collectPlaceholders(value, keys) {
+  if (typeof value === 'string') {
+    const match = PLACEHOLDER_RE.exec(value);
+    if (match) keys.add(match[1]);
+  } else if (Array.isArray(value)) {
+    value.forEach((item) => collectPlaceholders(item, keys));
+  } else if (value && typeof value === 'object') {
+    Object.values(value).forEach((item) => collectPlaceholders(item, keys));
+  }
+}
+
+// Schema v2: `%key%` placeholders resolve from locales/<defaultLocale>.json.
+function validateLocales(manifest, errors, options) {
+  if (!isNonEmptyString(manifest.defaultLocale)) {
+    pushError(errors, 'defaultLocale is required when schemaVersion is 2');
+    return;
+  }
+
+  if (!options.checkEntry || !options.rootDir) {
+    return;
+  }
+
+  const localePath = path.join(options.rootDir, 'locales', `${manifest.defaultLocale}.json`);
+  if (!fs.existsSync(localePath)) {
+    pushError(errors, `default locale file does not exist: locales/${manifest.defaultLocale}.json`);
+    return;
+  }
+
+  let messages;
+  try {
+    messages = readJsonFile(localePath);
+  } catch (error) {
+    pushError(errors, `Failed to parse locales/${manifest.defaultLocale}.json: ${error instanceof Error ? error.message : String(error)}`);
+    return;
+  }
+
+  const keys = new Set();
+  collectPlaceholders([manifest.name, manifest.description, manifest.contributes], keys);
+  for (const key of keys) {
+    if (typeof messages[key] !== 'string') {
+      pushError(errors, `locales/${manifest.defaultLocale}.json is missing message: ${key}`);
+    }
+  }
+}
+
 export function validatePluginManifest(manifest, options = {}) {
   const errors = [];
   const warnings = [];
@@ -462,8 +595,12 @@ export function validatePluginManifest(manifest, options = {}) {
 
   validateNoUnknownKeys(manifest, TOP_LEVEL_KEYS, errors, 'manifest');
 
-  if (manifest.schemaVersion !== 1) {
-    pushError(errors, 'schemaVersion must equal 1');
+  if (manifest.schemaVersion !== 1 && manifest.schemaVersion !== 2) {
+    pushError(errors, 'schemaVersion must equal 1 or 2');
+  }
+
+  if (manifest.schemaVersion === 2) {
+    validateLocales(manifest, errors, options);
   }
 
   if (!isValidPluginId(manifest.id)) {
@@ -523,6 +660,11 @@ export function validatePluginManifest(manifest, options = {}) {
   for (const host of networkAllowlist) {
     if (!isNonEmptyString(host)) {
       pushError(errors, 'networkAllowlist entries must be non-empty strings');
+    } else if (!isValidAllowlistEntry(host)) {
+      pushError(
+        errors,
+        `networkAllowlist entry '${host}' must be a host name or *.domain, optionally with :port`
+      );
     }
   }
   if (networkAllowlist.length > 0 && !optionalPermissions.includes('network:https')) {
@@ -543,7 +685,8 @@ export function validatePluginManifest(manifest, options = {}) {
   const contributes = validateContributes(manifest.contributes, errors);
 
   for (const event of activationEvents) {
-    if (event === 'onStartup' || typeof event !== 'string') {
+    // Scheduled tasks are registered at runtime, not contributed.
+    if (event === 'onStartup' || typeof event !== 'string' || event.startsWith('onSchedule:')) {
       continue;
     }
 
@@ -0,0 +1,124 @@
+use super::pages::PageEstimate;
+use crate::document;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterStats {
+    pub name: String,
+    /// Number of speeches (character cues) for this character.
+    pub line_count: usize,
+    pub word_count: usize,
+    /// 1-based positions of the scenes the character speaks in.
+    pub scenes: Vec<usize>,
+    pub first_page: u32,
+    pub last_page: u32,
+    /// Share of all dialogue words, as a percentage.
+    pub dialogue_percentage: f64,
+    pub extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterReport {
+    pub characters: Vec<CharacterStats>,
+    pub total_dialogue_words: usize,
+    pub total_speeches: usize,
+    pub total_pages: u32,
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+pub fn analyze(root: &Value, pages: &PageEstimate) -> CharacterReport {
+    let blocks = document::blocks(root);
+    let scenes = document::scenes(root);
+    let scene_by_block = document::scene_index_by_block(&scenes, blocks.len());
+
+    let mut order = Vec::new();
+    let mut stats: HashMap<String, CharacterStats> = HashMap::new();
+    let mut speaker: Option<String> = None;
+
+    for (block_index, block) in blocks.iter().enumerate() {
+        let page = pages.block_pages.get(block_index).copied().unwrap_or(1);
+
+        match document::node_type(block) {
+            "character" => {
+                let name = document::character_cue_name(&document::node_text(block));
+                if name.is_empty() {
+                    speaker = None;
+                    continue;
+                }
+
+                let entry = stats.entry(name.clone()).or_insert_with(|| {
+                    order.push(name.clone());
+                    CharacterStats {
+                        name: name.clone(),
+                        line_count: 0,
+                        word_count: 0,
+                        scenes: Vec::new(),
+                        first_page: page,
+                        last_page: page,
+                        dialogue_percentage: 0.0,
+                        extensions: Vec::new(),
+                    }
+                });
+
+                entry.line_count += 1;
+                entry.last_page = page;
+                if let Some(scene_index) = scene_by_block[block_index] {
+                    if !entry.scenes.contains(&(scene_index + 1)) {
+                        entry.scenes.push(scene_index + 1);
+                    }
+                }
+                if let Some(extension) = document::node_attr_str(block, "extension") {
+                    if !entry.extensions.iter().any(|item| item == extension) {
+                        entry.extensions.push(extension.to_string());
+                    }
+                }
+
+                speaker = Some(name);
+            }
+            "dialogue" => {
+                if let Some(entry) = speaker.as_ref().and_then(|name| stats.get_mut(name)) {
+                    entry.word_count += word_count(&document::node_text(block));
+                    entry.last_page = page;
+                }
+            }
+            "parenthetical" => {}
+            _ => speaker = None,
+        }
+    }
+
+    let total_dialogue_words = stats.values().map(|entry| entry.word_count).sum::<usize>();
+    let total_speeches = stats.values().map(|entry| entry.line_count).sum::<usize>();
+
+    let mut characters = order
+        .into_iter()
+        .filter_map(|name| stats.remove(&name))
+        .map(|mut entry| {
+            if total_dialogue_words > 0 {
+                entry.dialogue_percentage =
+                    entry.word_count as f64 * 100.0 / total_dialogue_words as f64;
+            }
+            entry
+        })
+        .collect::<Vec<_>>();
+
+    characters.sort_by(|a, b| {
+        b.word_count
+            .cmp(&a.word_count)
+            .then_with(|| b.line_count.cmp(&a.line_count))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    CharacterReport {
+        characters,
+        total_dialogue_words,
+        total_speeches,
+        total_pages: pages.total_pages,
+    }
+}
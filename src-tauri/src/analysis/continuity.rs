@@ -0,0 +1,298 @@
+use crate::document::{self, slugline};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Transitions that are conventionally written without a trailing `TO:`.
+const TRANSITION_EXCEPTIONS: [&str; 6] = [
+    "FADE IN:",
+    "FADE OUT.",
+    "FADE TO BLACK.",
+    "CUT TO BLACK.",
+    "SMASH CUT TO BLACK.",
+    "THE END",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuityDiagnostic {
+    /// Stable identifier: `missing-time`, `unknown-prefix`, `location-typo`
+    /// or `transition-format`.
+    pub code: String,
+    pub severity: String,
+    pub message: String,
+    pub block_index: usize,
+    /// Character offsets into the block's text.
+    pub start: usize,
+    pub end: usize,
+    /// Editor positions of the same range in the document.
+    pub from: usize,
+    pub to: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+/// Where a block sits in the document: its index, the editor position its
+/// text starts at, and the text.
+struct BlockAt<'a> {
+    index: usize,
+    text_start: usize,
+    text: &'a str,
+}
+
+impl BlockAt<'_> {
+    /// Editor position of character `offset` in the block's text.
+    fn position(&self, offset: usize) -> usize {
+        self.text_start
+            + self
+                .text
+                .chars()
+                .take(offset)
+                .map(char::len_utf16)
+                .sum::<usize>()
+    }
+}
+
+fn diagnostic(
+    code: &str,
+    severity: &str,
+    message: String,
+    block: &BlockAt,
+    (start, end): (usize, usize),
+) -> ContinuityDiagnostic {
+    ContinuityDiagnostic {
+        code: code.to_string(),
+        severity: severity.to_string(),
+        message,
+        block_index: block.index,
+        start,
+        end,
+        from: block.position(start),
+        to: block.position(end),
+        suggestion: None,
+    }
+}
+
+/// Character range of the trimmed text within `text`.
+fn trimmed_range(text: &str) -> (usize, usize) {
+    let start = text.chars().take_while(|c| c.is_whitespace()).count();
+    (start, start + text.trim().chars().count())
+}
+
+/// Character range of the last match of `needle` within `text`, compared
+/// case-insensitively. Characters are upper-cased one at a time so the range
+/// counts characters of `text` itself, since upper-casing can change the
+/// length (ß becomes SS).
+fn find_range(text: &str, needle: &str) -> Option<(usize, usize)> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let needle = needle
+        .chars()
+        .flat_map(char::to_uppercase)
+        .collect::<Vec<_>>();
+    if needle.is_empty() {
+        return None;
+    }
+
+    (0..chars.len()).rev().find_map(|start| {
+        let mut matched = 0;
+        for (offset, c) in chars[start..].iter().enumerate() {
+            for upper in c.to_uppercase() {
+                if needle.get(matched) != Some(&upper) {
+                    return None;
+                }
+                matched += 1;
+            }
+            if matched == needle.len() {
+                return Some((start, start + offset + 1));
+            }
+        }
+        None
+    })
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, left) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, right) in b.iter().enumerate() {
+            let cost = usize::from(left != right);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Picks the most-used location within a small edit distance of a location
+/// that only appears once.
+fn likely_location<'a>(location: &str, counts: &'a HashMap<String, usize>) -> Option<&'a str> {
+    let allowed = if location.chars().count() < 8 { 1 } else { 2 };
+    counts
+        .iter()
+        .filter(|(other, count)| {
+            **count > 1 && other.as_str() != location && edit_distance(location, other) <= allowed
+        })
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(other, _)| other.as_str())
+}
+
+pub fn analyze(root: &Value) -> Vec<ContinuityDiagnostic> {
+    let blocks = document::blocks(root);
+    let texts = blocks.iter().map(document::node_text).collect::<Vec<_>>();
+    let mut diagnostics = Vec::new();
+    let mut locations = Vec::new();
+    let mut block_start = 0;
+
+    for (index, block) in blocks.iter().enumerate() {
+        let text = &texts[index];
+        let at = BlockAt {
+            index,
+            text_start: block_start + 1,
+            text,
+        };
+        block_start += document::node_size(block);
+
+        match document::node_type(block) {
+            "sceneHeading" => {
+                let parts = slugline::parse(text);
+
+                if parts.int_ext.is_empty() {
+                    diagnostics.push(diagnostic(
+                        "unknown-prefix",
+                        "warning",
+                        "Scene heading does not start with INT., EXT., INT./EXT. or EST."
+                            .to_string(),
+                        &at,
+                        trimmed_range(text),
+                    ));
+                }
+
+                if parts.time.is_empty() {
+                    diagnostics.push(diagnostic(
+                        "missing-time",
+                        "warning",
+                        "Scene heading has no time of day (e.g. DAY or NIGHT)".to_string(),
+                        &at,
+                        trimmed_range(text),
+                    ));
+                }
+
+                if !parts.location.is_empty() {
+                    locations.push((at, parts.location));
+                }
+            }
+            "transition" => {
+                let upper = text.trim().to_uppercase();
+                if !upper.is_empty()
+                    && !upper.ends_with("TO:")
+                    && !TRANSITION_EXCEPTIONS.contains(&upper.as_str())
+                {
+                    diagnostics.push(diagnostic(
+                        "transition-format",
+                        "info",
+                        "Transition does not end in \"TO:\"".to_string(),
+                        &at,
+                        trimmed_range(text),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for (_, location) in &locations {
+        *counts.entry(location.clone()).or_default() += 1;
+    }
+
+    for (at, location) in &locations {
+        if counts[location] != 1 {
+            continue;
+        }
+
+        if let Some(likely) = likely_location(location, &counts) {
+            let range = find_range(at.text, location).unwrap_or_else(|| trimmed_range(at.text));
+            let mut item = diagnostic(
+                "location-typo",
+                "info",
+                format!(
+                    "Location \"{}\" appears only once; did you mean \"{}\"?",
+                    location, likely
+                ),
+                at,
+                range,
+            );
+            item.suggestion = Some(likely.to_string());
+            diagnostics.push(item);
+        }
+    }
+
+    diagnostics.sort_by_key(|item| item.block_index);
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    #[test]
+    fn flags_slugline_and_transition_problems() {
+        let root = json!({
+            "type": "doc",
+            "content": [
+                block("sceneHeading", "INT. FARMHOUSE KITCHEN - DAY"),
+                block("transition", "CUT TO:"),
+                block("sceneHeading", "INT. FARMHOUSE KITCHEN - NIGHT"),
+                block("transition", "DISSOLVE"),
+                block("sceneHeading", "int. farmhouse kitchn"),
+                block("sceneHeading", "BACKYARD - DAY"),
+            ]
+        });
+
+        let codes = analyze(&root)
+            .into_iter()
+            .map(|item| (item.code, item.block_index, item.start, item.end))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            codes,
+            vec![
+                ("transition-format".to_string(), 3, 0, 8),
+                ("missing-time".to_string(), 4, 0, 21),
+                ("location-typo".to_string(), 4, 5, 21),
+                ("unknown-prefix".to_string(), 5, 0, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn ranges_follow_the_original_text() {
+        let root = json!({
+            "type": "doc",
+            "content": [
+                block("sceneHeading", "INT. STRASSE - DAY"),
+                block("sceneHeading", "INT. STRASSE - NIGHT"),
+                { "type": "pageBreak" },
+                block("sceneHeading", "int. straßen - day"),
+            ]
+        });
+
+        let typos = analyze(&root)
+            .into_iter()
+            .map(|item| (item.block_index, item.start, item.end, item.from, item.to))
+            .collect::<Vec<_>>();
+
+        // "straßen" is seven characters even though "STRASSEN" is eight
+        assert_eq!(typos, vec![(3, 5, 12, 49, 56)]);
+        assert_eq!(find_range("Straße straße", "STRASSE"), Some((7, 13)));
+        assert_eq!(find_range("STRASS", "STRASSE"), None);
+    }
+}
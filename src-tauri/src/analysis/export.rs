@@ -0,0 +1,85 @@
+use super::characters::{self, CharacterReport};
+use super::continuity::{self, ContinuityDiagnostic};
+use super::pacing::{self, RuntimeOptions, RuntimeReport};
+use super::pages;
+use super::repetition::{self, RepetitionOptions, RepetitionReport};
+use super::scenes::{self, SceneReport};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Bumped whenever a field is removed or changes meaning, so external tools
+/// can tell which layout they are reading. New fields don't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Every analysis of a script in one document, for dashboards and studio
+/// tracking systems.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisExport {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub generated_at: String,
+    pub title: String,
+    pub total_pages: u32,
+    pub characters: CharacterReport,
+    pub scenes: SceneReport,
+    /// Runtime per scene; `runtime.pages` is the per-page pacing.
+    pub runtime: RuntimeReport,
+    pub repetition: RepetitionReport,
+    pub continuity: Vec<ContinuityDiagnostic>,
+}
+
+pub fn build(
+    root: &Value,
+    title: &str,
+    runtime_options: &RuntimeOptions,
+    generated_at: String,
+) -> AnalysisExport {
+    let pages = pages::estimate_pages(root);
+    AnalysisExport {
+        schema_version: SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at,
+        title: title.to_string(),
+        total_pages: pages.total_pages,
+        characters: characters::analyze(root, &pages),
+        scenes: scenes::analyze(root, &pages),
+        runtime: pacing::analyze(root, &pages, runtime_options),
+        repetition: repetition::analyze(root, &RepetitionOptions::default()),
+        continuity: continuity::analyze(root),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn exports_every_report_under_camel_case_keys() {
+        let root = json!({
+            "type": "doc",
+            "content": [
+                { "type": "sceneHeading", "content": [{ "type": "text", "text": "INT. KITCHEN - DAY" }] },
+                { "type": "action", "content": [{ "type": "text", "text": "Jane waits." }] },
+                { "type": "character", "content": [{ "type": "text", "text": "JANE" }] },
+                { "type": "dialogue", "content": [{ "type": "text", "text": "Well?" }] },
+            ],
+        });
+        let export = build(
+            &root,
+            "Pilot",
+            &RuntimeOptions::default(),
+            "2026-01-01T00:00:00Z".to_string(),
+        );
+        let value = serde_json::to_value(&export).unwrap();
+
+        assert_eq!(value["schemaVersion"], SCHEMA_VERSION);
+        assert_eq!(value["totalPages"], 1);
+        assert_eq!(value["characters"]["characters"][0]["name"], "JANE");
+        assert_eq!(value["scenes"]["scenes"][0]["location"], "KITCHEN");
+        assert!(value["runtime"]["pages"].is_array());
+        assert!(value["repetition"]["crutchWords"].is_array());
+        assert!(value["continuity"].is_array());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{document, jobs, reports};
+use std::fs;
+use tauri::AppHandle;
+
+mod characters;
+mod continuity;
+mod export;
+mod pacing;
+pub(crate) mod pages;
+mod production;
+mod repetition;
+mod scenes;
+
+#[tauri::command]
+pub async fn analyze_characters(
+    app: AppHandle,
+    content_json: String,
+) -> Result<characters::CharacterReport, String> {
+    jobs::run(&app, "analysis", "Analyzing characters", move |_| {
+        let root = document::parse_json(&content_json)?;
+        let pages = pages::estimate_pages(&root);
+        Ok(characters::analyze(&root, &pages))
+    })
+    .await
+}
+
+/// Lists every scene; when `format` and `output_path` are given the report is
+/// also written as CSV or PDF.
+#[tauri::command]
+pub async fn scene_report(
+    app: AppHandle,
+    content_json: String,
+    document_title: Option<String>,
+    format: Option<String>,
+    output_path: Option<String>,
+) -> Result<scenes::SceneReport, String> {
+    jobs::run(&app, "report", "Building scene report", move |job| {
+        let root = document::parse_json(&content_json)?;
+        let pages = pages::estimate_pages(&root);
+        let report = scenes::analyze(&root, &pages);
+
+        if let (Some(format), Some(output_path)) = (format, output_path) {
+            let format = reports::ReportFormat::parse(&format)?;
+            let title = document_title.unwrap_or_else(|| "Untitled".to_string());
+            job.check_cancelled()?;
+            job.progress(None, "Writing report");
+            reports::write_report(&scenes::to_report(&report, &title), format, &output_path)?;
+        }
+
+        Ok(report)
+    })
+    .await
+}
+
+/// Writes one of the production reports (`cast`, `location`, `day-night` or
+/// `stripboard`), as a PDF unless `format` asks for CSV or XLSX.
+#[tauri::command]
+pub async fn production_report(
+    app: AppHandle,
+    content_json: String,
+    kind: String,
+    output_path: String,
+    document_title: Option<String>,
+    format: Option<String>,
+) -> Result<(), String> {
+    jobs::run(&app, "report", "Building production report", move |job| {
+        let kind = production::ProductionReportKind::parse(&kind)?;
+        let format = match format {
+            Some(format) => reports::ReportFormat::parse(&format)?,
+            None => reports::ReportFormat::Pdf,
+        };
+        let root = document::parse_json(&content_json)?;
+        let pages = pages::estimate_pages(&root);
+        let scene_report = scenes::analyze(&root, &pages);
+        let title = document_title.unwrap_or_else(|| "Untitled".to_string());
+
+        job.check_cancelled()?;
+        job.progress(None, "Writing report");
+        reports::write_report(
+            &production::build(kind, &scene_report, &title),
+            format,
+            &output_path,
+        )
+    })
+    .await
+}
+
+/// Estimates runtime per scene and in total, with per-page dialogue/action
+/// pacing data for the pacing graph.
+#[tauri::command]
+pub async fn analyze_runtime(
+    app: AppHandle,
+    content_json: String,
+    options: Option<pacing::RuntimeOptions>,
+) -> Result<pacing::RuntimeReport, String> {
+    jobs::run(&app, "analysis", "Estimating runtime", move |_| {
+        let root = document::parse_json(&content_json)?;
+        let pages = pages::estimate_pages(&root);
+        Ok(pacing::analyze(&root, &pages, &options.unwrap_or_default()))
+    })
+    .await
+}
+
+/// Finds crutch words and over-used phrases, with locations for highlighting.
+#[tauri::command]
+pub async fn analyze_repetition(
+    app: AppHandle,
+    content_json: String,
+    options: Option<repetition::RepetitionOptions>,
+) -> Result<repetition::RepetitionReport, String> {
+    jobs::run(&app, "analysis", "Finding repetition", move |_| {
+        let root = document::parse_json(&content_json)?;
+        Ok(repetition::analyze(&root, &options.unwrap_or_default()))
+    })
+    .await
+}
+
+/// Lint-style checks on scene headings and transitions.
+#[tauri::command]
+pub fn check_continuity(
+    content_json: String,
+) -> Result<Vec<continuity::ContinuityDiagnostic>, String> {
+    let root = document::parse_json(&content_json)?;
+    Ok(continuity::analyze(&root))
+}
+
+/// Writes every analysis as one JSON document to `output_path`, for
+/// dashboards and tracking tools outside the app.
+#[tauri::command]
+pub async fn export_analysis_json(
+    app: AppHandle,
+    content_json: String,
+    output_path: String,
+    document_title: Option<String>,
+    options: Option<pacing::RuntimeOptions>,
+) -> Result<(), String> {
+    jobs::run(&app, "export", "Exporting analysis", move |job| {
+        let root = document::parse_json(&content_json)?;
+        let title = document_title.unwrap_or_else(|| "Untitled".to_string());
+        let export = export::build(
+            &root,
+            &title,
+            &options.unwrap_or_default(),
+            chrono::Utc::now().to_rfc3339(),
+        );
+
+        job.check_cancelled()?;
+        job.progress(None, "Writing analysis");
+        let json = serde_json::to_string_pretty(&export)
+            .map_err(|error| format!("Failed to serialize analysis: {}", error))?;
+        fs::write(&output_path, json)
+            .map_err(|error| format!("Failed to write analysis: {}", error))
+    })
+    .await
+}
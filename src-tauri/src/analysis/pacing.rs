@@ -0,0 +1,158 @@
+use super::pages::PageEstimate;
+use crate::document;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const DEFAULT_DIALOGUE_WPM: f64 = 150.0;
+// Action reads faster than it plays; this rate approximates screen time.
+const DEFAULT_ACTION_WPM: f64 = 60.0;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeOptions {
+    #[serde(default)]
+    pub dialogue_wpm: Option<f64>,
+    #[serde(default)]
+    pub action_wpm: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneRuntime {
+    pub number: String,
+    pub heading: String,
+    pub start_seconds: f64,
+    /// One page per minute, from the scene length in eighths.
+    pub page_seconds: f64,
+    /// Words spoken at `dialogueWpm` plus action at `actionWpm`.
+    pub speech_seconds: f64,
+    pub estimated_seconds: f64,
+    pub dialogue_words: usize,
+    pub action_words: usize,
+    /// Dialogue words over all dialogue + action words, 0..=1.
+    pub dialogue_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PagePacing {
+    pub page: u32,
+    pub dialogue_words: usize,
+    pub action_words: usize,
+    pub dialogue_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeReport {
+    pub scenes: Vec<SceneRuntime>,
+    pub pages: Vec<PagePacing>,
+    pub page_seconds: f64,
+    pub speech_seconds: f64,
+    pub estimated_seconds: f64,
+}
+
+fn ratio(dialogue: usize, action: usize) -> f64 {
+    if dialogue + action == 0 {
+        0.0
+    } else {
+        dialogue as f64 / (dialogue + action) as f64
+    }
+}
+
+/// Which side of the dialogue/action split a block's words count towards.
+fn word_bucket(node_type: &str) -> Option<bool> {
+    match node_type {
+        "dialogue" | "parenthetical" => Some(true),
+        "action" | "caption" | "soundEffect" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn analyze(root: &Value, pages: &PageEstimate, options: &RuntimeOptions) -> RuntimeReport {
+    let dialogue_wpm = options
+        .dialogue_wpm
+        .filter(|wpm| *wpm > 0.0)
+        .unwrap_or(DEFAULT_DIALOGUE_WPM);
+    let action_wpm = options
+        .action_wpm
+        .filter(|wpm| *wpm > 0.0)
+        .unwrap_or(DEFAULT_ACTION_WPM);
+    let blocks = document::blocks(root);
+
+    let mut page_pacing = (1..=pages.total_pages)
+        .map(|page| PagePacing {
+            page,
+            dialogue_words: 0,
+            action_words: 0,
+            dialogue_ratio: 0.0,
+        })
+        .collect::<Vec<_>>();
+
+    for (block_index, block) in blocks.iter().enumerate() {
+        let Some(is_dialogue) = word_bucket(document::node_type(block)) else {
+            continue;
+        };
+        let page = pages.block_pages.get(block_index).copied().unwrap_or(1);
+        let words = document::node_text(block).split_whitespace().count();
+        if let Some(entry) = page_pacing.get_mut(page as usize - 1) {
+            if is_dialogue {
+                entry.dialogue_words += words;
+            } else {
+                entry.action_words += words;
+            }
+        }
+    }
+
+    for entry in &mut page_pacing {
+        entry.dialogue_ratio = ratio(entry.dialogue_words, entry.action_words);
+    }
+
+    let mut elapsed = 0.0;
+    let scenes = document::scenes(root)
+        .into_iter()
+        .map(|scene| {
+            let mut dialogue_words = 0;
+            let mut action_words = 0;
+            for block in &blocks[scene.heading_block..scene.end_block] {
+                let words = document::node_text(block).split_whitespace().count();
+                match word_bucket(document::node_type(block)) {
+                    Some(true) => dialogue_words += words,
+                    Some(false) => action_words += words,
+                    None => {}
+                }
+            }
+
+            let page_seconds =
+                pages.eighths(scene.heading_block, scene.end_block) as f64 * 60.0 / 8.0;
+            let speech_seconds = dialogue_words as f64 * 60.0 / dialogue_wpm
+                + action_words as f64 * 60.0 / action_wpm;
+            let estimated_seconds = (page_seconds + speech_seconds) / 2.0;
+            let start_seconds = elapsed;
+            elapsed += estimated_seconds;
+
+            SceneRuntime {
+                number: scene
+                    .scene_number
+                    .clone()
+                    .unwrap_or_else(|| (scene.index + 1).to_string()),
+                heading: scene.heading,
+                start_seconds,
+                page_seconds,
+                speech_seconds,
+                estimated_seconds,
+                dialogue_words,
+                action_words,
+                dialogue_ratio: ratio(dialogue_words, action_words),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    RuntimeReport {
+        page_seconds: scenes.iter().map(|scene| scene.page_seconds).sum(),
+        speech_seconds: scenes.iter().map(|scene| scene.speech_seconds).sum(),
+        estimated_seconds: elapsed,
+        scenes,
+        pages: page_pacing,
+    }
+}
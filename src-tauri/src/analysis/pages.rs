@@ -0,0 +1,56 @@
+use crate::document;
+use crate::pagination::{self, PaperSize};
+use serde_json::Value;
+
+/// Page layout on Letter paper, as the PDF export prints it: the 1-based page
+/// each block starts on, its absolute line offset from the top of page one,
+/// and the total page count.
+pub struct PageEstimate {
+    pub block_pages: Vec<u32>,
+    pub block_lines: Vec<usize>,
+    pub total_pages: u32,
+    pub total_lines: usize,
+    pub lines_per_page: usize,
+}
+
+impl PageEstimate {
+    /// Length of a block range in eighths of a page, as
+    /// `Pagination::eighths` counts it.
+    pub fn eighths(&self, start_block: usize, end_block: usize) -> u32 {
+        let start = self
+            .block_lines
+            .get(start_block)
+            .copied()
+            .unwrap_or(self.total_lines);
+        let end = self
+            .block_lines
+            .get(end_block)
+            .copied()
+            .unwrap_or(self.total_lines);
+        pagination::lines_to_eighths(end.saturating_sub(start), self.lines_per_page)
+    }
+}
+
+/// Formats eighths the way schedules print them, e.g. `1 3/8` or `5/8`.
+pub fn format_eighths(eighths: u32) -> String {
+    match (eighths / 8, eighths % 8) {
+        (0, remainder) => format!("{}/8", remainder),
+        (pages, 0) => pages.to_string(),
+        (pages, remainder) => format!("{} {}/8", pages, remainder),
+    }
+}
+
+pub fn estimate_pages(root: &Value) -> PageEstimate {
+    estimate_block_pages(document::blocks(root))
+}
+
+pub fn estimate_block_pages(blocks: &[Value]) -> PageEstimate {
+    let layout = pagination::paginate(blocks, PaperSize::Letter);
+    PageEstimate {
+        block_pages: layout.blocks.iter().map(|block| block.page).collect(),
+        block_lines: layout.blocks.iter().map(|block| block.line).collect(),
+        total_pages: layout.total_pages,
+        total_lines: layout.total_lines,
+        lines_per_page: layout.lines_per_page,
+    }
+}
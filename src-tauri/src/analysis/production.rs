@@ -0,0 +1,263 @@
+use super::pages::format_eighths;
+use super::scenes::{SceneReport, SceneReportEntry};
+use crate::document::slugline;
+use crate::reports::{Report, ReportColumn, ReportSection};
+use std::collections::BTreeMap;
+
+const DAY_NIGHT_ORDER: [&str; 5] = ["DAY", "NIGHT", "DAWN", "DUSK", "OTHER"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProductionReportKind {
+    Cast,
+    Location,
+    DayNight,
+    Stripboard,
+}
+
+impl ProductionReportKind {
+    pub fn parse(kind: &str) -> Result<Self, String> {
+        match kind.to_ascii_lowercase().as_str() {
+            "cast" => Ok(Self::Cast),
+            "location" | "locations" => Ok(Self::Location),
+            "day-night" | "daynight" => Ok(Self::DayNight),
+            "stripboard" => Ok(Self::Stripboard),
+            _ => Err(format!("Unknown production report '{}'", kind)),
+        }
+    }
+}
+
+fn scene_numbers(scenes: &[&SceneReportEntry]) -> String {
+    scenes
+        .iter()
+        .map(|scene| scene.number.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn total_eighths(scenes: &[&SceneReportEntry]) -> u32 {
+    scenes.iter().map(|scene| scene.eighths).sum()
+}
+
+/// Characters with their scenes, in cast-number order: the usual
+/// convention of most scenes first.
+fn numbered_cast(scene_report: &SceneReport) -> Vec<(&str, Vec<&SceneReportEntry>)> {
+    let mut cast: BTreeMap<&str, Vec<&SceneReportEntry>> = BTreeMap::new();
+    for scene in &scene_report.scenes {
+        for name in &scene.characters {
+            cast.entry(name.as_str()).or_default().push(scene);
+        }
+    }
+
+    let mut cast = cast.into_iter().collect::<Vec<_>>();
+    cast.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+    cast
+}
+
+fn cast_report(scene_report: &SceneReport, title: &str) -> Report {
+    let cast = numbered_cast(scene_report);
+
+    Report {
+        title: format!("{} - Cast List", title),
+        subtitle: Some(format!("{} speaking roles", cast.len())),
+        sections: vec![ReportSection {
+            heading: None,
+            columns: vec![
+                ReportColumn::new("#", 4),
+                ReportColumn::new("Character", 24),
+                ReportColumn::new("Scenes", 6),
+                ReportColumn::new("Pages", 7),
+                ReportColumn::new("Scene Numbers", 45),
+            ],
+            rows: cast
+                .iter()
+                .enumerate()
+                .map(|(index, (name, scenes))| {
+                    vec![
+                        (index + 1).to_string(),
+                        name.to_string(),
+                        scenes.len().to_string(),
+                        format_eighths(total_eighths(scenes)),
+                        scene_numbers(scenes),
+                    ]
+                })
+                .collect(),
+        }],
+    }
+}
+
+fn location_report(scene_report: &SceneReport, title: &str) -> Report {
+    let mut locations: BTreeMap<&str, Vec<&SceneReportEntry>> = BTreeMap::new();
+    for scene in &scene_report.scenes {
+        locations
+            .entry(scene.location.as_str())
+            .or_default()
+            .push(scene);
+    }
+
+    Report {
+        title: format!("{} - Location Report", title),
+        subtitle: Some(format!("{} locations", locations.len())),
+        sections: vec![ReportSection {
+            heading: None,
+            columns: vec![
+                ReportColumn::new("Location", 28),
+                ReportColumn::new("I/E", 10),
+                ReportColumn::new("Scenes", 6),
+                ReportColumn::new("Pages", 7),
+                ReportColumn::new("Scene Numbers", 35),
+            ],
+            rows: locations
+                .iter()
+                .map(|(location, scenes)| {
+                    let mut int_ext = Vec::new();
+                    for scene in scenes {
+                        if !scene.int_ext.is_empty() && !int_ext.contains(&scene.int_ext.as_str()) {
+                            int_ext.push(scene.int_ext.as_str());
+                        }
+                    }
+
+                    vec![
+                        location.to_string(),
+                        int_ext.join(", "),
+                        scenes.len().to_string(),
+                        format_eighths(total_eighths(scenes)),
+                        scene_numbers(scenes),
+                    ]
+                })
+                .collect(),
+        }],
+    }
+}
+
+fn day_night_report(scene_report: &SceneReport, title: &str) -> Report {
+    let mut buckets: BTreeMap<&str, Vec<&SceneReportEntry>> = BTreeMap::new();
+    for scene in &scene_report.scenes {
+        buckets
+            .entry(slugline::day_night(&scene.time))
+            .or_default()
+            .push(scene);
+    }
+
+    let total = scene_report.total_eighths.max(1) as f64;
+
+    Report {
+        title: format!("{} - Day/Night Breakdown", title),
+        subtitle: Some(format!(
+            "{} scenes, {} pages",
+            scene_report.scenes.len(),
+            format_eighths(scene_report.total_eighths)
+        )),
+        sections: vec![ReportSection {
+            heading: None,
+            columns: vec![
+                ReportColumn::new("Time", 8),
+                ReportColumn::new("Scenes", 6),
+                ReportColumn::new("Pages", 7),
+                ReportColumn::new("Share", 6),
+                ReportColumn::new("Scene Numbers", 55),
+            ],
+            rows: DAY_NIGHT_ORDER
+                .iter()
+                .filter_map(|bucket| {
+                    let scenes = buckets.get(bucket)?;
+                    let eighths = total_eighths(scenes);
+                    Some(vec![
+                        bucket.to_string(),
+                        scenes.len().to_string(),
+                        format_eighths(eighths),
+                        format!("{:.0}%", eighths as f64 * 100.0 / total),
+                        scene_numbers(scenes),
+                    ])
+                })
+                .collect(),
+        }],
+    }
+}
+
+/// One strip per scene, grouped by set and then day/night the way a board
+/// is first laid out. Cast ids match the cast list, so the columns map
+/// directly onto a scheduling program's import fields.
+fn stripboard_report(scene_report: &SceneReport, title: &str) -> Report {
+    let cast_ids = numbered_cast(scene_report)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, _))| (name, index + 1))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut groups: BTreeMap<(&str, usize), Vec<&SceneReportEntry>> = BTreeMap::new();
+    for scene in &scene_report.scenes {
+        let day_night = slugline::day_night(&scene.time);
+        let order = DAY_NIGHT_ORDER
+            .iter()
+            .position(|bucket| *bucket == day_night)
+            .unwrap_or(DAY_NIGHT_ORDER.len());
+        groups
+            .entry((scene.location.as_str(), order))
+            .or_default()
+            .push(scene);
+    }
+
+    let columns = vec![
+        ReportColumn::new("Scene", 5),
+        ReportColumn::new("I/E", 7),
+        ReportColumn::new("Set", 20),
+        ReportColumn::new("D/N", 5),
+        ReportColumn::new("Pages", 6),
+        ReportColumn::new("Page", 4),
+        ReportColumn::new("Cast IDs", 10),
+        ReportColumn::new("Cast", 22),
+    ];
+
+    Report {
+        title: format!("{} - Stripboard", title),
+        subtitle: Some(format!(
+            "{} strips, {} pages",
+            scene_report.scenes.len(),
+            format_eighths(scene_report.total_eighths)
+        )),
+        sections: groups
+            .into_iter()
+            .map(|((location, order), scenes)| {
+                let day_night = DAY_NIGHT_ORDER.get(order).copied().unwrap_or("OTHER");
+                ReportSection {
+                    heading: Some(format!("{} - {}", location, day_night)),
+                    columns: columns.clone(),
+                    rows: scenes
+                        .iter()
+                        .map(|scene| {
+                            let mut ids = scene
+                                .characters
+                                .iter()
+                                .filter_map(|name| cast_ids.get(name.as_str()).copied())
+                                .collect::<Vec<_>>();
+                            ids.sort_unstable();
+
+                            vec![
+                                scene.number.clone(),
+                                scene.int_ext.clone(),
+                                location.to_string(),
+                                day_night.to_string(),
+                                format_eighths(scene.eighths),
+                                scene.page.to_string(),
+                                ids.iter()
+                                    .map(usize::to_string)
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                                scene.characters.join(", "),
+                            ]
+                        })
+                        .collect(),
+                }
+            })
+            .collect(),
+    }
+}
+
+pub fn build(kind: ProductionReportKind, scene_report: &SceneReport, title: &str) -> Report {
+    match kind {
+        ProductionReportKind::Cast => cast_report(scene_report, title),
+        ProductionReportKind::Location => location_report(scene_report, title),
+        ProductionReportKind::DayNight => day_night_report(scene_report, title),
+        ProductionReportKind::Stripboard => stripboard_report(scene_report, title),
+    }
+}
@@ -0,0 +1,334 @@
+use crate::document;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+const DEFAULT_CRUTCH_WORDS: [&str; 14] = [
+    "suddenly",
+    "we see",
+    "we hear",
+    "begins to",
+    "starts to",
+    "just",
+    "very",
+    "really",
+    "slowly",
+    "quickly",
+    "somehow",
+    "actually",
+    "literally",
+    "in order to",
+];
+const STOPWORDS: [&str; 40] = [
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "he", "her", "his", "i",
+    "in", "is", "it", "its", "me", "my", "of", "on", "or", "she", "so", "that", "the", "their",
+    "them", "they", "this", "to", "up", "was", "we", "with", "you", "your", "out",
+];
+const DEFAULT_MIN_PHRASE_COUNT: usize = 3;
+const DEFAULT_MIN_WORD_COUNT: usize = 8;
+const DEFAULT_MAX_PHRASE_WORDS: usize = 4;
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepetitionOptions {
+    /// Restricts the scan to these node types (e.g. `["action"]`); empty means all.
+    #[serde(default)]
+    pub element_types: Vec<String>,
+    /// Replaces the built-in crutch word list when given.
+    #[serde(default)]
+    pub crutch_words: Option<Vec<String>>,
+    #[serde(default)]
+    pub min_phrase_count: Option<usize>,
+    #[serde(default)]
+    pub min_word_count: Option<usize>,
+    #[serde(default)]
+    pub max_phrase_words: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhraseLocation {
+    pub block_index: usize,
+    pub element_type: String,
+    /// Character offsets into the block's text.
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhraseUsage {
+    pub phrase: String,
+    pub count: usize,
+    pub locations: Vec<PhraseLocation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepetitionReport {
+    pub crutch_words: Vec<PhraseUsage>,
+    /// Multi-word phrases used at least `minPhraseCount` times.
+    pub repeated_phrases: Vec<PhraseUsage>,
+    /// Single words (minus stopwords and character names) used at least
+    /// `minWordCount` times.
+    pub repeated_words: Vec<PhraseUsage>,
+}
+
+struct Token {
+    word: String,
+    start: usize,
+    end: usize,
+    /// True when sentence punctuation separates this token from the previous one.
+    breaks_before: bool,
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    let mut pending_break = false;
+
+    for (offset, c) in text.chars().chain(std::iter::once(' ')).enumerate() {
+        if c.is_alphanumeric() || (c == '\'' && !current.is_empty()) {
+            if current.is_empty() {
+                start = offset;
+            }
+            current.extend(c.to_lowercase());
+            continue;
+        }
+
+        if !current.is_empty() {
+            tokens.push(Token {
+                word: current.trim_end_matches('\'').to_string(),
+                start,
+                end: offset,
+                breaks_before: pending_break,
+            });
+            current.clear();
+            pending_break = false;
+        }
+
+        if matches!(c, '.' | '!' | '?' | ';' | ':' | '\u{2014}' | '\u{2026}') {
+            pending_break = true;
+        }
+    }
+
+    tokens
+}
+
+fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word)
+}
+
+fn sorted_usages(
+    usages: HashMap<String, Vec<PhraseLocation>>,
+    min_count: usize,
+) -> Vec<PhraseUsage> {
+    let mut usages = usages
+        .into_iter()
+        .filter(|(_, locations)| locations.len() >= min_count)
+        .map(|(phrase, locations)| PhraseUsage {
+            phrase,
+            count: locations.len(),
+            locations,
+        })
+        .collect::<Vec<_>>();
+    usages.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.phrase.cmp(&b.phrase)));
+    usages
+}
+
+pub fn analyze(root: &Value, options: &RepetitionOptions) -> RepetitionReport {
+    let crutch_words = options
+        .crutch_words
+        .clone()
+        .unwrap_or_else(|| {
+            DEFAULT_CRUTCH_WORDS
+                .iter()
+                .map(|word| word.to_string())
+                .collect()
+        })
+        .iter()
+        .map(|phrase| {
+            tokenize(phrase)
+                .into_iter()
+                .map(|token| token.word)
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect::<Vec<_>>();
+    let min_phrase_count = options
+        .min_phrase_count
+        .unwrap_or(DEFAULT_MIN_PHRASE_COUNT)
+        .max(2);
+    let min_word_count = options
+        .min_word_count
+        .unwrap_or(DEFAULT_MIN_WORD_COUNT)
+        .max(2);
+    let max_phrase_words = options
+        .max_phrase_words
+        .unwrap_or(DEFAULT_MAX_PHRASE_WORDS)
+        .max(2);
+    let limit = options.limit.unwrap_or(DEFAULT_LIMIT);
+
+    let blocks = document::blocks(root);
+    let names = blocks
+        .iter()
+        .filter(|block| document::node_type(block) == "character")
+        .map(|block| document::character_cue_name(&document::node_text(block)).to_lowercase())
+        .collect::<HashSet<_>>();
+
+    let mut crutch_usages: HashMap<String, Vec<PhraseLocation>> = HashMap::new();
+    let mut phrase_usages: HashMap<String, Vec<PhraseLocation>> = HashMap::new();
+    let mut word_usages: HashMap<String, Vec<PhraseLocation>> = HashMap::new();
+
+    for (block_index, block) in blocks.iter().enumerate() {
+        let element_type = document::node_type(block);
+        if element_type == "character"
+            || (!options.element_types.is_empty()
+                && !options
+                    .element_types
+                    .iter()
+                    .any(|item| item == element_type))
+        {
+            continue;
+        }
+
+        let tokens = tokenize(&document::node_text(block));
+        let location = |first: usize, last: usize| PhraseLocation {
+            block_index,
+            element_type: element_type.to_string(),
+            start: tokens[first].start,
+            end: tokens[last].end,
+        };
+
+        for first in 0..tokens.len() {
+            for crutch in &crutch_words {
+                let last = first + crutch.len() - 1;
+                if last < tokens.len()
+                    && crutch
+                        .iter()
+                        .zip(&tokens[first..=last])
+                        .all(|(word, token)| *word == token.word)
+                    && !tokens[first + 1..=last]
+                        .iter()
+                        .any(|token| token.breaks_before)
+                {
+                    crutch_usages
+                        .entry(crutch.join(" "))
+                        .or_default()
+                        .push(location(first, last));
+                }
+            }
+
+            let word = &tokens[first].word;
+            if word.chars().count() > 2 && !is_stopword(word) && !names.contains(word) {
+                word_usages
+                    .entry(word.clone())
+                    .or_default()
+                    .push(location(first, first));
+            }
+
+            for size in 2..=max_phrase_words {
+                let last = first + size - 1;
+                if last >= tokens.len() || tokens[last].breaks_before {
+                    break;
+                }
+
+                let words = &tokens[first..=last];
+                if words.iter().all(|token| is_stopword(&token.word)) {
+                    continue;
+                }
+
+                let phrase = words
+                    .iter()
+                    .map(|token| token.word.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                phrase_usages
+                    .entry(phrase)
+                    .or_default()
+                    .push(location(first, last));
+            }
+        }
+    }
+
+    let mut repeated_phrases = sorted_usages(phrase_usages, min_phrase_count);
+    // A phrase that only ever appears inside a longer repeated phrase adds noise.
+    let snapshot = repeated_phrases
+        .iter()
+        .map(|usage| (usage.phrase.clone(), usage.count))
+        .collect::<Vec<_>>();
+    repeated_phrases.retain(|usage| {
+        !snapshot.iter().any(|(phrase, count)| {
+            *count == usage.count
+                && phrase.len() > usage.phrase.len()
+                && format!(" {} ", phrase).contains(&format!(" {} ", usage.phrase))
+        })
+    });
+    repeated_phrases.truncate(limit);
+
+    let mut repeated_words = sorted_usages(word_usages, min_word_count);
+    repeated_words.truncate(limit);
+
+    RepetitionReport {
+        crutch_words: sorted_usages(crutch_usages, 1),
+        repeated_phrases,
+        repeated_words,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    #[test]
+    fn finds_crutch_words_and_repeated_phrases() {
+        let root = json!({
+            "type": "doc",
+            "content": [
+                block("action", "Suddenly we see the old lighthouse."),
+                block("action", "We see the old lighthouse again. We. See nothing."),
+                block("dialogue", "We see the old lighthouse, suddenly."),
+            ]
+        });
+
+        let report = analyze(
+            &root,
+            &RepetitionOptions {
+                element_types: vec!["action".to_string()],
+                min_phrase_count: Some(2),
+                ..RepetitionOptions::default()
+            },
+        );
+
+        let we_see = report
+            .crutch_words
+            .iter()
+            .find(|usage| usage.phrase == "we see")
+            .unwrap();
+        assert_eq!(we_see.count, 2);
+        assert_eq!(we_see.locations[0].start, 9);
+        assert_eq!(we_see.locations[0].end, 15);
+        assert_eq!(
+            report
+                .crutch_words
+                .iter()
+                .find(|usage| usage.phrase == "suddenly")
+                .map(|usage| usage.count),
+            Some(1)
+        );
+
+        let phrases = report
+            .repeated_phrases
+            .iter()
+            .map(|usage| usage.phrase.as_str())
+            .collect::<Vec<_>>();
+        assert!(phrases.contains(&"we see the old"));
+        assert!(!phrases.contains(&"see the old"));
+    }
+}
@@ -0,0 +1,108 @@
+use super::pages::{format_eighths, PageEstimate};
+use crate::document::{self, slugline};
+use crate::reports::{Report, ReportColumn, ReportSection};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneReportEntry {
+    pub number: String,
+    pub block_index: usize,
+    pub heading: String,
+    pub int_ext: String,
+    pub location: String,
+    pub time: String,
+    pub page: u32,
+    pub eighths: u32,
+    pub characters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneReport {
+    pub scenes: Vec<SceneReportEntry>,
+    pub total_eighths: u32,
+}
+
+pub fn analyze(root: &Value, pages: &PageEstimate) -> SceneReport {
+    let blocks = document::blocks(root);
+    let scenes = document::scenes(root)
+        .into_iter()
+        .map(|scene| {
+            let parts = slugline::parse(&scene.heading);
+            let mut characters = Vec::new();
+            for block in &blocks[scene.heading_block..scene.end_block] {
+                if document::node_type(block) != "character" {
+                    continue;
+                }
+                let name = document::character_cue_name(&document::node_text(block));
+                if !name.is_empty() && !characters.contains(&name) {
+                    characters.push(name);
+                }
+            }
+
+            SceneReportEntry {
+                number: scene
+                    .scene_number
+                    .clone()
+                    .unwrap_or_else(|| (scene.index + 1).to_string()),
+                block_index: scene.heading_block,
+                heading: scene.heading.clone(),
+                int_ext: parts.int_ext,
+                location: parts.location,
+                time: parts.time,
+                page: pages
+                    .block_pages
+                    .get(scene.heading_block)
+                    .copied()
+                    .unwrap_or(1),
+                eighths: pages.eighths(scene.heading_block, scene.end_block),
+                characters,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    SceneReport {
+        total_eighths: scenes.iter().map(|scene| scene.eighths).sum(),
+        scenes,
+    }
+}
+
+pub fn to_report(scene_report: &SceneReport, title: &str) -> Report {
+    Report {
+        title: format!("{} - Scene Report", title),
+        subtitle: Some(format!(
+            "{} scenes, {} pages",
+            scene_report.scenes.len(),
+            format_eighths(scene_report.total_eighths)
+        )),
+        sections: vec![ReportSection {
+            heading: None,
+            columns: vec![
+                ReportColumn::new("Scene", 5),
+                ReportColumn::new("I/E", 7),
+                ReportColumn::new("Location", 28),
+                ReportColumn::new("Time", 10),
+                ReportColumn::new("Page", 4),
+                ReportColumn::new("Length", 6),
+                ReportColumn::new("Characters", 30),
+            ],
+            rows: scene_report
+                .scenes
+                .iter()
+                .map(|scene| {
+                    vec![
+                        scene.number.clone(),
+                        scene.int_ext.clone(),
+                        scene.location.clone(),
+                        scene.time.clone(),
+                        scene.page.to_string(),
+                        format_eighths(scene.eighths),
+                        scene.characters.join(", "),
+                    ]
+                })
+                .collect(),
+        }],
+    }
+}
@@ -0,0 +1,381 @@
+//! Whole app data backups for moving to another computer. The archive holds
+//! settings, plugins and their data, per-document data such as snapshots,
+//! presets, snippets and the rest of app data, plus a `grainery-backup.json`
+//! manifest. Logs, crash reports, unsaved-edit journals and earlier backups
+//! stay behind. Restoring first saves the current data to `backups/`, and
+//! the app has to relaunch afterwards since settings and plugins are held
+//! in memory.
+
+use crate::{app_data, jobs, migrations};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST_NAME: &str = "grainery-backup.json";
+const SAFETY_DIR: &str = "backups";
+const STAGING_DIR: &str = ".restore";
+/// Top-level entries that belong to this machine or this run.
+const EXCLUDED: [&str; 6] = [
+    "backups",
+    "crashes",
+    "journals",
+    "logs",
+    STAGING_DIR,
+    ".DS_Store",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub app_version: String,
+    pub created_at: String,
+    /// App data version from `migrations`; newer backups can't be restored.
+    pub data_version: usize,
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub manifest: BackupManifest,
+    /// Archive of the data that was replaced.
+    pub previous_data_path: String,
+}
+
+fn is_excluded(name: &str) -> bool {
+    EXCLUDED.contains(&name)
+}
+
+fn collect_files(root: &Path, current: &Path, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(current)? {
+        let entry = entry?;
+        let path = entry.path();
+        if current == root && is_excluded(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file() {
+            let relative = path.strip_prefix(root).map_err(io::Error::other)?;
+            let parts = relative
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// Zips everything under `root` except the excluded entries into
+/// `destination`, reporting progress as `(done, total)`.
+fn write_backup(
+    root: &Path,
+    destination: &Path,
+    data_version: usize,
+    progress: impl FnMut(usize, usize) -> Result<(), String>,
+) -> Result<BackupManifest, String> {
+    let mut files = Vec::new();
+    collect_files(root, root, &mut files)
+        .map_err(|error| format!("Failed to read app data: {}", error))?;
+    files.sort();
+
+    let manifest = BackupManifest {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        data_version,
+        files: files.len(),
+    };
+    // Written next to the destination and renamed, so a failed backup never
+    // replaces a good one.
+    let partial = destination.with_extension("partial");
+    let written = write_archive(root, &partial, &manifest, &files, progress).and_then(|_| {
+        fs::rename(&partial, destination)
+            .map_err(|error| format!("Failed to save backup archive: {}", error))
+    });
+    if written.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    written.map(|_| manifest)
+}
+
+fn write_archive(
+    root: &Path,
+    destination: &Path,
+    manifest: &BackupManifest,
+    files: &[String],
+    mut progress: impl FnMut(usize, usize) -> Result<(), String>,
+) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let file = File::create(destination)
+        .map_err(|error| format!("Failed to create backup archive: {}", error))?;
+    let mut writer = ZipWriter::new(file);
+    let mut add = |name: &str, contents: &[u8]| {
+        writer
+            .start_file(name, options)
+            .map_err(io::Error::other)
+            .and_then(|_| writer.write_all(contents))
+            .map_err(|error| format!("Failed to write backup archive: {}", error))
+    };
+
+    let payload = serde_json::to_vec_pretty(manifest)
+        .map_err(|error| format!("Failed to serialize backup manifest: {}", error))?;
+    add(MANIFEST_NAME, &payload)?;
+    for (index, name) in files.iter().enumerate() {
+        progress(index, files.len())?;
+        let contents = fs::read(root.join(name))
+            .map_err(|error| format!("Failed to read {}: {}", name, error))?;
+        add(name, &contents)?;
+    }
+    writer
+        .finish()
+        .map(|_| ())
+        .map_err(|error| format!("Failed to write backup archive: {}", error))
+}
+
+/// Checks the manifest and unpacks the archive into `staging`.
+fn extract_backup(
+    archive_path: &Path,
+    staging: &Path,
+    supported_version: usize,
+) -> Result<BackupManifest, String> {
+    let file = File::open(archive_path)
+        .map_err(|error| format!("Failed to open backup archive: {}", error))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|error| format!("Failed to read backup archive: {}", error))?;
+
+    let manifest = archive
+        .by_name(MANIFEST_NAME)
+        .map_err(|_| "This file is not a Grainery backup".to_string())
+        .and_then(|entry| {
+            serde_json::from_reader::<_, BackupManifest>(entry)
+                .map_err(|error| format!("Failed to parse backup manifest: {}", error))
+        })?;
+    if manifest.data_version > supported_version {
+        return Err(format!(
+            "This backup was made by Grainery {}, which is newer than this version",
+            manifest.app_version
+        ));
+    }
+
+    let _ = fs::remove_dir_all(staging);
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| format!("Failed to read archive entry {}: {}", index, error))?;
+        if entry.name() == MANIFEST_NAME || entry.is_dir() {
+            continue;
+        }
+        let relative = entry
+            .enclosed_name()
+            .ok_or_else(|| format!("Archive entry contains invalid path: {}", entry.name()))?;
+        let top = relative
+            .components()
+            .next()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default();
+        if is_excluded(&top) {
+            continue;
+        }
+
+        let output_path = staging.join(relative);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|error| format!("Failed to create restore directory: {}", error))?;
+        }
+        let mut output = File::create(&output_path)
+            .map_err(|error| format!("Failed to create {:?}: {}", output_path, error))?;
+        io::copy(&mut entry, &mut output)
+            .map_err(|error| format!("Failed to write {:?}: {}", output_path, error))?;
+    }
+
+    Ok(manifest)
+}
+
+/// Replaces the backed-up entries of `root` with the staged ones.
+fn swap_in(root: &Path, staging: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        if is_excluded(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    if staging.exists() {
+        for entry in fs::read_dir(staging)? {
+            let entry = entry?;
+            fs::rename(entry.path(), root.join(entry.file_name()))?;
+        }
+    }
+    fs::remove_dir_all(staging).or_else(|error| match error.kind() {
+        io::ErrorKind::NotFound => Ok(()),
+        _ => Err(error),
+    })
+}
+
+fn restore(root: &Path, archive_path: &Path, stamp: &str) -> Result<RestoreResult, String> {
+    let staging = root.join(STAGING_DIR);
+    let manifest = match extract_backup(archive_path, &staging, migrations::current_version()) {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(error);
+        }
+    };
+
+    let safety_dir = root.join(SAFETY_DIR);
+    fs::create_dir_all(&safety_dir)
+        .map_err(|error| format!("Failed to create backups directory: {}", error))?;
+    let previous = safety_dir.join(format!("before-restore-{}.zip", stamp));
+    let current_version = migrations::current_version();
+    if let Err(error) = write_backup(root, &previous, current_version, |_, _| Ok(())) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(format!("Failed to save the current data first: {}", error));
+    }
+
+    swap_in(root, &staging).map_err(|error| {
+        format!(
+            "Failed to restore app data: {}. Your previous data is in {}",
+            error,
+            previous.display()
+        )
+    })?;
+
+    Ok(RestoreResult {
+        manifest,
+        previous_data_path: previous.to_string_lossy().to_string(),
+    })
+}
+
+/// Writes settings, plugins and the rest of app data to a zip at `path`.
+#[tauri::command]
+pub async fn backup_app_data(app: AppHandle, path: String) -> Result<BackupManifest, String> {
+    let root = app_data::app_data_root(&app)?;
+    jobs::run(&app, "backup", "Backing up app data", move |job| {
+        write_backup(
+            &root,
+            Path::new(&path),
+            migrations::current_version(),
+            |done, total| {
+                job.check_cancelled()?;
+                job.progress(
+                    Some(done as f32 / total.max(1) as f32),
+                    format!("{} of {} files", done, total),
+                );
+                Ok(())
+            },
+        )
+    })
+    .await
+}
+
+/// Replaces app data with the backup at `path`. The app must be relaunched
+/// afterwards; older backups are migrated on that launch.
+#[tauri::command]
+pub async fn restore_app_data(app: AppHandle, path: String) -> Result<RestoreResult, String> {
+    let root = app_data::app_data_root(&app)?;
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    jobs::run(&app, "backup", "Restoring app data", move |_| {
+        restore(&root, &PathBuf::from(path), &stamp)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("grainery-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(root: &Path, name: &str, contents: &str) {
+        let path = root.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn backs_up_and_restores_everything_but_local_files() {
+        let old = temp_dir("backup-old");
+        write(&old, "settings.json", r#"{"theme":"dark"}"#);
+        write(&old, "plugins/plugins-state.json", "{}");
+        write(&old, "documents/doc-1.json", "snapshots");
+        write(&old, "logs/grainery.log", "old machine");
+        let archive = temp_dir("backup-archive").join("grainery.zip");
+
+        let manifest = write_backup(&old, &archive, 0, |_, _| Ok(())).unwrap();
+        assert_eq!(manifest.files, 3);
+
+        let new = temp_dir("backup-new");
+        write(&new, "settings.json", r#"{"theme":"light"}"#);
+        write(&new, "snippets.json", "[]");
+        write(&new, "logs/grainery.log", "new machine");
+        let result = restore(&new, &archive, "t1").unwrap();
+
+        assert_eq!(result.manifest, manifest);
+        assert_eq!(
+            fs::read_to_string(new.join("settings.json")).unwrap(),
+            r#"{"theme":"dark"}"#
+        );
+        assert_eq!(
+            fs::read_to_string(new.join("documents/doc-1.json")).unwrap(),
+            "snapshots"
+        );
+        assert!(new.join("plugins/plugins-state.json").exists());
+        assert!(!new.join("snippets.json").exists());
+        assert!(!new.join(STAGING_DIR).exists());
+        assert_eq!(
+            fs::read_to_string(new.join("logs/grainery.log")).unwrap(),
+            "new machine"
+        );
+
+        // The replaced data can be restored in turn.
+        let previous = PathBuf::from(&result.previous_data_path);
+        let staging = temp_dir("backup-check");
+        extract_backup(&previous, &staging, 0).unwrap();
+        assert!(staging.join("snippets.json").exists());
+        for dir in [old, new, staging, archive.parent().unwrap().to_path_buf()] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn rejects_foreign_and_newer_archives() {
+        let root = temp_dir("backup-reject");
+        write(&root, "settings.json", "{}");
+        let archive = root.join(SAFETY_DIR).join("newer.zip");
+        fs::create_dir_all(archive.parent().unwrap()).unwrap();
+        write_backup(&root, &archive, 3, |_, _| Ok(())).unwrap();
+
+        let staging = root.join(STAGING_DIR);
+        let error = extract_backup(&archive, &staging, 1).unwrap_err();
+        assert!(error.contains("newer"), "{}", error);
+
+        let foreign = root.join(SAFETY_DIR).join("foreign.zip");
+        let mut writer = ZipWriter::new(File::create(&foreign).unwrap());
+        writer
+            .start_file("notes.txt", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(
+            extract_backup(&foreign, &staging, 1).unwrap_err(),
+            "This file is not a Grainery backup"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+pub fn app_data_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let root = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| format!("Failed to resolve app data directory: {}", error))?;
+
+    fs::create_dir_all(&root)
+        .map_err(|error| format!("Failed to create app data directory: {}", error))?;
+
+    Ok(root)
+}
+
+/// Resolves (and creates) a subdirectory of the app data directory.
+pub fn app_data_dir(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app_data_root(app)?.join(name);
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("Failed to create {} directory: {}", name, error))?;
+    Ok(dir)
+}
+
+/// Reads a JSON file from app data, falling back to the default value when it
+/// has not been written yet.
+pub fn load_json<T: DeserializeOwned + Default>(
+    app: &AppHandle,
+    file_name: &str,
+) -> Result<T, String> {
+    let path = app_data_root(app)?.join(file_name);
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read {}: {}", file_name, error))?;
+
+    serde_json::from_str::<T>(&content)
+        .map_err(|error| format!("Failed to parse {}: {}", file_name, error))
+}
+
+pub fn save_json<T: Serialize>(app: &AppHandle, file_name: &str, value: &T) -> Result<(), String> {
+    let path = app_data_root(app)?.join(file_name);
+    let payload = serde_json::to_string_pretty(value)
+        .map_err(|error| format!("Failed to serialize {}: {}", file_name, error))?;
+
+    fs::write(path, payload).map_err(|error| format!("Failed to save {}: {}", file_name, error))
+}
@@ -0,0 +1,320 @@
+use crate::{app_data, document};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, State};
+
+const AUTOCAPS_RULES_FILE: &str = "autocaps-rules.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutocapRules {
+    /// Prefixes that turn a line into a scene heading (matched case-insensitively).
+    pub scene_prefixes: Vec<String>,
+    pub uppercase_scene_headings: bool,
+    pub uppercase_character_cues: bool,
+    pub uppercase_transitions: bool,
+    pub complete_known_names: bool,
+    /// Names offered for completion in addition to the cues already in the script.
+    #[serde(default)]
+    pub known_names: Vec<String>,
+}
+
+impl Default for AutocapRules {
+    fn default() -> Self {
+        Self {
+            scene_prefixes: ["INT.", "EXT.", "INT./EXT.", "I/E.", "EST."]
+                .iter()
+                .map(|prefix| prefix.to_string())
+                .collect(),
+            uppercase_scene_headings: true,
+            uppercase_character_cues: true,
+            uppercase_transitions: true,
+            complete_known_names: true,
+            known_names: Vec::new(),
+        }
+    }
+}
+
+/// The saved rules, read once and kept current by `autocaps_set_rules` so
+/// the per-keystroke suggestions don't touch the disk.
+#[derive(Default)]
+pub struct AutocapRulesCache(Mutex<Option<AutocapRules>>);
+
+impl AutocapRulesCache {
+    fn lock(&self) -> MutexGuard<'_, Option<AutocapRules>> {
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn with<T>(&self, app: &AppHandle, read: impl FnOnce(&AutocapRules) -> T) -> Result<T, String> {
+        let mut cached = self.lock();
+        let rules = match cached.take() {
+            Some(rules) => rules,
+            None => app_data::load_json(app, AUTOCAPS_RULES_FILE)?,
+        };
+        let output = read(&rules);
+        *cached = Some(rules);
+        Ok(output)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutocapSuggestion {
+    pub element_type: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutocapCleanupResult {
+    pub content_json: String,
+    pub changed_blocks: Vec<usize>,
+}
+
+fn matches_scene_prefix(rules: &AutocapRules, text: &str) -> bool {
+    let upper = text.trim_start().to_uppercase();
+    rules.scene_prefixes.iter().any(|prefix| {
+        let prefix = prefix.trim().to_uppercase();
+        !prefix.is_empty()
+            && upper
+                .strip_prefix(&prefix)
+                .map(|rest| rest.starts_with(' '))
+                .unwrap_or(false)
+    })
+}
+
+fn should_uppercase(rules: &AutocapRules, element_type: &str) -> bool {
+    match element_type {
+        "sceneHeading" => rules.uppercase_scene_headings,
+        "character" => rules.uppercase_character_cues,
+        "transition" => rules.uppercase_transitions,
+        _ => false,
+    }
+}
+
+fn complete_name(rules: &AutocapRules, prefix: &str, script_names: &[String]) -> Option<String> {
+    let prefix = prefix.trim().to_uppercase();
+    if prefix.is_empty() {
+        return None;
+    }
+
+    script_names
+        .iter()
+        .cloned()
+        .chain(
+            rules
+                .known_names
+                .iter()
+                .map(|name| name.trim().to_uppercase()),
+        )
+        .find(|name| name.len() > prefix.len() && name.starts_with(&prefix))
+}
+
+pub fn suggest(
+    rules: &AutocapRules,
+    element_type: &str,
+    text: &str,
+    script_names: &[String],
+) -> AutocapSuggestion {
+    let element_type = if element_type == "action" && matches_scene_prefix(rules, text) {
+        "sceneHeading"
+    } else {
+        element_type
+    };
+
+    let text = if should_uppercase(rules, element_type) {
+        text.to_uppercase()
+    } else {
+        text.to_string()
+    };
+
+    let completion = if element_type == "character" && rules.complete_known_names {
+        complete_name(rules, &text, script_names)
+    } else {
+        None
+    };
+
+    AutocapSuggestion {
+        element_type: element_type.to_string(),
+        text,
+        completion,
+    }
+}
+
+fn uppercase_runs(node: &mut Value) -> bool {
+    if let Some(text) = node.get("text").and_then(Value::as_str) {
+        let upper = text.to_uppercase();
+        if upper == text {
+            return false;
+        }
+        node["text"] = Value::String(upper);
+        return true;
+    }
+
+    let mut changed = false;
+    if let Some(children) = node.get_mut("content").and_then(Value::as_array_mut) {
+        for child in children {
+            changed |= uppercase_runs(child);
+        }
+    }
+    changed
+}
+
+/// Applies the rules to every block, returning the indices that changed.
+pub fn cleanup_document(rules: &AutocapRules, root: &mut Value) -> Vec<usize> {
+    let mut changed_blocks = Vec::new();
+    let Some(blocks) = document::blocks_mut(root) else {
+        return changed_blocks;
+    };
+
+    for (index, block) in blocks.iter_mut().enumerate() {
+        let mut changed = false;
+
+        if document::node_type(block) == "action"
+            && matches_scene_prefix(rules, &document::node_text(block))
+        {
+            block["type"] = Value::String("sceneHeading".to_string());
+            changed = true;
+        }
+
+        if should_uppercase(rules, document::node_type(block)) {
+            changed |= uppercase_runs(block);
+        }
+
+        if changed {
+            changed_blocks.push(index);
+        }
+    }
+
+    changed_blocks
+}
+
+#[tauri::command]
+pub fn autocaps_get_rules(
+    app: AppHandle,
+    cache: State<'_, AutocapRulesCache>,
+) -> Result<AutocapRules, String> {
+    cache.with(&app, AutocapRules::clone)
+}
+
+#[tauri::command]
+pub fn autocaps_set_rules(
+    app: AppHandle,
+    cache: State<'_, AutocapRulesCache>,
+    rules: AutocapRules,
+) -> Result<AutocapRules, String> {
+    if rules
+        .scene_prefixes
+        .iter()
+        .all(|prefix| prefix.trim().is_empty())
+    {
+        return Err("At least one scene heading prefix is required".to_string());
+    }
+
+    app_data::save_json(&app, AUTOCAPS_RULES_FILE, &rules)?;
+    *cache.lock() = Some(rules.clone());
+    Ok(rules)
+}
+
+/// Per-keystroke entry point: returns the element type and text the current
+/// block should have, plus a name completion for character cues.
+#[tauri::command]
+pub fn autocaps_suggest(
+    app: AppHandle,
+    cache: State<'_, AutocapRulesCache>,
+    element_type: String,
+    text: String,
+    script_names: Option<Vec<String>>,
+) -> Result<AutocapSuggestion, String> {
+    cache.with(&app, |rules| {
+        suggest(
+            rules,
+            &element_type,
+            &text,
+            &script_names.unwrap_or_default(),
+        )
+    })
+}
+
+#[tauri::command]
+pub fn autocaps_cleanup(
+    app: AppHandle,
+    cache: State<'_, AutocapRulesCache>,
+    content_json: String,
+) -> Result<AutocapCleanupResult, String> {
+    let mut root = document::parse_json(&content_json)?;
+    let changed_blocks = cache.with(&app, |rules| cleanup_document(rules, &mut root))?;
+    let content_json = serde_json::to_string(&root)
+        .map_err(|error| format!("Failed to serialize document: {}", error))?;
+
+    Ok(AutocapCleanupResult {
+        content_json,
+        changed_blocks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn suggests_headings_caps_and_name_completions() {
+        let rules = AutocapRules {
+            known_names: vec!["marlowe".to_string()],
+            ..AutocapRules::default()
+        };
+        let names = ["MARA".to_string()];
+
+        let heading = suggest(&rules, "action", "int. kitchen - night", &names);
+        assert_eq!(heading.element_type, "sceneHeading");
+        assert_eq!(heading.text, "INT. KITCHEN - NIGHT");
+        // A prefix needs the space after it, so words like "Internal" stay action.
+        assert_eq!(
+            suggest(&rules, "action", "Internal memo.", &names).element_type,
+            "action"
+        );
+
+        let cue = suggest(&rules, "character", "ma", &names);
+        assert_eq!(cue.text, "MA");
+        assert_eq!(cue.completion.as_deref(), Some("MARA"));
+        let known = suggest(&rules, "character", "marl", &names);
+        assert_eq!(known.completion.as_deref(), Some("MARLOWE"));
+
+        let relaxed = AutocapRules {
+            uppercase_character_cues: false,
+            complete_known_names: false,
+            ..AutocapRules::default()
+        };
+        let cue = suggest(&relaxed, "character", "ma", &names);
+        assert_eq!((cue.text.as_str(), cue.completion), ("ma", None));
+    }
+
+    #[test]
+    fn cleans_up_every_block() {
+        let mut root = json!({ "type": "doc", "content": [
+            { "type": "action", "content": [{ "type": "text", "text": "ext. dock - day" }] },
+            { "type": "action", "content": [{ "type": "text", "text": "Waves." }] },
+            { "type": "character", "content": [
+                { "type": "text", "text": "mara " },
+                { "type": "text", "text": "(v.o.)", "marks": [{ "type": "italic" }] },
+            ] },
+            { "type": "transition", "content": [{ "type": "text", "text": "CUT TO:" }] },
+        ]});
+
+        let changed = cleanup_document(&AutocapRules::default(), &mut root);
+
+        assert_eq!(changed, [0, 2]);
+        assert_eq!(root["content"][0]["type"], "sceneHeading");
+        assert_eq!(document::node_text(&root["content"][0]), "EXT. DOCK - DAY");
+        assert_eq!(document::node_text(&root["content"][1]), "Waves.");
+        assert_eq!(root["content"][2]["content"][1]["text"], "(V.O.)");
+        assert_eq!(
+            root["content"][2]["content"][1]["marks"][0]["type"],
+            "italic"
+        );
+    }
+}
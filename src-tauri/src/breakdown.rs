@@ -0,0 +1,328 @@
+use crate::sidecar::{self, TextAnchor};
+use crate::{document, jobs, reports};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+const BREAKDOWN_CATEGORIES: [&str; 14] = [
+    "cast",
+    "extras",
+    "stunts",
+    "props",
+    "wardrobe",
+    "makeup",
+    "vehicles",
+    "animals",
+    "sfx",
+    "vfx",
+    "sound",
+    "music",
+    "set-dressing",
+    "special-equipment",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakdownTag {
+    pub id: String,
+    pub category: String,
+    /// The production element, e.g. "Revolver" for a props tag.
+    pub name: String,
+    pub anchor: TextAnchor,
+    #[serde(default)]
+    pub note: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakdownTagInput {
+    pub category: String,
+    pub name: String,
+    pub anchor: TextAnchor,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakdownSceneEntry {
+    pub number: String,
+    pub heading: String,
+    /// Category -> sorted, de-duplicated element names.
+    pub categories: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakdownReport {
+    pub scenes: Vec<BreakdownSceneEntry>,
+    /// Tags whose text can no longer be found inside a scene.
+    pub unplaced_tags: Vec<String>,
+}
+
+fn validate_input(input: &BreakdownTagInput) -> Result<(), String> {
+    if !BREAKDOWN_CATEGORIES.contains(&input.category.as_str()) {
+        return Err(format!("Unknown breakdown category '{}'", input.category));
+    }
+
+    if input.name.trim().is_empty() {
+        return Err("Breakdown tag name is required".to_string());
+    }
+
+    if input.anchor.end < input.anchor.start {
+        return Err("Breakdown tag anchor end must not precede its start".to_string());
+    }
+
+    Ok(())
+}
+
+fn category_order(category: &str) -> usize {
+    BREAKDOWN_CATEGORIES
+        .iter()
+        .position(|item| *item == category)
+        .unwrap_or(BREAKDOWN_CATEGORIES.len())
+}
+
+pub fn build_report(root: &serde_json::Value, tags: &[BreakdownTag]) -> BreakdownReport {
+    let blocks = document::blocks(root);
+    let scenes = document::scenes(root);
+    let scene_by_block = document::scene_index_by_block(&scenes, blocks.len());
+
+    let mut entries = scenes
+        .iter()
+        .map(|scene| BreakdownSceneEntry {
+            number: scene
+                .scene_number
+                .clone()
+                .unwrap_or_else(|| (scene.index + 1).to_string()),
+            heading: scene.heading.clone(),
+            categories: BTreeMap::new(),
+        })
+        .collect::<Vec<_>>();
+    let mut unplaced_tags = Vec::new();
+
+    for tag in tags {
+        // Anchors are saved by block index, so edits above a tag move its
+        // text; the anchor text finds it again.
+        let Some(scene_index) = tag
+            .anchor
+            .resolve(blocks)
+            .and_then(|anchor| scene_by_block.get(anchor.block_index).copied().flatten())
+        else {
+            unplaced_tags.push(tag.id.clone());
+            continue;
+        };
+
+        let names = entries[scene_index]
+            .categories
+            .entry(tag.category.clone())
+            .or_default();
+        let name = tag.name.trim().to_string();
+        if !names.contains(&name) {
+            names.push(name);
+            names.sort();
+        }
+    }
+
+    entries.retain(|entry| !entry.categories.is_empty());
+
+    BreakdownReport {
+        scenes: entries,
+        unplaced_tags,
+    }
+}
+
+fn to_report(breakdown: &BreakdownReport, title: &str) -> reports::Report {
+    reports::Report {
+        title: format!("{} - Breakdown", title),
+        subtitle: None,
+        sections: breakdown
+            .scenes
+            .iter()
+            .map(|scene| {
+                let mut categories = scene.categories.iter().collect::<Vec<_>>();
+                categories.sort_by_key(|(category, _)| category_order(category));
+
+                reports::ReportSection {
+                    heading: Some(format!("{}. {}", scene.number, scene.heading)),
+                    columns: vec![
+                        reports::ReportColumn::new("Category", 18),
+                        reports::ReportColumn::new("Elements", 70),
+                    ],
+                    rows: categories
+                        .into_iter()
+                        .map(|(category, names)| vec![category.clone(), names.join(", ")])
+                        .collect(),
+                }
+            })
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub fn breakdown_list_tags(
+    app: AppHandle,
+    document_id: String,
+) -> Result<Vec<BreakdownTag>, String> {
+    Ok(sidecar::load(&app, &document_id)?.breakdown_tags)
+}
+
+#[tauri::command]
+pub fn breakdown_add_tag(
+    app: AppHandle,
+    document_id: String,
+    tag: BreakdownTagInput,
+) -> Result<BreakdownTag, String> {
+    validate_input(&tag)?;
+
+    sidecar::update(&app, &document_id, |store| {
+        let now = Utc::now().to_rfc3339();
+        let created = BreakdownTag {
+            id: sidecar::next_id("tag"),
+            category: tag.category,
+            name: tag.name.trim().to_string(),
+            anchor: tag.anchor,
+            note: tag.note,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        store.breakdown_tags.push(created.clone());
+        Ok(created)
+    })
+}
+
+#[tauri::command]
+pub fn breakdown_update_tag(
+    app: AppHandle,
+    document_id: String,
+    tag_id: String,
+    tag: BreakdownTagInput,
+) -> Result<BreakdownTag, String> {
+    validate_input(&tag)?;
+
+    sidecar::update(&app, &document_id, |store| {
+        let existing = store
+            .breakdown_tags
+            .iter_mut()
+            .find(|item| item.id == tag_id)
+            .ok_or_else(|| format!("Breakdown tag '{}' not found", tag_id))?;
+
+        existing.category = tag.category;
+        existing.name = tag.name.trim().to_string();
+        existing.anchor = tag.anchor;
+        existing.note = tag.note;
+        existing.updated_at = Utc::now().to_rfc3339();
+        Ok(existing.clone())
+    })
+}
+
+#[tauri::command]
+pub fn breakdown_delete_tag(
+    app: AppHandle,
+    document_id: String,
+    tag_id: String,
+) -> Result<(), String> {
+    sidecar::update(&app, &document_id, |store| {
+        let before = store.breakdown_tags.len();
+        store.breakdown_tags.retain(|item| item.id != tag_id);
+        if before == store.breakdown_tags.len() {
+            return Err(format!("Breakdown tag '{}' not found", tag_id));
+        }
+        Ok(())
+    })
+}
+
+/// Groups the document's tags by scene and category; optionally writes the
+/// breakdown as CSV or PDF.
+#[tauri::command]
+pub async fn breakdown_report(
+    app: AppHandle,
+    document_id: String,
+    content_json: String,
+    document_title: Option<String>,
+    format: Option<String>,
+    output_path: Option<String>,
+) -> Result<BreakdownReport, String> {
+    let handle = app.clone();
+    jobs::run(&app, "report", "Building breakdown report", move |job| {
+        let root = document::parse_json(&content_json)?;
+        let tags = sidecar::load(&handle, &document_id)?.breakdown_tags;
+        let report = build_report(&root, &tags);
+
+        if let (Some(format), Some(output_path)) = (format, output_path) {
+            let format = reports::ReportFormat::parse(&format)?;
+            let title = document_title.unwrap_or_else(|| "Untitled".to_string());
+            job.check_cancelled()?;
+            job.progress(None, "Writing report");
+            reports::write_report(&to_report(&report, &title), format, &output_path)?;
+        }
+
+        Ok(report)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tag(id: &str, category: &str, block_index: usize, text: &str) -> BreakdownTag {
+        BreakdownTag {
+            id: id.to_string(),
+            category: category.to_string(),
+            name: text.to_string(),
+            anchor: TextAnchor {
+                block_index,
+                start: 2,
+                end: 2 + text.chars().count(),
+                text: text.to_string(),
+            },
+            note: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn finds_tags_again_after_blocks_are_inserted_above_them() {
+        let tags = [
+            tag("gun", "props", 1, "gun"),
+            tag("car", "vehicles", 3, "car"),
+            tag("gone", "props", 3, "umbrella"),
+        ];
+        // Saved against: ONE heading, "A gun.", TWO heading, "A car."
+        let root = json!({ "type": "doc", "content": [
+            { "type": "sceneHeading", "content": [{ "type": "text", "text": "INT. ONE - DAY" }] },
+            { "type": "action", "content": [{ "type": "text", "text": "Rain." }] },
+            { "type": "action", "content": [{ "type": "text", "text": "A gun." }] },
+            { "type": "sceneHeading", "content": [{ "type": "text", "text": "INT. TWO - DAY" }] },
+            { "type": "action", "content": [{ "type": "text", "text": "A car." }] },
+        ]});
+
+        let report = build_report(&root, &tags);
+
+        let placed = report
+            .scenes
+            .iter()
+            .map(|scene| (scene.heading.as_str(), scene.categories.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            placed,
+            [
+                (
+                    "INT. ONE - DAY",
+                    BTreeMap::from([("props".to_string(), vec!["gun".to_string()])])
+                ),
+                (
+                    "INT. TWO - DAY",
+                    BTreeMap::from([("vehicles".to_string(), vec!["car".to_string()])])
+                ),
+            ]
+        );
+        assert_eq!(report.unplaced_tags, ["gone"]);
+    }
+}
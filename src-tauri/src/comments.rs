@@ -0,0 +1,333 @@
+use crate::{document, sidecar};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const MAX_AUTHOR_CHARS: usize = 60;
+
+/// The commented text. `from` and `to` are editor positions; `quote` is the
+/// text they covered, so the editor can re-anchor after edits elsewhere.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentAnchor {
+    pub from: usize,
+    pub to: usize,
+    #[serde(default)]
+    pub quote: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReply {
+    pub id: String,
+    pub author: String,
+    pub body: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A note on a range of the script and the thread of replies under it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub anchor: CommentAnchor,
+    pub author: String,
+    pub body: String,
+    #[serde(default)]
+    pub resolved: bool,
+    #[serde(default)]
+    pub replies: Vec<CommentReply>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentsUpdate {
+    pub document_json: String,
+    pub comments: Vec<Comment>,
+}
+
+pub(crate) fn read_comments(document: &Value) -> Result<Vec<Comment>, String> {
+    match document.get("comments") {
+        Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+            .map_err(|error| format!("Failed to parse comments: {}", error)),
+        _ => Ok(Vec::new()),
+    }
+}
+
+pub(crate) fn write_comments(document: &mut Value, comments: &[Comment]) -> Result<(), String> {
+    let Some(object) = document.as_object_mut() else {
+        return Err("Comments require a full document".to_string());
+    };
+
+    if comments.is_empty() {
+        object.remove("comments");
+        return Ok(());
+    }
+    let value = serde_json::to_value(comments)
+        .map_err(|error| format!("Failed to serialize comments: {}", error))?;
+    object.insert("comments".to_string(), value);
+    Ok(())
+}
+
+fn validate_text(author: &str, body: &str) -> Result<(), String> {
+    if author.trim().is_empty() {
+        return Err("Comment author is required".to_string());
+    }
+    if author.trim().chars().count() > MAX_AUTHOR_CHARS {
+        return Err(format!(
+            "Author names are limited to {} characters",
+            MAX_AUTHOR_CHARS
+        ));
+    }
+    if body.trim().is_empty() {
+        return Err("Comment text is required".to_string());
+    }
+    Ok(())
+}
+
+fn find_comment<'a>(
+    comments: &'a mut [Comment],
+    comment_id: &str,
+) -> Result<&'a mut Comment, String> {
+    comments
+        .iter_mut()
+        .find(|comment| comment.id == comment_id)
+        .ok_or_else(|| format!("Comment '{}' not found", comment_id))
+}
+
+pub(crate) fn new_comment(anchor: CommentAnchor, author: &str, body: &str) -> Comment {
+    let now = Utc::now().to_rfc3339();
+    Comment {
+        id: sidecar::next_id("comment"),
+        anchor,
+        author: author.trim().to_string(),
+        body: body.trim().to_string(),
+        resolved: false,
+        replies: Vec::new(),
+        created_at: now.clone(),
+        updated_at: now,
+    }
+}
+
+/// Reads the comments, applies `change`, and writes them back.
+fn edit<F>(document_json: &str, change: F) -> Result<CommentsUpdate, String>
+where
+    F: FnOnce(&mut Vec<Comment>) -> Result<(), String>,
+{
+    let mut document = document::parse_json(document_json)?;
+    let mut comments = read_comments(&document)?;
+    change(&mut comments)?;
+    comments.sort_by_key(|comment| comment.anchor.from);
+    write_comments(&mut document, &comments)?;
+
+    Ok(CommentsUpdate {
+        document_json: serde_json::to_string(&document)
+            .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        comments,
+    })
+}
+
+/// Comments in document order.
+#[tauri::command]
+pub fn comments_list(document_json: String) -> Result<Vec<Comment>, String> {
+    let document = document::parse_json(&document_json)?;
+    let mut comments = read_comments(&document)?;
+    comments.sort_by_key(|comment| comment.anchor.from);
+    Ok(comments)
+}
+
+#[tauri::command]
+pub fn comments_add(
+    document_json: String,
+    anchor: CommentAnchor,
+    author: String,
+    body: String,
+) -> Result<CommentsUpdate, String> {
+    validate_text(&author, &body)?;
+    if anchor.from > anchor.to {
+        return Err("Comment range is invalid".to_string());
+    }
+
+    edit(&document_json, |comments| {
+        comments.push(new_comment(anchor, &author, &body));
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn comments_reply(
+    document_json: String,
+    comment_id: String,
+    author: String,
+    body: String,
+) -> Result<CommentsUpdate, String> {
+    validate_text(&author, &body)?;
+
+    edit(&document_json, |comments| {
+        let comment = find_comment(comments, &comment_id)?;
+        let now = Utc::now().to_rfc3339();
+        comment.replies.push(CommentReply {
+            id: sidecar::next_id("reply"),
+            author: author.trim().to_string(),
+            body: body.trim().to_string(),
+            created_at: now.clone(),
+            updated_at: now.clone(),
+        });
+        // A reply reopens the thread.
+        comment.resolved = false;
+        comment.updated_at = now;
+        Ok(())
+    })
+}
+
+/// Edits a comment's text, or one of its replies when `reply_id` is given.
+#[tauri::command]
+pub fn comments_update(
+    document_json: String,
+    comment_id: String,
+    reply_id: Option<String>,
+    body: String,
+) -> Result<CommentsUpdate, String> {
+    if body.trim().is_empty() {
+        return Err("Comment text is required".to_string());
+    }
+
+    edit(&document_json, |comments| {
+        let comment = find_comment(comments, &comment_id)?;
+        let now = Utc::now().to_rfc3339();
+        match reply_id.as_deref() {
+            Some(reply_id) => {
+                let reply = comment
+                    .replies
+                    .iter_mut()
+                    .find(|reply| reply.id == reply_id)
+                    .ok_or_else(|| format!("Reply '{}' not found", reply_id))?;
+                reply.body = body.trim().to_string();
+                reply.updated_at = now.clone();
+            }
+            None => comment.body = body.trim().to_string(),
+        }
+        comment.updated_at = now;
+        Ok(())
+    })
+}
+
+/// Moves a comment after the editor re-anchors it.
+#[tauri::command]
+pub fn comments_move(
+    document_json: String,
+    comment_id: String,
+    anchor: CommentAnchor,
+) -> Result<CommentsUpdate, String> {
+    if anchor.from > anchor.to {
+        return Err("Comment range is invalid".to_string());
+    }
+
+    edit(&document_json, |comments| {
+        find_comment(comments, &comment_id)?.anchor = anchor;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn comments_set_resolved(
+    document_json: String,
+    comment_id: String,
+    resolved: bool,
+) -> Result<CommentsUpdate, String> {
+    edit(&document_json, |comments| {
+        let comment = find_comment(comments, &comment_id)?;
+        comment.resolved = resolved;
+        comment.updated_at = Utc::now().to_rfc3339();
+        Ok(())
+    })
+}
+
+/// Deletes a comment and its thread, or just one reply when `reply_id` is
+/// given.
+#[tauri::command]
+pub fn comments_delete(
+    document_json: String,
+    comment_id: String,
+    reply_id: Option<String>,
+) -> Result<CommentsUpdate, String> {
+    edit(&document_json, |comments| match reply_id.as_deref() {
+        Some(reply_id) => {
+            let comment = find_comment(comments, &comment_id)?;
+            let before = comment.replies.len();
+            comment.replies.retain(|reply| reply.id != reply_id);
+            if comment.replies.len() == before {
+                return Err(format!("Reply '{}' not found", reply_id));
+            }
+            Ok(())
+        }
+        None => {
+            let before = comments.len();
+            comments.retain(|comment| comment.id != comment_id);
+            if comments.len() == before {
+                return Err(format!("Comment '{}' not found", comment_id));
+            }
+            Ok(())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn threads_are_saved_with_the_document() {
+        let document = json!({
+            "formatVersion": "1.0",
+            "document": { "type": "doc", "content": [] },
+        })
+        .to_string();
+        let anchor = |from, to| CommentAnchor {
+            from,
+            to,
+            quote: String::new(),
+        };
+
+        let update =
+            comments_add(document, anchor(40, 52), "Sam".into(), "Cut this?".into()).unwrap();
+        let update = comments_add(
+            update.document_json,
+            anchor(3, 9),
+            "Sam".into(),
+            "Love it".into(),
+        )
+        .unwrap();
+        assert_eq!(update.comments[0].body, "Love it");
+        let id = update.comments[1].id.clone();
+
+        let update = comments_set_resolved(update.document_json, id.clone(), true).unwrap();
+        let update = comments_reply(
+            update.document_json,
+            id.clone(),
+            "Jo".into(),
+            "Kept it".into(),
+        )
+        .unwrap();
+        let thread = comments_list(update.document_json.clone())
+            .unwrap()
+            .remove(1);
+        assert!(!thread.resolved);
+        assert_eq!(thread.replies[0].author, "Jo");
+
+        assert!(comments_add(
+            update.document_json.clone(),
+            anchor(9, 3),
+            "Sam".into(),
+            "x".into()
+        )
+        .is_err());
+        let reply_id = thread.replies[0].id.clone();
+        let update = comments_delete(update.document_json, id.clone(), Some(reply_id)).unwrap();
+        assert!(update.comments[1].replies.is_empty());
+    }
+}
@@ -0,0 +1,469 @@
+//! Three-way merge of two copies of a saved document that both descend from
+//! `base`. Blocks are aligned with the same comparison as the draft diff; a
+//! run of blocks changed differently on both sides keeps both copies, one
+//! after the other, and gets a comment so the writer can pick one.
+
+use super::{align, Step};
+use crate::comments::{Comment, CommentAnchor};
+use crate::document;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::slice;
+
+const CONFLICT_AUTHOR: &str = "Grainery Sync";
+
+pub struct Merged {
+    pub document: Value,
+    /// Runs of blocks that were changed on both sides.
+    pub conflicts: usize,
+}
+
+/// A run of merged blocks holding both sides' copies of a conflict.
+struct Conflict {
+    start: usize,
+    first_len: usize,
+    second_len: usize,
+}
+
+/// The side that changed, or either side when both made the same change.
+/// `None` when both changed it differently.
+fn pick<'a, T: PartialEq + ?Sized>(base: &T, first: &'a T, second: &'a T) -> Option<&'a T> {
+    if first == base {
+        Some(second)
+    } else if second == base || first == second {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+/// For each base block, the index of the same block in `other`, if it was kept.
+fn matches(base: &[Value], other: &[Value]) -> Vec<Option<usize>> {
+    let mut matched = vec![None; base.len()];
+    for step in align(base, other, |a, b| a == b) {
+        if let Step::Same(i, j) = step {
+            matched[i] = Some(j);
+        }
+    }
+    matched
+}
+
+fn resolve(
+    merged: &mut Vec<Value>,
+    conflicts: &mut Vec<Conflict>,
+    old: &[Value],
+    ours: &[Value],
+    theirs: &[Value],
+) {
+    match pick(old, ours, theirs) {
+        Some(blocks) => merged.extend_from_slice(blocks),
+        None => {
+            conflicts.push(Conflict {
+                start: merged.len(),
+                first_len: ours.len(),
+                second_len: theirs.len(),
+            });
+            merged.extend_from_slice(ours);
+            merged.extend_from_slice(theirs);
+        }
+    }
+}
+
+/// Walks the blocks kept unchanged on both sides and resolves the runs in
+/// between them.
+fn merge_blocks(base: &[Value], first: &[Value], second: &[Value]) -> (Vec<Value>, Vec<Conflict>) {
+    let in_first = matches(base, first);
+    let in_second = matches(base, second);
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+    let (mut i, mut j, mut k) = (0, 0, 0);
+    loop {
+        let (m, a, b) = (i..base.len())
+            .find_map(|m| Some((m, in_first[m]?, in_second[m]?)))
+            .unwrap_or((base.len(), first.len(), second.len()));
+        let (old, ours, theirs) = (&base[i..m], &first[j..a], &second[k..b]);
+        if pick(old, ours, theirs).is_none() && ours.len() == old.len() && theirs.len() == old.len()
+        {
+            // Blocks edited in place, like neighbouring paragraphs changed on
+            // different devices, only conflict where the same block changed
+            for ((old, ours), theirs) in old.iter().zip(ours).zip(theirs) {
+                resolve(
+                    &mut merged,
+                    &mut conflicts,
+                    slice::from_ref(old),
+                    slice::from_ref(ours),
+                    slice::from_ref(theirs),
+                );
+            }
+        } else {
+            resolve(&mut merged, &mut conflicts, old, ours, theirs);
+        }
+        if m == base.len() {
+            break;
+        }
+        merged.push(base[m].clone());
+        (i, j, k) = (m + 1, a + 1, b + 1);
+    }
+    (merged, conflicts)
+}
+
+fn comment_list(document: &Value) -> &[Value] {
+    document
+        .get("comments")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+/// Merges comment threads by id, so a thread added, edited or deleted on
+/// one side carries over. A thread edited on both keeps the first side's.
+fn merge_comments(base: &Value, first: &Value, second: &Value) -> Vec<Value> {
+    let id = |comment: &Value| {
+        comment
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let find = |document: &Value, key: &str| {
+        comment_list(document)
+            .iter()
+            .find(|comment| id(comment).as_deref() == Some(key))
+            .cloned()
+    };
+
+    let mut ids = Vec::new();
+    for key in comment_list(first)
+        .iter()
+        .chain(comment_list(second))
+        .filter_map(id)
+    {
+        if !ids.contains(&key) {
+            ids.push(key);
+        }
+    }
+    ids.into_iter()
+        .filter_map(|key| {
+            let (old, ours, theirs) = (find(base, &key), find(first, &key), find(second, &key));
+            pick(&old, &ours, &theirs).unwrap_or(&ours).clone()
+        })
+        .collect()
+}
+
+/// A comment on the first block of the conflict's second copy (or of the
+/// first copy when the second side deleted the run). Its id and dates only
+/// depend on the conflict, so both machines merging the same two versions
+/// produce the same document.
+fn conflict_comment(blocks: &[Value], conflict: &Conflict, at: &str) -> Comment {
+    let anchor_index = if conflict.second_len > 0 {
+        conflict.start + conflict.first_len
+    } else {
+        conflict.start
+    };
+    let from = blocks[..anchor_index]
+        .iter()
+        .map(document::node_size)
+        .sum::<usize>()
+        + 1;
+    let quote = document::node_text(&blocks[anchor_index]);
+    let run = &blocks[conflict.start..conflict.start + conflict.first_len + conflict.second_len];
+    let digest = format!(
+        "{:x}",
+        Sha256::digest(Value::Array(run.to_vec()).to_string())
+    );
+    let body = format!(
+        "Changed on two devices at once, so both versions were kept: {} block{} from one device, then {} from the other. Delete the version you don't want.",
+        conflict.first_len,
+        if conflict.first_len == 1 { "" } else { "s" },
+        conflict.second_len
+    );
+
+    Comment {
+        id: format!("comment-sync-{}", &digest[..12]),
+        anchor: CommentAnchor {
+            from,
+            to: from + quote.encode_utf16().count(),
+            quote,
+        },
+        author: CONFLICT_AUTHOR.to_string(),
+        body,
+        resolved: false,
+        replies: Vec::new(),
+        created_at: at.to_string(),
+        updated_at: at.to_string(),
+    }
+}
+
+/// What two copies with no saved version in common agree on: the fields
+/// and comment threads equal on both, and the blocks both have, in order.
+/// Merging against it keeps everything either copy added, and keeps both
+/// versions of a block they disagree on.
+pub fn shared_base(first: &Value, second: &Value) -> Value {
+    let empty = Map::new();
+    let second_fields = second.as_object().unwrap_or(&empty);
+    let mut base = first
+        .as_object()
+        .unwrap_or(&empty)
+        .iter()
+        .filter(|(key, value)| second_fields.get(*key) == Some(*value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<Map<_, _>>();
+
+    let comments = comment_list(first)
+        .iter()
+        .filter(|comment| comment_list(second).contains(comment))
+        .cloned()
+        .collect();
+    base.insert("comments".to_string(), Value::Array(comments));
+
+    let first_blocks = document::blocks(first);
+    let blocks = matches(first_blocks, document::blocks(second))
+        .into_iter()
+        .zip(first_blocks)
+        .filter_map(|(matched, block)| matched.map(|_| block.clone()))
+        .collect();
+    let mut content = document::content_root(first).clone();
+    if let Some(object) = content.as_object_mut() {
+        object.insert("content".to_string(), Value::Array(blocks));
+    }
+    base.insert("document".to_string(), content);
+    Value::Object(base)
+}
+
+/// Merges two saved documents against their common ancestor. Top-level
+/// fields take whichever side changed them, preferring `first` when both
+/// did; the script itself is merged block by block. `at` dates the comments
+/// marking conflicts.
+pub fn merge(base: &Value, first: &Value, second: &Value, at: &str) -> Merged {
+    let empty = Map::new();
+    let fields = |document: &Value| document.as_object().unwrap_or(&empty).clone();
+    let (base_fields, first_fields, second_fields) = (fields(base), fields(first), fields(second));
+
+    let mut merged = Map::new();
+    for key in first_fields.keys().chain(second_fields.keys()) {
+        if merged.contains_key(key) || key == "document" || key == "comments" {
+            continue;
+        }
+        let (old, ours, theirs) = (
+            base_fields.get(key),
+            first_fields.get(key),
+            second_fields.get(key),
+        );
+        if let Some(value) = pick(&old, &ours, &theirs).unwrap_or(&ours) {
+            merged.insert(key.clone(), (*value).clone());
+        }
+    }
+
+    let mut content = document::content_root(first).clone();
+    let (blocks, conflicts) = merge_blocks(
+        document::blocks(base),
+        document::blocks(first),
+        document::blocks(second),
+    );
+    let mut comments = merge_comments(base, first, second);
+    for conflict in &conflicts {
+        if let Ok(comment) = serde_json::to_value(conflict_comment(&blocks, conflict, at)) {
+            comments.push(comment);
+        }
+    }
+    if let Some(object) = content.as_object_mut() {
+        object.insert("content".to_string(), Value::Array(blocks));
+    }
+    merged.insert("document".to_string(), content);
+    if !comments.is_empty() {
+        merged.insert("comments".to_string(), Value::Array(comments));
+    }
+
+    Merged {
+        document: Value::Object(merged),
+        conflicts: conflicts.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comments::read_comments;
+    use crate::document::block;
+    use serde_json::json;
+
+    fn saved(title: &str, blocks: Vec<Value>) -> Value {
+        json!({
+            "meta": { "id": "doc-1" },
+            "titlePage": { "title": title },
+            "document": { "type": "doc", "content": blocks },
+        })
+    }
+
+    fn texts(document: &Value) -> Vec<String> {
+        document::blocks(document)
+            .iter()
+            .map(document::node_text)
+            .collect()
+    }
+
+    #[test]
+    fn takes_each_sides_changes() {
+        let base = saved(
+            "Night Shift",
+            vec![
+                block("sceneHeading", "INT. OFFICE - DAY"),
+                block("action", "Anna types."),
+                block("sceneHeading", "EXT. ROOF - NIGHT"),
+                block("action", "Wind."),
+            ],
+        );
+        let first = saved(
+            "Night Shift",
+            vec![
+                block("sceneHeading", "INT. OFFICE - DAY"),
+                block("action", "Anna types fast."),
+                block("sceneHeading", "EXT. ROOF - NIGHT"),
+                block("action", "Wind."),
+            ],
+        );
+        let second = saved(
+            "Day Shift",
+            vec![
+                block("sceneHeading", "INT. OFFICE - DAY"),
+                block("action", "Anna types."),
+                block("sceneHeading", "EXT. ROOF - NIGHT"),
+                block("action", "Rain."),
+                block("action", "Thunder."),
+            ],
+        );
+
+        let merged = merge(&base, &first, &second, "2026-01-01T00:00:00Z");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.document["titlePage"]["title"], "Day Shift");
+        assert_eq!(
+            texts(&merged.document),
+            vec![
+                "INT. OFFICE - DAY",
+                "Anna types fast.",
+                "EXT. ROOF - NIGHT",
+                "Rain.",
+                "Thunder."
+            ]
+        );
+        assert!(merged.document.get("comments").is_none());
+    }
+
+    #[test]
+    fn keeps_both_copies_of_a_conflict_and_comments_on_it() {
+        let base = saved(
+            "Night Shift",
+            vec![block("character", "ANNA"), block("dialogue", "Hi.")],
+        );
+        let first = saved(
+            "Night Shift",
+            vec![block("character", "ANNA"), block("dialogue", "Hello.")],
+        );
+        let second = saved(
+            "Night Shift",
+            vec![block("character", "ANNA"), block("dialogue", "Hey there.")],
+        );
+
+        let merged = merge(&base, &first, &second, "2026-01-01T00:00:00Z");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            texts(&merged.document),
+            vec!["ANNA", "Hello.", "Hey there."]
+        );
+        let comments = read_comments(&merged.document).unwrap();
+        assert_eq!(comments.len(), 1);
+        // ANNA takes 6 positions and Hello. 8, so the second copy starts at 15
+        assert_eq!(comments[0].anchor.from, 15);
+        assert_eq!(comments[0].anchor.to, 25);
+        assert_eq!(comments[0].anchor.quote, "Hey there.");
+        // Merging the same versions again gives the same document
+        let again = merge(&base, &first, &second, "2026-01-01T00:00:00Z");
+        assert_eq!(again.document, merged.document);
+    }
+
+    #[test]
+    fn resolves_neighbouring_edits_block_by_block() {
+        let base = saved(
+            "Night Shift",
+            vec![
+                block("action", "One."),
+                block("action", "Two."),
+                block("action", "Three."),
+            ],
+        );
+        let first = saved(
+            "Night Shift",
+            vec![
+                block("action", "One!"),
+                block("action", "Two."),
+                block("action", "Three?"),
+            ],
+        );
+        let second = saved(
+            "Night Shift",
+            vec![
+                block("action", "One."),
+                block("action", "Two!"),
+                block("action", "Three!"),
+            ],
+        );
+
+        let merged = merge(&base, &first, &second, "2026-01-01T00:00:00Z");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            texts(&merged.document),
+            vec!["One!", "Two!", "Three?", "Three!"]
+        );
+    }
+
+    #[test]
+    fn merges_comment_threads_by_id() {
+        let mut base = saved("Night Shift", vec![block("action", "Wind.")]);
+        base["comments"] = json!([{ "id": "a", "body": "Old" }, { "id": "b", "body": "Keep" }]);
+        let mut first = base.clone();
+        first["comments"] = json!([{ "id": "b", "body": "Keep" }, { "id": "c", "body": "New" }]);
+        let mut second = base.clone();
+        second["comments"] = json!([
+            { "id": "a", "body": "Old" },
+            { "id": "b", "body": "Edited" },
+        ]);
+
+        let merged = merge(&base, &first, &second, "2026-01-01T00:00:00Z");
+        assert_eq!(
+            merged.document["comments"],
+            json!([{ "id": "b", "body": "Edited" }, { "id": "c", "body": "New" }])
+        );
+    }
+
+    #[test]
+    fn merges_copies_without_shared_history() {
+        let mut first = saved(
+            "Night Shift",
+            vec![
+                block("action", "One."),
+                block("action", "Two."),
+                block("action", "Three."),
+            ],
+        );
+        first["meta"]["modifiedAt"] = json!("2026-01-01T00:00:00Z");
+        let mut second = saved(
+            "Night Shift",
+            vec![
+                block("action", "One."),
+                block("action", "Two?"),
+                block("action", "Three."),
+                block("action", "Four."),
+            ],
+        );
+        second["meta"]["modifiedAt"] = json!("2026-01-02T00:00:00Z");
+
+        let base = shared_base(&first, &second);
+        assert_eq!(texts(&base), vec!["One.", "Three."]);
+        let merged = merge(&base, &first, &second, "2026-01-02T00:00:00Z");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            texts(&merged.document),
+            vec!["One.", "Two.", "Two?", "Three.", "Four."]
+        );
+        assert_eq!(merged.document["titlePage"]["title"], "Night Shift");
+    }
+}
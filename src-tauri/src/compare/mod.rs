@@ -0,0 +1,449 @@
+use crate::{document, jobs};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+pub(crate) mod merge;
+mod pdf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementChange {
+    pub kind: ChangeKind,
+    pub element_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_text: Option<String>,
+    /// Speaking character for dialogue and parentheticals.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub character: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneChange {
+    pub kind: ChangeKind,
+    pub heading: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_number: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterDialogueChanges {
+    pub name: String,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Changeset {
+    pub elements: Vec<ElementChange>,
+    pub scenes: Vec<SceneChange>,
+    pub dialogue_by_character: Vec<CharacterDialogueChanges>,
+    pub unchanged_elements: usize,
+}
+
+/// One side of the comparison, flattened to what the diff needs.
+struct Element {
+    element_type: String,
+    text: String,
+    character: Option<String>,
+}
+
+fn elements(root: &Value) -> Vec<Element> {
+    let mut character = None;
+    document::blocks(root)
+        .iter()
+        .map(|block| {
+            let element_type = document::node_type(block).to_string();
+            let text = document::node_text(block).trim().to_string();
+            match element_type.as_str() {
+                "character" => character = Some(document::character_cue_name(&text)),
+                "dialogue" | "parenthetical" => {}
+                _ => character = None,
+            }
+
+            Element {
+                character: match element_type.as_str() {
+                    "dialogue" | "parenthetical" => character.clone(),
+                    _ => None,
+                },
+                element_type,
+                text,
+            }
+        })
+        .collect()
+}
+
+enum Step {
+    Same(usize, usize),
+    Old(usize),
+    New(usize),
+}
+
+/// Longest-common-subsequence alignment after trimming the shared prefix and
+/// suffix, which keeps the table small for typical draft-to-draft edits.
+fn align<T>(old: &[T], new: &[T], same: impl Fn(&T, &T) -> bool) -> Vec<Step> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(a, b)| same(*a, *b))
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| same(*a, *b))
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let width = new_mid.len() + 1;
+    let mut table = vec![0u32; (old_mid.len() + 1) * width];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            table[i * width + j] = if same(&old_mid[i], &new_mid[j]) {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut steps = (0..prefix).map(|i| Step::Same(i, i)).collect::<Vec<_>>();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && same(&old_mid[i], &new_mid[j]) {
+            steps.push(Step::Same(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if j < new_mid.len()
+            && (i == old_mid.len() || table[i * width + j + 1] >= table[(i + 1) * width + j])
+        {
+            steps.push(Step::New(prefix + j));
+            j += 1;
+        } else {
+            steps.push(Step::Old(prefix + i));
+            i += 1;
+        }
+    }
+    steps.extend((0..suffix).map(|k| Step::Same(old.len() - suffix + k, new.len() - suffix + k)));
+    steps
+}
+
+/// Turns the alignment into element changes, pairing a removal with an
+/// addition of the same element type in the same run as a single edit.
+fn element_changes(old: &[Element], new: &[Element]) -> (Vec<ElementChange>, usize) {
+    let steps = align(old, new, |a, b| {
+        a.element_type == b.element_type && a.text == b.text
+    });
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    let mut removed: Vec<usize> = Vec::new();
+    let mut added: Vec<usize> = Vec::new();
+
+    let flush =
+        |removed: &mut Vec<usize>, added: &mut Vec<usize>, changes: &mut Vec<ElementChange>| {
+            let mut pending_added = std::mem::take(added);
+            for old_index in removed.drain(..) {
+                let before = &old[old_index];
+                let paired = pending_added
+                    .iter()
+                    .position(|new_index| new[*new_index].element_type == before.element_type);
+                match paired {
+                    Some(position) => {
+                        let new_index = pending_added.remove(position);
+                        let after = &new[new_index];
+                        changes.push(ElementChange {
+                            kind: ChangeKind::Changed,
+                            element_type: after.element_type.clone(),
+                            old_index: Some(old_index),
+                            new_index: Some(new_index),
+                            old_text: Some(before.text.clone()),
+                            new_text: Some(after.text.clone()),
+                            character: after.character.clone().or_else(|| before.character.clone()),
+                        });
+                    }
+                    None => changes.push(ElementChange {
+                        kind: ChangeKind::Removed,
+                        element_type: before.element_type.clone(),
+                        old_index: Some(old_index),
+                        new_index: None,
+                        old_text: Some(before.text.clone()),
+                        new_text: None,
+                        character: before.character.clone(),
+                    }),
+                }
+            }
+
+            for new_index in pending_added {
+                let after = &new[new_index];
+                changes.push(ElementChange {
+                    kind: ChangeKind::Added,
+                    element_type: after.element_type.clone(),
+                    old_index: None,
+                    new_index: Some(new_index),
+                    old_text: None,
+                    new_text: Some(after.text.clone()),
+                    character: after.character.clone(),
+                });
+            }
+        };
+
+    for step in steps {
+        match step {
+            Step::Same(..) => {
+                flush(&mut removed, &mut added, &mut changes);
+                unchanged += 1;
+            }
+            Step::Old(index) => removed.push(index),
+            Step::New(index) => added.push(index),
+        }
+    }
+    flush(&mut removed, &mut added, &mut changes);
+
+    (changes, unchanged)
+}
+
+fn scene_number(scene: &document::SceneSpan) -> String {
+    scene
+        .scene_number
+        .clone()
+        .unwrap_or_else(|| (scene.index + 1).to_string())
+}
+
+/// The elements under a scene heading, up to the next one.
+fn scene_body<'a>(elements: &'a [Element], scene: &document::SceneSpan) -> Vec<(&'a str, &'a str)> {
+    elements[scene.heading_block + 1..scene.end_block]
+        .iter()
+        .map(|element| (element.element_type.as_str(), element.text.as_str()))
+        .collect()
+}
+
+fn scene_changes(
+    old_root: &Value,
+    new_root: &Value,
+    old: &[Element],
+    new: &[Element],
+) -> Vec<SceneChange> {
+    let old_scenes = document::scenes(old_root);
+    let new_scenes = document::scenes(new_root);
+
+    align(&old_scenes, &new_scenes, |a, b| {
+        a.heading.eq_ignore_ascii_case(&b.heading)
+    })
+    .into_iter()
+    .filter_map(|step| match step {
+        Step::Same(i, j) => {
+            let (before, after) = (&old_scenes[i], &new_scenes[j]);
+            (scene_body(old, before) != scene_body(new, after)).then(|| SceneChange {
+                kind: ChangeKind::Changed,
+                heading: after.heading.clone(),
+                old_number: Some(scene_number(before)),
+                new_number: Some(scene_number(after)),
+            })
+        }
+        Step::Old(i) => Some(SceneChange {
+            kind: ChangeKind::Removed,
+            heading: old_scenes[i].heading.clone(),
+            old_number: Some(scene_number(&old_scenes[i])),
+            new_number: None,
+        }),
+        Step::New(j) => Some(SceneChange {
+            kind: ChangeKind::Added,
+            heading: new_scenes[j].heading.clone(),
+            old_number: None,
+            new_number: Some(scene_number(&new_scenes[j])),
+        }),
+    })
+    .collect()
+}
+
+fn dialogue_by_character(changes: &[ElementChange]) -> Vec<CharacterDialogueChanges> {
+    let mut totals: BTreeMap<String, CharacterDialogueChanges> = BTreeMap::new();
+    for change in changes {
+        if change.element_type != "dialogue" {
+            continue;
+        }
+        let Some(name) = change.character.as_ref().filter(|name| !name.is_empty()) else {
+            continue;
+        };
+
+        let entry = totals
+            .entry(name.clone())
+            .or_insert_with(|| CharacterDialogueChanges {
+                name: name.clone(),
+                ..CharacterDialogueChanges::default()
+            });
+        match change.kind {
+            ChangeKind::Added => entry.added += 1,
+            ChangeKind::Removed => entry.removed += 1,
+            ChangeKind::Changed => entry.changed += 1,
+        }
+    }
+    totals.into_values().collect()
+}
+
+pub fn compare(old_root: &Value, new_root: &Value) -> Changeset {
+    let old = elements(old_root);
+    let new = elements(new_root);
+    let (changes, unchanged_elements) = element_changes(&old, &new);
+
+    Changeset {
+        scenes: scene_changes(old_root, new_root, &old, &new),
+        dialogue_by_character: dialogue_by_character(&changes),
+        elements: changes,
+        unchanged_elements,
+    }
+}
+
+fn read_document(path: &str) -> Result<Value, String> {
+    let content =
+        fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {}", path, error))?;
+    document::parse_json(&content)
+}
+
+fn draft_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Diffs two saved documents element by element. With `pdf_output_path` the
+/// new draft is also rendered with change marks in the margin.
+#[tauri::command]
+pub async fn compare_documents(
+    app: AppHandle,
+    old_path: String,
+    new_path: String,
+    pdf_output_path: Option<String>,
+) -> Result<Changeset, String> {
+    jobs::run(&app, "compare", "Comparing drafts", move |job| {
+        let old_root = read_document(&old_path)?;
+        let new_root = read_document(&new_path)?;
+        let changeset = compare(&old_root, &new_root);
+
+        if let Some(output_path) = pdf_output_path {
+            let title = format!(
+                "Comparison: {} -> {}",
+                draft_name(&old_path),
+                draft_name(&new_path)
+            );
+            job.check_cancelled()?;
+            job.progress(None, "Rendering PDF");
+            pdf::write_comparison(&title, &old_root, &new_root, &changeset, &output_path)?;
+        }
+
+        Ok(changeset)
+    })
+    .await
+}
+
+fn changes_since_save(path: &str, content_json: &str) -> Result<Changeset, String> {
+    let saved_root = read_document(path)?;
+    let buffer_root = document::parse_json(content_json)?;
+    Ok(compare(&saved_root, &buffer_root))
+}
+
+/// Diffs the saved file at `path` against the unsaved editor buffer, so the
+/// user can see what a revert would throw away or an overwrite would change.
+#[tauri::command]
+pub async fn diff_against_disk(path: String, content_json: String) -> Result<Changeset, String> {
+    tauri::async_runtime::spawn_blocking(move || changes_since_save(&path, &content_json))
+        .await
+        .map_err(|error| format!("Failed to compare with saved file: {}", error))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    #[test]
+    fn reports_element_scene_and_dialogue_changes() {
+        let old = json!({ "type": "doc", "content": [
+            block("sceneHeading", "INT. OFFICE - DAY"),
+            block("character", "ANNA"),
+            block("dialogue", "We need to talk."),
+            block("sceneHeading", "EXT. ROOF - NIGHT"),
+            block("action", "Wind."),
+        ]});
+        let new = json!({ "type": "doc", "content": [
+            block("sceneHeading", "INT. OFFICE - DAY"),
+            block("character", "ANNA"),
+            block("dialogue", "We really need to talk."),
+            block("sceneHeading", "EXT. STREET - NIGHT"),
+            block("action", "Rain."),
+        ]});
+
+        let changeset = compare(&old, &new);
+
+        assert_eq!(changeset.unchanged_elements, 2);
+        assert_eq!(changeset.elements.len(), 3);
+        assert_eq!(changeset.elements[0].kind, ChangeKind::Changed);
+        assert_eq!(changeset.elements[0].character.as_deref(), Some("ANNA"));
+
+        let scene_kinds = changeset
+            .scenes
+            .iter()
+            .map(|scene| scene.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scene_kinds,
+            vec![ChangeKind::Changed, ChangeKind::Added, ChangeKind::Removed]
+        );
+
+        assert_eq!(changeset.dialogue_by_character.len(), 1);
+        assert_eq!(changeset.dialogue_by_character[0].changed, 1);
+    }
+
+    #[test]
+    fn diffs_the_buffer_against_the_saved_file() {
+        let saved = json!({ "meta": { "id": "doc-1" }, "document": { "type": "doc", "content": [
+            block("sceneHeading", "INT. OFFICE - DAY"),
+            block("action", "Quiet."),
+        ]}});
+        let path = std::env::temp_dir().join(format!(
+            "grainery-diff-against-disk-{}.gwx",
+            std::process::id()
+        ));
+        fs::write(&path, saved.to_string()).unwrap();
+
+        let buffer = json!({ "type": "doc", "content": [
+            block("sceneHeading", "INT. OFFICE - DAY"),
+            block("action", "Loud."),
+        ]});
+        let changeset = changes_since_save(&path.to_string_lossy(), &buffer.to_string());
+        let _ = fs::remove_file(&path);
+
+        let changeset = changeset.unwrap();
+        assert_eq!(changeset.unchanged_elements, 1);
+        assert_eq!(changeset.elements.len(), 1);
+        assert_eq!(changeset.elements[0].old_text.as_deref(), Some("Quiet."));
+        assert_eq!(changeset.elements[0].new_text.as_deref(), Some("Loud."));
+        assert!(changes_since_save("/nonexistent/draft.gwx", "{}").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use super::{ChangeKind, Changeset};
+use crate::document;
+use printpdf::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 54.0;
+const TEXT_X: f32 = 90.0;
+const TITLE_SIZE: f32 = 14.0;
+const BODY_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 12.0;
+const MAX_CHARS: usize = 76;
+
+struct ComparisonPdf {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    bold_font: IndirectFontRef,
+    y: f32,
+}
+
+impl ComparisonPdf {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(
+            title,
+            Mm::from(Pt(PAGE_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT)),
+            "Layer 1",
+        );
+        let font = doc
+            .add_builtin_font(BuiltinFont::Courier)
+            .map_err(|e| format!("Failed to add font: {}", e))?;
+        let bold_font = doc
+            .add_builtin_font(BuiltinFont::CourierBold)
+            .map_err(|e| format!("Failed to add font: {}", e))?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            font,
+            bold_font,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    fn ensure_space(&mut self) {
+        if self.y - LINE_HEIGHT >= MARGIN {
+            return;
+        }
+
+        let (page, layer) = self.doc.add_page(
+            Mm::from(Pt(PAGE_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT)),
+            "Layer 1",
+        );
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Writes one line with an optional change mark in the left margin.
+    fn line(&mut self, mark: &str, text: &str, size: f32, bold: bool) {
+        self.ensure_space();
+        let baseline = Mm::from(Pt(self.y - size));
+        if !mark.is_empty() {
+            self.layer
+                .use_text(mark, size, Mm::from(Pt(MARGIN)), baseline, &self.bold_font);
+        }
+        let font = if bold { &self.bold_font } else { &self.font };
+        self.layer
+            .use_text(text, size, Mm::from(Pt(TEXT_X)), baseline, font);
+        self.y -= LINE_HEIGHT.max(size + 4.0);
+    }
+
+    fn paragraph(&mut self, mark: &str, text: &str, bold: bool) {
+        for (index, line) in wrap(text, MAX_CHARS).iter().enumerate() {
+            self.line(if index == 0 { mark } else { "" }, line, BODY_SIZE, bold);
+        }
+        self.y -= LINE_HEIGHT / 2.0;
+    }
+}
+
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+fn display_text(element_type: &str, text: &str) -> String {
+    match element_type {
+        "sceneHeading" | "character" | "transition" => text.to_uppercase(),
+        _ => text.to_string(),
+    }
+}
+
+/// Renders the new draft with `+` (added) and `*` (changed) marks, and the
+/// removed elements struck in place as `-` lines.
+pub fn write_comparison(
+    title: &str,
+    old_root: &Value,
+    new_root: &Value,
+    changeset: &Changeset,
+    output_path: &str,
+) -> Result<(), String> {
+    let mut new_marks: HashMap<usize, ChangeKind> = HashMap::new();
+    let mut removed_before: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut trailing_removed = Vec::new();
+    // Cursors just past the last change on each side; the distance from
+    // them to a change is a run of unchanged elements.
+    let (mut old_cursor, mut new_cursor) = (0, 0);
+
+    for change in &changeset.elements {
+        match (change.old_index, change.new_index) {
+            (Some(old_index), Some(new_index)) => {
+                new_marks.insert(new_index, change.kind);
+                old_cursor = old_index + 1;
+                new_cursor = new_index + 1;
+            }
+            (None, Some(new_index)) => {
+                new_marks.insert(new_index, change.kind);
+                old_cursor += new_index.saturating_sub(new_cursor);
+                new_cursor = new_index + 1;
+            }
+            (Some(old_index), None) => {
+                let position = new_cursor + old_index.saturating_sub(old_cursor);
+                removed_before.entry(position).or_default().push(old_index);
+                old_cursor = old_index + 1;
+                new_cursor = position;
+            }
+            (None, None) => {}
+        }
+    }
+
+    let old_blocks = document::blocks(old_root);
+    let new_blocks = document::blocks(new_root);
+    if let Some(removed) = removed_before.remove(&new_blocks.len()) {
+        trailing_removed = removed;
+    }
+
+    let mut pdf = ComparisonPdf::new(title)?;
+    pdf.line("", &title.to_uppercase(), TITLE_SIZE, true);
+    pdf.line(
+        "",
+        &format!(
+            "{} changed, {} added, {} removed element(s)",
+            changeset
+                .elements
+                .iter()
+                .filter(|change| change.kind == ChangeKind::Changed)
+                .count(),
+            changeset
+                .elements
+                .iter()
+                .filter(|change| change.kind == ChangeKind::Added)
+                .count(),
+            changeset
+                .elements
+                .iter()
+                .filter(|change| change.kind == ChangeKind::Removed)
+                .count(),
+        ),
+        BODY_SIZE,
+        false,
+    );
+    pdf.y -= LINE_HEIGHT;
+
+    let write_removed = |pdf: &mut ComparisonPdf, indices: &[usize]| {
+        for old_index in indices {
+            let block = &old_blocks[*old_index];
+            let text = display_text(document::node_type(block), &document::node_text(block));
+            pdf.paragraph("-", &format!("[REMOVED] {}", text.trim()), false);
+        }
+    };
+
+    for (index, block) in new_blocks.iter().enumerate() {
+        if let Some(removed) = removed_before.get(&index) {
+            write_removed(&mut pdf, removed);
+        }
+
+        let node_type = document::node_type(block);
+        let mark = match new_marks.get(&index) {
+            Some(ChangeKind::Added) => "+",
+            Some(ChangeKind::Changed) => "*",
+            _ => "",
+        };
+        let text = display_text(node_type, &document::node_text(block));
+        pdf.paragraph(mark, text.trim(), node_type == "sceneHeading");
+    }
+    write_removed(&mut pdf, &trailing_removed);
+
+    let file = File::create(output_path).map_err(|e| format!("Failed to create file: {}", e))?;
+    pdf.doc
+        .save(&mut BufWriter::new(file))
+        .map_err(|e| format!("Failed to save PDF: {}", e))
+}
@@ -0,0 +1,234 @@
+use super::{fdx, fountain, write_output, LoadedDocument};
+use crate::error::CommandError;
+use crate::jobs;
+use crate::pdf::{self, PdfOptions};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Outputs rendered at once; PDF rendering is CPU-bound, so more than this
+/// only contends for cores.
+const MAX_WORKERS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Pdf,
+    Fdx,
+    Fountain,
+}
+
+/// One file to write. Watermarked copies are PDF targets that differ only
+/// in `pdf_options.watermark`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTarget {
+    pub format: ExportFormat,
+    pub path: String,
+    #[serde(default)]
+    pub pdf_options: Option<PdfOptions>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub format: ExportFormat,
+    pub path: String,
+    /// `None` when the file was written.
+    pub error: Option<CommandError>,
+}
+
+/// Runs `task` on every item using up to `workers` threads. Results are in
+/// item order.
+fn run_pooled<T, R>(items: &[T], workers: usize, task: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = task(item);
+                if let Ok(mut results) = results.lock() {
+                    results[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|error| error.into_inner())
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// The loaded document with its JSON serialized once for every PDF target.
+struct Source<'a> {
+    loaded: &'a LoadedDocument,
+    content_json: String,
+    title_page_json: Option<String>,
+    title: &'a str,
+}
+
+fn export_one(source: &Source, target: &ExportTarget) -> Result<(), String> {
+    let loaded = source.loaded;
+    let path = Path::new(&target.path);
+    match target.format {
+        ExportFormat::Pdf => pdf::generate_pdf_with_options(
+            &source.content_json,
+            source.title_page_json.as_deref(),
+            &target.path,
+            source.title,
+            &loaded.document_mode,
+            &target.pdf_options.clone().unwrap_or_default(),
+        ),
+        ExportFormat::Fdx if loaded.document_mode != "screenplay" => {
+            Err("Final Draft export is only available for screenplay documents".to_string())
+        }
+        ExportFormat::Fdx => write_output(
+            path,
+            &fdx::export(&loaded.content, loaded.title_page.as_ref()),
+        ),
+        ExportFormat::Fountain => write_output(
+            path,
+            &fountain::export(&loaded.content, loaded.title_page.as_ref()),
+        ),
+    }
+}
+
+/// Writes every target in parallel. A failed output doesn't stop the
+/// others; each result carries its own error. `on_written` is called as
+/// outputs finish, and targets not yet started when `cancelled` turns true
+/// are skipped.
+pub(super) fn export_all(
+    loaded: &LoadedDocument,
+    title: &str,
+    targets: &[ExportTarget],
+    on_written: impl Fn(usize) + Sync,
+    cancelled: impl Fn() -> bool + Sync,
+) -> Result<Vec<ExportResult>, String> {
+    let source = Source {
+        loaded,
+        content_json: serde_json::to_string(&loaded.content)
+            .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        title_page_json: loaded
+            .title_page
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|error| format!("Failed to serialize title page: {}", error))?,
+        title,
+    };
+    let written = AtomicUsize::new(0);
+
+    Ok(run_pooled(targets, MAX_WORKERS, |target| {
+        let result = if cancelled() {
+            Err(jobs::CANCELLED.to_string())
+        } else {
+            export_one(&source, target)
+        };
+        on_written(written.fetch_add(1, Ordering::Relaxed) + 1);
+        ExportResult {
+            format: target.format,
+            path: target.path.clone(),
+            error: result
+                .err()
+                .map(|error| CommandError::write_failed(&target.path, error)),
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use serde_json::json;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn pooled_results_keep_item_order() {
+        let items = (0..20).collect::<Vec<u64>>();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let results = run_pooled(&items, 3, |item| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20 - item));
+            running.fetch_sub(1, Ordering::SeqCst);
+            item * 2
+        });
+
+        assert_eq!(
+            results,
+            items.iter().map(|item| item * 2).collect::<Vec<_>>()
+        );
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(run_pooled(&[] as &[u64], 3, |item| *item).is_empty());
+    }
+
+    #[test]
+    fn reports_each_output_separately() {
+        let dir = std::env::temp_dir().join(format!("grainery-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let loaded = LoadedDocument {
+            content: json!({
+                "type": "doc",
+                "content": [{ "type": "action", "content": [{ "type": "text", "text": "Rain." }] }],
+            }),
+            title_page: None,
+            document_mode: "screenplay".to_string(),
+        };
+        let target = |format, name: &str| ExportTarget {
+            format,
+            path: dir.join(name).to_string_lossy().to_string(),
+            pdf_options: None,
+        };
+        let targets = vec![
+            target(ExportFormat::Fountain, "draft.fountain"),
+            target(ExportFormat::Fdx, "draft.fdx"),
+            target(ExportFormat::Fountain, "missing/draft.fountain"),
+        ];
+
+        let written = AtomicUsize::new(0);
+        let results = export_all(
+            &loaded,
+            "Draft",
+            &targets,
+            |count| {
+                written.fetch_max(count, Ordering::SeqCst);
+            },
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(written.load(Ordering::SeqCst), 3);
+        assert_eq!(results[0].error, None);
+        assert_eq!(results[1].error, None);
+        let missing = results[2].error.as_ref().unwrap();
+        assert_eq!(missing.kind, ErrorKind::NotFound);
+        assert_eq!(missing.path.as_deref(), Some(targets[2].path.as_str()));
+        assert!(fs::read_to_string(&targets[0].path)
+            .unwrap()
+            .contains("Rain."));
+        assert!(fs::read_to_string(&targets[1].path)
+            .unwrap()
+            .contains("Rain."));
+
+        let skipped = export_all(&loaded, "Draft", &targets[..1], |_| {}, || true).unwrap();
+        let cancelled = skipped[0].error.as_ref().map(|error| error.kind);
+        assert_eq!(cancelled, Some(ErrorKind::Cancelled));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
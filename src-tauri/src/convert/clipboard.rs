@@ -0,0 +1,169 @@
+//! Puts plain text, RTF and HTML on the system clipboard in one go, so the
+//! app being pasted into picks the richest flavor it understands. The
+//! clipboard plugin only knows text and HTML, so macOS and Windows write
+//! the pasteboard directly; elsewhere RTF is left out.
+
+#[cfg(not(any(target_os = "macos", windows)))]
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use tauri::AppHandle;
+
+#[cfg(target_os = "macos")]
+pub fn write_rich(_app: &AppHandle, text: &str, rtf: &str, html: &str) -> Result<(), String> {
+    use cocoa::base::{id, nil, BOOL, NO};
+    use cocoa::foundation::NSString;
+    use std::ffi::c_void;
+
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let _: isize = msg_send![pasteboard, clearContents];
+
+        let set_string = |value: &str, kind: &str| -> BOOL {
+            let value = NSString::alloc(nil).init_str(value);
+            let kind = NSString::alloc(nil).init_str(kind);
+            msg_send![pasteboard, setString: value forType: kind]
+        };
+        let rtf_data: id = msg_send![
+            class!(NSData),
+            dataWithBytes: rtf.as_ptr() as *const c_void
+            length: rtf.len()
+        ];
+        let rtf_kind = NSString::alloc(nil).init_str("public.rtf");
+        let wrote_rtf: BOOL = msg_send![pasteboard, setData: rtf_data forType: rtf_kind];
+
+        if set_string(text, "public.utf8-plain-text") == NO
+            || set_string(html, "public.html") == NO
+            || wrote_rtf == NO
+        {
+            return Err("Failed to write clipboard".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// The `HTML Format` clipboard flavor: the markup behind a header of byte
+/// offsets to the document and the fragment being pasted.
+#[cfg(any(windows, test))]
+fn cf_html(fragment: &str) -> String {
+    const HEADER: &str = "Version:0.9\r\nStartHTML:0000000000\r\nEndHTML:0000000000\r\n\
+        StartFragment:0000000000\r\nEndFragment:0000000000\r\n";
+    let start_html = HEADER.len();
+    let prefix = "<html><body><!--StartFragment-->";
+    let start_fragment = start_html + prefix.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + "<!--EndFragment--></body></html>".len();
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\n\
+         EndFragment:{:010}\r\n{}{}<!--EndFragment--></body></html>",
+        start_html, end_html, start_fragment, end_fragment, prefix, fragment
+    )
+}
+
+#[cfg(windows)]
+pub fn write_rich(_app: &AppHandle, text: &str, rtf: &str, html: &str) -> Result<(), String> {
+    use std::ffi::c_void;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn OpenClipboard(owner: *mut c_void) -> i32;
+        fn EmptyClipboard() -> i32;
+        fn CloseClipboard() -> i32;
+        fn SetClipboardData(format: u32, memory: *mut c_void) -> *mut c_void;
+        fn RegisterClipboardFormatW(name: *const u16) -> u32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GlobalAlloc(flags: u32, bytes: usize) -> *mut c_void;
+        fn GlobalLock(memory: *mut c_void) -> *mut c_void;
+        fn GlobalUnlock(memory: *mut c_void) -> i32;
+        fn GlobalFree(memory: *mut c_void) -> *mut c_void;
+    }
+
+    const CF_UNICODETEXT: u32 = 13;
+    const GMEM_MOVEABLE: u32 = 0x0002;
+
+    let wide = |value: &str| value.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+    let register = |name: &str| unsafe { RegisterClipboardFormatW(wide(name).as_ptr()) };
+
+    // The clipboard owns the memory once `SetClipboardData` succeeds.
+    let set = |format: u32, bytes: &[u8]| -> Result<(), String> {
+        unsafe {
+            let memory = GlobalAlloc(GMEM_MOVEABLE, bytes.len());
+            if memory.is_null() {
+                return Err("Failed to allocate clipboard memory".to_string());
+            }
+            let target = GlobalLock(memory) as *mut u8;
+            if target.is_null() {
+                GlobalFree(memory);
+                return Err("Failed to allocate clipboard memory".to_string());
+            }
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), target, bytes.len());
+            GlobalUnlock(memory);
+            if SetClipboardData(format, memory).is_null() {
+                GlobalFree(memory);
+                return Err("Failed to write clipboard".to_string());
+            }
+        }
+        Ok(())
+    };
+
+    let text_bytes = wide(text)
+        .iter()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect::<Vec<u8>>();
+    let nul_terminated = |value: String| {
+        let mut bytes = value.into_bytes();
+        bytes.push(0);
+        bytes
+    };
+
+    unsafe {
+        if OpenClipboard(std::ptr::null_mut()) == 0 {
+            return Err("The clipboard is in use by another application".to_string());
+        }
+        let result = if EmptyClipboard() == 0 {
+            Err("Failed to clear clipboard".to_string())
+        } else {
+            set(CF_UNICODETEXT, &text_bytes)
+                .and_then(|_| {
+                    set(
+                        register("Rich Text Format"),
+                        &nul_terminated(rtf.to_string()),
+                    )
+                })
+                .and_then(|_| set(register("HTML Format"), &nul_terminated(cf_html(html))))
+        };
+        CloseClipboard();
+        result
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn write_rich(app: &AppHandle, text: &str, _rtf: &str, html: &str) -> Result<(), String> {
+    app.clipboard()
+        .write_html(html, Some(text))
+        .map_err(|error| format!("Failed to write clipboard: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cf_html_offsets_point_at_the_fragment() {
+        let flavor = cf_html("<p>INT. CAFÉ</p>");
+        let offset = |key: &str| -> usize {
+            let start = flavor.find(key).unwrap() + key.len();
+            flavor[start..start + 10].parse().unwrap()
+        };
+
+        assert_eq!(
+            &flavor[offset("StartFragment:")..offset("EndFragment:")],
+            "<p>INT. CAFÉ</p>"
+        );
+        assert!(flavor[offset("StartHTML:")..].starts_with("<html>"));
+        assert_eq!(offset("EndHTML:"), flavor.len());
+    }
+}
@@ -0,0 +1,503 @@
+//! Final Draft (FDX) export, matching the editor's `src/lib/fdx.ts`, and a
+//! lenient paragraph reader for FDX pasted from Final Draft.
+
+use crate::document::{node_attr_str, node_text, node_type};
+use crate::scene_numbers;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+const ELEMENT_SETTINGS: &str = r#"  <ElementSettings Type="Scene Heading">
+    <FontSpec Font="Courier Final Draft" Size="12" Style="AllCaps"/>
+    <ParagraphSpec Alignment="Left" FirstIndent="0.00" LeftIndent="1.50" RightIndent="7.50" SpaceBefore="24" Spacing="1"/>
+    <Behavior PaginateAs="Scene Heading" ReturnKey="Action" Shortcut="1"/>
+  </ElementSettings>
+  <ElementSettings Type="Action">
+    <FontSpec Font="Courier Final Draft" Size="12" Style=""/>
+    <ParagraphSpec Alignment="Left" FirstIndent="0.00" LeftIndent="1.50" RightIndent="7.50" SpaceBefore="12" Spacing="1"/>
+    <Behavior PaginateAs="Action" ReturnKey="Action" Shortcut="2"/>
+  </ElementSettings>
+  <ElementSettings Type="Character">
+    <FontSpec Font="Courier Final Draft" Size="12" Style="AllCaps"/>
+    <ParagraphSpec Alignment="Left" FirstIndent="0.00" LeftIndent="3.70" RightIndent="7.50" SpaceBefore="12" Spacing="1"/>
+    <Behavior PaginateAs="Character" ReturnKey="Dialogue" Shortcut="3"/>
+  </ElementSettings>
+  <ElementSettings Type="Parenthetical">
+    <FontSpec Font="Courier Final Draft" Size="12" Style=""/>
+    <ParagraphSpec Alignment="Left" FirstIndent="0.00" LeftIndent="3.10" RightIndent="5.50" SpaceBefore="0" Spacing="1"/>
+    <Behavior PaginateAs="Parenthetical" ReturnKey="Dialogue" Shortcut="4"/>
+  </ElementSettings>
+  <ElementSettings Type="Dialogue">
+    <FontSpec Font="Courier Final Draft" Size="12" Style=""/>
+    <ParagraphSpec Alignment="Left" FirstIndent="0.00" LeftIndent="2.50" RightIndent="6.00" SpaceBefore="0" Spacing="1"/>
+    <Behavior PaginateAs="Dialogue" ReturnKey="Action" Shortcut="5"/>
+  </ElementSettings>
+  <ElementSettings Type="Transition">
+    <FontSpec Font="Courier Final Draft" Size="12" Style="AllCaps"/>
+    <ParagraphSpec Alignment="Right" FirstIndent="0.00" LeftIndent="6.00" RightIndent="7.50" SpaceBefore="12" Spacing="1"/>
+    <Behavior PaginateAs="Transition" ReturnKey="Scene Heading" Shortcut="6"/>
+  </ElementSettings>
+  <PageLayout>
+    <PageSize Height="11.00" Width="8.50"/>
+    <Margins Bottom="1.00" Left="1.50" Right="1.00" Top="1.00"/>
+  </PageLayout>"#;
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Paragraph ids are derived from position and text rather than random, so
+/// scripted nightly exports of an unchanged draft produce identical files.
+fn paragraph_id(index: usize, text: &str) -> String {
+    let hash = Sha256::digest(format!("{}:{}", index, text).as_bytes());
+    let hex = hash
+        .iter()
+        .take(16)
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn marks(node: &Value) -> &[Value] {
+    node.get("marks")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn children(node: &Value) -> &[Value] {
+    node.get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn paragraph_alignment(node: &Value) -> &'static str {
+    match node_attr_str(node, "textAlign") {
+        Some("center") => "Center",
+        Some("right") => "Right",
+        _ => "Left",
+    }
+}
+
+fn max_text_size(node: &Value) -> f64 {
+    let own = marks(node)
+        .iter()
+        .filter(|mark| node_type(mark) == "textSize")
+        .filter_map(|mark| mark.get("attrs")?.get("sizePt")?.as_f64())
+        .map(|size| size.clamp(6.0, 72.0));
+
+    own.chain(children(node).iter().map(max_text_size))
+        .fold(12.0, f64::max)
+}
+
+fn paragraph_spacing_attrs(node: &Value) -> String {
+    let size = max_text_size(node);
+    if size <= 12.0 {
+        return String::new();
+    }
+
+    let space_before = ((size - 12.0) * 0.75 * 10.0).round() / 10.0;
+    format!(r#" SpaceBefore="{}" LineSpacing="1.2""#, space_before)
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn text_style_attrs(marks: &[Value]) -> String {
+    let mut style = Vec::new();
+    let mut add_style = |value: String| {
+        if !style.contains(&value) {
+            style.push(value);
+        }
+    };
+    let mut font = None;
+    let mut size = None;
+
+    for mark in marks {
+        let attr = |key: &str| mark.get("attrs").and_then(|attrs| attrs.get(key));
+        match node_type(mark) {
+            "bold" => add_style("Bold".to_string()),
+            "italic" => add_style("Italic".to_string()),
+            "underline" => add_style("Underline".to_string()),
+            "strike" => add_style("Strikeout".to_string()),
+            "fontFamily" => {
+                if let Some(family) = attr("fontFamily").and_then(Value::as_str) {
+                    font = Some(family.to_string());
+                }
+                if let Some(font_style) = attr("fontStyle")
+                    .and_then(Value::as_str)
+                    .filter(|font_style| *font_style != "normal")
+                {
+                    add_style(if font_style == "oblique" {
+                        "Italic".to_string()
+                    } else {
+                        capitalize(font_style)
+                    });
+                }
+                let weight = attr("fontWeight").and_then(|weight| {
+                    weight
+                        .as_f64()
+                        .or_else(|| weight.as_str().and_then(|weight| weight.parse().ok()))
+                });
+                if weight.is_some_and(|weight| weight >= 600.0) {
+                    add_style("Bold".to_string());
+                }
+            }
+            "textSize" => {
+                if let Some(value) = attr("sizePt").filter(|value| !value.is_null()) {
+                    size = Some(match value {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut attrs = Vec::new();
+    if let Some(font) = font {
+        attrs.push(format!(r#"Font="{}""#, escape_xml(&font)));
+    }
+    if let Some(size) = size {
+        attrs.push(format!(r#"Size="{}""#, escape_xml(&size)));
+    }
+    if !style.is_empty() {
+        attrs.push(format!(r#"Style="{}""#, escape_xml(&style.join("+"))));
+    }
+
+    if attrs.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", attrs.join(" "))
+    }
+}
+
+fn render_text_nodes(node: &Value, uppercase: bool) -> String {
+    if let Some(text) = node.get("text").and_then(Value::as_str) {
+        let text = if uppercase {
+            text.to_uppercase()
+        } else {
+            text.to_string()
+        };
+        return format!(
+            "<Text{}>{}</Text>",
+            text_style_attrs(marks(node)),
+            escape_xml(&text)
+        );
+    }
+
+    children(node)
+        .iter()
+        .map(|child| render_text_nodes(child, uppercase))
+        .collect::<Vec<_>>()
+        .join("\n      ")
+}
+
+/// Scene headings keep their assigned number, e.g. ` Number="12A"`.
+fn scene_number_attr(node: &Value) -> String {
+    scene_numbers::heading_number(node)
+        .map(|number| format!(r#" Number="{}""#, escape_xml(&number)))
+        .unwrap_or_default()
+}
+
+fn paragraph_open(node: &Value, index: usize, paragraph_type: &str) -> String {
+    format!(
+        r#"<Paragraph Type="{}"{} id="{}" Alignment="{}"{}>"#,
+        paragraph_type,
+        scene_number_attr(node),
+        paragraph_id(index, &node_text(node)),
+        paragraph_alignment(node),
+        paragraph_spacing_attrs(node)
+    )
+}
+
+fn paragraph(node: &Value, index: usize, paragraph_type: &str, body: &str) -> String {
+    format!(
+        "    {}\n      {}\n    </Paragraph>",
+        paragraph_open(node, index, paragraph_type),
+        body
+    )
+}
+
+fn format_title_page(title_page: &Value) -> String {
+    let fields = ["title", "credit", "author", "contact"]
+        .iter()
+        .filter_map(|field| {
+            title_page
+                .get(*field)
+                .and_then(Value::as_str)
+                .filter(|value| !value.is_empty())
+        })
+        .map(|value| {
+            format!(
+                "      <Paragraph Type=\"Text\">\n        <Text>{}</Text>\n      </Paragraph>",
+                escape_xml(value)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if fields.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "  <TitlePage>\n    <Content>\n{}\n    </Content>\n  </TitlePage>",
+        fields.join("\n")
+    )
+}
+
+fn node_to_fdx(node: &Value, index: usize) -> Option<String> {
+    let text = node_text(node);
+
+    match node_type(node) {
+        "sceneHeading" => Some(format!(
+            "    {}\n      <SceneProperties Length=\"1\" Page=\"1\" Title=\"\"/>\n      {}\n    </Paragraph>",
+            paragraph_open(node, index, "Scene Heading"),
+            render_text_nodes(node, true)
+        )),
+        "action" => (!text.trim().is_empty())
+            .then(|| paragraph(node, index, "Action", &render_text_nodes(node, false))),
+        "character" => {
+            let extension = node_attr_str(node, "extension")
+                .filter(|extension| !extension.is_empty())
+                .map(|extension| format!("<Text> ({})</Text>", escape_xml(extension)))
+                .unwrap_or_default();
+            Some(paragraph(
+                node,
+                index,
+                "Character",
+                &format!("{}{}", render_text_nodes(node, true), extension),
+            ))
+        }
+        "parenthetical" => Some(paragraph(
+            node,
+            index,
+            "Parenthetical",
+            &format!("<Text>(</Text>{}<Text>)</Text>", render_text_nodes(node, false)),
+        )),
+        "dialogue" => Some(paragraph(
+            node,
+            index,
+            "Dialogue",
+            &render_text_nodes(node, false),
+        )),
+        "transition" => Some(paragraph(
+            node,
+            index,
+            "Transition",
+            &render_text_nodes(node, true),
+        )),
+        "pageBreak" => Some(format!(
+            "    <Paragraph Type=\"Action\" id=\"{}\">\n      <Text></Text>\n    </Paragraph>",
+            paragraph_id(index, "")
+        )),
+        _ => (!text.trim().is_empty())
+            .then(|| paragraph(node, index, "Action", &render_text_nodes(node, false))),
+    }
+}
+
+/// Writes a ProseMirror `doc` and optional title page as FDX XML.
+pub fn export(content: &Value, title_page: Option<&Value>) -> String {
+    let paragraphs = children(content)
+        .iter()
+        .enumerate()
+        .filter_map(|(index, node)| node_to_fdx(node, index))
+        .collect::<Vec<_>>();
+    let title_page = title_page.map(format_title_page).unwrap_or_default();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FinalDraft DocumentType=\"Script\" Template=\"No\" Version=\"5\">\n{}\n  <Content>\n{}\n  </Content>\n{}\n</FinalDraft>",
+        title_page,
+        paragraphs.join("\n"),
+        ELEMENT_SETTINGS
+    )
+}
+
+const FDX_TO_NODE_TYPE: [(&str, &str); 6] = [
+    ("Scene Heading", "sceneHeading"),
+    ("Action", "action"),
+    ("Character", "character"),
+    ("Parenthetical", "parenthetical"),
+    ("Dialogue", "dialogue"),
+    ("Transition", "transition"),
+];
+
+const CHARACTER_EXTENSIONS: [&str; 4] = ["V.O.", "O.S.", "CONT'D", "O.C."];
+
+fn unescape_xml(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';').filter(|end| *end <= 10) else {
+            unescaped.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+/// Concatenates the contents of every `<Text>` element, dropping any
+/// markup nested inside them.
+fn paragraph_text(body: &str) -> String {
+    let mut text = String::new();
+    let mut rest = body;
+    while let Some(open) = rest.find("<Text") {
+        rest = &rest[open..];
+        let Some(open_end) = rest.find('>') else {
+            break;
+        };
+        if rest[..open_end].ends_with('/') {
+            rest = &rest[open_end + 1..];
+            continue;
+        }
+        rest = &rest[open_end + 1..];
+        let close = rest.find("</Text>").unwrap_or(rest.len());
+        let mut inner = &rest[..close];
+        while let Some(tag) = inner.find('<') {
+            text.push_str(&unescape_xml(&inner[..tag]));
+            inner = inner[tag..]
+                .find('>')
+                .map_or("", |end| &inner[tag + end + 1..]);
+        }
+        text.push_str(&unescape_xml(inner));
+        rest = &rest[close..];
+    }
+    text
+}
+
+fn paragraph_node(fdx_type: &str, number: Option<&str>, text: &str) -> Option<Value> {
+    let node_type = FDX_TO_NODE_TYPE
+        .iter()
+        .find(|(fdx, _)| *fdx == fdx_type)
+        .map_or("action", |(_, node_type)| *node_type);
+    let text = text.trim_end();
+    if text.trim().is_empty() && node_type == "action" {
+        return None;
+    }
+    let content = |text: &str| {
+        if text.is_empty() {
+            json!([])
+        } else {
+            json!([{ "type": "text", "text": text }])
+        }
+    };
+
+    Some(match node_type {
+        "character" => {
+            let extension = CHARACTER_EXTENSIONS.iter().find(|extension| {
+                text.to_uppercase()
+                    .ends_with(&format!("({})", extension.to_uppercase()))
+            });
+            let name = match extension {
+                Some(extension) => text[..text.len() - extension.len() - 2].trim_end(),
+                None => text,
+            };
+            json!({ "type": "character", "attrs": { "extension": extension }, "content": content(name) })
+        }
+        "parenthetical" => {
+            let trimmed = text.trim();
+            let inner = trimmed
+                .strip_prefix('(')
+                .and_then(|inner| inner.strip_suffix(')'))
+                .unwrap_or(trimmed);
+            json!({ "type": "parenthetical", "content": content(inner) })
+        }
+        "sceneHeading" => json!({
+            "type": "sceneHeading",
+            "attrs": { "sceneNumber": number },
+            "content": content(text),
+        }),
+        _ => json!({ "type": node_type, "content": content(text) }),
+    })
+}
+
+/// Reads the `<Paragraph>` elements of FDX markup, whether a whole file or
+/// a fragment copied out of one, into a ProseMirror `doc`. Title page
+/// paragraphs are skipped.
+pub fn import(xml: &str) -> Value {
+    let mut script = xml.to_string();
+    while let Some(start) = script.find("<TitlePage") {
+        let end = script[start..]
+            .find("</TitlePage>")
+            .map_or(script.len(), |end| start + end + "</TitlePage>".len());
+        script.replace_range(start..end, "");
+    }
+
+    let mut content = Vec::new();
+    let mut rest = script.as_str();
+    while let Some(open) = rest.find("<Paragraph") {
+        rest = &rest[open..];
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..tag_end];
+        let (body, next) = if tag.ends_with('/') {
+            ("", tag_end + 1)
+        } else {
+            let close = rest.find("</Paragraph>").unwrap_or(rest.len());
+            (&rest[tag_end + 1..close], close)
+        };
+        rest = &rest[next..];
+
+        let text = paragraph_text(body);
+        if attribute(tag, "StartsNewPage") == Some("Yes") && text.trim().is_empty() {
+            content.push(json!({ "type": "pageBreak" }));
+            continue;
+        }
+        let fdx_type = attribute(tag, "Type").unwrap_or("Action");
+        content.extend(paragraph_node(fdx_type, attribute(tag, "Number"), &text));
+    }
+
+    json!({ "type": "doc", "content": content })
+}
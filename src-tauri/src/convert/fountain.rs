@@ -0,0 +1,332 @@
+//! Fountain (https://fountain.io/syntax) import and export, matching the
+//! editor's `src/lib/fountain.ts` so CLI output agrees with the app.
+
+use crate::document::{node_attr_str, node_text, node_type};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+
+const TITLE_PAGE_FIELDS: [(&str, &str); 8] = [
+    ("title", "Title"),
+    ("credit", "Credit"),
+    ("author", "Author"),
+    ("source", "Source"),
+    ("draftDate", "Draft date"),
+    ("contact", "Contact"),
+    ("copyright", "Copyright"),
+    ("notes", "Notes"),
+];
+
+fn scene_heading_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^(INT|EXT|EST|INT\.?/EXT|I\.?/E)[.\s]").unwrap())
+}
+
+fn title_page_start_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^(Title|Author|Credit|Source|Draft date|Contact|Copyright|Notes):")
+            .unwrap()
+    })
+}
+
+fn title_page_field_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^([A-Za-z\s]+):\s*(.*)$").unwrap())
+}
+
+fn character_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^([A-Z][A-Z\s]+?)(?:\s*\((V\.O\.|O\.S\.|CONT'D|O\.C\.)\))?(?:\s*\^)?$")
+            .unwrap()
+    })
+}
+
+fn all_caps_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[A-Z][A-Z\s]+$").unwrap())
+}
+
+fn text_node(node_type: &str, text: &str) -> Value {
+    json!({ "type": node_type, "content": [{ "type": "text", "text": text }] })
+}
+
+fn parse_title_page(lines: &[&str]) -> Value {
+    let mut data = Map::new();
+    data.insert("title".to_string(), json!(""));
+    data.insert("author".to_string(), json!(""));
+
+    let mut set_field = |key: &str, value: Vec<String>| {
+        if let Some((field, _)) = TITLE_PAGE_FIELDS
+            .iter()
+            .find(|(_, label)| label.eq_ignore_ascii_case(key))
+        {
+            data.insert(field.to_string(), json!(value.join("\n")));
+        }
+    };
+
+    let mut current: Option<(String, Vec<String>)> = None;
+    for line in lines {
+        if let Some(captures) = title_page_field_re().captures(line) {
+            if let Some((key, value)) = current.take() {
+                set_field(&key, value);
+            }
+            let value = captures[2].to_string();
+            current = Some((
+                captures[1].trim().to_string(),
+                if value.is_empty() {
+                    vec![]
+                } else {
+                    vec![value]
+                },
+            ));
+        } else if line.starts_with("    ") {
+            if let Some((_, value)) = current.as_mut() {
+                value.push(line.trim().to_string());
+            }
+        }
+    }
+    if let Some((key, value)) = current {
+        set_field(&key, value);
+    }
+
+    Value::Object(data)
+}
+
+/// Parses Fountain text into an optional title page and a ProseMirror `doc`.
+pub fn parse(text: &str) -> (Option<Value>, Value) {
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut content = Vec::new();
+    let mut title_page = None;
+    let mut i = 0;
+
+    if lines
+        .first()
+        .is_some_and(|line| title_page_start_re().is_match(line))
+    {
+        let start = i;
+        while i < lines.len() && !lines[i].trim().is_empty() {
+            i += 1;
+        }
+        title_page = Some(parse_title_page(&lines[start..i]));
+        i += 1;
+    }
+
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if trimmed.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        if scene_heading_re().is_match(trimmed) || trimmed.starts_with('.') {
+            content.push(text_node(
+                "sceneHeading",
+                trimmed.strip_prefix('.').unwrap_or(trimmed),
+            ));
+            i += 1;
+            continue;
+        }
+
+        if trimmed.ends_with("TO:") || trimmed.starts_with('>') {
+            let transition = trimmed.strip_prefix('>').map(str::trim).unwrap_or(trimmed);
+            content.push(text_node("transition", transition));
+            i += 1;
+            continue;
+        }
+
+        if trimmed == trimmed.to_uppercase()
+            && trimmed.starts_with(|c: char| c.is_ascii_uppercase())
+            && !trimmed.contains('.')
+        {
+            if let Some(captures) = character_re().captures(trimmed) {
+                let mut character = text_node("character", captures[1].trim());
+                if let Some(extension) = captures.get(2) {
+                    character["attrs"] = json!({ "extension": extension.as_str() });
+                }
+                content.push(character);
+                i += 1;
+
+                while i < lines.len() {
+                    let next = lines[i].trim();
+                    if next.is_empty() {
+                        i += 1;
+                        break;
+                    }
+
+                    if next.starts_with('(') && next.ends_with(')') && next.len() >= 2 {
+                        content.push(text_node("parenthetical", &next[1..next.len() - 1]));
+                    } else if !all_caps_re().is_match(next) && !next.starts_with('.') {
+                        content.push(text_node("dialogue", next));
+                    } else {
+                        break;
+                    }
+                    i += 1;
+                }
+                continue;
+            }
+        }
+
+        content.push(text_node("action", trimmed));
+        i += 1;
+    }
+
+    if content.is_empty() {
+        content.push(json!({ "type": "action", "content": [] }));
+    }
+
+    (title_page, json!({ "type": "doc", "content": content }))
+}
+
+fn format_title_page(title_page: &Value) -> String {
+    let mut fields = Vec::new();
+    for (field, label) in TITLE_PAGE_FIELDS {
+        let Some(value) = title_page
+            .get(field)
+            .and_then(Value::as_str)
+            .filter(|value| !value.is_empty())
+        else {
+            continue;
+        };
+
+        // Only contact and notes are written as indented multi-line blocks.
+        if matches!(field, "contact" | "notes") && value.contains('\n') {
+            fields.push(format!("{}:", label));
+            fields.extend(value.split('\n').map(|line| format!("    {}", line)));
+        } else {
+            fields.push(format!("{}: {}", label, value));
+        }
+    }
+
+    fields.join("\n")
+}
+
+fn character_line(node: &Value) -> String {
+    let name = node_text(node).to_uppercase();
+    match node_attr_str(node, "extension").filter(|extension| !extension.is_empty()) {
+        Some(extension) => format!("{} ({})", name, extension),
+        None => name,
+    }
+}
+
+fn format_dual_dialogue(node: &Value) -> String {
+    let mut lines = Vec::new();
+    let columns = node
+        .get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    for (index, column) in columns.iter().enumerate() {
+        let children = column
+            .get("content")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        for child in children {
+            if node_type(child) == "character" {
+                let suffix = if index == 1 { " ^" } else { "" };
+                lines.push(format!("\n{}{}", character_line(child), suffix));
+            } else if let Some(text) = node_to_fountain(child) {
+                lines.push(text);
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn node_to_fountain(node: &Value) -> Option<String> {
+    let text = node_text(node);
+
+    match node_type(node) {
+        "sceneHeading" => {
+            let heading = text.to_uppercase();
+            if scene_heading_re().is_match(&heading) {
+                Some(format!("\n{}", heading))
+            } else {
+                Some(format!("\n.{}", heading))
+            }
+        }
+        "action" => (!text.trim().is_empty()).then(|| format!("\n{}", text)),
+        "character" => Some(format!("\n{}", character_line(node))),
+        "parenthetical" => Some(format!("({})", text)),
+        "dialogue" => Some(text),
+        "transition" => {
+            let transition = text.to_uppercase();
+            if transition.ends_with("TO:") {
+                Some(format!("\n{}", transition))
+            } else {
+                Some(format!("\n> {}", transition))
+            }
+        }
+        "note" => Some(format!("[[{}]]", text)),
+        "section" => {
+            let level = node
+                .get("attrs")
+                .and_then(|attrs| attrs.get("level"))
+                .and_then(Value::as_u64)
+                .filter(|level| *level > 0)
+                .unwrap_or(1);
+            Some(format!("\n{} {}", "#".repeat(level as usize), text))
+        }
+        "pageBreak" => Some("\n===\n".to_string()),
+        "dualDialogue" => Some(format_dual_dialogue(node)),
+        _ => (!text.is_empty()).then(|| format!("\n{}", text)),
+    }
+}
+
+/// Writes a ProseMirror `doc` and optional title page as Fountain text.
+pub fn export(content: &Value, title_page: Option<&Value>) -> String {
+    let mut lines = Vec::new();
+    if let Some(title_page) = title_page {
+        lines.push(format_title_page(title_page));
+        lines.push(String::new());
+    }
+
+    if let Some(nodes) = content.get("content").and_then(Value::as_array) {
+        lines.extend(nodes.iter().filter_map(node_to_fountain));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_exports_fountain() {
+        let source = "Title: Nightly Build\nAuthor: Sam\n\nINT. OFFICE - NIGHT\n\nThe build runs.\n\nSAM (CONT'D)\n(quietly)\nShip it.\n\nCUT TO:\n";
+        let (title_page, doc) = parse(source);
+
+        let title_page = title_page.unwrap();
+        assert_eq!(title_page["title"], "Nightly Build");
+        assert_eq!(title_page["author"], "Sam");
+
+        let types = doc["content"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(node_type)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                "sceneHeading",
+                "action",
+                "character",
+                "parenthetical",
+                "dialogue",
+                "transition"
+            ]
+        );
+        assert_eq!(doc["content"][2]["attrs"]["extension"], "CONT'D");
+
+        let exported = export(&doc, Some(&title_page));
+        assert!(exported.starts_with("Title: Nightly Build\nAuthor: Sam\n"));
+        assert!(exported.contains("\nSAM (CONT'D)\n(quietly)\nShip it."));
+        assert_eq!(parse(&exported).1, doc);
+    }
+}
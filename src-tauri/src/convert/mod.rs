@@ -0,0 +1,274 @@
+use crate::error::CommandError;
+use crate::{document, jobs};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+mod batch;
+mod clipboard;
+mod fdx;
+mod fountain;
+mod paste;
+mod rich_text;
+
+use batch::{ExportFormat, ExportResult, ExportTarget};
+
+const FOUNTAIN_EXTENSIONS: [&str; 3] = ["fountain", "spmd", "txt"];
+
+/// A document loaded from disk: the ProseMirror `doc`, its title page and
+/// the document mode it was written in.
+struct LoadedDocument {
+    content: Value,
+    title_page: Option<Value>,
+    document_mode: String,
+}
+
+fn is_fountain_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            FOUNTAIN_EXTENSIONS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(extension))
+        })
+}
+
+/// Reads a `.gwx` document or a Fountain file.
+fn load_document(path: &Path) -> Result<LoadedDocument, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {}", path.to_string_lossy(), error))?;
+
+    if is_fountain_path(path) {
+        let (title_page, content) = fountain::parse(&text);
+        return Ok(LoadedDocument {
+            content,
+            title_page,
+            document_mode: "screenplay".to_string(),
+        });
+    }
+
+    let value = document::parse_json(&text)?;
+    Ok(LoadedDocument {
+        content: document::content_root(&value).clone(),
+        title_page: value
+            .get("titlePage")
+            .filter(|page| page.is_object())
+            .cloned(),
+        document_mode: value
+            .get("documentMode")
+            .and_then(Value::as_str)
+            .unwrap_or("screenplay")
+            .to_string(),
+    })
+}
+
+fn write_output(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents)
+        .map_err(|error| format!("Failed to write {}: {}", path.to_string_lossy(), error))
+}
+
+fn cli_usage() -> String {
+    "Usage: grainery export <input.gwx|input.fountain> [--pdf <out.pdf>] [--fdx <out.fdx>] \
+     [--fountain <out.fountain>]"
+        .to_string()
+}
+
+struct ExportCliArgs {
+    input: PathBuf,
+    pdf: Option<PathBuf>,
+    fdx: Option<PathBuf>,
+    fountain: Option<PathBuf>,
+}
+
+fn parse_cli_args(args: &[String]) -> Result<ExportCliArgs, String> {
+    let mut input = None;
+    let mut pdf = None;
+    let mut fdx = None;
+    let mut fountain = None;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().map(PathBuf::from).ok_or_else(cli_usage);
+        match arg.as_str() {
+            "--pdf" => pdf = Some(value()?),
+            "--fdx" => fdx = Some(value()?),
+            "--fountain" => fountain = Some(value()?),
+            _ if arg.starts_with("--") || input.is_some() => {
+                return Err(format!("Unknown argument '{}'\n{}", arg, cli_usage()))
+            }
+            _ => input = Some(PathBuf::from(arg)),
+        }
+    }
+
+    if pdf.is_none() && fdx.is_none() && fountain.is_none() {
+        return Err(format!("No output format given\n{}", cli_usage()));
+    }
+
+    Ok(ExportCliArgs {
+        input: input.ok_or_else(cli_usage)?,
+        pdf,
+        fdx,
+        fountain,
+    })
+}
+
+fn run_export(cli: &ExportCliArgs) -> Result<(), String> {
+    let loaded = load_document(&cli.input)?;
+    let title = cli
+        .input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "untitled".to_string());
+
+    let targets = [
+        (ExportFormat::Pdf, &cli.pdf),
+        (ExportFormat::Fdx, &cli.fdx),
+        (ExportFormat::Fountain, &cli.fountain),
+    ]
+    .into_iter()
+    .filter_map(|(format, output)| {
+        output.as_ref().map(|output| ExportTarget {
+            format,
+            path: output.to_string_lossy().to_string(),
+            pdf_options: None,
+        })
+    })
+    .collect::<Vec<_>>();
+
+    let errors = batch::export_all(&loaded, &title, &targets, |_| {}, || false)?
+        .into_iter()
+        .filter_map(|result| result.error.map(|error| error.message))
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+/// Writes the document to every target at once (PDF, FDX and Fountain, or
+/// several watermarked PDF copies) and reports each output's result.
+#[tauri::command]
+pub async fn export_batch(
+    app: AppHandle,
+    content_json: String,
+    title_page_json: Option<String>,
+    document_title: String,
+    document_mode: String,
+    targets: Vec<ExportTarget>,
+) -> Result<Vec<ExportResult>, CommandError> {
+    let label = format!("Exporting {} files", targets.len());
+    jobs::run(&app, "export", label, move |job| {
+        let loaded = LoadedDocument {
+            content: document::content_root(&document::parse_json(&content_json)?).clone(),
+            title_page: title_page_json
+                .as_deref()
+                .map(document::parse_json)
+                .transpose()?
+                .filter(|page| page.is_object()),
+            document_mode,
+        };
+        let total = targets.len();
+        batch::export_all(
+            &loaded,
+            &document_title,
+            &targets,
+            |written| {
+                job.progress(
+                    Some(written as f32 / total as f32),
+                    format!("{} of {} written", written, total),
+                )
+            },
+            || job.is_cancelled(),
+        )
+    })
+    .await
+    .map_err(CommandError::from)
+}
+
+/// Copies the document, or a selection passed as its own `doc`, to the
+/// clipboard as Fountain text.
+#[tauri::command]
+pub fn copy_as_fountain(
+    app: AppHandle,
+    content_json: String,
+    title_page_json: Option<String>,
+) -> Result<(), String> {
+    let content = document::parse_json(&content_json)?;
+    let title_page = title_page_json
+        .as_deref()
+        .map(document::parse_json)
+        .transpose()?
+        .filter(|page| page.is_object());
+    let text = fountain::export(document::content_root(&content), title_page.as_ref());
+    app.clipboard()
+        .write_text(text.trim_start())
+        .map_err(|error| format!("Failed to write clipboard: {}", error))
+}
+
+/// Copies the document, or a selection, with screenplay formatting as RTF
+/// and HTML. Apps that only take plain text get Fountain.
+#[tauri::command]
+pub fn copy_as_formatted_text(app: AppHandle, content_json: String) -> Result<(), String> {
+    let content = document::parse_json(&content_json)?;
+    let content = document::content_root(&content);
+    let text = fountain::export(content, None);
+    clipboard::write_rich(
+        &app,
+        text.trim_start(),
+        &rich_text::to_rtf(content),
+        &rich_text::to_html(content),
+    )
+}
+
+/// Works out whether pasted text is Fountain, FDX or prose and converts it
+/// to block nodes, so a pasted scene keeps its elements.
+#[tauri::command]
+pub fn classify_clipboard_text(text: String) -> paste::ClassifiedPaste {
+    paste::classify(&text)
+}
+
+/// Handles `grainery export ...` before any window is created. Returns the
+/// process exit code when the subcommand is present, `None` otherwise.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    if args.first().map(String::as_str) != Some("export") {
+        return None;
+    }
+
+    match parse_cli_args(args).and_then(|cli| run_export(&cli)) {
+        Ok(()) => Some(0),
+        Err(error) => {
+            eprintln!("{}", error);
+            Some(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_export_args() {
+        let args = [
+            "export",
+            "draft.fountain",
+            "--pdf",
+            "out.pdf",
+            "--fdx",
+            "out.fdx",
+        ]
+        .map(str::to_string);
+        let cli = parse_cli_args(&args).unwrap();
+        assert_eq!(cli.input, PathBuf::from("draft.fountain"));
+        assert_eq!(cli.pdf, Some(PathBuf::from("out.pdf")));
+        assert_eq!(cli.fdx, Some(PathBuf::from("out.fdx")));
+        assert!(cli.fountain.is_none());
+        assert!(is_fountain_path(&cli.input));
+
+        assert!(parse_cli_args(&["export", "draft.gwx"].map(str::to_string)).is_err());
+        assert!(parse_cli_args(&["export", "--pdf", "out.pdf"].map(str::to_string)).is_err());
+    }
+}
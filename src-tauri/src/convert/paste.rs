@@ -0,0 +1,165 @@
+//! Smart paste: works out whether clipboard text is Fountain, FDX or plain
+//! prose and turns it into document nodes the editor can insert.
+
+use super::{fdx, fountain};
+use crate::document::{self, node_type};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteKind {
+    Fountain,
+    Fdx,
+    Prose,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifiedPaste {
+    pub kind: PasteKind,
+    /// Block nodes to insert at the cursor.
+    pub nodes: Vec<Value>,
+    /// Title page fields, when pasted Fountain starts with them.
+    pub title_page: Option<Value>,
+}
+
+fn looks_like_fdx(text: &str) -> bool {
+    let text = text.trim_start();
+    (text.starts_with("<?xml") || text.starts_with('<'))
+        && (text.contains("<FinalDraft") || text.contains("<Paragraph"))
+}
+
+/// Whether Fountain parsing found real screenplay structure. Every line of
+/// prose parses as action, and a lone capitalised line isn't enough, so it
+/// takes a scene heading, a transition or a cue with dialogue under it.
+fn has_screenplay_structure(nodes: &[Value]) -> bool {
+    nodes
+        .iter()
+        .enumerate()
+        .any(|(index, node)| match node_type(node) {
+            "sceneHeading" | "transition" => true,
+            "character" => nodes
+                .get(index + 1)
+                .is_some_and(|next| matches!(node_type(next), "dialogue" | "parenthetical")),
+            _ => false,
+        })
+}
+
+/// Prose paragraphs are separated by blank lines; the line breaks inside
+/// one are wrapping from wherever it was copied, so they are joined.
+fn prose_nodes(text: &str) -> Vec<Value> {
+    let mut nodes = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in text.lines().chain(std::iter::once("")) {
+        let line = line.trim();
+        if !line.is_empty() {
+            paragraph.push(line);
+            continue;
+        }
+        if !paragraph.is_empty() {
+            let joined = paragraph.join(" ");
+            nodes
+                .push(json!({ "type": "action", "content": [{ "type": "text", "text": joined }] }));
+            paragraph.clear();
+        }
+    }
+    nodes
+}
+
+pub fn classify(text: &str) -> ClassifiedPaste {
+    if looks_like_fdx(text) {
+        let doc = fdx::import(text);
+        let nodes = document::blocks(&doc).to_vec();
+        if !nodes.is_empty() {
+            return ClassifiedPaste {
+                kind: PasteKind::Fdx,
+                nodes,
+                title_page: None,
+            };
+        }
+    }
+
+    let (title_page, doc) = fountain::parse(text);
+    let nodes = document::blocks(&doc).to_vec();
+    if title_page.is_some() || has_screenplay_structure(&nodes) {
+        return ClassifiedPaste {
+            kind: PasteKind::Fountain,
+            nodes,
+            title_page,
+        };
+    }
+
+    ClassifiedPaste {
+        kind: PasteKind::Prose,
+        nodes: prose_nodes(text),
+        title_page: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(paste: &ClassifiedPaste) -> Vec<&str> {
+        paste.nodes.iter().map(node_type).collect()
+    }
+
+    #[test]
+    fn detects_fountain_and_fdx() {
+        let fountain = classify("INT. HALL - NIGHT\n\nTom waits.\n\nTOM\n(quietly)\nHello?\n");
+        assert_eq!(fountain.kind, PasteKind::Fountain);
+        assert_eq!(
+            types(&fountain),
+            [
+                "sceneHeading",
+                "action",
+                "character",
+                "parenthetical",
+                "dialogue"
+            ]
+        );
+
+        let fdx = classify(
+            r#"<?xml version="1.0"?><FinalDraft><Content>
+            <Paragraph Type="Scene Heading" Number="4A"><Text>INT. HALL - NIGHT</Text></Paragraph>
+            <Paragraph Type="Character"><Text>TOM</Text><Text> (V.O.)</Text></Paragraph>
+            <Paragraph Type="Parenthetical"><Text>(</Text><Text Style="Italic">quietly</Text><Text>)</Text></Paragraph>
+            <Paragraph Type="Dialogue"><Text>Fish &amp; chips &#x2014; now.</Text></Paragraph>
+            <Paragraph StartsNewPage="Yes" Type="Action"><Text></Text></Paragraph>
+            </Content><TitlePage><Content><Paragraph Type="Text"><Text>MY SCRIPT</Text></Paragraph></Content></TitlePage></FinalDraft>"#,
+        );
+        assert_eq!(fdx.kind, PasteKind::Fdx);
+        assert_eq!(
+            types(&fdx),
+            [
+                "sceneHeading",
+                "character",
+                "parenthetical",
+                "dialogue",
+                "pageBreak"
+            ]
+        );
+        assert_eq!(fdx.nodes[0]["attrs"]["sceneNumber"], "4A");
+        assert_eq!(fdx.nodes[1]["attrs"]["extension"], "V.O.");
+        assert_eq!(document::node_text(&fdx.nodes[1]), "TOM");
+        assert_eq!(document::node_text(&fdx.nodes[2]), "quietly");
+        assert_eq!(
+            document::node_text(&fdx.nodes[3]),
+            "Fish & chips \u{2014} now."
+        );
+    }
+
+    #[test]
+    fn joins_wrapped_prose_into_paragraphs() {
+        let prose = classify(
+            "The storm came in low over the harbour\nand nobody saw it coming.\n\nNOTHING MOVED.\nNot even the gulls.\n",
+        );
+        assert_eq!(prose.kind, PasteKind::Prose);
+        assert_eq!(types(&prose), ["action", "action"]);
+        assert_eq!(
+            document::node_text(&prose.nodes[0]),
+            "The storm came in low over the harbour and nobody saw it coming."
+        );
+    }
+}
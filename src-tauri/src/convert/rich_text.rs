@@ -0,0 +1,271 @@
+//! Screenplay-formatted RTF and HTML for the clipboard. Elements keep the
+//! PDF's indents relative to the text column, so a paste into a word
+//! processor, Google Docs or an email still reads like a script page.
+
+use crate::document::{self, node_type};
+use crate::pagination::{self, ElementFormat, PaperSize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Run {
+    text: String,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Paragraph {
+    format: ElementFormat,
+    runs: Vec<Run>,
+    page_break_before: bool,
+    space_before: bool,
+}
+
+fn has_mark(node: &Value, mark: &str) -> bool {
+    node.get("marks")
+        .and_then(Value::as_array)
+        .is_some_and(|marks| marks.iter().any(|entry| node_type(entry) == mark))
+}
+
+fn runs(node: &Value, format: &ElementFormat) -> Vec<Run> {
+    let plain = |text: &str| Run {
+        text: text.to_string(),
+        bold: format.bold,
+        ..Run::default()
+    };
+    let mut runs = vec![plain(format.prefix)];
+    for child in node
+        .get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+    {
+        let text = match node_type(child) {
+            "text" => child
+                .get("text")
+                .and_then(Value::as_str)
+                .unwrap_or_default(),
+            "hardBreak" => "\n",
+            _ => continue,
+        };
+        runs.push(Run {
+            text: if format.uppercase {
+                text.to_uppercase()
+            } else {
+                text.to_string()
+            },
+            bold: format.bold || has_mark(child, "bold"),
+            italic: has_mark(child, "italic"),
+            underline: has_mark(child, "underline"),
+        });
+    }
+    let suffix = match node_type(node) {
+        "character" => pagination::character_suffix(node.get("attrs")),
+        _ => format.suffix.to_string(),
+    };
+    runs.push(plain(&suffix));
+    runs.retain(|run| !run.text.is_empty());
+    runs
+}
+
+/// Printable elements in order, with dual dialogue columns one after the
+/// other.
+fn paragraphs(content: &Value) -> Vec<Paragraph> {
+    let content_width = PaperSize::Letter.content_width();
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut page_break = false;
+
+    let mut nodes = Vec::new();
+    for block in document::blocks(content) {
+        match node_type(block) {
+            "dualDialogue" => nodes.extend(
+                document::blocks(block)
+                    .iter()
+                    .flat_map(|column| document::blocks(column).iter()),
+            ),
+            _ => nodes.push(block),
+        }
+    }
+
+    for node in nodes {
+        if node_type(node) == "pageBreak" {
+            page_break = !paragraphs.is_empty();
+            continue;
+        }
+        if document::node_text(node).trim().is_empty() {
+            continue;
+        }
+        let format = ElementFormat::for_type(node_type(node), content_width);
+        paragraphs.push(Paragraph {
+            format,
+            runs: runs(node, &format),
+            page_break_before: std::mem::take(&mut page_break),
+            space_before: format.blank_before && !paragraphs.is_empty(),
+        });
+    }
+
+    paragraphs
+}
+
+/// Space between an element and the right edge of the text column.
+fn right_indent(format: &ElementFormat) -> f32 {
+    (PaperSize::Letter.content_width() - format.indent - format.width).max(0.0)
+}
+
+fn rtf_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\line "),
+            '\t' => escaped.push_str("\\tab "),
+            c if c.is_ascii() => escaped.push(c),
+            c => {
+                // RTF takes signed 16-bit code units, each with a `?`
+                // fallback for readers without Unicode.
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+    escaped
+}
+
+fn twips(points: f32) -> i32 {
+    (points * 20.0).round() as i32
+}
+
+pub fn to_rtf(content: &Value) -> String {
+    let mut rtf =
+        String::from("{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fmodern Courier New;}}\\f0\\fs24\n");
+    for paragraph in paragraphs(content) {
+        if paragraph.page_break_before {
+            rtf.push_str("\\page\n");
+        }
+        rtf.push_str(&format!(
+            "\\pard\\li{}\\ri{}\\sb{}{}",
+            twips(paragraph.format.indent),
+            twips(right_indent(&paragraph.format)),
+            if paragraph.space_before { 240 } else { 0 },
+            if paragraph.format.align == "right" {
+                "\\qr"
+            } else {
+                "\\ql"
+            },
+        ));
+        for run in &paragraph.runs {
+            let mut controls = String::new();
+            for (on, control) in [
+                (run.bold, "\\b"),
+                (run.italic, "\\i"),
+                (run.underline, "\\ul"),
+            ] {
+                if on {
+                    controls.push_str(control);
+                }
+            }
+            // A control word ends at the space after it; text starts right
+            // after that.
+            if !controls.is_empty() {
+                controls.push(' ');
+            }
+            rtf.push_str(&format!("{{{}{}}}", controls, rtf_escape(&run.text)));
+        }
+        rtf.push_str("\\par\n");
+    }
+    rtf.push('}');
+    rtf
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "<br>")
+}
+
+pub fn to_html(content: &Value) -> String {
+    let mut html = String::from(
+        "<div style=\"font-family: 'Courier New', Courier, monospace; font-size: 12pt; line-height: 1.15;\">\n",
+    );
+    for paragraph in paragraphs(content) {
+        let mut text = String::new();
+        for run in &paragraph.runs {
+            let mut run_html = html_escape(&run.text);
+            for (on, tag) in [(run.bold, "b"), (run.italic, "i"), (run.underline, "u")] {
+                if on {
+                    run_html = format!("<{}>{}</{}>", tag, run_html, tag);
+                }
+            }
+            text.push_str(&run_html);
+        }
+        html.push_str(&format!(
+            "<p style=\"margin: {}pt {}pt 0 {}pt; text-align: {};{}\">{}</p>\n",
+            if paragraph.space_before { 12 } else { 0 },
+            right_indent(&paragraph.format),
+            paragraph.format.indent,
+            paragraph.format.align,
+            if paragraph.page_break_before {
+                " page-break-before: always;"
+            } else {
+                ""
+            },
+            text,
+        ));
+    }
+    html.push_str("</div>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn script() -> Value {
+        json!({
+            "type": "doc",
+            "content": [
+                { "type": "sceneHeading", "content": [{ "type": "text", "text": "int. café - day" }] },
+                { "type": "character", "attrs": { "extension": "V.O." }, "content": [{ "type": "text", "text": "Jane" }] },
+                { "type": "parenthetical", "content": [{ "type": "text", "text": "softly" }] },
+                { "type": "dialogue", "content": [
+                    { "type": "text", "text": "It's " },
+                    { "type": "text", "text": "{fine}", "marks": [{ "type": "italic" }] },
+                ] },
+                { "type": "pageBreak" },
+                { "type": "transition", "content": [{ "type": "text", "text": "cut to:" }] },
+            ],
+        })
+    }
+
+    #[test]
+    fn writes_screenplay_indents_as_rtf() {
+        let rtf = to_rtf(&script());
+        assert!(rtf.starts_with("{\\rtf1\\ansi"));
+        assert!(rtf.contains("\\pard\\li0\\ri0\\sb0\\ql{\\b INT. CAF\\u201?"));
+        assert!(rtf.contains("\\li2880\\ri0\\sb240\\ql{JANE}{ (V.O.)}\\par"));
+        assert!(rtf.contains("\\li2160\\ri3600\\sb0\\ql{(}{softly}{)}\\par"));
+        assert!(rtf.contains("\\li1440\\ri2160\\sb0\\ql{It's }{\\i \\{fine\\}}\\par"));
+        assert!(rtf.contains("\\page\n\\pard\\li0\\ri0\\sb240\\qr{CUT TO:}\\par"));
+    }
+
+    #[test]
+    fn writes_the_same_layout_as_html() {
+        let html = to_html(&script());
+        assert!(html.contains(
+            "<p style=\"margin: 0pt 0pt 0 0pt; text-align: left;\"><b>INT. CAFÉ - DAY</b></p>"
+        ));
+        assert!(
+            html.contains("margin: 0pt 108pt 0 72pt; text-align: left;\">It's <i>{fine}</i></p>")
+        );
+        assert!(html.contains("text-align: right; page-break-before: always;\">CUT TO:</p>"));
+    }
+}
@@ -0,0 +1,407 @@
+//! Crash reports. A panic writes a report to `crashes/` under app data with
+//! the backtrace, app version, the last operations and any plugin host calls
+//! in flight. Reports never hold document text: operations are recorded by
+//! name and quoted text is cut from panic messages. On the next launch the
+//! frontend offers to send them; nothing leaves the machine unless the user
+//! agrees.
+
+use crate::{app_data, file_manager};
+use chrono::Utc;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const CRASH_DIR: &str = "crashes";
+const MAX_BREADCRUMBS: usize = 30;
+/// Older unsent reports are deleted.
+const MAX_REPORTS: usize = 5;
+const ISSUE_URL: &str = "https://github.com/jayrenteria/grainery/issues/new";
+/// Keeps the prefilled issue URL within what browsers and GitHub accept.
+const MAX_ISSUE_BODY_CHARS: usize = 6000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Breadcrumb {
+    pub at: String,
+    pub category: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCallContext {
+    pub plugin_id: String,
+    pub operation: String,
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    /// Oldest first.
+    pub breadcrumbs: Vec<Breadcrumb>,
+    /// Plugin host calls that hadn't returned when the panic happened.
+    pub plugin_calls: Vec<PluginCallContext>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub message: String,
+    pub plugin_ids: Vec<String>,
+}
+
+static BREADCRUMBS: Mutex<VecDeque<Breadcrumb>> = Mutex::new(VecDeque::new());
+static PLUGIN_CALLS: Mutex<Vec<(u64, PluginCallContext)>> = Mutex::new(Vec::new());
+static NEXT_PLUGIN_CALL: AtomicU64 = AtomicU64::new(1);
+
+/// Records an operation for the next crash report. `detail` names what was
+/// done and must not contain document text or file names.
+pub fn breadcrumb(category: &str, detail: impl Into<String>) {
+    let Ok(mut breadcrumbs) = BREADCRUMBS.lock() else {
+        return;
+    };
+    if breadcrumbs.len() == MAX_BREADCRUMBS {
+        breadcrumbs.pop_front();
+    }
+    breadcrumbs.push_back(Breadcrumb {
+        at: Utc::now().to_rfc3339(),
+        category: category.to_string(),
+        detail: detail.into(),
+    });
+}
+
+/// A plugin host call in progress. Crash reports list the calls alive when
+/// the panic happened, so a plugin-triggered crash names the plugin.
+pub struct PluginCall(u64);
+
+impl PluginCall {
+    pub fn enter(plugin_id: &str, operation: &str) -> Self {
+        breadcrumb("plugin", format!("{} {}", plugin_id, operation));
+        let id = NEXT_PLUGIN_CALL.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut calls) = PLUGIN_CALLS.lock() {
+            calls.push((
+                id,
+                PluginCallContext {
+                    plugin_id: plugin_id.to_string(),
+                    operation: operation.to_string(),
+                    started_at: Utc::now().to_rfc3339(),
+                },
+            ));
+        }
+        Self(id)
+    }
+}
+
+impl Drop for PluginCall {
+    fn drop(&mut self) {
+        if let Ok(mut calls) = PLUGIN_CALLS.lock() {
+            calls.retain(|(id, _)| *id != self.0);
+        }
+    }
+}
+
+/// Replaces text in backticks or double quotes, where panic messages put
+/// the strings they were handed, which may be script text.
+fn redact(message: &str) -> String {
+    let mut redacted = String::with_capacity(message.len());
+    let mut open_quote = None;
+    for character in message.chars() {
+        match open_quote {
+            Some(quote) if character == quote => {
+                redacted.push('…');
+                redacted.push(character);
+                open_quote = None;
+            }
+            Some(_) => {}
+            None => {
+                redacted.push(character);
+                if character == '`' || character == '"' {
+                    open_quote = Some(character);
+                }
+            }
+        }
+    }
+    if open_quote.is_some() {
+        redacted.push('…');
+    }
+    redacted
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    redact(&message)
+}
+
+/// Copies out what the locks hold without waiting; a panic while one is
+/// held shouldn't hang the hook.
+fn snapshot<T: Clone>(lock: &Mutex<T>) -> Option<T> {
+    lock.try_lock().ok().map(|value| value.clone())
+}
+
+fn build_report(message: String, location: Option<String>, backtrace: String) -> CrashReport {
+    let now = Utc::now();
+    CrashReport {
+        id: format!("crash-{}", now.timestamp_millis()),
+        created_at: now.to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current().name().map(str::to_string),
+        message,
+        location,
+        backtrace,
+        breadcrumbs: snapshot(&BREADCRUMBS).map(Vec::from).unwrap_or_default(),
+        plugin_calls: snapshot(&PLUGIN_CALLS)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, call)| call)
+            .collect(),
+    }
+}
+
+fn report_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let valid = id.starts_with("crash-")
+        && id
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '-');
+    if !valid {
+        return Err(format!("Invalid crash report id '{}'", id));
+    }
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> io::Result<()> {
+    let path = report_path(dir, &report.id).map_err(io::Error::other)?;
+    let payload = serde_json::to_string_pretty(report).map_err(io::Error::other)?;
+    fs::write(path, payload)
+}
+
+/// Saved reports, newest first. Reports past `MAX_REPORTS` and files that
+/// don't parse are deleted.
+fn pending_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let report = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CrashReport>(&content).ok());
+        match report {
+            Some(report) => reports.push(report),
+            None => {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    for stale in reports.split_off(MAX_REPORTS.min(reports.len())) {
+        if let Ok(path) = report_path(dir, &stale.id) {
+            let _ = fs::remove_file(path);
+        }
+    }
+    reports
+}
+
+fn issue_body(report: &CrashReport) -> String {
+    let mut body = format!(
+        "Grainery {} on {} ({})\n\n**Panic:** {}\n",
+        report.app_version, report.os, report.arch, report.message
+    );
+    if let Some(location) = &report.location {
+        body.push_str(&format!("**At:** {}\n", location));
+    }
+    if !report.plugin_calls.is_empty() {
+        body.push_str("\n**Plugin calls in progress:**\n");
+        for call in &report.plugin_calls {
+            body.push_str(&format!("- {} `{}`\n", call.plugin_id, call.operation));
+        }
+    }
+    if !report.breadcrumbs.is_empty() {
+        body.push_str("\n**Last operations:**\n");
+        for breadcrumb in &report.breadcrumbs {
+            body.push_str(&format!(
+                "- {} {}: {}\n",
+                breadcrumb.at, breadcrumb.category, breadcrumb.detail
+            ));
+        }
+    }
+    body.push_str("\n**Backtrace:**\n```\n");
+    let room = MAX_ISSUE_BODY_CHARS.saturating_sub(body.chars().count() + 4);
+    body.extend(report.backtrace.chars().take(room));
+    body.push_str("\n```\n");
+    body
+}
+
+fn issue_url(report: &CrashReport) -> Result<Url, String> {
+    let title = format!(
+        "Crash: {}",
+        report.message.chars().take(80).collect::<String>()
+    );
+    Url::parse_with_params(ISSUE_URL, [("title", title), ("body", issue_body(report))])
+        .map_err(|error| format!("Failed to build crash report link: {}", error))
+}
+
+/// Chains a hook that saves a crash report before the existing one runs.
+pub fn install(app: &AppHandle) {
+    let dir = match app_data::app_data_dir(app, CRASH_DIR) {
+        Ok(dir) => dir,
+        Err(error) => {
+            tracing::warn!("Crash reports are disabled: {}", error);
+            return;
+        }
+    };
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = build_report(
+            panic_message(info),
+            info.location().map(ToString::to_string),
+            std::backtrace::Backtrace::force_capture().to_string(),
+        );
+        if write_report(&dir, &report).is_ok() {
+            tracing::error!("Saved crash report {}", report.id);
+        }
+        previous(info);
+    }));
+}
+
+/// Reports saved by earlier crashes that the user hasn't sent or dismissed.
+#[tauri::command]
+pub fn crash_reports_pending(app: AppHandle) -> Result<Vec<CrashReportSummary>, String> {
+    let dir = app_data::app_data_dir(&app, CRASH_DIR)?;
+    Ok(pending_reports(&dir)
+        .into_iter()
+        .map(|report| CrashReportSummary {
+            plugin_ids: report
+                .plugin_calls
+                .iter()
+                .map(|call| call.plugin_id.clone())
+                .collect(),
+            id: report.id,
+            created_at: report.created_at,
+            app_version: report.app_version,
+            message: report.message,
+        })
+        .collect())
+}
+
+/// Opens a prefilled GitHub issue with the report in the browser, where the
+/// user can read it before submitting, and removes the saved copy.
+#[tauri::command]
+pub fn crash_reports_send(app: AppHandle, id: String) -> Result<(), String> {
+    let path = report_path(&app_data::app_data_dir(&app, CRASH_DIR)?, &id)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read crash report: {}", error))?;
+    let report = serde_json::from_str::<CrashReport>(&content)
+        .map_err(|error| format!("Failed to parse crash report: {}", error))?;
+
+    file_manager::open_with_default(Path::new(issue_url(&report)?.as_str()))?;
+    fs::remove_file(&path).map_err(|error| format!("Failed to remove crash report: {}", error))
+}
+
+#[tauri::command]
+pub fn crash_reports_dismiss(app: AppHandle, id: String) -> Result<(), String> {
+    let path = report_path(&app_data::app_data_dir(&app, CRASH_DIR)?, &id)?;
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove crash report: {}", error))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_quoted_text() {
+        assert_eq!(
+            redact("byte index 5 is not a char boundary; it is inside 'é' of `INT. CAFÉ`"),
+            "byte index 5 is not a char boundary; it is inside 'é' of `…`"
+        );
+        assert_eq!(
+            redact(r#"called `Result::unwrap()` on an `Err` value: "MARA (V.O.)""#),
+            r#"called `…` on an `…` value: "…""#
+        );
+        assert_eq!(redact("unterminated \"secret"), "unterminated \"…");
+    }
+
+    #[test]
+    fn reports_plugin_calls_in_flight() {
+        let first = PluginCall::enter("com.example.first", "fs:read");
+        let second = PluginCall::enter("com.example.second", "network:get_json");
+        drop(first);
+
+        let report = build_report("boom".to_string(), None, String::new());
+        let plugins = report
+            .plugin_calls
+            .iter()
+            .map(|call| call.plugin_id.as_str())
+            .collect::<Vec<_>>();
+        assert!(plugins.contains(&"com.example.second"));
+        assert!(!plugins.contains(&"com.example.first"));
+        assert!(report
+            .breadcrumbs
+            .iter()
+            .any(|breadcrumb| breadcrumb.detail == "com.example.first fs:read"));
+        drop(second);
+    }
+
+    #[test]
+    fn keeps_the_newest_reports_and_links_them() {
+        let dir = std::env::temp_dir().join(format!("grainery-crashes-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for index in 0..MAX_REPORTS + 2 {
+            let mut report = build_report("boom".to_string(), None, "frame\n".repeat(5000));
+            report.id = format!("crash-{}", index);
+            report.created_at = format!("2026-01-0{}T00:00:00Z", index + 1);
+            write_report(&dir, &report).unwrap();
+        }
+        fs::write(dir.join("crash-broken.json"), "{").unwrap();
+
+        let reports = pending_reports(&dir);
+        assert_eq!(reports.len(), MAX_REPORTS);
+        assert_eq!(reports[0].id, format!("crash-{}", MAX_REPORTS + 1));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), MAX_REPORTS);
+
+        let body = issue_body(&reports[0]);
+        assert!(body.chars().count() <= MAX_ISSUE_BODY_CHARS + 8);
+        assert!(issue_url(&reports[0])
+            .unwrap()
+            .as_str()
+            .starts_with(ISSUE_URL));
+        assert!(report_path(&dir, "../settings").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::app_data::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+const DIALOG_DIRECTORIES_FILE: &str = "dialog-directories.json";
+const MAX_FILE_STEM_CHARS: usize = 120;
+
+type Filter = (&'static str, &'static [&'static str]);
+
+/// File dialog settings for one operation. `extension` is set for save
+/// operations and used for the suggested file name.
+struct DialogFormat {
+    operation: &'static str,
+    title: &'static str,
+    extension: Option<&'static str>,
+    filters: &'static [Filter],
+}
+
+const ALL_FILES: Filter = ("All Files", &["*"]);
+
+const FORMATS: [DialogFormat; 8] = [
+    DialogFormat {
+        operation: "open",
+        title: "Open",
+        extension: None,
+        filters: &[
+            ("Screenplay", &["gwx", "fdx", "fountain"]),
+            ("Grainery", &["gwx"]),
+            ("Final Draft", &["fdx"]),
+            ("Fountain", &["fountain"]),
+            ALL_FILES,
+        ],
+    },
+    DialogFormat {
+        operation: "import_fdx",
+        title: "Import Final Draft",
+        extension: None,
+        filters: &[("Final Draft", &["fdx"]), ALL_FILES],
+    },
+    DialogFormat {
+        operation: "save",
+        title: "Save As",
+        extension: Some("gwx"),
+        filters: &[("Grainery Document", &["gwx"])],
+    },
+    DialogFormat {
+        operation: "export_fountain",
+        title: "Export as Fountain",
+        extension: Some("fountain"),
+        filters: &[("Fountain", &["fountain"])],
+    },
+    DialogFormat {
+        operation: "export_pdf",
+        title: "Export as PDF",
+        extension: Some("pdf"),
+        filters: &[("PDF", &["pdf"])],
+    },
+    DialogFormat {
+        operation: "export_fdx",
+        title: "Export to Final Draft",
+        extension: Some("fdx"),
+        filters: &[("Final Draft", &["fdx"])],
+    },
+    DialogFormat {
+        operation: "export_feedback",
+        title: "Export for Feedback",
+        extension: Some("html"),
+        filters: &[("Web Page", &["html"])],
+    },
+    DialogFormat {
+        operation: "import_feedback",
+        title: "Import Feedback",
+        extension: None,
+        filters: &[("Feedback", &["json"]), ALL_FILES],
+    },
+];
+
+/// Last directory used per operation, so exports and saves each reopen
+/// where they were last pointed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DialogDirectories {
+    #[serde(default)]
+    directories: BTreeMap<String, PathBuf>,
+}
+
+/// What the frontend knows about the open document when asking for a save
+/// location.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSuggestion {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub current_path: Option<String>,
+}
+
+fn format_for(operation: &str) -> Result<&'static DialogFormat, String> {
+    FORMATS
+        .iter()
+        .find(|format| format.operation == operation)
+        .ok_or_else(|| format!("Unknown file dialog operation '{}'", operation))
+}
+
+/// Turns a title page title into a file stem that is valid on every
+/// platform: path and reserved characters are dropped and whitespace,
+/// including line breaks, collapses to single spaces.
+pub fn sanitize_file_stem(title: &str) -> String {
+    let cleaned = title
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .filter(|c| !matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*'))
+        .collect::<String>();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let truncated = collapsed
+        .chars()
+        .take(MAX_FILE_STEM_CHARS)
+        .collect::<String>();
+    // Windows drops trailing dots and spaces from file names.
+    truncated.trim_end_matches(['.', ' ']).to_string()
+}
+
+/// Suggests a file name. Saves keep the current name when there is one;
+/// exports are named after the title page title.
+fn suggest_file_name(
+    operation: &str,
+    extension: &str,
+    title: Option<&str>,
+    current_path: Option<&str>,
+) -> String {
+    let from_title = title
+        .map(sanitize_file_stem)
+        .filter(|stem| !stem.is_empty());
+    let from_path = current_path
+        .and_then(|path| Path::new(path).file_stem())
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty());
+
+    let stem = if operation == "save" {
+        from_path.or(from_title)
+    } else {
+        from_title.or(from_path)
+    };
+    format!(
+        "{}.{}",
+        stem.unwrap_or_else(|| "untitled".to_string()),
+        extension
+    )
+}
+
+fn starting_directory(
+    app: &AppHandle,
+    operation: &str,
+    current_path: Option<&str>,
+) -> Option<PathBuf> {
+    load_json::<DialogDirectories>(app, DIALOG_DIRECTORIES_FILE)
+        .ok()
+        .and_then(|store| store.directories.get(operation).cloned())
+        .filter(|directory| directory.is_dir())
+        .or_else(|| {
+            current_path
+                .and_then(|path| Path::new(path).parent())
+                .filter(|directory| directory.is_dir())
+                .map(Path::to_path_buf)
+        })
+}
+
+fn remember_directory(app: &AppHandle, operation: &str, path: &Path) -> Result<(), String> {
+    let Some(directory) = path.parent() else {
+        return Ok(());
+    };
+
+    let mut store = load_json::<DialogDirectories>(app, DIALOG_DIRECTORIES_FILE)?;
+    store
+        .directories
+        .insert(operation.to_string(), directory.to_path_buf());
+    save_json(app, DIALOG_DIRECTORIES_FILE, &store)
+}
+
+fn file_dialog(
+    app: &AppHandle,
+    format: &DialogFormat,
+    directory: Option<PathBuf>,
+) -> tauri_plugin_dialog::FileDialogBuilder<tauri::Wry> {
+    let mut dialog = app.dialog().file().set_title(format.title);
+    #[cfg(desktop)]
+    if let Some(window) = app.get_webview_window("main") {
+        dialog = dialog.set_parent(&window);
+    }
+    for (name, extensions) in format.filters {
+        dialog = dialog.add_filter(*name, extensions);
+    }
+    if let Some(directory) = directory {
+        dialog = dialog.set_directory(directory);
+    }
+    dialog
+}
+
+fn into_path_string(
+    app: &AppHandle,
+    operation: &str,
+    picked: Option<tauri_plugin_dialog::FilePath>,
+) -> Result<Option<String>, String> {
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+
+    // The mobile pickers hand out URLs that are only usable as-is, so they
+    // are kept rather than converted to paths.
+    #[cfg(mobile)]
+    if let tauri_plugin_dialog::FilePath::Url(url) = &picked {
+        crate::mobile_files::remember(app, url.as_str())?;
+        return Ok(Some(url.to_string()));
+    }
+
+    let path = picked
+        .into_path()
+        .map_err(|error| format!("Failed to resolve selected file: {}", error))?;
+    remember_directory(app, operation, &path)?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Shows the open dialog for `open` or `import_fdx`. Returns `None` when
+/// cancelled.
+#[tauri::command]
+pub async fn dialog_open_path(
+    app: AppHandle,
+    operation: String,
+    current_path: Option<String>,
+) -> Result<Option<String>, String> {
+    let format = format_for(&operation)?;
+    if format.extension.is_some() {
+        return Err(format!("'{}' is not an open operation", operation));
+    }
+
+    let directory = starting_directory(&app, &operation, current_path.as_deref());
+    let picked = file_dialog(&app, format, directory).blocking_pick_file();
+    into_path_string(&app, &operation, picked)
+}
+
+/// Shows the save dialog for `save` or one of the `export_*` operations,
+/// with the format's filter and a suggested file name.
+#[tauri::command]
+pub async fn dialog_save_path(
+    app: AppHandle,
+    operation: String,
+    suggestion: Option<SaveSuggestion>,
+) -> Result<Option<String>, String> {
+    let format = format_for(&operation)?;
+    let Some(extension) = format.extension else {
+        return Err(format!("'{}' is not a save operation", operation));
+    };
+
+    let suggestion = suggestion.unwrap_or_default();
+    let file_name = suggest_file_name(
+        &operation,
+        extension,
+        suggestion.title.as_deref(),
+        suggestion.current_path.as_deref(),
+    );
+    let directory = starting_directory(&app, &operation, suggestion.current_path.as_deref());
+    let picked = file_dialog(&app, format, directory)
+        .set_file_name(file_name)
+        .blocking_save_file();
+    into_path_string(&app, &operation, picked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_file_names_from_title_page() {
+        assert_eq!(
+            suggest_file_name(
+                "export_pdf",
+                "pdf",
+                Some("  Mission: Possible?\nPart 2. "),
+                None
+            ),
+            "Mission Possible Part 2.pdf"
+        );
+        assert_eq!(
+            suggest_file_name(
+                "save",
+                "gwx",
+                Some("Nightfall"),
+                Some("/drafts/draft-3.gwx")
+            ),
+            "draft-3.gwx"
+        );
+        assert_eq!(
+            suggest_file_name("export_fdx", "fdx", Some("  "), Some("/drafts/draft-3.gwx")),
+            "draft-3.fdx"
+        );
+        assert_eq!(suggest_file_name("save", "gwx", None, None), "untitled.gwx");
+        assert!(format_for("export_docx").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use serde_json::Value;
+
+pub mod repair;
+pub mod slugline;
+
+/// Version of the `.gwx` layout this build reads and writes, stored as the
+/// document's `formatVersion`.
+pub const FORMAT_VERSION: &str = "1.0";
+
+fn parse_format_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version
+        .trim()
+        .split_once('.')
+        .unwrap_or((version.trim(), "0"));
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Whether `version` is a format written by a newer build than this one.
+/// Unreadable versions are not treated as newer.
+pub fn is_newer_format(version: &str) -> bool {
+    match (
+        parse_format_version(version),
+        parse_format_version(FORMAT_VERSION),
+    ) {
+        (Some(version), Some(current)) => version > current,
+        _ => false,
+    }
+}
+
+/// Parses editor JSON, accepting either a full `.gwx` document or a bare
+/// ProseMirror `doc` node.
+pub fn parse_json(json: &str) -> Result<Value, String> {
+    serde_json::from_str::<Value>(json)
+        .map_err(|error| format!("Failed to parse document: {}", error))
+}
+
+fn has_wrapped_document(value: &Value) -> bool {
+    value.get("document").map(Value::is_object).unwrap_or(false)
+}
+
+/// Returns the ProseMirror `doc` node, unwrapping a full `.gwx` document if needed.
+pub fn content_root(value: &Value) -> &Value {
+    if has_wrapped_document(value) {
+        return &value["document"];
+    }
+
+    value
+}
+
+pub fn content_root_mut(value: &mut Value) -> &mut Value {
+    if has_wrapped_document(value) {
+        return &mut value["document"];
+    }
+
+    value
+}
+
+/// Top-level block nodes of the document (scene headings, action, dialogue, ...).
+pub fn blocks(value: &Value) -> &[Value] {
+    content_root(value)
+        .get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+pub fn blocks_mut(value: &mut Value) -> Option<&mut Vec<Value>> {
+    content_root_mut(value)
+        .get_mut("content")
+        .and_then(Value::as_array_mut)
+}
+
+pub fn node_type(node: &Value) -> &str {
+    node.get("type").and_then(Value::as_str).unwrap_or_default()
+}
+
+pub fn node_attr_str<'a>(node: &'a Value, key: &str) -> Option<&'a str> {
+    node.get("attrs")
+        .and_then(|attrs| attrs.get(key))
+        .and_then(Value::as_str)
+}
+
+/// Size of a top-level block in editor positions. Text blocks take two
+/// positions plus their text length in UTF-16 units; page breaks take one.
+pub fn node_size(block: &Value) -> usize {
+    if node_type(block) == "pageBreak" {
+        return 1;
+    }
+    let inline = block
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|content| {
+            content
+                .iter()
+                .map(|node| match node.get("text").and_then(Value::as_str) {
+                    Some(text) => text.encode_utf16().count(),
+                    None => 1,
+                })
+                .sum::<usize>()
+        })
+        .unwrap_or(0);
+    inline + 2
+}
+
+pub fn node_text(node: &Value) -> String {
+    if let Some(text) = node.get("text").and_then(Value::as_str) {
+        return text.to_string();
+    }
+
+    node.get("content")
+        .and_then(Value::as_array)
+        .map(|children| children.iter().map(node_text).collect::<String>())
+        .unwrap_or_default()
+}
+
+/// A block holding a single text run, for tests that build documents.
+#[cfg(test)]
+pub(crate) fn block(node_type: &str, text: &str) -> Value {
+    serde_json::json!({ "type": node_type, "content": [{ "type": "text", "text": text }] })
+}
+
+/// Normalizes a character cue to the bare name, dropping inline extensions
+/// such as `(V.O.)` or `(CONT'D)`.
+pub fn character_cue_name(text: &str) -> String {
+    text.split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_uppercase()
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneSpan {
+    /// Zero-based position of the scene in the script.
+    pub index: usize,
+    /// Block index of the scene heading.
+    pub heading_block: usize,
+    /// One past the last block that belongs to the scene.
+    pub end_block: usize,
+    pub heading: String,
+    pub scene_number: Option<String>,
+    /// Stable id from `attrs.sceneId`, used to link outline cards.
+    pub scene_id: Option<String>,
+}
+
+/// Splits the document into scenes at each scene heading. Blocks before the
+/// first heading do not belong to any scene.
+pub fn scenes(value: &Value) -> Vec<SceneSpan> {
+    block_scenes(blocks(value))
+}
+
+pub fn block_scenes(blocks: &[Value]) -> Vec<SceneSpan> {
+    let mut scenes: Vec<SceneSpan> = Vec::new();
+
+    for (block_index, block) in blocks.iter().enumerate() {
+        if node_type(block) != "sceneHeading" {
+            continue;
+        }
+
+        if let Some(previous) = scenes.last_mut() {
+            previous.end_block = block_index;
+        }
+
+        let scene_number = node_attr_str(block, "sceneNumber")
+            .map(str::to_string)
+            .or_else(|| {
+                block
+                    .get("attrs")
+                    .and_then(|attrs| attrs.get("sceneNumber"))
+                    .and_then(Value::as_u64)
+                    .map(|number| number.to_string())
+            });
+
+        scenes.push(SceneSpan {
+            index: scenes.len(),
+            heading_block: block_index,
+            end_block: blocks.len(),
+            heading: node_text(block).trim().to_string(),
+            scene_number,
+            scene_id: node_attr_str(block, "sceneId").map(str::to_string),
+        });
+    }
+
+    scenes
+}
+
+/// Maps every block index to the scene it belongs to.
+pub fn scene_index_by_block(scenes: &[SceneSpan], block_count: usize) -> Vec<Option<usize>> {
+    let mut output = vec![None; block_count];
+    for scene in scenes {
+        for slot in output
+            .iter_mut()
+            .take(scene.end_block)
+            .skip(scene.heading_block)
+        {
+            *slot = Some(scene.index);
+        }
+    }
+    output
+}
@@ -0,0 +1,291 @@
+//! Repairs for documents that are valid JSON but don't fit the editor
+//! schema, such as files edited by hand, written by an older build or cut
+//! short. Anything the editor would reject is mapped to the nearest thing it
+//! accepts, and every change is reported so the user knows what to check.
+
+use super::node_type;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// A block type and its attributes, as the editor schema defines them.
+type NodeSchema = (&'static str, &'static [&'static str]);
+
+const SCRIPT_NODES: [NodeSchema; 11] = [
+    ("sceneHeading", &["textAlign", "sceneNumber", "sceneId"]),
+    ("action", &["textAlign"]),
+    ("character", &["textAlign", "extension"]),
+    ("dialogue", &["textAlign", "nodeId"]),
+    ("parenthetical", &["textAlign"]),
+    ("transition", &["textAlign"]),
+    ("comicPage", &["textAlign"]),
+    ("comicPanel", &["textAlign"]),
+    ("caption", &["textAlign"]),
+    ("soundEffect", &["textAlign"]),
+    ("pageBreak", &[]),
+];
+const FREEWRITE_NODES: [NodeSchema; 5] = [
+    ("title", &["textAlign"]),
+    ("heading", &["textAlign"]),
+    ("body", &["textAlign"]),
+    ("bulletItem", &["textAlign"]),
+    ("numberedItem", &["textAlign"]),
+];
+const MARKS: [&str; 6] = [
+    "bold",
+    "italic",
+    "underline",
+    "strike",
+    "fontFamily",
+    "textSize",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Repair {
+    /// Index of the block in the repaired document, or `None` for changes
+    /// to the document as a whole.
+    pub block: Option<usize>,
+    pub message: String,
+}
+
+/// Block types allowed in the document's mode and the type unknown blocks
+/// become.
+fn schema(document_mode: &str) -> (&'static [NodeSchema], &'static str) {
+    if document_mode == "freewrite" {
+        (&FREEWRITE_NODES, "body")
+    } else {
+        (&SCRIPT_NODES, "action")
+    }
+}
+
+/// Keeps text nodes with text and known marks; other inline content is
+/// turned into text where it has some and dropped otherwise.
+fn repair_inline(children: &[Value], changed: &mut bool) -> Vec<Value> {
+    let mut repaired = Vec::new();
+    for child in children {
+        let Some(text) = child.get("text").and_then(Value::as_str) else {
+            *changed = true;
+            continue;
+        };
+        if text.is_empty() {
+            *changed = true;
+            continue;
+        }
+        let mut node = json!({ "type": "text", "text": text });
+        if node_type(child) != "text" {
+            *changed = true;
+        }
+        if let Some(marks) = child.get("marks") {
+            let kept = marks
+                .as_array()
+                .map(|marks| {
+                    marks
+                        .iter()
+                        .filter(|mark| MARKS.contains(&node_type(mark)))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if marks.as_array().map(Vec::len) != Some(kept.len()) {
+                *changed = true;
+            }
+            if !kept.is_empty() {
+                node["marks"] = Value::Array(kept);
+            }
+        }
+        repaired.push(node);
+    }
+    repaired
+}
+
+/// Repairs one top-level block, or returns `None` when there is nothing in
+/// it worth keeping.
+fn repair_block(
+    block: &Value,
+    document_mode: &str,
+    index: usize,
+    repairs: &mut Vec<Repair>,
+) -> Option<Value> {
+    let (nodes, fallback) = schema(document_mode);
+    let mut report = |message: String| {
+        repairs.push(Repair {
+            block: Some(index),
+            message,
+        })
+    };
+
+    let Some(object) = block.as_object() else {
+        report("Removed a block that was not an element".to_string());
+        return None;
+    };
+    let original_type = node_type(block);
+    let (block_type, attr_names) = match nodes.iter().find(|(name, _)| *name == original_type) {
+        Some(&(name, attrs)) => (name, attrs),
+        None => {
+            // A stray text node at the top level is kept as its own block.
+            if original_type.is_empty() && object.get("text").is_none() {
+                report("Removed a block with no type".to_string());
+                return None;
+            }
+            report(if original_type.is_empty() || original_type == "text" {
+                format!("Wrapped loose text in a {} block", fallback)
+            } else {
+                format!(
+                    "Changed unknown element \"{}\" to {}",
+                    original_type, fallback
+                )
+            });
+            let fallback_attrs = nodes
+                .iter()
+                .find(|(name, _)| *name == fallback)
+                .map(|(_, attrs)| *attrs)
+                .unwrap_or_default();
+            (fallback, fallback_attrs)
+        }
+    };
+
+    let mut repaired = Map::new();
+    repaired.insert("type".to_string(), json!(block_type));
+
+    let mut attrs = match object.get("attrs") {
+        Some(Value::Object(attrs)) => attrs.clone(),
+        Some(_) => {
+            report("Reset invalid attributes".to_string());
+            Map::new()
+        }
+        None => Map::new(),
+    };
+    // Files from before an attribute existed lack it too, so defaults are
+    // filled in without being reported.
+    for name in attr_names {
+        attrs.entry(name.to_string()).or_insert(Value::Null);
+    }
+    if !attr_names.is_empty() {
+        repaired.insert("attrs".to_string(), Value::Object(attrs));
+    }
+
+    if block_type != "pageBreak" {
+        let children = if original_type == "text" || object.get("text").is_some() {
+            vec![block.clone()]
+        } else {
+            match object.get("content") {
+                Some(Value::Array(children)) => children.clone(),
+                Some(_) => {
+                    report("Removed invalid content".to_string());
+                    Vec::new()
+                }
+                None => Vec::new(),
+            }
+        };
+        let mut changed = false;
+        let content = repair_inline(&children, &mut changed);
+        if changed {
+            report("Cleaned up text with unsupported formatting or content".to_string());
+        }
+        if !content.is_empty() {
+            repaired.insert("content".to_string(), Value::Array(content));
+        }
+    }
+
+    Some(Value::Object(repaired))
+}
+
+/// Repairs the `doc` node in `root` in place for `document_mode` and
+/// reports what was changed. Returns no repairs for a healthy document.
+pub fn repair(root: &mut Value, document_mode: &str) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    let blocks = root
+        .get("content")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut repaired = Vec::new();
+    for block in &blocks {
+        let before = repairs.len();
+        let index = repaired.len();
+        if let Some(block) = repair_block(block, document_mode, index, &mut repairs) {
+            repaired.push(block);
+        } else if let Some(last) = repairs[before..].last_mut() {
+            // A dropped block has no index in the repaired document.
+            last.block = None;
+        }
+    }
+
+    if repaired.is_empty() {
+        let (_, fallback) = schema(document_mode);
+        repaired.push(json!({ "type": fallback }));
+        repairs.push(Repair {
+            block: None,
+            message: "Added an empty element to an empty document".to_string(),
+        });
+    }
+
+    if repairs.is_empty() {
+        return repairs;
+    }
+    if let Some(object) = root.as_object_mut() {
+        object.insert("content".to_string(), Value::Array(repaired));
+    }
+    repairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_healthy_documents_alone() {
+        let mut root = json!({ "type": "doc", "content": [
+            { "type": "sceneHeading", "attrs": { "textAlign": null, "sceneNumber": "1", "sceneId": null },
+              "content": [{ "type": "text", "text": "INT. HALL - DAY", "marks": [{ "type": "bold" }] }] },
+            { "type": "action", "content": [{ "type": "text", "text": "Quiet." }] },
+            { "type": "pageBreak" },
+        ]});
+        let original = root.clone();
+
+        assert!(repair(&mut root, "screenplay").is_empty());
+        assert_eq!(root, original);
+    }
+
+    #[test]
+    fn maps_unknown_blocks_and_defaults_missing_attrs() {
+        let mut root = json!({ "type": "doc", "content": [
+            { "type": "shot", "content": [{ "type": "text", "text": "CLOSE ON Tom." }] },
+            { "type": "character", "attrs": { "textAlign": null },
+              "content": [{ "type": "text", "text": "TOM", "marks": [{ "type": "highlight" }] }] },
+            "garbage",
+            { "type": "dialogue", "content": [
+                { "type": "text", "text": "" },
+                { "type": "hardBreak" },
+                { "type": "mention", "text": "Hi." },
+            ] },
+            { "type": "text", "text": "Loose." },
+        ]});
+
+        let repairs = repair(&mut root, "screenplay");
+        let blocks = root["content"].as_array().unwrap();
+
+        assert_eq!(
+            blocks.iter().map(node_type).collect::<Vec<_>>(),
+            ["action", "character", "dialogue", "action"]
+        );
+        assert_eq!(blocks[0]["content"][0]["text"], "CLOSE ON Tom.");
+        assert_eq!(blocks[1]["attrs"]["extension"], Value::Null);
+        assert!(blocks[1]["content"][0].get("marks").is_none());
+        assert_eq!(
+            blocks[2]["content"],
+            json!([{ "type": "text", "text": "Hi." }])
+        );
+        assert!(blocks[2]["attrs"].get("nodeId").is_some());
+        assert_eq!(blocks[3]["content"][0]["text"], "Loose.");
+
+        assert_eq!(repairs[0].block, Some(0));
+        assert!(repairs[0].message.contains("\"shot\""));
+        assert!(repairs.iter().any(|repair| repair.block.is_none()));
+
+        let mut empty = json!({ "type": "doc", "content": [] });
+        assert_eq!(repair(&mut empty, "freewrite").len(), 1);
+        assert_eq!(empty["content"], json!([{ "type": "body" }]));
+    }
+}
@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+const SCENE_PREFIXES: [&str; 7] = [
+    "INT./EXT.",
+    "EXT./INT.",
+    "INT/EXT",
+    "I/E.",
+    "INT.",
+    "EXT.",
+    "EST.",
+];
+const TIME_SEPARATORS: [&str; 3] = [" - ", " \u{2013} ", " \u{2014} "];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Slugline {
+    /// Normalized prefix: `INT`, `EXT`, `INT/EXT` or `EST`; empty when missing.
+    pub int_ext: String,
+    pub location: String,
+    pub time: String,
+}
+
+fn normalize_prefix(prefix: &str) -> &'static str {
+    match prefix {
+        "INT." => "INT",
+        "EXT." => "EXT",
+        "EST." => "EST",
+        _ => "INT/EXT",
+    }
+}
+
+/// Splits a scene heading like `INT. KITCHEN - NIGHT` into its parts.
+pub fn parse(heading: &str) -> Slugline {
+    let upper = heading.trim().to_uppercase();
+    let mut slugline = Slugline::default();
+
+    let rest = match SCENE_PREFIXES
+        .iter()
+        .find(|prefix| upper.starts_with(*prefix))
+    {
+        Some(prefix) => {
+            slugline.int_ext = normalize_prefix(prefix).to_string();
+            upper[prefix.len()..].trim()
+        }
+        None => upper.as_str(),
+    };
+
+    let split = TIME_SEPARATORS
+        .iter()
+        .filter_map(|separator| rest.rfind(separator).map(|index| (index, separator.len())))
+        .max_by_key(|(index, _)| *index);
+
+    match split {
+        Some((index, length)) => {
+            slugline.location = rest[..index].trim().to_string();
+            slugline.time = rest[index + length..].trim().to_string();
+        }
+        None => slugline.location = rest.to_string(),
+    }
+
+    slugline
+}
+
+/// Buckets a slugline time into the day/night categories used on
+/// production reports.
+pub fn day_night(time: &str) -> &'static str {
+    let time = time.to_uppercase();
+    let has = |word: &str| {
+        time.split(|c: char| !c.is_alphanumeric())
+            .any(|part| part == word)
+    };
+
+    if has("NIGHT") || has("EVENING") || has("MIDNIGHT") {
+        "NIGHT"
+    } else if has("DAWN") || has("SUNRISE") {
+        "DAWN"
+    } else if has("DUSK") || has("SUNSET") || has("TWILIGHT") {
+        "DUSK"
+    } else if has("DAY") || has("MORNING") || has("AFTERNOON") || has("NOON") {
+        "DAY"
+    } else {
+        "OTHER"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_prefix_location_and_time() {
+        assert_eq!(
+            parse("int. kitchen - night"),
+            Slugline {
+                int_ext: "INT".to_string(),
+                location: "KITCHEN".to_string(),
+                time: "NIGHT".to_string(),
+            }
+        );
+        assert_eq!(
+            parse("INT./EXT. CAR - MOVING - DAY").location,
+            "CAR - MOVING"
+        );
+        assert_eq!(parse("SPACE STATION").int_ext, "");
+    }
+
+    #[test]
+    fn buckets_times_of_day() {
+        assert_eq!(day_night("LATE NIGHT"), "NIGHT");
+        assert_eq!(day_night("CONTINUOUS"), "OTHER");
+        assert_eq!(day_night("MORNING (FLASHBACK)"), "DAY");
+    }
+}
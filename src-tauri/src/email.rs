@@ -0,0 +1,225 @@
+use crate::{dialogs, pdf};
+use chrono::Utc;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FALLBACK_SUBJECT: &str = "Screenplay";
+
+/// The title page title, else the document title.
+fn email_subject(title_page_json: Option<&str>, document_title: &str) -> String {
+    let title_page_title = title_page_json
+        .and_then(|json| serde_json::from_str::<Value>(json).ok())
+        .and_then(|page| {
+            page.get("title")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        });
+    let subject = [title_page_title.as_deref(), Some(document_title)]
+        .into_iter()
+        .flatten()
+        .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+        .find(|title| !title.is_empty());
+    subject.unwrap_or_else(|| FALLBACK_SUBJECT.to_string())
+}
+
+/// A fresh folder per email so the attachment can carry the script's name.
+fn attachment_path(subject: &str) -> Result<PathBuf, String> {
+    let folder =
+        std::env::temp_dir().join(format!("grainery-email-{}", Utc::now().timestamp_millis()));
+    fs::create_dir_all(&folder)
+        .map_err(|error| format!("Failed to create email attachment folder: {}", error))?;
+    let stem = dialogs::sanitize_file_stem(subject);
+    let stem = if stem.is_empty() {
+        FALLBACK_SUBJECT.to_string()
+    } else {
+        stem
+    };
+    Ok(folder.join(format!("{}.pdf", stem)))
+}
+
+#[cfg(target_os = "macos")]
+fn compose(subject: &str, attachment: &Path) -> Result<(), String> {
+    use cocoa::base::{id, nil, BOOL, NO};
+    use cocoa::foundation::{NSArray, NSString};
+
+    unsafe {
+        let name = NSString::alloc(nil).init_str("com.apple.share.Mail.compose");
+        let service: id = msg_send![class!(NSSharingService), sharingServiceNamed: name];
+        let path = NSString::alloc(nil).init_str(&attachment.to_string_lossy());
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: path];
+        let items = NSArray::arrayWithObject(nil, url);
+        let available: BOOL = if service == nil {
+            NO
+        } else {
+            msg_send![service, canPerformWithItems: items]
+        };
+        if available == NO {
+            return Err("No mail app is set up to send email".to_string());
+        }
+
+        let subject = NSString::alloc(nil).init_str(subject);
+        let () = msg_send![service, setSubject: subject];
+        let () = msg_send![service, performWithItems: items];
+    }
+
+    Ok(())
+}
+
+/// Hands the draft to the default mail client through Simple MAPI.
+/// `MAPISendMailW` can block until the draft is sent or closed, so it runs
+/// on its own thread and only failures reported straight away are returned.
+#[cfg(windows)]
+fn compose(subject: &str, attachment: &Path) -> Result<(), String> {
+    use std::ffi::{c_void, OsStr};
+    use std::os::windows::ffi::OsStrExt;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[repr(C)]
+    struct MapiFileDescW {
+        reserved: u32,
+        flags: u32,
+        position: u32,
+        path_name: *const u16,
+        file_name: *const u16,
+        file_type: *mut c_void,
+    }
+
+    #[repr(C)]
+    struct MapiMessageW {
+        reserved: u32,
+        subject: *const u16,
+        note_text: *const u16,
+        message_type: *const u16,
+        date_received: *const u16,
+        conversation_id: *const u16,
+        flags: u32,
+        originator: *mut c_void,
+        recip_count: u32,
+        recips: *mut c_void,
+        file_count: u32,
+        files: *mut MapiFileDescW,
+    }
+
+    #[link(name = "mapi32")]
+    extern "system" {
+        fn MAPISendMailW(
+            session: usize,
+            ui_param: usize,
+            message: *mut MapiMessageW,
+            flags: u32,
+            reserved: u32,
+        ) -> u32;
+    }
+
+    const MAPI_LOGON_UI: u32 = 0x1;
+    const MAPI_DIALOG: u32 = 0x8;
+    const SUCCESS_SUCCESS: u32 = 0;
+    const MAPI_USER_ABORT: u32 = 1;
+    const NO_POSITION: u32 = u32::MAX;
+    const FAILURE_GRACE: Duration = Duration::from_secs(2);
+
+    let wide = |value: &OsStr| value.encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let subject = wide(OsStr::new(subject));
+    let path_name = wide(attachment.as_os_str());
+    let file_name = wide(attachment.file_name().unwrap_or_default());
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut file = MapiFileDescW {
+            reserved: 0,
+            flags: 0,
+            position: NO_POSITION,
+            path_name: path_name.as_ptr(),
+            file_name: file_name.as_ptr(),
+            file_type: std::ptr::null_mut(),
+        };
+        let mut message = MapiMessageW {
+            reserved: 0,
+            subject: subject.as_ptr(),
+            note_text: std::ptr::null(),
+            message_type: std::ptr::null(),
+            date_received: std::ptr::null(),
+            conversation_id: std::ptr::null(),
+            flags: 0,
+            originator: std::ptr::null_mut(),
+            recip_count: 0,
+            recips: std::ptr::null_mut(),
+            file_count: 1,
+            files: &mut file,
+        };
+        let result = unsafe { MAPISendMailW(0, 0, &mut message, MAPI_LOGON_UI | MAPI_DIALOG, 0) };
+        let _ = sender.send(result);
+    });
+
+    match receiver.recv_timeout(FAILURE_GRACE) {
+        Ok(SUCCESS_SUCCESS | MAPI_USER_ABORT) | Err(mpsc::RecvTimeoutError::Timeout) => Ok(()),
+        Ok(code) => Err(format!(
+            "No mail app is set up to send email (MAPI error {})",
+            code
+        )),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err("Failed to open mail app".to_string()),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn compose(subject: &str, attachment: &Path) -> Result<(), String> {
+    std::process::Command::new("xdg-email")
+        .arg("--subject")
+        .arg(subject)
+        .arg("--attach")
+        .arg(attachment)
+        .spawn()
+        .map(|_| ())
+        .map_err(|error| format!("Failed to open mail app: {}", error))
+}
+
+/// Exports the document as a PDF and opens a new email in the default mail
+/// client with it attached and the title as the subject. The PDF is left in
+/// the temp folder, since the mail client reads it after this returns.
+#[tauri::command]
+pub fn share_via_email(
+    content_json: String,
+    title_page_json: Option<String>,
+    document_title: String,
+    document_mode: String,
+    options: Option<pdf::PdfOptions>,
+) -> Result<(), String> {
+    let subject = email_subject(title_page_json.as_deref(), &document_title);
+    let path = attachment_path(&subject)?;
+    pdf::generate_pdf_with_options(
+        &content_json,
+        title_page_json.as_deref(),
+        &path.to_string_lossy(),
+        &document_title,
+        &document_mode,
+        &options.unwrap_or_default(),
+    )?;
+    compose(&subject, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_the_subject_from_the_title_page() {
+        assert_eq!(
+            email_subject(
+                Some(r#"{"title":"  Night\nfall ","author":"J"}"#),
+                "draft-3"
+            ),
+            "Night fall"
+        );
+        assert_eq!(
+            email_subject(Some(r#"{"title":"   "}"#), "draft-3"),
+            "draft-3"
+        );
+        assert_eq!(email_subject(None, " "), "Screenplay");
+
+        let path = attachment_path("Pilot: Part 1").unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        assert!(path.ends_with("Pilot Part 1.pdf"));
+    }
+}
@@ -0,0 +1,211 @@
+//! Errors returned to the frontend by save, load, export and plugin
+//! commands. Besides the message they say what went wrong, which file it
+//! concerns and whether retrying elsewhere can help, so the UI can offer a
+//! way out instead of only showing text.
+//!
+//! Helpers inside the crate keep returning `Result<_, String>`; `?` turns
+//! those into an `Other` error, and commands classify the failures they can
+//! tell apart.
+
+use crate::jobs;
+use serde::Serialize;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    NotFound,
+    PermissionDenied,
+    DiskFull,
+    ReadOnly,
+    InvalidDocument,
+    Network,
+    Cancelled,
+    Other,
+}
+
+impl ErrorKind {
+    fn recoverable(self) -> bool {
+        !matches!(self, Self::InvalidDocument | Self::Other)
+    }
+
+    fn suggestion(self) -> Option<&'static str> {
+        match self {
+            Self::NotFound => Some("Check that the file or folder still exists."),
+            Self::PermissionDenied | Self::ReadOnly => Some("Choose a different folder."),
+            Self::DiskFull => Some("Free up disk space or choose a different drive."),
+            Self::InvalidDocument => {
+                Some("The file may be damaged. Try a backup or an earlier snapshot.")
+            }
+            Self::Network => Some("Check your internet connection and try again."),
+            Self::Cancelled | Self::Other => None,
+        }
+    }
+
+    fn from_io(kind: io::ErrorKind) -> Self {
+        match kind {
+            io::ErrorKind::NotFound => Self::NotFound,
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            io::ErrorKind::StorageFull => Self::DiskFull,
+            io::ErrorKind::ReadOnlyFilesystem => Self::ReadOnly,
+            io::ErrorKind::InvalidData => Self::InvalidDocument,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// File the command was reading or writing, when there is one.
+    pub path: Option<String>,
+    /// Whether the same action can succeed after the user does something,
+    /// such as picking another folder or reconnecting.
+    pub recoverable: bool,
+    pub suggestion: Option<String>,
+}
+
+impl CommandError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            path: None,
+            recoverable: kind.recoverable(),
+            suggestion: kind.suggestion().map(str::to_string),
+        }
+    }
+
+    /// A failed file operation, e.g. `io(&error, "save document", path)`.
+    pub fn io(error: &io::Error, action: &str, path: &str) -> Self {
+        Self::new(
+            ErrorKind::from_io(error.kind()),
+            format!("Failed to {}: {}", action, error),
+        )
+        .with_path(path)
+    }
+
+    /// A failed write to `path` whose cause was only reported as text. The
+    /// destination is checked again to find out why.
+    pub fn write_failed(path: &str, message: impl Into<String>) -> Self {
+        let message = message.into();
+        if message == jobs::CANCELLED {
+            return Self::from(message);
+        }
+        let kind = diagnose_write(Path::new(path)).unwrap_or(ErrorKind::Other);
+        Self::new(kind, message).with_path(path)
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+/// Why `path` can't be written, if the folder it goes in says so.
+fn diagnose_write(path: &Path) -> Option<ErrorKind> {
+    let folder = path
+        .parent()
+        .filter(|folder| !folder.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    if !folder.is_dir() {
+        return Some(ErrorKind::NotFound);
+    }
+
+    let probe = folder.join(format!(".grainery-write-check-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            None
+        }
+        Err(error) => {
+            Some(ErrorKind::from_io(error.kind())).filter(|kind| *kind != ErrorKind::Other)
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        let kind = if message == jobs::CANCELLED {
+            ErrorKind::Cancelled
+        } else {
+            ErrorKind::Other
+        };
+        Self::new(kind, message)
+    }
+}
+
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        error.message
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_io_errors() {
+        let error = io::Error::from(io::ErrorKind::PermissionDenied);
+        let error = CommandError::io(&error, "save document", "/scripts/pilot.gwx");
+        assert_eq!(error.kind, ErrorKind::PermissionDenied);
+        assert_eq!(error.path.as_deref(), Some("/scripts/pilot.gwx"));
+        assert!(error.recoverable);
+        assert_eq!(
+            error.suggestion.as_deref(),
+            Some("Choose a different folder.")
+        );
+        assert!(error.message.starts_with("Failed to save document: "));
+
+        let error = CommandError::from(jobs::CANCELLED.to_string());
+        assert_eq!(error.kind, ErrorKind::Cancelled);
+        let error = CommandError::from("Failed to parse JSON".to_string());
+        assert_eq!((error.kind, error.recoverable), (ErrorKind::Other, false));
+    }
+
+    #[test]
+    fn diagnoses_failed_writes() {
+        let dir = std::env::temp_dir().join(format!("grainery-error-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let missing = dir.join("missing").join("pilot.pdf");
+        let error = CommandError::write_failed(&missing.to_string_lossy(), "Failed");
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        assert!(error.recoverable);
+
+        let error = CommandError::write_failed(&dir.join("pilot.pdf").to_string_lossy(), "Failed");
+        assert_eq!(error.kind, ErrorKind::Other);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        let error = CommandError::write_failed("pilot.pdf", jobs::CANCELLED);
+        assert_eq!((error.kind, error.path), (ErrorKind::Cancelled, None));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn serializes_for_the_frontend() {
+        let error = CommandError::new(ErrorKind::DiskFull, "No space").with_path("/tmp/a.pdf");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "diskFull",
+                "message": "No space",
+                "path": "/tmp/a.pdf",
+                "recoverable": true,
+                "suggestion": "Free up disk space or choose a different drive.",
+            })
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use crate::app_data::{load_json, save_json};
+use crate::pdf::PdfOptions;
+use crate::settings::ExportFormat;
+use crate::sidecar;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+const EXPORT_PRESETS_FILE: &str = "export-presets.json";
+const MAX_PRESET_NAME_CHARS: usize = 60;
+const MAX_WATERMARK_CHARS: usize = 40;
+
+/// A named set of export options, e.g. "Production Draft".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPreset {
+    pub name: String,
+    pub format: ExportFormat,
+    #[serde(default = "default_include_title_page")]
+    pub include_title_page: bool,
+    /// Page setup for PDF exports; ignored by the text formats.
+    #[serde(default)]
+    pub pdf: PdfOptions,
+}
+
+fn default_include_title_page() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GlobalPresets {
+    #[serde(default)]
+    presets: Vec<ExportPreset>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PresetScope {
+    Document,
+    Global,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPresetEntry {
+    pub scope: PresetScope,
+    pub preset: ExportPreset,
+}
+
+fn validate(preset: &mut ExportPreset) -> Result<(), String> {
+    preset.name = preset.name.trim().to_string();
+    if preset.name.is_empty() {
+        return Err("Preset name is required".to_string());
+    }
+    if preset.name.chars().count() > MAX_PRESET_NAME_CHARS {
+        return Err(format!(
+            "Preset names are limited to {} characters",
+            MAX_PRESET_NAME_CHARS
+        ));
+    }
+
+    preset.pdf.watermark = preset
+        .pdf
+        .watermark
+        .as_deref()
+        .map(str::trim)
+        .filter(|watermark| !watermark.is_empty())
+        .map(str::to_string);
+    if preset
+        .pdf
+        .watermark
+        .as_ref()
+        .is_some_and(|watermark| watermark.chars().count() > MAX_WATERMARK_CHARS)
+    {
+        return Err(format!(
+            "Watermarks are limited to {} characters",
+            MAX_WATERMARK_CHARS
+        ));
+    }
+
+    Ok(())
+}
+
+fn same_name(preset: &ExportPreset, name: &str) -> bool {
+    preset.name.eq_ignore_ascii_case(name.trim())
+}
+
+/// Replaces the preset with the same name, or appends it.
+fn upsert(presets: &mut Vec<ExportPreset>, preset: ExportPreset) {
+    match presets
+        .iter_mut()
+        .find(|existing| same_name(existing, &preset.name))
+    {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+}
+
+/// Document presets first, then global presets whose name the document
+/// doesn't override.
+fn merge(document: Vec<ExportPreset>, global: Vec<ExportPreset>) -> Vec<ExportPresetEntry> {
+    let mut entries = document
+        .into_iter()
+        .map(|preset| ExportPresetEntry {
+            scope: PresetScope::Document,
+            preset,
+        })
+        .collect::<Vec<_>>();
+    for preset in global {
+        if !entries
+            .iter()
+            .any(|entry| same_name(&entry.preset, &preset.name))
+        {
+            entries.push(ExportPresetEntry {
+                scope: PresetScope::Global,
+                preset,
+            });
+        }
+    }
+    entries
+}
+
+fn list(app: &AppHandle, document_id: Option<&str>) -> Result<Vec<ExportPresetEntry>, String> {
+    let global = load_json::<GlobalPresets>(app, EXPORT_PRESETS_FILE)?.presets;
+    let document = match document_id {
+        Some(document_id) => sidecar::load(app, document_id)?.export_presets,
+        None => Vec::new(),
+    };
+    Ok(merge(document, global))
+}
+
+#[tauri::command]
+pub fn export_presets_list(
+    app: AppHandle,
+    document_id: Option<String>,
+) -> Result<Vec<ExportPresetEntry>, String> {
+    list(&app, document_id.as_deref())
+}
+
+/// Saves a preset for one document, or globally when `document_id` is
+/// omitted. A preset with the same name in that scope is replaced.
+#[tauri::command]
+pub fn export_presets_save(
+    app: AppHandle,
+    document_id: Option<String>,
+    mut preset: ExportPreset,
+) -> Result<Vec<ExportPresetEntry>, String> {
+    validate(&mut preset)?;
+
+    match document_id.as_deref() {
+        Some(document_id) => sidecar::update(&app, document_id, |store| {
+            upsert(&mut store.export_presets, preset);
+            Ok(())
+        })?,
+        None => {
+            let mut store = load_json::<GlobalPresets>(&app, EXPORT_PRESETS_FILE)?;
+            upsert(&mut store.presets, preset);
+            save_json(&app, EXPORT_PRESETS_FILE, &store)?;
+        }
+    }
+
+    list(&app, document_id.as_deref())
+}
+
+#[tauri::command]
+pub fn export_presets_delete(
+    app: AppHandle,
+    document_id: Option<String>,
+    scope: PresetScope,
+    name: String,
+) -> Result<Vec<ExportPresetEntry>, String> {
+    match (scope, document_id.as_deref()) {
+        (PresetScope::Document, Some(document_id)) => {
+            sidecar::update(&app, document_id, |store| {
+                store
+                    .export_presets
+                    .retain(|preset| !same_name(preset, &name));
+                Ok(())
+            })?
+        }
+        (PresetScope::Document, None) => {
+            return Err("A document is required to delete its presets".to_string())
+        }
+        (PresetScope::Global, _) => {
+            let mut store = load_json::<GlobalPresets>(&app, EXPORT_PRESETS_FILE)?;
+            store.presets.retain(|preset| !same_name(preset, &name));
+            save_json(&app, EXPORT_PRESETS_FILE, &store)?;
+        }
+    }
+
+    list(&app, document_id.as_deref())
+}
+
+/// Resolves a preset by name for an export, preferring the document's own.
+#[tauri::command]
+pub fn export_presets_apply(
+    app: AppHandle,
+    document_id: Option<String>,
+    name: String,
+) -> Result<ExportPreset, String> {
+    list(&app, document_id.as_deref())?
+        .into_iter()
+        .find(|entry| same_name(&entry.preset, &name))
+        .map(|entry| entry.preset)
+        .ok_or_else(|| format!("Export preset '{}' not found", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, format: ExportFormat) -> ExportPreset {
+        ExportPreset {
+            name: name.to_string(),
+            format,
+            include_title_page: true,
+            pdf: PdfOptions::default(),
+        }
+    }
+
+    #[test]
+    fn document_presets_shadow_global_ones() {
+        let mut global = vec![preset("Production Draft", ExportFormat::Pdf)];
+        upsert(&mut global, preset("Reading Copy", ExportFormat::Pdf));
+        upsert(&mut global, preset("reading copy", ExportFormat::Fountain));
+        assert_eq!(global.len(), 2);
+        assert_eq!(global[1].format, ExportFormat::Fountain);
+
+        let entries = merge(vec![preset("production draft", ExportFormat::Fdx)], global);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].scope, PresetScope::Document);
+        assert_eq!(entries[0].preset.format, ExportFormat::Fdx);
+        assert_eq!(entries[1].scope, PresetScope::Global);
+
+        let mut invalid = preset("  ", ExportFormat::Pdf);
+        assert!(validate(&mut invalid).is_err());
+    }
+}
@@ -0,0 +1,386 @@
+use crate::analysis::pages;
+use crate::comments::{self, CommentAnchor};
+use crate::{document, jobs};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use tauri::AppHandle;
+
+const FEEDBACK_FORMAT_VERSION: u32 = 1;
+const TEMPLATE: &str = include_str!("template.html");
+
+/// One note left in the web bundle by a reader. `quote` is the text they had
+/// selected, if any, inside block `block_index`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackNote {
+    pub block_index: usize,
+    #[serde(default)]
+    pub quote: String,
+    pub author: String,
+    pub body: String,
+}
+
+/// The JSON file the web bundle's "Download feedback" button writes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackFile {
+    pub format_version: u32,
+    #[serde(default)]
+    pub document_id: Option<String>,
+    #[serde(default)]
+    pub notes: Vec<FeedbackNote>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackImport {
+    pub document_json: String,
+    pub imported: usize,
+    /// Notes already imported, or pointing past the end of the script.
+    pub skipped: usize,
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn render_inline(block: &Value) -> String {
+    let Some(content) = block.get("content").and_then(Value::as_array) else {
+        return String::new();
+    };
+
+    let mut html = String::new();
+    for node in content {
+        if document::node_type(node) == "hardBreak" {
+            html.push_str("<br>");
+            continue;
+        }
+        let Some(text) = node.get("text").and_then(Value::as_str) else {
+            continue;
+        };
+
+        let mut text = escape_html(text);
+        for mark in node
+            .get("marks")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let tag = match document::node_type(mark) {
+                "bold" => "strong",
+                "italic" => "em",
+                "underline" => "u",
+                _ => continue,
+            };
+            text = format!("<{tag}>{text}</{tag}>");
+        }
+        html.push_str(&text);
+    }
+    html
+}
+
+fn render_title_page(title_page: &Value) -> String {
+    let field = |key: &str| {
+        title_page
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| escape_html(value).replace('\n', "<br>"))
+    };
+
+    let mut html = String::from("<section class=\"page title-page\" id=\"title-page\">");
+    for (key, class) in [
+        ("title", "title"),
+        ("credit", "credit"),
+        ("author", "author"),
+        ("source", "source"),
+    ] {
+        if let Some(value) = field(key) {
+            html.push_str(&format!("<p class=\"{}\">{}</p>", class, value));
+        }
+    }
+    for key in ["contact", "draftDate", "copyright"] {
+        if let Some(value) = field(key) {
+            html.push_str(&format!("<p class=\"contact\">{}</p>", value));
+        }
+    }
+    html.push_str("</section>");
+    html
+}
+
+/// Renders the script as one HTML file: pages laid out with the editor's
+/// page estimate, a page and scene navigator, and a form that collects
+/// notes into a `FeedbackFile` download. Nothing is loaded from the network.
+pub fn render(value: &Value, document_title: &str) -> String {
+    let root = document::content_root(value);
+    let blocks = document::blocks(value);
+    let estimate = pages::estimate_pages(root);
+
+    let mut body = String::new();
+    if let Some(title_page) = value.get("titlePage").filter(|page| page.is_object()) {
+        body.push_str(&render_title_page(title_page));
+    }
+
+    let mut current_page = 0;
+    for (index, block) in blocks.iter().enumerate() {
+        let page = estimate.block_pages.get(index).copied().unwrap_or(1);
+        if page != current_page {
+            if current_page != 0 {
+                body.push_str("</section>");
+            }
+            body.push_str(&format!(
+                "<section class=\"page\" id=\"page-{page}\"><div class=\"page-number\">{page}.</div>"
+            ));
+            current_page = page;
+        }
+
+        let node_type = document::node_type(block);
+        if node_type == "pageBreak" {
+            continue;
+        }
+        let mut inner = render_inline(block);
+        if node_type == "parenthetical" && !inner.is_empty() {
+            inner = format!("({})", inner);
+        }
+        body.push_str(&format!(
+            "<p class=\"block {}\" id=\"block-{}\" data-block=\"{}\">{}</p>",
+            escape_html(node_type),
+            index,
+            index,
+            inner
+        ));
+    }
+    if current_page != 0 {
+        body.push_str("</section>");
+    }
+
+    let mut nav = String::new();
+    for page in 1..=estimate.total_pages {
+        nav.push_str(&format!("<a href=\"#page-{page}\">{page}</a>"));
+    }
+    let mut scenes = String::new();
+    for scene in document::scenes(value) {
+        scenes.push_str(&format!(
+            "<li><a href=\"#block-{}\">{}</a></li>",
+            scene.heading_block,
+            escape_html(&scene.heading)
+        ));
+    }
+
+    // Closing tags are escaped so script text can't end the <script> block.
+    let meta = serde_json::json!({
+        "documentId": value.pointer("/meta/id").and_then(Value::as_str),
+        "title": document_title,
+        "formatVersion": FEEDBACK_FORMAT_VERSION,
+    })
+    .to_string()
+    .replace("</", "<\\/");
+
+    fill_template(&[
+        ("TITLE", escape_html(document_title)),
+        ("PAGE_NAV", nav),
+        ("SCENE_NAV", scenes),
+        ("META", meta),
+        ("BODY", body),
+    ])
+}
+
+/// Replaces `{{NAME}}` placeholders in one pass, so script text that happens
+/// to contain a placeholder is left alone.
+fn fill_template(values: &[(&str, String)]) -> String {
+    let mut html = String::with_capacity(TEMPLATE.len());
+    let mut rest = TEMPLATE;
+    while let Some(start) = rest.find("{{") {
+        html.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &after[..end])
+                .map(|(_, value)| (value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                html.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                html.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    html.push_str(rest);
+    html
+}
+
+/// Editor position where block `index`'s text starts.
+fn block_text_start(blocks: &[Value], index: usize) -> usize {
+    blocks[..index]
+        .iter()
+        .map(document::node_size)
+        .sum::<usize>()
+        + 1
+}
+
+/// Anchors a note on its quote when it is still in the block, otherwise on
+/// the whole block.
+fn anchor_for(blocks: &[Value], note: &FeedbackNote) -> Option<CommentAnchor> {
+    let block = blocks.get(note.block_index)?;
+    let start = block_text_start(blocks, note.block_index);
+    let text = document::node_text(block);
+    let quote = note.quote.trim();
+
+    let (from, to) = match text.find(quote).filter(|_| !quote.is_empty()) {
+        Some(offset) => {
+            let from = start + text[..offset].encode_utf16().count();
+            (from, from + quote.encode_utf16().count())
+        }
+        None => (start, start + text.encode_utf16().count()),
+    };
+    Some(CommentAnchor {
+        from,
+        to,
+        quote: if quote.is_empty() {
+            text
+        } else {
+            quote.to_string()
+        },
+    })
+}
+
+/// Adds the notes as comments. Notes matching an existing comment's anchor,
+/// author and text are skipped, so importing a file twice is harmless.
+pub fn import_notes(value: &mut Value, notes: Vec<FeedbackNote>) -> Result<(usize, usize), String> {
+    let mut existing = comments::read_comments(value)?;
+    let blocks = document::blocks(value).to_vec();
+    let (mut imported, mut skipped) = (0, 0);
+
+    for note in notes {
+        let (author, body) = (note.author.trim(), note.body.trim());
+        let Some(anchor) = anchor_for(&blocks, &note) else {
+            skipped += 1;
+            continue;
+        };
+        if author.is_empty()
+            || body.is_empty()
+            || existing.iter().any(|comment| {
+                comment.anchor == anchor && comment.author == author && comment.body == body
+            })
+        {
+            skipped += 1;
+            continue;
+        }
+
+        existing.push(comments::new_comment(anchor, author, body));
+        imported += 1;
+    }
+
+    existing.sort_by_key(|comment| comment.anchor.from);
+    comments::write_comments(value, &existing)?;
+    Ok((imported, skipped))
+}
+
+#[tauri::command]
+pub async fn export_feedback_bundle(
+    app: AppHandle,
+    document_json: String,
+    output_path: String,
+    document_title: String,
+) -> Result<(), String> {
+    jobs::run(&app, "export", "Exporting feedback bundle", move |_| {
+        let value = document::parse_json(&document_json)?;
+        fs::write(&output_path, render(&value, &document_title))
+            .map_err(|error| format!("Failed to write feedback bundle: {}", error))
+    })
+    .await
+}
+
+/// Reads a feedback file written by the web bundle and adds its notes to
+/// the document as comments.
+#[tauri::command]
+pub fn import_feedback(
+    document_json: String,
+    input_path: String,
+) -> Result<FeedbackImport, String> {
+    let content = fs::read_to_string(&input_path)
+        .map_err(|error| format!("Failed to read feedback: {}", error))?;
+    let file = serde_json::from_str::<FeedbackFile>(&content)
+        .map_err(|error| format!("Failed to parse feedback: {}", error))?;
+    if file.format_version != FEEDBACK_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported feedback format {}",
+            file.format_version
+        ));
+    }
+
+    let mut value = document::parse_json(&document_json)?;
+    if let (Some(expected), Some(actual)) = (
+        file.document_id.as_deref(),
+        value.pointer("/meta/id").and_then(Value::as_str),
+    ) {
+        if expected != actual {
+            return Err("This feedback was written for a different script".to_string());
+        }
+    }
+
+    let (imported, skipped) = import_notes(&mut value, file.notes)?;
+    Ok(FeedbackImport {
+        document_json: serde_json::to_string(&value)
+            .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        imported,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_escaped_script_and_imports_notes() {
+        let mut value = json!({
+            "meta": { "id": "doc-1" },
+            "document": {
+                "type": "doc",
+                "content": [
+                    { "type": "sceneHeading", "content": [{ "type": "text", "text": "INT. BAR - NIGHT" }] },
+                    { "type": "action", "content": [{ "type": "text", "text": "Jo's <drink> spills." }] },
+                ],
+            },
+        });
+
+        let html = render(&value, "Nightfall");
+        assert!(html.contains("Jo&#39;s &lt;drink&gt; spills."));
+        assert!(html.contains("<a href=\"#block-0\">INT. BAR - NIGHT</a>"));
+        assert!(!html.contains("{{"));
+
+        let note = FeedbackNote {
+            block_index: 1,
+            quote: "spills".to_string(),
+            author: "Sam".to_string(),
+            body: "Too clumsy?".to_string(),
+        };
+        let (imported, _) = import_notes(&mut value, vec![note.clone()]).unwrap();
+        assert_eq!(imported, 1);
+        // "INT. BAR - NIGHT" is 16 long, so block 1's text starts at 19.
+        let anchor = &comments::read_comments(&value).unwrap()[0].anchor;
+        assert_eq!((anchor.from, anchor.to), (19 + 13, 19 + 19));
+
+        assert_eq!(import_notes(&mut value, vec![note]).unwrap(), (0, 1));
+    }
+}
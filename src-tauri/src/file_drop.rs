@@ -0,0 +1,87 @@
+use crate::PendingOpenFiles;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, WindowEvent};
+
+/// Files the editor can open: documents, Final Draft and Fountain imports.
+const OPENABLE_EXTENSIONS: [&str; 3] = ["gwx", "fdx", "fountain"];
+
+fn has_openable_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            OPENABLE_EXTENSIONS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(extension))
+        })
+}
+
+/// Splits dropped paths into openable files and the names of rejected ones,
+/// keeping the drop order.
+fn partition_paths(paths: &[PathBuf]) -> (Vec<String>, Vec<String>) {
+    let mut openable = Vec::new();
+    let mut rejected = Vec::new();
+    for path in paths {
+        if has_openable_extension(path) && !path.is_dir() {
+            openable.push(path.to_string_lossy().to_string());
+        } else {
+            rejected.push(
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.to_string_lossy().to_string()),
+            );
+        }
+    }
+    (openable, rejected)
+}
+
+/// Routes files dropped on the window through the same pending-open queue
+/// and `app-open-file` event as launcher and Finder opens. Unsupported files
+/// are reported with `app-open-file-rejected`.
+fn handle_drop(app: &AppHandle, paths: &[PathBuf]) {
+    let (openable, rejected) = partition_paths(paths);
+    let window = app.get_webview_window("main");
+
+    if !rejected.is_empty() {
+        if let Some(window) = &window {
+            let _ = window.emit("app-open-file-rejected", rejected);
+        }
+    }
+    if openable.is_empty() {
+        return;
+    }
+
+    app.state::<PendingOpenFiles>().push_paths(openable.clone());
+    if let Some(window) = &window {
+        let _ = window.emit("app-open-file", openable);
+    }
+}
+
+/// Opens documents dropped onto `window`.
+pub fn install(window: &tauri::WebviewWindow) {
+    let app = window.app_handle().clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+            handle_drop(&app, paths);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partitions_dropped_paths_by_extension() {
+        let paths = [
+            "/scripts/Pilot.FOUNTAIN",
+            "/scripts/notes.pdf",
+            "/scripts/draft.gwx",
+            "/scripts/README",
+        ]
+        .map(PathBuf::from);
+
+        let (openable, rejected) = partition_paths(&paths);
+        assert_eq!(openable, ["/scripts/Pilot.FOUNTAIN", "/scripts/draft.gwx"]);
+        assert_eq!(rejected, ["notes.pdf", "README"]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Opening screenplay files without stalling the webview. The file is read
+//! in chunks on a blocking thread, native documents are parsed here before
+//! any content is handed over, and the text then crosses IPC in bounded
+//! chunks with progress reported along the way.
+
+use crate::document::repair::Repair;
+use crate::error::{CommandError, ErrorKind};
+use crate::{crash_reports, document};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+
+#[cfg(mobile)]
+use crate::mobile_files;
+
+const READ_CHUNK_BYTES: usize = 1024 * 1024;
+const SEND_CHUNK_BYTES: usize = 512 * 1024;
+/// Imported formats the frontend parses itself; anything else is a native
+/// JSON document.
+const IMPORT_EXTENSIONS: &[&str] = &["fdx", "fountain"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LoadStage {
+    Reading,
+    Validating,
+    Sending,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum LoadEvent {
+    Progress {
+        stage: LoadStage,
+        loaded_bytes: u64,
+        total_bytes: Option<u64>,
+    },
+    /// Sent before the first chunk when the document had to be repaired to
+    /// open; the chunks then carry the repaired text.
+    Repaired { repairs: Vec<Repair> },
+    /// Sent before the first chunk when the file was written by a newer
+    /// build in a format this one only partly understands.
+    NewerFormat(NewerFormat),
+    /// Next piece of the file's text; the pieces join to the whole file.
+    Chunk { data: String },
+    /// Sent after the last chunk. Channel messages can arrive after the
+    /// command resolves, so this rather than the result marks the end.
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewerFormat {
+    pub format_version: String,
+    /// App version recorded in the file, when it has one.
+    pub app_version: Option<String>,
+}
+
+/// What validating a native document found.
+#[derive(Debug, Default, PartialEq)]
+struct Validated {
+    /// The repaired text, when the document had to be repaired to open.
+    repaired: Option<String>,
+    repairs: Vec<Repair>,
+    newer_format: Option<NewerFormat>,
+}
+
+fn send(on_event: &Channel<LoadEvent>, event: LoadEvent) -> Result<(), String> {
+    on_event
+        .send(event)
+        .map_err(|error| format!("Failed to send document: {}", error))
+}
+
+fn progress(stage: LoadStage, loaded_bytes: u64, total_bytes: Option<u64>) -> LoadEvent {
+    LoadEvent::Progress {
+        stage,
+        loaded_bytes,
+        total_bytes,
+    }
+}
+
+fn is_native_document(path: &str) -> bool {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    !IMPORT_EXTENSIONS.contains(&extension.as_str())
+}
+
+/// Reads `reader` to the end, calling `on_progress` with the bytes read so
+/// far after every chunk.
+fn read_chunked(
+    mut reader: impl Read,
+    size_hint: usize,
+    mut on_progress: impl FnMut(u64) -> Result<(), String>,
+) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(size_hint);
+    let mut buffer = vec![0; READ_CHUNK_BYTES];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(bytes),
+            Ok(read) => read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(format!("Failed to read document: {}", error)),
+        };
+        bytes.extend_from_slice(&buffer[..read]);
+        on_progress(bytes.len() as u64)?;
+    }
+}
+
+#[cfg_attr(desktop, allow(unused_variables))]
+fn read_file(
+    app: &AppHandle,
+    path: &str,
+    on_event: &Channel<LoadEvent>,
+) -> Result<Vec<u8>, CommandError> {
+    #[cfg(mobile)]
+    if mobile_files::is_document_uri(path) {
+        let bytes = mobile_files::read(app, path)
+            .map_err(|error| CommandError::from(error).with_path(path))?;
+        let total = bytes.len() as u64;
+        send(on_event, progress(LoadStage::Reading, total, Some(total)))?;
+        return Ok(bytes);
+    }
+
+    let file = File::open(path).map_err(|error| CommandError::io(&error, "open document", path))?;
+    let total = file.metadata().ok().map(|metadata| metadata.len());
+    send(on_event, progress(LoadStage::Reading, 0, total))?;
+    read_chunked(file, total.unwrap_or(0) as usize, |loaded| {
+        send(on_event, progress(LoadStage::Reading, loaded, total))
+    })
+    .map_err(|error| CommandError::from(error).with_path(path))
+}
+
+fn newer_format(value: &Value) -> Option<NewerFormat> {
+    let format_version = value.get("formatVersion").and_then(Value::as_str)?;
+    document::is_newer_format(format_version).then(|| NewerFormat {
+        format_version: format_version.to_string(),
+        app_version: value
+            .get("application")
+            .and_then(|application| application.get("version"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    })
+}
+
+/// Checks that `content` is a saved document: a JSON object whose
+/// `document` is a ProseMirror `doc` node. Blocks the editor wouldn't accept
+/// are repaired, which for a newer format keeps as much as this build can
+/// show.
+fn validate_document(content: &str) -> Result<Validated, String> {
+    let mut value = document::parse_json(content)?;
+    let newer_format = newer_format(&value);
+    let document_mode = value
+        .get("documentMode")
+        .and_then(Value::as_str)
+        .unwrap_or("screenplay")
+        .to_string();
+    let root = value.get_mut("document").filter(|root| root.is_object());
+    let root = match root {
+        Some(root) if document::node_type(root) == "doc" => root,
+        _ => return Err("Failed to parse document: not a screenplay file".to_string()),
+    };
+    if !matches!(root.get("content"), None | Some(Value::Array(_))) {
+        return Err("Failed to parse document: invalid document content".to_string());
+    }
+
+    let repairs = document::repair::repair(root, &document_mode);
+    let repaired = if repairs.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&value)
+                .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        )
+    };
+    Ok(Validated {
+        repaired,
+        repairs,
+        newer_format,
+    })
+}
+
+/// Splits `text` into pieces of at most `max_bytes`, never inside a
+/// character.
+fn split_chunks(text: &str, max_bytes: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = max_bytes.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            end = rest
+                .chars()
+                .next()
+                .map(char::len_utf8)
+                .unwrap_or(rest.len());
+        }
+        let (chunk, next) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = next;
+    }
+    chunks
+}
+
+fn invalid_document(path: &str, message: impl Into<String>) -> CommandError {
+    CommandError::new(ErrorKind::InvalidDocument, message).with_path(path)
+}
+
+fn load(app: &AppHandle, path: &str, on_event: &Channel<LoadEvent>) -> Result<(), CommandError> {
+    crash_reports::breadcrumb("file", "open");
+    let bytes = read_file(app, path, on_event)?;
+    let mut content = String::from_utf8(bytes).map_err(|_| {
+        invalid_document(path, "Failed to read document: the file is not UTF-8 text")
+    })?;
+    let mut total = content.len() as u64;
+
+    if is_native_document(path) {
+        send(
+            on_event,
+            progress(LoadStage::Validating, total, Some(total)),
+        )?;
+        let validated =
+            validate_document(&content).map_err(|error| invalid_document(path, error))?;
+        if let Some(newer_format) = validated.newer_format {
+            send(on_event, LoadEvent::NewerFormat(newer_format))?;
+        }
+        if let Some(repaired) = validated.repaired {
+            send(
+                on_event,
+                LoadEvent::Repaired {
+                    repairs: validated.repairs,
+                },
+            )?;
+            content = repaired;
+            total = content.len() as u64;
+        }
+    }
+
+    let mut sent = 0;
+    for chunk in split_chunks(&content, SEND_CHUNK_BYTES) {
+        sent += chunk.len() as u64;
+        send(
+            on_event,
+            LoadEvent::Chunk {
+                data: chunk.to_string(),
+            },
+        )?;
+        send(on_event, progress(LoadStage::Sending, sent, Some(total)))?;
+    }
+    Ok(send(on_event, LoadEvent::Done)?)
+}
+
+/// Streams the file at `path` to `on_event` as progress and text chunks.
+/// Resolves once the last chunk has been sent; a file that can't be read or
+/// isn't a valid document fails before any text is sent.
+#[tauri::command]
+pub async fn load_screenplay(
+    app: AppHandle,
+    path: String,
+    on_event: Channel<LoadEvent>,
+) -> Result<(), CommandError> {
+    tauri::async_runtime::spawn_blocking(move || load(&app, &path, &on_event))
+        .await
+        .map_err(|error| format!("Failed to load document: {}", error))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_in_chunks_with_progress() {
+        let data = vec![b'x'; READ_CHUNK_BYTES * 2 + 10];
+        let mut reported = Vec::new();
+        let bytes = read_chunked(data.as_slice(), data.len(), |loaded| {
+            reported.push(loaded);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(bytes, data);
+        assert_eq!(reported.last(), Some(&(data.len() as u64)));
+        assert!(reported.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn splits_on_character_boundaries() {
+        let text = "INT. CAFÉ — NIGHT ".repeat(100);
+        let chunks = split_chunks(&text, 7);
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks
+            .iter()
+            .all(|chunk| !chunk.is_empty() && chunk.len() <= 7));
+        assert!(split_chunks("", 7).is_empty());
+    }
+
+    #[test]
+    fn validates_native_documents() {
+        assert_eq!(
+            validate_document(
+                r#"{"meta":{},"document":{"type":"doc","content":[{"type":"action"}]}}"#
+            ),
+            Ok(Validated::default())
+        );
+        assert!(validate_document(r#"{"document":{"type":"doc","content":[]}"#).is_err());
+        assert!(validate_document(r#"{"type":"doc","content":[]}"#).is_err());
+        assert!(validate_document(r#"{"document":{"type":"doc","content":{}}}"#).is_err());
+        assert!(is_native_document("/scripts/pilot.gwx"));
+        assert!(!is_native_document("/scripts/pilot.FDX"));
+        assert!(!is_native_document("/scripts/pilot.fountain"));
+    }
+
+    #[test]
+    fn repairs_documents_the_editor_would_reject() {
+        let validated = validate_document(
+            r#"{"documentMode":"screenplay","document":{"type":"doc","content":[{"type":"insert"}]}}"#,
+        )
+        .unwrap();
+        let value = document::parse_json(&validated.repaired.unwrap()).unwrap();
+        assert_eq!(value["documentMode"], "screenplay");
+        assert_eq!(value["document"]["content"][0]["type"], "action");
+        assert_eq!(validated.repairs.len(), 1);
+        assert_eq!(validated.newer_format, None);
+    }
+
+    #[test]
+    fn flags_documents_from_newer_builds() {
+        let validated = validate_document(
+            r#"{"formatVersion":"2.1","application":{"name":"Grainery","version":"3.0.0"},
+                "document":{"type":"doc","content":[{"type":"action"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            validated.newer_format,
+            Some(NewerFormat {
+                format_version: "2.1".to_string(),
+                app_version: Some("3.0.0".to_string()),
+            })
+        );
+        assert!(validated.repaired.is_none());
+
+        assert!(!document::is_newer_format(document::FORMAT_VERSION));
+        assert!(!document::is_newer_format("0.9"));
+        assert!(document::is_newer_format("1.1"));
+        assert!(document::is_newer_format("2"));
+        assert!(!document::is_newer_format("next"));
+    }
+}
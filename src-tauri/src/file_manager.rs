@@ -0,0 +1,266 @@
+use crate::menu_locale::MenuStrings;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::menu::{MenuItem, MenuItemBuilder};
+use tauri::{App, AppHandle, Manager, State, Wry};
+
+#[cfg(target_os = "macos")]
+fn file_manager_name(_labels: &MenuStrings) -> String {
+    "Finder".to_string()
+}
+
+#[cfg(windows)]
+fn file_manager_name(_labels: &MenuStrings) -> String {
+    "Explorer".to_string()
+}
+
+/// Other desktops get the localized generic name.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn file_manager_name(labels: &MenuStrings) -> String {
+    labels.get("file_manager")
+}
+
+/// The saved document and the last export, which the File menu can reveal
+/// or open. Menu items stay disabled until there is a file to act on.
+pub struct FileTargets {
+    document: Mutex<Option<PathBuf>>,
+    export: Mutex<Option<PathBuf>>,
+    reveal_document: MenuItem<Wry>,
+    open_document: MenuItem<Wry>,
+    reveal_export: MenuItem<Wry>,
+    open_export: MenuItem<Wry>,
+}
+
+fn existing_path(path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", path.to_string_lossy()));
+    }
+    Ok(path)
+}
+
+fn spawn(command: &mut Command) -> Result<(), String> {
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|error| format!("Failed to launch {:?}: {}", command.get_program(), error))
+}
+
+/// Runs a shell verb (`open`, `print`, ...) on a file with its registered
+/// handler.
+#[cfg(windows)]
+pub fn shell_execute(verb: &str, path: &Path) -> Result<(), String> {
+    use std::ffi::{c_void, OsStr};
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn ShellExecuteW(
+            hwnd: *mut c_void,
+            operation: *const u16,
+            file: *const u16,
+            parameters: *const u16,
+            directory: *const u16,
+            show_command: i32,
+        ) -> isize;
+    }
+
+    let wide = |value: &OsStr| value.encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let operation = wide(OsStr::new(verb));
+    let file = wide(path.as_os_str());
+    // SW_SHOWNORMAL
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            operation.as_ptr(),
+            file.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null(),
+            1,
+        )
+    };
+
+    // Values of 32 or less are errors, e.g. no application registered for
+    // the file type and verb.
+    if result <= 32 {
+        return Err(format!(
+            "No application is available to {} {} (error {})",
+            verb,
+            path.to_string_lossy(),
+            result
+        ));
+    }
+
+    Ok(())
+}
+
+fn reveal(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        spawn(Command::new("open").arg("-R").arg(path))
+    }
+
+    #[cfg(windows)]
+    {
+        // Explorer parses `/select,` itself, so the path goes in the same
+        // argument. Its exit code is unreliable and is not checked.
+        let mut argument = std::ffi::OsString::from("/select,");
+        argument.push(path.as_os_str());
+        spawn(Command::new("explorer").arg(argument))
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    {
+        // Ask the desktop's file manager to select the file, falling back to
+        // opening the containing folder.
+        let shown = tauri::Url::from_file_path(path).ok().is_some_and(|url| {
+            Command::new("dbus-send")
+                .args([
+                    "--session",
+                    "--dest=org.freedesktop.FileManager1",
+                    "--type=method_call",
+                    "/org/freedesktop/FileManager1",
+                    "org.freedesktop.FileManager1.ShowItems",
+                ])
+                .arg(format!("array:string:{}", url))
+                .arg("string:")
+                .status()
+                .is_ok_and(|status| status.success())
+        });
+        if shown {
+            return Ok(());
+        }
+
+        let folder = path.parent().unwrap_or(path);
+        spawn(Command::new("xdg-open").arg(folder))
+    }
+}
+
+pub fn open_with_default(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        spawn(Command::new("open").arg(path))
+    }
+
+    #[cfg(windows)]
+    {
+        shell_execute("open", path)
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    {
+        spawn(Command::new("xdg-open").arg(path))
+    }
+}
+
+/// Builds the File menu items and registers their state. Returns them in
+/// menu order: reveal document, open document, reveal export, open export.
+pub fn build_menu_items(app: &App, labels: &MenuStrings) -> Result<[MenuItem<Wry>; 4], String> {
+    let file_manager = file_manager_name(labels);
+    let item = |id: &str| {
+        MenuItemBuilder::with_id(id, labels.get(id).replace("{fileManager}", &file_manager))
+            .enabled(false)
+            .build(app)
+            .map_err(|error| format!("Failed to build File menu: {}", error))
+    };
+
+    let items = [
+        item("reveal_document")?,
+        item("open_document_default")?,
+        item("reveal_export")?,
+        item("open_export_default")?,
+    ];
+
+    app.manage(FileTargets {
+        document: Mutex::new(None),
+        export: Mutex::new(None),
+        reveal_document: items[0].clone(),
+        open_document: items[1].clone(),
+        reveal_export: items[2].clone(),
+        open_export: items[3].clone(),
+    });
+
+    Ok(items)
+}
+
+fn set_target(
+    target: &Mutex<Option<PathBuf>>,
+    items: [&MenuItem<Wry>; 2],
+    path: Option<String>,
+) -> Result<(), String> {
+    let path = path
+        .filter(|path| !path.trim().is_empty())
+        .map(PathBuf::from);
+    for item in items {
+        let _ = item.set_enabled(path.is_some());
+    }
+
+    let mut target = target
+        .lock()
+        .map_err(|_| "File menu state is unavailable".to_string())?;
+    *target = path;
+    Ok(())
+}
+
+/// Handles the reveal/open File menu items. Returns `false` for ids that
+/// belong elsewhere.
+pub fn handle_menu_event(app: &AppHandle, id: &str) -> bool {
+    let targets = app.state::<FileTargets>();
+    let (target, reveal_file) = match id {
+        "reveal_document" => (&targets.document, true),
+        "open_document_default" => (&targets.document, false),
+        "reveal_export" => (&targets.export, true),
+        "open_export_default" => (&targets.export, false),
+        _ => return false,
+    };
+
+    let path = target.lock().ok().and_then(|target| target.clone());
+    if let Some(path) = path {
+        let result = if reveal_file {
+            reveal(&path)
+        } else {
+            open_with_default(&path)
+        };
+        if let Err(error) = result {
+            tracing::warn!("{}", error);
+        }
+    }
+    true
+}
+
+#[tauri::command]
+pub fn reveal_in_file_manager(path: String) -> Result<(), String> {
+    reveal(&existing_path(&path)?)
+}
+
+#[tauri::command]
+pub fn open_with_default_app(path: String) -> Result<(), String> {
+    open_with_default(&existing_path(&path)?)
+}
+
+/// Called by the frontend when the open document's path changes.
+#[tauri::command]
+pub fn file_targets_set_document(
+    targets: State<'_, FileTargets>,
+    path: Option<String>,
+) -> Result<(), String> {
+    set_target(
+        &targets.document,
+        [&targets.reveal_document, &targets.open_document],
+        path,
+    )
+}
+
+/// Called by the frontend after a successful export.
+#[tauri::command]
+pub fn file_targets_set_export(
+    targets: State<'_, FileTargets>,
+    path: Option<String>,
+) -> Result<(), String> {
+    set_target(
+        &targets.export,
+        [&targets.reveal_export, &targets.open_export],
+        path,
+    )
+}
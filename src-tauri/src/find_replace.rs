@@ -0,0 +1,288 @@
+use crate::document;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceOptions {
+    pub query: String,
+    #[serde(default)]
+    pub replacement: Option<String>,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Restricts matching to these node types (e.g. `["dialogue"]`); empty means all.
+    #[serde(default)]
+    pub element_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindMatch {
+    pub block_index: usize,
+    pub element_type: String,
+    /// Character offsets into the block's text.
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceResult {
+    pub matches: Vec<FindMatch>,
+    pub replaced_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_json: Option<String>,
+}
+
+fn build_pattern(options: &FindReplaceOptions) -> Result<Regex, String> {
+    if options.query.is_empty() {
+        return Err("Search query must not be empty".to_string());
+    }
+
+    let source = if options.regex {
+        options.query.clone()
+    } else {
+        regex::escape(&options.query)
+    };
+    let source = if options.whole_word {
+        format!(r"\b(?:{})\b", source)
+    } else {
+        source
+    };
+
+    RegexBuilder::new(&source)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|error| format!("Invalid search pattern: {}", error))
+}
+
+fn char_offset(text: &str, byte_index: usize) -> usize {
+    text[..byte_index].chars().count()
+}
+
+/// A match in byte offsets, with the expanded replacement text when replacing.
+struct BlockMatch {
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+fn find_in_block(pattern: &Regex, text: &str, options: &FindReplaceOptions) -> Vec<BlockMatch> {
+    pattern
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            if whole.start() == whole.end() {
+                return None;
+            }
+
+            let replacement = match &options.replacement {
+                Some(template) if options.regex => {
+                    let mut expanded = String::new();
+                    captures.expand(template, &mut expanded);
+                    expanded
+                }
+                Some(literal) => literal.clone(),
+                None => String::new(),
+            };
+
+            Some(BlockMatch {
+                start: whole.start(),
+                end: whole.end(),
+                replacement,
+            })
+        })
+        .collect()
+}
+
+/// Rewrites the block's inline text children for a single match. Text that
+/// spans several marked runs collapses into the first run's marks.
+fn replace_in_block(block: &mut Value, start: usize, end: usize, replacement: &str) {
+    let Some(children) = block.get_mut("content").and_then(Value::as_array_mut) else {
+        return;
+    };
+
+    let mut offset = 0;
+    let mut inserted = false;
+
+    for child in children.iter_mut() {
+        let Some(text) = child.get("text").and_then(Value::as_str) else {
+            continue;
+        };
+        let child_start = offset;
+        let child_end = offset + text.len();
+        offset = child_end;
+
+        if child_end <= start || child_start >= end {
+            continue;
+        }
+
+        let local_start = start.saturating_sub(child_start);
+        let local_end = (end - child_start).min(text.len());
+        let mut next = String::with_capacity(text.len() + replacement.len());
+        next.push_str(&text[..local_start]);
+        if !inserted {
+            next.push_str(replacement);
+            inserted = true;
+        }
+        next.push_str(&text[local_end..]);
+        child["text"] = Value::String(next);
+    }
+
+    children.retain(|child| {
+        child
+            .get("text")
+            .and_then(Value::as_str)
+            .map(|text| !text.is_empty())
+            .unwrap_or(true)
+    });
+}
+
+pub fn find_replace_in_document(
+    root: &mut Value,
+    options: &FindReplaceOptions,
+) -> Result<(Vec<FindMatch>, usize), String> {
+    let pattern = build_pattern(options)?;
+    let mut matches = Vec::new();
+    let mut replaced_count = 0;
+
+    let Some(blocks) = document::blocks_mut(root) else {
+        return Ok((matches, replaced_count));
+    };
+
+    for (block_index, block) in blocks.iter_mut().enumerate() {
+        let element_type = document::node_type(block).to_string();
+        if !options.element_types.is_empty()
+            && !options
+                .element_types
+                .iter()
+                .any(|item| item == &element_type)
+        {
+            continue;
+        }
+
+        let text = document::node_text(block);
+        let block_matches = find_in_block(&pattern, &text, options);
+
+        for item in &block_matches {
+            matches.push(FindMatch {
+                block_index,
+                element_type: element_type.clone(),
+                start: char_offset(&text, item.start),
+                end: char_offset(&text, item.end),
+                text: text[item.start..item.end].to_string(),
+            });
+        }
+
+        if options.replacement.is_some() {
+            // Apply back to front so earlier byte offsets stay valid
+            for item in block_matches.iter().rev() {
+                replace_in_block(block, item.start, item.end, &item.replacement);
+                replaced_count += 1;
+            }
+        }
+    }
+
+    Ok((matches, replaced_count))
+}
+
+/// Finds matches and, when a replacement is given, replaces all of them. The
+/// document is only returned once every replacement has been applied.
+#[tauri::command]
+pub fn find_replace(
+    content_json: String,
+    options: FindReplaceOptions,
+) -> Result<FindReplaceResult, String> {
+    let mut root = document::parse_json(&content_json)?;
+    let (matches, replaced_count) = find_replace_in_document(&mut root, &options)?;
+
+    let content_json = if options.replacement.is_some() {
+        Some(
+            serde_json::to_string(&root)
+                .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        )
+    } else {
+        None
+    };
+
+    Ok(FindReplaceResult {
+        matches,
+        replaced_count,
+        content_json,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(query: &str, replacement: Option<&str>) -> FindReplaceOptions {
+        FindReplaceOptions {
+            query: query.to_string(),
+            replacement: replacement.map(str::to_string),
+            regex: false,
+            case_sensitive: false,
+            whole_word: false,
+            element_types: Vec::new(),
+        }
+    }
+
+    fn sample() -> Value {
+        json!({
+            "type": "doc",
+            "content": [
+                { "type": "action", "content": [{ "type": "text", "text": "Jane enters. Jane sits." }] },
+                { "type": "character", "content": [{ "type": "text", "text": "JANE" }] },
+                { "type": "dialogue", "content": [
+                    { "type": "text", "text": "I said Ja" },
+                    { "type": "text", "text": "ne!", "marks": [{ "type": "bold" }] }
+                ] }
+            ]
+        })
+    }
+
+    #[test]
+    fn filters_by_element_type_and_whole_word() {
+        let mut root = sample();
+        let mut opts = options("jane", None);
+        opts.element_types = vec!["dialogue".to_string()];
+        let (matches, _) = find_replace_in_document(&mut root, &opts).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].start, matches[0].end), (7, 11));
+
+        let mut opts = options("Jan", None);
+        opts.whole_word = true;
+        let (matches, _) = find_replace_in_document(&mut root, &opts).unwrap();
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn replaces_across_marked_runs_with_captures() {
+        let mut root = sample();
+        let mut opts = options(r"(J)ane", Some("${1}ohn"));
+        opts.regex = true;
+        opts.case_sensitive = true;
+        let (matches, replaced) = find_replace_in_document(&mut root, &opts).unwrap();
+        assert_eq!(matches.len(), 3);
+        assert_eq!(replaced, 3);
+
+        let blocks = document::blocks(&root);
+        assert_eq!(document::node_text(&blocks[0]), "John enters. John sits.");
+        assert_eq!(document::node_text(&blocks[1]), "JANE");
+        assert_eq!(document::node_text(&blocks[2]), "I said John!");
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let mut opts = options("(", Some("x"));
+        opts.regex = true;
+        assert!(find_replace(sample().to_string(), opts).is_err());
+    }
+}
@@ -76,7 +76,11 @@ pub fn resolve_system_font(
         .variants
         .iter()
         .min_by_key(|variant| {
-            let style_penalty = if variant.style == requested_style { 0 } else { 1000 };
+            let style_penalty = if variant.style == requested_style {
+                0
+            } else {
+                1000
+            };
             let weight_delta = variant.weight.abs_diff(requested_weight);
             style_penalty + weight_delta
         })
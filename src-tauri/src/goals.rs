@@ -0,0 +1,149 @@
+use crate::{app_data, writing_stats};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+const WRITING_GOALS_FILE: &str = "writing-goals.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritingGoals {
+    #[serde(default)]
+    pub daily_word_target: Option<u32>,
+    /// Draft deadline as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub deadline: Option<String>,
+    #[serde(default)]
+    pub target_pages: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoalsStore {
+    #[serde(default)]
+    documents: BTreeMap<String, WritingGoals>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProgress {
+    pub goals: WritingGoals,
+    pub words_today: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words_remaining_today: Option<u32>,
+    /// Days left including today; `0` once the deadline has passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_remaining: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages_remaining: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages_per_day: Option<f64>,
+}
+
+fn parse_deadline(deadline: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(deadline.trim(), "%Y-%m-%d")
+        .map_err(|error| format!("Invalid deadline '{}': {}", deadline, error))
+}
+
+fn load_goals(app: &AppHandle, document_id: &str) -> Result<WritingGoals, String> {
+    let store: GoalsStore = app_data::load_json(app, WRITING_GOALS_FILE)?;
+    Ok(store
+        .documents
+        .get(document_id)
+        .cloned()
+        .unwrap_or_default())
+}
+
+pub fn progress(
+    goals: WritingGoals,
+    words_today: u32,
+    current_pages: u32,
+    today: NaiveDate,
+) -> Result<GoalProgress, String> {
+    let days_remaining = match goals.deadline.as_deref() {
+        Some(deadline) => {
+            let days = (parse_deadline(deadline)? - today).num_days() + 1;
+            Some(days.max(0) as u32)
+        }
+        None => None,
+    };
+    let pages_remaining = goals
+        .target_pages
+        .map(|target| target.saturating_sub(current_pages));
+    let pages_per_day = match (pages_remaining, days_remaining) {
+        (Some(pages), Some(days)) if days > 0 => Some(pages as f64 / days as f64),
+        (Some(pages), Some(_)) => Some(pages as f64),
+        _ => None,
+    };
+
+    Ok(GoalProgress {
+        words_remaining_today: goals
+            .daily_word_target
+            .map(|target| target.saturating_sub(words_today)),
+        words_today,
+        days_remaining,
+        pages_remaining,
+        pages_per_day,
+        goals,
+    })
+}
+
+#[tauri::command]
+pub fn goals_get(app: AppHandle, document_id: String) -> Result<WritingGoals, String> {
+    load_goals(&app, &document_id)
+}
+
+#[tauri::command]
+pub fn goals_set(
+    app: AppHandle,
+    document_id: String,
+    goals: WritingGoals,
+) -> Result<WritingGoals, String> {
+    if let Some(deadline) = goals.deadline.as_deref() {
+        parse_deadline(deadline)?;
+    }
+    if goals.daily_word_target == Some(0) {
+        return Err("Daily word target must be greater than zero".to_string());
+    }
+
+    let mut store: GoalsStore = app_data::load_json(&app, WRITING_GOALS_FILE)?;
+    store.documents.insert(document_id, goals.clone());
+    app_data::save_json(&app, WRITING_GOALS_FILE, &store)?;
+    Ok(goals)
+}
+
+#[tauri::command]
+pub fn goals_progress(
+    app: AppHandle,
+    document_id: String,
+    current_pages: u32,
+) -> Result<GoalProgress, String> {
+    let goals = load_goals(&app, &document_id)?;
+    let today = Local::now().date_naive();
+    let store = writing_stats::load_store(&app)?;
+    let words_today = writing_stats::summary(&store, Some(&document_id), today).words_today;
+
+    progress(goals, words_today, current_pages, today)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads_remaining_pages_over_days_left() {
+        let goals = WritingGoals {
+            daily_word_target: Some(1000),
+            deadline: Some("2026-03-21".to_string()),
+            target_pages: Some(110),
+        };
+        let today = NaiveDate::from_ymd_opt(2026, 3, 12).unwrap();
+
+        let result = progress(goals, 400, 90, today).unwrap();
+        assert_eq!(result.words_remaining_today, Some(600));
+        assert_eq!(result.days_remaining, Some(10));
+        assert_eq!(result.pages_remaining, Some(20));
+        assert_eq!(result.pages_per_day, Some(2.0));
+    }
+}
@@ -0,0 +1,446 @@
+//! Long-running backend work (exports, reports, analysis, plugin downloads)
+//! runs as jobs so the frontend can show one activity indicator for all of
+//! it. Every change to a job is emitted as a `job-changed` event; jobs can be
+//! cancelled, and CPU-bound jobs queue for a limited number of worker slots.
+
+use crate::crash_reports;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const JOB_EVENT: &str = "job-changed";
+/// Finished jobs kept for `jobs_list` after they end.
+const MAX_FINISHED_JOBS: usize = 20;
+const MIN_WORKERS: usize = 2;
+const MAX_WORKERS: usize = 4;
+
+/// Error a job returns when it stops because it was cancelled.
+pub const CANCELLED: &str = "Cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub id: u64,
+    /// What the job does, e.g. `export` or `plugin-download`.
+    pub kind: String,
+    pub label: String,
+    pub state: JobState,
+    /// 0 to 1, or `None` while the job can't tell how far along it is.
+    pub progress: Option<f32>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+}
+
+struct Entry {
+    info: JobInfo,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    entries: Vec<Entry>,
+    /// Jobs holding a worker slot.
+    running: usize,
+}
+
+impl Jobs {
+    fn entry_mut(&mut self, id: u64) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|entry| entry.info.id == id)
+    }
+
+    fn prune_finished(&mut self) {
+        let finished = self
+            .entries
+            .iter()
+            .filter(|entry| entry.info.state.is_finished())
+            .count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.entries.retain(|entry| {
+            if excess > 0 && entry.info.state.is_finished() {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+    }
+}
+
+/// Job registry and worker slots. Each method returns the job as it now
+/// stands so the caller can emit it.
+pub struct JobManager {
+    jobs: Mutex<Jobs>,
+    slot_freed: Condvar,
+    workers: usize,
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        let workers = std::thread::available_parallelism()
+            .map(|count| count.get().saturating_sub(1))
+            .unwrap_or(MIN_WORKERS);
+        Self::new(workers.clamp(MIN_WORKERS, MAX_WORKERS))
+    }
+}
+
+impl JobManager {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            jobs: Mutex::new(Jobs::default()),
+            slot_freed: Condvar::new(),
+            workers: workers.max(1),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Jobs> {
+        self.jobs.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn add(&self, kind: &str, label: String, state: JobState) -> (JobInfo, Arc<AtomicBool>) {
+        let mut jobs = self.lock();
+        jobs.next_id += 1;
+        let info = JobInfo {
+            id: jobs.next_id,
+            kind: kind.to_string(),
+            label,
+            state,
+            progress: None,
+            message: None,
+            error: None,
+        };
+        let cancelled = Arc::new(AtomicBool::new(false));
+        jobs.entries.push(Entry {
+            info: info.clone(),
+            cancelled: cancelled.clone(),
+        });
+        (info, cancelled)
+    }
+
+    /// Waits until job `id` is the oldest queued job and a worker slot is
+    /// free, then marks it running. Returns `None` when the job was
+    /// cancelled while it waited.
+    fn acquire(&self, id: u64) -> Option<JobInfo> {
+        let mut jobs = self.lock();
+        loop {
+            let first_queued = jobs
+                .entries
+                .iter()
+                .find(|entry| entry.info.state == JobState::Queued)
+                .map(|entry| entry.info.id);
+            let running = jobs.running;
+            let entry = jobs.entry_mut(id)?;
+            if entry.info.state != JobState::Queued {
+                return None;
+            }
+            if first_queued == Some(id) && running < self.workers {
+                entry.info.state = JobState::Running;
+                let info = entry.info.clone();
+                jobs.running += 1;
+                return Some(info);
+            }
+            jobs = self
+                .slot_freed
+                .wait(jobs)
+                .unwrap_or_else(|error| error.into_inner());
+        }
+    }
+
+    fn update(&self, id: u64, progress: Option<f32>, message: Option<String>) -> Option<JobInfo> {
+        let mut jobs = self.lock();
+        let entry = jobs.entry_mut(id)?;
+        if entry.info.state != JobState::Running {
+            return None;
+        }
+        entry.info.progress = progress.map(|progress| progress.clamp(0.0, 1.0));
+        entry.info.message = message;
+        Some(entry.info.clone())
+    }
+
+    /// Records how job `id` ended and frees its worker slot if it held one.
+    fn finish(&self, id: u64, error: Option<String>, held_slot: bool) -> Option<JobInfo> {
+        let mut jobs = self.lock();
+        if held_slot {
+            jobs.running = jobs.running.saturating_sub(1);
+        }
+        let entry = jobs.entry_mut(id)?;
+        let cancelled = entry.cancelled.load(Ordering::Relaxed);
+        entry.info.state = match &error {
+            None => JobState::Succeeded,
+            Some(_) if cancelled => JobState::Cancelled,
+            Some(error) if error == CANCELLED => JobState::Cancelled,
+            Some(_) => JobState::Failed,
+        };
+        if entry.info.state == JobState::Succeeded {
+            entry.info.progress = Some(1.0);
+        }
+        entry.info.error = error.filter(|_| entry.info.state == JobState::Failed);
+        let info = entry.info.clone();
+        jobs.prune_finished();
+        drop(jobs);
+        self.slot_freed.notify_all();
+        Some(info)
+    }
+
+    /// Asks job `id` to stop. A queued job is cancelled on the spot; a
+    /// running one stops the next time it checks.
+    fn cancel(&self, id: u64) -> Result<JobInfo, String> {
+        let mut jobs = self.lock();
+        let entry = jobs
+            .entry_mut(id)
+            .ok_or_else(|| format!("Unknown job: {}", id))?;
+        entry.cancelled.store(true, Ordering::Relaxed);
+        if entry.info.state == JobState::Queued {
+            entry.info.state = JobState::Cancelled;
+        }
+        let info = entry.info.clone();
+        drop(jobs);
+        self.slot_freed.notify_all();
+        Ok(info)
+    }
+
+    fn list(&self) -> Vec<JobInfo> {
+        self.lock()
+            .entries
+            .iter()
+            .map(|entry| entry.info.clone())
+            .collect()
+    }
+}
+
+fn emit(app: &AppHandle, info: Option<JobInfo>) {
+    if let Some(info) = info {
+        let _ = app.emit(JOB_EVENT, info);
+    }
+}
+
+/// Handle a job's work uses to report progress and notice cancellation.
+/// Dropping it before `finish` ends the job as failed.
+pub struct Job {
+    app: AppHandle,
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+    held_slot: bool,
+    finished: bool,
+}
+
+impl Job {
+    /// `progress` runs from 0 to 1; `None` shows the job as busy.
+    pub fn progress(&self, progress: Option<f32>, message: impl Into<String>) {
+        let manager = self.app.state::<JobManager>();
+        emit(
+            &self.app,
+            manager.update(self.id, progress, Some(message.into())),
+        );
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// `Err(CANCELLED)` once the job has been cancelled, for use with `?`.
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn finish<T, E: ToString>(mut self, result: &Result<T, E>) {
+        self.end(result.as_ref().err().map(ToString::to_string));
+    }
+
+    fn end(&mut self, error: Option<String>) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        let manager = self.app.state::<JobManager>();
+        let info = manager.finish(self.id, error, self.held_slot);
+        if let Some(info) = info.as_ref().filter(|info| info.state == JobState::Failed) {
+            tracing::warn!(
+                kind = %info.kind,
+                "{} failed: {}",
+                info.label,
+                info.error.as_deref().unwrap_or_default()
+            );
+        }
+        emit(&self.app, info);
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        self.end(Some("Job ended unexpectedly".to_string()));
+    }
+}
+
+/// Starts a job for async work, such as a download, that waits on I/O
+/// rather than a worker slot. The caller reports its outcome with
+/// `Job::finish`.
+pub fn start(app: &AppHandle, kind: &str, label: impl Into<String>) -> Job {
+    let manager = app.state::<JobManager>();
+    let (info, cancelled) = manager.add(kind, label.into(), JobState::Running);
+    let id = info.id;
+    crash_reports::breadcrumb("job", &info.label);
+    emit(app, Some(info));
+    Job {
+        app: app.clone(),
+        id,
+        cancelled,
+        held_slot: false,
+        finished: false,
+    }
+}
+
+/// Runs CPU-bound `work` as a job on a blocking thread once a worker slot
+/// is free, and returns its result. Fails with `CANCELLED` if the job is
+/// cancelled before it starts.
+pub async fn run<T, F>(
+    app: &AppHandle,
+    kind: &str,
+    label: impl Into<String>,
+    work: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&Job) -> Result<T, String> + Send + 'static,
+{
+    let manager = app.state::<JobManager>();
+    let (info, cancelled) = manager.add(kind, label.into(), JobState::Queued);
+    let id = info.id;
+    crash_reports::breadcrumb("job", &info.label);
+    emit(app, Some(info));
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let running = app.state::<JobManager>().acquire(id);
+        if running.is_none() {
+            return Err(CANCELLED.to_string());
+        }
+        emit(&app, running);
+
+        let job = Job {
+            app,
+            id,
+            cancelled,
+            held_slot: true,
+            finished: false,
+        };
+        let result = work(&job);
+        job.finish(&result);
+        result
+    })
+    .await
+    .map_err(|error| format!("Job failed: {}", error))?
+}
+
+/// Current and recently finished jobs, oldest first.
+#[tauri::command]
+pub fn jobs_list(manager: State<'_, JobManager>) -> Vec<JobInfo> {
+    manager.list()
+}
+
+#[tauri::command]
+pub fn jobs_cancel(
+    app: AppHandle,
+    manager: State<'_, JobManager>,
+    id: u64,
+) -> Result<JobInfo, String> {
+    let info = manager.cancel(id)?;
+    emit(&app, Some(info.clone()));
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn runs_queued_jobs_in_order_within_the_worker_limit() {
+        let manager = JobManager::new(1);
+        let (first, _) = manager.add("export", "First".to_string(), JobState::Queued);
+        let (second, _) = manager.add("export", "Second".to_string(), JobState::Queued);
+
+        assert_eq!(
+            manager.acquire(first.id).map(|info| info.state),
+            Some(JobState::Running)
+        );
+        thread::scope(|scope| {
+            let waiting = scope.spawn(|| manager.acquire(second.id));
+            thread::sleep(Duration::from_millis(50));
+            assert!(!waiting.is_finished());
+
+            manager.update(first.id, Some(1.5), Some("Writing".to_string()));
+            let finished = manager.finish(first.id, None, true).unwrap();
+            assert_eq!(finished.state, JobState::Succeeded);
+            assert_eq!(finished.progress, Some(1.0));
+            assert_eq!(
+                waiting.join().unwrap().map(|info| info.state),
+                Some(JobState::Running)
+            );
+        });
+    }
+
+    #[test]
+    fn cancels_queued_and_running_jobs() {
+        let manager = JobManager::new(1);
+        let (running, running_flag) = manager.add("export", "A".to_string(), JobState::Queued);
+        let (queued, _) = manager.add("export", "B".to_string(), JobState::Queued);
+        manager.acquire(running.id);
+
+        thread::scope(|scope| {
+            let waiting = scope.spawn(|| manager.acquire(queued.id));
+            thread::sleep(Duration::from_millis(20));
+            assert_eq!(
+                manager.cancel(queued.id).unwrap().state,
+                JobState::Cancelled
+            );
+            assert!(waiting.join().unwrap().is_none());
+        });
+
+        assert_eq!(manager.cancel(running.id).unwrap().state, JobState::Running);
+        assert!(running_flag.load(Ordering::Relaxed));
+        let finished = manager
+            .finish(running.id, Some("Stopped early".to_string()), true)
+            .unwrap();
+        assert_eq!(finished.state, JobState::Cancelled);
+        assert_eq!(finished.error, None);
+        assert!(manager.cancel(999).is_err());
+    }
+
+    #[test]
+    fn keeps_failures_and_prunes_old_finished_jobs() {
+        let manager = JobManager::new(2);
+        for index in 0..MAX_FINISHED_JOBS + 5 {
+            let (info, _) = manager.add("report", format!("Report {}", index), JobState::Running);
+            manager.finish(info.id, Some("Disk full".to_string()), false);
+        }
+        let (active, _) = manager.add("report", "Active".to_string(), JobState::Running);
+
+        let jobs = manager.list();
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 1);
+        assert_eq!(jobs[0].label, "Report 5");
+        assert_eq!(jobs[0].state, JobState::Failed);
+        assert_eq!(jobs[0].error.as_deref(), Some("Disk full"));
+        assert_eq!(jobs.last().unwrap().id, active.id);
+    }
+}
@@ -0,0 +1,453 @@
+//! Crash journal for the open document. The editor streams its block edits
+//! here as it goes; they are kept in memory and appended to a JSON Lines
+//! file every couple of seconds, on top of a copy of the document as last
+//! saved. A save compacts the journal back down to that one base line, and
+//! a clean close deletes it, so a journal with edits left in it on the next
+//! open means the app went down with unsaved typing that can be replayed.
+
+use crate::app_data;
+use crate::document;
+use crate::live_document::BlockEdit;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, State};
+
+const JOURNAL_DIR: &str = "journals";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+/// Journals of documents that were never reopened are cleared after this.
+const MAX_JOURNAL_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum JournalEntry {
+    /// The document as last saved; always the first line.
+    Base {
+        document: Value,
+        at: String,
+    },
+    Edits {
+        edits: Vec<BlockEdit>,
+        at: String,
+    },
+}
+
+/// Unsaved work replayed from a journal left behind by a crash.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalRecovery {
+    pub document_json: String,
+    pub edit_count: usize,
+    pub last_edit_at: String,
+}
+
+struct OpenJournal {
+    document_id: String,
+    path: PathBuf,
+    /// Entries not yet written to disk.
+    pending: Vec<JournalEntry>,
+}
+
+impl OpenJournal {
+    fn flush(&mut self) -> Result<(), String> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = String::new();
+        for entry in &self.pending {
+            lines.push_str(&to_line(entry)?);
+        }
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .map_err(|error| format!("Failed to open journal: {}", error))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|error| format!("Failed to write journal: {}", error))?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// The journal of the document open in the editor, if any.
+#[derive(Default)]
+pub struct SaveJournal(Mutex<Option<OpenJournal>>);
+
+impl SaveJournal {
+    fn lock(&self) -> Result<MutexGuard<'_, Option<OpenJournal>>, String> {
+        self.0
+            .lock()
+            .map_err(|_| "Journal is unavailable".to_string())
+    }
+
+    /// Starts the background thread that writes buffered edits to disk.
+    pub fn start(app: AppHandle) {
+        thread::spawn(move || loop {
+            thread::sleep(FLUSH_INTERVAL);
+            // A failed flush keeps its entries for the next one.
+            let _ = app.state::<SaveJournal>().flush();
+        });
+    }
+
+    fn flush(&self) -> Result<(), String> {
+        match self.lock()?.as_mut() {
+            Some(journal) => journal.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Deletes the journal, buffered edits and all; the document was saved
+    /// or its changes discarded.
+    pub fn close(&self) -> Result<(), String> {
+        if let Some(journal) = self.lock()?.take() {
+            remove_journal(&journal.path)?;
+        }
+        Ok(())
+    }
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339()
+}
+
+fn to_line(entry: &JournalEntry) -> Result<String, String> {
+    let mut line = serde_json::to_string(entry)
+        .map_err(|error| format!("Failed to serialize journal entry: {}", error))?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn validate_document_id(document_id: &str) -> Result<(), String> {
+    if document_id.is_empty()
+        || !document_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid document id '{}'", document_id));
+    }
+
+    Ok(())
+}
+
+fn journal_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app_data::app_data_dir(app, JOURNAL_DIR)
+}
+
+fn journal_path(dir: &Path, document_id: &str) -> Result<PathBuf, String> {
+    validate_document_id(document_id)?;
+    Ok(dir.join(format!("{}.jsonl", document_id)))
+}
+
+fn document_id(document: &Value) -> Result<String, String> {
+    document
+        .get("meta")
+        .and_then(|meta| meta.get("id"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "Document has no id".to_string())
+}
+
+fn remove_journal(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(format!("Failed to remove journal: {}", error)),
+    }
+}
+
+/// Replaces the journal at `path` with a single base line, through a
+/// temporary file so a crash mid-write leaves the old journal intact.
+fn write_base(path: &Path, document: Value) -> Result<(), String> {
+    let line = to_line(&JournalEntry::Base {
+        document,
+        at: now(),
+    })?;
+    let temp_path = path.with_extension("jsonl.tmp");
+    let mut file = fs::File::create(&temp_path)
+        .map_err(|error| format!("Failed to create journal: {}", error))?;
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|error| format!("Failed to write journal: {}", error))?;
+    fs::rename(&temp_path, path).map_err(|error| format!("Failed to write journal: {}", error))
+}
+
+/// Entries up to the first line that doesn't parse; power loss can cut the
+/// last write short.
+fn read_entries(content: &str) -> Vec<JournalEntry> {
+    content
+        .lines()
+        .map_while(|line| serde_json::from_str::<JournalEntry>(line).ok())
+        .collect()
+}
+
+/// Applies the journal's edits to its base. Stops at an edit that doesn't
+/// fit the document, which means the editor and journal fell out of step;
+/// everything before it is still good. Returns `None` without edits.
+fn replay(entries: Vec<JournalEntry>) -> Option<JournalRecovery> {
+    let mut entries = entries.into_iter();
+    let Some(JournalEntry::Base { mut document, .. }) = entries.next() else {
+        return None;
+    };
+
+    let mut edit_count = 0;
+    let mut last_edit_at = None;
+    'entries: for entry in entries {
+        let JournalEntry::Edits { edits, at } = entry else {
+            break;
+        };
+        let Some(blocks) = document::blocks_mut(&mut document) else {
+            break;
+        };
+        for edit in edits {
+            let Some(end) = edit.from.checked_add(edit.delete_count) else {
+                break 'entries;
+            };
+            if end > blocks.len() {
+                break 'entries;
+            }
+            blocks.splice(edit.from..end, edit.insert);
+            edit_count += 1;
+            last_edit_at = Some(at.clone());
+        }
+    }
+
+    Some(JournalRecovery {
+        document_json: serde_json::to_string(&document).ok()?,
+        edit_count,
+        last_edit_at: last_edit_at?,
+    })
+}
+
+/// Deletes journals untouched for `MAX_JOURNAL_AGE`, left by crashed
+/// documents that were never opened again.
+fn prune_stale(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > MAX_JOURNAL_AGE);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Starts journaling `document_json`, the document just opened in the
+/// editor. Replaces the previous document's journal, whose changes were
+/// saved or discarded before the switch.
+#[tauri::command]
+pub fn journal_open(
+    app: AppHandle,
+    journal: State<'_, SaveJournal>,
+    document_json: String,
+) -> Result<(), String> {
+    let document = document::parse_json(&document_json)?;
+    let document_id = document_id(&document)?;
+    let dir = journal_dir(&app)?;
+    let path = journal_path(&dir, &document_id)?;
+
+    let mut active = journal.lock()?;
+    if let Some(previous) = active.take() {
+        if previous.document_id != document_id {
+            remove_journal(&previous.path)?;
+        }
+    }
+    prune_stale(&dir);
+    write_base(&path, document)?;
+    *active = Some(OpenJournal {
+        document_id,
+        path,
+        pending: Vec::new(),
+    });
+    Ok(())
+}
+
+/// Buffers block edits, in the form the live document mirror takes them,
+/// for the next flush.
+#[tauri::command]
+pub fn journal_append(
+    journal: State<'_, SaveJournal>,
+    edits: Vec<BlockEdit>,
+) -> Result<(), String> {
+    if edits.is_empty() {
+        return Ok(());
+    }
+    let mut active = journal.lock()?;
+    let journal = active
+        .as_mut()
+        .ok_or_else(|| "No document journal is open".to_string())?;
+    journal
+        .pending
+        .push(JournalEntry::Edits { edits, at: now() });
+    Ok(())
+}
+
+/// Compacts the journal after a save: `document_json` is what was written
+/// to disk, and becomes the new base with no edits on top.
+#[tauri::command]
+pub fn journal_compact(
+    journal: State<'_, SaveJournal>,
+    document_json: String,
+) -> Result<(), String> {
+    let document = document::parse_json(&document_json)?;
+    let mut active = journal.lock()?;
+    let journal = active
+        .as_mut()
+        .ok_or_else(|| "No document journal is open".to_string())?;
+    if document_id(&document)? != journal.document_id {
+        return Err("Saved document doesn't match the open journal".to_string());
+    }
+    journal.pending.clear();
+    write_base(&journal.path, document)
+}
+
+/// Unsaved edits left in the journal of `document_id` by a crash, replayed
+/// onto the last saved copy.
+#[tauri::command]
+pub fn journal_recover(
+    app: AppHandle,
+    document_id: String,
+) -> Result<Option<JournalRecovery>, String> {
+    let path = journal_path(&journal_dir(&app)?, &document_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|error| format!("Failed to read journal: {}", error))?;
+    Ok(replay(read_entries(&content)))
+}
+
+#[tauri::command]
+pub fn journal_close(journal: State<'_, SaveJournal>) -> Result<(), String> {
+    journal.close()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    fn saved(blocks: Vec<Value>) -> Value {
+        json!({
+            "meta": { "id": "doc-1" },
+            "document": { "type": "doc", "content": blocks },
+        })
+    }
+
+    fn edit(from: usize, delete_count: usize, insert: Vec<Value>) -> BlockEdit {
+        BlockEdit {
+            from,
+            delete_count,
+            insert,
+        }
+    }
+
+    fn edits(edits: Vec<BlockEdit>) -> JournalEntry {
+        JournalEntry::Edits {
+            edits,
+            at: "2026-10-15T09:00:00Z".to_string(),
+        }
+    }
+
+    fn recovered_texts(recovery: &JournalRecovery) -> Vec<String> {
+        let value = document::parse_json(&recovery.document_json).unwrap();
+        document::blocks(&value)
+            .iter()
+            .map(document::node_text)
+            .collect()
+    }
+
+    #[test]
+    fn replays_edits_onto_the_base() {
+        let entries = vec![
+            JournalEntry::Base {
+                document: saved(vec![block("action", "One"), block("action", "Two")]),
+                at: now(),
+            },
+            edits(vec![edit(1, 1, vec![block("action", "Two!")])]),
+            edits(vec![
+                edit(2, 0, vec![block("action", "Three")]),
+                edit(0, 1, vec![]),
+            ]),
+        ];
+
+        let recovery = replay(entries).unwrap();
+        assert_eq!(recovered_texts(&recovery), vec!["Two!", "Three"]);
+        assert_eq!(recovery.edit_count, 3);
+        assert_eq!(recovery.last_edit_at, "2026-10-15T09:00:00Z");
+    }
+
+    #[test]
+    fn stops_at_torn_lines_and_edits_that_do_not_fit() {
+        let base = JournalEntry::Base {
+            document: saved(vec![block("action", "One")]),
+            at: now(),
+        };
+        let good = edits(vec![edit(1, 0, vec![block("action", "Two")])]);
+        let torn = to_line(&edits(vec![edit(0, 1, vec![])])).unwrap();
+        let content = format!(
+            "{}{}{}",
+            to_line(&base).unwrap(),
+            to_line(&good).unwrap(),
+            &torn[..torn.len() / 2]
+        );
+
+        let entries = read_entries(&content);
+        assert_eq!(entries.len(), 2);
+        let recovery = replay(entries.clone()).unwrap();
+        assert_eq!(recovered_texts(&recovery), vec!["One", "Two"]);
+
+        let mut out_of_step = entries;
+        out_of_step.push(edits(vec![edit(5, 1, vec![])]));
+        out_of_step.push(edits(vec![edit(0, 1, vec![])]));
+        let recovery = replay(out_of_step).unwrap();
+        assert_eq!(recovered_texts(&recovery), vec!["One", "Two"]);
+
+        assert!(replay(vec![base]).is_none());
+        assert!(replay(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn flushes_appends_and_compacts() {
+        let dir = std::env::temp_dir().join(format!("grainery-journal-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = journal_path(&dir, "doc-1").unwrap();
+        assert!(journal_path(&dir, "../doc").is_err());
+
+        write_base(&path, saved(vec![block("action", "One")])).unwrap();
+        let mut journal = OpenJournal {
+            document_id: "doc-1".to_string(),
+            path: path.clone(),
+            pending: vec![edits(vec![edit(1, 0, vec![block("action", "Two")])])],
+        };
+        journal.flush().unwrap();
+        assert!(journal.pending.is_empty());
+        journal
+            .pending
+            .push(edits(vec![edit(2, 0, vec![block("action", "Three")])]));
+        journal.flush().unwrap();
+
+        let recovery = replay(read_entries(&fs::read_to_string(&path).unwrap())).unwrap();
+        assert_eq!(recovered_texts(&recovery), vec!["One", "Two", "Three"]);
+
+        write_base(&path, saved(vec![block("action", "Saved")])).unwrap();
+        assert!(replay(read_entries(&fs::read_to_string(&path).unwrap())).is_none());
+
+        remove_journal(&path).unwrap();
+        remove_journal(&path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,391 @@
+//! Encrypted connection between two devices. Each side sends a hello with
+//! its device id, its long-term ed25519 key and a fresh X25519 key, then
+//! signs the transcript of both hellos with its long-term key. The X25519
+//! secret, salted with the transcript hash, gives one ChaCha20-Poly1305 key
+//! per direction; every later frame is a length-prefixed JSON message sealed
+//! with the next nonce of its direction.
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305};
+use ring::agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, X25519};
+use ring::hkdf::{self, Prk, Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+const PROTOCOL_VERSION: u32 = 1;
+const TRANSCRIPT_LABEL: &[u8] = b"grainery lan sync";
+/// Largest frame accepted; a screenplay's stored history is far smaller.
+const MAX_FRAME_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Purpose {
+    /// First contact; keys are checked by comparing the pairing code.
+    Pair,
+    /// Both devices must already be paired with each other's keys.
+    Sync,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hello {
+    pub version: u32,
+    pub purpose: Purpose,
+    pub device_id: String,
+    pub device_name: String,
+    /// Base64 ed25519 key the device signs the handshake with.
+    pub identity_key: String,
+    /// Base64 X25519 key used for this connection only.
+    pub exchange_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Proof {
+    signature: String,
+}
+
+/// This device's id, name and long-term signing key.
+pub struct Identity {
+    pub device_id: String,
+    pub device_name: String,
+    pub signing_key: SigningKey,
+}
+
+impl Identity {
+    pub fn public_key(&self) -> String {
+        BASE64_STANDARD.encode(self.signing_key.verifying_key().to_bytes())
+    }
+}
+
+/// Fills 32 bytes from the system RNG, for new signing keys and device ids.
+pub fn random_bytes() -> Result<[u8; 32], String> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate random bytes".to_string())?;
+    Ok(bytes)
+}
+
+pub struct Channel {
+    stream: TcpStream,
+    sealing: LessSafeKey,
+    opening: LessSafeKey,
+    sent: u64,
+    received: u64,
+    pub peer: Hello,
+    /// Six digits both sides derive from the handshake. They only match when
+    /// nobody sat between the two devices, which is what pairing checks.
+    pub pairing_code: String,
+}
+
+fn write_frame(stream: &mut TcpStream, payload: &[u8]) -> Result<(), String> {
+    let length = u32::try_from(payload.len()).map_err(|_| "Message is too large".to_string())?;
+    stream
+        .write_all(&length.to_be_bytes())
+        .and_then(|_| stream.write_all(payload))
+        .map_err(|error| format!("Failed to send to device: {}", error))
+}
+
+fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+    let mut length = [0u8; 4];
+    stream
+        .read_exact(&mut length)
+        .map_err(|error| format!("Failed to read from device: {}", error))?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME_BYTES {
+        return Err(format!("Device sent a {} byte message", length));
+    }
+    let mut payload = vec![0u8; length];
+    stream
+        .read_exact(&mut payload)
+        .map_err(|error| format!("Failed to read from device: {}", error))?;
+    Ok(payload)
+}
+
+fn decode_key<const N: usize>(key_b64: &str, what: &str) -> Result<[u8; N], String> {
+    BASE64_STANDARD
+        .decode(key_b64)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("Device sent an invalid {}", what))
+}
+
+fn transcript_hash(initiator_hello: &[u8], responder_hello: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(TRANSCRIPT_LABEL);
+    for hello in [initiator_hello, responder_hello] {
+        hasher.update((hello.len() as u64).to_be_bytes());
+        hasher.update(hello);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// What a side signs: the transcript plus its role, so a signature can't be
+/// sent back to the device that made it.
+fn signed_message(transcript: &[u8], initiator: bool) -> Vec<u8> {
+    let mut message = transcript.to_vec();
+    message.push(if initiator { b'i' } else { b'r' });
+    message
+}
+
+fn aead_key(prk: &Prk, label: &[u8]) -> Result<LessSafeKey, String> {
+    let info = [label];
+    let okm = prk
+        .expand(&info, &CHACHA20_POLY1305)
+        .map_err(|_| "Failed to derive session key".to_string())?;
+    Ok(LessSafeKey::new(UnboundKey::from(okm)))
+}
+
+struct CodeLength;
+
+impl hkdf::KeyType for CodeLength {
+    fn len(&self) -> usize {
+        4
+    }
+}
+
+fn pairing_code(prk: &Prk) -> Result<String, String> {
+    let mut bytes = [0u8; 4];
+    prk.expand(&[b"pairing code"], CodeLength)
+        .and_then(|okm| okm.fill(&mut bytes))
+        .map_err(|_| "Failed to derive pairing code".to_string())?;
+    Ok(format!("{:06}", u32::from_be_bytes(bytes) % 1_000_000))
+}
+
+fn nonce(counter: u64) -> Nonce {
+    let mut bytes = [0u8; aead::NONCE_LEN];
+    bytes[4..].copy_from_slice(&counter.to_be_bytes());
+    Nonce::assume_unique_for_key(bytes)
+}
+
+/// Runs the handshake as the side that opened the connection.
+pub fn connect(
+    stream: TcpStream,
+    identity: &Identity,
+    purpose: Purpose,
+    check: impl FnOnce(&Hello) -> Result<(), String>,
+) -> Result<Channel, String> {
+    handshake(stream, identity, Some(purpose), check)
+}
+
+/// Runs the handshake as the side that accepted the connection, for
+/// whichever purpose the other side asked for.
+pub fn accept(
+    stream: TcpStream,
+    identity: &Identity,
+    check: impl FnOnce(&Hello) -> Result<(), String>,
+) -> Result<Channel, String> {
+    handshake(stream, identity, None, check)
+}
+
+fn handshake(
+    mut stream: TcpStream,
+    identity: &Identity,
+    purpose: Option<Purpose>,
+    check: impl FnOnce(&Hello) -> Result<(), String>,
+) -> Result<Channel, String> {
+    let initiator = purpose.is_some();
+    let rng = SystemRandom::new();
+    let exchange = EphemeralPrivateKey::generate(&X25519, &rng)
+        .map_err(|_| "Failed to generate exchange key".to_string())?;
+    let exchange_key = exchange
+        .compute_public_key()
+        .map_err(|_| "Failed to generate exchange key".to_string())?;
+    let hello = |purpose| Hello {
+        version: PROTOCOL_VERSION,
+        purpose,
+        device_id: identity.device_id.clone(),
+        device_name: identity.device_name.clone(),
+        identity_key: identity.public_key(),
+        exchange_key: BASE64_STANDARD.encode(exchange_key.as_ref()),
+    };
+    let encode = |hello: &Hello| {
+        serde_json::to_vec(hello).map_err(|error| format!("Failed to encode hello: {}", error))
+    };
+    let decode = |bytes: &[u8]| {
+        serde_json::from_slice::<Hello>(bytes)
+            .map_err(|error| format!("Device sent an invalid hello: {}", error))
+    };
+
+    let (peer, transcript) = match purpose {
+        Some(purpose) => {
+            let ours = encode(&hello(purpose))?;
+            write_frame(&mut stream, &ours)?;
+            let theirs = read_frame(&mut stream)?;
+            let peer = decode(&theirs)?;
+            if peer.purpose != purpose {
+                return Err("Device answered for a different purpose".to_string());
+            }
+            (peer, transcript_hash(&ours, &theirs))
+        }
+        None => {
+            let theirs = read_frame(&mut stream)?;
+            let peer = decode(&theirs)?;
+            let ours = encode(&hello(peer.purpose))?;
+            write_frame(&mut stream, &ours)?;
+            (peer, transcript_hash(&theirs, &ours))
+        }
+    };
+    if peer.version != PROTOCOL_VERSION {
+        return Err(format!(
+            "{} uses a different sync version; update Grainery on both devices",
+            peer.device_name
+        ));
+    }
+    if peer.device_id == identity.device_id {
+        return Err("Refusing to sync with this device itself".to_string());
+    }
+    check(&peer)?;
+
+    let signature = identity
+        .signing_key
+        .sign(&signed_message(&transcript, initiator));
+    let proof = Proof {
+        signature: BASE64_STANDARD.encode(signature.to_bytes()),
+    };
+    write_frame(
+        &mut stream,
+        &serde_json::to_vec(&proof)
+            .map_err(|error| format!("Failed to encode proof: {}", error))?,
+    )?;
+    let their_proof = serde_json::from_slice::<Proof>(&read_frame(&mut stream)?)
+        .map_err(|error| format!("Device sent an invalid proof: {}", error))?;
+    let peer_key = VerifyingKey::from_bytes(&decode_key::<32>(&peer.identity_key, "identity key")?)
+        .map_err(|_| "Device sent an invalid identity key".to_string())?;
+    let their_signature =
+        Signature::from_bytes(&decode_key::<64>(&their_proof.signature, "signature")?);
+    peer_key
+        .verify(&signed_message(&transcript, !initiator), &their_signature)
+        .map_err(|_| format!("{} failed to prove its identity", peer.device_name))?;
+
+    let their_exchange = decode_key::<32>(&peer.exchange_key, "exchange key")?;
+    let prk = agreement::agree_ephemeral(
+        exchange,
+        &UnparsedPublicKey::new(&X25519, their_exchange),
+        |secret| Salt::new(HKDF_SHA256, &transcript).extract(secret),
+    )
+    .map_err(|_| "Key exchange failed".to_string())?;
+    let initiator_key = aead_key(&prk, b"initiator")?;
+    let responder_key = aead_key(&prk, b"responder")?;
+    let (sealing, opening) = if initiator {
+        (initiator_key, responder_key)
+    } else {
+        (responder_key, initiator_key)
+    };
+
+    Ok(Channel {
+        stream,
+        sealing,
+        opening,
+        sent: 0,
+        received: 0,
+        pairing_code: pairing_code(&prk)?,
+        peer,
+    })
+}
+
+impl Channel {
+    pub fn send<T: Serialize>(&mut self, message: &T) -> Result<(), String> {
+        let mut payload = serde_json::to_vec(message)
+            .map_err(|error| format!("Failed to encode message: {}", error))?;
+        self.sealing
+            .seal_in_place_append_tag(nonce(self.sent), Aad::empty(), &mut payload)
+            .map_err(|_| "Failed to encrypt message".to_string())?;
+        self.sent += 1;
+        write_frame(&mut self.stream, &payload)
+    }
+
+    pub fn receive<T: DeserializeOwned>(&mut self) -> Result<T, String> {
+        let mut payload = read_frame(&mut self.stream)?;
+        let plain = self
+            .opening
+            .open_in_place(nonce(self.received), Aad::empty(), &mut payload)
+            .map_err(|_| "Device sent a message that failed to decrypt".to_string())?;
+        self.received += 1;
+        serde_json::from_slice(plain)
+            .map_err(|error| format!("Device sent an invalid message: {}", error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn identity(device_id: &str) -> Identity {
+        Identity {
+            device_id: device_id.to_string(),
+            device_name: format!("{} laptop", device_id),
+            signing_key: SigningKey::from_bytes(&random_bytes().unwrap()),
+        }
+    }
+
+    fn connected_pair(
+        responder_check: impl FnOnce(&Hello) -> Result<(), String> + Send + 'static,
+    ) -> (Result<Channel, String>, Result<Channel, String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let responder = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            accept(stream, &identity("desk"), responder_check)
+        });
+        let stream = TcpStream::connect(address).unwrap();
+        let initiator = connect(stream, &identity("lap"), Purpose::Pair, |_| Ok(()));
+        (initiator, responder.join().unwrap())
+    }
+
+    #[test]
+    fn exchanges_encrypted_messages() {
+        let (initiator, responder) = connected_pair(|_| Ok(()));
+        let (mut initiator, mut responder) = (initiator.unwrap(), responder.unwrap());
+
+        assert_eq!(initiator.peer.device_id, "desk");
+        assert_eq!(responder.peer.device_id, "lap");
+        assert_eq!(responder.peer.purpose, Purpose::Pair);
+        assert_eq!(initiator.pairing_code, responder.pairing_code);
+        assert_eq!(initiator.pairing_code.len(), 6);
+
+        for text in ["first", "second"] {
+            initiator.send(&text).unwrap();
+            assert_eq!(responder.receive::<String>().unwrap(), text);
+        }
+        responder.send(&vec![1, 2, 3]).unwrap();
+        assert_eq!(initiator.receive::<Vec<u32>>().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn stops_when_the_device_is_not_trusted() {
+        let (_, responder) =
+            connected_pair(|hello| Err(format!("Unknown device {}", hello.device_id)));
+        assert_eq!(responder.err().as_deref(), Some("Unknown device lap"));
+    }
+
+    #[test]
+    fn rejects_tampered_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let responder = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut channel = accept(stream, &identity("desk"), |_| Ok(())).unwrap();
+            channel.receive::<String>()
+        });
+        let stream = TcpStream::connect(address).unwrap();
+        let mut channel = connect(stream, &identity("lap"), Purpose::Sync, |_| Ok(())).unwrap();
+
+        // A frame sealed with the wrong nonce, as a replayed or reordered one would be
+        let mut payload = serde_json::to_vec("hello").unwrap();
+        channel
+            .sealing
+            .seal_in_place_append_tag(nonce(7), Aad::empty(), &mut payload)
+            .unwrap();
+        write_frame(&mut channel.stream, &payload).unwrap();
+        assert!(responder.join().unwrap().is_err());
+    }
+}
@@ -0,0 +1,492 @@
+//! mDNS advertising and browsing for `_grainery._tcp.local` (RFC 6762 and
+//! 6763), kept to the records sync needs: a PTR to this device's instance,
+//! its SRV port, a TXT record with the device id, name and key fingerprint,
+//! and an A record for its host. Browsing asks the same PTR question and
+//! reads the answers other devices multicast back.
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SERVICE: &str = "_grainery._tcp.local";
+const MDNS_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+const RECORD_TTL: u32 = 120;
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(30);
+/// Answers to other devices' questions are sent at most this often.
+const ANSWER_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_NAME_BYTES: usize = 200;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+/// Set on records only this device answers for (RFC 6762 section 10.2).
+const CACHE_FLUSH: u16 = 0x8000;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_AUTHORITATIVE: u16 = 0x0400;
+
+/// What this device advertises.
+#[derive(Debug, Clone)]
+pub struct Advert {
+    pub device_id: String,
+    pub name: String,
+    pub fingerprint: String,
+    pub port: u16,
+}
+
+/// A device found on the network. A `ttl` of zero means it is leaving.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Announcement {
+    pub device_id: String,
+    pub name: String,
+    pub fingerprint: String,
+    pub port: u16,
+    pub ttl: u32,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Packet {
+    /// True for a question about the Grainery service.
+    pub asks_for_service: bool,
+    pub announcements: Vec<Announcement>,
+}
+
+fn instance_name(device_id: &str) -> String {
+    format!("{}.{}", device_id, SERVICE)
+}
+
+fn host_name(device_id: &str) -> String {
+    format!("{}.local", device_id)
+}
+
+fn write_name(out: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        let label = &label.as_bytes()[..label.len().min(63)];
+        out.push(label.len() as u8);
+        out.extend_from_slice(label);
+    }
+    out.push(0);
+}
+
+fn write_header(out: &mut Vec<u8>, flags: u16, questions: u16, answers: u16) {
+    for field in [0, flags, questions, answers, 0, 0] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+}
+
+fn write_record(
+    out: &mut Vec<u8>,
+    name: &str,
+    record_type: u16,
+    class: u16,
+    ttl: u32,
+    data: &[u8],
+) {
+    write_name(out, name);
+    out.extend_from_slice(&record_type.to_be_bytes());
+    out.extend_from_slice(&class.to_be_bytes());
+    out.extend_from_slice(&ttl.to_be_bytes());
+    out.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    out.extend_from_slice(data);
+}
+
+/// Truncates `text` to at most `max` bytes without splitting a character.
+fn truncate(text: &str, max: usize) -> &str {
+    let mut end = text.len().min(max);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+pub fn encode_query() -> Vec<u8> {
+    let mut out = Vec::new();
+    write_header(&mut out, 0, 1, 0);
+    write_name(&mut out, SERVICE);
+    out.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    out.extend_from_slice(&CLASS_IN.to_be_bytes());
+    out
+}
+
+/// The records advertising `advert`. A `ttl` of zero withdraws them.
+pub fn encode_announcement(advert: &Advert, address: Option<Ipv4Addr>, ttl: u32) -> Vec<u8> {
+    let instance = instance_name(&advert.device_id);
+    let host = host_name(&advert.device_id);
+    let mut out = Vec::new();
+    write_header(
+        &mut out,
+        FLAG_RESPONSE | FLAG_AUTHORITATIVE,
+        0,
+        if address.is_some() { 4 } else { 3 },
+    );
+
+    let mut ptr = Vec::new();
+    write_name(&mut ptr, &instance);
+    write_record(&mut out, SERVICE, TYPE_PTR, CLASS_IN, ttl, &ptr);
+
+    let mut srv = Vec::new();
+    srv.extend_from_slice(&0u16.to_be_bytes());
+    srv.extend_from_slice(&0u16.to_be_bytes());
+    srv.extend_from_slice(&advert.port.to_be_bytes());
+    write_name(&mut srv, &host);
+    write_record(
+        &mut out,
+        &instance,
+        TYPE_SRV,
+        CLASS_IN | CACHE_FLUSH,
+        ttl,
+        &srv,
+    );
+
+    let mut txt = Vec::new();
+    for entry in [
+        format!("id={}", advert.device_id),
+        format!("name={}", truncate(&advert.name, MAX_NAME_BYTES)),
+        format!("fp={}", advert.fingerprint),
+    ] {
+        let entry = truncate(&entry, 255);
+        txt.push(entry.len() as u8);
+        txt.extend_from_slice(entry.as_bytes());
+    }
+    write_record(
+        &mut out,
+        &instance,
+        TYPE_TXT,
+        CLASS_IN | CACHE_FLUSH,
+        ttl,
+        &txt,
+    );
+
+    if let Some(address) = address {
+        write_record(
+            &mut out,
+            &host,
+            TYPE_A,
+            CLASS_IN | CACHE_FLUSH,
+            ttl,
+            &address.octets(),
+        );
+    }
+    out
+}
+
+fn read_u16(packet: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(packet.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(packet: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(packet.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Reads a possibly compressed name at `at`; returns it and the offset just
+/// past it in the record.
+fn read_name(packet: &[u8], at: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut position = at;
+    let mut end = None;
+    for _ in 0..128 {
+        let length = *packet.get(position)? as usize;
+        if length == 0 {
+            return Some((labels.join("."), end.unwrap_or(position + 1)));
+        }
+        if length & 0xc0 == 0xc0 {
+            let pointer = (read_u16(packet, position)? & 0x3fff) as usize;
+            end.get_or_insert(position + 2);
+            position = pointer;
+            continue;
+        }
+        let label = packet.get(position + 1..position + 1 + length)?;
+        labels.push(String::from_utf8_lossy(label).to_string());
+        position += 1 + length;
+    }
+    None
+}
+
+fn txt_entries(data: &[u8]) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut position = 0;
+    while let Some(&length) = data.get(position) {
+        let Some(entry) = data.get(position + 1..position + 1 + length as usize) else {
+            break;
+        };
+        let entry = String::from_utf8_lossy(entry);
+        if let Some((key, value)) = entry.split_once('=') {
+            entries.push((key.to_string(), value.to_string()));
+        }
+        position += 1 + length as usize;
+    }
+    entries
+}
+
+#[derive(Default)]
+struct Instance {
+    port: Option<u16>,
+    ttl: u32,
+    entries: Vec<(String, String)>,
+}
+
+fn instance<'a>(instances: &'a mut Vec<(String, Instance)>, name: &str) -> &'a mut Instance {
+    let index = match instances
+        .iter()
+        .position(|(existing, _)| existing.eq_ignore_ascii_case(name))
+    {
+        Some(index) => index,
+        None => {
+            instances.push((name.to_string(), Instance::default()));
+            instances.len() - 1
+        }
+    };
+    &mut instances[index].1
+}
+
+/// Reads the parts of an mDNS packet sync cares about. Returns `None` for
+/// packets too short or malformed to read.
+pub fn parse(packet: &[u8]) -> Option<Packet> {
+    let flags = read_u16(packet, 2)?;
+    let questions = read_u16(packet, 4)?;
+    let records = [6, 8, 10]
+        .into_iter()
+        .map(|at| read_u16(packet, at).map(usize::from))
+        .sum::<Option<usize>>()?;
+    let mut position = 12;
+    let mut result = Packet::default();
+
+    for _ in 0..questions {
+        let (name, next) = read_name(packet, position)?;
+        let question_type = read_u16(packet, next)?;
+        position = next + 4;
+        if flags & FLAG_RESPONSE == 0
+            && name.eq_ignore_ascii_case(SERVICE)
+            && matches!(question_type, TYPE_PTR | TYPE_ANY)
+        {
+            result.asks_for_service = true;
+        }
+    }
+
+    let mut instances = Vec::<(String, Instance)>::new();
+    for _ in 0..records {
+        let (name, next) = read_name(packet, position)?;
+        let record_type = read_u16(packet, next)?;
+        let ttl = read_u32(packet, next + 4)?;
+        let length = read_u16(packet, next + 8)? as usize;
+        let data_start = next + 10;
+        let data = packet.get(data_start..data_start + length)?;
+        position = data_start + length;
+
+        match record_type {
+            TYPE_PTR if name.eq_ignore_ascii_case(SERVICE) => {
+                let (target, _) = read_name(packet, data_start)?;
+                instance(&mut instances, &target).ttl = ttl;
+            }
+            TYPE_SRV => {
+                let found = instance(&mut instances, &name);
+                found.port = read_u16(data, 4);
+                found.ttl = ttl;
+            }
+            TYPE_TXT => {
+                instance(&mut instances, &name).entries = txt_entries(data);
+            }
+            _ => {}
+        }
+    }
+
+    let service_suffix = format!(".{}", SERVICE);
+    for (name, found) in instances {
+        if !name.to_ascii_lowercase().ends_with(&service_suffix) {
+            continue;
+        }
+        let entry = |key: &str| {
+            found
+                .entries
+                .iter()
+                .find(|(existing, _)| existing == key)
+                .map(|(_, value)| value.clone())
+        };
+        let (Some(device_id), Some(port)) = (entry("id"), found.port) else {
+            continue;
+        };
+        result.announcements.push(Announcement {
+            device_id,
+            name: entry("name").unwrap_or_default(),
+            fingerprint: entry("fp").unwrap_or_default(),
+            port,
+            ttl: found.ttl,
+        });
+    }
+    Some(result)
+}
+
+fn open_socket() -> Result<UdpSocket, String> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
+        .map_err(|error| format!("Failed to open discovery socket: {}", error))?;
+    let configure = || -> std::io::Result<()> {
+        // Other mDNS responders on the machine share the port
+        socket.set_reuse_address(true)?;
+        #[cfg(unix)]
+        socket.set_reuse_port(true)?;
+        socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, MDNS_PORT)).into())?;
+        socket.join_multicast_v4(&MDNS_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+        socket.set_multicast_ttl_v4(255)?;
+        socket.set_multicast_loop_v4(true)?;
+        socket.set_read_timeout(Some(Duration::from_secs(1)))
+    };
+    configure().map_err(|error| format!("Failed to set up discovery: {}", error))?;
+    Ok(socket.into())
+}
+
+/// The address other devices reach this one at: the source address the OS
+/// picks for multicast. Nothing is sent to find it.
+fn local_address() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((MDNS_ADDR, MDNS_PORT)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(address) if !address.is_unspecified() => Some(address),
+        _ => None,
+    }
+}
+
+/// Advertises `advert` and reports every other Grainery device heard, with
+/// the address it was heard from, until `stop` is set. Withdraws the advert
+/// on the way out.
+pub fn run(
+    advert: &Advert,
+    stop: &Arc<AtomicBool>,
+    mut found: impl FnMut(Announcement, IpAddr),
+) -> Result<(), String> {
+    let socket = open_socket()?;
+    let destination = SocketAddr::from((MDNS_ADDR, MDNS_PORT));
+    let send = |packet: Vec<u8>| {
+        if let Err(error) = socket.send_to(&packet, destination) {
+            tracing::warn!("Failed to send discovery packet: {}", error);
+        }
+    };
+    let mut last_announced: Option<Instant> = None;
+    let mut last_answered: Option<Instant> = None;
+    let mut buffer = [0u8; 9000];
+
+    while !stop.load(Ordering::Relaxed) {
+        if last_announced.is_none_or(|at| at.elapsed() >= ANNOUNCE_INTERVAL) {
+            send(encode_announcement(advert, local_address(), RECORD_TTL));
+            send(encode_query());
+            last_announced = Some(Instant::now());
+        }
+
+        let (length, from) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue
+            }
+            Err(error) => {
+                tracing::warn!("Failed to read discovery packet: {}", error);
+                std::thread::sleep(Duration::from_secs(1));
+                continue;
+            }
+        };
+        let Some(packet) = parse(&buffer[..length]) else {
+            continue;
+        };
+        if packet.asks_for_service && last_answered.is_none_or(|at| at.elapsed() >= ANSWER_INTERVAL)
+        {
+            send(encode_announcement(advert, local_address(), RECORD_TTL));
+            last_answered = Some(Instant::now());
+        }
+        for announcement in packet.announcements {
+            if announcement.device_id != advert.device_id {
+                found(announcement, from.ip());
+            }
+        }
+    }
+
+    send(encode_announcement(advert, local_address(), 0));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advert() -> Advert {
+        Advert {
+            device_id: "3f2a9c01d4e5b6a7".to_string(),
+            name: "Studio iMac".to_string(),
+            fingerprint: "ab12:cd34".to_string(),
+            port: 50123,
+        }
+    }
+
+    #[test]
+    fn reads_back_its_own_announcement() {
+        let packet =
+            encode_announcement(&advert(), Some(Ipv4Addr::new(192, 168, 1, 20)), RECORD_TTL);
+        let parsed = parse(&packet).unwrap();
+        assert!(!parsed.asks_for_service);
+        assert_eq!(
+            parsed.announcements,
+            vec![Announcement {
+                device_id: "3f2a9c01d4e5b6a7".to_string(),
+                name: "Studio iMac".to_string(),
+                fingerprint: "ab12:cd34".to_string(),
+                port: 50123,
+                ttl: RECORD_TTL,
+            }]
+        );
+
+        let goodbye = parse(&encode_announcement(&advert(), None, 0)).unwrap();
+        assert_eq!(goodbye.announcements[0].ttl, 0);
+        assert!(parse(&encode_query()).unwrap().asks_for_service);
+    }
+
+    #[test]
+    fn follows_compressed_names() {
+        // A response as another responder would send it: the PTR target and
+        // the SRV and TXT owners point back at the service name
+        let mut packet = Vec::new();
+        write_header(&mut packet, FLAG_RESPONSE, 0, 3);
+        let service_at = packet.len() as u16;
+        write_name(&mut packet, SERVICE);
+        packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet.extend_from_slice(&120u32.to_be_bytes());
+        packet.extend_from_slice(&6u16.to_be_bytes());
+        let instance_at = packet.len() as u16;
+        packet.extend_from_slice(&[3, b'd', b'e', b'v']);
+        packet.extend_from_slice(&(0xc000 | service_at).to_be_bytes());
+        let pointer = (0xc000 | instance_at).to_be_bytes();
+
+        packet.extend_from_slice(&pointer);
+        packet.extend_from_slice(&TYPE_SRV.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet.extend_from_slice(&120u32.to_be_bytes());
+        packet.extend_from_slice(&8u16.to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0, 0, 0x1f, 0x90, 0xc0, 12]);
+
+        packet.extend_from_slice(&pointer);
+        packet.extend_from_slice(&TYPE_TXT.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet.extend_from_slice(&120u32.to_be_bytes());
+        packet.extend_from_slice(&7u16.to_be_bytes());
+        packet.extend_from_slice(&[6, b'i', b'd', b'=', b'd', b'e', b'v']);
+
+        let parsed = parse(&packet).unwrap();
+        assert_eq!(parsed.announcements.len(), 1);
+        assert_eq!(parsed.announcements[0].device_id, "dev");
+        assert_eq!(parsed.announcements[0].port, 8080);
+        assert!(parse(&packet[..packet.len() - 3]).is_none());
+    }
+
+    #[test]
+    fn truncates_names_on_character_boundaries() {
+        assert_eq!(truncate("Café", 4), "Caf");
+        assert_eq!(truncate("Café", 5), "Café");
+    }
+}
@@ -0,0 +1,861 @@
+//! LAN sync between a writer's own devices, with no server in between.
+//! Devices find each other over mDNS (`discovery`), pair once by comparing
+//! a six-digit code, and then swap stored versions over an encrypted
+//! connection (`channel`). Each side merges what it received into its own
+//! `HEAD` with `versions::merge_head` and writes the result to the
+//! document's file. See docs/lan-sync.md for the protocol.
+
+mod channel;
+mod discovery;
+
+use crate::app_data::{load_json, save_json};
+use crate::versions::{self, StoredVersion};
+use crate::{plugins, sidecar};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use channel::{Channel, Hello, Identity, Purpose};
+use chrono::Utc;
+use discovery::Announcement;
+use ed25519_dalek::SigningKey;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+const SYNC_FILE: &str = "lan-sync.json";
+const KEYCHAIN_SERVICE: &str = "com.grainery.sync";
+const STATUS_EVENT: &str = "lan-sync-status";
+const PAIRING_EVENT: &str = "lan-sync-pairing";
+const DOCUMENT_EVENT: &str = "lan-sync-document";
+const MAX_DEVICE_NAME_CHARS: usize = 60;
+/// Paired devices on the network are synced this often, and after each save.
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+/// A device that stopped announcing itself counts as gone after this.
+const NEARBY_EXPIRY: Duration = Duration::from_secs(150);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long a pairing waits for the writer to confirm the code.
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Serializes read-modify-write of the sync file.
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    device_id: String,
+    #[serde(default)]
+    device_name: String,
+    #[serde(default)]
+    paired: Vec<PairedDevice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedDevice {
+    pub device_id: String,
+    pub name: String,
+    /// Base64 ed25519 key the device proved it holds when it was paired.
+    /// The private half never leaves that device's keychain.
+    pub public_key: String,
+    pub paired_at: String,
+    #[serde(default)]
+    pub last_synced_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedDeviceStatus {
+    #[serde(flatten)]
+    pub device: PairedDevice,
+    pub fingerprint: String,
+    pub online: bool,
+}
+
+/// An unpaired Grainery device heard on the network.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearbyDevice {
+    pub device_id: String,
+    pub name: String,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub enabled: bool,
+    pub device_id: String,
+    pub device_name: String,
+    /// This device's key fingerprint; known once sync has been turned on.
+    pub fingerprint: Option<String>,
+    pub nearby: Vec<NearbyDevice>,
+    pub paired: Vec<PairedDeviceStatus>,
+    pub last_error: Option<String>,
+}
+
+/// Asks the writer to compare `code` with the one shown on the other device.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PairingRequest {
+    pairing_id: String,
+    device_name: String,
+    code: String,
+    /// True on the device where pairing was started.
+    initiated: bool,
+}
+
+/// A document whose `HEAD` a sync moved. Its file was already rewritten; an
+/// editor showing it without unsaved changes should load `document_json`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedDocument {
+    document_id: String,
+    hash: String,
+    document_json: String,
+    conflicts: usize,
+    device_name: String,
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Heads {
+    /// `HEAD` of every document the device has versions of.
+    documents: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Have {
+    /// Every stored version hash, for the documents whose heads differ.
+    documents: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedVersion {
+    hash: String,
+    #[serde(flatten)]
+    version: StoredVersion,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Versions {
+    /// The versions the other device said it doesn't have.
+    documents: BTreeMap<String, Vec<SyncedVersion>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PairingAnswer {
+    accepted: bool,
+}
+
+struct NearbyPeer {
+    name: String,
+    fingerprint: String,
+    address: SocketAddr,
+    seen: Instant,
+}
+
+struct Runtime {
+    stop: Arc<AtomicBool>,
+    trigger: Sender<()>,
+}
+
+#[derive(Default)]
+struct SyncState {
+    identity: Option<Arc<Identity>>,
+    runtime: Option<Runtime>,
+    nearby: HashMap<String, NearbyPeer>,
+    /// Open pairings waiting for the writer's answer, by pairing id.
+    pairings: HashMap<String, Sender<bool>>,
+    last_error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct LanSync(Mutex<SyncState>);
+
+impl LanSync {
+    fn lock(&self) -> MutexGuard<'_, SyncState> {
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+fn default_device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .ok()
+        .or_else(|| {
+            std::process::Command::new("hostname")
+                .output()
+                .ok()
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+        })
+        .map(|name| name.trim().trim_end_matches(".local").to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Grainery".to_string())
+}
+
+/// Reads the sync file, giving the device an id and a name the first time.
+fn load_config(app: &AppHandle) -> Result<SyncConfig, String> {
+    update_config(app, |_| {})
+}
+
+fn update_config(
+    app: &AppHandle,
+    change: impl FnOnce(&mut SyncConfig),
+) -> Result<SyncConfig, String> {
+    let _guard = CONFIG_LOCK
+        .lock()
+        .unwrap_or_else(|error| error.into_inner());
+    let mut config = load_json::<SyncConfig>(app, SYNC_FILE)?;
+    let before = serde_json::to_value(&config).ok();
+    if config.device_id.is_empty() {
+        config.device_id = channel::random_bytes()?[..8]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+    }
+    if config.device_name.is_empty() {
+        config.device_name = default_device_name();
+    }
+    change(&mut config);
+    if serde_json::to_value(&config).ok() != before {
+        save_json(app, SYNC_FILE, &config)?;
+    }
+    Ok(config)
+}
+
+/// This device's signing key, kept in the OS keychain and created the first
+/// time sync is turned on.
+fn identity(app: &AppHandle) -> Result<Arc<Identity>, String> {
+    let config = load_config(app)?;
+    let state = app.state::<LanSync>();
+    let mut state = state.lock();
+    if let Some(identity) = &state.identity {
+        if identity.device_name == config.device_name {
+            return Ok(identity.clone());
+        }
+    }
+
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, &config.device_id)
+        .map_err(|error| format!("Failed to open keychain: {}", error))?;
+    let seed = match entry.get_password() {
+        Ok(seed) => BASE64_STANDARD
+            .decode(seed)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| "The stored sync key is damaged".to_string())?,
+        Err(keyring::Error::NoEntry) => {
+            let seed = channel::random_bytes()?;
+            entry
+                .set_password(&BASE64_STANDARD.encode(seed))
+                .map_err(|error| format!("Failed to store sync key: {}", error))?;
+            seed
+        }
+        Err(error) => return Err(format!("Failed to read sync key: {}", error)),
+    };
+    let identity = Arc::new(Identity {
+        device_id: config.device_id,
+        device_name: config.device_name,
+        signing_key: SigningKey::from_bytes(&seed),
+    });
+    state.identity = Some(identity.clone());
+    Ok(identity)
+}
+
+fn status(app: &AppHandle) -> Result<SyncStatus, String> {
+    let config = load_config(app)?;
+    let state = app.state::<LanSync>();
+    let state = state.lock();
+    let online = |device_id: &str| {
+        state
+            .nearby
+            .get(device_id)
+            .filter(|peer| peer.seen.elapsed() < NEARBY_EXPIRY)
+    };
+
+    let mut nearby = state
+        .nearby
+        .iter()
+        .filter(|(device_id, _)| {
+            online(device_id).is_some()
+                && !config
+                    .paired
+                    .iter()
+                    .any(|device| &device.device_id == *device_id)
+        })
+        .map(|(device_id, peer)| NearbyDevice {
+            device_id: device_id.clone(),
+            name: peer.name.clone(),
+            fingerprint: peer.fingerprint.clone(),
+        })
+        .collect::<Vec<_>>();
+    nearby.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(SyncStatus {
+        enabled: state.runtime.is_some(),
+        fingerprint: state
+            .identity
+            .as_ref()
+            .map(|identity| plugins::key_fingerprint(&identity.public_key())),
+        nearby,
+        paired: config
+            .paired
+            .iter()
+            .map(|device| PairedDeviceStatus {
+                fingerprint: plugins::key_fingerprint(&device.public_key),
+                online: online(&device.device_id).is_some(),
+                device: device.clone(),
+            })
+            .collect(),
+        last_error: state.last_error.clone(),
+        device_id: config.device_id,
+        device_name: config.device_name,
+    })
+}
+
+fn emit_status(app: &AppHandle) {
+    if let Ok(status) = status(app) {
+        let _ = app.emit(STATUS_EVENT, status);
+    }
+}
+
+fn set_error(app: &AppHandle, error: Option<String>) {
+    if let Some(error) = &error {
+        tracing::warn!("LAN sync: {}", error);
+    }
+    app.state::<LanSync>().lock().last_error = error;
+    emit_status(app);
+}
+
+/// Asks for a sync with every paired device on the network, after a save.
+pub fn request_sync(app: &AppHandle) {
+    if let Some(state) = app.try_state::<LanSync>() {
+        if let Some(runtime) = &state.lock().runtime {
+            let _ = runtime.trigger.send(());
+        }
+    }
+}
+
+fn prepare(stream: &TcpStream, timeout: Duration) -> Result<(), String> {
+    stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_read_timeout(Some(timeout)))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .and_then(|_| stream.set_nodelay(true))
+        .map_err(|error| format!("Failed to set up connection: {}", error))
+}
+
+fn connect_to(address: SocketAddr, timeout: Duration) -> Result<TcpStream, String> {
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+        .map_err(|error| format!("Failed to connect to {}: {}", address, error))?;
+    prepare(&stream, timeout)?;
+    Ok(stream)
+}
+
+fn trusted<'a>(paired: &'a [PairedDevice], hello: &Hello) -> Result<&'a PairedDevice, String> {
+    paired
+        .iter()
+        .find(|device| {
+            device.device_id == hello.device_id && device.public_key == hello.identity_key
+        })
+        .ok_or_else(|| format!("{} is not paired with this device", hello.device_name))
+}
+
+/// Sends `message` and receives the other side's. The device that opened
+/// the connection sends first, so two large messages never wait on each
+/// other.
+fn exchange<T: Serialize + DeserializeOwned>(
+    channel: &mut Channel,
+    initiated: bool,
+    message: &T,
+) -> Result<T, String> {
+    if initiated {
+        channel.send(message)?;
+        channel.receive()
+    } else {
+        let reply = channel.receive()?;
+        channel.send(message)?;
+        Ok(reply)
+    }
+}
+
+/// Shows the pairing code, waits for the writer on both devices to confirm
+/// it, and remembers the other device's key if both did.
+fn pair(app: &AppHandle, mut channel: Channel, initiated: bool) -> Result<(), String> {
+    let pairing_id = sidecar::next_id("pairing");
+    let (answer, answered) = mpsc::channel();
+    let state = app.state::<LanSync>();
+    state.lock().pairings.insert(pairing_id.clone(), answer);
+    let _ = app.emit(
+        PAIRING_EVENT,
+        PairingRequest {
+            pairing_id: pairing_id.clone(),
+            device_name: channel.peer.device_name.clone(),
+            code: channel.pairing_code.clone(),
+            initiated,
+        },
+    );
+    let accepted = answered.recv_timeout(PAIRING_TIMEOUT).unwrap_or(false);
+    state.lock().pairings.remove(&pairing_id);
+
+    let theirs = exchange(&mut channel, initiated, &PairingAnswer { accepted })?;
+    if !accepted {
+        return Ok(());
+    }
+    let peer = channel.peer;
+    if !theirs.accepted {
+        return Err(format!("{} didn't confirm the pairing", peer.device_name));
+    }
+    update_config(app, |config| {
+        config
+            .paired
+            .retain(|device| device.device_id != peer.device_id);
+        config.paired.push(PairedDevice {
+            device_id: peer.device_id.clone(),
+            name: peer.device_name.clone(),
+            public_key: peer.identity_key.clone(),
+            paired_at: Utc::now().to_rfc3339(),
+            last_synced_at: None,
+        });
+    })?;
+    set_error(app, None);
+    request_sync(app);
+    Ok(())
+}
+
+/// Swaps the versions the other device is missing for each document both
+/// have saved, then merges what came in.
+fn sync_documents(app: &AppHandle, mut channel: Channel, initiated: bool) -> Result<(), String> {
+    let heads = versions::heads(app)?;
+    let theirs = exchange(
+        &mut channel,
+        initiated,
+        &Heads {
+            documents: heads.clone(),
+        },
+    )?
+    .documents;
+
+    // A document has to be saved once on each device before it syncs, which
+    // also tells this device where its file is
+    let mut dirs = BTreeMap::new();
+    for (document_id, head) in &heads {
+        if theirs.get(document_id).is_some_and(|theirs| theirs != head) {
+            dirs.insert(
+                document_id.clone(),
+                versions::versions_dir(app, document_id)?,
+            );
+        }
+    }
+
+    let have = dirs
+        .iter()
+        .map(|(document_id, dir)| (document_id.clone(), versions::stored_hashes(dir)))
+        .collect();
+    let their_have = exchange(&mut channel, initiated, &Have { documents: have })?.documents;
+
+    let outgoing = dirs
+        .iter()
+        .map(|(document_id, dir)| {
+            let known = their_have
+                .get(document_id)
+                .map(|hashes| hashes.iter().map(String::as_str).collect::<HashSet<_>>())
+                .unwrap_or_default();
+            let missing = versions::stored_hashes(dir)
+                .into_iter()
+                .filter(|hash| !known.contains(hash.as_str()))
+                .filter_map(|hash| {
+                    Some(SyncedVersion {
+                        version: versions::read_version(dir, &hash).ok()?,
+                        hash,
+                    })
+                })
+                .collect();
+            (document_id.clone(), missing)
+        })
+        .collect();
+    let mut incoming = exchange(
+        &mut channel,
+        initiated,
+        &Versions {
+            documents: outgoing,
+        },
+    )?
+    .documents;
+
+    for (document_id, dir) in &dirs {
+        let received = incoming.remove(document_id).unwrap_or_default();
+        if let Err(error) = apply(
+            app,
+            document_id,
+            dir,
+            &received,
+            &theirs[document_id],
+            &channel.peer.device_name,
+        ) {
+            tracing::warn!("Failed to sync document {}: {}", document_id, error);
+        }
+    }
+
+    let device_id = channel.peer.device_id;
+    update_config(app, |config| {
+        if let Some(device) = config
+            .paired
+            .iter_mut()
+            .find(|device| device.device_id == device_id)
+        {
+            device.last_synced_at = Some(Utc::now().to_rfc3339());
+        }
+    })?;
+    Ok(())
+}
+
+/// Stores the versions received for one document and brings the other
+/// device's `HEAD` into this one's.
+fn apply(
+    app: &AppHandle,
+    document_id: &str,
+    dir: &Path,
+    received: &[SyncedVersion],
+    head: &str,
+    device_name: &str,
+) -> Result<(), String> {
+    let (change, path) = {
+        let _guard = versions::lock();
+        for synced in received {
+            versions::import(dir, &synced.hash, &synced.version)?;
+        }
+        let Some(change) = versions::merge_head(dir, head)? else {
+            return Ok(());
+        };
+        let path = versions::write_saved_file(dir, &change.document)?;
+        (change, path)
+    };
+
+    if change.conflicts > 0 {
+        tracing::info!(
+            "Merged {} with changes from {}; {} conflicts were marked",
+            document_id,
+            device_name,
+            change.conflicts
+        );
+    }
+    let document_json = serde_json::to_string(&change.document)
+        .map_err(|error| format!("Failed to serialize document: {}", error))?;
+    let _ = app.emit(
+        DOCUMENT_EVENT,
+        SyncedDocument {
+            document_id: document_id.to_string(),
+            hash: change.hash,
+            document_json,
+            conflicts: change.conflicts,
+            device_name: device_name.to_string(),
+            path,
+        },
+    );
+    Ok(())
+}
+
+fn sync_with(app: &AppHandle, device: &PairedDevice, address: SocketAddr) -> Result<(), String> {
+    let stream = connect_to(address, IO_TIMEOUT)?;
+    let identity = identity(app)?;
+    let channel = channel::connect(stream, &identity, Purpose::Sync, |hello| {
+        trusted(std::slice::from_ref(device), hello).map(|_| ())
+    })?;
+    sync_documents(app, channel, true)
+}
+
+fn sync_online_devices(app: &AppHandle) {
+    let Ok(config) = load_config(app) else {
+        return;
+    };
+    let online = {
+        let state = app.state::<LanSync>();
+        let state = state.lock();
+        config
+            .paired
+            .into_iter()
+            .filter_map(|device| {
+                let peer = state.nearby.get(&device.device_id)?;
+                (peer.seen.elapsed() < NEARBY_EXPIRY).then_some((device, peer.address))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for (device, address) in online {
+        match sync_with(app, &device, address) {
+            Ok(()) => set_error(app, None),
+            Err(error) => set_error(
+                app,
+                Some(format!("Failed to sync with {}: {}", device.name, error)),
+            ),
+        }
+    }
+}
+
+/// Answers a connection from another device: a pairing request from anyone,
+/// or a sync from a paired device.
+fn answer(app: &AppHandle, stream: TcpStream) -> Result<(), String> {
+    // Pairing waits on the writer, so allow for that on every connection
+    prepare(&stream, PAIRING_TIMEOUT + IO_TIMEOUT)?;
+    let identity = identity(app)?;
+    let paired = load_config(app)?.paired;
+    let channel = channel::accept(stream, &identity, |hello| match hello.purpose {
+        Purpose::Pair => Ok(()),
+        Purpose::Sync => trusted(&paired, hello).map(|_| ()),
+    })?;
+    match channel.peer.purpose {
+        Purpose::Pair => pair(app, channel, false),
+        Purpose::Sync => sync_documents(app, channel, false),
+    }
+}
+
+fn listen(app: AppHandle, listener: TcpListener, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, address)) => {
+                let app = app.clone();
+                thread::spawn(move || {
+                    if let Err(error) = answer(&app, stream) {
+                        tracing::warn!("LAN sync with {} failed: {}", address, error);
+                    }
+                });
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(250));
+            }
+            Err(error) => {
+                tracing::warn!("Failed to accept LAN sync connection: {}", error);
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+    }
+}
+
+fn found(app: &AppHandle, announcement: Announcement, address: IpAddr) {
+    let changed = {
+        let state = app.state::<LanSync>();
+        let mut state = state.lock();
+        if announcement.ttl == 0 {
+            state.nearby.remove(&announcement.device_id).is_some()
+        } else {
+            let peer = NearbyPeer {
+                name: announcement.name,
+                fingerprint: announcement.fingerprint,
+                address: SocketAddr::new(address, announcement.port),
+                seen: Instant::now(),
+            };
+            let changed = state
+                .nearby
+                .get(&announcement.device_id)
+                .is_none_or(|known| {
+                    known.address != peer.address
+                        || known.name != peer.name
+                        || known.seen.elapsed() >= NEARBY_EXPIRY
+                });
+            state.nearby.insert(announcement.device_id, peer);
+            changed
+        }
+    };
+    if changed {
+        emit_status(app);
+        request_sync(app);
+    }
+}
+
+/// Syncs when asked to and every `SYNC_INTERVAL`, until `stop` is set.
+fn run_worker(app: AppHandle, triggered: Receiver<()>, stop: Arc<AtomicBool>) {
+    let mut last_run: Option<Instant> = None;
+    let mut pending = false;
+    while !stop.load(Ordering::Relaxed) {
+        match triggered.recv_timeout(Duration::from_secs(1)) {
+            Ok(()) => pending = true,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if pending || last_run.is_none_or(|at| at.elapsed() >= SYNC_INTERVAL) {
+            pending = false;
+            sync_online_devices(&app);
+            last_run = Some(Instant::now());
+        }
+    }
+}
+
+fn start(app: &AppHandle) -> Result<(), String> {
+    if app.state::<LanSync>().lock().runtime.is_some() {
+        return Ok(());
+    }
+    let identity = identity(app)?;
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+        .map_err(|error| format!("Failed to listen for devices: {}", error))?;
+    let port = listener
+        .local_addr()
+        .map_err(|error| format!("Failed to listen for devices: {}", error))?
+        .port();
+    let stop = Arc::new(AtomicBool::new(false));
+    let (trigger, triggered) = mpsc::channel();
+
+    let (handle, stopped) = (app.clone(), stop.clone());
+    thread::spawn(move || listen(handle, listener, stopped));
+
+    let advert = discovery::Advert {
+        device_id: identity.device_id.clone(),
+        name: identity.device_name.clone(),
+        fingerprint: plugins::key_fingerprint(&identity.public_key()),
+        port,
+    };
+    let (handle, stopped) = (app.clone(), stop.clone());
+    thread::spawn(move || {
+        let result = discovery::run(&advert, &stopped, |announcement, address| {
+            found(&handle, announcement, address)
+        });
+        if let Err(error) = result {
+            set_error(&handle, Some(error));
+        }
+    });
+
+    let (handle, stopped) = (app.clone(), stop.clone());
+    thread::spawn(move || run_worker(handle, triggered, stopped));
+
+    let state = app.state::<LanSync>();
+    let mut state = state.lock();
+    state.runtime = Some(Runtime { stop, trigger });
+    state.last_error = None;
+    Ok(())
+}
+
+fn stop(app: &AppHandle) {
+    let state = app.state::<LanSync>();
+    let mut state = state.lock();
+    if let Some(runtime) = state.runtime.take() {
+        runtime.stop.store(true, Ordering::Relaxed);
+    }
+    state.nearby.clear();
+}
+
+/// Starts sync at launch when the writer left it on.
+pub fn start_if_enabled(app: &AppHandle) {
+    match load_config(app) {
+        Ok(config) if config.enabled => {
+            if let Err(error) = start(app) {
+                set_error(app, Some(error));
+            }
+        }
+        Ok(_) => {}
+        Err(error) => tracing::warn!("Failed to read LAN sync settings: {}", error),
+    }
+}
+
+#[tauri::command]
+pub fn lan_sync_status(app: AppHandle) -> Result<SyncStatus, String> {
+    status(&app)
+}
+
+#[tauri::command]
+pub fn lan_sync_set_enabled(app: AppHandle, enabled: bool) -> Result<SyncStatus, String> {
+    update_config(&app, |config| config.enabled = enabled)?;
+    if enabled {
+        start(&app)?;
+    } else {
+        stop(&app);
+    }
+    emit_status(&app);
+    status(&app)
+}
+
+/// Renames this device as other devices see it.
+#[tauri::command]
+pub fn lan_sync_rename_device(app: AppHandle, name: String) -> Result<SyncStatus, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_DEVICE_NAME_CHARS {
+        return Err(format!(
+            "Device name must be 1 to {} characters",
+            MAX_DEVICE_NAME_CHARS
+        ));
+    }
+    update_config(&app, |config| config.device_name = name)?;
+    // Advertise the new name
+    if app.state::<LanSync>().lock().runtime.is_some() {
+        stop(&app);
+        start(&app)?;
+    }
+    emit_status(&app);
+    status(&app)
+}
+
+/// Starts pairing with a nearby device. Both devices then get a
+/// `lan-sync-pairing` event with the code to compare.
+#[tauri::command]
+pub fn lan_sync_pair(app: AppHandle, device_id: String) -> Result<(), String> {
+    let address = app
+        .state::<LanSync>()
+        .lock()
+        .nearby
+        .get(&device_id)
+        .map(|peer| peer.address)
+        .ok_or_else(|| "That device is no longer on the network".to_string())?;
+
+    thread::spawn(move || {
+        let result = connect_to(address, PAIRING_TIMEOUT + IO_TIMEOUT).and_then(|stream| {
+            let identity = identity(&app)?;
+            let channel = channel::connect(stream, &identity, Purpose::Pair, |_| Ok(()))?;
+            pair(&app, channel, true)
+        });
+        if let Err(error) = result {
+            set_error(&app, Some(format!("Failed to pair: {}", error)));
+        }
+    });
+    Ok(())
+}
+
+/// The writer's answer to a `lan-sync-pairing` event: whether the codes on
+/// both devices match.
+#[tauri::command]
+pub fn lan_sync_answer_pairing(
+    app: AppHandle,
+    pairing_id: String,
+    accepted: bool,
+) -> Result<(), String> {
+    let answer = app
+        .state::<LanSync>()
+        .lock()
+        .pairings
+        .remove(&pairing_id)
+        .ok_or_else(|| "The pairing request has expired".to_string())?;
+    answer
+        .send(accepted)
+        .map_err(|_| "The pairing request has expired".to_string())
+}
+
+#[tauri::command]
+pub fn lan_sync_unpair(app: AppHandle, device_id: String) -> Result<SyncStatus, String> {
+    update_config(&app, |config| {
+        config.paired.retain(|device| device.device_id != device_id)
+    })?;
+    emit_status(&app);
+    status(&app)
+}
+
+#[tauri::command]
+pub fn lan_sync_now(app: AppHandle) -> Result<(), String> {
+    if app.state::<LanSync>().lock().runtime.is_none() {
+        return Err("LAN sync is turned off".to_string());
+    }
+    request_sync(&app);
+    Ok(())
+}
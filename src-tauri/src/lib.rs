@@ -2,21 +2,84 @@
 #[macro_use]
 extern crate objc;
 
+use crate::error::CommandError;
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use tauri::menu::{MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder};
 use tauri::{Emitter, Manager, TitleBarStyle, WebviewUrl, WebviewWindowBuilder};
 #[cfg(desktop)]
 use tauri_plugin_window_state::{StateFlags, DEFAULT_FILENAME};
 
+mod analysis;
+mod app_backup;
+mod app_data;
+mod autocaps;
+mod breakdown;
+mod comments;
+mod compare;
+mod convert;
+mod crash_reports;
+mod dialogs;
+mod document;
+mod email;
+mod error;
+mod export_presets;
+mod feedback;
+mod file_drop;
+mod file_loader;
+mod file_manager;
+mod find_replace;
 mod fonts;
+mod goals;
+mod jobs;
+mod journal;
+#[cfg(desktop)]
+mod lan_sync;
+mod live_document;
+mod logging;
+mod menu_locale;
+mod migrations;
+#[cfg(mobile)]
+mod mobile_files;
+mod names;
+mod outline;
+mod pagination;
 mod pdf;
 mod plugins;
+mod print;
+mod proxy;
+mod recent_files;
+mod reports;
+mod restructure;
+mod revisions;
+mod save_target;
+mod scene_numbers;
+mod scratchpad;
+mod settings;
+#[cfg(desktop)]
+mod shortcuts;
+mod sidecar;
+mod sides;
+mod snippets;
+mod sprint;
+mod table_read;
+mod takes;
+mod tasks;
+mod thesaurus;
+mod title_page_presets;
+mod treatment;
+mod typography;
+mod versions;
+mod view_settings;
+mod writing_stats;
 
 #[derive(Default)]
 struct PendingOpenFiles {
     paths: Mutex<Vec<String>>,
+    /// Set when launched from the jump list's New Screenplay task.
+    new_screenplay: AtomicBool,
 }
 
 #[derive(Default)]
@@ -24,17 +87,20 @@ struct ExitControl {
     allow_exit: Mutex<bool>,
 }
 
+/// Fallback chrome colors used until the frontend reports its theme: the
+/// base surface of the default light and dark themes.
+const LIGHT_CHROME_COLOR: (u8, u8, u8) = (245, 241, 232);
+const DARK_CHROME_COLOR: (u8, u8, u8) = (29, 35, 42);
+
 #[cfg(target_os = "macos")]
-fn apply_macos_titlebar_theme(
+fn apply_macos_titlebar_background(
     window: &tauri::WebviewWindow,
     red: u8,
     green: u8,
     blue: u8,
-    dark: bool,
 ) -> Result<(), String> {
     use cocoa::appkit::{NSColor, NSWindow, NSWindowTitleVisibility};
     use cocoa::base::{id, nil};
-    use cocoa::foundation::NSString;
 
     let ns_window = window.ns_window().map_err(|e| e.to_string())? as id;
     unsafe {
@@ -46,22 +112,44 @@ fn apply_macos_titlebar_theme(
             1.0,
         );
         ns_window.setBackgroundColor_(bg_color);
-
-        let appearance_name = if dark {
-            "NSAppearanceNameDarkAqua"
-        } else {
-            "NSAppearanceNameAqua"
-        };
-        let appearance_name = NSString::alloc(nil).init_str(appearance_name);
-        let appearance: id = msg_send![class!(NSAppearance), appearanceNamed: appearance_name];
-        let () = msg_send![ns_window, setAppearance: appearance];
-
         ns_window.setTitleVisibility_(NSWindowTitleVisibility::NSWindowTitleVisible);
     }
 
     Ok(())
 }
 
+/// Colors the native window chrome. With `follow_system` the window keeps
+/// tracking the OS appearance (NSAppearance on macOS, the immersive dark
+/// title bar on Windows, the GTK theme on Linux); otherwise it is pinned to
+/// light or dark to match the in-app theme.
+fn apply_window_theme(
+    window: &tauri::WebviewWindow,
+    (red, green, blue): (u8, u8, u8),
+    dark: bool,
+    follow_system: bool,
+) -> Result<(), String> {
+    let theme = match (follow_system, dark) {
+        (true, _) => None,
+        (false, true) => Some(tauri::Theme::Dark),
+        (false, false) => Some(tauri::Theme::Light),
+    };
+    window
+        .set_theme(theme)
+        .map_err(|error| format!("Failed to set window theme: {}", error))?;
+
+    #[cfg(target_os = "macos")]
+    {
+        apply_macos_titlebar_background(window, red, green, blue)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        window
+            .set_background_color(Some(tauri::window::Color(red, green, blue, 255)))
+            .map_err(|error| format!("Failed to set window background: {}", error))
+    }
+}
+
 impl PendingOpenFiles {
     fn push_paths(&self, mut new_paths: Vec<String>) {
         if new_paths.is_empty() {
@@ -78,35 +166,87 @@ impl PendingOpenFiles {
     }
 }
 
+// On mobile, `path` may be a document picker URL; see `mobile_files`.
 #[tauri::command]
-fn save_screenplay(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, &content).map_err(|e| e.to_string())
+#[cfg_attr(desktop, allow(unused_variables))]
+fn save_screenplay(
+    app: tauri::AppHandle,
+    path: String,
+    content: String,
+) -> Result<(), CommandError> {
+    crash_reports::breadcrumb("file", "save");
+
+    #[cfg(mobile)]
+    if mobile_files::is_document_uri(&path) {
+        return mobile_files::write(&app, &path, content.as_bytes())
+            .map_err(|error| CommandError::write_failed(&path, error));
+    }
+
+    fs::write(&path, &content).map_err(|error| CommandError::io(&error, "save document", &path))
 }
 
 #[tauri::command]
-fn export_pdf(
+async fn export_pdf(
+    app: tauri::AppHandle,
     content_json: String,
     title_page_json: Option<String>,
     output_path: String,
     document_title: String,
     document_mode: String,
-) -> Result<(), String> {
-    pdf::generate_pdf(
-        &content_json,
-        title_page_json.as_deref(),
-        &output_path,
-        &document_title,
-        &document_mode,
-    )
-}
+    options: Option<pdf::PdfOptions>,
+) -> Result<(), CommandError> {
+    #[cfg(mobile)]
+    let handle = app.clone();
+    let path = output_path.clone();
+    jobs::run(&app, "export", "Exporting PDF", move |job| {
+        let options = options.unwrap_or_default();
+        job.progress(None, "Rendering PDF");
+
+        // Picker URLs can't be written by path, so render to a temporary file
+        // and copy it through.
+        #[cfg(mobile)]
+        if mobile_files::is_document_uri(&output_path) {
+            let temp_path = std::env::temp_dir().join(format!(
+                "grainery-export-{}.pdf",
+                chrono::Utc::now().timestamp_millis()
+            ));
+            pdf::generate_pdf_with_options(
+                &content_json,
+                title_page_json.as_deref(),
+                &temp_path.to_string_lossy(),
+                &document_title,
+                &document_mode,
+                &options,
+            )?;
+            let result = job
+                .check_cancelled()
+                .and_then(|_| fs::read(&temp_path).map_err(|e| e.to_string()))
+                .and_then(|bytes| mobile_files::write(&handle, &output_path, &bytes));
+            let _ = fs::remove_file(&temp_path);
+            return result;
+        }
 
-#[tauri::command]
-fn load_screenplay(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| e.to_string())
+        pdf::generate_pdf_with_options(
+            &content_json,
+            title_page_json.as_deref(),
+            &output_path,
+            &document_title,
+            &document_mode,
+            &options,
+        )
+    })
+    .await
+    .map_err(|error| CommandError::write_failed(&path, error))
 }
 
 #[tauri::command]
-fn file_exists(path: String) -> bool {
+#[cfg_attr(desktop, allow(unused_variables))]
+fn file_exists(app: tauri::AppHandle, path: String) -> bool {
+    #[cfg(mobile)]
+    if mobile_files::is_document_uri(&path) {
+        return mobile_files::exists(&app, &path);
+    }
+
     Path::new(&path).exists()
 }
 
@@ -127,11 +267,18 @@ fn consume_pending_open_files(state: tauri::State<'_, PendingOpenFiles>) -> Vec<
     state.take_paths()
 }
 
+#[tauri::command]
+fn consume_new_screenplay_request(state: tauri::State<'_, PendingOpenFiles>) -> bool {
+    state.new_screenplay.swap(false, Ordering::SeqCst)
+}
+
 #[tauri::command]
 fn exit_app(app: tauri::AppHandle, state: tauri::State<'_, ExitControl>) {
     if let Ok(mut allow_exit) = state.allow_exit.lock() {
         *allow_exit = true;
     }
+    // Quitting follows a save or a discard, so nothing is left to recover.
+    let _ = app.state::<journal::SaveJournal>().close();
     app.exit(0);
 }
 
@@ -142,20 +289,17 @@ fn set_titlebar_theme_color(
     green: u8,
     blue: u8,
     dark: bool,
+    follow_system: Option<bool>,
 ) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        let window = app
-            .get_webview_window("main")
-            .ok_or_else(|| "main window not found".to_string())?;
-        apply_macos_titlebar_theme(&window, red, green, blue, dark)
-    }
-
-    #[cfg(not(target_os = "macos"))]
-    {
-        let _ = (app, red, green, blue, dark);
-        Ok(())
-    }
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "main window not found".to_string())?;
+    apply_window_theme(
+        &window,
+        (red, green, blue),
+        dark,
+        follow_system.unwrap_or(false),
+    )
 }
 
 #[cfg(desktop)]
@@ -177,13 +321,37 @@ fn has_saved_window_state(app: &tauri::AppHandle) -> bool {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let Some(code) = plugins::run_pack_cli(&args).or_else(|| convert::run_cli(&args)) {
+        std::process::exit(code);
+    }
+
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(PendingOpenFiles::default())
         .manage(ExitControl::default())
+        .manage(sprint::SprintTimer::default())
+        .manage(plugins::PermissionBroker::default())
+        .manage(plugins::NotificationLimiter::default())
+        .manage(plugins::TaskScheduler::default())
+        .manage(plugins::EventBus::default())
+        .manage(plugins::PluginMetrics::default())
+        .manage(scratchpad::ActiveScratchpad::default())
+        .manage(live_document::LiveDocument::default())
+        .manage(jobs::JobManager::default())
+        .manage(journal::SaveJournal::default())
+        .manage(autocaps::AutocapRulesCache::default())
         .setup(|app| {
+            logging::init(app.handle());
+            crash_reports::install(app.handle());
+            app.manage(migrations::run(app.handle()));
+            app.manage(settings::SettingsStore::load(app.handle()));
+            print::remove_stale_files();
+
             #[cfg(desktop)]
             app.handle().plugin(
                 tauri_plugin_window_state::Builder::default()
@@ -193,43 +361,81 @@ pub fn run() {
                     .build(),
             )?;
 
+            // Global shortcuts only exist on desktop; mobile has no OS-wide hotkeys.
+            #[cfg(desktop)]
+            {
+                app.manage(shortcuts::ShortcutRegistry::default());
+                app.handle().plugin(
+                    tauri_plugin_global_shortcut::Builder::new()
+                        .with_handler(|app, shortcut, event| {
+                            shortcuts::handle_shortcut(app, shortcut, event.state())
+                        })
+                        .build(),
+                )?;
+            }
+
+            // LAN sync pairs desktops and laptops; mobile files are picker URIs
+            // it couldn't write merged copies back to.
+            #[cfg(desktop)]
+            {
+                app.manage(lan_sync::LanSync::default());
+                lan_sync::start_if_enabled(app.handle());
+            }
+
+            // Reads and writes `content://` document URIs; see `mobile_files`.
+            #[cfg(target_os = "android")]
+            app.handle().plugin(tauri_plugin_fs::init())?;
+
+            let labels = menu_locale::load_strings(app.handle());
+
             // File menu items
-            let new_item = MenuItemBuilder::with_id("new", "New Screenplay")
+            let new_item = MenuItemBuilder::with_id("new", labels.get("new"))
                 .accelerator("CmdOrCtrl+N")
                 .build(app)?;
-            let new_comic_item = MenuItemBuilder::with_id("new_comic", "New Comic").build(app)?;
+            let new_comic_item =
+                MenuItemBuilder::with_id("new_comic", labels.get("new_comic")).build(app)?;
             let new_freewrite_item =
-                MenuItemBuilder::with_id("new_freewrite", "New Free Write").build(app)?;
-            let open_item = MenuItemBuilder::with_id("open", "Open...")
+                MenuItemBuilder::with_id("new_freewrite", labels.get("new_freewrite"))
+                    .build(app)?;
+            let open_item = MenuItemBuilder::with_id("open", labels.get("open"))
                 .accelerator("CmdOrCtrl+O")
                 .build(app)?;
             let import_fdx_item =
-                MenuItemBuilder::with_id("import_fdx", "Import Final Draft...").build(app)?;
+                MenuItemBuilder::with_id("import_fdx", labels.get("import_fdx")).build(app)?;
             let start_screen_item =
-                MenuItemBuilder::with_id("start_screen", "Start Screen").build(app)?;
-            let save_item = MenuItemBuilder::with_id("save", "Save")
+                MenuItemBuilder::with_id("start_screen", labels.get("start_screen")).build(app)?;
+            let quick_capture_item =
+                MenuItemBuilder::with_id("quick_capture", labels.get("quick_capture"))
+                    .build(app)?;
+            let save_item = MenuItemBuilder::with_id("save", labels.get("save"))
                 .accelerator("CmdOrCtrl+S")
                 .build(app)?;
-            let save_as_item = MenuItemBuilder::with_id("save_as", "Save As...")
+            let save_as_item = MenuItemBuilder::with_id("save_as", labels.get("save_as"))
                 .accelerator("CmdOrCtrl+Shift+S")
                 .build(app)?;
             let export_fountain_item =
-                MenuItemBuilder::with_id("export_fountain", "Export as Fountain...")
+                MenuItemBuilder::with_id("export_fountain", labels.get("export_fountain"))
                     .accelerator("CmdOrCtrl+Shift+E")
                     .build(app)?;
-            let export_pdf_item = MenuItemBuilder::with_id("export_pdf", "Export as PDF...")
+            let export_pdf_item = MenuItemBuilder::with_id("export_pdf", labels.get("export_pdf"))
                 .accelerator("CmdOrCtrl+Shift+P")
                 .build(app)?;
             let export_fdx_item =
-                MenuItemBuilder::with_id("export_fdx", "Export to Final Draft...").build(app)?;
+                MenuItemBuilder::with_id("export_fdx", labels.get("export_fdx")).build(app)?;
+            let print_item = MenuItemBuilder::with_id("print", labels.get("print"))
+                .accelerator("CmdOrCtrl+P")
+                .build(app)?;
+            let [reveal_document_item, open_document_item, reveal_export_item, open_export_item] =
+                file_manager::build_menu_items(app, &labels)?;
 
-            let file_menu = SubmenuBuilder::new(app, "File")
+            let file_menu = SubmenuBuilder::new(app, labels.get("file_menu"))
                 .item(&new_item)
                 .item(&new_comic_item)
                 .item(&new_freewrite_item)
                 .item(&open_item)
                 .item(&import_fdx_item)
                 .item(&start_screen_item)
+                .item(&quick_capture_item)
                 .separator()
                 .item(&save_item)
                 .item(&save_as_item)
@@ -237,79 +443,139 @@ pub fn run() {
                 .item(&export_fountain_item)
                 .item(&export_pdf_item)
                 .item(&export_fdx_item)
+                .separator()
+                .item(&reveal_document_item)
+                .item(&open_document_item)
+                .item(&reveal_export_item)
+                .item(&open_export_item)
+                .separator()
+                .item(&print_item)
                 .build()?;
 
-            let find_item = MenuItemBuilder::with_id("find", "Find...")
+            let find_item = MenuItemBuilder::with_id("find", labels.get("find"))
                 .accelerator("CmdOrCtrl+F")
                 .build(app)?;
-            let find_next_item = MenuItemBuilder::with_id("find_next", "Find Next")
+            let find_next_item = MenuItemBuilder::with_id("find_next", labels.get("find_next"))
                 .accelerator("CmdOrCtrl+G")
                 .build(app)?;
-            let find_prev_item = MenuItemBuilder::with_id("find_prev", "Find Previous")
+            let find_prev_item = MenuItemBuilder::with_id("find_prev", labels.get("find_prev"))
                 .accelerator("CmdOrCtrl+Shift+G")
                 .build(app)?;
-            let replace_item = MenuItemBuilder::with_id("replace", "Replace...")
+            let replace_item = MenuItemBuilder::with_id("replace", labels.get("replace"))
                 .accelerator("CmdOrCtrl+H")
                 .build(app)?;
 
             // Edit menu with standard items
-            let edit_menu = SubmenuBuilder::new(app, "Edit")
-                .undo()
-                .redo()
+            let edit_menu = SubmenuBuilder::new(app, labels.get("edit_menu"))
+                .item(&PredefinedMenuItem::undo(
+                    app,
+                    Some(labels.get("undo").as_str()),
+                )?)
+                .item(&PredefinedMenuItem::redo(
+                    app,
+                    Some(labels.get("redo").as_str()),
+                )?)
                 .separator()
                 .item(&find_item)
                 .item(&find_next_item)
                 .item(&find_prev_item)
                 .item(&replace_item)
                 .separator()
-                .cut()
-                .copy()
-                .paste()
-                .select_all()
+                .item(&PredefinedMenuItem::cut(
+                    app,
+                    Some(labels.get("cut").as_str()),
+                )?)
+                .item(&PredefinedMenuItem::copy(
+                    app,
+                    Some(labels.get("copy").as_str()),
+                )?)
+                .item(&PredefinedMenuItem::paste(
+                    app,
+                    Some(labels.get("paste").as_str()),
+                )?)
+                .item(&PredefinedMenuItem::select_all(
+                    app,
+                    Some(labels.get("select_all").as_str()),
+                )?)
                 .build()?;
 
             // Grainery (app) menu with Settings
-            let settings_item = MenuItemBuilder::with_id("settings", "Settings...")
+            let settings_item = MenuItemBuilder::with_id("settings", labels.get("settings"))
                 .accelerator("CmdOrCtrl+,")
                 .build(app)?;
             let check_updates_item =
-                MenuItemBuilder::with_id("check_updates", "Check for Updates...").build(app)?;
-            let quit_item = MenuItemBuilder::with_id("quit", "Quit Grainery")
+                MenuItemBuilder::with_id("check_updates", labels.get("check_updates"))
+                    .build(app)?;
+            let quit_item = MenuItemBuilder::with_id("quit", labels.get("quit"))
                 .accelerator("CmdOrCtrl+Q")
                 .build(app)?;
 
-            let app_menu = SubmenuBuilder::new(app, "Grainery")
-                .about(None)
+            let app_menu = SubmenuBuilder::new(app, labels.get("app_menu"))
+                .item(&PredefinedMenuItem::about(
+                    app,
+                    Some(labels.get("about").as_str()),
+                    None,
+                )?)
                 .separator()
                 .item(&settings_item)
                 .item(&check_updates_item)
                 .separator()
-                .services()
+                .item(&PredefinedMenuItem::services(
+                    app,
+                    Some(labels.get("services").as_str()),
+                )?)
                 .separator()
-                .hide()
-                .hide_others()
-                .show_all()
+                .item(&PredefinedMenuItem::hide(
+                    app,
+                    Some(labels.get("hide").as_str()),
+                )?)
+                .item(&PredefinedMenuItem::hide_others(
+                    app,
+                    Some(labels.get("hide_others").as_str()),
+                )?)
+                .item(&PredefinedMenuItem::show_all(
+                    app,
+                    Some(labels.get("show_all").as_str()),
+                )?)
                 .separator()
                 .item(&quit_item)
                 .build()?;
 
+            let view_menu = view_settings::build_menu(app, &labels)?;
+
             // Window menu
-            let window_menu = SubmenuBuilder::new(app, "Window")
-                .minimize()
-                .item(&PredefinedMenuItem::maximize(app, None)?)
+            let window_menu = SubmenuBuilder::new(app, labels.get("window_menu"))
+                .item(&PredefinedMenuItem::minimize(
+                    app,
+                    Some(labels.get("minimize").as_str()),
+                )?)
+                .item(&PredefinedMenuItem::maximize(
+                    app,
+                    Some(labels.get("maximize").as_str()),
+                )?)
                 .separator()
-                .close_window()
+                .item(&PredefinedMenuItem::close_window(
+                    app,
+                    Some(labels.get("close_window").as_str()),
+                )?)
                 .build()?;
 
             let menu = MenuBuilder::new(app)
-                .items(&[&app_menu, &file_menu, &edit_menu, &window_menu])
+                .items(&[&app_menu, &file_menu, &edit_menu, &view_menu, &window_menu])
                 .build()?;
 
             app.set_menu(menu)?;
+            #[cfg(desktop)]
+            shortcuts::refresh(app.handle());
 
             // Handle menu events
             app.on_menu_event(move |app_handle, event| {
                 let event_id = event.id().0.as_str();
+                if view_settings::handle_menu_event(app_handle, event_id)
+                    || file_manager::handle_menu_event(app_handle, event_id)
+                {
+                    return;
+                }
                 // Emit event to frontend
                 if let Some(window) = app_handle.get_webview_window("main") {
                     let _ = window.emit("menu-event", event_id);
@@ -338,6 +604,7 @@ pub fn run() {
             let win_builder = win_builder.title_bar_style(TitleBarStyle::Transparent);
 
             let window = win_builder.build().unwrap();
+            file_drop::install(&window);
 
             #[cfg(desktop)]
             {
@@ -355,35 +622,246 @@ pub fn run() {
                 let pending = app.state::<PendingOpenFiles>();
                 pending.push_paths(startup_paths.clone());
             }
-
-            // set background color and title color only when building for macOS
-            #[cfg(target_os = "macos")]
-            {
-                let _ = apply_macos_titlebar_theme(&window, 245, 241, 232, false);
+            if std::env::args().any(|arg| arg == recent_files::NEW_SCREENPLAY_ARG) {
+                let pending = app.state::<PendingOpenFiles>();
+                pending.new_screenplay.store(true, Ordering::SeqCst);
             }
 
+            plugins::TaskScheduler::start(app.handle().clone());
+            journal::SaveJournal::start(app.handle().clone());
+            recent_files::init(app.handle());
+            view_settings::restore_zoom(app.handle());
+
+            // Match the OS appearance until the frontend applies the user's theme.
+            let dark = window
+                .theme()
+                .is_ok_and(|theme| theme == tauri::Theme::Dark);
+            let chrome_color = if dark {
+                DARK_CHROME_COLOR
+            } else {
+                LIGHT_CHROME_COLOR
+            };
+            let _ = apply_window_theme(&window, chrome_color, dark, true);
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             save_screenplay,
-            load_screenplay,
+            save_target::check_save_target,
+            file_loader::load_screenplay,
             file_exists,
             get_update_target,
             consume_pending_open_files,
+            consume_new_screenplay_request,
+            recent_files::recent_files_get,
+            recent_files::recent_files_set,
             exit_app,
             set_titlebar_theme_color,
             export_pdf,
+            convert::export_batch,
+            convert::copy_as_fountain,
+            convert::copy_as_formatted_text,
+            convert::classify_clipboard_text,
+            pagination::paginate_document,
+            jobs::jobs_list,
+            jobs::jobs_cancel,
+            logging::logs_recent,
+            logging::logs_open_folder,
+            migrations::migrations_status,
+            app_backup::backup_app_data,
+            app_backup::restore_app_data,
+            crash_reports::crash_reports_pending,
+            crash_reports::crash_reports_send,
+            crash_reports::crash_reports_dismiss,
+            export_presets::export_presets_list,
+            export_presets::export_presets_save,
+            export_presets::export_presets_delete,
+            export_presets::export_presets_apply,
+            title_page_presets::title_page_presets_list,
+            title_page_presets::title_page_presets_save,
+            title_page_presets::title_page_presets_delete,
+            title_page_presets::title_page_presets_set_default,
+            title_page_presets::title_page_presets_apply,
+            snippets::snippets_list,
+            snippets::snippets_save,
+            snippets::snippets_delete,
+            snippets::snippets_expand,
+            snippets::snippets_export,
+            snippets::snippets_import,
+            print::print_screenplay,
+            email::share_via_email,
+            dialogs::dialog_open_path,
+            dialogs::dialog_save_path,
+            file_manager::reveal_in_file_manager,
+            file_manager::open_with_default_app,
+            file_manager::file_targets_set_document,
+            file_manager::file_targets_set_export,
+            scratchpad::quick_capture_open,
+            scratchpad::quick_capture_close,
+            scratchpad::scratchpad_set_project,
+            scratchpad::scratchpad_get_project,
+            scratchpad::scratchpad_append,
+            scratchpad::scratchpad_read,
+            view_settings::view_settings_get,
+            view_settings::view_settings_update,
+            menu_locale::menu_locale_get,
+            menu_locale::menu_locale_set,
+            settings::settings_get,
+            settings::settings_set,
+            settings::settings_subscribe,
+            settings::settings_unsubscribe,
+            settings::settings_import_legacy,
+            find_replace::find_replace,
+            live_document::live_document_load,
+            live_document::live_document_apply,
+            journal::journal_open,
+            journal::journal_append,
+            journal::journal_compact,
+            journal::journal_recover,
+            journal::journal_close,
+            versions::versions_record,
+            versions::versions_list,
+            versions::versions_load,
+            versions::versions_head,
+            #[cfg(desktop)]
+            lan_sync::lan_sync_status,
+            #[cfg(desktop)]
+            lan_sync::lan_sync_set_enabled,
+            #[cfg(desktop)]
+            lan_sync::lan_sync_rename_device,
+            #[cfg(desktop)]
+            lan_sync::lan_sync_pair,
+            #[cfg(desktop)]
+            lan_sync::lan_sync_answer_pairing,
+            #[cfg(desktop)]
+            lan_sync::lan_sync_unpair,
+            #[cfg(desktop)]
+            lan_sync::lan_sync_now,
+            live_document::get_character_suggestions,
+            live_document::get_location_suggestions,
+            live_document::get_time_suggestions,
+            live_document::get_scene_list,
+            live_document::get_pagination,
+            analysis::analyze_characters,
+            analysis::scene_report,
+            analysis::production_report,
+            analysis::analyze_runtime,
+            analysis::analyze_repetition,
+            analysis::check_continuity,
+            analysis::export_analysis_json,
+            breakdown::breakdown_list_tags,
+            breakdown::breakdown_add_tag,
+            breakdown::breakdown_update_tag,
+            breakdown::breakdown_delete_tag,
+            breakdown::breakdown_report,
+            outline::outline_list_cards,
+            outline::outline_add_card,
+            outline::outline_update_card,
+            outline::outline_delete_card,
+            outline::outline_reorder_cards,
+            outline::outline_generate_scenes,
+            restructure::restructure_move_scenes,
+            restructure::restructure_split_scene,
+            restructure::restructure_merge_scenes,
+            treatment::treatment_export,
+            sides::export_sides,
+            table_read::table_read_voices,
+            table_read::table_read_set_voices,
+            table_read::export_table_read,
+            takes::takes_list,
+            takes::takes_add,
+            takes::takes_promote,
+            takes::takes_delete,
+            comments::comments_list,
+            comments::comments_add,
+            comments::comments_reply,
+            comments::comments_update,
+            comments::comments_move,
+            comments::comments_set_resolved,
+            comments::comments_delete,
+            tasks::get_tasks,
+            tasks::tasks_set_done,
+            feedback::export_feedback_bundle,
+            feedback::import_feedback,
+            compare::compare_documents,
+            compare::diff_against_disk,
+            autocaps::autocaps_get_rules,
+            autocaps::autocaps_set_rules,
+            autocaps::autocaps_suggest,
+            autocaps::autocaps_cleanup,
+            revisions::revision_start,
+            revisions::revision_end,
+            revisions::revision_record_changes,
+            revisions::revision_summary,
+            scene_numbers::scene_numbers_assign,
+            scene_numbers::scene_numbers_renumber,
+            scene_numbers::scene_numbers_lock,
             plugins::plugin_list_installed,
+            plugins::plugin_list_themes,
+            plugins::plugin_read_theme,
+            plugins::plugin_list_dictionaries,
+            plugins::plugin_read_dictionary,
             plugins::plugin_get_lock_records,
             plugins::plugin_install_from_file,
+            plugins::plugin_list_trusted_keys,
+            plugins::plugin_preview_trusted_key,
+            plugins::plugin_add_trusted_key,
+            plugins::plugin_remove_trusted_key,
             plugins::plugin_install_from_registry,
             plugins::plugin_uninstall,
+            plugins::plugin_set_update_channel,
             plugins::plugin_enable_disable,
             plugins::plugin_update_permissions,
+            plugins::plugin_record_activation,
+            plugins::plugin_performance_report,
             plugins::plugin_record_diagnostic,
             plugins::plugin_clear_diagnostics,
+            plugins::plugin_report_crash,
             plugins::plugin_fetch_registry_index,
-            plugins::plugin_host_call
+            plugins::plugin_list_registries,
+            plugins::plugin_save_registry,
+            plugins::plugin_remove_registry,
+            plugins::plugin_search_registries,
+            plugins::plugin_search_registry,
+            plugins::plugin_check_updates,
+            plugins::plugin_check_revocations,
+            plugins::plugin_update,
+            plugins::plugin_host_call,
+            #[cfg(desktop)]
+            shortcuts::shortcuts_apply,
+            #[cfg(desktop)]
+            shortcuts::shortcuts_set,
+            #[cfg(desktop)]
+            shortcuts::shortcuts_reset,
+            proxy::proxy_get_settings,
+            proxy::proxy_set_settings,
+            plugins::plugin_respond_permission_request,
+            plugins::plugin_query_audit_log,
+            plugins::plugin_disk_usage,
+            plugins::plugin_set_disk_quota,
+            plugins::plugin_set_download_timeout,
+            plugins::plugin_clear_data,
+            plugins::plugin_list_scheduled_tasks,
+            plugins::plugin_cancel_scheduled_task,
+            plugins::plugin_process_start,
+            plugins::plugin_process_restart,
+            plugins::plugin_export_profile,
+            plugins::plugin_import_profile,
+            plugins::plugin_pack,
+            thesaurus::thesaurus_lookup,
+            names::names_options,
+            names::generate_names,
+            names::check_character_name,
+            typography::apply_smart_typography,
+            writing_stats::stats_record_session,
+            writing_stats::stats_history,
+            writing_stats::stats_summary,
+            goals::goals_get,
+            goals::goals_set,
+            goals::goals_progress,
+            sprint::sprint_start,
+            sprint::sprint_cancel,
+            sprint::sprint_complete
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
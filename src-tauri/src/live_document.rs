@@ -0,0 +1,652 @@
+use crate::document::slugline::{self, Slugline};
+use crate::pagination::{IncrementalPagination, Pagination, PaperSize};
+use crate::{document, sidecar};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+const DEFAULT_SUGGESTION_LIMIT: usize = 10;
+
+/// Replaces `delete_count` top-level blocks starting at `from` with `insert`.
+/// The editor sends one of these per transaction, covering the span of
+/// blocks between its unchanged prefix and suffix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEdit {
+    pub from: usize,
+    pub delete_count: usize,
+    #[serde(default)]
+    pub insert: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterSuggestion {
+    pub name: String,
+    /// Number of cues for this character in the script.
+    pub speak_count: usize,
+    /// Block index of the character's last cue.
+    pub last_block: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationSuggestion {
+    pub location: String,
+    /// Time of day the location was used with; empty when sluglines omit it.
+    pub time: String,
+    /// Number of scene headings using this location and time.
+    pub use_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSuggestion {
+    pub time: String,
+    pub use_count: usize,
+}
+
+/// One row of the scene navigator.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneListEntry {
+    /// `attrs.sceneId` of the heading, when the scene has one.
+    pub scene_id: Option<String>,
+    pub scene_number: Option<String>,
+    pub slugline: String,
+    /// Block index of the scene heading.
+    pub block_index: usize,
+    /// Characters with a cue in the scene, in order of first appearance.
+    pub characters: Vec<String>,
+    /// 1-based page the scene starts on, on Letter paper.
+    pub page: u32,
+    /// Length in eighths of a page, on Letter paper.
+    pub eighths: u32,
+    /// Color of the outline card linked to the scene.
+    pub color: Option<String>,
+}
+
+struct CharacterEntry {
+    speak_count: usize,
+    last_block: usize,
+}
+
+/// Character cue names with speak counts, kept current block by block so
+/// lookups never rescan the script.
+#[derive(Default)]
+struct CharacterIndex {
+    /// Cue name for each block; `None` for anything that is not a cue.
+    cues: Vec<Option<String>>,
+    entries: HashMap<String, CharacterEntry>,
+}
+
+fn cue_name(block: &Value) -> Option<String> {
+    if document::node_type(block) != "character" {
+        return None;
+    }
+
+    let name = document::character_cue_name(&document::node_text(block));
+    (!name.is_empty()).then_some(name)
+}
+
+/// Locations and times of day from the script's scene headings, counted so
+/// suggestions can favor the variants used most.
+#[derive(Default)]
+struct SluglineIndex {
+    /// Parsed heading for each block; `None` for anything else.
+    headings: Vec<Option<Slugline>>,
+    /// Use counts keyed by `(location, time)`.
+    locations: HashMap<(String, String), usize>,
+    times: HashMap<String, usize>,
+}
+
+fn heading_slugline(block: &Value) -> Option<Slugline> {
+    if document::node_type(block) != "sceneHeading" {
+        return None;
+    }
+
+    let slugline = slugline::parse(&document::node_text(block));
+    (!slugline.location.is_empty()).then_some(slugline)
+}
+
+fn decrement<K: Eq + std::hash::Hash>(counts: &mut HashMap<K, usize>, key: K) {
+    if let Some(count) = counts.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
+}
+
+impl SluglineIndex {
+    fn splice(&mut self, from: usize, delete_count: usize, inserted: &[Value]) {
+        let added = inserted.iter().map(heading_slugline).collect::<Vec<_>>();
+        for heading in added.iter().flatten() {
+            *self
+                .locations
+                .entry((heading.location.clone(), heading.time.clone()))
+                .or_default() += 1;
+            if !heading.time.is_empty() {
+                *self.times.entry(heading.time.clone()).or_default() += 1;
+            }
+        }
+
+        let removed = self
+            .headings
+            .splice(from..from + delete_count, added)
+            .collect::<Vec<_>>();
+        for heading in removed.into_iter().flatten() {
+            decrement(&mut self.times, heading.time.clone());
+            decrement(&mut self.locations, (heading.location, heading.time));
+        }
+    }
+
+    /// Location and time pairs matching a partly typed heading such as
+    /// `INT. KI` or `INT. KITCHEN - N`.
+    fn location_suggestions(&self, prefix: &str, limit: usize) -> Vec<LocationSuggestion> {
+        let query = slugline::parse(prefix);
+        let location = query
+            .location
+            .trim_end_matches(['-', '\u{2013}', '\u{2014}'])
+            .trim_end();
+        let mut suggestions = self
+            .locations
+            .iter()
+            .filter(|((candidate, time), _)| {
+                candidate.starts_with(location) && time.starts_with(&query.time)
+            })
+            .map(|((location, time), use_count)| LocationSuggestion {
+                location: location.clone(),
+                time: time.clone(),
+                use_count: *use_count,
+            })
+            .collect::<Vec<_>>();
+
+        suggestions.sort_by(|a, b| {
+            b.use_count
+                .cmp(&a.use_count)
+                .then(a.location.cmp(&b.location))
+                .then(a.time.cmp(&b.time))
+        });
+        suggestions.truncate(limit);
+        suggestions
+    }
+
+    fn time_suggestions(&self, prefix: &str, limit: usize) -> Vec<TimeSuggestion> {
+        let prefix = prefix.trim().to_uppercase();
+        let mut suggestions = self
+            .times
+            .iter()
+            .filter(|(time, _)| time.starts_with(&prefix))
+            .map(|(time, use_count)| TimeSuggestion {
+                time: time.clone(),
+                use_count: *use_count,
+            })
+            .collect::<Vec<_>>();
+
+        suggestions.sort_by(|a, b| b.use_count.cmp(&a.use_count).then(a.time.cmp(&b.time)));
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+impl CharacterIndex {
+    fn splice(&mut self, from: usize, delete_count: usize, inserted: &[Value]) {
+        let removed_end = from + delete_count;
+        let removed = self
+            .cues
+            .splice(from..removed_end, inserted.iter().map(cue_name))
+            .collect::<Vec<_>>();
+
+        // Names whose last cue was inside the removed span need a rescan.
+        let mut stale = HashSet::new();
+        for name in removed.into_iter().flatten() {
+            if let Some(entry) = self.entries.get_mut(&name) {
+                entry.speak_count = entry.speak_count.saturating_sub(1);
+            }
+            stale.insert(name);
+        }
+
+        for (name, entry) in self.entries.iter_mut() {
+            if entry.last_block >= removed_end {
+                entry.last_block = entry.last_block + inserted.len() - delete_count;
+            } else if entry.last_block >= from {
+                stale.insert(name.clone());
+            }
+        }
+
+        for (offset, name) in self.cues[from..from + inserted.len()].iter().enumerate() {
+            let Some(name) = name else {
+                continue;
+            };
+            let entry = self.entries.entry(name.clone()).or_insert(CharacterEntry {
+                speak_count: 0,
+                last_block: 0,
+            });
+            entry.speak_count += 1;
+            entry.last_block = entry.last_block.max(from + offset);
+        }
+
+        for name in stale {
+            let last_block = self
+                .cues
+                .iter()
+                .rposition(|cue| cue.as_deref() == Some(name.as_str()));
+            match (self.entries.get_mut(&name), last_block) {
+                (Some(entry), Some(last_block)) if entry.speak_count > 0 => {
+                    entry.last_block = last_block;
+                }
+                _ => {
+                    self.entries.remove(&name);
+                }
+            }
+        }
+    }
+
+    /// Names starting with `prefix`, most-spoken first; ties go to the
+    /// character who spoke most recently in the script.
+    fn suggestions(&self, prefix: &str, limit: usize) -> Vec<CharacterSuggestion> {
+        let prefix = document::character_cue_name(prefix);
+        let mut suggestions = self
+            .entries
+            .iter()
+            .filter(|(name, _)| name.starts_with(&prefix))
+            .map(|(name, entry)| CharacterSuggestion {
+                name: name.clone(),
+                speak_count: entry.speak_count,
+                last_block: entry.last_block,
+            })
+            .collect::<Vec<_>>();
+
+        suggestions.sort_by(|a, b| {
+            b.speak_count
+                .cmp(&a.speak_count)
+                .then(b.last_block.cmp(&a.last_block))
+                .then(a.name.cmp(&b.name))
+        });
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+#[derive(Default)]
+struct LiveState {
+    blocks: Vec<Value>,
+    characters: CharacterIndex,
+    sluglines: SluglineIndex,
+    /// Page layout for the paper size last asked for, repaginated from the
+    /// edited block on each edit.
+    pagination: Option<IncrementalPagination>,
+    /// Scene list as of the last edit, rebuilt on the next request.
+    scenes: Option<Vec<SceneListEntry>>,
+}
+
+impl LiveState {
+    fn apply(&mut self, edit: BlockEdit) -> Result<(), String> {
+        let end = edit.from + edit.delete_count;
+        if end > self.blocks.len() {
+            return Err(format!(
+                "Edit covers blocks {}..{} but the document has {}",
+                edit.from,
+                end,
+                self.blocks.len()
+            ));
+        }
+
+        self.characters
+            .splice(edit.from, edit.delete_count, &edit.insert);
+        if let Some(pagination) = self.pagination.as_mut() {
+            pagination.splice(edit.from, edit.delete_count, &edit.insert);
+        }
+        self.sluglines
+            .splice(edit.from, edit.delete_count, &edit.insert);
+        self.blocks.splice(edit.from..end, edit.insert);
+        self.scenes = None;
+        Ok(())
+    }
+
+    fn pagination(&mut self, paper_size: PaperSize) -> &Pagination {
+        if self
+            .pagination
+            .as_ref()
+            .is_some_and(|pagination| pagination.paper_size() != paper_size)
+        {
+            self.pagination = None;
+        }
+        let blocks = &self.blocks;
+        self.pagination
+            .get_or_insert_with(|| IncrementalPagination::new(blocks, paper_size))
+            .pagination()
+    }
+
+    fn scene_list(&mut self) -> &[SceneListEntry] {
+        if self.scenes.is_none() {
+            let spans = document::block_scenes(&self.blocks);
+            let pagination = self.pagination(PaperSize::Letter);
+            let pages = pagination
+                .blocks
+                .iter()
+                .map(|block| block.page)
+                .collect::<Vec<_>>();
+            let eighths = pagination.scene_eighths(&spans);
+            let cues = &self.characters.cues;
+            let scenes = spans
+                .into_iter()
+                .zip(eighths)
+                .map(|(scene, eighths)| {
+                    let mut characters: Vec<String> = Vec::new();
+                    for name in cues[scene.heading_block..scene.end_block].iter().flatten() {
+                        if !characters.contains(name) {
+                            characters.push(name.clone());
+                        }
+                    }
+
+                    SceneListEntry {
+                        scene_id: scene.scene_id,
+                        scene_number: scene.scene_number,
+                        slugline: scene.heading,
+                        block_index: scene.heading_block,
+                        characters,
+                        page: pages.get(scene.heading_block).copied().unwrap_or(1),
+                        eighths,
+                        color: None,
+                    }
+                })
+                .collect();
+            self.scenes = Some(scenes);
+        }
+        self.scenes.as_deref().unwrap_or_default()
+    }
+}
+
+/// Backend mirror of the open document's top-level blocks. The editor loads
+/// it once and then streams block edits, and indexes built here answer
+/// lookups without the frontend serializing the whole script.
+#[derive(Default)]
+pub struct LiveDocument {
+    state: Mutex<LiveState>,
+}
+
+impl LiveDocument {
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, LiveState>, String> {
+        self.state
+            .lock()
+            .map_err(|_| "Live document is unavailable".to_string())
+    }
+
+    /// Names of every character with a cue in the open script.
+    pub fn character_names(&self) -> Result<Vec<String>, String> {
+        let state = self.lock()?;
+        Ok(state.characters.entries.keys().cloned().collect())
+    }
+}
+
+#[tauri::command]
+pub fn live_document_load(
+    live: State<'_, LiveDocument>,
+    content_json: String,
+) -> Result<(), String> {
+    let root = document::parse_json(&content_json)?;
+    let mut state = LiveState::default();
+    state.apply(BlockEdit {
+        from: 0,
+        delete_count: 0,
+        insert: document::blocks(&root).to_vec(),
+    })?;
+    *live.lock()? = state;
+    Ok(())
+}
+
+/// Applies edits in order. On error the mirror is out of step with the
+/// editor, which should reload it with `live_document_load`.
+#[tauri::command]
+pub fn live_document_apply(
+    live: State<'_, LiveDocument>,
+    edits: Vec<BlockEdit>,
+) -> Result<(), String> {
+    let mut state = live.lock()?;
+    for edit in edits {
+        state.apply(edit)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_character_suggestions(
+    live: State<'_, LiveDocument>,
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<CharacterSuggestion>, String> {
+    let state = live.lock()?;
+    Ok(state
+        .characters
+        .suggestions(&prefix, limit.unwrap_or(DEFAULT_SUGGESTION_LIMIT)))
+}
+
+#[tauri::command]
+pub fn get_location_suggestions(
+    live: State<'_, LiveDocument>,
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<LocationSuggestion>, String> {
+    let state = live.lock()?;
+    Ok(state
+        .sluglines
+        .location_suggestions(&prefix, limit.unwrap_or(DEFAULT_SUGGESTION_LIMIT)))
+}
+
+#[tauri::command]
+pub fn get_time_suggestions(
+    live: State<'_, LiveDocument>,
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<TimeSuggestion>, String> {
+    let state = live.lock()?;
+    Ok(state
+        .sluglines
+        .time_suggestions(&prefix, limit.unwrap_or(DEFAULT_SUGGESTION_LIMIT)))
+}
+
+/// Page layout of the open document, as the PDF export would print it.
+#[tauri::command]
+pub fn get_pagination(
+    live: State<'_, LiveDocument>,
+    paper_size: Option<PaperSize>,
+) -> Result<Pagination, String> {
+    let mut state = live.lock()?;
+    Ok(state.pagination(paper_size.unwrap_or_default()).clone())
+}
+
+/// Lists the scenes of the open document. With `document_id`, each scene
+/// takes the color of the outline card linked to it.
+#[tauri::command]
+pub fn get_scene_list(
+    app: AppHandle,
+    live: State<'_, LiveDocument>,
+    document_id: Option<String>,
+) -> Result<Vec<SceneListEntry>, String> {
+    let mut scenes = live.lock()?.scene_list().to_vec();
+
+    if let Some(document_id) = document_id {
+        let colors = sidecar::load(&app, &document_id)?
+            .index_cards
+            .into_iter()
+            .filter_map(|card| Some((card.scene_id?, card.color?)))
+            .collect::<HashMap<_, _>>();
+        for scene in scenes.iter_mut() {
+            scene.color = scene
+                .scene_id
+                .as_ref()
+                .and_then(|scene_id| colors.get(scene_id))
+                .cloned();
+        }
+    }
+
+    Ok(scenes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cue(name: &str) -> Value {
+        json!({ "type": "character", "content": [{ "type": "text", "text": name }] })
+    }
+
+    fn line(text: &str) -> Value {
+        json!({ "type": "dialogue", "content": [{ "type": "text", "text": text }] })
+    }
+
+    #[test]
+    fn index_follows_block_edits() {
+        let mut state = LiveState::default();
+        state
+            .apply(BlockEdit {
+                from: 0,
+                delete_count: 0,
+                insert: vec![
+                    cue("JANE"),
+                    line("Hi."),
+                    cue("JOHN"),
+                    line("Hey."),
+                    cue("Jane (V.O.)"),
+                ],
+            })
+            .unwrap();
+
+        let suggestions = state.characters.suggestions("j", 10);
+        assert_eq!(suggestions[0].name, "JANE");
+        assert_eq!(suggestions[0].speak_count, 2);
+        assert_eq!(suggestions[0].last_block, 4);
+        assert_eq!(suggestions[1].name, "JOHN");
+
+        // Retyping John's cue as Jack drops John and shifts nothing.
+        state
+            .apply(BlockEdit {
+                from: 2,
+                delete_count: 1,
+                insert: vec![cue("JACK")],
+            })
+            .unwrap();
+        let names = state
+            .characters
+            .suggestions("JA", 10)
+            .into_iter()
+            .map(|suggestion| suggestion.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["JANE", "JACK"]);
+        assert!(state.characters.suggestions("JO", 10).is_empty());
+
+        // Deleting Jane's last cue falls back to her earlier one, and
+        // inserting ahead of it shifts the position.
+        state
+            .apply(BlockEdit {
+                from: 4,
+                delete_count: 1,
+                insert: Vec::new(),
+            })
+            .unwrap();
+        state
+            .apply(BlockEdit {
+                from: 0,
+                delete_count: 0,
+                insert: vec![line("Before.")],
+            })
+            .unwrap();
+        let jane = &state.characters.suggestions("JANE", 1)[0];
+        assert_eq!((jane.speak_count, jane.last_block), (1, 1));
+
+        assert!(state
+            .apply(BlockEdit {
+                from: 3,
+                delete_count: 5,
+                insert: Vec::new(),
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn scene_list_tracks_edits() {
+        let heading = |text: &str| json!({ "type": "sceneHeading", "attrs": { "sceneId": text }, "content": [{ "type": "text", "text": text }] });
+        let mut state = LiveState::default();
+        state
+            .apply(BlockEdit {
+                from: 0,
+                delete_count: 0,
+                insert: vec![
+                    heading("INT. OFFICE - DAY"),
+                    cue("JANE"),
+                    line("Hi."),
+                    cue("JOHN"),
+                    cue("JANE"),
+                    heading("EXT. STREET - NIGHT"),
+                ],
+            })
+            .unwrap();
+
+        let scenes = state.scene_list();
+        assert_eq!(scenes.len(), 2);
+        assert_eq!(scenes[0].characters, vec!["JANE", "JOHN"]);
+        assert_eq!(scenes[0].page, 1);
+        assert_eq!(scenes[0].eighths, 2);
+        assert!(scenes[1].characters.is_empty());
+
+        state
+            .apply(BlockEdit {
+                from: 6,
+                delete_count: 0,
+                insert: vec![cue("JOHN")],
+            })
+            .unwrap();
+        let scenes = state.scene_list();
+        assert_eq!(scenes[1].slugline, "EXT. STREET - NIGHT");
+        assert_eq!(scenes[1].block_index, 5);
+        assert_eq!(scenes[1].characters, vec!["JOHN"]);
+    }
+
+    #[test]
+    fn suggests_locations_and_times_from_sluglines() {
+        let heading = |text: &str| json!({ "type": "sceneHeading", "content": [{ "type": "text", "text": text }] });
+        let mut state = LiveState::default();
+        state
+            .apply(BlockEdit {
+                from: 0,
+                delete_count: 0,
+                insert: vec![
+                    heading("INT. KITCHEN - DAY"),
+                    heading("INT. KITCHEN - NIGHT"),
+                    heading("EXT. KITCHEN GARDEN - DAY"),
+                    heading("INT. KITCHEN - NIGHT"),
+                ],
+            })
+            .unwrap();
+
+        let suggestions = state.sluglines.location_suggestions("int. ki", 10);
+        let labels = suggestions
+            .iter()
+            .map(|suggestion| format!("{} - {}", suggestion.location, suggestion.time))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec!["KITCHEN - NIGHT", "KITCHEN - DAY", "KITCHEN GARDEN - DAY"]
+        );
+        assert_eq!(
+            state.sluglines.location_suggestions("INT. KITCHEN - D", 10)[0].time,
+            "DAY"
+        );
+
+        state
+            .apply(BlockEdit {
+                from: 1,
+                delete_count: 1,
+                insert: Vec::new(),
+            })
+            .unwrap();
+        let times = state.sluglines.time_suggestions("", 10);
+        assert_eq!((times[0].time.as_str(), times[0].use_count), ("DAY", 2));
+        assert_eq!(times[1].use_count, 1);
+    }
+}
@@ -0,0 +1,244 @@
+//! Diagnostics log. `tracing` events go to stderr and to `logs/grainery.log`
+//! under app data, which rolls over to `grainery.1.log` and so on once it
+//! grows past `MAX_LOG_BYTES`. The level defaults to `info` and can be set
+//! with `GRAINERY_LOG` (e.g. `GRAINERY_LOG=debug`).
+
+use crate::{app_data, file_manager};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tracing::Level;
+use tracing_subscriber::fmt::writer::{BoxMakeWriter, MakeWriter, MakeWriterExt};
+
+const LOG_DIR: &str = "logs";
+const LOG_NAME: &str = "grainery";
+const LEVEL_VAR: &str = "GRAINERY_LOG";
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+/// Rolled-over files kept besides the current one.
+const KEPT_LOGS: usize = 3;
+const DEFAULT_RECENT_LINES: usize = 500;
+const MAX_RECENT_LINES: usize = 5000;
+
+/// `grainery.log` for `index` 0, `grainery.<index>.log` for older files.
+fn log_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(format!("{}.log", LOG_NAME))
+    } else {
+        dir.join(format!("{}.{}.log", LOG_NAME, index))
+    }
+}
+
+struct RotatingFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            file: None,
+            written: 0,
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        match fs::remove_file(log_path(&self.dir, KEPT_LOGS)) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+        for index in (0..KEPT_LOGS).rev() {
+            let from = log_path(&self.dir, index);
+            if from.exists() {
+                fs::rename(&from, log_path(&self.dir, index + 1))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path(&self.dir, 0))?;
+        self.written = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Appends one formatted event, rolling the file over first if the
+    /// event would take it past the limit.
+    fn append(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.written > 0 && self.written + bytes.len() as u64 > self.max_bytes {
+            self.rotate()?;
+            self.open()?;
+        }
+        if let Some(file) = &mut self.file {
+            file.write_all(bytes)?;
+        }
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct LogWriter(Arc<Mutex<RotatingFile>>);
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut file = self
+            .0
+            .lock()
+            .map_err(|_| io::Error::other("Log file is unavailable"))?;
+        file.append(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogWriter {
+    type Writer = LogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Up to `limit` of the newest lines across the current and rolled-over
+/// files, oldest first.
+fn recent_lines(dir: &Path, limit: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for index in 0..=KEPT_LOGS {
+        let Ok(bytes) = fs::read(log_path(dir, index)) else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(&bytes);
+        let mut older = text.lines().map(str::to_string).collect::<Vec<_>>();
+        older.append(&mut lines);
+        lines = older;
+        if lines.len() >= limit {
+            break;
+        }
+    }
+    let skipped = lines.len().saturating_sub(limit);
+    lines.split_off(skipped)
+}
+
+/// Installs the global subscriber and logs panics. Without an app data
+/// directory events only go to stderr.
+pub fn init(app: &AppHandle) {
+    let level = std::env::var(LEVEL_VAR)
+        .ok()
+        .and_then(|value| value.parse::<Level>().ok())
+        .unwrap_or(Level::INFO);
+    let log_dir = app_data::app_data_dir(app, LOG_DIR);
+    let writer = match &log_dir {
+        Ok(dir) => BoxMakeWriter::new(io::stderr.and(LogWriter(Arc::new(Mutex::new(
+            RotatingFile::new(dir.clone(), MAX_LOG_BYTES),
+        ))))),
+        Err(_) => BoxMakeWriter::new(io::stderr),
+    };
+    let installed = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_ansi(false)
+        .with_writer(writer)
+        .try_init();
+    if installed.is_err() {
+        return;
+    }
+
+    std::panic::set_hook(Box::new(|info| {
+        tracing::error!(
+            "panic: {}\n{}",
+            info,
+            std::backtrace::Backtrace::force_capture()
+        );
+    }));
+
+    tracing::info!(
+        version = env!("CARGO_PKG_VERSION"),
+        os = std::env::consts::OS,
+        arch = std::env::consts::ARCH,
+        "Grainery started"
+    );
+    if let Err(error) = log_dir {
+        tracing::warn!("Logging to stderr only: {}", error);
+    }
+}
+
+/// The newest `limit` log lines (500 by default), oldest first, for
+/// attaching to bug reports.
+#[tauri::command]
+pub fn logs_recent(app: AppHandle, limit: Option<usize>) -> Result<Vec<String>, String> {
+    let dir = app_data::app_data_dir(&app, LOG_DIR)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_RECENT_LINES)
+        .clamp(1, MAX_RECENT_LINES);
+    Ok(recent_lines(&dir, limit))
+}
+
+#[tauri::command]
+pub fn logs_open_folder(app: AppHandle) -> Result<(), String> {
+    file_manager::open_with_default(&app_data::app_data_dir(&app, LOG_DIR)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("grainery-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn rolls_over_and_keeps_a_bounded_history() {
+        let dir = temp_dir("log-rotation");
+        let mut file = RotatingFile::new(dir.clone(), 100);
+        for line in 0..40 {
+            file.append(format!("line {:02} of the log\n", line).as_bytes())
+                .unwrap();
+        }
+
+        for index in 0..=KEPT_LOGS {
+            let size = fs::metadata(log_path(&dir, index)).unwrap().len();
+            assert!(size <= 100, "file {} has {} bytes", index, size);
+        }
+        assert!(!log_path(&dir, KEPT_LOGS + 1).exists());
+
+        let lines = recent_lines(&dir, 8);
+        let expected = (32..40)
+            .map(|line| format!("line {:02} of the log", line))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, expected);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reopens_an_existing_log() {
+        let dir = temp_dir("log-reopen");
+        fs::write(log_path(&dir, 0), "earlier run\n").unwrap();
+        let mut file = RotatingFile::new(dir.clone(), 1024);
+        file.append(b"this run\n").unwrap();
+
+        assert_eq!(recent_lines(&dir, 10), ["earlier run", "this run"]);
+        let empty = temp_dir("log-empty");
+        assert!(recent_lines(&empty, 10).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&empty);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::app_data::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+const MENU_LOCALE_FILE: &str = "menu-locale.json";
+const FALLBACK_LOCALE: &str = "en";
+
+/// Menu label resources by locale code, keyed by menu item id. English is
+/// complete; other locales fall back to it for missing keys.
+const LOCALES: [(&str, &str, &str); 4] = [
+    ("en", "English", include_str!("../locales/menus/en.json")),
+    ("de", "Deutsch", include_str!("../locales/menus/de.json")),
+    ("es", "Español", include_str!("../locales/menus/es.json")),
+    ("fr", "Français", include_str!("../locales/menus/fr.json")),
+];
+
+/// The menu language chosen in Settings. `None` follows the system locale.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MenuLocaleSettings {
+    #[serde(default)]
+    locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleOption {
+    pub code: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuLocaleInfo {
+    /// Locale the menus are built with on the next launch.
+    pub active: String,
+    pub override_locale: Option<String>,
+    pub system_locale: Option<String>,
+    pub available: Vec<LocaleOption>,
+}
+
+/// Menu labels for one locale.
+pub struct MenuStrings {
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl MenuStrings {
+    fn load(locale: &str) -> Self {
+        let parse = |code: &str| {
+            LOCALES
+                .iter()
+                .find(|(known, _, _)| *known == code)
+                .and_then(|(_, _, resource)| serde_json::from_str(resource).ok())
+                .unwrap_or_default()
+        };
+
+        Self {
+            strings: parse(locale),
+            fallback: parse(FALLBACK_LOCALE),
+        }
+    }
+
+    /// Returns the label for a menu id, falling back to English and then to
+    /// the id itself.
+    pub fn get(&self, key: &str) -> String {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+/// Normalizes OS locale names such as `de_DE.UTF-8` or `fr-CA` to lowercase
+/// BCP 47-style tags.
+fn normalize_locale(locale: &str) -> String {
+    locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-")
+        .to_ascii_lowercase()
+}
+
+/// Picks the best available locale: an exact match first, then the
+/// language alone, e.g. `fr-ca` resolves to `fr`.
+fn resolve_locale(requested: &str) -> Option<&'static str> {
+    let requested = normalize_locale(requested);
+    let language = requested.split('-').next().unwrap_or_default();
+    LOCALES
+        .iter()
+        .map(|(code, _, _)| *code)
+        .find(|code| *code == requested)
+        .or_else(|| {
+            LOCALES
+                .iter()
+                .map(|(code, _, _)| *code)
+                .find(|code| *code == language)
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn system_locale() -> Option<String> {
+    use cocoa::base::{id, nil};
+    use std::ffi::CStr;
+
+    unsafe {
+        let languages: id = msg_send![class!(NSLocale), preferredLanguages];
+        let first: id = msg_send![languages, firstObject];
+        if first == nil {
+            return None;
+        }
+        let utf8: *const std::os::raw::c_char = msg_send![first, UTF8String];
+        (!utf8.is_null()).then(|| CStr::from_ptr(utf8).to_string_lossy().to_string())
+    }
+}
+
+#[cfg(windows)]
+fn system_locale() -> Option<String> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserDefaultLocaleName(locale_name: *mut u16, length: i32) -> i32;
+    }
+
+    // LOCALE_NAME_MAX_LENGTH
+    let mut buffer = [0u16; 85];
+    let length = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+    if length <= 1 {
+        return None;
+    }
+    Some(String::from_utf16_lossy(&buffer[..length as usize - 1]))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+fn active_locale(settings: &MenuLocaleSettings, system: Option<&str>) -> &'static str {
+    settings
+        .locale
+        .as_deref()
+        .and_then(resolve_locale)
+        .or_else(|| system.and_then(resolve_locale))
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+/// Loads the menu labels for the saved override or the system locale.
+pub fn load_strings(app: &AppHandle) -> MenuStrings {
+    let settings = load_json::<MenuLocaleSettings>(app, MENU_LOCALE_FILE).unwrap_or_default();
+    MenuStrings::load(active_locale(&settings, system_locale().as_deref()))
+}
+
+fn locale_info(settings: MenuLocaleSettings) -> MenuLocaleInfo {
+    let system = system_locale();
+    MenuLocaleInfo {
+        active: active_locale(&settings, system.as_deref()).to_string(),
+        override_locale: settings.locale,
+        system_locale: system,
+        available: LOCALES
+            .iter()
+            .map(|(code, name, _)| LocaleOption {
+                code: code.to_string(),
+                name: name.to_string(),
+            })
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub fn menu_locale_get(app: AppHandle) -> Result<MenuLocaleInfo, String> {
+    Ok(locale_info(load_json(&app, MENU_LOCALE_FILE)?))
+}
+
+/// Saves the menu language override (`None` follows the system). Menus are
+/// built at launch, so the change applies after a restart.
+#[tauri::command]
+pub fn menu_locale_set(app: AppHandle, locale: Option<String>) -> Result<MenuLocaleInfo, String> {
+    let locale = match locale {
+        Some(locale) => Some(
+            resolve_locale(&locale)
+                .ok_or_else(|| format!("Unsupported menu language '{}'", locale))?
+                .to_string(),
+        ),
+        None => None,
+    };
+
+    let settings = MenuLocaleSettings { locale };
+    save_json(&app, MENU_LOCALE_FILE, &settings)?;
+    Ok(locale_info(settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_system_locale_names() {
+        assert_eq!(resolve_locale("de_DE.UTF-8"), Some("de"));
+        assert_eq!(resolve_locale("fr-CA"), Some("fr"));
+        assert_eq!(resolve_locale("ja-JP"), None);
+
+        let settings = MenuLocaleSettings {
+            locale: Some("es".to_string()),
+        };
+        assert_eq!(active_locale(&settings, Some("fr-FR")), "es");
+        assert_eq!(
+            active_locale(&MenuLocaleSettings::default(), Some("ja")),
+            "en"
+        );
+    }
+
+    #[test]
+    fn locale_resources_cover_english_keys() {
+        let english = MenuStrings::load(FALLBACK_LOCALE).strings;
+        assert!(!english.is_empty());
+        for (code, _, _) in LOCALES {
+            let strings = MenuStrings::load(code).strings;
+            let missing = english
+                .keys()
+                .filter(|key| !strings.contains_key(*key))
+                .collect::<Vec<_>>();
+            assert!(missing.is_empty(), "{} is missing {:?}", code, missing);
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! Versioned upgrades for files under app data. The version the data was
+//! last written with lives in `app-data-version.json`; on startup every
+//! pending migration runs in order. The files a migration touches are copied
+//! to `backups/migration-<from>-<to>-<time>/` first, and if any step fails
+//! they are put back and the version is left alone, so the app keeps
+//! working with the data it had. The outcome is exposed through
+//! `migrations_status` for the frontend to report.
+
+use crate::app_data;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+const VERSION_FILE: &str = "app-data-version.json";
+const BACKUP_DIR: &str = "backups";
+
+/// One upgrade step. Migration `n` in `MIGRATIONS` upgrades version `n`
+/// data to version `n + 1`.
+pub struct Migration {
+    pub description: &'static str,
+    /// Files and folders under app data the step may change. Only these are
+    /// backed up and restored.
+    pub paths: &'static [&'static str],
+    pub run: fn(&Path) -> Result<(), String>,
+}
+
+/// Append new steps here; never reorder or remove one that has shipped.
+/// Single-file schemas such as `settings.json` keep their own versioning.
+const MIGRATIONS: &[Migration] = &[];
+
+/// The app data version this build writes.
+pub fn current_version() -> usize {
+    MIGRATIONS.len()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionFile {
+    version: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationFailure {
+    /// The version the failed step would have upgraded from.
+    pub version: usize,
+    pub description: String,
+    pub error: String,
+    /// Copies of the pre-migration files, when a backup was made.
+    pub backup_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+    /// The version app data is at after startup.
+    pub version: usize,
+    /// Descriptions of the steps that ran this launch.
+    pub applied: Vec<String>,
+    pub failure: Option<MigrationFailure>,
+}
+
+fn read_version(root: &Path) -> Result<usize, String> {
+    let path = root.join(VERSION_FILE);
+    if !path.exists() {
+        return Ok(0);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read {}: {}", VERSION_FILE, error))?;
+    serde_json::from_str::<VersionFile>(&content)
+        .map(|file| file.version)
+        .map_err(|error| format!("Failed to parse {}: {}", VERSION_FILE, error))
+}
+
+fn write_version(root: &Path, version: usize) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(&VersionFile { version })
+        .map_err(|error| format!("Failed to serialize {}: {}", VERSION_FILE, error))?;
+    fs::write(root.join(VERSION_FILE), payload)
+        .map_err(|error| format!("Failed to save {}: {}", VERSION_FILE, error))
+}
+
+fn copy_path(from: &Path, to: &Path) -> io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match removed {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// Puts every backed-up path back. Paths that didn't exist before the
+/// migration are removed again.
+fn restore(root: &Path, backup: &Path, paths: &[&str]) -> io::Result<()> {
+    for path in paths {
+        remove_path(&root.join(path))?;
+        let saved = backup.join(path);
+        if saved.exists() {
+            copy_path(&saved, &root.join(path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs the migrations from the stored version up to `migrations.len()`.
+fn migrate_dir(root: &Path, migrations: &[Migration], stamp: &str) -> MigrationStatus {
+    // Data is always left at the version it was read at when a step fails.
+    let fail =
+        |version: usize, step: usize, description: &str, error: String, backup: Option<&Path>| {
+            MigrationStatus {
+                version,
+                applied: Vec::new(),
+                failure: Some(MigrationFailure {
+                    version: step,
+                    description: description.to_string(),
+                    error,
+                    backup_dir: backup.map(|dir| dir.to_string_lossy().to_string()),
+                }),
+            }
+        };
+
+    let from = match read_version(root) {
+        Ok(version) => version,
+        Err(error) => return fail(0, 0, "Read the app data version", error, None),
+    };
+    let target = migrations.len();
+    if from > target {
+        return fail(
+            from,
+            from,
+            "Check the app data version",
+            format!(
+                "App data version {} is newer than this version of Grainery supports",
+                from
+            ),
+            None,
+        );
+    }
+    let pending = &migrations[from..];
+    if pending.is_empty() {
+        return MigrationStatus {
+            version: from,
+            ..MigrationStatus::default()
+        };
+    }
+
+    let mut paths = pending
+        .iter()
+        .flat_map(|migration| migration.paths.iter().copied())
+        .collect::<Vec<_>>();
+    paths.sort_unstable();
+    paths.dedup();
+
+    let backup = root
+        .join(BACKUP_DIR)
+        .join(format!("migration-{}-{}-{}", from, target, stamp));
+    for path in &paths {
+        let source = root.join(path);
+        if !source.exists() {
+            continue;
+        }
+        if let Err(error) = copy_path(&source, &backup.join(path)) {
+            return fail(
+                from,
+                from,
+                pending[0].description,
+                format!("Failed to back up {}: {}", path, error),
+                Some(&backup),
+            );
+        }
+    }
+
+    let mut applied = Vec::new();
+    for (offset, migration) in pending.iter().enumerate() {
+        if let Err(error) = (migration.run)(root) {
+            let error = match restore(root, &backup, &paths) {
+                Ok(()) => error,
+                Err(restore_error) => format!(
+                    "{} (restoring the backup also failed: {})",
+                    error, restore_error
+                ),
+            };
+            return fail(
+                from,
+                from + offset,
+                migration.description,
+                error,
+                Some(&backup),
+            );
+        }
+        applied.push(migration.description.to_string());
+    }
+
+    if let Err(error) = write_version(root, target) {
+        let _ = restore(root, &backup, &paths);
+        return fail(from, from, pending[0].description, error, Some(&backup));
+    }
+
+    MigrationStatus {
+        version: target,
+        applied,
+        failure: None,
+    }
+}
+
+/// Upgrades app data before anything else reads it. Never fails: problems
+/// are logged and kept in the returned status.
+pub fn run(app: &AppHandle) -> MigrationStatus {
+    let root = match app_data::app_data_root(app) {
+        Ok(root) => root,
+        Err(error) => {
+            tracing::warn!("Skipped app data migrations: {}", error);
+            return MigrationStatus::default();
+        }
+    };
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    let status = migrate_dir(&root, MIGRATIONS, &stamp);
+
+    for description in &status.applied {
+        tracing::info!("Migrated app data: {}", description);
+    }
+    if let Some(failure) = &status.failure {
+        tracing::error!(
+            version = failure.version,
+            backup = failure.backup_dir.as_deref().unwrap_or("none"),
+            "App data migration '{}' failed: {}",
+            failure.description,
+            failure.error
+        );
+    }
+    status
+}
+
+/// The result of this launch's migrations.
+#[tauri::command]
+pub fn migrations_status(status: State<'_, MigrationStatus>) -> MigrationStatus {
+    status.inner().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("grainery-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn rename_stats(root: &Path) -> Result<(), String> {
+        let content = fs::read_to_string(root.join("stats.json")).map_err(|e| e.to_string())?;
+        fs::write(
+            root.join("stats.json"),
+            content.replace("words", "wordsAdded"),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    fn add_snapshot(root: &Path) -> Result<(), String> {
+        fs::create_dir_all(root.join("snapshots")).map_err(|e| e.to_string())?;
+        fs::write(root.join("snapshots/index.json"), "[]").map_err(|e| e.to_string())
+    }
+
+    fn broken(_: &Path) -> Result<(), String> {
+        Err("unexpected layout".to_string())
+    }
+
+    const STATS: Migration = Migration {
+        description: "Rename words",
+        paths: &["stats.json"],
+        run: rename_stats,
+    };
+    const SNAPSHOTS: Migration = Migration {
+        description: "Index snapshots",
+        paths: &["snapshots"],
+        run: add_snapshot,
+    };
+
+    #[test]
+    fn runs_pending_steps_once_and_backs_up_first() {
+        let root = temp_dir("migrate-ok");
+        fs::write(root.join("stats.json"), r#"{"words":3}"#).unwrap();
+
+        let status = migrate_dir(&root, &[STATS, SNAPSHOTS], "t1");
+        assert_eq!(status.version, 2);
+        assert_eq!(status.applied, ["Rename words", "Index snapshots"]);
+        assert!(status.failure.is_none());
+        assert_eq!(
+            fs::read_to_string(root.join("stats.json")).unwrap(),
+            r#"{"wordsAdded":3}"#
+        );
+        let backup = root.join(BACKUP_DIR).join("migration-0-2-t1");
+        assert_eq!(
+            fs::read_to_string(backup.join("stats.json")).unwrap(),
+            r#"{"words":3}"#
+        );
+        assert!(!backup.join("snapshots").exists());
+
+        let again = migrate_dir(&root, &[STATS, SNAPSHOTS], "t2");
+        assert_eq!(again.version, 2);
+        assert!(again.applied.is_empty());
+        assert!(!root.join(BACKUP_DIR).join("migration-2-2-t2").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn restores_the_backup_when_a_step_fails() {
+        let root = temp_dir("migrate-fail");
+        fs::write(root.join("stats.json"), r#"{"words":3}"#).unwrap();
+        let failing = Migration {
+            description: "Break things",
+            paths: &["snapshots"],
+            run: broken,
+        };
+
+        let status = migrate_dir(&root, &[STATS, SNAPSHOTS, failing], "t1");
+        let failure = status.failure.unwrap();
+        assert_eq!(failure.version, 2);
+        assert_eq!(failure.description, "Break things");
+        assert_eq!(failure.error, "unexpected layout");
+        assert!(failure.backup_dir.is_some());
+        assert_eq!(
+            fs::read_to_string(root.join("stats.json")).unwrap(),
+            r#"{"words":3}"#
+        );
+        assert!(!root.join("snapshots").exists());
+        assert_eq!(read_version(&root), Ok(0));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn leaves_newer_data_alone() {
+        let root = temp_dir("migrate-newer");
+        write_version(&root, 5).unwrap();
+
+        let status = migrate_dir(&root, &[STATS], "t1");
+        assert_eq!(status.version, 5);
+        assert!(status.failure.unwrap().error.contains("newer"));
+        assert!(!root.join(BACKUP_DIR).exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,253 @@
+//! Document access on iOS and Android, where the document picker hands out
+//! URLs instead of plain paths.
+//!
+//! On iOS a picked file is only readable while its security scope is open,
+//! so each picked URL is stored as a security-scoped bookmark and resolved
+//! again on later reads and writes (recent files, autosave). On Android the
+//! picker returns `content://` URIs, which are read and written through the
+//! fs plugin's content resolver bridge.
+
+#[cfg(target_os = "ios")]
+use crate::app_data::{load_json, save_json};
+#[cfg(target_os = "ios")]
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "ios")]
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+#[cfg(target_os = "ios")]
+const DOCUMENT_BOOKMARKS_FILE: &str = "document-bookmarks.json";
+
+/// Bookmark data per picked document URL, base64 encoded.
+#[cfg(target_os = "ios")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentBookmarks {
+    #[serde(default)]
+    bookmarks: BTreeMap<String, String>,
+}
+
+/// Whether `path` is a picker URL rather than a plain file path.
+pub fn is_document_uri(path: &str) -> bool {
+    path.starts_with("file://") || path.starts_with("content://")
+}
+
+#[cfg(target_os = "ios")]
+mod security_scope {
+    use objc::runtime::{Object, BOOL, NO, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::{CStr, CString};
+    use std::path::PathBuf;
+    use std::ptr::null_mut;
+
+    type Id = *mut Object;
+
+    unsafe fn ns_string(value: &str) -> Result<Id, String> {
+        let value = CString::new(value).map_err(|_| "Invalid document URL".to_string())?;
+        Ok(msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()])
+    }
+
+    unsafe fn url_path(url: Id) -> Option<PathBuf> {
+        let path: Id = msg_send![url, path];
+        if path.is_null() {
+            return None;
+        }
+        let utf8: *const std::os::raw::c_char = msg_send![path, UTF8String];
+        (!utf8.is_null()).then(|| PathBuf::from(CStr::from_ptr(utf8).to_string_lossy().as_ref()))
+    }
+
+    pub fn url_from_string(value: &str) -> Result<Id, String> {
+        unsafe {
+            let url: Id = msg_send![class!(NSURL), URLWithString: ns_string(value)?];
+            if url.is_null() {
+                return Err(format!("Invalid document URL: {}", value));
+            }
+            Ok(url)
+        }
+    }
+
+    /// Creates bookmark data for a URL the picker just granted access to.
+    pub fn bookmark(url: Id) -> Result<Vec<u8>, String> {
+        unsafe {
+            let accessing: BOOL = msg_send![url, startAccessingSecurityScopedResource];
+            let mut error: Id = null_mut();
+            let data: Id = msg_send![url,
+                bookmarkDataWithOptions: 0usize
+                includingResourceValuesForKeys: null_mut::<Object>()
+                relativeToURL: null_mut::<Object>()
+                error: &mut error];
+            if accessing == YES {
+                let _: () = msg_send![url, stopAccessingSecurityScopedResource];
+            }
+            if data.is_null() {
+                return Err("Failed to create document bookmark".to_string());
+            }
+
+            let length: usize = msg_send![data, length];
+            let bytes: *const u8 = msg_send![data, bytes];
+            Ok(std::slice::from_raw_parts(bytes, length).to_vec())
+        }
+    }
+
+    /// Resolves bookmark data to a URL. The flag is set when the bookmark is
+    /// stale and should be recreated.
+    pub fn resolve(bookmark: &[u8]) -> Result<(Id, bool), String> {
+        unsafe {
+            let data: Id = msg_send![class!(NSData),
+                dataWithBytes: bookmark.as_ptr()
+                length: bookmark.len()];
+            let mut stale: BOOL = NO;
+            let mut error: Id = null_mut();
+            let url: Id = msg_send![class!(NSURL),
+                URLByResolvingBookmarkData: data
+                options: 0usize
+                relativeToURL: null_mut::<Object>()
+                bookmarkDataIsStale: &mut stale
+                error: &mut error];
+            if url.is_null() {
+                return Err("The document is no longer available".to_string());
+            }
+            Ok((url, stale == YES))
+        }
+    }
+
+    /// Runs `access` on the URL's file path while its security scope is open.
+    pub fn with_access<T>(
+        url: Id,
+        access: impl FnOnce(&std::path::Path) -> Result<T, String>,
+    ) -> Result<T, String> {
+        unsafe {
+            let path = url_path(url).ok_or_else(|| "Invalid document URL".to_string())?;
+            let accessing: BOOL = msg_send![url, startAccessingSecurityScopedResource];
+            let result = access(&path);
+            if accessing == YES {
+                let _: () = msg_send![url, stopAccessingSecurityScopedResource];
+            }
+            result
+        }
+    }
+}
+
+#[cfg(target_os = "ios")]
+fn store_bookmark(app: &AppHandle, uri: &str, bookmark: &[u8]) -> Result<(), String> {
+    use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+    use base64::Engine;
+
+    let mut store = load_json::<DocumentBookmarks>(app, DOCUMENT_BOOKMARKS_FILE)?;
+    store
+        .bookmarks
+        .insert(uri.to_string(), BASE64_STANDARD.encode(bookmark));
+    save_json(app, DOCUMENT_BOOKMARKS_FILE, &store)
+}
+
+/// Resolves a document URL, preferring its stored bookmark so access
+/// survives app restarts.
+#[cfg(target_os = "ios")]
+fn resolve_url(app: &AppHandle, uri: &str) -> Result<*mut objc::runtime::Object, String> {
+    use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+    use base64::Engine;
+
+    let store = load_json::<DocumentBookmarks>(app, DOCUMENT_BOOKMARKS_FILE)?;
+    let Some(bookmark) = store
+        .bookmarks
+        .get(uri)
+        .and_then(|encoded| BASE64_STANDARD.decode(encoded).ok())
+    else {
+        return security_scope::url_from_string(uri);
+    };
+
+    let (url, stale) = security_scope::resolve(&bookmark)?;
+    if stale {
+        if let Ok(refreshed) = security_scope::bookmark(url) {
+            let _ = store_bookmark(app, uri, &refreshed);
+        }
+    }
+    Ok(url)
+}
+
+/// Remembers a URL returned by the document picker so it can be reopened
+/// later, e.g. from the recent files list.
+pub fn remember(app: &AppHandle, uri: &str) -> Result<(), String> {
+    #[cfg(target_os = "ios")]
+    {
+        let url = security_scope::url_from_string(uri)?;
+        store_bookmark(app, uri, &security_scope::bookmark(url)?)
+    }
+
+    // Android keeps persistable URI grants itself.
+    #[cfg(not(target_os = "ios"))]
+    {
+        let _ = (app, uri);
+        Ok(())
+    }
+}
+
+pub fn read(app: &AppHandle, uri: &str) -> Result<Vec<u8>, String> {
+    #[cfg(target_os = "ios")]
+    {
+        security_scope::with_access(resolve_url(app, uri)?, |path| {
+            std::fs::read(path).map_err(|error| format!("Failed to read document: {}", error))
+        })
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        use std::io::Read;
+        use tauri_plugin_fs::{FsExt, OpenOptions};
+
+        let url =
+            tauri::Url::parse(uri).map_err(|error| format!("Invalid document URL: {}", error))?;
+        let mut options = OpenOptions::new();
+        options.read(true);
+        let mut file = app
+            .fs()
+            .open(url, options)
+            .map_err(|error| format!("Failed to open document: {}", error))?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .map_err(|error| format!("Failed to read document: {}", error))?;
+        Ok(content)
+    }
+}
+
+pub fn write(app: &AppHandle, uri: &str, content: &[u8]) -> Result<(), String> {
+    #[cfg(target_os = "ios")]
+    {
+        security_scope::with_access(resolve_url(app, uri)?, |path| {
+            std::fs::write(path, content)
+                .map_err(|error| format!("Failed to write document: {}", error))
+        })
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        use std::io::Write;
+        use tauri_plugin_fs::{FsExt, OpenOptions};
+
+        let url =
+            tauri::Url::parse(uri).map_err(|error| format!("Invalid document URL: {}", error))?;
+        let mut options = OpenOptions::new();
+        options.write(true).truncate(true).create(true);
+        let mut file = app
+            .fs()
+            .open(url, options)
+            .map_err(|error| format!("Failed to open document: {}", error))?;
+        file.write_all(content)
+            .map_err(|error| format!("Failed to write document: {}", error))
+    }
+}
+
+/// A document URL exists when it can still be resolved and opened.
+pub fn exists(app: &AppHandle, uri: &str) -> bool {
+    #[cfg(target_os = "ios")]
+    {
+        resolve_url(app, uri)
+            .and_then(|url| security_scope::with_access(url, |path| Ok(path.exists())))
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        read(app, uri).is_ok()
+    }
+}
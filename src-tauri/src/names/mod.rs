@@ -0,0 +1,339 @@
+use crate::live_document::LiveDocument;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tauri::State;
+
+// Curated given names and surnames, bundled so the generator works offline.
+const GIVEN_NAMES_DATA: &str = include_str!("given_names.txt");
+const SURNAMES_DATA: &str = include_str!("surnames.txt");
+const DEFAULT_COUNT: usize = 10;
+const MAX_COUNT: usize = 50;
+/// Draws per requested name before giving up on filling the list.
+const ATTEMPTS_PER_NAME: usize = 20;
+
+struct GivenName {
+    name: &'static str,
+    gender: &'static str,
+    origin: &'static str,
+    eras: Vec<&'static str>,
+}
+
+struct Surname {
+    name: &'static str,
+    origin: &'static str,
+}
+
+struct NameData {
+    given: Vec<GivenName>,
+    surnames: Vec<Surname>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameCriteria {
+    #[serde(default)]
+    pub origin: Option<String>,
+    /// `classic`, `midcentury` or `contemporary`.
+    #[serde(default)]
+    pub era: Option<String>,
+    /// `f` or `m`; unisex names match either.
+    #[serde(default)]
+    pub gender: Option<String>,
+    /// Only names whose given name starts with this letter.
+    #[serde(default)]
+    pub initial: Option<String>,
+    #[serde(default)]
+    pub given_only: bool,
+    /// Keeps names that share a word with a character already in the script.
+    #[serde(default)]
+    pub allow_conflicts: bool,
+    #[serde(default)]
+    pub count: Option<usize>,
+    /// Fixes the draw so the same criteria give the same names.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameCheck {
+    /// Characters in the script sharing a given name or surname.
+    pub conflicts: Vec<String>,
+    /// Characters whose name starts with the same letter, which readers
+    /// tend to mix up.
+    pub same_initial: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedName {
+    pub full_name: String,
+    pub given: String,
+    pub surname: Option<String>,
+    pub origin: String,
+    #[serde(flatten)]
+    pub check: NameCheck,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameOptions {
+    pub origins: Vec<String>,
+    pub eras: Vec<String>,
+}
+
+fn data_lines(data: &'static str) -> impl Iterator<Item = Vec<&'static str>> {
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split('|').map(str::trim).collect())
+}
+
+fn name_data() -> &'static NameData {
+    static DATA: OnceLock<NameData> = OnceLock::new();
+    DATA.get_or_init(|| NameData {
+        given: data_lines(GIVEN_NAMES_DATA)
+            .filter_map(|fields| match fields.as_slice() {
+                [name, gender, origin, eras] => Some(GivenName {
+                    name,
+                    gender,
+                    origin,
+                    eras: eras.split(',').map(str::trim).collect(),
+                }),
+                _ => None,
+            })
+            .collect(),
+        surnames: data_lines(SURNAMES_DATA)
+            .filter_map(|fields| match fields.as_slice() {
+                [name, origin] => Some(Surname { name, origin }),
+                _ => None,
+            })
+            .collect(),
+    })
+}
+
+/// SplitMix64; plenty for picking names and keeps seeded draws reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn pick<'a, T>(&mut self, items: &[&'a T]) -> &'a T {
+        items[(self.next() % items.len() as u64) as usize]
+    }
+}
+
+fn normalized(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+fn name_words(name: &str) -> Vec<String> {
+    name.split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|word| !word.is_empty())
+        .map(str::to_uppercase)
+        .collect()
+}
+
+/// Compares a candidate name with the character cues already in the script.
+pub fn check_name(name: &str, characters: &[String]) -> NameCheck {
+    let words = name_words(name);
+    let initial = words.first().and_then(|word| word.chars().next());
+    let mut check = NameCheck {
+        conflicts: Vec::new(),
+        same_initial: Vec::new(),
+    };
+
+    for character in characters {
+        let existing = name_words(character);
+        if existing.iter().any(|word| words.contains(word)) {
+            check.conflicts.push(character.clone());
+        } else if initial.is_some()
+            && existing.first().and_then(|word| word.chars().next()) == initial
+        {
+            check.same_initial.push(character.clone());
+        }
+    }
+
+    check
+}
+
+pub fn generate(
+    criteria: &NameCriteria,
+    characters: &[String],
+) -> Result<Vec<GeneratedName>, String> {
+    let data = name_data();
+    let origin = normalized(&criteria.origin);
+    let era = normalized(&criteria.era);
+    let gender = normalized(&criteria.gender);
+    let initial = normalized(&criteria.initial).and_then(|initial| initial.chars().next());
+
+    let given = data
+        .given
+        .iter()
+        .filter(|name| origin.as_deref().is_none_or(|origin| name.origin == origin))
+        .filter(|name| era.as_deref().is_none_or(|era| name.eras.contains(&era)))
+        .filter(|name| {
+            gender
+                .as_deref()
+                .is_none_or(|gender| name.gender == gender || name.gender == "u")
+        })
+        .filter(|name| initial.is_none_or(|initial| name.name.to_lowercase().starts_with(initial)))
+        .collect::<Vec<_>>();
+    if given.is_empty() {
+        return Err("No names match those criteria".to_string());
+    }
+
+    let count = criteria.count.unwrap_or(DEFAULT_COUNT).clamp(1, MAX_COUNT);
+    let mut rng = Rng(criteria
+        .seed
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64));
+    let all_surnames = data.surnames.iter().collect::<Vec<_>>();
+    let mut names: Vec<GeneratedName> = Vec::new();
+
+    for _ in 0..count * ATTEMPTS_PER_NAME {
+        if names.len() == count {
+            break;
+        }
+
+        let given_name = rng.pick(&given);
+        let surname = if criteria.given_only {
+            None
+        } else {
+            // Pair with a surname of the same origin when the data has one.
+            let matching = all_surnames
+                .iter()
+                .copied()
+                .filter(|surname| surname.origin == given_name.origin)
+                .collect::<Vec<_>>();
+            let pool = if matching.is_empty() {
+                &all_surnames
+            } else {
+                &matching
+            };
+            Some(rng.pick(pool).name)
+        };
+
+        let full_name = match surname {
+            Some(surname) => format!("{} {}", given_name.name, surname),
+            None => given_name.name.to_string(),
+        };
+        if names.iter().any(|name| name.full_name == full_name) {
+            continue;
+        }
+
+        let check = check_name(&full_name, characters);
+        if !criteria.allow_conflicts && !check.conflicts.is_empty() {
+            continue;
+        }
+
+        names.push(GeneratedName {
+            full_name,
+            given: given_name.name.to_string(),
+            surname: surname.map(str::to_string),
+            origin: given_name.origin.to_string(),
+            check,
+        });
+    }
+
+    Ok(names)
+}
+
+fn options() -> NameOptions {
+    let data = name_data();
+    let mut origins = data
+        .given
+        .iter()
+        .map(|name| name.origin.to_string())
+        .collect::<Vec<_>>();
+    origins.sort();
+    origins.dedup();
+
+    NameOptions {
+        origins,
+        eras: ["classic", "midcentury", "contemporary"]
+            .iter()
+            .map(|era| era.to_string())
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub fn names_options() -> NameOptions {
+    options()
+}
+
+/// Draws names for `criteria`, skipping any that share a word with a
+/// character in the open script unless `allowConflicts` is set.
+#[tauri::command]
+pub fn generate_names(
+    live: State<'_, LiveDocument>,
+    criteria: Option<NameCriteria>,
+) -> Result<Vec<GeneratedName>, String> {
+    let characters = live.character_names()?;
+    generate(&criteria.unwrap_or_default(), &characters)
+}
+
+#[tauri::command]
+pub fn check_character_name(
+    live: State<'_, LiveDocument>,
+    name: String,
+) -> Result<NameCheck, String> {
+    Ok(check_name(&name, &live.character_names()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_data_parses() {
+        let data = name_data();
+        assert!(data.given.len() > 100);
+        assert!(data.surnames.len() > 50);
+        assert!(options().origins.contains(&"japanese".to_string()));
+    }
+
+    #[test]
+    fn generates_names_that_avoid_existing_characters() {
+        let characters = vec!["KENJI".to_string(), "MARY TANAKA".to_string()];
+        let criteria = NameCriteria {
+            origin: Some("Japanese".to_string()),
+            gender: Some("m".to_string()),
+            count: Some(5),
+            seed: Some(7),
+            ..NameCriteria::default()
+        };
+
+        let names = generate(&criteria, &characters).unwrap();
+        assert!(!names.is_empty());
+        for name in &names {
+            assert_eq!(name.origin, "japanese");
+            assert!(name.check.conflicts.is_empty(), "{}", name.full_name);
+        }
+        let again = generate(&criteria, &characters).unwrap();
+        assert_eq!(names[0].full_name, again[0].full_name);
+
+        let check = check_name("Kenji Mori", &characters);
+        assert_eq!(check.conflicts, vec!["KENJI"]);
+        let check = check_name("Maeve Keane", &characters);
+        assert_eq!(check.same_initial, vec!["MARY TANAKA"]);
+
+        assert!(generate(
+            &NameCriteria {
+                origin: Some("atlantean".to_string()),
+                ..NameCriteria::default()
+            },
+            &characters
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,396 @@
+use crate::document;
+use crate::sidecar;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/// A beat or index card. `scene_id` links it to the scene heading carrying
+/// the same `attrs.sceneId`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexCard {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub synopsis: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    pub order: u32,
+    #[serde(default)]
+    pub scene_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexCardInput {
+    pub title: String,
+    #[serde(default)]
+    pub synopsis: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub scene_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedScenes {
+    pub document_json: String,
+    pub cards: Vec<IndexCard>,
+    /// Ids of the cards that got a new scene heading.
+    pub created: Vec<String>,
+}
+
+fn validate_input(card: &IndexCardInput) -> Result<(), String> {
+    if card.title.trim().is_empty() {
+        return Err("Card title is required".to_string());
+    }
+    Ok(())
+}
+
+fn sorted(mut cards: Vec<IndexCard>) -> Vec<IndexCard> {
+    cards.sort_by_key(|card| card.order);
+    cards
+}
+
+/// Renumbers cards 0..n in their current order.
+fn renumber(cards: &mut [IndexCard]) {
+    cards.sort_by_key(|card| card.order);
+    for (order, card) in cards.iter_mut().enumerate() {
+        card.order = order as u32;
+    }
+}
+
+/// Puts cards in the order of `card_ids`, which must list every card once.
+fn reorder(cards: &mut [IndexCard], card_ids: &[String]) -> Result<(), String> {
+    if card_ids.len() != cards.len() {
+        return Err("Reorder must list every card exactly once".to_string());
+    }
+    let positions = card_ids
+        .iter()
+        .enumerate()
+        .map(|(order, id)| (id.as_str(), order as u32))
+        .collect::<HashMap<_, _>>();
+    if positions.len() != cards.len() {
+        return Err("Reorder must list every card exactly once".to_string());
+    }
+
+    let mut reordered = Vec::with_capacity(cards.len());
+    for card in cards.iter() {
+        let order = positions
+            .get(card.id.as_str())
+            .ok_or_else(|| format!("Card '{}' is missing from the new order", card.id))?;
+        reordered.push(*order);
+    }
+    for (card, order) in cards.iter_mut().zip(reordered) {
+        card.order = order;
+    }
+    renumber(cards);
+    Ok(())
+}
+
+/// Re-sorts the cards linked to scenes into `scene_ids` order after the
+/// script was restructured. Linked cards trade places among themselves;
+/// unlinked cards keep their place in the outline.
+pub fn follow_scenes(cards: &mut [IndexCard], scene_ids: &[String]) {
+    renumber(cards);
+    let scene_position = |card: &IndexCard| {
+        card.scene_id
+            .as_ref()
+            .and_then(|scene_id| scene_ids.iter().position(|id| id == scene_id))
+    };
+    let slots = cards
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| scene_position(card).is_some())
+        .map(|(slot, _)| slot)
+        .collect::<Vec<_>>();
+    let mut linked = slots
+        .iter()
+        .map(|slot| cards[*slot].clone())
+        .collect::<Vec<_>>();
+    linked.sort_by_key(|card| scene_position(card));
+
+    let now = Utc::now().to_rfc3339();
+    for (slot, card) in slots.into_iter().zip(linked) {
+        if cards[slot].id != card.id {
+            cards[slot] = IndexCard {
+                order: slot as u32,
+                updated_at: now.clone(),
+                ..card
+            };
+        }
+    }
+}
+
+/// Relinks the cards of scene `removed` after it was merged into scene
+/// `kept`. When `kept` already has a card, the removed scene's synopsis is
+/// appended to it and its card is dropped.
+pub fn merge_scene_cards(cards: &mut Vec<IndexCard>, removed: &str, kept: &str) {
+    let now = Utc::now().to_rfc3339();
+    let Some(kept_slot) = cards
+        .iter()
+        .position(|card| card.scene_id.as_deref() == Some(kept))
+    else {
+        for card in cards
+            .iter_mut()
+            .filter(|card| card.scene_id.as_deref() == Some(removed))
+        {
+            card.scene_id = Some(kept.to_string());
+            card.updated_at = now.clone();
+        }
+        return;
+    };
+
+    let synopses = cards
+        .iter()
+        .filter(|card| card.scene_id.as_deref() == Some(removed))
+        .map(|card| card.synopsis.trim().to_string())
+        .filter(|synopsis| !synopsis.is_empty())
+        .collect::<Vec<_>>();
+    if !synopses.is_empty() {
+        let kept_card = &mut cards[kept_slot];
+        kept_card.synopsis = std::iter::once(kept_card.synopsis.trim().to_string())
+            .filter(|synopsis| !synopsis.is_empty())
+            .chain(synopses)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        kept_card.updated_at = now;
+    }
+    cards.retain(|card| card.scene_id.as_deref() != Some(removed));
+    renumber(cards);
+}
+
+pub fn scene_heading(title: &str, scene_id: &str) -> Value {
+    json!({
+        "type": "sceneHeading",
+        "attrs": { "sceneNumber": null, "sceneId": scene_id },
+        "content": [{ "type": "text", "text": title.trim().to_uppercase() }],
+    })
+}
+
+/// Adds a scene heading for every card not linked to a scene in the
+/// document. New headings go after the scene of the closest earlier linked
+/// card, or before the first scene, so the script follows the card order.
+pub fn generate_scenes(
+    document: &mut Value,
+    cards: &mut [IndexCard],
+) -> Result<Vec<String>, String> {
+    renumber(cards);
+    let scenes = document::scenes(document);
+    let scene_end = |scene_id: &str| {
+        scenes
+            .iter()
+            .find(|scene| scene.scene_id.as_deref() == Some(scene_id))
+            .map(|scene| scene.end_block)
+    };
+
+    let blocks =
+        document::blocks_mut(document).ok_or_else(|| "Document has no content".to_string())?;
+    // Insert positions refer to the blocks as they were before inserting.
+    let mut anchor = scenes
+        .first()
+        .map(|scene| scene.heading_block)
+        .unwrap_or(blocks.len());
+    let mut insertions = Vec::new();
+    let mut created = Vec::new();
+
+    for card in cards.iter_mut() {
+        if let Some(end) = card.scene_id.as_deref().and_then(scene_end) {
+            anchor = end;
+            continue;
+        }
+
+        let scene_id = sidecar::next_id("scene");
+        insertions.push((anchor, scene_heading(&card.title, &scene_id)));
+        card.scene_id = Some(scene_id);
+        card.updated_at = Utc::now().to_rfc3339();
+        created.push(card.id.clone());
+    }
+
+    // Back to front, so earlier positions stay valid and cards sharing a
+    // position keep their order.
+    for (index, heading) in insertions.into_iter().rev() {
+        blocks.insert(index, heading);
+    }
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub fn outline_list_cards(app: AppHandle, document_id: String) -> Result<Vec<IndexCard>, String> {
+    Ok(sorted(sidecar::load(&app, &document_id)?.index_cards))
+}
+
+/// Adds a card at `position` in the outline, or at the end.
+#[tauri::command]
+pub fn outline_add_card(
+    app: AppHandle,
+    document_id: String,
+    card: IndexCardInput,
+    position: Option<u32>,
+) -> Result<IndexCard, String> {
+    validate_input(&card)?;
+
+    sidecar::update(&app, &document_id, |store| {
+        renumber(&mut store.index_cards);
+        let count = store.index_cards.len() as u32;
+        let order = position.unwrap_or(count).min(count);
+        for existing in store.index_cards.iter_mut() {
+            if existing.order >= order {
+                existing.order += 1;
+            }
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let created = IndexCard {
+            id: sidecar::next_id("card"),
+            title: card.title.trim().to_string(),
+            synopsis: card.synopsis,
+            color: card.color,
+            order,
+            scene_id: card.scene_id,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        store.index_cards.push(created.clone());
+        renumber(&mut store.index_cards);
+        Ok(created)
+    })
+}
+
+#[tauri::command]
+pub fn outline_update_card(
+    app: AppHandle,
+    document_id: String,
+    card_id: String,
+    card: IndexCardInput,
+) -> Result<IndexCard, String> {
+    validate_input(&card)?;
+
+    sidecar::update(&app, &document_id, |store| {
+        let existing = store
+            .index_cards
+            .iter_mut()
+            .find(|item| item.id == card_id)
+            .ok_or_else(|| format!("Card '{}' not found", card_id))?;
+
+        existing.title = card.title.trim().to_string();
+        existing.synopsis = card.synopsis;
+        existing.color = card.color;
+        existing.scene_id = card.scene_id;
+        existing.updated_at = Utc::now().to_rfc3339();
+        Ok(existing.clone())
+    })
+}
+
+#[tauri::command]
+pub fn outline_delete_card(
+    app: AppHandle,
+    document_id: String,
+    card_id: String,
+) -> Result<(), String> {
+    sidecar::update(&app, &document_id, |store| {
+        let before = store.index_cards.len();
+        store.index_cards.retain(|item| item.id != card_id);
+        if before == store.index_cards.len() {
+            return Err(format!("Card '{}' not found", card_id));
+        }
+        renumber(&mut store.index_cards);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn outline_reorder_cards(
+    app: AppHandle,
+    document_id: String,
+    card_ids: Vec<String>,
+) -> Result<Vec<IndexCard>, String> {
+    sidecar::update(&app, &document_id, |store| {
+        reorder(&mut store.index_cards, &card_ids)?;
+        Ok(store.index_cards.clone())
+    })
+}
+
+/// Creates scene headings for unlinked cards and links the cards to them;
+/// returns the updated document JSON.
+#[tauri::command]
+pub fn outline_generate_scenes(
+    app: AppHandle,
+    document_id: String,
+    document_json: String,
+) -> Result<GeneratedScenes, String> {
+    let mut document = document::parse_json(&document_json)?;
+
+    sidecar::update(&app, &document_id, |store| {
+        let created = generate_scenes(&mut document, &mut store.index_cards)?;
+        let document_json = serde_json::to_string(&document)
+            .map_err(|error| format!("Failed to serialize document: {}", error))?;
+        Ok(GeneratedScenes {
+            document_json,
+            cards: store.index_cards.clone(),
+            created,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: &str, order: u32, scene_id: Option<&str>) -> IndexCard {
+        IndexCard {
+            id: id.to_string(),
+            title: format!("{} title", id),
+            synopsis: String::new(),
+            color: None,
+            order,
+            scene_id: scene_id.map(str::to_string),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn generates_headings_in_card_order() {
+        let mut document = json!({
+            "type": "doc",
+            "content": [
+                { "type": "sceneHeading", "attrs": { "sceneId": "s1" }, "content": [{ "type": "text", "text": "INT. OFFICE - DAY" }] },
+                { "type": "action", "content": [{ "type": "text", "text": "Jane types." }] },
+            ],
+        });
+        let mut cards = vec![
+            card("b", 1, Some("s1")),
+            card("a", 0, None),
+            card("c", 2, None),
+        ];
+
+        let created = generate_scenes(&mut document, &mut cards).unwrap();
+        assert_eq!(created, vec!["a", "c"]);
+
+        let headings = document::scenes(&document)
+            .into_iter()
+            .map(|scene| scene.heading)
+            .collect::<Vec<_>>();
+        assert_eq!(headings, vec!["A TITLE", "INT. OFFICE - DAY", "C TITLE"]);
+        assert!(cards.iter().all(|card| card.scene_id.is_some()));
+
+        reorder(
+            &mut cards,
+            &["c".to_string(), "a".to_string(), "b".to_string()],
+        )
+        .unwrap();
+        assert_eq!(cards[0].id, "c");
+        assert!(reorder(
+            &mut cards,
+            &["a".to_string(), "a".to_string(), "b".to_string()]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,362 @@
+use super::{
+    absolute_line, measure_block, place_block, slot, BlockLayout, BlockMeasure, PageBoundary,
+    PageCursor, Pagination, PaperSize,
+};
+use serde_json::Value;
+
+/// Layout of the blocks after an edit, from before the edit. Kept so a
+/// re-flow can stop once it lines up with it again.
+struct OldTail {
+    /// Old index of the first block in the tail.
+    first_block: usize,
+    starts: Vec<PageCursor>,
+    blocks: Vec<BlockLayout>,
+    pages: Vec<PageBoundary>,
+    end: PageCursor,
+}
+
+/// Pagination kept current across block edits. Blocks are measured once;
+/// an edit measures only the blocks it inserts and re-flows pages from the
+/// first block it touches. The re-flow stops at the first later block that
+/// starts where it did before the edit, since everything from there on lays
+/// out the same and only shifts index.
+pub struct IncrementalPagination {
+    content_width: f32,
+    measures: Vec<BlockMeasure>,
+    /// Cursor each block starts from.
+    starts: Vec<PageCursor>,
+    /// Cursor after the last block.
+    end: PageCursor,
+    pagination: Pagination,
+}
+
+impl IncrementalPagination {
+    pub fn new(blocks: &[Value], paper_size: PaperSize) -> Self {
+        let content_width = paper_size.content_width();
+        let start = PageCursor::new(paper_size.dimensions().1);
+        let mut pagination = Self {
+            content_width,
+            measures: blocks
+                .iter()
+                .map(|block| measure_block(block, content_width))
+                .collect(),
+            starts: Vec::with_capacity(blocks.len()),
+            end: start,
+            pagination: Pagination {
+                paper_size,
+                lines_per_page: paper_size.lines_per_page(),
+                pages: vec![PageBoundary {
+                    page: 1,
+                    first_block: 0,
+                    manual: false,
+                }],
+                blocks: Vec::with_capacity(blocks.len()),
+                total_pages: 1,
+                total_lines: 0,
+            },
+        };
+        pagination.reflow(0, start, 0, None);
+        pagination
+    }
+
+    pub fn pagination(&self) -> &Pagination {
+        &self.pagination
+    }
+
+    pub fn into_pagination(self) -> Pagination {
+        self.pagination
+    }
+
+    pub fn paper_size(&self) -> PaperSize {
+        self.pagination.paper_size
+    }
+
+    /// Replaces `delete_count` blocks at `from` with `inserted`. The caller
+    /// checks the range against the document.
+    pub fn splice(&mut self, from: usize, delete_count: usize, inserted: &[Value]) {
+        let content_width = self.content_width;
+        self.measures.splice(
+            from..from + delete_count,
+            inserted
+                .iter()
+                .map(|block| measure_block(block, content_width)),
+        );
+
+        let start = self.starts.get(from).copied().unwrap_or(self.end);
+        let kept_pages = self
+            .pagination
+            .pages
+            .partition_point(|boundary| boundary.page == 1 || boundary.first_block < from);
+        let mut starts = self.starts.split_off(from);
+        let mut blocks = self.pagination.blocks.split_off(from);
+        starts.drain(..delete_count);
+        blocks.drain(..delete_count);
+        let tail = OldTail {
+            first_block: from + delete_count,
+            starts,
+            blocks,
+            pages: self.pagination.pages.split_off(kept_pages),
+            end: self.end,
+        };
+
+        self.reflow(from, start, inserted.len(), Some(tail));
+    }
+
+    /// Lays blocks out from `from`, starting at `cursor`. Blocks past the
+    /// `inserted` ones that start where they did in `tail` end the re-flow.
+    fn reflow(
+        &mut self,
+        from: usize,
+        mut cursor: PageCursor,
+        inserted: usize,
+        mut tail: Option<OldTail>,
+    ) {
+        let lines_per_page = self.pagination.lines_per_page;
+
+        for index in from..self.measures.len() {
+            // Position in the old tail of the block now at `index`
+            let skipped = index.checked_sub(from + inserted);
+            let caught_up = match (tail.as_mut(), skipped) {
+                (Some(tail), Some(skipped))
+                    if tail
+                        .starts
+                        .get(skipped)
+                        .is_some_and(|start| start.same_position(&cursor)) =>
+                {
+                    Some((tail, skipped))
+                }
+                _ => None,
+            };
+            if let Some((tail, skipped)) = caught_up {
+                let old_index = tail.first_block + skipped;
+                let shift = |block: usize| block + index - old_index;
+
+                self.starts.extend(tail.starts.drain(skipped..));
+                self.pagination.blocks.extend(tail.blocks.drain(skipped..));
+                self.pagination.pages.extend(
+                    std::mem::take(&mut tail.pages)
+                        .into_iter()
+                        .filter(|boundary| boundary.first_block >= old_index)
+                        .map(|boundary| PageBoundary {
+                            first_block: shift(boundary.first_block),
+                            ..boundary
+                        }),
+                );
+                self.finish(tail.end);
+                return;
+            }
+
+            self.starts.push(cursor);
+            let layout = place_block(
+                &self.measures[index],
+                index,
+                &mut cursor,
+                lines_per_page,
+                &mut self.pagination.pages,
+            );
+            self.pagination.blocks.push(layout);
+        }
+
+        self.finish(cursor);
+    }
+
+    fn finish(&mut self, end: PageCursor) {
+        let lines_per_page = self.pagination.lines_per_page;
+        self.end = end;
+        self.pagination.total_pages = end.page();
+        self.pagination.total_lines =
+            absolute_line(end.page(), slot(&end, lines_per_page), lines_per_page);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::paginate;
+    use super::*;
+    use crate::document::{self, block};
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    const WORDS: &[&str] = &[
+        "the", "door", "swings", "open", "and", "rain", "pours", "into", "hallway", "she", "waits",
+        "for", "an", "answer", "that", "never", "comes",
+    ];
+
+    /// Deterministic stand-in for random numbers.
+    struct Sequence(u64);
+
+    impl Sequence {
+        fn next(&mut self, below: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % below.max(1)
+        }
+    }
+
+    fn text(sequence: &mut Sequence, words: usize) -> String {
+        (0..words)
+            .map(|_| WORDS[sequence.next(WORDS.len())])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn random_block(sequence: &mut Sequence) -> Value {
+        match sequence.next(20) {
+            0 => json!({ "type": "pageBreak" }),
+            1 => json!({ "type": "action", "content": [] }),
+            2..=3 => block("sceneHeading", "INT. HOUSE - NIGHT"),
+            4..=7 => block("character", "MARA"),
+            8..=9 => block("parenthetical", &text(sequence, 3)),
+            10..=14 => {
+                let words = 2 + sequence.next(40);
+                block("dialogue", &text(sequence, words))
+            }
+            _ => {
+                let words = 3 + sequence.next(90);
+                block("action", &text(sequence, words))
+            }
+        }
+    }
+
+    /// Scenes of heading, action and dialogue until the script runs to
+    /// `pages` pages.
+    fn script(pages: u32) -> Vec<Value> {
+        let mut sequence = Sequence(7);
+        let mut blocks = Vec::new();
+        while paginate(&blocks, PaperSize::Letter).total_pages < pages {
+            for _ in 0..100 {
+                blocks.push(block("sceneHeading", "INT. HOUSE - NIGHT"));
+                let words = 10 + sequence.next(60);
+                blocks.push(block("action", &text(&mut sequence, words)));
+                for _ in 0..4 {
+                    blocks.push(block("character", "MARA"));
+                    let words = 4 + sequence.next(30);
+                    blocks.push(block("dialogue", &text(&mut sequence, words)));
+                }
+            }
+        }
+        blocks
+    }
+
+    fn assert_same_layout(incremental: &Pagination, full: &Pagination) {
+        assert_eq!(incremental.pages, full.pages);
+        assert_eq!(incremental.total_pages, full.total_pages);
+        assert_eq!(incremental.total_lines, full.total_lines);
+        assert_eq!(incremental.blocks.len(), full.blocks.len());
+        for (index, (a, b)) in incremental.blocks.iter().zip(&full.blocks).enumerate() {
+            assert_eq!((a.page, a.line), (b.page, b.line), "block {}", index);
+            assert_eq!(a.lines.len(), b.lines.len(), "block {}", index);
+            for (a, b) in a.lines.iter().zip(&b.lines) {
+                assert_eq!((a.page, a.line), (b.page, b.line), "block {}", index);
+                assert!((a.top - b.top).abs() < 0.01, "block {}", index);
+            }
+        }
+    }
+
+    #[test]
+    fn edits_match_full_repagination() {
+        let mut sequence = Sequence(42);
+        let mut blocks = (0..300)
+            .map(|_| random_block(&mut sequence))
+            .collect::<Vec<_>>();
+
+        for paper_size in [PaperSize::Letter, PaperSize::A4] {
+            let mut incremental = IncrementalPagination::new(&blocks, paper_size);
+            for _ in 0..300 {
+                let from = sequence.next(blocks.len() + 1);
+                let delete_count = sequence.next(4).min(blocks.len() - from);
+                let inserted = (0..sequence.next(4))
+                    .map(|_| random_block(&mut sequence))
+                    .collect::<Vec<_>>();
+
+                blocks.splice(from..from + delete_count, inserted.iter().cloned());
+                incremental.splice(from, delete_count, &inserted);
+                assert_same_layout(incremental.pagination(), &paginate(&blocks, paper_size));
+            }
+        }
+    }
+
+    /// An edit for a benchmark round: start block, blocks removed and the
+    /// blocks put in their place.
+    type Edit = Box<dyn Fn(usize) -> (usize, usize, Vec<Value>)>;
+
+    fn time_edits(
+        blocks: &[Value],
+        pagination: &mut IncrementalPagination,
+        edit: impl Fn(usize) -> (usize, usize, Vec<Value>),
+    ) -> Duration {
+        let mut blocks = blocks.to_vec();
+        let mut slowest = Duration::ZERO;
+        for round in 0..50 {
+            let (from, delete_count, inserted) = edit(round);
+            blocks.splice(from..from + delete_count, inserted.iter().cloned());
+            let started = Instant::now();
+            pagination.splice(from, delete_count, &inserted);
+            slowest = slowest.max(started.elapsed());
+        }
+        assert_same_layout(
+            pagination.pagination(),
+            &paginate(&blocks, PaperSize::Letter),
+        );
+        slowest
+    }
+
+    /// Typing, adding lines and adding or removing blocks near the top of a
+    /// 200-page script, where every page after the edit is in play.
+    /// `cargo test --release -- --ignored --nocapture repagination_benchmark`
+    #[test]
+    #[ignore = "benchmark; run in release"]
+    fn repagination_benchmark() {
+        let blocks = script(200);
+        let started = Instant::now();
+        let mut pagination = IncrementalPagination::new(&blocks, PaperSize::Letter);
+        println!(
+            "full pagination of {} pages ({} blocks): {:?}",
+            pagination.pagination().total_pages,
+            blocks.len(),
+            started.elapsed()
+        );
+
+        let middle = blocks.len() / 2;
+        let dialogue = (middle..).find(|index| document::node_type(&blocks[*index]) == "dialogue");
+        let dialogue = dialogue.expect("script has dialogue");
+        let cases: Vec<(&str, Edit)> = vec![
+            (
+                "typing in dialogue",
+                Box::new(move |round| {
+                    let typed = format!("{} {}", "she waits", "x".repeat(round + 1));
+                    (dialogue, 1, vec![block("dialogue", &typed)])
+                }),
+            ),
+            (
+                "growing action near the top",
+                Box::new(|round| {
+                    let typed = "rain pours ".repeat(round * 3 + 1);
+                    (1, 1, vec![block("action", &typed)])
+                }),
+            ),
+            (
+                "inserting blocks near the top",
+                Box::new(|_| (2, 0, vec![block("action", "The lights go out.")])),
+            ),
+            (
+                "deleting blocks near the top",
+                Box::new(|_| (2, 1, Vec::new())),
+            ),
+        ];
+
+        for (name, edit) in cases {
+            let slowest = time_edits(&blocks, &mut pagination, edit);
+            pagination = IncrementalPagination::new(&blocks, PaperSize::Letter);
+            println!("{}: slowest update {:?}", name, slowest);
+            assert!(
+                slowest < Duration::from_millis(10),
+                "{} took {:?}",
+                name,
+                slowest
+            );
+        }
+    }
+}
@@ -0,0 +1,660 @@
+use crate::document;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+mod incremental;
+
+pub use incremental::IncrementalPagination;
+
+// Screenplay page layout (in points, 72 points = 1 inch). The PDF export,
+// page estimates and the editor's page view all lay pages out from here.
+pub const MARGIN_TOP: f32 = 72.0; // 1 inch
+pub const MARGIN_BOTTOM: f32 = 72.0; // 1 inch
+pub const MARGIN_LEFT: f32 = 108.0; // 1.5 inches
+pub const MARGIN_RIGHT: f32 = 72.0; // 1 inch
+pub const FONT_SIZE: f32 = 12.0;
+pub const LINE_HEIGHT: f32 = 12.0; // Single-spaced Courier
+pub const COURIER_CHAR_WIDTH: f32 = 7.2; // Courier at 12pt
+
+// Element indents (from left margin)
+pub const CHARACTER_INDENT: f32 = 144.0; // 2 inches from margin
+pub const DIALOGUE_INDENT: f32 = 72.0; // 1 inch from margin
+pub const DIALOGUE_WIDTH: f32 = 252.0; // 3.5 inches
+pub const PARENTHETICAL_INDENT: f32 = 108.0; // 1.5 inches from margin
+pub const PARENTHETICAL_WIDTH: f32 = 144.0; // 2 inches
+pub const CAPTION_INDENT: f32 = 36.0; // comic captions and sound effects
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaperSize {
+    #[default]
+    Letter,
+    A4,
+}
+
+impl PaperSize {
+    /// Page width and height in points.
+    pub fn dimensions(self) -> (f32, f32) {
+        match self {
+            PaperSize::Letter => (612.0, 792.0), // 8.5 x 11 inches
+            PaperSize::A4 => (595.28, 841.89),   // 210 x 297 mm
+        }
+    }
+
+    pub fn content_width(self) -> f32 {
+        self.dimensions().0 - MARGIN_LEFT - MARGIN_RIGHT
+    }
+
+    /// Full lines of body text between the margins: 54 on Letter.
+    pub fn lines_per_page(self) -> usize {
+        ((self.dimensions().1 - MARGIN_TOP - MARGIN_BOTTOM) / LINE_HEIGHT) as usize
+    }
+}
+
+/// Where a screenplay element sits on the page and the spacing around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElementFormat {
+    /// Offset from the left margin.
+    pub indent: f32,
+    pub width: f32,
+    pub uppercase: bool,
+    pub bold: bool,
+    pub blank_before: bool,
+    pub blank_after: bool,
+    /// Lines that must fit after the blank before, or the element moves to
+    /// the next page. Every element also keeps its own lines together.
+    pub keep_lines: usize,
+    pub prefix: &'static str,
+    pub suffix: &'static str,
+    /// Alignment when the block has no `textAlign` of its own.
+    pub align: &'static str,
+}
+
+impl ElementFormat {
+    /// Format for a node type; unknown types lay out like action.
+    pub fn for_type(node_type: &str, content_width: f32) -> Self {
+        let action = Self {
+            indent: 0.0,
+            width: content_width,
+            uppercase: false,
+            bold: false,
+            blank_before: true,
+            blank_after: false,
+            keep_lines: 0,
+            prefix: "",
+            suffix: "",
+            align: "left",
+        };
+
+        match node_type {
+            "comicPage" => Self {
+                uppercase: true,
+                blank_before: false,
+                blank_after: true,
+                keep_lines: 2,
+                ..action
+            },
+            "comicPanel" => Self {
+                uppercase: true,
+                blank_after: true,
+                keep_lines: 2,
+                ..action
+            },
+            "caption" | "soundEffect" => Self {
+                indent: CAPTION_INDENT,
+                width: content_width - CAPTION_INDENT,
+                uppercase: node_type == "soundEffect",
+                blank_before: false,
+                ..action
+            },
+            "sceneHeading" => Self {
+                uppercase: true,
+                bold: true,
+                blank_after: true,
+                ..action
+            },
+            "character" => Self {
+                indent: CHARACTER_INDENT,
+                width: (content_width - CHARACTER_INDENT).max(COURIER_CHAR_WIDTH),
+                uppercase: true,
+                ..action
+            },
+            "dialogue" => Self {
+                indent: DIALOGUE_INDENT,
+                width: DIALOGUE_WIDTH,
+                blank_before: false,
+                ..action
+            },
+            "parenthetical" => Self {
+                indent: PARENTHETICAL_INDENT,
+                width: PARENTHETICAL_WIDTH,
+                blank_before: false,
+                prefix: "(",
+                suffix: ")",
+                ..action
+            },
+            "transition" => Self {
+                uppercase: true,
+                blank_after: true,
+                align: "right",
+                ..action
+            },
+            _ => action,
+        }
+    }
+
+    pub fn max_chars(&self) -> usize {
+        ((self.width / COURIER_CHAR_WIDTH) as usize).max(1)
+    }
+}
+
+/// ` (V.O.)` and the like, from a character cue's `attrs.extension`.
+pub fn character_suffix(attrs: Option<&Value>) -> String {
+    attrs
+        .and_then(|attrs| attrs.get("extension"))
+        .and_then(|value| value.as_str())
+        .map(|extension| format!(" ({})", extension))
+        .unwrap_or_default()
+}
+
+/// Word-wraps characters by count. Runs of whitespace collapse to a single
+/// space, styled by `space` from the characters on either side.
+pub fn wrap<T: Clone>(
+    chars: &[(char, T)],
+    max_chars: usize,
+    space: impl Fn(&T, &T) -> T,
+) -> Vec<Vec<(char, T)>> {
+    let mut words: Vec<&[(char, T)]> = Vec::new();
+    let mut start = None;
+    for (index, (c, _)) in chars.iter().enumerate() {
+        match (c.is_whitespace(), start) {
+            (true, Some(word_start)) => {
+                words.push(&chars[word_start..index]);
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(&chars[word_start..]);
+    }
+
+    let mut lines: Vec<Vec<(char, T)>> = Vec::new();
+    let mut line: Vec<(char, T)> = Vec::new();
+
+    for word in words {
+        if line.is_empty() {
+            line = word.to_vec();
+        } else if line.len() + 1 + word.len() <= max_chars {
+            let prev = &line[line.len() - 1].1;
+            let next = &word[0].1;
+            line.push((' ', space(prev, next)));
+            line.extend_from_slice(word);
+        } else {
+            lines.push(std::mem::take(&mut line));
+            line = word.to_vec();
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        lines.push(Vec::new());
+    }
+
+    lines
+}
+
+/// Height of a line of text set at the given sizes. Text larger than the
+/// body size opens the line up; smaller text keeps the body line height.
+pub fn line_advance(sizes: impl IntoIterator<Item = f32>) -> f32 {
+    sizes
+        .into_iter()
+        .filter(|size| *size > FONT_SIZE)
+        .map(|size| size * 1.2)
+        .fold(LINE_HEIGHT, f32::max)
+}
+
+/// Vertical position on the page, measured up from the bottom edge as PDF
+/// coordinates are, and the page number it is on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageCursor {
+    page_height: f32,
+    y: f32,
+    page: u32,
+}
+
+impl PageCursor {
+    pub fn new(page_height: f32) -> Self {
+        Self {
+            page_height,
+            y: page_height - MARGIN_TOP,
+            page: 1,
+        }
+    }
+
+    pub fn page(&self) -> u32 {
+        self.page
+    }
+
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    /// Places the cursor anywhere on the current page, e.g. on a title page.
+    pub fn set_y(&mut self, y: f32) {
+        self.y = y;
+    }
+
+    /// Distance below the top margin.
+    pub fn offset(&self) -> f32 {
+        self.page_height - MARGIN_TOP - self.y
+    }
+
+    pub fn at_top(&self) -> bool {
+        self.offset() < 0.5
+    }
+
+    pub fn fits(&self, space: f32) -> bool {
+        self.y - space >= MARGIN_BOTTOM
+    }
+
+    pub fn advance(&mut self, space: f32) {
+        self.y -= space;
+    }
+
+    pub fn next_page(&mut self) {
+        self.page += 1;
+        self.y = self.page_height - MARGIN_TOP;
+    }
+
+    /// Same page and line, allowing for rounding in how `y` was reached.
+    fn same_position(&self, other: &PageCursor) -> bool {
+        self.page == other.page && (self.y - other.y).abs() < 0.01
+    }
+}
+
+/// A rendered line of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinePosition {
+    pub page: u32,
+    /// 0-based line slot on the page.
+    pub line: usize,
+    /// Points below the top margin.
+    pub top: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockLayout {
+    /// Page the block's first line is on. Empty blocks take the page they
+    /// would start on.
+    pub page: u32,
+    /// Line offset of the block's first line from the top of page one,
+    /// counting `linesPerPage` lines to each page.
+    pub line: usize,
+    /// Empty for blank blocks and page breaks.
+    pub lines: Vec<LinePosition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageBoundary {
+    pub page: u32,
+    /// Block whose line opens the page. A long block can run onto the page
+    /// from the one before.
+    pub first_block: usize,
+    /// True when a page break block started the page.
+    pub manual: bool,
+}
+
+/// Page layout of a screenplay-mode document, as the PDF export prints it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    pub paper_size: PaperSize,
+    pub lines_per_page: usize,
+    /// One entry per page, in order; the first is always page one.
+    pub pages: Vec<PageBoundary>,
+    /// One entry per top-level block.
+    pub blocks: Vec<BlockLayout>,
+    pub total_pages: u32,
+    /// Line offset of the end of the script, as for `BlockLayout::line`.
+    pub total_lines: usize,
+}
+
+impl Pagination {
+    /// Line offset where block `index` starts, or the end of the script.
+    fn line_at(&self, index: usize) -> usize {
+        self.blocks
+            .get(index)
+            .map(|block| block.line)
+            .unwrap_or(self.total_lines)
+    }
+
+    /// Length of blocks `start_block..end_block` in eighths of a page,
+    /// counting blank lines and the rest of a page left by a break.
+    pub fn eighths(&self, start_block: usize, end_block: usize) -> u32 {
+        let lines = self
+            .line_at(end_block)
+            .saturating_sub(self.line_at(start_block));
+        lines_to_eighths(lines, self.lines_per_page)
+    }
+
+    /// Each scene's length in eighths, in script order.
+    pub fn scene_eighths(&self, scenes: &[document::SceneSpan]) -> Vec<u32> {
+        scenes
+            .iter()
+            .map(|scene| self.eighths(scene.heading_block, scene.end_block))
+            .collect()
+    }
+}
+
+/// Converts a line count to eighths of a page, rounded up with a minimum
+/// of one eighth (the smallest length on a breakdown sheet or schedule).
+pub fn lines_to_eighths(lines: usize, lines_per_page: usize) -> u32 {
+    ((lines as f64 * 8.0 / lines_per_page.max(1) as f64).ceil() as u32).max(1)
+}
+
+fn collect_sized_chars(node: &Value, out: &mut Vec<(char, f32)>) {
+    if let Some(text) = node.get("text").and_then(Value::as_str) {
+        let size = node
+            .get("marks")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|mark| mark.get("type").and_then(Value::as_str) == Some("textSize"))
+            .filter_map(|mark| mark.pointer("/attrs/sizePt").and_then(Value::as_f64))
+            .next_back()
+            .map(|size| (size as f32).clamp(6.0, 72.0))
+            .unwrap_or(FONT_SIZE);
+        out.extend(text.chars().map(|c| (c, size)));
+        return;
+    }
+
+    if let Some(content) = node.get("content").and_then(Value::as_array) {
+        for child in content {
+            collect_sized_chars(child, out);
+        }
+    }
+}
+
+/// What a block asks of the page wherever it lands. Depends only on the
+/// block and the paper size, so it is worked out once per block.
+#[derive(Debug, Clone, PartialEq)]
+enum BlockMeasure {
+    PageBreak,
+    /// Blank blocks print nothing.
+    Empty,
+    Text {
+        blank_before: bool,
+        keep_lines: usize,
+        blank_after: bool,
+        /// Height of each line the block wraps to.
+        advances: Vec<f32>,
+    },
+}
+
+fn measure_block(block: &Value, content_width: f32) -> BlockMeasure {
+    let node_type = document::node_type(block);
+    if node_type == "pageBreak" {
+        return BlockMeasure::PageBreak;
+    }
+    if document::node_text(block).trim().is_empty() {
+        return BlockMeasure::Empty;
+    }
+
+    let format = ElementFormat::for_type(node_type, content_width);
+    let mut chars = Vec::new();
+    collect_sized_chars(block, &mut chars);
+    if format.uppercase {
+        chars = chars
+            .into_iter()
+            .flat_map(|(c, size)| c.to_uppercase().map(move |upper| (upper, size)))
+            .collect();
+    }
+
+    let suffix = if node_type == "character" {
+        character_suffix(block.get("attrs"))
+    } else {
+        format.suffix.to_string()
+    };
+    let mut affixed = format
+        .prefix
+        .chars()
+        .map(|c| (c, FONT_SIZE))
+        .collect::<Vec<_>>();
+    affixed.extend(chars);
+    affixed.extend(suffix.chars().map(|c| (c, FONT_SIZE)));
+
+    BlockMeasure::Text {
+        blank_before: format.blank_before,
+        keep_lines: format.keep_lines,
+        blank_after: format.blank_after,
+        advances: wrap(&affixed, format.max_chars(), |prev, _| *prev)
+            .iter()
+            .map(|line| line_advance(line.iter().map(|(_, size)| *size)))
+            .collect(),
+    }
+}
+
+/// Line slot of the cursor on its page.
+fn slot(cursor: &PageCursor, lines_per_page: usize) -> usize {
+    ((cursor.offset().max(0.0) / LINE_HEIGHT) as usize).min(lines_per_page)
+}
+
+fn absolute_line(page: u32, line: usize, lines_per_page: usize) -> usize {
+    (page as usize - 1) * lines_per_page + line
+}
+
+/// Places block `index` at `cursor` with the PDF export's rules: blank lines
+/// around elements, and an element that does not fit in what is left of a
+/// page starts the next one. Elements longer than a page run on. Pages the
+/// block opens are added to `pages`.
+fn place_block(
+    measure: &BlockMeasure,
+    index: usize,
+    cursor: &mut PageCursor,
+    lines_per_page: usize,
+    pages: &mut Vec<PageBoundary>,
+) -> BlockLayout {
+    let page_before = cursor.page();
+    let (blank_before, keep_lines, blank_after, advances) = match measure {
+        BlockMeasure::PageBreak | BlockMeasure::Empty => {
+            if *measure == BlockMeasure::PageBreak {
+                cursor.next_page();
+                pages.push(PageBoundary {
+                    page: cursor.page(),
+                    first_block: index,
+                    manual: true,
+                });
+            }
+            return BlockLayout {
+                page: cursor.page(),
+                line: absolute_line(cursor.page(), slot(cursor, lines_per_page), lines_per_page),
+                lines: Vec::new(),
+            };
+        }
+        BlockMeasure::Text {
+            blank_before,
+            keep_lines,
+            blank_after,
+            advances,
+        } => (*blank_before, *keep_lines, *blank_after, advances),
+    };
+
+    if blank_before {
+        cursor.advance(LINE_HEIGHT);
+    }
+    if !cursor.fits(keep_lines as f32 * LINE_HEIGHT) {
+        cursor.next_page();
+    }
+    if !cursor.fits(advances.iter().sum()) {
+        cursor.next_page();
+    }
+
+    let mut lines = Vec::with_capacity(advances.len());
+    for advance in advances {
+        if !cursor.fits(*advance) {
+            cursor.next_page();
+        }
+        lines.push(LinePosition {
+            page: cursor.page(),
+            line: slot(cursor, lines_per_page),
+            top: cursor.offset(),
+        });
+        cursor.advance(*advance);
+    }
+    if blank_after {
+        cursor.advance(LINE_HEIGHT);
+    }
+
+    for page in page_before + 1..=cursor.page() {
+        pages.push(PageBoundary {
+            page,
+            first_block: index,
+            manual: false,
+        });
+    }
+
+    let first = lines[0];
+    BlockLayout {
+        page: first.page,
+        line: absolute_line(first.page, first.line, lines_per_page),
+        lines,
+    }
+}
+
+/// Lays out `blocks` line by line as the PDF export prints them.
+pub fn paginate(blocks: &[Value], paper_size: PaperSize) -> Pagination {
+    IncrementalPagination::new(blocks, paper_size).into_pagination()
+}
+
+/// Paginates a document (or bare content) without producing a PDF.
+#[tauri::command]
+pub fn paginate_document(
+    content_json: String,
+    paper_size: Option<PaperSize>,
+) -> Result<Pagination, String> {
+    let root = document::parse_json(&content_json)?;
+    Ok(paginate(
+        document::blocks(&root),
+        paper_size.unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    #[test]
+    fn wraps_words_by_character_count() {
+        let chars = "the quick  brown fox"
+            .chars()
+            .map(|c| (c, ()))
+            .collect::<Vec<_>>();
+        let lines = wrap(&chars, 10, |_, _| ())
+            .into_iter()
+            .map(|line| line.into_iter().map(|(c, _)| c).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec!["the quick", "brown fox"]);
+        assert_eq!(wrap::<()>(&[], 10, |_, _| ()).len(), 1);
+    }
+
+    #[test]
+    fn lays_out_elements_with_blank_lines_and_breaks() {
+        let blocks = vec![
+            block("sceneHeading", "INT. OFFICE - DAY"),
+            block("action", "Jane types."),
+            block("character", "JANE"),
+            block("parenthetical", "quietly"),
+            block("dialogue", "Done."),
+            block("action", ""),
+            json!({ "type": "pageBreak" }),
+            block("caption", "Later."),
+        ];
+        let pagination = paginate(&blocks, PaperSize::Letter);
+        let first_lines = pagination
+            .blocks
+            .iter()
+            .map(|block| block.lines.first().map(|line| (line.page, line.line)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            first_lines,
+            vec![
+                Some((1, 1)),
+                Some((1, 4)),
+                Some((1, 6)),
+                Some((1, 7)),
+                Some((1, 8)),
+                None,
+                None,
+                Some((2, 0)),
+            ]
+        );
+        assert_eq!(pagination.total_pages, 2);
+        assert_eq!(pagination.blocks[6].page, 2);
+        assert!(pagination.pages[1].manual);
+        assert_eq!(pagination.total_lines, 55);
+
+        let scenes = document::block_scenes(&blocks);
+        assert_eq!(pagination.scene_eighths(&scenes), vec![8]);
+        assert_eq!(pagination.eighths(0, 5), 2);
+        assert_eq!(lines_to_eighths(0, 55), 1);
+        assert_eq!(lines_to_eighths(110, 55), 16);
+    }
+
+    #[test]
+    fn moves_elements_that_do_not_fit_to_the_next_page() {
+        let mut blocks = vec![block("action", "Opening.")];
+        // Blank line plus one line each, ending on line 53 of 54.
+        for _ in 0..26 {
+            blocks.push(block("action", "Beat."));
+        }
+        blocks.push(block("sceneHeading", "EXT. ROOF - NIGHT"));
+        blocks.push(block("action", &"word ".repeat(800)));
+
+        let letter = paginate(&blocks, PaperSize::Letter);
+        assert_eq!(letter.blocks[26].lines[0].line, 53);
+        assert_eq!(letter.blocks[27].page, 2);
+        assert_eq!(letter.blocks[27].line, 54);
+        assert_eq!(letter.blocks[28].page, 3);
+        assert_eq!(letter.blocks[28].lines.len(), 67);
+        assert_eq!(letter.blocks[28].lines[66].page, 4);
+        assert_eq!(letter.total_pages, 4);
+        let firsts = letter
+            .pages
+            .iter()
+            .map(|page| page.first_block)
+            .collect::<Vec<_>>();
+        assert_eq!(firsts, vec![0, 27, 28, 28]);
+
+        let a4 = paginate(&blocks, PaperSize::A4);
+        assert_eq!(a4.lines_per_page, 58);
+        assert_eq!(a4.blocks[27].page, 1);
+    }
+
+    #[test]
+    fn larger_text_opens_up_the_line() {
+        let blocks = vec![
+            json!({
+                "type": "action",
+                "content": [
+                    { "type": "text", "text": "Big", "marks": [{ "type": "textSize", "attrs": { "sizePt": 20 } }] },
+                    { "type": "text", "text": " words" },
+                ],
+            }),
+            block("action", "After."),
+        ];
+        let pagination = paginate(&blocks, PaperSize::Letter);
+        assert_eq!(pagination.blocks[0].lines[0].top, 12.0);
+        assert_eq!(pagination.blocks[1].lines[0].top, 12.0 + 24.0 + 12.0);
+    }
+}
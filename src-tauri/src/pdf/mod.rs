@@ -1,31 +1,27 @@
-use crate::fonts;
+use crate::pagination::{
+    self, ElementFormat, PageCursor, COURIER_CHAR_WIDTH, FONT_SIZE, LINE_HEIGHT, MARGIN_BOTTOM,
+    MARGIN_LEFT, MARGIN_RIGHT,
+};
+use crate::{document, fonts};
 use owned_ttf_parser::{AsFaceRef, OwnedFace};
+use printpdf::path::PaintMode;
 use printpdf::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::Path;
 
-// Screenplay formatting constants (in points, 72 points = 1 inch)
-const PAGE_WIDTH: f32 = 612.0; // 8.5 inches
-const PAGE_HEIGHT: f32 = 792.0; // 11 inches
-const MARGIN_TOP: f32 = 72.0; // 1 inch
-const MARGIN_BOTTOM: f32 = 72.0; // 1 inch
-const MARGIN_LEFT: f32 = 108.0; // 1.5 inches
-const MARGIN_RIGHT: f32 = 72.0; // 1 inch
-const FONT_SIZE: f32 = 12.0;
-const LINE_HEIGHT: f32 = 12.0; // Single-spaced Courier
-
-// Element indents (from left margin)
-const CHARACTER_INDENT: f32 = 144.0; // 2 inches from margin
-const DIALOGUE_INDENT: f32 = 72.0; // 1 inch from margin
-const DIALOGUE_WIDTH: f32 = 252.0; // 3.5 inches
-const PARENTHETICAL_INDENT: f32 = 108.0; // 1.5 inches from margin
-const PARENTHETICAL_WIDTH: f32 = 144.0; // 2 inches
+// Page layout and element geometry live in `crate::pagination`.
+const REVISION_MARK_OFFSET: f32 = 18.0; // asterisk in the right margin
+const SCENE_NUMBER_LEFT_X: f32 = MARGIN_LEFT - 54.0; // 0.75 inch left of the heading
+const SCENE_NUMBER_RIGHT_OFFSET: f32 = 12.0; // after the right margin
+const WATERMARK_SIZE: f32 = 72.0;
+const WATERMARK_ANGLE: f32 = 45.0;
+const HIGHLIGHT_PADDING: f32 = 2.0;
+const DIMMED_GRAY: f32 = 0.55;
 
 // Font metrics for line wrapping / centering estimates
-const COURIER_CHAR_WIDTH: f32 = 7.2; // Courier at 12pt
 const HELVETICA_CHAR_WIDTH_RATIO: f32 = 0.52; // average glyph width per pt of font size
 
 // Free write formatting (1 inch margins, proportional type)
@@ -45,6 +41,37 @@ const FW_TITLE_SPACE_AFTER: f32 = 6.0;
 const FW_HEADING_SPACE_BEFORE: f32 = 12.0;
 const FW_HEADING_SPACE_AFTER: f32 = 2.0;
 
+pub use crate::pagination::PaperSize;
+
+/// Page setup and markings for a PDF export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PdfOptions {
+    pub paper_size: PaperSize,
+    /// Drawn diagonally across every page, e.g. a recipient's name.
+    pub watermark: Option<String>,
+    /// Prints scene numbers in both margins of each scene heading. Locked
+    /// numbers from `attrs.sceneNumber` are kept; other scenes are counted.
+    pub scene_numbers: bool,
+    /// Marks revised lines with an asterisk in the right margin.
+    pub revision_marks: bool,
+    /// Character whose cues and dialogue are highlighted, with everyone
+    /// else's dialogue printed in gray, for sides.
+    pub highlight_character: Option<String>,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            paper_size: PaperSize::Letter,
+            watermark: None,
+            scene_numbers: false,
+            revision_marks: true,
+            highlight_character: None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TitlePageData {
     pub title: Option<String>,
@@ -86,6 +113,14 @@ struct TextStyle {
     size_pt: Option<f32>,
 }
 
+/// How the dialogue being written relates to `PdfOptions::highlight_character`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LineTint {
+    Normal,
+    Highlight,
+    Dim,
+}
+
 #[derive(Debug, Clone)]
 struct StyledSegment {
     text: String,
@@ -193,61 +228,14 @@ fn helvetica_width_units(c: char, bold: bool) -> u16 {
     }
 }
 
-/// Word-wraps styled characters by character count, mirroring `wrap_text`
+/// Word-wraps styled characters by character count, like the page layout
 fn wrap_styled(chars: &[(char, TextStyle)], max_chars: usize) -> Vec<Vec<(char, TextStyle)>> {
-    let mut words: Vec<Vec<(char, TextStyle)>> = Vec::new();
-    let mut current_word: Vec<(char, TextStyle)> = Vec::new();
-
-    for (c, style) in chars {
-        if c.is_whitespace() {
-            if !current_word.is_empty() {
-                words.push(std::mem::take(&mut current_word));
-            }
-        } else {
-            current_word.push((*c, style.clone()));
-        }
-    }
-    if !current_word.is_empty() {
-        words.push(current_word);
-    }
-
-    let mut lines: Vec<Vec<(char, TextStyle)>> = Vec::new();
-    let mut line: Vec<(char, TextStyle)> = Vec::new();
-
-    for word in words {
-        if line.is_empty() {
-            line = word;
-        } else if line.len() + 1 + word.len() <= max_chars {
-            let prev = line.last().map(|(_, s)| s.clone()).unwrap_or_default();
-            let next = word.first().map(|(_, s)| s.clone()).unwrap_or_default();
-            // Joining spaces keep decorations only when both neighbors share them
-            let space_style = TextStyle {
-                bold: prev.bold,
-                italic: prev.italic,
-                underline: prev.underline && next.underline,
-                strike: prev.strike && next.strike,
-                font_family: prev.font_family.clone(),
-                font_weight: prev.font_weight,
-                font_style: prev.font_style.clone(),
-                size_pt: prev.size_pt,
-            };
-            line.push((' ', space_style));
-            line.extend(word);
-        } else {
-            lines.push(std::mem::take(&mut line));
-            line = word;
-        }
-    }
-
-    if !line.is_empty() {
-        lines.push(line);
-    }
-
-    if lines.is_empty() {
-        lines.push(Vec::new());
-    }
-
-    lines
+    // Joining spaces keep decorations only when both neighbors share them
+    pagination::wrap(chars, max_chars, |prev, next| TextStyle {
+        underline: prev.underline && next.underline,
+        strike: prev.strike && next.strike,
+        ..prev.clone()
+    })
 }
 
 /// Groups a wrapped line into runs of identical style
@@ -286,17 +274,28 @@ pub struct PdfGenerator {
     external_font_metrics: HashMap<String, Option<ExternalFontMetrics>>,
     is_sans: bool,
     char_width: f32,
-    y_position: f32,
-    page_number: i32,
+    /// Position on the page and physical page number.
+    cursor: PageCursor,
     has_title_page: bool,
+    revision_mark: bool,
+    page_width: f32,
+    page_height: f32,
+    options: PdfOptions,
+    scene_count: u32,
+    /// Scene number printed beside the next line written.
+    pending_scene_number: Option<String>,
+    /// Cue name of the dialogue block being written, if any.
+    speaker: Option<String>,
+    line_tint: LineTint,
 }
 
 impl PdfGenerator {
-    pub fn new(title: &str, document_mode: &str) -> Result<Self, String> {
+    pub fn new(title: &str, document_mode: &str, options: &PdfOptions) -> Result<Self, String> {
+        let (page_width, page_height) = options.paper_size.dimensions();
         let (doc, page1, layer1) = PdfDocument::new(
             title,
-            Mm::from(Pt(PAGE_WIDTH)),
-            Mm::from(Pt(PAGE_HEIGHT)),
+            Mm::from(Pt(page_width)),
+            Mm::from(Pt(page_height)),
             "Layer 1",
         );
 
@@ -334,7 +333,7 @@ impl PdfGenerator {
             COURIER_CHAR_WIDTH
         };
 
-        Ok(Self {
+        let generator = Self {
             doc,
             current_page: page1,
             current_layer: layer1,
@@ -346,10 +345,19 @@ impl PdfGenerator {
             external_font_metrics: HashMap::new(),
             is_sans: is_freewrite,
             char_width,
-            y_position: PAGE_HEIGHT - MARGIN_TOP,
-            page_number: 1,
+            cursor: PageCursor::new(page_height),
             has_title_page: false,
-        })
+            revision_mark: false,
+            page_width,
+            page_height,
+            options: options.clone(),
+            scene_count: 0,
+            pending_scene_number: None,
+            speaker: None,
+            line_tint: LineTint::Normal,
+        };
+        generator.write_watermark();
+        Ok(generator)
     }
 
     fn external_font_request(style: &TextStyle) -> Option<(&str, u16, &str)> {
@@ -572,23 +580,54 @@ impl PdfGenerator {
 
     fn new_page(&mut self) {
         let (page, layer) = self.doc.add_page(
-            Mm::from(Pt(PAGE_WIDTH)),
-            Mm::from(Pt(PAGE_HEIGHT)),
+            Mm::from(Pt(self.page_width)),
+            Mm::from(Pt(self.page_height)),
             "Layer 1",
         );
         self.current_page = page;
         self.current_layer = layer;
-        self.y_position = PAGE_HEIGHT - MARGIN_TOP;
-        self.page_number += 1;
+        self.cursor.next_page();
+        self.write_watermark();
 
         // Add page number (top right)
         // Skip numbering on pages 1-2 when there's a title page (title page + first content page)
         // Page numbering starts at "2." on the 3rd physical page
-        if !self.has_title_page || self.page_number > 2 {
+        if !self.has_title_page || self.cursor.page() > 2 {
             self.write_page_number();
         }
     }
 
+    /// Draws the watermark in light grey beneath the page's text.
+    fn write_watermark(&self) {
+        let Some(watermark) = self
+            .options
+            .watermark
+            .as_deref()
+            .map(str::trim)
+            .filter(|watermark| !watermark.is_empty())
+        else {
+            return;
+        };
+        let layer = self
+            .doc
+            .get_page(self.current_page)
+            .get_layer(self.current_layer);
+
+        // Center the rotated text on the page.
+        let width = watermark.chars().count() as f32 * self.char_width / FONT_SIZE * WATERMARK_SIZE;
+        let angle = WATERMARK_ANGLE.to_radians();
+        let x = (self.page_width - width * angle.cos()) / 2.0;
+        let y = (self.page_height - width * angle.sin()) / 2.0;
+
+        layer.begin_text_section();
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.85, None)));
+        layer.set_font(&self.bold_font, WATERMARK_SIZE);
+        layer.set_text_matrix(TextMatrix::TranslateRotate(Pt(x), Pt(y), WATERMARK_ANGLE));
+        layer.write_text(watermark, &self.bold_font);
+        layer.end_text_section();
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+    }
+
     fn write_page_number(&self) {
         let layer = self
             .doc
@@ -596,24 +635,23 @@ impl PdfGenerator {
             .get_layer(self.current_layer);
         // When there's a title page, subtract 1 so 3rd physical page shows "2."
         let display_number = if self.has_title_page {
-            self.page_number - 1
+            self.cursor.page() - 1
         } else {
-            self.page_number
+            self.cursor.page()
         };
         let page_num = format!("{}.", display_number);
 
         layer.use_text(
             &page_num,
             FONT_SIZE as f32,
-            Mm::from(Pt(PAGE_WIDTH - MARGIN_RIGHT - 20.0)),
-            Mm::from(Pt(PAGE_HEIGHT - 36.0)), // 0.5 inch from top
+            Mm::from(Pt(self.page_width - MARGIN_RIGHT - 20.0)),
+            Mm::from(Pt(self.page_height - 36.0)), // 0.5 inch from top
             &self.font,
         );
     }
 
     fn check_page_break(&mut self, lines_needed: i32) {
-        let space_needed = lines_needed as f32 * LINE_HEIGHT;
-        if self.y_position - space_needed < MARGIN_BOTTOM {
+        if !self.cursor.fits(lines_needed as f32 * LINE_HEIGHT) {
             self.new_page();
         }
     }
@@ -634,15 +672,15 @@ impl PdfGenerator {
             text,
             FONT_SIZE,
             Mm::from(Pt(MARGIN_LEFT + x_offset)),
-            Mm::from(Pt(self.y_position)),
+            Mm::from(Pt(self.cursor.y())),
             &font,
         );
 
-        self.y_position -= LINE_HEIGHT;
+        self.cursor.advance(LINE_HEIGHT);
     }
 
     fn write_blank_line(&mut self) {
-        self.y_position -= LINE_HEIGHT;
+        self.cursor.advance(LINE_HEIGHT);
     }
 
     fn get_node_text(node: &DocumentNode) -> String {
@@ -801,7 +839,7 @@ impl PdfGenerator {
         default_alignment: &str,
     ) {
         let space_needed = self.styled_lines_height(lines, size, line_height);
-        if self.y_position - space_needed < MARGIN_BOTTOM {
+        if !self.cursor.fits(space_needed) {
             self.new_page();
         }
 
@@ -821,11 +859,11 @@ impl PdfGenerator {
         line_height: f32,
     ) {
         let line_advance = self.styled_line_advance(segments, size, line_height);
-        if self.y_position - line_advance < MARGIN_BOTTOM {
+        if !self.cursor.fits(line_advance) {
             self.new_page();
         }
 
-        let y = self.y_position
+        let y = self.cursor.y()
             - Self::styled_line_baseline_adjust(segments, size, line_height, line_advance);
         let mut runs = Vec::new();
         for segment in segments {
@@ -839,6 +877,27 @@ impl PdfGenerator {
             .get_page(self.current_page)
             .get_layer(self.current_layer);
 
+        match self.line_tint {
+            LineTint::Highlight => {
+                let width = runs.iter().map(|(_, _, _, width)| width).sum::<f32>();
+                layer.set_fill_color(Color::Rgb(Rgb::new(1.0, 0.93, 0.45, None)));
+                layer.add_rect(
+                    Rect::new(
+                        Mm::from(Pt(x - HIGHLIGHT_PADDING)),
+                        Mm::from(Pt(y - size * 0.3)),
+                        Mm::from(Pt(x + width + HIGHLIGHT_PADDING)),
+                        Mm::from(Pt(y + size * 0.85)),
+                    )
+                    .with_mode(PaintMode::Fill),
+                );
+                layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+            }
+            LineTint::Dim => {
+                layer.set_fill_color(Color::Greyscale(Greyscale::new(DIMMED_GRAY, None)));
+            }
+            LineTint::Normal => {}
+        }
+
         layer.begin_text_section();
         layer.set_text_cursor(Mm::from(Pt(x)), Mm::from(Pt(y)));
         for (segment, font, segment_size, _) in &runs {
@@ -846,6 +905,34 @@ impl PdfGenerator {
             layer.write_text(&segment.text, font);
         }
         layer.end_text_section();
+        if self.line_tint == LineTint::Dim {
+            layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+        }
+
+        if self.revision_mark && self.options.revision_marks {
+            layer.use_text(
+                "*",
+                FONT_SIZE,
+                Mm::from(Pt(self.page_width - MARGIN_RIGHT + REVISION_MARK_OFFSET)),
+                Mm::from(Pt(y)),
+                &self.font,
+            );
+        }
+
+        if let Some(number) = self.pending_scene_number.take() {
+            for x in [
+                SCENE_NUMBER_LEFT_X,
+                self.page_width - MARGIN_RIGHT + SCENE_NUMBER_RIGHT_OFFSET,
+            ] {
+                layer.use_text(
+                    &number,
+                    FONT_SIZE,
+                    Mm::from(Pt(x)),
+                    Mm::from(Pt(y)),
+                    &self.font,
+                );
+            }
+        }
 
         // Underline / strikethrough rules
         let mut cursor_x = x;
@@ -871,7 +958,7 @@ impl PdfGenerator {
             cursor_x += *width;
         }
 
-        self.y_position -= line_advance;
+        self.cursor.advance(line_advance);
     }
 
     fn draw_rule(layer: &PdfLayerReference, x: f32, y: f32, width: f32, thickness: f32) {
@@ -913,10 +1000,10 @@ impl PdfGenerator {
 
         // Calculate vertical position for centering
         let total_height = lines_to_render.len() as f32 * LINE_HEIGHT;
-        self.y_position = (PAGE_HEIGHT + total_height) / 2.0;
+        self.cursor.set_y((self.page_height + total_height) / 2.0);
 
         // Center the title block
-        let content_width = PAGE_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        let content_width = self.page_width - MARGIN_LEFT - MARGIN_RIGHT;
 
         for (text, _is_title) in &lines_to_render {
             if text.is_empty() {
@@ -931,7 +1018,7 @@ impl PdfGenerator {
 
         // Contact info at bottom left
         if let Some(contact) = &title_page.contact {
-            self.y_position = MARGIN_BOTTOM + 72.0; // 1 inch above bottom margin
+            self.cursor.set_y(MARGIN_BOTTOM + 72.0); // 1 inch above bottom margin
             for line in contact.lines() {
                 self.write_line(line, 0.0);
             }
@@ -940,15 +1027,15 @@ impl PdfGenerator {
         // Copyright at bottom left (below contact or at same position)
         if let Some(copyright) = &title_page.copyright {
             if title_page.contact.is_none() {
-                self.y_position = MARGIN_BOTTOM + 72.0;
+                self.cursor.set_y(MARGIN_BOTTOM + 72.0);
             }
             self.write_line(copyright, 0.0);
         }
 
         // Draft date at bottom right
         if let Some(draft_date) = &title_page.draft_date {
-            self.y_position = MARGIN_BOTTOM + 72.0;
-            let content_width = PAGE_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+            self.cursor.set_y(MARGIN_BOTTOM + 72.0);
+            let content_width = self.page_width - MARGIN_LEFT - MARGIN_RIGHT;
             let text_width = draft_date.len() as f32 * self.char_width;
             self.write_line(draft_date, content_width - text_width);
         }
@@ -995,15 +1082,15 @@ impl PdfGenerator {
     }
 
     fn ensure_freewrite_space(&mut self, line_height: f32) {
-        if self.y_position - line_height < MARGIN_BOTTOM {
+        if !self.cursor.fits(line_height) {
             self.new_page();
         }
     }
 
     fn freewrite_space_before(&mut self, space: f32) {
         // Skip spacing at the top of a page
-        if self.y_position < PAGE_HEIGHT - MARGIN_TOP - 0.5 {
-            self.y_position -= space;
+        if !self.cursor.at_top() {
+            self.cursor.advance(space);
         }
     }
 
@@ -1018,13 +1105,13 @@ impl PdfGenerator {
             text,
             size,
             Mm::from(Pt(x)),
-            Mm::from(Pt(self.y_position)),
+            Mm::from(Pt(self.cursor.y())),
             font,
         );
     }
 
     fn render_freewrite_node(&mut self, node: &DocumentNode, list_number: i32) {
-        let max_width = PAGE_WIDTH - FW_MARGIN_LEFT - FW_MARGIN_RIGHT;
+        let max_width = self.page_width - FW_MARGIN_LEFT - FW_MARGIN_RIGHT;
         let max_chars =
             |size: f32, width: f32| ((width / (size * HELVETICA_CHAR_WIDTH_RATIO)) as usize).max(1);
         let bold_base = TextStyle {
@@ -1045,7 +1132,7 @@ impl PdfGenerator {
                     FW_TITLE_SIZE,
                     FW_TITLE_LINE_HEIGHT,
                 );
-                self.y_position -= FW_TITLE_SPACE_AFTER;
+                self.cursor.advance(FW_TITLE_SPACE_AFTER);
             }
             "heading" => {
                 self.freewrite_space_before(FW_HEADING_SPACE_BEFORE);
@@ -1063,7 +1150,7 @@ impl PdfGenerator {
                     FW_HEADING_SIZE,
                     FW_HEADING_LINE_HEIGHT,
                 );
-                self.y_position -= FW_HEADING_SPACE_AFTER;
+                self.cursor.advance(FW_HEADING_SPACE_AFTER);
             }
             "bulletItem" | "numberedItem" => {
                 let lines = Self::styled_lines(
@@ -1095,7 +1182,7 @@ impl PdfGenerator {
                     FW_BODY_SIZE,
                     FW_BODY_LINE_HEIGHT,
                 );
-                self.y_position -= FW_LIST_SPACING;
+                self.cursor.advance(FW_LIST_SPACING);
             }
             // "body" and anything unexpected render as plain paragraphs
             _ => {
@@ -1113,207 +1200,118 @@ impl PdfGenerator {
                     FW_BODY_SIZE,
                     FW_BODY_LINE_HEIGHT,
                 );
-                self.y_position -= FW_BLOCK_SPACING;
+                self.cursor.advance(FW_BLOCK_SPACING);
             }
         }
     }
 
-    fn render_node(&mut self, node: &DocumentNode, _document_mode: &str) {
-        let text = Self::get_node_text(node);
-        if text.trim().is_empty() && node.node_type != "pageBreak" {
-            return;
-        }
-        let content_width = PAGE_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
-        let content_max_chars = (content_width / self.char_width) as usize;
+    /// Blocks changed under a revision carry its id in `attrs.revision`.
+    fn is_revised(node: &DocumentNode) -> bool {
+        node.attrs
+            .as_ref()
+            .and_then(|attrs| attrs.get("revision"))
+            .and_then(|value| value.as_str())
+            .map(|revision| !revision.is_empty())
+            .unwrap_or(false)
+    }
 
+    /// The heading's locked number, or the next number in sequence.
+    fn scene_number(&mut self, node: &DocumentNode) -> String {
+        self.scene_count += 1;
+        node.attrs
+            .as_ref()
+            .and_then(|attrs| attrs.get("sceneNumber"))
+            .and_then(|value| match value {
+                serde_json::Value::String(number) => Some(number.trim().to_string()),
+                serde_json::Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            })
+            .filter(|number| !number.is_empty())
+            .unwrap_or_else(|| self.scene_count.to_string())
+    }
+
+    /// Follows cues through dialogue blocks to tint lines for sides.
+    fn dialogue_tint(&mut self, node: &DocumentNode) -> LineTint {
+        let Some(highlight) = self.options.highlight_character.as_deref() else {
+            return LineTint::Normal;
+        };
         match node.node_type.as_str() {
-            "comicPage" => {
-                self.check_page_break(2);
-                let lines =
-                    Self::styled_lines(node, TextStyle::default(), true, content_max_chars.max(1));
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT,
-                    content_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-                self.write_blank_line();
-            }
-            "comicPanel" => {
-                self.write_blank_line();
-                self.check_page_break(2);
-                let lines =
-                    Self::styled_lines(node, TextStyle::default(), true, content_max_chars.max(1));
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT,
-                    content_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-                self.write_blank_line();
-            }
-            "caption" => {
-                let max_width = content_width - 36.0;
-                let max_chars = (max_width / self.char_width) as usize;
-                let lines = Self::styled_lines(node, TextStyle::default(), false, max_chars);
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT + 36.0,
-                    max_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-            }
-            "soundEffect" => {
-                let max_width = content_width - 36.0;
-                let max_chars = (max_width / self.char_width) as usize;
-                let lines = Self::styled_lines(node, TextStyle::default(), true, max_chars);
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT + 36.0,
-                    max_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-            }
-            "sceneHeading" => {
-                self.write_blank_line();
-                let lines = Self::styled_lines(
-                    node,
-                    TextStyle {
-                        bold: true,
-                        ..TextStyle::default()
-                    },
-                    true,
-                    content_max_chars.max(1),
-                );
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT,
-                    content_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-                self.write_blank_line();
-            }
-            "action" => {
-                self.write_blank_line();
-                let lines =
-                    Self::styled_lines(node, TextStyle::default(), false, content_max_chars.max(1));
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT,
-                    content_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-            }
             "character" => {
-                self.write_blank_line();
-                let suffix = node
-                    .attrs
-                    .as_ref()
-                    .and_then(|attrs| attrs.get("extension"))
-                    .and_then(|value| value.as_str())
-                    .map(|extension| format!(" ({})", extension))
-                    .unwrap_or_default();
-                let max_width = (content_width - CHARACTER_INDENT).max(self.char_width);
-                let max_chars = (max_width / self.char_width) as usize;
-                let lines = Self::styled_lines_with_affixes(
-                    node,
-                    TextStyle::default(),
-                    true,
-                    max_chars.max(1),
-                    "",
-                    &suffix,
-                );
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT + CHARACTER_INDENT,
-                    max_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-            }
-            "dialogue" => {
-                let max_chars = (DIALOGUE_WIDTH / self.char_width) as usize;
-                let lines = Self::styled_lines(node, TextStyle::default(), false, max_chars.max(1));
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT + DIALOGUE_INDENT,
-                    DIALOGUE_WIDTH,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-            }
-            "parenthetical" => {
-                let max_chars = (PARENTHETICAL_WIDTH / self.char_width) as usize;
-                let lines = Self::styled_lines_with_affixes(
-                    node,
-                    TextStyle::default(),
-                    false,
-                    max_chars.max(1),
-                    "(",
-                    ")",
-                );
-                self.write_styled_lines_aligned(
-                    node,
-                    &lines,
-                    MARGIN_LEFT + PARENTHETICAL_INDENT,
-                    PARENTHETICAL_WIDTH,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                );
-            }
-            "transition" => {
-                self.write_blank_line();
-                let lines =
-                    Self::styled_lines(node, TextStyle::default(), true, content_max_chars.max(1));
-                self.write_styled_lines_aligned_with_default(
-                    node,
-                    &lines,
-                    MARGIN_LEFT,
-                    content_width,
-                    FONT_SIZE,
-                    LINE_HEIGHT,
-                    "right",
-                );
-                self.write_blank_line();
-            }
-            "pageBreak" => {
-                self.new_page();
+                self.speaker = Some(document::character_cue_name(&Self::get_node_text(node)));
             }
+            "parenthetical" | "dialogue" => {}
             _ => {
-                // Unknown node type, render as action
-                if !text.is_empty() {
-                    self.write_blank_line();
-                    let lines = Self::styled_lines(
-                        node,
-                        TextStyle::default(),
-                        false,
-                        content_max_chars.max(1),
-                    );
-                    self.write_styled_lines_aligned(
-                        node,
-                        &lines,
-                        MARGIN_LEFT,
-                        content_width,
-                        FONT_SIZE,
-                        LINE_HEIGHT,
-                    );
-                }
+                self.speaker = None;
+                return LineTint::Normal;
             }
         }
+        match &self.speaker {
+            Some(speaker) if *speaker == document::character_cue_name(highlight) => {
+                LineTint::Highlight
+            }
+            Some(_) => LineTint::Dim,
+            None => LineTint::Normal,
+        }
+    }
+
+    fn render_node(&mut self, node: &DocumentNode, document_mode: &str) {
+        self.revision_mark = Self::is_revised(node);
+        self.line_tint = self.dialogue_tint(node);
+        self.render_screenplay_node(node, document_mode);
+        self.revision_mark = false;
+        self.line_tint = LineTint::Normal;
+    }
+
+    fn render_screenplay_node(&mut self, node: &DocumentNode, _document_mode: &str) {
+        let text = Self::get_node_text(node);
+        if node.node_type == "pageBreak" {
+            self.new_page();
+            return;
+        }
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let format =
+            ElementFormat::for_type(&node.node_type, self.options.paper_size.content_width());
+        if format.blank_before {
+            self.write_blank_line();
+        }
+        if format.keep_lines > 0 {
+            self.check_page_break(format.keep_lines as i32);
+        }
+        if node.node_type == "sceneHeading" && self.options.scene_numbers {
+            self.pending_scene_number = Some(self.scene_number(node));
+        }
+
+        let suffix = if node.node_type == "character" {
+            pagination::character_suffix(node.attrs.as_ref())
+        } else {
+            format.suffix.to_string()
+        };
+        let lines = Self::styled_lines_with_affixes(
+            node,
+            TextStyle {
+                bold: format.bold,
+                ..TextStyle::default()
+            },
+            format.uppercase,
+            format.max_chars(),
+            format.prefix,
+            &suffix,
+        );
+        self.write_styled_lines_aligned_with_default(
+            node,
+            &lines,
+            MARGIN_LEFT + format.indent,
+            format.width,
+            FONT_SIZE,
+            LINE_HEIGHT,
+            format.align,
+        );
+        if format.blank_after {
+            self.write_blank_line();
+        }
     }
 
     pub fn save(self, path: &str) -> Result<(), String> {
@@ -1334,6 +1332,24 @@ pub fn generate_pdf(
     output_path: &str,
     document_title: &str,
     document_mode: &str,
+) -> Result<(), String> {
+    generate_pdf_with_options(
+        content_json,
+        title_page_json,
+        output_path,
+        document_title,
+        document_mode,
+        &PdfOptions::default(),
+    )
+}
+
+pub fn generate_pdf_with_options(
+    content_json: &str,
+    title_page_json: Option<&str>,
+    output_path: &str,
+    document_title: &str,
+    document_mode: &str,
+    options: &PdfOptions,
 ) -> Result<(), String> {
     let content: ScreenplayContent = serde_json::from_str(content_json)
         .map_err(|e| format!("Failed to parse content: {}", e))?;
@@ -1347,7 +1363,7 @@ pub fn generate_pdf(
         None
     };
 
-    let mut generator = PdfGenerator::new(document_title, document_mode)?;
+    let mut generator = PdfGenerator::new(document_title, document_mode, options)?;
 
     if let Some(tp) = title_page {
         generator.render_title_page(&tp);
@@ -1500,4 +1516,44 @@ mod tests {
             "screenplay PDFs should not use Helvetica"
         );
     }
+
+    #[test]
+    fn applies_page_setup_options() {
+        let content = format!(
+            r#"{{"type":"doc","content":[{},{}]}}"#,
+            text_node("sceneHeading", "INT. OFFICE - DAY"),
+            text_node("action", "The phone rings.")
+        );
+        let render = |options: &PdfOptions, filename: &str| {
+            let path = std::env::temp_dir().join(filename);
+            generate_pdf_with_options(
+                &content,
+                None,
+                &path.to_string_lossy(),
+                "Test",
+                "screenplay",
+                options,
+            )
+            .unwrap();
+            std::fs::read(&path).unwrap()
+        };
+
+        let plain = render(&PdfOptions::default(), "grainery-options-plain-test.pdf");
+        let production = render(
+            &PdfOptions {
+                paper_size: PaperSize::A4,
+                watermark: Some("J. Smith".to_string()),
+                scene_numbers: true,
+                revision_marks: false,
+                highlight_character: None,
+            },
+            "grainery-options-production-test.pdf",
+        );
+        assert!(production.starts_with(b"%PDF"));
+        assert!(production.len() > plain.len());
+
+        let partial: PdfOptions = serde_json::from_str(r#"{"paperSize":"a4"}"#).unwrap();
+        assert_eq!(partial.paper_size, PaperSize::A4);
+        assert!(partial.revision_marks);
+    }
 }
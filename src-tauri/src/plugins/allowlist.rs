@@ -0,0 +1,176 @@
+use reqwest::Url;
+use std::net::IpAddr;
+
+const HTTPS_PORT: u16 = 443;
+
+/// A parsed allowlist entry: `host`, `*.host` (subdomains only), either
+/// optionally pinned to a port with `:port`. Unpinned entries allow only the
+/// default HTTPS port.
+#[derive(Debug, PartialEq)]
+struct Pattern {
+    wildcard: bool,
+    host: String,
+    port: u16,
+}
+
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '-')
+}
+
+fn parse_pattern(entry: &str) -> Result<Pattern, String> {
+    let entry = entry.trim().to_ascii_lowercase();
+    if entry.contains("://") || entry.contains('/') {
+        return Err(format!(
+            "Network allowlist entry '{}' must be a host, not a URL",
+            entry
+        ));
+    }
+
+    let (host, port) = match entry.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port != 0)
+                .ok_or_else(|| {
+                    format!("Network allowlist entry '{}' has an invalid port", entry)
+                })?;
+            (host.to_string(), port)
+        }
+        None => (entry.clone(), HTTPS_PORT),
+    };
+
+    let (wildcard, host) = match host.strip_prefix("*.") {
+        Some(rest) => (true, rest.to_string()),
+        None => (false, host),
+    };
+
+    if host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return Err(format!(
+            "Network allowlist entry '{}' is an IP address; use a host name",
+            entry
+        ));
+    }
+
+    let labels = host.split('.').collect::<Vec<_>>();
+    if !labels.iter().all(|label| is_valid_label(label)) {
+        return Err(format!(
+            "Network allowlist entry '{}' is not a valid host name",
+            entry
+        ));
+    }
+    if wildcard && labels.len() < 2 {
+        return Err(format!(
+            "Network allowlist wildcard '{}' must cover a registrable domain, e.g. *.example.com",
+            entry
+        ));
+    }
+
+    Ok(Pattern {
+        wildcard,
+        host,
+        port,
+    })
+}
+
+/// Checks allowlist syntax at install time.
+pub fn validate(entries: &[String]) -> Result<(), String> {
+    for entry in entries {
+        parse_pattern(entry)?;
+    }
+    Ok(())
+}
+
+/// Returns an error unless `url` is https, addressed by host name, and
+/// matches one of the entries.
+pub fn check(entries: &[String], url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url).map_err(|error| format!("Invalid URL '{}': {}", url, error))?;
+
+    if parsed.scheme() != "https" {
+        return Err("Only https:// URLs are allowed for plugin network calls".to_string());
+    }
+
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "URL is missing a host".to_string())?
+        .to_ascii_lowercase();
+    if host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return Err("IP address hosts are not allowed for plugin network calls".to_string());
+    }
+    let port = parsed.port_or_known_default().unwrap_or(HTTPS_PORT);
+
+    if entries.is_empty() {
+        return Err("Plugin has an empty network allowlist".to_string());
+    }
+
+    let allowed = entries
+        .iter()
+        .filter_map(|entry| parse_pattern(entry).ok())
+        .any(|pattern| {
+            let host_matches = if pattern.wildcard {
+                host.strip_suffix(&pattern.host)
+                    .is_some_and(|prefix| prefix.ends_with('.') && prefix.len() > 1)
+            } else {
+                host == pattern.host
+            };
+            host_matches && port == pattern.port
+        });
+
+    if !allowed {
+        return Err(format!(
+            "Host '{}' (port {}) is not in plugin allowlist ({})",
+            host,
+            port,
+            entries.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wildcards_and_pinned_ports() {
+        let entries = vec![
+            "api.example.com".to_string(),
+            "*.cdn.example.org".to_string(),
+            "local.example.net:8443".to_string(),
+        ];
+
+        assert!(check(&entries, "https://api.example.com/v1").is_ok());
+        assert!(check(&entries, "https://API.example.com:443/v1").is_ok());
+        assert!(check(&entries, "https://api.example.com:8443/v1").is_err());
+        assert!(check(&entries, "https://img.cdn.example.org/a.png").is_ok());
+        assert!(check(&entries, "https://a.b.cdn.example.org/").is_ok());
+        assert!(check(&entries, "https://cdn.example.org/").is_err());
+        assert!(check(&entries, "https://evilcdn.example.org/").is_err());
+        assert!(check(&entries, "https://local.example.net:8443/").is_ok());
+        assert!(check(&entries, "https://local.example.net/").is_err());
+        assert!(check(&entries, "https://93.184.216.34/").is_err());
+        assert!(check(&entries, "http://api.example.com/").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        for entry in [
+            "*.com",
+            "10.0.0.1",
+            "[::1]:443",
+            "https://example.com",
+            "*.",
+            "a..b",
+        ] {
+            assert!(validate(&[entry.to_string()]).is_err(), "{}", entry);
+        }
+        assert!(validate(&["*.example.com:8080".to_string()]).is_ok());
+    }
+}
@@ -0,0 +1,260 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ARCHIVES: usize = 5;
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    pub timestamp: String,
+    pub plugin_id: String,
+    pub operation: String,
+    #[serde(default)]
+    pub payload: Value,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogQuery {
+    pub plugin_id: Option<String>,
+    /// Exact operation, or a namespace such as `fs:` to match all of it.
+    pub operation: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+    /// Also search rotated archives; defaults to the active log only.
+    #[serde(default)]
+    pub include_archives: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditLogEntry>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub has_more: bool,
+}
+
+fn archive_path(log_path: &Path, index: usize) -> PathBuf {
+    log_path.with_extension(format!("{}.log.zip", index))
+}
+
+/// Compresses the active log into `<name>.1.log.zip` once it passes the size
+/// cap, shifting older archives down and dropping the oldest.
+pub fn rotate_if_needed(log_path: &Path) -> Result<(), String> {
+    let size = match fs::metadata(log_path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return Ok(()),
+    };
+    if size <= MAX_LOG_BYTES {
+        return Ok(());
+    }
+
+    let oldest = archive_path(log_path, MAX_ARCHIVES);
+    if oldest.exists() {
+        fs::remove_file(&oldest)
+            .map_err(|error| format!("Failed to remove old audit log archive: {}", error))?;
+    }
+    for index in (1..MAX_ARCHIVES).rev() {
+        let from = archive_path(log_path, index);
+        if from.exists() {
+            fs::rename(&from, archive_path(log_path, index + 1))
+                .map_err(|error| format!("Failed to rotate audit log archive: {}", error))?;
+        }
+    }
+
+    let content =
+        fs::read(log_path).map_err(|error| format!("Failed to read audit log: {}", error))?;
+    let file = fs::File::create(archive_path(log_path, 1))
+        .map_err(|error| format!("Failed to create audit log archive: {}", error))?;
+    let mut writer = ZipWriter::new(file);
+    let entry_name = log_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("audit.log");
+    writer
+        .start_file(
+            entry_name,
+            SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+        )
+        .map_err(|error| format!("Failed to write audit log archive: {}", error))?;
+    writer
+        .write_all(&content)
+        .map_err(|error| format!("Failed to write audit log archive: {}", error))?;
+    writer
+        .finish()
+        .map_err(|error| format!("Failed to finish audit log archive: {}", error))?;
+
+    fs::write(log_path, b"").map_err(|error| format!("Failed to truncate audit log: {}", error))
+}
+
+fn read_archive(path: &Path) -> Result<String, String> {
+    let bytes =
+        fs::read(path).map_err(|error| format!("Failed to read audit log archive: {}", error))?;
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|error| format!("Failed to open audit log archive: {}", error))?;
+    let mut file = archive
+        .by_index(0)
+        .map_err(|error| format!("Failed to open audit log archive: {}", error))?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .map_err(|error| format!("Failed to read audit log archive: {}", error))?;
+    Ok(content)
+}
+
+fn parse_lines(content: impl BufRead, entries: &mut Vec<AuditLogEntry>) {
+    // Partial or hand-edited lines are skipped rather than failing the query.
+    entries.extend(
+        content
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<AuditLogEntry>(&line).ok()),
+    );
+}
+
+/// Reads entries oldest-first from the archives (if requested) and the active log.
+fn read_entries(log_path: &Path, include_archives: bool) -> Result<Vec<AuditLogEntry>, String> {
+    let mut entries = Vec::new();
+    if include_archives {
+        for index in (1..=MAX_ARCHIVES).rev() {
+            let path = archive_path(log_path, index);
+            if path.exists() {
+                parse_lines(Cursor::new(read_archive(&path)?), &mut entries);
+            }
+        }
+    }
+
+    if log_path.exists() {
+        let file = fs::File::open(log_path)
+            .map_err(|error| format!("Failed to open plugin audit log: {}", error))?;
+        parse_lines(BufReader::new(file), &mut entries);
+    }
+
+    Ok(entries)
+}
+
+fn parse_time(
+    value: &Option<String>,
+    field: &str,
+) -> Result<Option<DateTime<FixedOffset>>, String> {
+    value
+        .as_deref()
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map_err(|error| format!("Invalid '{}' timestamp: {}", field, error))
+        })
+        .transpose()
+}
+
+fn operation_matches(filter: &str, operation: &str) -> bool {
+    if filter.ends_with(':') {
+        operation.starts_with(filter)
+    } else {
+        operation == filter
+    }
+}
+
+/// Filters and pages entries, newest first.
+pub fn filter_entries(
+    entries: Vec<AuditLogEntry>,
+    query: &AuditLogQuery,
+) -> Result<AuditLogPage, String> {
+    let since = parse_time(&query.since, "since")?;
+    let until = parse_time(&query.until, "until")?;
+
+    let matching = entries
+        .into_iter()
+        .rev()
+        .filter(|entry| match &query.plugin_id {
+            Some(plugin_id) => &entry.plugin_id == plugin_id,
+            None => true,
+        })
+        .filter(|entry| match &query.operation {
+            Some(operation) => operation_matches(operation, &entry.operation),
+            None => true,
+        })
+        .filter(|entry| {
+            if since.is_none() && until.is_none() {
+                return true;
+            }
+            let Ok(timestamp) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+                return false;
+            };
+            since.is_none_or(|since| timestamp >= since)
+                && until.is_none_or(|until| timestamp <= until)
+        })
+        .collect::<Vec<_>>();
+
+    let total = matching.len();
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let entries = matching
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .collect::<Vec<_>>();
+
+    Ok(AuditLogPage {
+        has_more: offset.saturating_add(entries.len()) < total,
+        entries,
+        total,
+        offset,
+        limit,
+    })
+}
+
+pub fn query(log_path: &Path, query: &AuditLogQuery) -> Result<AuditLogPage, String> {
+    filter_entries(read_entries(log_path, query.include_archives)?, query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(timestamp: &str, plugin_id: &str, operation: &str) -> AuditLogEntry {
+        AuditLogEntry {
+            timestamp: timestamp.to_string(),
+            plugin_id: plugin_id.to_string(),
+            operation: operation.to_string(),
+            payload: json!({}),
+        }
+    }
+
+    #[test]
+    fn filters_by_plugin_namespace_and_time_newest_first() {
+        let entries = vec![
+            entry("2026-01-01T00:00:00+00:00", "alpha", "fs:read"),
+            entry("2026-01-02T00:00:00+00:00", "beta", "fs:write"),
+            entry("2026-01-03T00:00:00+00:00", "alpha", "fs:write"),
+            entry("2026-01-04T00:00:00+00:00", "alpha", "storage:get"),
+        ];
+        let query = AuditLogQuery {
+            plugin_id: Some("alpha".to_string()),
+            operation: Some("fs:".to_string()),
+            since: Some("2026-01-01T12:00:00+00:00".to_string()),
+            limit: Some(10),
+            ..AuditLogQuery::default()
+        };
+
+        let page = filter_entries(entries, &query).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].operation, "fs:write");
+        assert!(!page.has_more);
+    }
+}
@@ -0,0 +1,57 @@
+use super::{has_permission, InstalledPlugin};
+use serde_json::{json, Value};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+const MAX_CLIPBOARD_BYTES: usize = 1024 * 1024;
+
+pub fn handle(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    operation: &str,
+    payload: &Value,
+) -> Result<Value, String> {
+    if !has_permission(plugin, operation) {
+        return Err(format!("Permission denied: {}", operation));
+    }
+
+    match operation {
+        "clipboard:read" => {
+            let text = app
+                .clipboard()
+                .read_text()
+                .map_err(|error| format!("Failed to read clipboard: {}", error))?;
+            Ok(json!({ "text": text }))
+        }
+
+        "clipboard:write" => {
+            let text = payload
+                .get("text")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Payload must include string field 'text'".to_string())?;
+            let html = payload.get("html").and_then(Value::as_str);
+            let size = text.len() + html.map_or(0, str::len);
+            if size > MAX_CLIPBOARD_BYTES {
+                return Err(format!(
+                    "Clipboard content exceeds {} byte limit",
+                    MAX_CLIPBOARD_BYTES
+                ));
+            }
+
+            match html {
+                // Rich targets get the HTML; plain-text pastes get `text`.
+                Some(html) => app
+                    .clipboard()
+                    .write_html(html, Some(text))
+                    .map_err(|error| format!("Failed to write clipboard: {}", error))?,
+                None => app
+                    .clipboard()
+                    .write_text(text)
+                    .map_err(|error| format!("Failed to write clipboard: {}", error))?,
+            }
+            Ok(json!({ "ok": true }))
+        }
+
+        _ => Err(format!("Unsupported host operation '{}'", operation)),
+    }
+}
@@ -0,0 +1,233 @@
+use super::InstalledPlugin;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const PERMISSION_REQUEST_EVENT: &str = "plugin-permission-request";
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PermissionRequest {
+    request_id: String,
+    plugin_id: String,
+    permission: String,
+    operation: String,
+}
+
+/// Calls waiting on a prompt another call opened for the same plugin and
+/// permission. They get the plugin with the decision recorded, or `None` when
+/// the prompt ended without one.
+type PromptWaiters = Vec<Sender<Option<InstalledPlugin>>>;
+
+/// Pending consent prompts, keyed by request id. Only one prompt is open per
+/// plugin and permission; concurrent calls wait for its answer. Denials are
+/// remembered for the session so a plugin can't re-prompt on every call; the
+/// user can still grant the permission from settings.
+#[derive(Default)]
+pub struct PermissionBroker {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<String, Sender<bool>>>,
+    open: Mutex<HashMap<(String, String), PromptWaiters>>,
+    denied: Mutex<HashSet<(String, String)>>,
+}
+
+/// Marks a prompt as open while its owner waits for the answer. Dropping it
+/// closes the prompt, so waiters aren't left hanging if the owner goes away.
+struct OpenPrompt<'a> {
+    broker: &'a PermissionBroker,
+    key: (String, String),
+}
+
+impl OpenPrompt<'_> {
+    fn close(self) -> PromptWaiters {
+        self.broker
+            .open
+            .lock()
+            .ok()
+            .and_then(|mut open| open.remove(&self.key))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for OpenPrompt<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut open) = self.broker.open.lock() {
+            open.remove(&self.key);
+        }
+    }
+}
+
+impl PermissionBroker {
+    fn is_denied(&self, plugin_id: &str, permission: &str) -> bool {
+        self.denied
+            .lock()
+            .map(|denied| denied.contains(&(plugin_id.to_string(), permission.to_string())))
+            .unwrap_or(false)
+    }
+
+    fn remember(&self, plugin_id: &str, permission: &str, granted: bool) {
+        if let Ok(mut denied) = self.denied.lock() {
+            let key = (plugin_id.to_string(), permission.to_string());
+            if granted {
+                denied.remove(&key);
+            } else {
+                denied.insert(key);
+            }
+        }
+    }
+
+    pub fn respond(&self, request_id: &str, granted: bool) -> Result<(), String> {
+        let sender = self
+            .pending
+            .lock()
+            .map_err(|_| "Permission broker is unavailable".to_string())?
+            .remove(request_id)
+            .ok_or_else(|| format!("Permission request '{}' is not pending", request_id))?;
+        sender
+            .send(granted)
+            .map_err(|_| format!("Permission request '{}' already expired", request_id))
+    }
+}
+
+/// The optional permission an operation needs before it can run, if any.
+/// `network:credentials` depends on the request headers, so it's checked
+/// (without prompting) by the network module.
+pub fn required_permission(operation: &str) -> Option<&'static str> {
+    match operation {
+        "network:get_json" | "network:get_text" | "network:request" | "network:download" => {
+            Some("network:https")
+        }
+        "fs:pick-read" => Some("fs:pick-read"),
+        "fs:pick-write" => Some("fs:pick-write"),
+        "system:list_fonts" => Some("system:fonts"),
+        "clipboard:read" => Some("clipboard:read"),
+        "clipboard:write" => Some("clipboard:write"),
+        "ui:notify" => Some("ui:notify"),
+        "scheduler:register" => Some("scheduler:tasks"),
+        "events:publish" | "events:subscribe" => Some("events:bus"),
+        _ => None,
+    }
+}
+
+/// Prompts only for permissions the manifest declares as optional, that have
+/// no saved decision either way, and that haven't been denied this session.
+pub fn should_prompt(app: &AppHandle, plugin: &InstalledPlugin, permission: &str) -> bool {
+    plugin
+        .manifest
+        .optional_permissions
+        .iter()
+        .any(|item| item == permission)
+        && !plugin
+            .granted_permissions
+            .iter()
+            .any(|grant| grant.permission == permission)
+        && !app
+            .state::<PermissionBroker>()
+            .is_denied(&plugin.id, permission)
+}
+
+/// Asks the user for `permission` and records the answer, returning the
+/// plugin with the decision saved. Returns `None` on timeout, which is treated
+/// as a denial but not persisted. A call made while the same prompt is
+/// already showing waits for that prompt instead of opening another.
+pub async fn request(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    permission: &str,
+    operation: &str,
+) -> Result<Option<InstalledPlugin>, String> {
+    let broker = app.state::<PermissionBroker>();
+    let key = (plugin.id.clone(), permission.to_string());
+    let receiver = {
+        let mut open = broker
+            .open
+            .lock()
+            .map_err(|_| "Permission broker is unavailable".to_string())?;
+        match open.get_mut(&key) {
+            Some(waiters) => {
+                let (sender, receiver) = mpsc::channel();
+                waiters.push(sender);
+                Some(receiver)
+            }
+            None => {
+                open.insert(key.clone(), Vec::new());
+                None
+            }
+        }
+    };
+    if let Some(receiver) = receiver {
+        let answer = tauri::async_runtime::spawn_blocking(move || receiver.recv())
+            .await
+            .map_err(|error| format!("Permission request failed: {}", error))?;
+        return Ok(answer.ok().flatten());
+    }
+
+    let prompt = OpenPrompt {
+        broker: &broker,
+        key,
+    };
+    let updated = match prompt_user(app, &broker, plugin, permission, operation).await {
+        Ok(Some(granted)) => {
+            broker.remember(&plugin.id, permission, granted);
+            super::record_permission_decision(app, plugin, permission, granted).map(Some)
+        }
+        Ok(None) => Ok(None),
+        Err(error) => Err(error),
+    };
+    for waiter in prompt.close() {
+        let _ = waiter.send(updated.clone().ok().flatten());
+    }
+    updated
+}
+
+/// Emits `plugin-permission-request` and blocks until the frontend answers
+/// through `plugin_respond_permission_request`, or returns `None` on timeout.
+async fn prompt_user(
+    app: &AppHandle,
+    broker: &PermissionBroker,
+    plugin: &InstalledPlugin,
+    permission: &str,
+    operation: &str,
+) -> Result<Option<bool>, String> {
+    let request_id = format!(
+        "perm-{}",
+        broker.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    );
+    let (sender, receiver) = mpsc::channel();
+    broker
+        .pending
+        .lock()
+        .map_err(|_| "Permission broker is unavailable".to_string())?
+        .insert(request_id.clone(), sender);
+
+    app.emit(
+        PERMISSION_REQUEST_EVENT,
+        PermissionRequest {
+            request_id: request_id.clone(),
+            plugin_id: plugin.id.clone(),
+            permission: permission.to_string(),
+            operation: operation.to_string(),
+        },
+    )
+    .map_err(|error| format!("Failed to request permission: {}", error))?;
+
+    let response =
+        tauri::async_runtime::spawn_blocking(move || receiver.recv_timeout(RESPONSE_TIMEOUT))
+            .await
+            .map_err(|error| format!("Permission request failed: {}", error))?;
+
+    match response {
+        Ok(granted) => Ok(Some(granted)),
+        Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {
+            if let Ok(mut pending) = broker.pending.lock() {
+                pending.remove(&request_id);
+            }
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::InstalledPlugin;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Crashes within this window count toward an automatic disable.
+const CRASH_WINDOW_MINUTES: i64 = 10;
+const MAX_CRASHES_IN_WINDOW: usize = 3;
+/// First automatic re-enable delay; doubles with each automatic disable.
+const BASE_REENABLE_SECONDS: i64 = 60;
+const MAX_REENABLE_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashOutcome {
+    pub plugin_id: String,
+    pub plugin_name: String,
+    pub crash_count: u32,
+    pub crashes_in_window: usize,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reenable_at: Option<String>,
+}
+
+fn parse(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+fn reenable_delay(auto_disable_count: u32) -> Duration {
+    let exponent = auto_disable_count.saturating_sub(1).min(16);
+    Duration::seconds((BASE_REENABLE_SECONDS << exponent).min(MAX_REENABLE_SECONDS))
+}
+
+/// Records a crash at `now` (`crash_count` is bumped by the diagnostic
+/// itself) and disables the plugin once it has crashed
+/// `MAX_CRASHES_IN_WINDOW` times within the window, scheduling a re-enable
+/// with exponential backoff.
+pub fn register_crash(plugin: &mut InstalledPlugin, now: DateTime<Utc>) -> CrashOutcome {
+    let window_start = now - Duration::minutes(CRASH_WINDOW_MINUTES);
+    plugin
+        .recent_crashes
+        .retain(|timestamp| parse(timestamp).is_some_and(|at| at > window_start));
+    plugin.recent_crashes.push(now.to_rfc3339());
+
+    let crashes_in_window = plugin.recent_crashes.len();
+    let disabled = plugin.enabled && crashes_in_window >= MAX_CRASHES_IN_WINDOW;
+    if disabled {
+        plugin.enabled = false;
+        plugin.auto_disable_count = plugin.auto_disable_count.saturating_add(1);
+        plugin.reenable_at = Some((now + reenable_delay(plugin.auto_disable_count)).to_rfc3339());
+        plugin.recent_crashes.clear();
+    }
+
+    CrashOutcome {
+        plugin_id: plugin.id.clone(),
+        plugin_name: plugin.name.clone(),
+        crash_count: plugin.crash_count,
+        crashes_in_window,
+        disabled,
+        reenable_at: plugin.reenable_at.clone(),
+    }
+}
+
+/// Re-enables an automatically disabled plugin once its backoff has passed.
+pub fn reenable_if_due(plugin: &mut InstalledPlugin, now: DateTime<Utc>) -> bool {
+    let due = plugin
+        .reenable_at
+        .as_deref()
+        .and_then(parse)
+        .is_some_and(|at| at <= now);
+    if due {
+        plugin.enabled = true;
+        plugin.reenable_at = None;
+    }
+    due
+}
+
+/// Manual enable/disable overrides any automatic schedule.
+pub fn clear_schedule(plugin: &mut InstalledPlugin) {
+    plugin.reenable_at = None;
+    plugin.recent_crashes.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plugin() -> InstalledPlugin {
+        serde_json::from_value(json!({
+            "id": "demo",
+            "name": "Demo",
+            "version": "1.0.0",
+            "description": "",
+            "enabled": true,
+            "trust": "unverified",
+            "installSource": "sideload",
+            "installedAt": "2026-01-01T00:00:00+00:00",
+            "updatedAt": "2026-01-01T00:00:00+00:00",
+            "entryPath": "index.js",
+            "crashCount": 0,
+            "manifest": {
+                "schemaVersion": 1,
+                "id": "demo",
+                "name": "Demo",
+                "version": "1.0.0",
+                "description": "",
+                "engine": { "grainery": "^1.2.0", "pluginApi": "^1.2.0" },
+                "entry": "index.js",
+                "permissions": []
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn disables_after_repeated_crashes_and_backs_off() {
+        let mut plugin = plugin();
+        let start = Utc::now();
+
+        assert!(!register_crash(&mut plugin, start).disabled);
+        // Outside the window: doesn't count toward the next disable.
+        let later = start + Duration::minutes(30);
+        assert!(!register_crash(&mut plugin, later).disabled);
+        assert!(!register_crash(&mut plugin, later + Duration::seconds(1)).disabled);
+        let outcome = register_crash(&mut plugin, later + Duration::seconds(2));
+        assert!(outcome.disabled);
+        assert!(!plugin.enabled);
+
+        assert!(!reenable_if_due(&mut plugin, later + Duration::seconds(30)));
+        assert!(reenable_if_due(&mut plugin, later + Duration::minutes(2)));
+        assert!(plugin.enabled);
+
+        for offset in 0..3 {
+            register_crash(&mut plugin, later + Duration::minutes(5 + offset));
+        }
+        assert_eq!(plugin.auto_disable_count, 2);
+        assert!(!reenable_if_due(&mut plugin, later + Duration::minutes(8)));
+    }
+}
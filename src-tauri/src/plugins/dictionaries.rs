@@ -0,0 +1,230 @@
+use super::{
+    plugin_install_base_dir, sanitize_plugin_id, ContributedDictionary, InstalledPlugin,
+    PluginManifest,
+};
+use serde::Serialize;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+const MAX_DICTIONARY_FILE_BYTES: u64 = 8 * 1024 * 1024;
+const MAX_WORD_CHARS: usize = 64;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDictionary {
+    pub plugin_id: String,
+    pub id: String,
+    pub label: String,
+    pub language: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDictionaryWords {
+    pub plugin_id: String,
+    pub id: String,
+    pub language: String,
+    pub kind: String,
+    pub words: Vec<String>,
+}
+
+fn is_valid_language_tag(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let primary = parts.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_lowercase())
+        && parts.all(|part| {
+            (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+/// Word lists are UTF-8 text, one word per line. Blank lines and lines
+/// starting with `#` are skipped; words are deduplicated and sorted.
+fn parse_words(dictionary_id: &str, content: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let word = line.trim();
+        if word.is_empty() || word.starts_with('#') {
+            continue;
+        }
+        if word.chars().count() > MAX_WORD_CHARS
+            || word.chars().any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(format!(
+                "Invalid word on line {} of dictionary '{}'",
+                index + 1,
+                dictionary_id
+            ));
+        }
+        words.push(word.to_string());
+    }
+
+    words.sort();
+    words.dedup();
+    Ok(words)
+}
+
+pub(super) fn validate_contribution(dictionary: &ContributedDictionary) -> Result<(), String> {
+    if dictionary.label.trim().is_empty() {
+        return Err(format!("Dictionary '{}' label is required", dictionary.id));
+    }
+    if !is_valid_language_tag(&dictionary.language) {
+        return Err(format!(
+            "Invalid dictionary language '{}' for '{}'; expected a tag like en or en-GB",
+            dictionary.language, dictionary.id
+        ));
+    }
+    if !matches!(dictionary.kind.as_str(), "language" | "wordlist") {
+        return Err(format!(
+            "Invalid dictionary kind '{}' for '{}'; expected language or wordlist",
+            dictionary.kind, dictionary.id
+        ));
+    }
+    if dictionary.path.contains("..")
+        || Path::new(&dictionary.path).is_absolute()
+        || !dictionary.path.ends_with(".txt")
+    {
+        return Err(format!(
+            "Dictionary '{}' path must be a relative .txt path within the archive",
+            dictionary.id
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that every contributed dictionary's word list is in the archive
+/// and valid.
+pub(super) fn validate_archive(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+    manifest: &PluginManifest,
+) -> Result<(), String> {
+    for dictionary in &manifest.contributes.dictionaries {
+        let path = dictionary.path.trim_start_matches("./");
+        let mut file = archive
+            .by_name(path)
+            .map_err(|_| format!("Dictionary file '{}' is missing", dictionary.path))?;
+        if file.size() > MAX_DICTIONARY_FILE_BYTES {
+            return Err(format!(
+                "Dictionary file '{}' exceeds {} bytes",
+                dictionary.path, MAX_DICTIONARY_FILE_BYTES
+            ));
+        }
+
+        let mut content = String::new();
+        file.read_to_string(&mut content).map_err(|error| {
+            format!(
+                "Failed to read dictionary file '{}': {}",
+                dictionary.path, error
+            )
+        })?;
+        parse_words(&dictionary.id, &content)?;
+    }
+
+    Ok(())
+}
+
+fn is_active(plugin: &InstalledPlugin) -> bool {
+    plugin.enabled && plugin.revocation.is_none()
+}
+
+/// Whether a dictionary for `available` applies to `requested`: an exact
+/// match, or a dictionary tagged with the requested primary language alone
+/// (`en` applies to `en-GB`).
+fn language_matches(available: &str, requested: &str) -> bool {
+    let requested = requested.replace('_', "-");
+    available.eq_ignore_ascii_case(&requested)
+        || requested
+            .split('-')
+            .next()
+            .is_some_and(|primary| available.eq_ignore_ascii_case(primary))
+}
+
+/// Dictionaries contributed by enabled plugins, optionally only those that
+/// apply to `language`.
+pub(super) fn list(plugins: &[InstalledPlugin], language: Option<&str>) -> Vec<PluginDictionary> {
+    plugins
+        .iter()
+        .filter(|plugin| is_active(plugin))
+        .flat_map(|plugin| {
+            plugin
+                .manifest
+                .contributes
+                .dictionaries
+                .iter()
+                .map(|dictionary| PluginDictionary {
+                    plugin_id: plugin.id.clone(),
+                    id: dictionary.id.clone(),
+                    label: dictionary.label.clone(),
+                    language: dictionary.language.clone(),
+                    kind: dictionary.kind.clone(),
+                })
+        })
+        .filter(|dictionary| {
+            language.is_none_or(|language| language_matches(&dictionary.language, language))
+        })
+        .collect()
+}
+
+pub(super) fn read(
+    app: &tauri::AppHandle,
+    plugin: &InstalledPlugin,
+    dictionary_id: &str,
+) -> Result<PluginDictionaryWords, String> {
+    if !is_active(plugin) {
+        return Err(format!("Plugin '{}' is disabled", plugin.id));
+    }
+
+    let dictionary = plugin
+        .manifest
+        .contributes
+        .dictionaries
+        .iter()
+        .find(|dictionary| dictionary.id == dictionary_id)
+        .ok_or_else(|| {
+            format!(
+                "Dictionary '{}' not found in '{}'",
+                dictionary_id, plugin.id
+            )
+        })?;
+
+    let path = plugin_install_base_dir(app)?
+        .join(sanitize_plugin_id(&plugin.id))
+        .join(&plugin.version)
+        .join(dictionary.path.trim_start_matches("./"));
+    let content = fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Failed to read dictionary file '{}': {}",
+            dictionary.path, error
+        )
+    })?;
+
+    Ok(PluginDictionaryWords {
+        plugin_id: plugin.id.clone(),
+        id: dictionary.id.clone(),
+        language: dictionary.language.clone(),
+        kind: dictionary.kind.clone(),
+        words: parse_words(&dictionary.id, &content)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_word_lists_and_matches_languages() {
+        let words = parse_words("screen", "# screenwriting\nINT.\nEXT.\n\nINT.\nO.S.\n").unwrap();
+        assert_eq!(words, vec!["EXT.", "INT.", "O.S."]);
+        assert!(parse_words("bad", "two words\n").is_err());
+
+        assert!(is_valid_language_tag("en-GB"));
+        assert!(!is_valid_language_tag("English"));
+        assert!(language_matches("en", "en_GB"));
+        assert!(language_matches("en-GB", "en-gb"));
+        assert!(!language_matches("en-GB", "en-US"));
+    }
+}
@@ -0,0 +1,283 @@
+use super::PluginStore;
+use crate::error::{CommandError, ErrorKind};
+use crate::jobs::{self, Job};
+use crate::proxy;
+use reqwest::header::{HeaderValue, CONTENT_RANGE, ETAG, IF_RANGE, RANGE};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub(super) const DEFAULT_TIMEOUT_SECONDS: u64 = 120;
+pub(super) const MIN_TIMEOUT_SECONDS: u64 = 10;
+pub(super) const MAX_TIMEOUT_SECONDS: u64 = 60 * 60;
+const MAX_ATTEMPTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+const MAX_ARCHIVE_BYTES: u64 = 200 * 1024 * 1024;
+const PROGRESS_INTERVAL_BYTES: u64 = 256 * 1024;
+const INSTALL_PROGRESS_EVENT: &str = "plugin-install-progress";
+
+enum AttemptError {
+    Retryable(String),
+    Fatal(String),
+}
+
+/// Archive bytes received so far, kept across attempts so a retry can
+/// resume with a range request.
+#[derive(Default)]
+struct PartialDownload {
+    bytes: Vec<u8>,
+    total: Option<u64>,
+    etag: Option<HeaderValue>,
+}
+
+struct Progress<'a> {
+    app: &'a AppHandle,
+    job: &'a Job,
+    plugin_id: &'a str,
+    version: &'a str,
+}
+
+impl Progress<'_> {
+    fn emit(&self, state: &str, download: &PartialDownload, attempt: u32) {
+        let _ = self.app.emit(
+            INSTALL_PROGRESS_EVENT,
+            json!({
+                "pluginId": self.plugin_id,
+                "version": self.version,
+                "state": state,
+                "receivedBytes": download.bytes.len(),
+                "totalBytes": download.total,
+                "attempt": attempt,
+            }),
+        );
+        let fraction = download
+            .total
+            .filter(|total| *total > 0)
+            .map(|total| download.bytes.len() as f32 / total as f32);
+        self.job.progress(fraction, state);
+    }
+}
+
+/// Per-attempt request timeout; retries resume, so slow links only need
+/// enough time to make progress.
+pub(super) fn timeout(store: &PluginStore) -> Duration {
+    Duration::from_secs(
+        store
+            .download_timeout_seconds
+            .unwrap_or(DEFAULT_TIMEOUT_SECONDS),
+    )
+}
+
+/// Delay before retry `attempt` (1-based): 0.5 s, 1 s, 2 s, ... capped at 8 s.
+fn backoff(attempt: u32) -> Duration {
+    BASE_BACKOFF
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF)
+}
+
+/// Parses `bytes <start>-<end>/<total>`; the total may be `*`.
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (span, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = span.split_once('-')?;
+    Some((start.trim().parse().ok()?, total.trim().parse().ok()))
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+async fn attempt_download(
+    client: &Client,
+    url: &str,
+    download: &mut PartialDownload,
+    progress: &Progress<'_>,
+    attempt: u32,
+) -> Result<(), AttemptError> {
+    let mut request = client.get(url);
+    if !download.bytes.is_empty() {
+        request = request.header(RANGE, format!("bytes={}-", download.bytes.len()));
+        if let Some(etag) = &download.etag {
+            request = request.header(IF_RANGE, etag.clone());
+        }
+    }
+
+    let mut response = request.send().await.map_err(|error| {
+        AttemptError::Retryable(format!("Failed to download plugin archive: {}", error))
+    })?;
+
+    let status = response.status();
+    if status == StatusCode::PARTIAL_CONTENT {
+        let resumed = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_content_range);
+        match resumed {
+            Some((start, total)) if start == download.bytes.len() as u64 => {
+                download.total = total.or(download.total);
+            }
+            _ => {
+                *download = PartialDownload::default();
+                return Err(AttemptError::Retryable(
+                    "Plugin download resumed at an unexpected offset".to_string(),
+                ));
+            }
+        }
+    } else if status.is_success() {
+        // The server ignored the range or the file changed; start over.
+        *download = PartialDownload {
+            total: response.content_length(),
+            etag: response.headers().get(ETAG).cloned(),
+            ..PartialDownload::default()
+        };
+    } else if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        *download = PartialDownload::default();
+        return Err(AttemptError::Retryable(format!(
+            "Plugin download failed with HTTP status {}",
+            status
+        )));
+    } else if is_retryable_status(status) {
+        return Err(AttemptError::Retryable(format!(
+            "Plugin download failed with HTTP status {}",
+            status
+        )));
+    } else {
+        return Err(AttemptError::Fatal(format!(
+            "Plugin download failed with HTTP status {}",
+            status
+        )));
+    }
+
+    if download
+        .total
+        .is_some_and(|total| total > MAX_ARCHIVE_BYTES)
+    {
+        return Err(AttemptError::Fatal(format!(
+            "Plugin archive exceeds {} byte limit",
+            MAX_ARCHIVE_BYTES
+        )));
+    }
+
+    let mut last_reported = download.bytes.len() as u64;
+    while let Some(chunk) = response.chunk().await.map_err(|error| {
+        AttemptError::Retryable(format!(
+            "Failed to read plugin download response: {}",
+            error
+        ))
+    })? {
+        if progress.job.is_cancelled() {
+            return Err(AttemptError::Fatal(jobs::CANCELLED.to_string()));
+        }
+        download.bytes.extend_from_slice(&chunk);
+        let received = download.bytes.len() as u64;
+        if received > MAX_ARCHIVE_BYTES {
+            return Err(AttemptError::Fatal(format!(
+                "Plugin archive exceeds {} byte limit",
+                MAX_ARCHIVE_BYTES
+            )));
+        }
+        if received - last_reported >= PROGRESS_INTERVAL_BYTES {
+            last_reported = received;
+            progress.emit("downloading", download, attempt);
+        }
+    }
+
+    if download
+        .total
+        .is_some_and(|total| total != download.bytes.len() as u64)
+    {
+        return Err(AttemptError::Retryable(
+            "Plugin download ended before the archive was complete".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Downloads a registry archive, retrying network errors, timeouts and 5xx
+/// responses with exponential backoff and resuming with HTTP range requests.
+/// Emits `plugin-install-progress` events as `downloading`, `retrying` and
+/// `complete`, and runs as a cancellable `plugin-download` job.
+pub(super) async fn fetch_archive(
+    app: &AppHandle,
+    plugin_id: &str,
+    version: &str,
+    url: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, CommandError> {
+    let job = jobs::start(
+        app,
+        "plugin-download",
+        format!("Downloading {} {}", plugin_id, version),
+    );
+    let progress = Progress {
+        app,
+        job: &job,
+        plugin_id,
+        version,
+    };
+    let result = download_archive(&progress, url, timeout).await;
+    job.finish(&result);
+    result
+}
+
+async fn download_archive(
+    progress: &Progress<'_>,
+    url: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, CommandError> {
+    let client = proxy::client_builder(progress.app)?
+        .timeout(timeout)
+        .build()
+        .map_err(|error| format!("Failed to create HTTP client: {}", error))?;
+
+    let mut download = PartialDownload::default();
+    let mut attempt = 1;
+    loop {
+        progress.emit("downloading", &download, attempt);
+        match attempt_download(&client, url, &mut download, progress, attempt).await {
+            Ok(()) => {
+                progress.emit("complete", &download, attempt);
+                return Ok(download.bytes);
+            }
+            Err(AttemptError::Fatal(error)) => return Err(error.into()),
+            Err(AttemptError::Retryable(error)) if attempt >= MAX_ATTEMPTS => {
+                return Err(CommandError::new(
+                    ErrorKind::Network,
+                    format!("{} (after {} attempts)", error, attempt),
+                ));
+            }
+            Err(AttemptError::Retryable(_)) => {
+                progress.emit("retrying", &download, attempt);
+                let delay = backoff(attempt);
+                let _ = tauri::async_runtime::spawn_blocking(move || thread::sleep(delay)).await;
+                progress.job.check_cancelled()?;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_content_range_and_backoff() {
+        assert_eq!(
+            parse_content_range("bytes 100-999/1000"),
+            Some((100, Some(1000)))
+        );
+        assert_eq!(parse_content_range("bytes 100-999/*"), Some((100, None)));
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+
+        assert_eq!(backoff(1), Duration::from_millis(500));
+        assert_eq!(backoff(3), Duration::from_secs(2));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+    }
+}
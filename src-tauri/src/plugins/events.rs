@@ -0,0 +1,199 @@
+use super::{
+    has_permission, load_store, validate_local_contribution_id, validate_plugin_id, InstalledPlugin,
+};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+const MAX_EVENT_BYTES: usize = 256 * 1024;
+const MAX_SUBSCRIPTIONS_PER_PLUGIN: usize = 50;
+
+/// In-memory topic subscriptions, plugin id -> topic patterns. Workers
+/// subscribe again when they start, so nothing is persisted.
+#[derive(Default)]
+pub struct EventBus {
+    subscriptions: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+/// Topics are namespaced by the publishing plugin: `<plugin id>/<name>`.
+/// Subscribers may use `<plugin id>/*` for every topic of one publisher.
+fn topic_matches(pattern: &str, topic: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => prefix.ends_with('/') && topic.starts_with(prefix),
+        None => pattern == topic,
+    }
+}
+
+fn validate_pattern(pattern: &str) -> Result<(), String> {
+    let valid = pattern.split_once('/').is_some_and(|(plugin_id, name)| {
+        validate_plugin_id(plugin_id) && (name == "*" || validate_local_contribution_id(name))
+    });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid topic '{}'; expected '<plugin id>/<topic>' or '<plugin id>/*'",
+            pattern
+        ))
+    }
+}
+
+fn payload_topic(payload: &Value) -> Result<&str, String> {
+    payload
+        .get("topic")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Payload must include string field 'topic'".to_string())
+}
+
+impl EventBus {
+    fn subscribe(&self, plugin_id: &str, pattern: &str) -> Result<(), String> {
+        let mut subscriptions = self
+            .subscriptions
+            .lock()
+            .map_err(|_| "Event bus is unavailable".to_string())?;
+        let patterns = subscriptions.entry(plugin_id.to_string()).or_default();
+        if !patterns.contains(pattern) && patterns.len() >= MAX_SUBSCRIPTIONS_PER_PLUGIN {
+            return Err(format!(
+                "Plugins are limited to {} event subscriptions",
+                MAX_SUBSCRIPTIONS_PER_PLUGIN
+            ));
+        }
+        patterns.insert(pattern.to_string());
+        Ok(())
+    }
+
+    fn unsubscribe(&self, plugin_id: &str, pattern: Option<&str>) -> Result<(), String> {
+        let mut subscriptions = self
+            .subscriptions
+            .lock()
+            .map_err(|_| "Event bus is unavailable".to_string())?;
+        match pattern {
+            Some(pattern) => {
+                if let Some(patterns) = subscriptions.get_mut(plugin_id) {
+                    patterns.remove(pattern);
+                }
+            }
+            None => {
+                subscriptions.remove(plugin_id);
+            }
+        }
+        Ok(())
+    }
+
+    fn subscribers(&self, publisher: &str, topic: &str) -> Vec<String> {
+        let Ok(subscriptions) = self.subscriptions.lock() else {
+            return Vec::new();
+        };
+        let mut subscribers = subscriptions
+            .iter()
+            .filter(|(plugin_id, patterns)| {
+                plugin_id.as_str() != publisher
+                    && patterns.iter().any(|pattern| topic_matches(pattern, topic))
+            })
+            .map(|(plugin_id, _)| plugin_id.clone())
+            .collect::<Vec<_>>();
+        subscribers.sort();
+        subscribers
+    }
+}
+
+/// Handles `events:publish`, `events:subscribe` and `events:unsubscribe`.
+/// Publishing and subscribing need `events:bus`; a plugin may only publish
+/// under its own id. Each delivery is a `plugin-bus-event` addressed to one
+/// subscriber that is still enabled and still holds the permission.
+pub fn handle(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    operation: &str,
+    payload: &Value,
+) -> Result<Value, String> {
+    let bus = app.state::<EventBus>();
+
+    match operation {
+        "events:publish" => {
+            if !has_permission(plugin, "events:bus") {
+                return Err("Permission denied: events:bus".to_string());
+            }
+
+            let name = payload_topic(payload)?;
+            let topic = format!("{}/{}", plugin.id, name);
+            validate_pattern(&topic)?;
+            if name == "*" {
+                return Err("Cannot publish to a wildcard topic".to_string());
+            }
+
+            let data = payload.get("data").cloned().unwrap_or(Value::Null);
+            if serde_json::to_vec(&data).map_or(0, |bytes| bytes.len()) > MAX_EVENT_BYTES {
+                return Err(format!(
+                    "Event payloads are limited to {} bytes",
+                    MAX_EVENT_BYTES
+                ));
+            }
+
+            let store = load_store(app)?;
+            let mut delivered = 0;
+            for subscriber in bus.subscribers(&plugin.id, &topic) {
+                let active = store.installed_plugins.iter().any(|candidate| {
+                    candidate.id == subscriber
+                        && candidate.enabled
+                        && candidate.revocation.is_none()
+                        && has_permission(candidate, "events:bus")
+                });
+                if !active {
+                    continue;
+                }
+
+                let _ = app.emit(
+                    "plugin-bus-event",
+                    json!({
+                        "pluginId": subscriber,
+                        "source": plugin.id,
+                        "topic": topic,
+                        "data": data,
+                    }),
+                );
+                delivered += 1;
+            }
+
+            Ok(json!({ "topic": topic, "delivered": delivered }))
+        }
+
+        "events:subscribe" => {
+            if !has_permission(plugin, "events:bus") {
+                return Err("Permission denied: events:bus".to_string());
+            }
+
+            let pattern = payload_topic(payload)?;
+            validate_pattern(pattern)?;
+            bus.subscribe(&plugin.id, pattern)?;
+            Ok(json!({ "ok": true }))
+        }
+
+        "events:unsubscribe" => {
+            let pattern = payload.get("topic").and_then(Value::as_str);
+            bus.unsubscribe(&plugin.id, pattern)?;
+            Ok(json!({ "ok": true }))
+        }
+
+        _ => Err(format!("Unsupported host operation '{}'", operation)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_namespaced_topics() {
+        assert!(topic_matches("tagger/tags-changed", "tagger/tags-changed"));
+        assert!(topic_matches("tagger/*", "tagger/tags-changed"));
+        assert!(!topic_matches("tagger/*", "taggerx/tags-changed"));
+        assert!(!topic_matches("tagger/tags", "tagger/tags-changed"));
+
+        assert!(validate_pattern("com.example.tagger/*").is_ok());
+        assert!(validate_pattern("tags-changed").is_err());
+        assert!(validate_pattern("tagger/a/b").is_err());
+    }
+}
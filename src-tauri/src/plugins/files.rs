@@ -0,0 +1,274 @@
+use super::{has_permission, plugin_root, quota, sanitize_plugin_id, InstalledPlugin};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+const DATA_DIR: &str = "data";
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+pub(super) const MAX_DATA_DIR_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Per-plugin sandbox, kept outside the install directory so files survive
+/// updates and reinstalls.
+pub(super) fn data_dir(app: &AppHandle, plugin_id: &str) -> Result<PathBuf, String> {
+    let dir = plugin_root(app)?
+        .join(DATA_DIR)
+        .join(sanitize_plugin_id(plugin_id));
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("Failed to create plugin data directory: {}", error))?;
+    Ok(dir)
+}
+
+/// Resolves a payload path inside the sandbox, rejecting absolute paths and
+/// any `..` component.
+pub(super) fn sandboxed_path(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(relative);
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!(
+            "Path '{}' must be relative to the plugin data directory",
+            relative.display()
+        ));
+    }
+
+    Ok(root.join(relative))
+}
+
+fn payload_str<'a>(payload: &'a Value, field: &str) -> Result<&'a str, String> {
+    payload
+        .get(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Payload must include string field '{}'", field))
+}
+
+fn is_base64(payload: &Value) -> bool {
+    payload.get("encoding").and_then(Value::as_str) == Some("base64")
+}
+
+fn encode_content(bytes: Vec<u8>, base64: bool) -> Result<Value, String> {
+    if base64 {
+        return Ok(Value::String(BASE64_STANDARD.encode(bytes)));
+    }
+
+    String::from_utf8(bytes)
+        .map(Value::String)
+        .map_err(|_| "File is not valid UTF-8; read it with encoding 'base64'".to_string())
+}
+
+fn decode_content(payload: &Value) -> Result<Vec<u8>, String> {
+    let content = payload_str(payload, "content")?;
+    let bytes = if is_base64(payload) {
+        BASE64_STANDARD
+            .decode(content)
+            .map_err(|error| format!("Invalid base64 content: {}", error))?
+    } else {
+        content.as_bytes().to_vec()
+    };
+
+    if bytes.len() as u64 > MAX_FILE_BYTES {
+        return Err(format!("Files are limited to {} bytes", MAX_FILE_BYTES));
+    }
+
+    Ok(bytes)
+}
+
+pub(super) fn directory_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| match entry.metadata() {
+                    Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+                    Ok(metadata) => metadata.len(),
+                    Err(_) => 0,
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn read_limited(path: &Path) -> Result<Vec<u8>, String> {
+    let size = fs::metadata(path)
+        .map_err(|error| format!("Failed to read file: {}", error))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!("Files are limited to {} bytes", MAX_FILE_BYTES));
+    }
+
+    fs::read(path).map_err(|error| format!("Failed to read file: {}", error))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn extensions(payload: &Value) -> Vec<String> {
+    payload
+        .get("extensions")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|extension| extension.trim_start_matches('.').to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn pick_file(
+    app: &AppHandle,
+    payload: &Value,
+    save: bool,
+) -> Result<Option<PathBuf>, String> {
+    let mut dialog = app.dialog().file();
+    let extensions = extensions(payload);
+    if !extensions.is_empty() {
+        let extensions = extensions.iter().map(String::as_str).collect::<Vec<_>>();
+        dialog = dialog.add_filter("Files", &extensions);
+    }
+    if let Some(name) = payload.get("suggestedName").and_then(Value::as_str) {
+        dialog = dialog.set_file_name(name);
+    }
+
+    let picked = tauri::async_runtime::spawn_blocking(move || {
+        if save {
+            dialog.blocking_save_file()
+        } else {
+            dialog.blocking_pick_file()
+        }
+    })
+    .await
+    .map_err(|error| format!("File picker failed: {}", error))?;
+
+    picked
+        .map(|path| {
+            path.into_path()
+                .map_err(|error| format!("Unsupported file location: {}", error))
+        })
+        .transpose()
+}
+
+/// Handles the sandboxed `fs:read`, `fs:write` and `fs:list` operations and
+/// the picker-based `fs:pick-read` / `fs:pick-write` operations. Picker
+/// operations never reveal the chosen path, only its file name.
+pub async fn handle(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    operation: &str,
+    payload: &Value,
+) -> Result<Value, String> {
+    match operation {
+        "fs:read" => {
+            let root = data_dir(app, &plugin.id)?;
+            let path = sandboxed_path(&root, payload_str(payload, "path")?)?;
+            let content = encode_content(read_limited(&path)?, is_base64(payload))?;
+            Ok(json!({ "content": content }))
+        }
+
+        "fs:write" => {
+            let root = data_dir(app, &plugin.id)?;
+            let path = sandboxed_path(&root, payload_str(payload, "path")?)?;
+            let bytes = decode_content(payload)?;
+
+            let existing = fs::metadata(&path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            let projected = directory_size(&root).saturating_sub(existing) + bytes.len() as u64;
+            if projected > MAX_DATA_DIR_BYTES {
+                return Err(format!(
+                    "Plugin data directory quota exceeded ({} bytes)",
+                    MAX_DATA_DIR_BYTES
+                ));
+            }
+            quota::ensure_room(app, &plugin.id, existing, bytes.len() as u64)?;
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|error| format!("Failed to create directory: {}", error))?;
+            }
+            fs::write(&path, &bytes).map_err(|error| format!("Failed to write file: {}", error))?;
+
+            Ok(json!({ "ok": true, "bytes": bytes.len() }))
+        }
+
+        "fs:list" => {
+            let root = data_dir(app, &plugin.id)?;
+            let path = sandboxed_path(
+                &root,
+                payload.get("path").and_then(Value::as_str).unwrap_or("."),
+            )?;
+            if !path.exists() {
+                return Ok(json!({ "entries": [] }));
+            }
+
+            let mut entries = fs::read_dir(&path)
+                .map_err(|error| format!("Failed to list directory: {}", error))?
+                .flatten()
+                .map(|entry| {
+                    let metadata = entry.metadata().ok();
+                    json!({
+                        "name": file_name(&entry.path()),
+                        "isDirectory": metadata.as_ref().map(|item| item.is_dir()).unwrap_or(false),
+                        "size": metadata.as_ref().map(|item| item.len()).unwrap_or(0),
+                    })
+                })
+                .collect::<Vec<_>>();
+            entries.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+            Ok(json!({ "entries": entries }))
+        }
+
+        "fs:pick-read" => {
+            if !has_permission(plugin, "fs:pick-read") {
+                return Err("Permission denied: fs:pick-read".to_string());
+            }
+
+            let Some(path) = pick_file(app, payload, false).await? else {
+                return Ok(json!({ "cancelled": true }));
+            };
+            let content = encode_content(read_limited(&path)?, is_base64(payload))?;
+
+            Ok(json!({ "cancelled": false, "name": file_name(&path), "content": content }))
+        }
+
+        "fs:pick-write" => {
+            if !has_permission(plugin, "fs:pick-write") {
+                return Err("Permission denied: fs:pick-write".to_string());
+            }
+
+            let bytes = decode_content(payload)?;
+            let Some(path) = pick_file(app, payload, true).await? else {
+                return Ok(json!({ "cancelled": true }));
+            };
+            fs::write(&path, &bytes).map_err(|error| format!("Failed to write file: {}", error))?;
+
+            Ok(json!({ "cancelled": false, "name": file_name(&path), "bytes": bytes.len() }))
+        }
+
+        _ => Err(format!("Unsupported host operation '{}'", operation)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_paths_that_escape_the_sandbox() {
+        let root = Path::new("/data/plugin");
+        assert_eq!(
+            sandboxed_path(root, "notes/today.txt").unwrap(),
+            root.join("notes/today.txt")
+        );
+        assert!(sandboxed_path(root, "../other/secret.txt").is_err());
+        assert!(sandboxed_path(root, "/etc/passwd").is_err());
+    }
+}
@@ -0,0 +1,279 @@
+use super::PluginManifest;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+pub(super) const LOCALES_DIR: &str = "locales";
+const MAX_LOCALE_FILE_BYTES: u64 = 256 * 1024;
+
+/// Locale tag -> message key -> localized string.
+pub(super) type Locales = HashMap<String, HashMap<String, String>>;
+
+/// Schema v2 manifests may use `%key%` placeholders in `name`, `description`
+/// and any string under `contributes`; they are resolved from
+/// `locales/<tag>.json` files.
+fn placeholder_key(value: &str) -> Option<&str> {
+    let key = value.strip_prefix('%')?.strip_suffix('%')?;
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_');
+    valid.then_some(key)
+}
+
+fn is_valid_locale_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= 35
+        && tag
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+fn parse_locale_file(tag: &str, content: &str) -> Result<HashMap<String, String>, String> {
+    serde_json::from_str(content)
+        .map_err(|error| format!("Invalid locale file '{}.json': {}", tag, error))
+}
+
+fn locale_tag(file_name: &str) -> Option<&str> {
+    file_name.strip_suffix(".json")
+}
+
+pub(super) fn read_archive_locales(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+) -> Result<Locales, String> {
+    let mut locales = Locales::new();
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .map_err(|error| format!("Failed to read plugin archive entry: {}", error))?;
+        let Some(tag) = file
+            .name()
+            .strip_prefix(&format!("{}/", LOCALES_DIR))
+            .and_then(locale_tag)
+            .map(str::to_string)
+        else {
+            continue;
+        };
+
+        if !is_valid_locale_tag(&tag) {
+            return Err(format!("Invalid locale file name '{}.json'", tag));
+        }
+        if file.size() > MAX_LOCALE_FILE_BYTES {
+            return Err(format!(
+                "Locale file '{}.json' exceeds {} bytes",
+                tag, MAX_LOCALE_FILE_BYTES
+            ));
+        }
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|error| format!("Failed to read locale file '{}.json': {}", tag, error))?;
+        let messages = parse_locale_file(&tag, &content)?;
+        locales.insert(tag, messages);
+    }
+
+    Ok(locales)
+}
+
+/// Reads the extracted `locales/` folder of an installed plugin; unreadable
+/// files are skipped since they were validated at install time.
+pub(super) fn read_installed_locales(version_dir: &Path) -> Locales {
+    let Ok(entries) = fs::read_dir(version_dir.join(LOCALES_DIR)) else {
+        return Locales::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let tag = locale_tag(&file_name)?.to_string();
+            let content = fs::read_to_string(entry.path()).ok()?;
+            Some((tag.clone(), parse_locale_file(&tag, &content).ok()?))
+        })
+        .collect()
+}
+
+fn collect_placeholders(value: &Value, keys: &mut BTreeSet<String>) {
+    match value {
+        Value::String(text) => {
+            if let Some(key) = placeholder_key(text) {
+                keys.insert(key.to_string());
+            }
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_placeholders(item, keys)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|item| collect_placeholders(item, keys)),
+        _ => {}
+    }
+}
+
+fn replace_placeholders(value: &mut Value, messages: &HashMap<String, String>) {
+    match value {
+        Value::String(text) => {
+            if let Some(localized) = placeholder_key(text).and_then(|key| messages.get(key)) {
+                *text = localized.clone();
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| replace_placeholders(item, messages)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|item| replace_placeholders(item, messages)),
+        _ => {}
+    }
+}
+
+/// The localizable subset of a manifest, as JSON.
+fn localizable(manifest: &PluginManifest) -> Result<Value, String> {
+    let value = serde_json::to_value(manifest)
+        .map_err(|error| format!("Failed to serialize plugin manifest: {}", error))?;
+    Ok(serde_json::json!({
+        "name": value["name"],
+        "description": value["description"],
+        "contributes": value["contributes"],
+    }))
+}
+
+/// Checks that the default locale exists and defines every placeholder the
+/// manifest uses.
+pub(super) fn validate(manifest: &PluginManifest, locales: &Locales) -> Result<(), String> {
+    let Some(default_locale) = manifest.default_locale.as_deref() else {
+        return Err("Schema v2 manifests must declare defaultLocale".to_string());
+    };
+    let messages = locales.get(default_locale).ok_or_else(|| {
+        format!(
+            "Default locale file '{}/{}.json' is missing",
+            LOCALES_DIR, default_locale
+        )
+    })?;
+
+    let mut keys = BTreeSet::new();
+    collect_placeholders(&localizable(manifest)?, &mut keys);
+    let missing = keys
+        .into_iter()
+        .filter(|key| !messages.contains_key(key))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Default locale '{}' is missing messages: {}",
+            default_locale,
+            missing.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Picks the best available locale for `requested`: an exact tag match,
+/// then the language alone (`fr-CA` -> `fr`), then the manifest default.
+fn negotiate(requested: Option<&str>, locales: &Locales, default_locale: &str) -> String {
+    if let Some(requested) = requested {
+        let requested = requested.replace('_', "-");
+        let find = |tag: &str| {
+            locales
+                .keys()
+                .find(|available| available.eq_ignore_ascii_case(tag))
+                .cloned()
+        };
+
+        if let Some(tag) = find(&requested) {
+            return tag;
+        }
+        if let Some(tag) = requested.split('-').next().and_then(find) {
+            return tag;
+        }
+    }
+
+    default_locale.to_string()
+}
+
+/// Resolves placeholders for `requested`, falling back message by message
+/// to the default locale. Returns the localized manifest and the locale
+/// used; schema v1 manifests are returned unchanged.
+pub(super) fn localize(
+    manifest: &PluginManifest,
+    locales: &Locales,
+    requested: Option<&str>,
+) -> (PluginManifest, Option<String>) {
+    let Some(default_locale) = manifest
+        .default_locale
+        .as_deref()
+        .filter(|_| manifest.schema_version >= 2)
+    else {
+        return (manifest.clone(), None);
+    };
+
+    let locale = negotiate(requested, locales, default_locale);
+    let mut messages = locales.get(default_locale).cloned().unwrap_or_default();
+    if let Some(preferred) = locales.get(&locale) {
+        messages.extend(preferred.clone());
+    }
+
+    let Ok(mut value) = serde_json::to_value(manifest) else {
+        return (manifest.clone(), None);
+    };
+    for field in ["name", "description", "contributes"] {
+        if let Some(item) = value.get_mut(field) {
+            replace_placeholders(item, &messages);
+        }
+    }
+
+    match serde_json::from_value(value) {
+        Ok(localized) => (localized, Some(locale)),
+        Err(_) => (manifest.clone(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resolves_placeholders_with_locale_fallback() {
+        let manifest: PluginManifest = serde_json::from_value(json!({
+            "schemaVersion": 2,
+            "id": "demo",
+            "name": "%name%",
+            "version": "1.0.0",
+            "description": "%description%",
+            "defaultLocale": "en",
+            "engine": { "grainery": "^1.2.0", "pluginApi": "^1.2.0" },
+            "entry": "index.js",
+            "permissions": [],
+            "contributes": { "commands": [{ "id": "run", "title": "%command.run%" }] }
+        }))
+        .unwrap();
+
+        let mut locales = Locales::new();
+        locales.insert(
+            "en".to_string(),
+            HashMap::from([
+                ("name".to_string(), "Demo".to_string()),
+                ("description".to_string(), "A demo".to_string()),
+                ("command.run".to_string(), "Run".to_string()),
+            ]),
+        );
+        locales.insert(
+            "fr".to_string(),
+            HashMap::from([("name".to_string(), "Démo".to_string())]),
+        );
+        assert!(validate(&manifest, &locales).is_ok());
+
+        let (localized, locale) = localize(&manifest, &locales, Some("fr-CA"));
+        assert_eq!(locale.as_deref(), Some("fr"));
+        assert_eq!(localized.name, "Démo");
+        assert_eq!(localized.description, "A demo");
+        assert_eq!(localized.contributes.commands[0].title, "Run");
+
+        let (_, locale) = localize(&manifest, &locales, Some("de"));
+        assert_eq!(locale.as_deref(), Some("en"));
+    }
+}
@@ -0,0 +1,100 @@
+use super::InstalledPlugin;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Performance counters for one plugin since the app started.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginPerformance {
+    pub plugin_id: String,
+    pub name: String,
+    pub activation_count: u32,
+    pub last_activation_event: Option<String>,
+    pub last_activation_ms: Option<u64>,
+    pub total_activation_ms: u64,
+    pub host_calls: u64,
+    pub failed_host_calls: u64,
+    pub host_call_ms: u64,
+    pub network_bytes_sent: u64,
+    pub network_bytes_received: u64,
+}
+
+/// In-memory counters, plugin id -> performance. Nothing is persisted; the
+/// report describes the current session.
+#[derive(Default)]
+pub struct PluginMetrics {
+    entries: Mutex<HashMap<String, PluginPerformance>>,
+}
+
+impl PluginMetrics {
+    fn update(&self, plugin_id: &str, apply: impl FnOnce(&mut PluginPerformance)) {
+        if let Ok(mut entries) = self.entries.lock() {
+            let entry = entries
+                .entry(plugin_id.to_string())
+                .or_insert_with(|| PluginPerformance {
+                    plugin_id: plugin_id.to_string(),
+                    ..PluginPerformance::default()
+                });
+            apply(entry);
+        }
+    }
+
+    pub(super) fn record_activation(&self, plugin_id: &str, event: &str, duration_ms: u64) {
+        self.update(plugin_id, |entry| {
+            entry.activation_count += 1;
+            entry.last_activation_event = Some(event.to_string());
+            entry.last_activation_ms = Some(duration_ms);
+            entry.total_activation_ms += duration_ms;
+        });
+    }
+
+    pub(super) fn record_host_call(&self, plugin_id: &str, elapsed: Duration, ok: bool) {
+        self.update(plugin_id, |entry| {
+            entry.host_calls += 1;
+            entry.host_call_ms += elapsed.as_millis() as u64;
+            if !ok {
+                entry.failed_host_calls += 1;
+            }
+        });
+    }
+
+    pub(super) fn record_network(&self, plugin_id: &str, sent: u64, received: u64) {
+        self.update(plugin_id, |entry| {
+            entry.network_bytes_sent += sent;
+            entry.network_bytes_received += received;
+        });
+    }
+
+    /// One row per installed plugin, slowest activation first, then by time
+    /// spent in host calls.
+    pub(super) fn report(&self, installed: &[InstalledPlugin]) -> Vec<PluginPerformance> {
+        let entries = self
+            .entries
+            .lock()
+            .map(|entries| entries.clone())
+            .unwrap_or_default();
+
+        let mut report = installed
+            .iter()
+            .map(|plugin| PluginPerformance {
+                name: plugin.name.clone(),
+                ..entries
+                    .get(&plugin.id)
+                    .cloned()
+                    .unwrap_or_else(|| PluginPerformance {
+                        plugin_id: plugin.id.clone(),
+                        ..PluginPerformance::default()
+                    })
+            })
+            .collect::<Vec<_>>();
+        report.sort_by(|a, b| {
+            b.total_activation_ms
+                .cmp(&a.total_activation_ms)
+                .then_with(|| b.host_call_ms.cmp(&a.host_call_ms))
+                .then_with(|| a.plugin_id.cmp(&b.plugin_id))
+        });
+        report
+    }
+}
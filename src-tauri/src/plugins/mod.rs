@@ -1,25 +1,59 @@
-use crate::fonts;
+use crate::error::{CommandError, ErrorKind};
+use crate::{crash_reports, fonts, proxy};
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine;
 use chrono::Utc;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
-use reqwest::Client;
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{Cursor, Read, Write};
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
 use zip::ZipArchive;
 
+mod allowlist;
+mod audit;
+mod clipboard;
+mod consent;
+mod crashes;
+mod dictionaries;
+mod download;
+mod events;
+mod files;
+mod localization;
+mod metrics;
+mod network;
+mod notify;
+mod pack;
+mod process;
+mod profile;
+mod quota;
+mod revocation;
+mod scheduler;
+mod signing;
+mod storage;
+mod themes;
+
+pub use consent::PermissionBroker;
+pub use events::EventBus;
+pub use metrics::PluginMetrics;
+pub use notify::NotificationLimiter;
+pub use pack::run_cli as run_pack_cli;
+pub use scheduler::TaskScheduler;
+
 const PLUGIN_STORE_FILE: &str = "plugins-state.json";
 const PLUGIN_AUDIT_LOG_FILE: &str = "plugin-audit.log";
 const MANIFEST_FILE_NAME: &str = "grainery-plugin.manifest.json";
+const UNSIGNED_ARCHIVE_ERROR: &str = "Plugin archive is not signed:";
 const PLUGIN_API_VERSION: &str = "1.2.0";
 const REQUIRED_PLUGIN_API_RANGE: &str = "^1.2.0";
+const REGISTRY_SEARCH_PAGE_SIZE: u32 = 20;
+const REGISTRY_SEARCH_MAX_PAGE_SIZE: u32 = 100;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -61,6 +95,10 @@ pub struct PluginContributions {
     pub ui_panels: Vec<ContributedUiPanel>,
     #[serde(default)]
     pub transforms: Vec<ContributedTransform>,
+    #[serde(default)]
+    pub themes: Vec<ContributedTheme>,
+    #[serde(default)]
+    pub dictionaries: Vec<ContributedDictionary>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -162,6 +200,31 @@ pub struct ContributedStatusBadge {
     pub priority: Option<i64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributedTheme {
+    pub id: String,
+    pub label: String,
+    /// `light` or `dark`; the built-in theme the tokens are layered over.
+    pub base: String,
+    /// JSON token file in the archive.
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributedDictionary {
+    pub id: String,
+    pub label: String,
+    /// BCP 47 language tag, e.g. `en` or `en-GB`.
+    pub language: String,
+    /// `language` for a full language pack, `wordlist` for extra words such
+    /// as genre or character-name lists.
+    pub kind: String,
+    /// Plain-text word list in the archive, one word per line.
+    pub path: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ContributedInlineAnnotationProvider {
@@ -249,6 +312,9 @@ pub struct PluginManifest {
     pub permission_rationales: HashMap<String, String>,
     #[serde(default)]
     pub signature: Option<PluginSignature>,
+    /// Schema v2: locale whose `locales/<tag>.json` backs `%key%` placeholders.
+    #[serde(default)]
+    pub default_locale: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -304,6 +370,37 @@ pub struct InstalledPlugin {
     pub manifest: PluginManifest,
     #[serde(default)]
     pub granted_permissions: Vec<PluginPermissionGrant>,
+    /// Configured registry the plugin was installed from, if any.
+    #[serde(default)]
+    pub registry_id: Option<String>,
+    /// Crash timestamps inside the current crash window.
+    #[serde(default)]
+    pub recent_crashes: Vec<String>,
+    #[serde(default)]
+    pub auto_disable_count: u32,
+    /// When an automatically disabled plugin will be re-enabled.
+    #[serde(default)]
+    pub reenable_at: Option<String>,
+    /// Set when this version has been revoked; revoked plugins never run.
+    #[serde(default)]
+    pub revocation: Option<revocation::PluginRevocation>,
+    /// Release channel used when resolving the latest registry version.
+    #[serde(default)]
+    pub update_channel: PluginChannel,
+    /// Locale the listed name, description and contributions were resolved
+    /// to; only set on `plugin_list_installed` output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+/// Registry release channel. Entries without a channel are stable; plugins
+/// following `beta` also receive stable releases.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginChannel {
+    #[default]
+    Stable,
+    Beta,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -318,6 +415,33 @@ pub struct PluginRegistryEntry {
     pub sha256: String,
     pub signature_key_id: String,
     pub signature: String,
+    #[serde(default)]
+    pub channel: PluginChannel,
+    #[serde(default)]
+    pub changelog: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub downloads: Option<u64>,
+    #[serde(default)]
+    pub rating: Option<f32>,
+    #[serde(default)]
+    pub rating_count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUpdateInfo {
+    pub plugin_id: String,
+    pub name: String,
+    pub installed_version: String,
+    pub available_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<String>,
+    pub registry_url: String,
+    /// Required or optional permissions the new version asks for that the
+    /// installed one did not.
+    pub new_permissions: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -334,6 +458,8 @@ pub struct PluginLockRecord {
     #[serde(default)]
     pub registry_url: Option<String>,
     #[serde(default)]
+    pub registry_id: Option<String>,
+    #[serde(default)]
     pub download_url: Option<String>,
     pub trust: String,
     pub enabled: bool,
@@ -342,6 +468,90 @@ pub struct PluginLockRecord {
     pub updated_at: String,
 }
 
+/// A configured plugin registry. Lower `priority` values win when several
+/// registries offer the same plugin id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRegistryConfig {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub priority: u32,
+    pub enabled: bool,
+    /// Signing key id -> base64 ed25519 public key. Empty means the
+    /// registry is trusted with the curated keys.
+    #[serde(default)]
+    pub trusted_keys: HashMap<String, String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginTrustedKey {
+    pub key_id: String,
+    pub public_key: String,
+    /// SHA-256 of the raw key bytes, shown so users can compare it with the
+    /// fingerprint the publisher gave them before trusting the key.
+    pub fingerprint: String,
+    pub builtin: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRegistryInput {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub priority: u32,
+    #[serde(default = "default_registry_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub trusted_keys: HashMap<String, String>,
+}
+
+fn default_registry_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedRegistryEntry {
+    /// Configured registry id; `None` when searching an ad-hoc registry URL.
+    pub registry_id: Option<String>,
+    pub registry_url: String,
+    #[serde(flatten)]
+    pub entry: PluginRegistryEntry,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryFetchError {
+    pub registry_id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedRegistryIndex {
+    pub entries: Vec<MergedRegistryEntry>,
+    /// Registries that could not be fetched; the rest of the index is still usable.
+    pub errors: Vec<RegistryFetchError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySearchPage {
+    pub entries: Vec<MergedRegistryEntry>,
+    /// Matching entries across all pages.
+    pub total: usize,
+    pub page: u32,
+    pub page_size: u32,
+    /// Every category in the searched index, for the browser's filter list.
+    pub categories: Vec<String>,
+    pub errors: Vec<RegistryFetchError>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct PluginStore {
@@ -349,6 +559,21 @@ struct PluginStore {
     installed_plugins: Vec<InstalledPlugin>,
     #[serde(default)]
     lock_records: Vec<PluginLockRecord>,
+    #[serde(default)]
+    registries: Vec<PluginRegistryConfig>,
+    /// User-added signing keys, key id -> base64 ed25519 public key.
+    #[serde(default)]
+    trusted_keys: HashMap<String, String>,
+    /// Last verified revocation entries from all checked registries.
+    #[serde(default)]
+    revocations: Vec<revocation::PluginRevocation>,
+    /// Disk quota applied to each plugin; defaults to `quota::DEFAULT_QUOTA_BYTES`.
+    #[serde(default)]
+    disk_quota_bytes: Option<u64>,
+    /// Per-attempt registry download timeout; defaults to
+    /// `download::DEFAULT_TIMEOUT_SECONDS`.
+    #[serde(default)]
+    download_timeout_seconds: Option<u64>,
 }
 
 fn now_iso() -> String {
@@ -384,6 +609,13 @@ fn is_optional_permission(permission: &str) -> bool {
             | "ui:mount"
             | "editor:annotations"
             | "system:fonts"
+            | "network:credentials"
+            | "clipboard:read"
+            | "clipboard:write"
+            | "ui:notify"
+            | "scheduler:tasks"
+            | "events:bus"
+            | "document:selection"
     )
 }
 
@@ -461,8 +693,9 @@ fn is_valid_activation_event(event: &str) -> bool {
         return true;
     }
 
-    const PREFIXES: [&str; 7] = [
+    const PREFIXES: [&str; 8] = [
         "onCommand:",
+        "onSchedule:",
         "onExporter:",
         "onImporter:",
         "onUIControl:",
@@ -554,8 +787,8 @@ fn read_manifest_from_zip(
 }
 
 fn validate_manifest(manifest: &PluginManifest) -> Result<(), String> {
-    if manifest.schema_version != 1 {
-        return Err("Unsupported manifest schemaVersion. Expected 1".to_string());
+    if !matches!(manifest.schema_version, 1 | 2) {
+        return Err("Unsupported manifest schemaVersion. Expected 1 or 2".to_string());
     }
 
     if !validate_plugin_id(&manifest.id) {
@@ -629,6 +862,8 @@ fn validate_manifest(manifest: &PluginManifest) -> Result<(), String> {
         }
     }
 
+    allowlist::validate(&manifest.network_allowlist)?;
+
     for permission in manifest.permission_rationales.keys() {
         if !manifest
             .optional_permissions
@@ -858,6 +1093,25 @@ fn validate_manifest(manifest: &PluginManifest) -> Result<(), String> {
         }
     }
 
+    for theme in &manifest.contributes.themes {
+        if !validate_local_contribution_id(&theme.id) {
+            return Err(format!("Invalid theme contribution id '{}'", theme.id));
+        }
+
+        themes::validate_contribution(theme)?;
+    }
+
+    for dictionary in &manifest.contributes.dictionaries {
+        if !validate_local_contribution_id(&dictionary.id) {
+            return Err(format!(
+                "Invalid dictionary contribution id '{}'",
+                dictionary.id
+            ));
+        }
+
+        dictionaries::validate_contribution(dictionary)?;
+    }
+
     for event in &manifest.activation_events {
         if event == "onStartup" {
             continue;
@@ -1032,37 +1286,123 @@ fn trusted_registry_keys() -> HashMap<&'static str, &'static str> {
     HashMap::from([("main-2026", "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=")])
 }
 
+fn configured_registry<'a>(
+    store: &'a PluginStore,
+    registry_url: &str,
+) -> Option<&'a PluginRegistryConfig> {
+    store
+        .registries
+        .iter()
+        .find(|registry| registry.url == registry_url)
+}
+
+/// Keys trusted for signatures from `registry_url`: the registry's own keys
+/// when it has any, otherwise the curated and user-added keys.
+fn registry_trust_keys(store: &PluginStore, registry_url: &str) -> HashMap<String, String> {
+    match configured_registry(store, registry_url) {
+        Some(registry) if !registry.trusted_keys.is_empty() => registry.trusted_keys.clone(),
+        _ => sideload_trust_keys(store),
+    }
+}
+
+/// The curated keys plus any the user added; built-in ids can't be
+/// shadowed.
+fn sideload_trust_keys(store: &PluginStore) -> HashMap<String, String> {
+    let mut keys = trusted_registry_keys()
+        .into_iter()
+        .map(|(key_id, key)| (key_id.to_string(), key.to_string()))
+        .collect::<HashMap<_, _>>();
+    for (key_id, key) in &store.trusted_keys {
+        keys.entry(key_id.clone()).or_insert_with(|| key.clone());
+    }
+    keys
+}
+
+/// Checks the manifest's embedded signature against the archive contents.
+/// Returns the signing key id, or `None` for unsigned archives; a signature
+/// that doesn't verify is always an error.
+fn verify_embedded_signature(
+    store: &PluginStore,
+    manifest: &PluginManifest,
+    zip_bytes: &[u8],
+) -> Result<Option<String>, String> {
+    let Some(signature) = &manifest.signature else {
+        return Ok(None);
+    };
+
+    let digest = signing::archive_digest(zip_bytes)?;
+    if !digest.eq_ignore_ascii_case(&signature.sha256) {
+        return Err(format!(
+            "Plugin archive contents do not match its signature. Expected {}, got {}",
+            signature.sha256, digest
+        ));
+    }
+
+    verify_registry_signature(
+        &sideload_trust_keys(store),
+        &signature.key_id,
+        &signature.sig,
+        &digest,
+    )?;
+    Ok(Some(signature.key_id.clone()))
+}
+
+/// SHA-256 of the decoded key bytes in colon-separated groups of four hex
+/// digits.
+pub(crate) fn key_fingerprint(key_b64: &str) -> String {
+    let key_bytes = BASE64_STANDARD.decode(key_b64).unwrap_or_default();
+    compute_sha256_hex(&key_bytes)
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn trusted_key(key_id: &str, public_key: &str, builtin: bool) -> PluginTrustedKey {
+    PluginTrustedKey {
+        key_id: key_id.to_string(),
+        public_key: public_key.to_string(),
+        fingerprint: key_fingerprint(public_key),
+        builtin,
+    }
+}
+
+fn decode_public_key(key_b64: &str) -> Result<VerifyingKey, String> {
+    let key_bytes = BASE64_STANDARD
+        .decode(key_b64)
+        .map_err(|error| format!("Invalid trusted public key encoding: {}", error))?;
+
+    let key_array: [u8; 32] = key_bytes
+        .try_into()
+        .map_err(|_| "Trusted key must decode to 32 bytes".to_string())?;
+
+    VerifyingKey::from_bytes(&key_array)
+        .map_err(|error| format!("Failed to parse trusted public key: {}", error))
+}
+
 fn verify_registry_signature(
+    keys: &HashMap<String, String>,
     signature_key_id: &str,
     signature_b64: &str,
     sha256_hex: &str,
 ) -> Result<(), String> {
-    let keys = trusted_registry_keys();
     let key_b64 = keys.get(signature_key_id).ok_or_else(|| {
         format!(
-            "Unknown signature key id: {} (expected trusted curated key)",
+            "Unknown signature key id: {} (not trusted for this registry)",
             signature_key_id
         )
     })?;
 
-    let key_bytes = BASE64_STANDARD
-        .decode(key_b64)
-        .map_err(|error| format!("Invalid trusted public key encoding: {}", error))?;
-
     let signature_bytes = BASE64_STANDARD
         .decode(signature_b64)
         .map_err(|error| format!("Invalid signature encoding: {}", error))?;
 
-    let key_array: [u8; 32] = key_bytes
-        .try_into()
-        .map_err(|_| "Trusted key must decode to 32 bytes".to_string())?;
-
     let signature_array: [u8; 64] = signature_bytes
         .try_into()
         .map_err(|_| "Signature must decode to 64 bytes".to_string())?;
 
-    let verifying_key = VerifyingKey::from_bytes(&key_array)
-        .map_err(|error| format!("Failed to parse trusted public key: {}", error))?;
+    let verifying_key = decode_public_key(key_b64)?;
     let signature = Signature::from_bytes(&signature_array);
 
     verifying_key
@@ -1114,7 +1454,7 @@ fn has_permission(plugin: &InstalledPlugin, permission: &str) -> bool {
 }
 
 fn hydrate_entry_source(plugin: &mut InstalledPlugin) {
-    if !plugin.enabled {
+    if !plugin.enabled || plugin.revocation.is_some() {
         plugin.entry_source = None;
         return;
     }
@@ -1151,8 +1491,30 @@ fn install_plugin_from_zip_bytes(
 
     let manifest = read_manifest_from_zip(&mut archive)?;
     validate_manifest(&manifest)?;
+    let locales = if manifest.schema_version >= 2 {
+        let locales = localization::read_archive_locales(&mut archive)?;
+        localization::validate(&manifest, &locales)?;
+        locales
+    } else {
+        localization::Locales::new()
+    };
+    themes::validate_archive(&mut archive, &manifest)?;
+    dictionaries::validate_archive(&mut archive, &manifest)?;
+    // Stored name and description use the default locale so backend
+    // messages never show raw placeholders.
+    let (default_localized, _) = localization::localize(&manifest, &locales, None);
 
     let mut store = load_store(app)?;
+    if let Some(revoked) = revocation::find(&store.revocations, &manifest.id, &manifest.version) {
+        return Err(format!(
+            "Plugin '{}' {} has been revoked: {}",
+            manifest.id, manifest.version, revoked.reason
+        ));
+    }
+    let registry_id = registry_url
+        .as_deref()
+        .and_then(|url| configured_registry(&store, url))
+        .map(|registry| registry.id.clone());
     let previous = store
         .installed_plugins
         .iter()
@@ -1163,6 +1525,16 @@ fn install_plugin_from_zip_bytes(
     let plugin_dir = install_base.join(sanitize_plugin_id(&manifest.id));
     let version_dir = plugin_dir.join(&manifest.version);
 
+    let unpacked_bytes = (0..archive.len())
+        .filter_map(|index| archive.by_index(index).ok().map(|file| file.size()))
+        .sum::<u64>();
+    quota::ensure_room(
+        app,
+        &manifest.id,
+        files::directory_size(&plugin_dir),
+        unpacked_bytes,
+    )?;
+
     remove_plugin_installation(app, &manifest.id)?;
 
     fs::create_dir_all(&version_dir)
@@ -1187,9 +1559,9 @@ fn install_plugin_from_zip_bytes(
 
     let installed_plugin = InstalledPlugin {
         id: manifest.id.clone(),
-        name: manifest.name.clone(),
+        name: default_localized.name.clone(),
         version: manifest.version.clone(),
-        description: manifest.description.clone(),
+        description: default_localized.description.clone(),
         enabled: previous
             .as_ref()
             .map(|plugin| plugin.enabled)
@@ -1220,6 +1592,21 @@ fn install_plugin_from_zip_bytes(
                 .unwrap_or_default(),
         ),
         manifest: manifest.clone(),
+        registry_id: registry_id.clone(),
+        recent_crashes: Vec::new(),
+        auto_disable_count: previous
+            .as_ref()
+            .map(|plugin| plugin.auto_disable_count)
+            .unwrap_or(0),
+        reenable_at: previous
+            .as_ref()
+            .and_then(|plugin| plugin.reenable_at.clone()),
+        revocation: None,
+        update_channel: previous
+            .as_ref()
+            .map(|plugin| plugin.update_channel)
+            .unwrap_or_default(),
+        locale: None,
     };
 
     store
@@ -1239,6 +1626,7 @@ fn install_plugin_from_zip_bytes(
         signature_key_id,
         install_source: Some(install_source.to_string()),
         registry_url,
+        registry_id,
         download_url,
         trust: trust.to_string(),
         enabled: installed_plugin.enabled,
@@ -1253,46 +1641,55 @@ fn install_plugin_from_zip_bytes(
     Ok(installed_plugin)
 }
 
-async fn fetch_registry_entries(registry_url: &str) -> Result<Vec<PluginRegistryEntry>, String> {
-    let client = Client::new();
-    let response = client
+async fn fetch_registry_entries(
+    app: &AppHandle,
+    registry_url: &str,
+) -> Result<Vec<PluginRegistryEntry>, CommandError> {
+    let network_error = |message: String| CommandError::new(ErrorKind::Network, message);
+    let response = proxy::client(app)?
         .get(registry_url)
         .send()
         .await
-        .map_err(|error| format!("Failed to fetch registry index: {}", error))?;
+        .map_err(|error| network_error(format!("Failed to fetch registry index: {}", error)))?;
 
     if !response.status().is_success() {
-        return Err(format!(
+        return Err(network_error(format!(
             "Registry request failed with HTTP status {}",
             response.status()
-        ));
+        )));
     }
 
-    let raw_json = response
-        .text()
-        .await
-        .map_err(|error| format!("Failed to read registry response body: {}", error))?;
+    let raw_json = response.text().await.map_err(|error| {
+        network_error(format!("Failed to read registry response body: {}", error))
+    })?;
 
     let value: Value = serde_json::from_str(&raw_json)
         .map_err(|error| format!("Failed to parse registry JSON: {}", error))?;
 
     if let Some(array) = value.as_array() {
-        return serde_json::from_value(Value::Array(array.clone()))
-            .map_err(|error| format!("Failed to parse registry entries: {}", error));
+        return Ok(serde_json::from_value(Value::Array(array.clone()))
+            .map_err(|error| format!("Failed to parse registry entries: {}", error))?);
     }
 
     if let Some(plugins) = value.get("plugins") {
-        return serde_json::from_value(plugins.clone())
-            .map_err(|error| format!("Failed to parse registry plugins array: {}", error));
+        return Ok(serde_json::from_value(plugins.clone())
+            .map_err(|error| format!("Failed to parse registry plugins array: {}", error))?);
     }
 
-    Err("Registry JSON must be an array or contain a 'plugins' array".to_string())
+    Err(
+        "Registry JSON must be an array or contain a 'plugins' array"
+            .to_string()
+            .into(),
+    )
 }
 
+/// Picks `version` exactly, or else the highest version published on
+/// `channel` or a more stable one. Pinned versions ignore the channel.
 fn select_registry_entry(
     entries: &[PluginRegistryEntry],
     plugin_id: &str,
     version: Option<&str>,
+    channel: PluginChannel,
 ) -> Result<PluginRegistryEntry, String> {
     let mut matches = entries
         .iter()
@@ -1321,6 +1718,7 @@ fn select_registry_entry(
         return Ok(exact);
     }
 
+    matches.retain(|entry| entry.channel <= channel);
     matches.sort_by(|a, b| {
         let left = Version::parse(&a.version).ok();
         let right = Version::parse(&b.version).ok();
@@ -1337,34 +1735,7 @@ fn select_registry_entry(
 }
 
 fn enforce_network_allowlist(plugin: &InstalledPlugin, url: &str) -> Result<(), String> {
-    let parsed =
-        reqwest::Url::parse(url).map_err(|error| format!("Invalid URL '{}': {}", url, error))?;
-
-    if parsed.scheme() != "https" {
-        return Err("Only https:// URLs are allowed for plugin network calls".to_string());
-    }
-
-    let host = parsed
-        .host_str()
-        .ok_or_else(|| "URL is missing a host".to_string())?;
-
-    if plugin.network_allowlist.is_empty() {
-        return Err("Plugin has an empty network allowlist".to_string());
-    }
-
-    if !plugin
-        .network_allowlist
-        .iter()
-        .any(|allowed| allowed.eq_ignore_ascii_case(host))
-    {
-        return Err(format!(
-            "Host '{}' is not in plugin allowlist ({})",
-            host,
-            plugin.network_allowlist.join(", ")
-        ));
-    }
-
-    Ok(())
+    allowlist::check(&plugin.network_allowlist, url)
 }
 
 fn append_audit_log(
@@ -1374,6 +1745,7 @@ fn append_audit_log(
     payload: &Value,
 ) -> Result<(), String> {
     let log_path = plugin_root(app)?.join(PLUGIN_AUDIT_LOG_FILE);
+    audit::rotate_if_needed(&log_path)?;
     let mut file = fs::OpenOptions::new()
         .append(true)
         .create(true)
@@ -1391,6 +1763,22 @@ fn append_audit_log(
         .map_err(|error| format!("Failed to write plugin audit log entry: {}", error))
 }
 
+/// Replaces bulky or sensitive payload fields (file contents, stored values,
+/// request bodies and headers) with their size so the audit log stays
+/// readable and free of credentials.
+fn audit_payload(payload: &Value) -> Value {
+    let mut payload = payload.clone();
+    if let Some(object) = payload.as_object_mut() {
+        for field in ["content", "value", "body", "headers"] {
+            if let Some(value) = object.get_mut(field) {
+                let size = value.to_string().len();
+                *value = json!({ "redactedBytes": size });
+            }
+        }
+    }
+    payload
+}
+
 fn is_supported_diagnostic_kind(kind: &str) -> bool {
     matches!(
         kind,
@@ -1418,104 +1806,479 @@ fn trim_diagnostics(diagnostics: &mut Vec<PluginDiagnostic>) {
     diagnostics.drain(0..extra);
 }
 
+/// Keybindings contributed by enabled plugins as (`plugin:command`,
+/// accelerator) pairs, using the current platform's variant.
+#[cfg(desktop)]
+pub fn contributed_shortcuts(app: &AppHandle) -> Result<Vec<(String, String)>, String> {
+    let store = load_store(app)?;
+    Ok(store
+        .installed_plugins
+        .iter()
+        .filter(|plugin| plugin.enabled)
+        .flat_map(|plugin| {
+            plugin
+                .manifest
+                .contributes
+                .keybindings
+                .iter()
+                .map(move |keybinding| {
+                    let platform_key = if cfg!(target_os = "macos") {
+                        keybinding.mac.as_ref()
+                    } else if cfg!(target_os = "windows") {
+                        keybinding.windows.as_ref()
+                    } else {
+                        keybinding.linux.as_ref()
+                    };
+                    (
+                        format!("{}:{}", plugin.id, keybinding.command),
+                        platform_key.unwrap_or(&keybinding.key).clone(),
+                    )
+                })
+        })
+        .collect())
+}
+
+/// Applies the negotiated locale to a plugin's listed name, description and
+/// contribution labels.
+fn localize_installed(
+    app: &AppHandle,
+    plugin: &mut InstalledPlugin,
+    locale: Option<&str>,
+) -> Result<(), String> {
+    if plugin.manifest.schema_version < 2 {
+        return Ok(());
+    }
+
+    let version_dir = plugin_install_base_dir(app)?
+        .join(sanitize_plugin_id(&plugin.id))
+        .join(&plugin.version);
+    let locales = localization::read_installed_locales(&version_dir);
+    let (manifest, resolved) = localization::localize(&plugin.manifest, &locales, locale);
+
+    plugin.name = manifest.name.clone();
+    plugin.description = manifest.description.clone();
+    plugin.manifest = manifest;
+    plugin.locale = resolved;
+    Ok(())
+}
+
+/// Lists installed plugins with v2 manifests localized for `locale` (a BCP
+/// 47 tag such as `fr-CA`), falling back to each plugin's default locale.
 #[tauri::command]
-pub fn plugin_list_installed(app: AppHandle) -> Result<Vec<InstalledPlugin>, String> {
+pub fn plugin_list_installed(
+    app: AppHandle,
+    locale: Option<String>,
+) -> Result<Vec<InstalledPlugin>, String> {
     let mut store = load_store(&app)?;
 
+    let now = Utc::now();
+    let mut reenabled = false;
+    for plugin in &mut store.installed_plugins {
+        if crashes::reenable_if_due(plugin, now) {
+            plugin.updated_at = now.to_rfc3339();
+            reenabled = true;
+        }
+    }
+    if reenabled {
+        sync_lock_enabled(&mut store);
+        save_store(&app, &store)?;
+    }
+
     for plugin in &mut store.installed_plugins {
         hydrate_entry_source(plugin);
+        localize_installed(&app, plugin, locale.as_deref())?;
     }
 
     Ok(store.installed_plugins)
 }
 
+/// Themes contributed by enabled plugins, with labels localized for
+/// `locale`.
 #[tauri::command]
-pub fn plugin_get_lock_records(app: AppHandle) -> Result<Vec<PluginLockRecord>, String> {
-    let store = load_store(&app)?;
-    Ok(store.lock_records)
+pub fn plugin_list_themes(
+    app: AppHandle,
+    locale: Option<String>,
+) -> Result<Vec<themes::PluginTheme>, String> {
+    let mut store = load_store(&app)?;
+    for plugin in &mut store.installed_plugins {
+        if !plugin.manifest.contributes.themes.is_empty() {
+            localize_installed(&app, plugin, locale.as_deref())?;
+        }
+    }
+
+    Ok(themes::list(&store.installed_plugins))
 }
 
+/// Reads and re-validates a contributed theme's token file.
 #[tauri::command]
-pub fn plugin_install_from_file(app: AppHandle, path: String) -> Result<InstalledPlugin, String> {
-    let zip_bytes = fs::read(&path)
-        .map_err(|error| format!("Failed to read plugin archive '{}': {}", path, error))?;
+pub fn plugin_read_theme(
+    app: AppHandle,
+    plugin_id: String,
+    theme_id: String,
+) -> Result<themes::PluginThemeTokens, String> {
+    let store = load_store(&app)?;
+    let plugin = store
+        .installed_plugins
+        .iter()
+        .find(|plugin| plugin.id == plugin_id)
+        .ok_or_else(|| format!("Plugin '{}' is not installed", plugin_id))?;
 
-    install_plugin_from_zip_bytes(
-        &app,
-        zip_bytes,
-        "sideload",
-        "unverified",
-        false,
-        None,
-        None,
-        None,
-    )
+    themes::read(&app, plugin, &theme_id)
 }
 
+/// Dictionaries contributed by enabled plugins for the spell checker,
+/// optionally only those that apply to `language` (e.g. `en-GB`).
 #[tauri::command]
-pub async fn plugin_fetch_registry_index(
-    registry_url: String,
-) -> Result<Vec<PluginRegistryEntry>, String> {
-    fetch_registry_entries(&registry_url).await
+pub fn plugin_list_dictionaries(
+    app: AppHandle,
+    language: Option<String>,
+    locale: Option<String>,
+) -> Result<Vec<dictionaries::PluginDictionary>, String> {
+    let mut store = load_store(&app)?;
+    for plugin in &mut store.installed_plugins {
+        if !plugin.manifest.contributes.dictionaries.is_empty() {
+            localize_installed(&app, plugin, locale.as_deref())?;
+        }
+    }
+
+    Ok(dictionaries::list(
+        &store.installed_plugins,
+        language.as_deref(),
+    ))
 }
 
+/// Reads and re-validates a contributed dictionary's word list.
 #[tauri::command]
-pub async fn plugin_install_from_registry(
+pub fn plugin_read_dictionary(
     app: AppHandle,
-    registry_url: String,
     plugin_id: String,
-    version: Option<String>,
-) -> Result<InstalledPlugin, String> {
-    let entries = fetch_registry_entries(&registry_url).await?;
-    let selected = select_registry_entry(&entries, &plugin_id, version.as_deref())?;
+    dictionary_id: String,
+) -> Result<dictionaries::PluginDictionaryWords, String> {
+    let store = load_store(&app)?;
+    let plugin = store
+        .installed_plugins
+        .iter()
+        .find(|plugin| plugin.id == plugin_id)
+        .ok_or_else(|| format!("Plugin '{}' is not installed", plugin_id))?;
 
-    if selected.manifest.id != selected.id {
-        return Err("Registry manifest id does not match registry entry id".to_string());
-    }
+    dictionaries::read(&app, plugin, &dictionary_id)
+}
 
-    if selected.manifest.version != selected.version {
-        return Err("Registry manifest version does not match registry entry version".to_string());
-    }
+#[tauri::command]
+pub fn plugin_get_lock_records(app: AppHandle) -> Result<Vec<PluginLockRecord>, String> {
+    let store = load_store(&app)?;
+    Ok(store.lock_records)
+}
 
-    validate_manifest(&selected.manifest)?;
+/// Installs a local archive. Signed archives are verified against the
+/// curated and user-added keys; unsigned ones need `allow_unsigned`.
+#[tauri::command]
+pub fn plugin_install_from_file(
+    app: AppHandle,
+    path: String,
+    allow_unsigned: Option<bool>,
+) -> Result<InstalledPlugin, CommandError> {
+    let zip_bytes =
+        fs::read(&path).map_err(|error| CommandError::io(&error, "read plugin archive", &path))?;
 
-    verify_registry_signature(
-        &selected.signature_key_id,
-        &selected.signature,
-        &selected.sha256,
-    )?;
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes.clone()))
+        .map_err(|error| format!("Failed to parse plugin archive: {}", error))?;
+    let manifest = read_manifest_from_zip(&mut archive)?;
+    let signature_key_id = verify_embedded_signature(&load_store(&app)?, &manifest, &zip_bytes)?;
 
-    let client = Client::new();
-    let response = client
-        .get(&selected.download_url)
-        .send()
-        .await
-        .map_err(|error| format!("Failed to download plugin archive: {}", error))?;
+    if signature_key_id.is_none() && !allow_unsigned.unwrap_or(false) {
+        return Err(format!("{} '{}'", UNSIGNED_ARCHIVE_ERROR, manifest.id).into());
+    }
 
+    let trust = if signature_key_id.is_some() {
+        "verified"
+    } else {
+        "unverified"
+    };
+    Ok(install_plugin_from_zip_bytes(
+        &app,
+        zip_bytes,
+        "sideload",
+        trust,
+        signature_key_id.is_some(),
+        signature_key_id,
+        None,
+        None,
+    )?)
+}
+
+async fn fetch_revocations(
+    app: &AppHandle,
+    store: &PluginStore,
+    registry_url: &str,
+) -> Result<Vec<revocation::PluginRevocation>, String> {
+    let response = proxy::client(app)?
+        .get(revocation::revocations_url(registry_url)?)
+        .send()
+        .await
+        .map_err(|error| format!("Failed to fetch revocation list: {}", error))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
     if !response.status().is_success() {
         return Err(format!(
-            "Plugin download failed with HTTP status {}",
+            "Revocation list request failed with HTTP status {}",
             response.status()
         ));
     }
 
-    let bytes = response
-        .bytes()
+    let list = response
+        .json::<revocation::RevocationList>()
         .await
-        .map_err(|error| format!("Failed to read plugin download response: {}", error))?;
+        .map_err(|error| format!("Failed to parse revocation list: {}", error))?;
+    verify_registry_signature(
+        &registry_trust_keys(store, registry_url),
+        &list.signature_key_id,
+        &list.signature,
+        &revocation::list_digest(&list),
+    )?;
+    revocation::parse_entries(&list)
+}
+
+/// Fetches the signed revocation list from every enabled registry and every
+/// registry a plugin was installed from, then disables revoked plugins.
+/// Registry plugins are matched against their own registry's list; sideloaded
+/// ones against all lists. Returns the plugins that are now revoked.
+#[tauri::command]
+pub async fn plugin_check_revocations(app: AppHandle) -> Result<Vec<InstalledPlugin>, String> {
+    let mut store = load_store(&app)?;
+
+    let mut registry_urls = enabled_registries(&store)
+        .into_iter()
+        .map(|registry| registry.url)
+        .chain(
+            store
+                .lock_records
+                .iter()
+                .filter_map(|record| record.registry_url.clone()),
+        )
+        .collect::<Vec<_>>();
+    registry_urls.sort();
+    registry_urls.dedup();
+
+    let mut by_registry = HashMap::new();
+    for url in &registry_urls {
+        // An unreachable registry keeps the last verified list.
+        match fetch_revocations(&app, &store, url).await {
+            Ok(entries) => {
+                by_registry.insert(url.clone(), entries);
+            }
+            Err(error) => tracing::warn!("{}: {}", url, error),
+        }
+    }
+    if by_registry.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    store.revocations = revocation::merge(&store.revocations, by_registry.clone(), &registry_urls);
+    let mut newly_revoked = Vec::new();
+    let mut revoked = Vec::new();
+    for plugin in &mut store.installed_plugins {
+        let source = store
+            .lock_records
+            .iter()
+            .find(|record| record.plugin_id == plugin.id)
+            .and_then(|record| record.registry_url.clone());
+        let entries = match source.as_ref().and_then(|url| by_registry.get(url)) {
+            Some(entries) => entries.clone(),
+            None if source.is_some() => continue,
+            None => store.revocations.clone(),
+        };
+
+        let found = revocation::find(&entries, &plugin.id, &plugin.version).cloned();
+        if found.is_some() && plugin.revocation.is_none() {
+            plugin.enabled = false;
+            plugin.updated_at = now_iso();
+            newly_revoked.push((plugin.id.clone(), found.clone()));
+        }
+        plugin.revocation = found;
+        if plugin.revocation.is_some() {
+            revoked.push(plugin.clone());
+        }
+    }
+
+    sync_lock_enabled(&mut store);
+    save_store(&app, &store)?;
+
+    for (plugin_id, found) in newly_revoked {
+        append_audit_log(
+            &app,
+            &plugin_id,
+            "plugin:revoked",
+            &json!({ "revocation": found }),
+        )?;
+    }
+
+    Ok(revoked)
+}
+
+#[tauri::command]
+pub fn plugin_list_trusted_keys(app: AppHandle) -> Result<Vec<PluginTrustedKey>, String> {
+    let store = load_store(&app)?;
+
+    let mut keys = trusted_registry_keys()
+        .into_iter()
+        .map(|(key_id, key)| trusted_key(key_id, key, true))
+        .chain(
+            store
+                .trusted_keys
+                .iter()
+                .map(|(key_id, key)| trusted_key(key_id, key, false)),
+        )
+        .collect::<Vec<_>>();
+    keys.sort_by(|a, b| a.key_id.cmp(&b.key_id));
+    Ok(keys)
+}
+
+fn validate_new_trusted_key(key_id: &str, public_key: &str) -> Result<PluginTrustedKey, String> {
+    if key_id.is_empty() {
+        return Err("Trusted key id is required".to_string());
+    }
+    if trusted_registry_keys().contains_key(key_id) {
+        return Err(format!("Key id '{}' is reserved by a built-in key", key_id));
+    }
+    decode_public_key(public_key)?;
+    Ok(trusted_key(key_id, public_key, false))
+}
+
+/// Validates a key and returns its fingerprint without trusting it, so the
+/// user can confirm the fingerprint before calling `plugin_add_trusted_key`.
+#[tauri::command]
+pub fn plugin_preview_trusted_key(
+    key_id: String,
+    public_key: String,
+) -> Result<PluginTrustedKey, String> {
+    validate_new_trusted_key(key_id.trim(), public_key.trim())
+}
 
-    let zip_bytes = bytes.to_vec();
+/// Trusts a signing key. `fingerprint` must be the fingerprint the user
+/// confirmed from `plugin_preview_trusted_key`.
+#[tauri::command]
+pub fn plugin_add_trusted_key(
+    app: AppHandle,
+    key_id: String,
+    public_key: String,
+    fingerprint: String,
+) -> Result<PluginTrustedKey, String> {
+    let key = validate_new_trusted_key(key_id.trim(), public_key.trim())?;
+    if !key.fingerprint.eq_ignore_ascii_case(fingerprint.trim()) {
+        return Err(format!(
+            "Fingerprint mismatch for key '{}'; the key was not trusted",
+            key.key_id
+        ));
+    }
+
+    let mut store = load_store(&app)?;
+    store
+        .trusted_keys
+        .insert(key.key_id.clone(), key.public_key.clone());
+    save_store(&app, &store)?;
+    Ok(key)
+}
+
+#[tauri::command]
+pub fn plugin_remove_trusted_key(app: AppHandle, key_id: String) -> Result<(), String> {
+    if trusted_registry_keys().contains_key(key_id.as_str()) {
+        return Err(format!("Built-in key '{}' cannot be removed", key_id));
+    }
+
+    let mut store = load_store(&app)?;
+    if store.trusted_keys.remove(&key_id).is_none() {
+        return Err(format!("Trusted key '{}' not found", key_id));
+    }
+    save_store(&app, &store)
+}
+
+#[tauri::command]
+pub async fn plugin_fetch_registry_index(
+    app: AppHandle,
+    registry_url: String,
+) -> Result<Vec<PluginRegistryEntry>, CommandError> {
+    fetch_registry_entries(&app, &registry_url).await
+}
+
+fn is_newer_version(installed: &str, candidate: &str) -> bool {
+    match (Version::parse(installed), Version::parse(candidate)) {
+        (Ok(installed), Ok(candidate)) => candidate > installed,
+        _ => false,
+    }
+}
+
+fn added_permissions(previous: &PluginManifest, next: &PluginManifest) -> Vec<String> {
+    next.permissions
+        .iter()
+        .chain(next.optional_permissions.iter())
+        .filter(|permission| {
+            !previous.permissions.contains(*permission)
+                && !previous.optional_permissions.contains(*permission)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Registry the plugin was installed from, according to its lock record.
+fn locked_registry_url(store: &PluginStore, plugin_id: &str) -> Option<String> {
+    store
+        .lock_records
+        .iter()
+        .find(|record| record.plugin_id == plugin_id)
+        .and_then(|record| record.registry_url.clone())
+}
+
+async fn install_registry_entry(
+    app: &AppHandle,
+    registry_url: String,
+    selected: PluginRegistryEntry,
+) -> Result<InstalledPlugin, CommandError> {
+    if selected.manifest.id != selected.id {
+        return Err("Registry manifest id does not match registry entry id"
+            .to_string()
+            .into());
+    }
+
+    if selected.manifest.version != selected.version {
+        return Err(
+            "Registry manifest version does not match registry entry version"
+                .to_string()
+                .into(),
+        );
+    }
+
+    validate_manifest(&selected.manifest)?;
+
+    let store = load_store(app)?;
+    verify_registry_signature(
+        &registry_trust_keys(&store, &registry_url),
+        &selected.signature_key_id,
+        &selected.signature,
+        &selected.sha256,
+    )?;
+
+    let zip_bytes = download::fetch_archive(
+        app,
+        &selected.id,
+        &selected.version,
+        &selected.download_url,
+        download::timeout(&store),
+    )
+    .await?;
     let computed_sha256 = compute_sha256_hex(&zip_bytes);
 
     if !computed_sha256.eq_ignore_ascii_case(&selected.sha256) {
         return Err(format!(
             "SHA256 mismatch for downloaded plugin archive. Expected {}, got {}",
             selected.sha256, computed_sha256
-        ));
+        )
+        .into());
     }
 
-    install_plugin_from_zip_bytes(
-        &app,
+    Ok(install_plugin_from_zip_bytes(
+        app,
         zip_bytes,
         "registry",
         "verified",
@@ -1523,9 +2286,594 @@ pub async fn plugin_install_from_registry(
         Some(selected.signature_key_id),
         Some(registry_url),
         Some(selected.download_url),
+    )?)
+}
+
+/// Installs from `registry_url`, or, without one, from the highest-priority
+/// enabled registry that offers the plugin.
+#[tauri::command]
+pub async fn plugin_install_from_registry(
+    app: AppHandle,
+    registry_url: Option<String>,
+    plugin_id: String,
+    version: Option<String>,
+) -> Result<InstalledPlugin, CommandError> {
+    let store = load_store(&app)?;
+    let channel = installed_channel(&store, &plugin_id);
+
+    if let Some(registry_url) = registry_url {
+        let entries = fetch_registry_entries(&app, &registry_url).await?;
+        let selected = select_registry_entry(&entries, &plugin_id, version.as_deref(), channel)?;
+        return install_registry_entry(&app, registry_url, selected).await;
+    }
+
+    let mut last_error = CommandError::from(format!(
+        "Plugin '{}' not found in any enabled registry",
+        plugin_id
+    ));
+    for registry in enabled_registries(&store) {
+        let selected = match fetch_registry_entries(&app, &registry.url).await {
+            Ok(entries) => select_registry_entry(&entries, &plugin_id, version.as_deref(), channel)
+                .map_err(CommandError::from),
+            Err(error) => Err(error),
+        };
+
+        match selected {
+            Ok(selected) => return install_registry_entry(&app, registry.url, selected).await,
+            Err(error) => last_error = error,
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Compares installed plugins with the latest registry versions. Without
+/// `registry_url`, each plugin is checked against the registry it was
+/// installed from; sideloaded plugins are skipped.
+#[tauri::command]
+pub async fn plugin_check_updates(
+    app: AppHandle,
+    registry_url: Option<String>,
+) -> Result<Vec<PluginUpdateInfo>, CommandError> {
+    let store = load_store(&app)?;
+    let mut indexes: HashMap<String, Vec<PluginRegistryEntry>> = HashMap::new();
+    let mut updates = Vec::new();
+
+    for plugin in &store.installed_plugins {
+        let Some(url) = registry_url
+            .clone()
+            .or_else(|| locked_registry_url(&store, &plugin.id))
+        else {
+            continue;
+        };
+
+        if !indexes.contains_key(&url) {
+            let entries = fetch_registry_entries(&app, &url).await?;
+            indexes.insert(url.clone(), entries);
+        }
+
+        let Ok(latest) =
+            select_registry_entry(&indexes[&url], &plugin.id, None, plugin.update_channel)
+        else {
+            continue;
+        };
+
+        if is_newer_version(&plugin.version, &latest.version) {
+            updates.push(PluginUpdateInfo {
+                plugin_id: plugin.id.clone(),
+                name: latest.name.clone(),
+                installed_version: plugin.version.clone(),
+                available_version: latest.version.clone(),
+                changelog: latest.changelog.clone(),
+                registry_url: url,
+                new_permissions: added_permissions(&plugin.manifest, &latest.manifest),
+            });
+        }
+    }
+
+    Ok(updates)
+}
+
+/// Installs the latest registry version of an installed plugin. Enabled
+/// state, permission grants and diagnostics carry over; configuration values
+/// are keyed by plugin id and are left untouched.
+#[tauri::command]
+pub async fn plugin_update(
+    app: AppHandle,
+    plugin_id: String,
+    registry_url: Option<String>,
+) -> Result<InstalledPlugin, CommandError> {
+    let store = load_store(&app)?;
+    let installed = store
+        .installed_plugins
+        .iter()
+        .find(|plugin| plugin.id == plugin_id)
+        .ok_or_else(|| format!("Plugin '{}' is not installed", plugin_id))?;
+
+    let registry_url = registry_url
+        .or_else(|| locked_registry_url(&store, &plugin_id))
+        .ok_or_else(|| {
+            format!(
+                "Plugin '{}' was not installed from a registry; provide a registry URL",
+                plugin_id
+            )
+        })?;
+
+    let entries = fetch_registry_entries(&app, &registry_url).await?;
+    let latest = select_registry_entry(&entries, &plugin_id, None, installed.update_channel)?;
+
+    if !is_newer_version(&installed.version, &latest.version) {
+        return Err(format!(
+            "Plugin '{}' is already up to date ({})",
+            plugin_id, installed.version
+        )
+        .into());
+    }
+
+    let updated = install_registry_entry(&app, registry_url, latest).await?;
+    append_audit_log(
+        &app,
+        &plugin_id,
+        "plugin:update",
+        &json!({ "from": installed.version, "to": updated.version }),
+    )?;
+
+    Ok(updated)
+}
+
+fn installed_channel(store: &PluginStore, plugin_id: &str) -> PluginChannel {
+    store
+        .installed_plugins
+        .iter()
+        .find(|plugin| plugin.id == plugin_id)
+        .map(|plugin| plugin.update_channel)
+        .unwrap_or_default()
+}
+
+fn enabled_registries(store: &PluginStore) -> Vec<PluginRegistryConfig> {
+    let mut registries = store
+        .registries
+        .iter()
+        .filter(|registry| registry.enabled)
+        .cloned()
+        .collect::<Vec<_>>();
+    registries.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.id.cmp(&b.id)));
+    registries
+}
+
+fn validate_registry_input(input: &PluginRegistryInput) -> Result<(), String> {
+    if !validate_local_contribution_id(&input.id) {
+        return Err(format!("Invalid registry id '{}'", input.id));
+    }
+
+    if input.name.trim().is_empty() {
+        return Err("Registry name is required".to_string());
+    }
+
+    let parsed = reqwest::Url::parse(&input.url)
+        .map_err(|error| format!("Invalid registry URL '{}': {}", input.url, error))?;
+    let is_local = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if parsed.scheme() != "https" && !(parsed.scheme() == "http" && is_local) {
+        return Err(
+            "Registry URLs must use https:// (http:// is allowed for localhost)".to_string(),
+        );
+    }
+
+    for (key_id, key) in &input.trusted_keys {
+        if key_id.trim().is_empty() {
+            return Err("Trusted key ids must not be empty".to_string());
+        }
+        decode_public_key(key).map_err(|error| format!("Trusted key '{}': {}", key_id, error))?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn plugin_list_registries(app: AppHandle) -> Result<Vec<PluginRegistryConfig>, String> {
+    let mut registries = load_store(&app)?.registries;
+    registries.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.id.cmp(&b.id)));
+    Ok(registries)
+}
+
+/// Adds a registry or replaces the one with the same id.
+#[tauri::command]
+pub fn plugin_save_registry(
+    app: AppHandle,
+    registry: PluginRegistryInput,
+) -> Result<PluginRegistryConfig, String> {
+    validate_registry_input(&registry)?;
+
+    let mut store = load_store(&app)?;
+    if store
+        .registries
+        .iter()
+        .any(|item| item.url == registry.url && item.id != registry.id)
+    {
+        return Err(format!(
+            "Registry URL '{}' is already configured",
+            registry.url
+        ));
+    }
+
+    let config = PluginRegistryConfig {
+        id: registry.id,
+        name: registry.name.trim().to_string(),
+        url: registry.url,
+        priority: registry.priority,
+        enabled: registry.enabled,
+        trusted_keys: registry.trusted_keys,
+        updated_at: now_iso(),
+    };
+    store.registries.retain(|item| item.id != config.id);
+    store.registries.push(config.clone());
+    save_store(&app, &store)?;
+
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn plugin_remove_registry(app: AppHandle, registry_id: String) -> Result<(), String> {
+    let mut store = load_store(&app)?;
+    let before_count = store.registries.len();
+    store.registries.retain(|item| item.id != registry_id);
+
+    if before_count == store.registries.len() {
+        return Err(format!("Registry '{}' is not configured", registry_id));
+    }
+
+    save_store(&app, &store)
+}
+
+/// Latest version of each plugin in one registry index, sorted by id.
+fn latest_registry_entries(entries: &[PluginRegistryEntry]) -> Vec<PluginRegistryEntry> {
+    let mut plugin_ids = entries
+        .iter()
+        .map(|entry| entry.id.clone())
+        .collect::<Vec<_>>();
+    plugin_ids.sort();
+    plugin_ids.dedup();
+
+    plugin_ids
+        .iter()
+        .filter_map(|plugin_id| {
+            select_registry_entry(entries, plugin_id, None, PluginChannel::Stable).ok()
+        })
+        .collect()
+}
+
+/// Fetches every enabled registry and merges their indexes. For each plugin
+/// id the latest version from the highest-priority registry is kept.
+async fn fetch_merged_index(app: &AppHandle, store: &PluginStore) -> MergedRegistryIndex {
+    let mut entries: Vec<MergedRegistryEntry> = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen_ids = HashSet::new();
+
+    for registry in enabled_registries(store) {
+        let registry_entries = match fetch_registry_entries(app, &registry.url).await {
+            Ok(registry_entries) => registry_entries,
+            Err(error) => {
+                errors.push(RegistryFetchError {
+                    registry_id: registry.id.clone(),
+                    message: error.message,
+                });
+                continue;
+            }
+        };
+
+        for entry in latest_registry_entries(&registry_entries) {
+            if seen_ids.insert(entry.id.clone()) {
+                entries.push(MergedRegistryEntry {
+                    registry_id: Some(registry.id.clone()),
+                    registry_url: registry.url.clone(),
+                    entry,
+                });
+            }
+        }
+    }
+
+    MergedRegistryIndex { entries, errors }
+}
+
+/// `query` must already be lowercased.
+fn registry_entry_matches(entry: &PluginRegistryEntry, query: &str) -> bool {
+    entry.id.to_lowercase().contains(query)
+        || entry.name.to_lowercase().contains(query)
+        || entry.description.to_lowercase().contains(query)
+}
+
+fn normalize_search_query(query: Option<String>) -> Option<String> {
+    query
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+#[tauri::command]
+pub async fn plugin_search_registries(
+    app: AppHandle,
+    query: Option<String>,
+) -> Result<MergedRegistryIndex, String> {
+    let store = load_store(&app)?;
+    let mut index = fetch_merged_index(&app, &store).await;
+
+    if let Some(query) = normalize_search_query(query) {
+        index
+            .entries
+            .retain(|item| registry_entry_matches(&item.entry, &query));
+    }
+
+    Ok(index)
+}
+
+/// Filters and pages a registry index for the plugin browser. Searches
+/// `registry_url` when given, otherwise the merged index of all enabled
+/// registries. Results are ordered by name match, then downloads, then name.
+#[tauri::command]
+pub async fn plugin_search_registry(
+    app: AppHandle,
+    query: Option<String>,
+    category: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    registry_url: Option<String>,
+) -> Result<RegistrySearchPage, String> {
+    let store = load_store(&app)?;
+    let index = match registry_url {
+        Some(registry_url) => MergedRegistryIndex {
+            entries: latest_registry_entries(&fetch_registry_entries(&app, &registry_url).await?)
+                .into_iter()
+                .map(|entry| MergedRegistryEntry {
+                    registry_id: configured_registry(&store, &registry_url)
+                        .map(|registry| registry.id.clone()),
+                    registry_url: registry_url.clone(),
+                    entry,
+                })
+                .collect(),
+            errors: Vec::new(),
+        },
+        None => fetch_merged_index(&app, &store).await,
+    };
+
+    let mut categories = index
+        .entries
+        .iter()
+        .flat_map(|item| item.entry.categories.iter().cloned())
+        .collect::<Vec<_>>();
+    categories.sort();
+    categories.dedup();
+
+    let query = normalize_search_query(query);
+    let category = category
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let mut entries = index
+        .entries
+        .into_iter()
+        .filter(|item| {
+            query
+                .as_deref()
+                .map(|query| registry_entry_matches(&item.entry, query))
+                .unwrap_or(true)
+                && category
+                    .as_ref()
+                    .map(|category| {
+                        item.entry
+                            .categories
+                            .iter()
+                            .any(|value| value.eq_ignore_ascii_case(category))
+                    })
+                    .unwrap_or(true)
+        })
+        .collect::<Vec<_>>();
+
+    let name_match = |item: &MergedRegistryEntry| {
+        query
+            .as_deref()
+            .map(|query| {
+                item.entry.id.to_lowercase().starts_with(query)
+                    || item.entry.name.to_lowercase().starts_with(query)
+            })
+            .unwrap_or(false)
+    };
+    entries.sort_by(|a, b| {
+        name_match(b)
+            .cmp(&name_match(a))
+            .then_with(|| b.entry.downloads.cmp(&a.entry.downloads))
+            .then_with(|| {
+                a.entry
+                    .name
+                    .to_lowercase()
+                    .cmp(&b.entry.name.to_lowercase())
+            })
+    });
+
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size
+        .unwrap_or(REGISTRY_SEARCH_PAGE_SIZE)
+        .clamp(1, REGISTRY_SEARCH_MAX_PAGE_SIZE);
+    let total = entries.len();
+    let entries = entries
+        .into_iter()
+        .skip((page - 1).saturating_mul(page_size) as usize)
+        .take(page_size as usize)
+        .collect();
+
+    Ok(RegistrySearchPage {
+        entries,
+        total,
+        page,
+        page_size,
+        categories,
+        errors: index.errors,
+    })
+}
+
+#[tauri::command]
+pub fn plugin_query_audit_log(
+    app: AppHandle,
+    query: audit::AuditLogQuery,
+) -> Result<audit::AuditLogPage, String> {
+    audit::query(&plugin_root(&app)?.join(PLUGIN_AUDIT_LOG_FILE), &query)
+}
+
+/// Disk usage of one installed plugin, or of every installed plugin.
+#[tauri::command]
+pub fn plugin_disk_usage(
+    app: AppHandle,
+    plugin_id: Option<String>,
+) -> Result<Vec<quota::PluginDiskUsage>, String> {
+    let store = load_store(&app)?;
+    store
+        .installed_plugins
+        .iter()
+        .filter(|plugin| plugin_id.as_ref().is_none_or(|id| &plugin.id == id))
+        .map(|plugin| quota::usage(&app, &store, &plugin.id))
+        .collect()
+}
+
+/// Sets the disk quota applied to each plugin; `None` restores the default.
+#[tauri::command]
+pub fn plugin_set_disk_quota(app: AppHandle, quota_bytes: Option<u64>) -> Result<(), String> {
+    if quota_bytes.is_some_and(|bytes| bytes < quota::MIN_QUOTA_BYTES) {
+        return Err(format!(
+            "Plugin disk quota must be at least {} bytes",
+            quota::MIN_QUOTA_BYTES
+        ));
+    }
+
+    let mut store = load_store(&app)?;
+    store.disk_quota_bytes = quota_bytes;
+    save_store(&app, &store)
+}
+
+/// Sets the per-attempt timeout for registry downloads; `None` restores the
+/// default.
+#[tauri::command]
+pub fn plugin_set_download_timeout(
+    app: AppHandle,
+    timeout_seconds: Option<u64>,
+) -> Result<(), String> {
+    if timeout_seconds.is_some_and(|seconds| {
+        !(download::MIN_TIMEOUT_SECONDS..=download::MAX_TIMEOUT_SECONDS).contains(&seconds)
+    }) {
+        return Err(format!(
+            "Download timeout must be between {} and {} seconds",
+            download::MIN_TIMEOUT_SECONDS,
+            download::MAX_TIMEOUT_SECONDS
+        ));
+    }
+
+    let mut store = load_store(&app)?;
+    store.download_timeout_seconds = timeout_seconds;
+    save_store(&app, &store)
+}
+
+/// Deletes a plugin's data directory and key-value storage to reclaim space.
+#[tauri::command]
+pub fn plugin_clear_data(
+    app: AppHandle,
+    plugin_id: String,
+) -> Result<quota::PluginDiskUsage, String> {
+    let store = load_store(&app)?;
+    if !store
+        .installed_plugins
+        .iter()
+        .any(|plugin| plugin.id == plugin_id)
+    {
+        return Err(format!("Plugin '{}' is not installed", plugin_id));
+    }
+
+    quota::clear_data(&app, &plugin_id)?;
+    append_audit_log(&app, &plugin_id, "plugin:clear-data", &json!({}))?;
+    quota::usage(&app, &store, &plugin_id)
+}
+
+/// Scheduled tasks of one plugin, or of every plugin.
+#[tauri::command]
+pub fn plugin_list_scheduled_tasks(
+    app: AppHandle,
+    plugin_id: Option<String>,
+) -> Result<Vec<scheduler::ScheduledTask>, String> {
+    scheduler::list(&app, plugin_id.as_deref())
+}
+
+#[tauri::command]
+pub fn plugin_cancel_scheduled_task(
+    app: AppHandle,
+    plugin_id: String,
+    task_id: String,
+) -> Result<(), String> {
+    if !scheduler::cancel(&app, &plugin_id, &task_id)? {
+        return Err(format!(
+            "Plugin '{}' has no scheduled task '{}'",
+            plugin_id, task_id
+        ));
+    }
+
+    append_audit_log(
+        &app,
+        &plugin_id,
+        "scheduler:cancel",
+        &json!({ "taskId": task_id, "by": "user" }),
+    )
+}
+
+/// Starts the hidden window the plugin workers run in. Async so the window
+/// is not created from a blocked IPC thread.
+#[tauri::command]
+pub async fn plugin_process_start(app: AppHandle) -> Result<(), String> {
+    process::start(&app)
+}
+
+/// Kills the plugin process after it stopped answering and starts a new one.
+#[tauri::command]
+pub async fn plugin_process_restart(app: AppHandle) -> Result<(), String> {
+    process::restart(&app)
+}
+
+#[tauri::command]
+pub fn plugin_export_profile(
+    app: AppHandle,
+    output_path: String,
+) -> Result<profile::PluginProfile, String> {
+    profile::export(&app, &output_path)
+}
+
+/// Packs an unpacked plugin directory into a deterministic archive,
+/// optionally signing it with a local ed25519 key.
+#[tauri::command]
+pub fn plugin_pack(
+    input_dir: String,
+    output_path: Option<String>,
+    signing_key_path: Option<String>,
+    key_id: Option<String>,
+) -> Result<pack::PluginPackResult, String> {
+    let signing_key = match (signing_key_path, key_id) {
+        (Some(key_path), Some(key_id)) => Some(pack::PackSigningKey {
+            key_id,
+            key_path: PathBuf::from(key_path),
+        }),
+        (None, None) => None,
+        _ => return Err("A signing key requires both a key path and a key id".to_string()),
+    };
+
+    pack::pack(
+        Path::new(&input_dir),
+        output_path.as_deref().map(Path::new),
+        signing_key.as_ref(),
     )
 }
 
+/// Reinstalls the plugins listed in an exported profile from their
+/// registries and re-applies enabled state and permission grants.
+#[tauri::command]
+pub async fn plugin_import_profile(
+    app: AppHandle,
+    input_path: String,
+) -> Result<profile::PluginProfileImportReport, String> {
+    profile::import(&app, &input_path).await
+}
+
 #[tauri::command]
 pub fn plugin_uninstall(app: AppHandle, plugin_id: String) -> Result<(), String> {
     if !validate_plugin_id(&plugin_id) {
@@ -1547,11 +2895,39 @@ pub fn plugin_uninstall(app: AppHandle, plugin_id: String) -> Result<(), String>
     }
 
     remove_plugin_installation(&app, &plugin_id)?;
+    scheduler::remove_plugin(&app, &plugin_id)?;
     save_store(&app, &store)?;
 
     Ok(())
 }
 
+/// Switches the release channel used by update checks for one plugin. The
+/// installed version is kept until the next update.
+#[tauri::command]
+pub fn plugin_set_update_channel(
+    app: AppHandle,
+    plugin_id: String,
+    channel: PluginChannel,
+) -> Result<InstalledPlugin, String> {
+    let mut store = load_store(&app)?;
+
+    let plugin = store
+        .installed_plugins
+        .iter_mut()
+        .find(|plugin| plugin.id == plugin_id)
+        .ok_or_else(|| format!("Plugin '{}' is not installed", plugin_id))?;
+
+    plugin.update_channel = channel;
+    plugin.updated_at = now_iso();
+
+    let mut output = plugin.clone();
+    hydrate_entry_source(&mut output);
+
+    save_store(&app, &store)?;
+
+    Ok(output)
+}
+
 #[tauri::command]
 pub fn plugin_enable_disable(
     app: AppHandle,
@@ -1566,8 +2942,18 @@ pub fn plugin_enable_disable(
         .find(|plugin| plugin.id == plugin_id)
         .ok_or_else(|| format!("Plugin '{}' is not installed", plugin_id))?;
 
+    if enabled {
+        if let Some(revoked) = &plugin.revocation {
+            return Err(format!(
+                "Plugin '{}' has been revoked and cannot be enabled: {}",
+                plugin_id, revoked.reason
+            ));
+        }
+    }
+
     plugin.enabled = enabled;
     plugin.updated_at = now_iso();
+    crashes::clear_schedule(plugin);
 
     let lock = store
         .lock_records
@@ -1642,6 +3028,75 @@ pub fn plugin_update_permissions(
     Ok(output)
 }
 
+/// Persists a brokered consent decision through the same path as the
+/// settings UI and audits it.
+fn record_permission_decision(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    permission: &str,
+    granted: bool,
+) -> Result<InstalledPlugin, String> {
+    let mut grants = plugin
+        .granted_permissions
+        .iter()
+        .filter(|grant| grant.permission != permission)
+        .cloned()
+        .collect::<Vec<_>>();
+    grants.push(PluginPermissionGrant {
+        permission: permission.to_string(),
+        granted,
+        granted_at: granted.then(now_iso),
+    });
+
+    let updated = plugin_update_permissions(app.clone(), plugin.id.clone(), grants)?;
+    append_audit_log(
+        app,
+        &plugin.id,
+        "permission:decision",
+        &json!({ "permission": permission, "granted": granted }),
+    )?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn plugin_respond_permission_request(
+    broker: State<'_, PermissionBroker>,
+    request_id: String,
+    granted: bool,
+) -> Result<(), String> {
+    broker.respond(&request_id, granted)
+}
+
+/// Records how long the frontend took to activate a plugin, from starting
+/// its worker until it reported ready.
+#[tauri::command]
+pub fn plugin_record_activation(
+    app: AppHandle,
+    plugin_id: String,
+    activation_event: String,
+    duration_ms: u64,
+) -> Result<(), String> {
+    if !validate_plugin_id(&plugin_id) {
+        return Err("Invalid plugin id".to_string());
+    }
+
+    app.state::<PluginMetrics>()
+        .record_activation(&plugin_id, &activation_event, duration_ms);
+    Ok(())
+}
+
+/// Activation time, host-call counts and network traffic per installed
+/// plugin for the current session, slowest to activate first.
+#[tauri::command]
+pub fn plugin_performance_report(
+    app: AppHandle,
+) -> Result<Vec<metrics::PluginPerformance>, String> {
+    let store = load_store(&app)?;
+    Ok(app
+        .state::<PluginMetrics>()
+        .report(&store.installed_plugins))
+}
+
 #[tauri::command]
 pub fn plugin_record_diagnostic(
     app: AppHandle,
@@ -1711,6 +3166,62 @@ pub fn plugin_record_diagnostic(
     Ok(output)
 }
 
+fn sync_lock_enabled(store: &mut PluginStore) {
+    for plugin in &store.installed_plugins {
+        if let Some(lock) = store
+            .lock_records
+            .iter_mut()
+            .find(|record| record.plugin_id == plugin.id)
+        {
+            lock.enabled = plugin.enabled;
+            lock.updated_at = plugin.updated_at.clone();
+        }
+    }
+}
+
+/// Records a worker crash and applies the crash policy. Emits
+/// `plugin-auto-disabled` when the plugin is disabled as a result.
+#[tauri::command]
+pub fn plugin_report_crash(
+    app: AppHandle,
+    plugin_id: String,
+    message: String,
+) -> Result<crashes::CrashOutcome, String> {
+    plugin_record_diagnostic(
+        app.clone(),
+        plugin_id.clone(),
+        PluginDiagnosticInput {
+            kind: "runtime-crash".to_string(),
+            message,
+            operation: Some("worker".to_string()),
+        },
+    )?;
+
+    let mut store = load_store(&app)?;
+    let plugin = store
+        .installed_plugins
+        .iter_mut()
+        .find(|plugin| plugin.id == plugin_id)
+        .ok_or_else(|| format!("Plugin '{}' is not installed", plugin_id))?;
+
+    let outcome = crashes::register_crash(plugin, Utc::now());
+    plugin.updated_at = now_iso();
+    sync_lock_enabled(&mut store);
+    save_store(&app, &store)?;
+
+    if outcome.disabled {
+        append_audit_log(
+            &app,
+            &plugin_id,
+            "plugin:auto-disable",
+            &json!({ "crashes": outcome.crashes_in_window, "reenableAt": outcome.reenable_at }),
+        )?;
+        let _ = app.emit("plugin-auto-disabled", outcome.clone());
+    }
+
+    Ok(outcome)
+}
+
 #[tauri::command]
 pub fn plugin_clear_diagnostics(
     app: AppHandle,
@@ -1729,6 +3240,8 @@ pub fn plugin_clear_diagnostics(
 
     plugin.diagnostics.clear();
     plugin.crash_count = 0;
+    plugin.auto_disable_count = 0;
+    crashes::clear_schedule(plugin);
     plugin.updated_at = now_iso();
 
     if let Some(lock) = store
@@ -1755,114 +3268,110 @@ pub async fn plugin_host_call(
     payload: Value,
 ) -> Result<Value, String> {
     let store = load_store(&app)?;
-    let plugin = store
+    let mut plugin = store
         .installed_plugins
-        .iter()
+        .into_iter()
         .find(|plugin| plugin.id == plugin_id)
         .ok_or_else(|| format!("Plugin '{}' is not installed", plugin_id))?;
 
     if !plugin.enabled {
         return Err(format!("Plugin '{}' is disabled", plugin_id));
     }
+    if plugin.revocation.is_some() {
+        return Err(format!("Plugin '{}' has been revoked", plugin_id));
+    }
 
-    append_audit_log(&app, &plugin_id, &operation, &payload)?;
-
-    match operation.as_str() {
-        "network:get_json" => {
-            if !has_permission(plugin, "network:https") {
-                return Err("Permission denied: network:https".to_string());
-            }
-
-            let url = payload
-                .get("url")
-                .and_then(Value::as_str)
-                .ok_or_else(|| "Payload must include string field 'url'".to_string())?;
-
-            enforce_network_allowlist(plugin, url)?;
-
-            let response = Client::new()
-                .get(url)
-                .send()
-                .await
-                .map_err(|error| format!("Network request failed: {}", error))?;
+    append_audit_log(&app, &plugin_id, &operation, &audit_payload(&payload))?;
+    let _call = crash_reports::PluginCall::enter(&plugin_id, &operation);
 
-            if !response.status().is_success() {
-                return Err(format!(
-                    "HTTP request failed with status {}",
-                    response.status()
-                ));
+    if let Some(permission) = consent::required_permission(&operation) {
+        if consent::should_prompt(&app, &plugin, permission) {
+            if let Some(updated) = consent::request(&app, &plugin, permission, &operation).await? {
+                plugin = updated;
             }
-
-            response
-                .json::<Value>()
-                .await
-                .map_err(|error| format!("Failed to parse JSON response: {}", error))
         }
-
-        "network:get_text" => {
-            if !has_permission(plugin, "network:https") {
-                return Err("Permission denied: network:https".to_string());
+    }
+    let plugin = &plugin;
+
+    let started = Instant::now();
+    let result = async {
+        match operation.as_str() {
+            "network:get_json" | "network:get_text" | "network:request" | "network:download" => {
+                if !has_permission(plugin, "network:https") {
+                    return Err("Permission denied: network:https".to_string());
+                }
+
+                match operation.as_str() {
+                    "network:get_json" => {
+                        network::get(&app, plugin, &payload, network::ResponseKind::Json).await
+                    }
+                    "network:get_text" => {
+                        network::get(&app, plugin, &payload, network::ResponseKind::Text).await
+                    }
+                    "network:download" => network::download(&app, plugin, &payload).await,
+                    _ => network::request(&app, plugin, &payload).await,
+                }
             }
 
-            let url = payload
-                .get("url")
-                .and_then(Value::as_str)
-                .ok_or_else(|| "Payload must include string field 'url'".to_string())?;
+            "audit:log" => Ok(json!({ "ok": true })),
 
-            enforce_network_allowlist(plugin, url)?;
+            "fs:read" | "fs:write" | "fs:list" | "fs:pick-read" | "fs:pick-write" => {
+                files::handle(&app, plugin, &operation, &payload).await
+            }
 
-            let response = Client::new()
-                .get(url)
-                .send()
-                .await
-                .map_err(|error| format!("Network request failed: {}", error))?;
+            "ui:notify" => notify::handle(&app, plugin, &payload),
 
-            if !response.status().is_success() {
-                return Err(format!(
-                    "HTTP request failed with status {}",
-                    response.status()
-                ));
+            "events:publish" | "events:subscribe" | "events:unsubscribe" => {
+                events::handle(&app, plugin, &operation, &payload)
             }
 
-            let body = response
-                .text()
-                .await
-                .map_err(|error| format!("Failed to read text response: {}", error))?;
-
-            Ok(json!({ "text": body }))
-        }
+            "scheduler:register" | "scheduler:cancel" | "scheduler:list" => {
+                scheduler::handle(&app, plugin, &operation, &payload)
+            }
 
-        "audit:log" => Ok(json!({ "ok": true })),
+            "clipboard:read" | "clipboard:write" => {
+                clipboard::handle(&app, plugin, &operation, &payload)
+            }
 
-        "system:list_fonts" => {
-            if !has_permission(plugin, "system:fonts") {
-                return Err("Permission denied: system:fonts".to_string());
+            "storage:get" | "storage:set" | "storage:delete" | "storage:list" => {
+                storage::handle(&app, &plugin_id, &operation, &payload)
             }
 
-            let families = fonts::list_system_font_families()
-                .into_iter()
-                .map(|family| {
-                    json!({
-                        "name": family.name,
-                        "variants": family.variants.into_iter().map(|variant| {
-                            json!({
-                                "name": variant.name,
-                                "weight": variant.weight,
-                                "style": variant.style,
-                            })
-                        }).collect::<Vec<_>>(),
+            "system:list_fonts" => {
+                if !has_permission(plugin, "system:fonts") {
+                    return Err("Permission denied: system:fonts".to_string());
+                }
+
+                let families = fonts::list_system_font_families()
+                    .into_iter()
+                    .map(|family| {
+                        json!({
+                            "name": family.name,
+                            "variants": family.variants.into_iter().map(|variant| {
+                                json!({
+                                    "name": variant.name,
+                                    "weight": variant.weight,
+                                    "style": variant.style,
+                                })
+                            }).collect::<Vec<_>>(),
+                        })
                     })
-                })
-                .collect::<Vec<_>>();
+                    .collect::<Vec<_>>();
 
-            Ok(json!({ "families": families }))
-        }
+                Ok(json!({ "families": families }))
+            }
 
-        "document:get" | "document:replace" => Err(
-            "Document operations must be brokered by the frontend host, not plugin_host_call"
-                .to_string(),
-        ),
+            "document:get" | "document:replace" => Err(
+                "Document operations must be brokered by the frontend host, not plugin_host_call"
+                    .to_string(),
+            ),
 
-        _ => Err(format!("Unsupported host operation '{}'", operation)),
+            _ => Err(format!("Unsupported host operation '{}'", operation)),
+        }
     }
+    .await;
+
+    app.state::<PluginMetrics>()
+        .record_host_call(&plugin_id, started.elapsed(), result.is_ok());
+    result
 }
@@ -0,0 +1,425 @@
+use super::{
+    enforce_network_allowlist, files, has_permission, quota, InstalledPlugin, PluginMetrics,
+};
+use crate::proxy;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{redirect, Client, Method};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const DEFAULT_TIMEOUT_MS: u64 = 15_000;
+const MAX_TIMEOUT_MS: u64 = 60_000;
+const DEFAULT_MAX_RESPONSE_BYTES: usize = 2 * 1024 * 1024;
+const MAX_RESPONSE_BYTES: usize = 10 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 5;
+const DOWNLOADS_DIR: &str = "downloads";
+const DEFAULT_MAX_DOWNLOAD_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_DOWNLOAD_BYTES: u64 = 50 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const PROGRESS_INTERVAL_BYTES: u64 = 256 * 1024;
+const DOWNLOAD_PROGRESS_EVENT: &str = "plugin-download-progress";
+
+/// Headers that carry credentials; plugins need `network:credentials` to set them.
+const CREDENTIAL_HEADERS: [&str; 5] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+    "x-auth-token",
+];
+/// Headers the host always controls.
+const FORBIDDEN_HEADERS: [&str; 5] = [
+    "host",
+    "content-length",
+    "connection",
+    "transfer-encoding",
+    "origin",
+];
+
+pub enum ResponseKind {
+    Json,
+    Text,
+}
+
+/// A validated plugin HTTP request.
+pub struct PluginRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+    pub timeout: Duration,
+    pub max_response_bytes: usize,
+}
+
+fn parse_method(payload: &Value) -> Result<Method, String> {
+    match payload
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET")
+        .to_ascii_uppercase()
+        .as_str()
+    {
+        "GET" => Ok(Method::GET),
+        "POST" => Ok(Method::POST),
+        "PUT" => Ok(Method::PUT),
+        "PATCH" => Ok(Method::PATCH),
+        "DELETE" => Ok(Method::DELETE),
+        "HEAD" => Ok(Method::HEAD),
+        other => Err(format!("Unsupported HTTP method '{}'", other)),
+    }
+}
+
+fn parse_headers(plugin: &InstalledPlugin, payload: &Value) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    let Some(entries) = payload.get("headers") else {
+        return Ok(headers);
+    };
+    let entries = entries
+        .as_object()
+        .ok_or_else(|| "Field 'headers' must be an object of strings".to_string())?;
+
+    for (name, value) in entries {
+        let lower = name.to_ascii_lowercase();
+        if FORBIDDEN_HEADERS.contains(&lower.as_str()) {
+            return Err(format!("Header '{}' cannot be set by plugins", name));
+        }
+        if CREDENTIAL_HEADERS.contains(&lower.as_str())
+            && !has_permission(plugin, "network:credentials")
+        {
+            return Err(format!(
+                "Permission denied: network:credentials (required for header '{}')",
+                name
+            ));
+        }
+
+        let value = value
+            .as_str()
+            .ok_or_else(|| format!("Header '{}' must be a string", name))?;
+        headers.insert(
+            HeaderName::from_bytes(lower.as_bytes())
+                .map_err(|error| format!("Invalid header name '{}': {}", name, error))?,
+            HeaderValue::from_str(value)
+                .map_err(|error| format!("Invalid value for header '{}': {}", name, error))?,
+        );
+    }
+
+    Ok(headers)
+}
+
+/// `body` is sent as-is when it is a string and as JSON otherwise.
+fn parse_body(payload: &Value, headers: &mut HeaderMap) -> Option<Vec<u8>> {
+    match payload.get("body") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(text)) => Some(text.as_bytes().to_vec()),
+        Some(value) => {
+            headers
+                .entry(reqwest::header::CONTENT_TYPE)
+                .or_insert(HeaderValue::from_static("application/json"));
+            Some(value.to_string().into_bytes())
+        }
+    }
+}
+
+pub fn parse_request(plugin: &InstalledPlugin, payload: &Value) -> Result<PluginRequest, String> {
+    let url = payload
+        .get("url")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Payload must include string field 'url'".to_string())?;
+    enforce_network_allowlist(plugin, url)?;
+
+    let method = parse_method(payload)?;
+    let mut headers = parse_headers(plugin, payload)?;
+    let body = parse_body(payload, &mut headers);
+    if body.is_some() && matches!(method, Method::GET | Method::HEAD) {
+        return Err(format!("{} requests cannot have a body", method));
+    }
+
+    let timeout_ms = payload
+        .get("timeoutMs")
+        .and_then(Value::as_u64)
+        .unwrap_or(DEFAULT_TIMEOUT_MS)
+        .clamp(1, MAX_TIMEOUT_MS);
+    let max_response_bytes = payload
+        .get("maxResponseBytes")
+        .and_then(Value::as_u64)
+        .map(|limit| limit as usize)
+        .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES)
+        .clamp(1, MAX_RESPONSE_BYTES);
+
+    Ok(PluginRequest {
+        method,
+        url: url.to_string(),
+        headers,
+        body,
+        timeout: Duration::from_millis(timeout_ms),
+        max_response_bytes,
+    })
+}
+
+/// A client that follows redirects only to allowlisted hosts.
+fn client_for(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    timeout: Duration,
+) -> Result<Client, String> {
+    let redirect_plugin = plugin.clone();
+    proxy::client_builder(app)?
+        .timeout(timeout)
+        .redirect(redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("Too many redirects");
+            }
+            match enforce_network_allowlist(&redirect_plugin, attempt.url().as_str()) {
+                Ok(()) => attempt.follow(),
+                Err(error) => attempt.error(error),
+            }
+        }))
+        .build()
+        .map_err(|error| format!("Failed to create HTTP client: {}", error))
+}
+
+/// Sends the request, following redirects only to allowlisted hosts, and
+/// stops reading once the response exceeds the size limit.
+pub async fn send(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    request: PluginRequest,
+) -> Result<(u16, HeaderMap, Vec<u8>), String> {
+    let client = client_for(app, plugin, request.timeout)?;
+    let sent = request.body.as_ref().map_or(0, |body| body.len() as u64);
+
+    let mut builder = client
+        .request(request.method, &request.url)
+        .headers(request.headers);
+    if let Some(body) = request.body {
+        builder = builder.body(body);
+    }
+
+    let mut response = builder
+        .send()
+        .await
+        .map_err(|error| format!("Network request failed: {}", error))?;
+
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|error| format!("Failed to read response: {}", error))?
+    {
+        if bytes.len() + chunk.len() > request.max_response_bytes {
+            return Err(format!(
+                "Response exceeds {} byte limit",
+                request.max_response_bytes
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    app.state::<PluginMetrics>()
+        .record_network(&plugin.id, sent, bytes.len() as u64);
+    Ok((status, headers, bytes))
+}
+
+/// The original GET helpers: fail on non-2xx and return only the body.
+pub async fn get(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    payload: &Value,
+    kind: ResponseKind,
+) -> Result<Value, String> {
+    let request = parse_request(
+        plugin,
+        &json!({ "url": payload.get("url").cloned().unwrap_or(Value::Null) }),
+    )?;
+    let (status, _, bytes) = send(app, plugin, request).await?;
+    if !(200..300).contains(&status) {
+        return Err(format!("HTTP request failed with status {}", status));
+    }
+
+    match kind {
+        ResponseKind::Json => serde_json::from_slice::<Value>(&bytes)
+            .map_err(|error| format!("Failed to parse JSON response: {}", error)),
+        ResponseKind::Text => Ok(json!({ "text": String::from_utf8_lossy(&bytes) })),
+    }
+}
+
+/// `network:request`: any method, with headers and body. Non-2xx responses
+/// are returned, not raised, so plugins can read API error bodies.
+pub async fn request(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    payload: &Value,
+) -> Result<Value, String> {
+    let response_type = payload
+        .get("responseType")
+        .and_then(Value::as_str)
+        .unwrap_or("text");
+    let request = parse_request(plugin, payload)?;
+    let (status, headers, bytes) = send(app, plugin, request).await?;
+
+    let headers = headers
+        .iter()
+        .filter(|(name, _)| name.as_str() != "set-cookie")
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_string(), Value::String(value.to_string())))
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    let body = match response_type {
+        "json" if bytes.is_empty() => Value::Null,
+        "json" => serde_json::from_slice::<Value>(&bytes)
+            .map_err(|error| format!("Failed to parse JSON response: {}", error))?,
+        "text" => Value::String(String::from_utf8_lossy(&bytes).to_string()),
+        other => return Err(format!("Unsupported response type '{}'", other)),
+    };
+
+    Ok(json!({
+        "status": status,
+        "ok": (200..300).contains(&status),
+        "headers": headers,
+        "body": body,
+    }))
+}
+
+fn download_file_name(url: &str) -> String {
+    let name = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()
+                .and_then(|mut segments| segments.next_back().map(str::to_string))
+        })
+        .unwrap_or_default()
+        .chars()
+        .filter(|character| character.is_ascii_alphanumeric() || "._-".contains(*character))
+        .collect::<String>();
+
+    match name.trim_start_matches('.') {
+        "" => "download".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// `network:download`: streams a GET response into the plugin's data
+/// directory (default `downloads/<file name>`), emitting
+/// `plugin-download-progress` events. The file is written to a `.part` path
+/// and renamed once complete, so partial downloads never look finished.
+pub async fn download(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    payload: &Value,
+) -> Result<Value, String> {
+    let url = payload
+        .get("url")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Payload must include string field 'url'".to_string())?;
+    enforce_network_allowlist(plugin, url)?;
+    let headers = parse_headers(plugin, payload)?;
+
+    let root = files::data_dir(app, &plugin.id)?;
+    let relative = payload
+        .get("path")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}/{}", DOWNLOADS_DIR, download_file_name(url)));
+    let destination = files::sandboxed_path(&root, &relative)?;
+
+    let quota_left = files::MAX_DATA_DIR_BYTES.saturating_sub(files::directory_size(&root));
+    let max_bytes = payload
+        .get("maxBytes")
+        .and_then(Value::as_u64)
+        .unwrap_or(DEFAULT_MAX_DOWNLOAD_BYTES)
+        .min(MAX_DOWNLOAD_BYTES)
+        .min(quota_left)
+        .min(quota::remaining(app, &plugin.id, 0)?);
+    let download_id = payload
+        .get("downloadId")
+        .and_then(Value::as_str)
+        .unwrap_or(relative.as_str())
+        .to_string();
+
+    let mut response = client_for(app, plugin, DOWNLOAD_TIMEOUT)?
+        .get(url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(|error| format!("Download failed: {}", error))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed with status {}", response.status()));
+    }
+
+    let total_bytes = response.content_length();
+    if total_bytes.is_some_and(|total| total > max_bytes) {
+        return Err(format!("Download exceeds {} byte limit", max_bytes));
+    }
+
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to create download directory: {}", error))?;
+    }
+    let partial = destination.with_file_name(format!(
+        "{}.part",
+        destination
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    ));
+    let mut file = fs::File::create(&partial)
+        .map_err(|error| format!("Failed to create download file: {}", error))?;
+
+    let progress = |received_bytes: u64| {
+        let _ = app.emit(
+            DOWNLOAD_PROGRESS_EVENT,
+            json!({
+                "pluginId": plugin.id,
+                "downloadId": download_id,
+                "receivedBytes": received_bytes,
+                "totalBytes": total_bytes,
+            }),
+        );
+    };
+
+    let mut received = 0u64;
+    let mut last_reported = 0u64;
+    let result = async {
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| format!("Download failed: {}", error))?
+        {
+            received += chunk.len() as u64;
+            if received > max_bytes {
+                return Err(format!("Download exceeds {} byte limit", max_bytes));
+            }
+            file.write_all(&chunk)
+                .map_err(|error| format!("Failed to write download: {}", error))?;
+
+            if received - last_reported >= PROGRESS_INTERVAL_BYTES {
+                last_reported = received;
+                progress(received);
+            }
+        }
+        file.flush()
+            .map_err(|error| format!("Failed to write download: {}", error))
+    }
+    .await;
+
+    app.state::<PluginMetrics>()
+        .record_network(&plugin.id, 0, received);
+    if let Err(error) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(error);
+    }
+    fs::rename(&partial, &destination)
+        .map_err(|error| format!("Failed to finish download: {}", error))?;
+    progress(received);
+
+    Ok(json!({ "path": relative, "bytes": received }))
+}
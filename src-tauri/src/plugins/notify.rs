@@ -0,0 +1,98 @@
+use super::{has_permission, InstalledPlugin};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const MAX_PER_WINDOW: usize = 5;
+const MAX_TITLE_CHARS: usize = 120;
+const MAX_BODY_CHARS: usize = 500;
+
+/// Per-plugin sliding-window limit on native notifications.
+#[derive(Default)]
+pub struct NotificationLimiter {
+    recent: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl NotificationLimiter {
+    fn try_acquire(&self, plugin_id: &str, now: Instant) -> bool {
+        let Ok(mut recent) = self.recent.lock() else {
+            return false;
+        };
+        let sent = recent.entry(plugin_id.to_string()).or_default();
+        while sent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            sent.pop_front();
+        }
+
+        if sent.len() >= MAX_PER_WINDOW {
+            return false;
+        }
+        sent.push_back(now);
+        true
+    }
+}
+
+fn truncate(value: &str, limit: usize) -> String {
+    value.chars().take(limit).collect()
+}
+
+pub fn handle(app: &AppHandle, plugin: &InstalledPlugin, payload: &Value) -> Result<Value, String> {
+    if !has_permission(plugin, "ui:notify") {
+        return Err("Permission denied: ui:notify".to_string());
+    }
+
+    let title = payload
+        .get("title")
+        .and_then(Value::as_str)
+        .filter(|title| !title.trim().is_empty())
+        .ok_or_else(|| "Payload must include non-empty string field 'title'".to_string())?;
+    let body = payload.get("body").and_then(Value::as_str).unwrap_or("");
+
+    if !app
+        .state::<NotificationLimiter>()
+        .try_acquire(&plugin.id, Instant::now())
+    {
+        return Err(format!(
+            "Notification rate limit reached ({} per minute)",
+            MAX_PER_WINDOW
+        ));
+    }
+
+    // Attribute the notification so plugins can't impersonate the app.
+    app.notification()
+        .builder()
+        .title(format!(
+            "{}: {}",
+            plugin.name,
+            truncate(title, MAX_TITLE_CHARS)
+        ))
+        .body(truncate(body, MAX_BODY_CHARS))
+        .show()
+        .map_err(|error| format!("Failed to show notification: {}", error))?;
+
+    Ok(json!({ "ok": true }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_each_plugin_independently() {
+        let limiter = NotificationLimiter::default();
+        let start = Instant::now();
+
+        for _ in 0..MAX_PER_WINDOW {
+            assert!(limiter.try_acquire("a", start));
+        }
+        assert!(!limiter.try_acquire("a", start));
+        assert!(limiter.try_acquire("b", start));
+        assert!(limiter.try_acquire("a", start + RATE_WINDOW));
+    }
+}
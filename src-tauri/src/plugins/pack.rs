@@ -0,0 +1,314 @@
+use super::{
+    compute_sha256_hex, dictionaries, localization, read_manifest_from_zip, signing, themes,
+    validate_manifest, PluginManifest, PluginSignature, MANIFEST_FILE_NAME,
+};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+const PLUGIN_ARCHIVE_EXTENSION: &str = ".grainery-plugin.zip";
+const IGNORED_ENTRIES: [&str; 3] = [".DS_Store", ".git", "node_modules"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginPackResult {
+    pub plugin_id: String,
+    pub version: String,
+    pub archive_path: String,
+    /// SHA-256 of the archive bytes, as published in registry entries.
+    pub sha256: String,
+    /// Content digest covered by the embedded manifest signature.
+    pub digest: String,
+    pub signature: Option<PluginSignature>,
+    /// Signature over `sha256` for the registry entry's `signature` field.
+    pub registry_signature: Option<String>,
+    pub public_key: Option<String>,
+}
+
+/// Local ed25519 key used to sign a packed archive.
+pub struct PackSigningKey {
+    pub key_id: String,
+    pub key_path: PathBuf,
+}
+
+fn collect_files(root: &Path, current: &Path, files: &mut Vec<String>) -> Result<(), String> {
+    let entries = fs::read_dir(current)
+        .map_err(|error| format!("Failed to read plugin directory: {}", error))?;
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if IGNORED_ENTRIES.contains(&name.as_str()) || name.ends_with(PLUGIN_ARCHIVE_EXTENSION) {
+            continue;
+        }
+
+        let path = entry.path();
+        let file_type = entry
+            .file_type()
+            .map_err(|error| format!("Failed to read plugin directory: {}", error))?;
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file() {
+            let relative = path
+                .strip_prefix(root)
+                .map_err(|error| format!("Failed to resolve plugin file path: {}", error))?;
+            let parts = relative
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            files.push(parts.join("/"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the archive with sorted entries, a fixed timestamp and fixed
+/// permissions so the same directory always packs to the same bytes.
+fn write_archive(root: &Path, files: &[String], manifest: &[u8]) -> Result<Vec<u8>, String> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for name in files {
+        let contents = if name == MANIFEST_FILE_NAME {
+            manifest.to_vec()
+        } else {
+            fs::read(root.join(name))
+                .map_err(|error| format!("Failed to read plugin file {}: {}", name, error))?
+        };
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|error| format!("Failed to write plugin archive: {}", error))?;
+        writer
+            .write_all(&contents)
+            .map_err(|error| format!("Failed to write plugin archive: {}", error))?;
+    }
+
+    writer
+        .finish()
+        .map(Cursor::into_inner)
+        .map_err(|error| format!("Failed to write plugin archive: {}", error))
+}
+
+/// Runs the same checks as install against the packed archive.
+fn validate_archive(zip_bytes: &[u8]) -> Result<PluginManifest, String> {
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes.to_vec()))
+        .map_err(|error| format!("Failed to parse plugin archive: {}", error))?;
+    let manifest = read_manifest_from_zip(&mut archive)?;
+    validate_manifest(&manifest)?;
+    if manifest.schema_version >= 2 {
+        let locales = localization::read_archive_locales(&mut archive)?;
+        localization::validate(&manifest, &locales)?;
+    }
+    themes::validate_archive(&mut archive, &manifest)?;
+    dictionaries::validate_archive(&mut archive, &manifest)?;
+
+    let entry = manifest.entry.trim_start_matches("./");
+    if archive.by_name(entry).is_err() {
+        return Err(format!("Plugin entry file '{}' is missing", manifest.entry));
+    }
+
+    Ok(manifest)
+}
+
+/// Secret keys are stored as base64 of the 32-byte ed25519 seed, matching
+/// the encoding of trusted public keys.
+fn read_signing_key(path: &Path) -> Result<SigningKey, String> {
+    let encoded = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read signing key: {}", error))?;
+    let key_bytes = BASE64_STANDARD
+        .decode(encoded.trim())
+        .map_err(|error| format!("Invalid signing key encoding: {}", error))?;
+    let key_array: [u8; 32] = key_bytes
+        .try_into()
+        .map_err(|_| "Signing key must decode to 32 bytes".to_string())?;
+    Ok(SigningKey::from_bytes(&key_array))
+}
+
+fn sign(key: &SigningKey, message: &str) -> String {
+    BASE64_STANDARD.encode(key.sign(message.as_bytes()).to_bytes())
+}
+
+pub(super) fn pack(
+    input_dir: &Path,
+    output_path: Option<&Path>,
+    signing_key: Option<&PackSigningKey>,
+) -> Result<PluginPackResult, String> {
+    if !input_dir.is_dir() {
+        return Err(format!(
+            "Plugin directory not found: {}",
+            input_dir.to_string_lossy()
+        ));
+    }
+
+    let manifest_bytes = fs::read(input_dir.join(MANIFEST_FILE_NAME))
+        .map_err(|_| format!("Plugin directory missing {}", MANIFEST_FILE_NAME))?;
+    let mut files = Vec::new();
+    collect_files(input_dir, input_dir, &mut files)?;
+    files.sort();
+
+    let mut zip_bytes = write_archive(input_dir, &files, &manifest_bytes)?;
+    let manifest = validate_archive(&zip_bytes)?;
+    let digest = signing::archive_digest(&zip_bytes)?;
+
+    let mut signature = None;
+    let mut registry_signature = None;
+    let mut public_key = None;
+    if let Some(signing_key) = signing_key {
+        let key = read_signing_key(&signing_key.key_path)?;
+        let embedded = PluginSignature {
+            key_id: signing_key.key_id.clone(),
+            sha256: digest.clone(),
+            sig: sign(&key, &digest),
+        };
+
+        let mut manifest_json = serde_json::from_slice::<Value>(&manifest_bytes)
+            .map_err(|error| format!("Failed to parse plugin manifest JSON: {}", error))?;
+        let Some(object) = manifest_json.as_object_mut() else {
+            return Err("Plugin manifest must be a JSON object".to_string());
+        };
+        object.insert(
+            "signature".to_string(),
+            serde_json::to_value(&embedded)
+                .map_err(|error| format!("Failed to serialize signature: {}", error))?,
+        );
+        let signed_manifest = serde_json::to_vec_pretty(&manifest_json)
+            .map_err(|error| format!("Failed to serialize plugin manifest: {}", error))?;
+
+        // The digest skips `signature`, so re-packing keeps it valid.
+        zip_bytes = write_archive(input_dir, &files, &signed_manifest)?;
+        registry_signature = Some(sign(&key, &compute_sha256_hex(&zip_bytes)));
+        public_key = Some(BASE64_STANDARD.encode(key.verifying_key().to_bytes()));
+        signature = Some(embedded);
+    }
+
+    let archive_path = match output_path {
+        Some(path) => path.to_path_buf(),
+        None => input_dir.join(format!(
+            "{}-{}{}",
+            manifest.id, manifest.version, PLUGIN_ARCHIVE_EXTENSION
+        )),
+    };
+    fs::write(&archive_path, &zip_bytes)
+        .map_err(|error| format!("Failed to write plugin archive: {}", error))?;
+
+    Ok(PluginPackResult {
+        plugin_id: manifest.id,
+        version: manifest.version,
+        archive_path: archive_path.to_string_lossy().to_string(),
+        sha256: compute_sha256_hex(&zip_bytes),
+        digest,
+        signature,
+        registry_signature,
+        public_key,
+    })
+}
+
+fn cli_usage() -> String {
+    "Usage: grainery --pack-plugin <plugin-dir> [--out <archive.grainery-plugin.zip>] \
+     [--sign-key <key-file> --key-id <key id>]"
+        .to_string()
+}
+
+struct PackCliArgs {
+    input_dir: PathBuf,
+    output_path: Option<PathBuf>,
+    signing_key: Option<PackSigningKey>,
+}
+
+fn parse_cli_args(args: &[String]) -> Result<PackCliArgs, String> {
+    let mut input_dir = None;
+    let mut output_path = None;
+    let mut key_path = None;
+    let mut key_id = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().cloned().ok_or_else(cli_usage);
+        match arg.as_str() {
+            "--pack-plugin" => input_dir = Some(PathBuf::from(value()?)),
+            "--out" => output_path = Some(PathBuf::from(value()?)),
+            "--sign-key" => key_path = Some(PathBuf::from(value()?)),
+            "--key-id" => key_id = Some(value()?),
+            _ => return Err(format!("Unknown argument '{}'\n{}", arg, cli_usage())),
+        }
+    }
+
+    let signing_key = match (key_path, key_id) {
+        (Some(key_path), Some(key_id)) => Some(PackSigningKey { key_id, key_path }),
+        (None, None) => None,
+        _ => return Err("--sign-key and --key-id must be used together".to_string()),
+    };
+
+    Ok(PackCliArgs {
+        input_dir: input_dir.ok_or_else(cli_usage)?,
+        output_path,
+        signing_key,
+    })
+}
+
+/// Handles `--pack-plugin` before the app starts. Returns the process exit
+/// code when the flag is present, `None` otherwise.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    if !args.iter().any(|arg| arg == "--pack-plugin") {
+        return None;
+    }
+
+    let output = parse_cli_args(args)
+        .and_then(|cli| {
+            pack(
+                &cli.input_dir,
+                cli.output_path.as_deref(),
+                cli.signing_key.as_ref(),
+            )
+        })
+        .and_then(|result| {
+            serde_json::to_string_pretty(&result)
+                .map_err(|error| format!("Failed to serialize pack result: {}", error))
+        });
+
+    match output {
+        Ok(output) => {
+            println!("{}", output);
+            Some(0)
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            Some(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cli_flags() {
+        let args = [
+            "--pack-plugin",
+            "demo",
+            "--sign-key",
+            "key.txt",
+            "--key-id",
+            "dev",
+        ]
+        .map(str::to_string);
+        let cli = parse_cli_args(&args).unwrap();
+        assert_eq!(cli.input_dir, PathBuf::from("demo"));
+        assert!(cli.output_path.is_none());
+        assert_eq!(cli.signing_key.unwrap().key_id, "dev");
+
+        let args = ["--pack-plugin", "demo", "--key-id", "dev"].map(str::to_string);
+        assert!(parse_cli_args(&args).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+/// Label of the hidden window whose web content process runs the plugin
+/// workers. The frontend checks the same label to start the worker relay
+/// instead of the editor.
+pub const PROCESS_WINDOW: &str = "plugin-process";
+
+/// Creates the hidden plugin window unless it is already running.
+pub fn start(app: &AppHandle) -> Result<(), String> {
+    if app.get_webview_window(PROCESS_WINDOW).is_some() {
+        return Ok(());
+    }
+
+    let builder = WebviewWindowBuilder::new(app, PROCESS_WINDOW, WebviewUrl::default())
+        .title("Grainery Plugins")
+        .visible(false);
+    #[cfg(desktop)]
+    let builder = builder.focused(false).skip_taskbar(true);
+    builder
+        .build()
+        .map_err(|error| format!("Failed to start plugin process: {}", error))?;
+
+    Ok(())
+}
+
+/// Destroys the plugin window, ending its web content process together with
+/// every plugin running in it, and starts a fresh one once it is gone.
+pub fn restart(app: &AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(PROCESS_WINDOW) else {
+        return start(app);
+    };
+
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            if let Err(error) = start(&handle) {
+                tracing::error!("{}", error);
+            }
+        }
+    });
+    window
+        .destroy()
+        .map_err(|error| format!("Failed to stop plugin process: {}", error))
+}
@@ -0,0 +1,173 @@
+use super::{
+    load_store, plugin_enable_disable, plugin_install_from_registry, plugin_update_permissions,
+    PluginPermissionGrant,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::AppHandle;
+
+const PROFILE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginProfileEntry {
+    pub id: String,
+    pub version: String,
+    pub enabled: bool,
+    pub install_source: String,
+    #[serde(default)]
+    pub registry_url: Option<String>,
+    #[serde(default)]
+    pub granted_permissions: Vec<PluginPermissionGrant>,
+}
+
+/// A portable snapshot of the installed plugin set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginProfile {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub plugins: Vec<PluginProfileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginProfileSkip {
+    pub id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginProfileImportReport {
+    pub installed: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Installed at a different version because the pinned one is gone.
+    pub version_changed: Vec<String>,
+    pub skipped: Vec<PluginProfileSkip>,
+}
+
+pub fn export(app: &AppHandle, output_path: &str) -> Result<PluginProfile, String> {
+    let store = load_store(app)?;
+    let plugins = store
+        .installed_plugins
+        .iter()
+        .map(|plugin| PluginProfileEntry {
+            id: plugin.id.clone(),
+            version: plugin.version.clone(),
+            enabled: plugin.enabled,
+            install_source: plugin.install_source.clone(),
+            registry_url: store
+                .lock_records
+                .iter()
+                .find(|record| record.plugin_id == plugin.id)
+                .and_then(|record| record.registry_url.clone()),
+            granted_permissions: plugin.granted_permissions.clone(),
+        })
+        .collect();
+
+    let profile = PluginProfile {
+        format_version: PROFILE_FORMAT_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        plugins,
+    };
+    let payload = serde_json::to_string_pretty(&profile)
+        .map_err(|error| format!("Failed to serialize plugin profile: {}", error))?;
+    fs::write(output_path, payload)
+        .map_err(|error| format!("Failed to write plugin profile: {}", error))?;
+
+    Ok(profile)
+}
+
+/// Installs `entry` pinned to its version when a registry still has it,
+/// else the latest. The original registry is tried first, then the enabled
+/// registries. Returns whether the installed version differs.
+async fn install_entry(app: &AppHandle, entry: &PluginProfileEntry) -> Result<bool, String> {
+    let mut attempts = Vec::new();
+    for version in [Some(entry.version.clone()), None] {
+        if entry.registry_url.is_some() {
+            attempts.push((entry.registry_url.clone(), version.clone()));
+        }
+        attempts.push((None, version));
+    }
+
+    let mut last_error = String::new();
+    for (registry_url, version) in attempts {
+        match plugin_install_from_registry(app.clone(), registry_url, entry.id.clone(), version)
+            .await
+        {
+            Ok(installed) => return Ok(installed.version != entry.version),
+            Err(error) => last_error = error.message,
+        }
+    }
+
+    Err(last_error)
+}
+
+pub async fn import(
+    app: &AppHandle,
+    input_path: &str,
+) -> Result<PluginProfileImportReport, String> {
+    let content = fs::read_to_string(input_path)
+        .map_err(|error| format!("Failed to read plugin profile: {}", error))?;
+    let profile = serde_json::from_str::<PluginProfile>(&content)
+        .map_err(|error| format!("Failed to parse plugin profile: {}", error))?;
+    if profile.format_version != PROFILE_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported plugin profile format {}",
+            profile.format_version
+        ));
+    }
+
+    let mut report = PluginProfileImportReport::default();
+    for entry in &profile.plugins {
+        let current = load_store(app)?
+            .installed_plugins
+            .into_iter()
+            .find(|plugin| plugin.id == entry.id);
+
+        match current {
+            Some(plugin) if plugin.version == entry.version => {
+                report.unchanged.push(entry.id.clone())
+            }
+            _ if entry.install_source != "registry" => {
+                report.skipped.push(PluginProfileSkip {
+                    id: entry.id.clone(),
+                    reason: "Sideloaded plugins must be installed from their archive".to_string(),
+                });
+                continue;
+            }
+            _ => match install_entry(app, entry).await {
+                Ok(true) => report.version_changed.push(entry.id.clone()),
+                Ok(false) => report.installed.push(entry.id.clone()),
+                Err(error) => {
+                    report.skipped.push(PluginProfileSkip {
+                        id: entry.id.clone(),
+                        reason: error,
+                    });
+                    continue;
+                }
+            },
+        }
+
+        // Grants for permissions the installed version no longer declares
+        // are dropped rather than failing the import.
+        let declared = load_store(app)?
+            .installed_plugins
+            .into_iter()
+            .find(|plugin| plugin.id == entry.id)
+            .map(|plugin| plugin.manifest.optional_permissions)
+            .unwrap_or_default();
+        let grants = entry
+            .granted_permissions
+            .iter()
+            .filter(|grant| declared.contains(&grant.permission))
+            .cloned()
+            .collect();
+        plugin_update_permissions(app.clone(), entry.id.clone(), grants)?;
+        plugin_enable_disable(app.clone(), entry.id.clone(), entry.enabled)?;
+    }
+
+    Ok(report)
+}
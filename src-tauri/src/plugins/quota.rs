@@ -0,0 +1,90 @@
+use super::{files, load_store, plugin_install_base_dir, sanitize_plugin_id, storage, PluginStore};
+use serde::Serialize;
+use std::fs;
+use tauri::AppHandle;
+
+pub(super) const DEFAULT_QUOTA_BYTES: u64 = 100 * 1024 * 1024;
+pub(super) const MIN_QUOTA_BYTES: u64 = 1024 * 1024;
+
+/// Disk space held by one plugin: its installed files plus the data
+/// directory and key-value storage that survive reinstalls.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDiskUsage {
+    pub plugin_id: String,
+    pub install_bytes: u64,
+    pub data_bytes: u64,
+    pub storage_bytes: u64,
+    pub total_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+pub(super) fn quota_bytes(store: &PluginStore) -> u64 {
+    store.disk_quota_bytes.unwrap_or(DEFAULT_QUOTA_BYTES)
+}
+
+pub(super) fn usage(
+    app: &AppHandle,
+    store: &PluginStore,
+    plugin_id: &str,
+) -> Result<PluginDiskUsage, String> {
+    let install_bytes =
+        files::directory_size(&plugin_install_base_dir(app)?.join(sanitize_plugin_id(plugin_id)));
+    let data_bytes = files::directory_size(&files::data_dir(app, plugin_id)?);
+    let storage_bytes = fs::metadata(storage::storage_path(app, plugin_id)?)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+
+    Ok(PluginDiskUsage {
+        plugin_id: plugin_id.to_string(),
+        install_bytes,
+        data_bytes,
+        storage_bytes,
+        total_bytes: install_bytes + data_bytes + storage_bytes,
+        quota_bytes: quota_bytes(store),
+    })
+}
+
+/// Bytes the plugin may still use once `replaced` bytes of its current
+/// footprint have been freed.
+pub(super) fn remaining(app: &AppHandle, plugin_id: &str, replaced: u64) -> Result<u64, String> {
+    let store = load_store(app)?;
+    let usage = usage(app, &store, plugin_id)?;
+    Ok(usage
+        .quota_bytes
+        .saturating_sub(usage.total_bytes.saturating_sub(replaced)))
+}
+
+/// Rejects a write of `added` bytes that replaces `replaced` existing bytes
+/// when it would push the plugin over its disk quota.
+pub(super) fn ensure_room(
+    app: &AppHandle,
+    plugin_id: &str,
+    replaced: u64,
+    added: u64,
+) -> Result<(), String> {
+    if added > remaining(app, plugin_id, replaced)? {
+        return Err(format!(
+            "Plugin disk quota exceeded ({} bytes)",
+            quota_bytes(&load_store(app)?)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Removes the plugin's data directory and key-value storage. Installed
+/// files are left alone; uninstall removes those.
+pub(super) fn clear_data(app: &AppHandle, plugin_id: &str) -> Result<(), String> {
+    let data_dir = files::data_dir(app, plugin_id)?;
+    fs::remove_dir_all(&data_dir)
+        .map_err(|error| format!("Failed to remove plugin data directory: {}", error))?;
+
+    let storage_path = storage::storage_path(app, plugin_id)?;
+    if storage_path.exists() {
+        fs::remove_file(storage_path)
+            .map_err(|error| format!("Failed to remove plugin storage: {}", error))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+const REVOCATIONS_FILE_NAME: &str = "revocations.json";
+
+/// One revoked plugin. An empty `versions` list revokes every version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRevocation {
+    pub plugin_id: String,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    pub reason: String,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+    /// Registry whose list the entry came from. Missing on entries saved
+    /// before lists were tracked per registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry_url: Option<String>,
+}
+
+/// Signed list published next to a registry index as `revocations.json`.
+/// The signature covers the SHA-256 of the canonical (sorted-key) JSON of
+/// `revocations`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationList {
+    pub revocations: Vec<Value>,
+    pub signature_key_id: String,
+    pub signature: String,
+}
+
+pub fn revocations_url(registry_url: &str) -> Result<String, String> {
+    let url = reqwest::Url::parse(registry_url)
+        .map_err(|error| format!("Invalid registry URL '{}': {}", registry_url, error))?;
+    url.join(REVOCATIONS_FILE_NAME)
+        .map(|url| url.to_string())
+        .map_err(|error| format!("Invalid revocation list URL: {}", error))
+}
+
+pub fn list_digest(list: &RevocationList) -> String {
+    let canonical = Value::Array(list.revocations.clone()).to_string();
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+pub fn parse_entries(list: &RevocationList) -> Result<Vec<PluginRevocation>, String> {
+    list.revocations
+        .iter()
+        .map(|value| {
+            serde_json::from_value::<PluginRevocation>(value.clone())
+                .map_err(|error| format!("Invalid revocation entry: {}", error))
+        })
+        .collect()
+}
+
+/// Replaces the stored entries of every registry in `fetched` with its new
+/// list and keeps the rest, so a registry that couldn't be reached still
+/// revokes what it revoked last time. Entries from before per-registry
+/// tracking are kept until every registry in `registry_urls` was fetched.
+pub fn merge(
+    stored: &[PluginRevocation],
+    mut fetched: HashMap<String, Vec<PluginRevocation>>,
+    registry_urls: &[String],
+) -> Vec<PluginRevocation> {
+    let all_fetched = registry_urls.iter().all(|url| fetched.contains_key(url));
+    let mut merged = stored
+        .iter()
+        .filter(|entry| match &entry.registry_url {
+            Some(url) => !fetched.contains_key(url),
+            None => !all_fetched,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    for url in registry_urls {
+        let entries = fetched.remove(url).unwrap_or_default();
+        merged.extend(entries.into_iter().map(|entry| PluginRevocation {
+            registry_url: Some(url.clone()),
+            ..entry
+        }));
+    }
+    merged
+}
+
+pub fn find<'a>(
+    revocations: &'a [PluginRevocation],
+    plugin_id: &str,
+    version: &str,
+) -> Option<&'a PluginRevocation> {
+    revocations.iter().find(|revocation| {
+        revocation.plugin_id == plugin_id
+            && (revocation.versions.is_empty()
+                || revocation.versions.iter().any(|item| item == version))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_specific_and_all_versions() {
+        let revocations = vec![
+            PluginRevocation {
+                plugin_id: "bad".to_string(),
+                versions: vec!["1.0.1".to_string()],
+                reason: "Leaks documents".to_string(),
+                revoked_at: None,
+                registry_url: None,
+            },
+            PluginRevocation {
+                plugin_id: "worse".to_string(),
+                versions: Vec::new(),
+                reason: "Malware".to_string(),
+                revoked_at: None,
+                registry_url: None,
+            },
+        ];
+
+        assert!(find(&revocations, "bad", "1.0.1").is_some());
+        assert!(find(&revocations, "bad", "1.0.2").is_none());
+        assert!(find(&revocations, "worse", "9.9.9").is_some());
+        assert_eq!(
+            revocations_url("https://plugins.example.com/v1/index.json").unwrap(),
+            "https://plugins.example.com/v1/revocations.json"
+        );
+    }
+
+    fn revoked(plugin_id: &str, registry_url: Option<&str>) -> PluginRevocation {
+        PluginRevocation {
+            plugin_id: plugin_id.to_string(),
+            versions: Vec::new(),
+            reason: "Malware".to_string(),
+            revoked_at: None,
+            registry_url: registry_url.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn keeps_the_last_list_of_an_unreachable_registry() {
+        let first = "https://one.example.com/".to_string();
+        let second = "https://two.example.com/".to_string();
+        let registry_urls = vec![first.clone(), second.clone()];
+        let stored = vec![
+            revoked("old-from-first", Some(&first)),
+            revoked("from-second", Some(&second)),
+            revoked("legacy", None),
+        ];
+
+        // The second registry is unreachable, so only the first list is fetched
+        let fetched = HashMap::from([(first.clone(), vec![revoked("new-from-first", None)])]);
+        let merged = merge(&stored, fetched, &registry_urls);
+        let ids = merged
+            .iter()
+            .map(|entry| entry.plugin_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["from-second", "legacy", "new-from-first"]);
+        assert_eq!(merged[2].registry_url.as_deref(), Some(first.as_str()));
+        assert!(find(&merged, "from-second", "1.0.0").is_some());
+
+        // Once both are reached, the untracked entries are dropped
+        let fetched = HashMap::from([(first, Vec::new()), (second, Vec::new())]);
+        assert!(merge(&merged, fetched, &registry_urls).is_empty());
+    }
+}
@@ -0,0 +1,458 @@
+use super::{
+    has_permission, load_store, now_iso, plugin_root, validate_local_contribution_id,
+    InstalledPlugin,
+};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDateTime, TimeZone, Timelike, Utc,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const SCHEDULE_FILE: &str = "scheduled-tasks.json";
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+const MIN_INTERVAL_SECONDS: u64 = 60;
+const MAX_TASKS_PER_PLUGIN: usize = 20;
+/// Cron searches give up after a year without a matching minute.
+const MAX_CRON_LOOKAHEAD_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TaskSchedule {
+    Interval {
+        seconds: u64,
+    },
+    /// Five-field cron expression (minute hour day month weekday) evaluated
+    /// in local time.
+    Cron {
+        expression: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    pub plugin_id: String,
+    pub task_id: String,
+    pub schedule: TaskSchedule,
+    pub created_at: String,
+    pub next_run_at: String,
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+}
+
+/// Serializes access to the schedule file between host calls, commands and
+/// the background tick.
+#[derive(Default)]
+pub struct TaskScheduler {
+    lock: Mutex<()>,
+}
+
+/// Parsed cron fields as bitmasks; bit `n` set means value `n` matches.
+#[derive(Debug, PartialEq)]
+struct CronSpec {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid cron step '{}'", part))?,
+            ),
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid cron value '{}'", part))?,
+                end.parse::<u32>()
+                    .map_err(|_| format!("Invalid cron value '{}'", part))?,
+            )
+        } else {
+            let value = range
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid cron value '{}'", part))?;
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!(
+                "Cron value '{}' must be between {} and {}",
+                part, min, max
+            ));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1u64 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_cron(expression: &str) -> Result<CronSpec, String> {
+    let fields = expression.split_whitespace().collect::<Vec<_>>();
+    let [minute, hour, day, month, weekday] = fields[..] else {
+        return Err(format!(
+            "Cron expression '{}' must have five fields",
+            expression
+        ));
+    };
+
+    let mut weekdays = parse_cron_field(weekday, 0, 7)?;
+    // Both 0 and 7 mean Sunday.
+    if weekdays & (1u64 << 7) != 0 {
+        weekdays |= 1;
+    }
+
+    Ok(CronSpec {
+        minutes: parse_cron_field(minute, 0, 59)?,
+        hours: parse_cron_field(hour, 0, 23)?,
+        days: parse_cron_field(day, 1, 31)?,
+        months: parse_cron_field(month, 1, 12)?,
+        weekdays,
+        any_day: day == "*",
+        any_weekday: weekday == "*",
+    })
+}
+
+impl CronSpec {
+    fn matches_day(&self, time: &NaiveDateTime) -> bool {
+        let day = self.days & (1u64 << time.day()) != 0;
+        let weekday = self.weekdays & (1u64 << time.weekday().num_days_from_sunday()) != 0;
+
+        // Standard cron: when both day fields are restricted, either matches.
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+
+    /// First matching local minute strictly after `after`.
+    fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let limit = after + ChronoDuration::days(MAX_CRON_LOOKAHEAD_DAYS);
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+
+        while time <= limit {
+            if self.months & (1u64 << time.month()) == 0 || !self.matches_day(&time) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1u64 << time.hour()) == 0 {
+                time = time.with_minute(0)? + ChronoDuration::hours(1);
+                continue;
+            }
+            if self.minutes & (1u64 << time.minute()) == 0 {
+                time += ChronoDuration::minutes(1);
+                continue;
+            }
+            return Some(time);
+        }
+
+        None
+    }
+}
+
+fn validate_schedule(schedule: &TaskSchedule) -> Result<(), String> {
+    match schedule {
+        TaskSchedule::Interval { seconds } if *seconds < MIN_INTERVAL_SECONDS => Err(format!(
+            "Task intervals must be at least {} seconds",
+            MIN_INTERVAL_SECONDS
+        )),
+        TaskSchedule::Interval { .. } => Ok(()),
+        TaskSchedule::Cron { expression } => parse_cron(expression).map(|_| ()),
+    }
+}
+
+fn next_run(schedule: &TaskSchedule, after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    match schedule {
+        TaskSchedule::Interval { seconds } => Ok(after + ChronoDuration::seconds(*seconds as i64)),
+        TaskSchedule::Cron { expression } => {
+            let spec = parse_cron(expression)?;
+            let mut local = after.with_timezone(&Local).naive_local();
+            // Minutes skipped by a DST transition have no local instant.
+            loop {
+                let next = spec
+                    .next_after(local)
+                    .ok_or_else(|| format!("Cron expression '{}' never matches", expression))?;
+                if let Some(time) = Local.from_local_datetime(&next).earliest() {
+                    return Ok(time.with_timezone(&Utc));
+                }
+                local = next;
+            }
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn schedule_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(plugin_root(app)?.join(SCHEDULE_FILE))
+}
+
+fn load(app: &AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    let path = schedule_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read scheduled tasks: {}", error))?;
+    serde_json::from_str(&content)
+        .map_err(|error| format!("Failed to parse scheduled tasks: {}", error))
+}
+
+fn save(app: &AppHandle, tasks: &[ScheduledTask]) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(tasks)
+        .map_err(|error| format!("Failed to serialize scheduled tasks: {}", error))?;
+    fs::write(schedule_path(app)?, payload)
+        .map_err(|error| format!("Failed to save scheduled tasks: {}", error))
+}
+
+/// Runs `update` on the persisted task list while holding the scheduler
+/// lock, saving the result.
+fn with_tasks<T>(
+    app: &AppHandle,
+    update: impl FnOnce(&mut Vec<ScheduledTask>) -> Result<T, String>,
+) -> Result<T, String> {
+    let scheduler = app.state::<TaskScheduler>();
+    let _guard = scheduler
+        .lock
+        .lock()
+        .map_err(|_| "Task scheduler is unavailable".to_string())?;
+
+    let mut tasks = load(app)?;
+    let output = update(&mut tasks)?;
+    save(app, &tasks)?;
+    Ok(output)
+}
+
+pub(super) fn list(app: &AppHandle, plugin_id: Option<&str>) -> Result<Vec<ScheduledTask>, String> {
+    with_tasks(app, |tasks| {
+        Ok(tasks
+            .iter()
+            .filter(|task| plugin_id.is_none_or(|id| task.plugin_id == id))
+            .cloned()
+            .collect())
+    })
+}
+
+pub(super) fn cancel(app: &AppHandle, plugin_id: &str, task_id: &str) -> Result<bool, String> {
+    with_tasks(app, |tasks| {
+        let before = tasks.len();
+        tasks.retain(|task| !(task.plugin_id == plugin_id && task.task_id == task_id));
+        Ok(tasks.len() != before)
+    })
+}
+
+pub(super) fn remove_plugin(app: &AppHandle, plugin_id: &str) -> Result<(), String> {
+    with_tasks(app, |tasks| {
+        tasks.retain(|task| task.plugin_id != plugin_id);
+        Ok(())
+    })
+}
+
+fn register(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    payload: &Value,
+) -> Result<ScheduledTask, String> {
+    let task_id = payload
+        .get("id")
+        .and_then(Value::as_str)
+        .filter(|id| validate_local_contribution_id(id))
+        .ok_or_else(|| "Payload must include a valid task 'id'".to_string())?;
+
+    let schedule = match (
+        payload.get("intervalSeconds").and_then(Value::as_u64),
+        payload.get("cron").and_then(Value::as_str),
+    ) {
+        (Some(seconds), None) => TaskSchedule::Interval { seconds },
+        (None, Some(expression)) => TaskSchedule::Cron {
+            expression: expression.trim().to_string(),
+        },
+        _ => {
+            return Err(
+                "Payload must include exactly one of 'intervalSeconds' or 'cron'".to_string(),
+            )
+        }
+    };
+    validate_schedule(&schedule)?;
+    let next_run_at = next_run(&schedule, Utc::now())?.to_rfc3339();
+
+    with_tasks(app, |tasks| {
+        if let Some(existing) = tasks
+            .iter_mut()
+            .find(|task| task.plugin_id == plugin.id && task.task_id == task_id)
+        {
+            existing.schedule = schedule;
+            existing.next_run_at = next_run_at;
+            return Ok(existing.clone());
+        }
+
+        if tasks
+            .iter()
+            .filter(|task| task.plugin_id == plugin.id)
+            .count()
+            >= MAX_TASKS_PER_PLUGIN
+        {
+            return Err(format!(
+                "Plugins are limited to {} scheduled tasks",
+                MAX_TASKS_PER_PLUGIN
+            ));
+        }
+
+        let task = ScheduledTask {
+            plugin_id: plugin.id.clone(),
+            task_id: task_id.to_string(),
+            schedule,
+            created_at: now_iso(),
+            next_run_at,
+            last_run_at: None,
+        };
+        tasks.push(task.clone());
+        Ok(task)
+    })
+}
+
+/// Handles `scheduler:register`, `scheduler:cancel` and `scheduler:list`.
+/// Registering requires `scheduler:tasks`; a plugin can always inspect and
+/// cancel its own tasks.
+pub fn handle(
+    app: &AppHandle,
+    plugin: &InstalledPlugin,
+    operation: &str,
+    payload: &Value,
+) -> Result<Value, String> {
+    match operation {
+        "scheduler:register" => {
+            if !has_permission(plugin, "scheduler:tasks") {
+                return Err("Permission denied: scheduler:tasks".to_string());
+            }
+            Ok(json!({ "task": register(app, plugin, payload)? }))
+        }
+
+        "scheduler:cancel" => {
+            let task_id = payload
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Payload must include string field 'id'".to_string())?;
+            Ok(json!({ "removed": cancel(app, &plugin.id, task_id)? }))
+        }
+
+        "scheduler:list" => Ok(json!({ "tasks": list(app, Some(&plugin.id))? })),
+
+        _ => Err(format!("Unsupported host operation '{}'", operation)),
+    }
+}
+
+/// Fires due tasks as `plugin-scheduled-task` events and reschedules them.
+/// Runs missed while the app was closed fire once, not once per missed slot.
+/// Tasks of disabled plugins or plugins without the permission are skipped
+/// but still rescheduled; tasks of uninstalled plugins are dropped.
+fn tick(app: &AppHandle) -> Result<(), String> {
+    let store = load_store(app)?;
+    let now = Utc::now();
+
+    with_tasks(app, |tasks| {
+        tasks.retain(|task| {
+            store
+                .installed_plugins
+                .iter()
+                .any(|plugin| plugin.id == task.plugin_id)
+        });
+
+        for task in tasks.iter_mut() {
+            if parse_time(&task.next_run_at).is_some_and(|due| due > now) {
+                continue;
+            }
+
+            let runnable = store.installed_plugins.iter().any(|plugin| {
+                plugin.id == task.plugin_id
+                    && plugin.enabled
+                    && plugin.revocation.is_none()
+                    && has_permission(plugin, "scheduler:tasks")
+            });
+            if runnable {
+                let _ = app.emit(
+                    "plugin-scheduled-task",
+                    json!({
+                        "pluginId": task.plugin_id,
+                        "taskId": task.task_id,
+                        "scheduledAt": task.next_run_at,
+                    }),
+                );
+                task.last_run_at = Some(now.to_rfc3339());
+            }
+
+            task.next_run_at = next_run(&task.schedule, now)?.to_rfc3339();
+        }
+
+        Ok(())
+    })
+}
+
+impl TaskScheduler {
+    /// Starts the background thread that checks schedules every 30 seconds.
+    pub fn start(app: AppHandle) {
+        thread::spawn(move || loop {
+            // A failed tick (e.g. an unreadable store) is retried on the next one.
+            let _ = tick(&app);
+            thread::sleep(TICK_INTERVAL);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_next_cron_match() {
+        // Weekdays at 09:30; 2026-03-06 is a Friday.
+        let spec = parse_cron("30 9 * * 1-5").unwrap();
+        assert_eq!(spec.next_after(at(6, 8, 0)), Some(at(6, 9, 30)));
+        assert_eq!(spec.next_after(at(6, 9, 30)), Some(at(9, 9, 30)));
+
+        let every_quarter = parse_cron("*/15 * * * *").unwrap();
+        assert_eq!(every_quarter.next_after(at(6, 10, 7)), Some(at(6, 10, 15)));
+
+        assert!(parse_cron("61 * * * *").is_err());
+        assert!(parse_cron("* * *").is_err());
+    }
+}
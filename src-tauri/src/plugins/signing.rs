@@ -0,0 +1,82 @@
+use super::MANIFEST_FILE_NAME;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Content digest covered by a manifest's embedded signature.
+///
+/// The archive can't hash itself, so the digest walks its files in name
+/// order, feeding each name, length and contents into SHA-256. The manifest
+/// is included as canonical JSON (sorted keys) with `signature` removed, so
+/// permissions and the allowlist are covered too.
+pub fn archive_digest(zip_bytes: &[u8]) -> Result<String, String> {
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes))
+        .map_err(|error| format!("Failed to parse plugin archive: {}", error))?;
+
+    let mut names = archive
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    names.sort();
+
+    let mut hasher = Sha256::new();
+    for name in names {
+        let mut file = archive
+            .by_name(&name)
+            .map_err(|error| format!("Failed to read archive entry {}: {}", name, error))?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)
+            .map_err(|error| format!("Failed to read archive entry {}: {}", name, error))?;
+
+        if name == MANIFEST_FILE_NAME {
+            let mut manifest = serde_json::from_slice::<Value>(&contents)
+                .map_err(|error| format!("Failed to parse plugin manifest JSON: {}", error))?;
+            if let Some(object) = manifest.as_object_mut() {
+                object.remove("signature");
+            }
+            contents = manifest.to_string().into_bytes();
+        }
+
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn archive(manifest: &str, entry: &str) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in [(MANIFEST_FILE_NAME, manifest), ("index.js", entry)] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn digest_ignores_signature_but_covers_contents() {
+        let unsigned = archive(r#"{"id":"demo","permissions":[]}"#, "run()");
+        let signed = archive(
+            r#"{"permissions":[],"id":"demo","signature":{"keyId":"k"}}"#,
+            "run()",
+        );
+        let tampered = archive(r#"{"id":"demo","permissions":[]}"#, "steal()");
+
+        let digest = archive_digest(&unsigned).unwrap();
+        assert_eq!(digest, archive_digest(&signed).unwrap());
+        assert_ne!(digest, archive_digest(&tampered).unwrap());
+    }
+}
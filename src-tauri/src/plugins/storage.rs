@@ -0,0 +1,129 @@
+use super::{plugin_root, quota, sanitize_plugin_id};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const STORAGE_DIR: &str = "storage";
+const MAX_KEY_LENGTH: usize = 256;
+const MAX_KEYS: usize = 1000;
+const MAX_STORAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Lives outside the install directory so data survives updates and
+/// reinstalls.
+pub(super) fn storage_path(app: &AppHandle, plugin_id: &str) -> Result<PathBuf, String> {
+    let dir = plugin_root(app)?.join(STORAGE_DIR);
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("Failed to create plugin storage directory: {}", error))?;
+    Ok(dir.join(format!("{}.json", sanitize_plugin_id(plugin_id))))
+}
+
+fn load(app: &AppHandle, plugin_id: &str) -> Result<Map<String, Value>, String> {
+    let path = storage_path(app, plugin_id)?;
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read plugin storage: {}", error))?;
+    serde_json::from_str(&content)
+        .map_err(|error| format!("Failed to parse plugin storage: {}", error))
+}
+
+fn save(app: &AppHandle, plugin_id: &str, entries: &Map<String, Value>) -> Result<usize, String> {
+    let payload = serde_json::to_string(entries)
+        .map_err(|error| format!("Failed to serialize plugin storage: {}", error))?;
+    if payload.len() > MAX_STORAGE_BYTES {
+        return Err(format!(
+            "Plugin storage quota exceeded ({} of {} bytes)",
+            payload.len(),
+            MAX_STORAGE_BYTES
+        ));
+    }
+
+    let path = storage_path(app, plugin_id)?;
+    let existing = fs::metadata(&path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    quota::ensure_room(app, plugin_id, existing, payload.len() as u64)?;
+
+    fs::write(&path, &payload)
+        .map_err(|error| format!("Failed to save plugin storage: {}", error))?;
+    Ok(payload.len())
+}
+
+fn payload_key(payload: &Value) -> Result<&str, String> {
+    let key = payload
+        .get("key")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Payload must include string field 'key'".to_string())?;
+
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(format!(
+            "Storage keys must be 1-{} bytes long",
+            MAX_KEY_LENGTH
+        ));
+    }
+
+    Ok(key)
+}
+
+/// Handles `storage:get`, `storage:set`, `storage:delete` and `storage:list`.
+pub fn handle(
+    app: &AppHandle,
+    plugin_id: &str,
+    operation: &str,
+    payload: &Value,
+) -> Result<Value, String> {
+    match operation {
+        "storage:get" => {
+            let key = payload_key(payload)?;
+            let entries = load(app, plugin_id)?;
+            Ok(json!({ "value": entries.get(key).cloned().unwrap_or(Value::Null) }))
+        }
+
+        "storage:set" => {
+            let key = payload_key(payload)?;
+            let value = payload
+                .get("value")
+                .cloned()
+                .ok_or_else(|| "Payload must include field 'value'".to_string())?;
+
+            let mut entries = load(app, plugin_id)?;
+            if !entries.contains_key(key) && entries.len() >= MAX_KEYS {
+                return Err(format!("Plugin storage is limited to {} keys", MAX_KEYS));
+            }
+            entries.insert(key.to_string(), value);
+            let used_bytes = save(app, plugin_id, &entries)?;
+
+            Ok(json!({ "ok": true, "usedBytes": used_bytes, "quotaBytes": MAX_STORAGE_BYTES }))
+        }
+
+        "storage:delete" => {
+            let key = payload_key(payload)?;
+            let mut entries = load(app, plugin_id)?;
+            let deleted = entries.remove(key).is_some();
+            if deleted {
+                save(app, plugin_id, &entries)?;
+            }
+
+            Ok(json!({ "deleted": deleted }))
+        }
+
+        "storage:list" => {
+            let prefix = payload
+                .get("prefix")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let keys = load(app, plugin_id)?
+                .keys()
+                .filter(|key| key.starts_with(prefix))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            Ok(json!({ "keys": keys }))
+        }
+
+        _ => Err(format!("Unsupported host operation '{}'", operation)),
+    }
+}
@@ -0,0 +1,212 @@
+use super::{
+    plugin_install_base_dir, sanitize_plugin_id, ContributedTheme, InstalledPlugin, PluginManifest,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+const MAX_THEME_FILE_BYTES: u64 = 64 * 1024;
+const MAX_THEME_TOKENS: usize = 512;
+const MAX_TOKEN_VALUE_CHARS: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginTheme {
+    pub plugin_id: String,
+    pub id: String,
+    pub label: String,
+    pub base: String,
+}
+
+/// A theme's tokens: CSS custom property name -> value.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginThemeTokens {
+    pub plugin_id: String,
+    pub id: String,
+    pub base: String,
+    pub tokens: BTreeMap<String, String>,
+}
+
+fn is_valid_token_name(name: &str) -> bool {
+    name.len() <= 64
+        && name.strip_prefix("--").is_some_and(|rest| {
+            !rest.is_empty()
+                && rest
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+}
+
+/// Values are applied as CSS custom properties, so anything that could
+/// close the declaration or open a new rule or URL is rejected.
+fn is_valid_token_value(value: &str) -> bool {
+    !value.trim().is_empty()
+        && value.chars().count() <= MAX_TOKEN_VALUE_CHARS
+        && !value
+            .chars()
+            .any(|c| matches!(c, ';' | '{' | '}' | '<' | '>' | '\\' | '"' | '\'') || c.is_control())
+        && !value.to_ascii_lowercase().contains("url(")
+}
+
+fn parse_tokens(theme_id: &str, content: &str) -> Result<BTreeMap<String, String>, String> {
+    let tokens = serde_json::from_str::<BTreeMap<String, String>>(content)
+        .map_err(|error| format!("Invalid token file for theme '{}': {}", theme_id, error))?;
+
+    if tokens.len() > MAX_THEME_TOKENS {
+        return Err(format!(
+            "Theme '{}' defines more than {} tokens",
+            theme_id, MAX_THEME_TOKENS
+        ));
+    }
+    for (name, value) in &tokens {
+        if !is_valid_token_name(name) {
+            return Err(format!(
+                "Invalid token name '{}' in theme '{}'; expected a CSS custom property like --color-primary",
+                name, theme_id
+            ));
+        }
+        if !is_valid_token_value(value) {
+            return Err(format!(
+                "Invalid value for token '{}' in theme '{}'",
+                name, theme_id
+            ));
+        }
+    }
+
+    Ok(tokens)
+}
+
+pub(super) fn validate_contribution(theme: &ContributedTheme) -> Result<(), String> {
+    if theme.label.trim().is_empty() {
+        return Err(format!("Theme '{}' label is required", theme.id));
+    }
+    if !matches!(theme.base.as_str(), "light" | "dark") {
+        return Err(format!(
+            "Invalid theme base '{}' for '{}'; expected light or dark",
+            theme.base, theme.id
+        ));
+    }
+    if theme.path.contains("..")
+        || Path::new(&theme.path).is_absolute()
+        || !theme.path.ends_with(".json")
+    {
+        return Err(format!(
+            "Theme '{}' path must be a relative .json path within the archive",
+            theme.id
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that every contributed theme's token file is in the archive and
+/// valid.
+pub(super) fn validate_archive(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+    manifest: &PluginManifest,
+) -> Result<(), String> {
+    for theme in &manifest.contributes.themes {
+        let path = theme.path.trim_start_matches("./");
+        let mut file = archive
+            .by_name(path)
+            .map_err(|_| format!("Theme file '{}' is missing", theme.path))?;
+        if file.size() > MAX_THEME_FILE_BYTES {
+            return Err(format!(
+                "Theme file '{}' exceeds {} bytes",
+                theme.path, MAX_THEME_FILE_BYTES
+            ));
+        }
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|error| format!("Failed to read theme file '{}': {}", theme.path, error))?;
+        parse_tokens(&theme.id, &content)?;
+    }
+
+    Ok(())
+}
+
+fn is_active(plugin: &InstalledPlugin) -> bool {
+    plugin.enabled && plugin.revocation.is_none()
+}
+
+/// Themes contributed by enabled plugins. Labels come from `plugins`, so
+/// callers pass localized plugins when they have them.
+pub(super) fn list(plugins: &[InstalledPlugin]) -> Vec<PluginTheme> {
+    plugins
+        .iter()
+        .filter(|plugin| is_active(plugin))
+        .flat_map(|plugin| {
+            plugin
+                .manifest
+                .contributes
+                .themes
+                .iter()
+                .map(|theme| PluginTheme {
+                    plugin_id: plugin.id.clone(),
+                    id: theme.id.clone(),
+                    label: theme.label.clone(),
+                    base: theme.base.clone(),
+                })
+        })
+        .collect()
+}
+
+pub(super) fn read(
+    app: &tauri::AppHandle,
+    plugin: &InstalledPlugin,
+    theme_id: &str,
+) -> Result<PluginThemeTokens, String> {
+    if !is_active(plugin) {
+        return Err(format!("Plugin '{}' is disabled", plugin.id));
+    }
+
+    let theme = plugin
+        .manifest
+        .contributes
+        .themes
+        .iter()
+        .find(|theme| theme.id == theme_id)
+        .ok_or_else(|| format!("Theme '{}' not found in '{}'", theme_id, plugin.id))?;
+
+    let path = plugin_install_base_dir(app)?
+        .join(sanitize_plugin_id(&plugin.id))
+        .join(&plugin.version)
+        .join(theme.path.trim_start_matches("./"));
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read theme file '{}': {}", theme.path, error))?;
+
+    Ok(PluginThemeTokens {
+        plugin_id: plugin.id.clone(),
+        id: theme.id.clone(),
+        base: theme.base.clone(),
+        tokens: parse_tokens(&theme.id, &content)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_theme_tokens() {
+        let tokens = parse_tokens(
+            "midnight",
+            r#"{ "--color-primary": "oklch(65% 0.2 260)", "--radius-box": "0.5rem" }"#,
+        )
+        .unwrap();
+        assert_eq!(tokens.len(), 2);
+
+        assert!(parse_tokens("bad", r#"{ "color": "red" }"#).is_err());
+        assert!(parse_tokens(
+            "bad",
+            r#"{ "--color-primary": "red; } body { display: none" }"#
+        )
+        .is_err());
+        assert!(parse_tokens("bad", r#"{ "--bg": "url(https://example.com/x.png)" }"#).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::pdf;
+use chrono::Utc;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const MAX_COPIES: u32 = 99;
+const PRINT_FILE_PREFIX: &str = "grainery-print-";
+/// Age after which a print file is assumed to have been read by the print
+/// handler and can be deleted.
+const STALE_PRINT_FILE_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Initial print dialog settings. Pages are 1-based and inclusive; leaving
+/// both unset prints all pages.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintOptions {
+    #[serde(default)]
+    pub first_page: Option<u32>,
+    #[serde(default)]
+    pub last_page: Option<u32>,
+    #[serde(default)]
+    pub copies: Option<u32>,
+}
+
+fn validate_options(options: &PrintOptions) -> Result<(), String> {
+    if options
+        .copies
+        .is_some_and(|copies| copies == 0 || copies > MAX_COPIES)
+    {
+        return Err(format!("Copies must be between 1 and {}", MAX_COPIES));
+    }
+    if options.first_page == Some(0) || options.last_page == Some(0) {
+        return Err("Page numbers start at 1".to_string());
+    }
+    if let (Some(first), Some(last)) = (options.first_page, options.last_page) {
+        if first > last {
+            return Err(format!("Invalid page range {}-{}", first, last));
+        }
+    }
+
+    Ok(())
+}
+
+fn print_file_path() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "{}{}.pdf",
+        PRINT_FILE_PREFIX,
+        Utc::now().timestamp_millis()
+    ))
+}
+
+/// Deletes print files in `dir` older than `max_age`.
+fn remove_stale_files_in(dir: &Path, max_age: Duration) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with(PRINT_FILE_PREFIX) || !name.ends_with(".pdf") {
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= max_age);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Deletes print files left behind by earlier prints. On Windows the print
+/// handler reads the file after `ShellExecute` returns, so it can't be
+/// removed right after printing; this runs at startup and before each print.
+pub fn remove_stale_files() {
+    remove_stale_files_in(&std::env::temp_dir(), STALE_PRINT_FILE_AGE);
+}
+
+#[cfg(target_os = "macos")]
+fn show_print_dialog(path: &Path, options: &PrintOptions) -> Result<(), String> {
+    use cocoa::base::{id, nil, BOOL, YES};
+    use cocoa::foundation::NSString;
+    use objc::runtime::Class;
+
+    #[link(name = "PDFKit", kind = "framework")]
+    extern "C" {}
+
+    let pdf_document_class =
+        Class::get("PDFDocument").ok_or_else(|| "PDFKit is not available".to_string())?;
+
+    unsafe {
+        let ns_path = NSString::alloc(nil).init_str(&path.to_string_lossy());
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+        let document: id = msg_send![pdf_document_class, alloc];
+        let document: id = msg_send![document, initWithURL: url];
+        if document == nil {
+            return Err("Failed to load print document".to_string());
+        }
+
+        let shared_info: id = msg_send![class!(NSPrintInfo), sharedPrintInfo];
+        let print_info: id = msg_send![shared_info, copy];
+        let settings: id = msg_send![print_info, dictionary];
+        let set_number = |key: &str, value: i64| {
+            let number: id = msg_send![class!(NSNumber), numberWithLongLong: value];
+            let key = NSString::alloc(nil).init_str(key);
+            let () = msg_send![settings, setObject: number forKey: key];
+        };
+        if let Some(copies) = options.copies {
+            set_number("NSCopies", copies as i64);
+        }
+        if options.first_page.is_some() || options.last_page.is_some() {
+            set_number("NSAllPages", 0);
+            set_number("NSFirstPage", options.first_page.unwrap_or(1) as i64);
+            set_number(
+                "NSLastPage",
+                options.last_page.unwrap_or(i32::MAX as u32) as i64,
+            );
+        }
+
+        // kPDFPrintPageScaleNone: pages are already letter size.
+        let operation: id = msg_send![
+            document,
+            printOperationForPrintInfo: print_info
+            scalingMode: 0_i64
+            autoRotate: YES
+        ];
+        if operation == nil {
+            let () = msg_send![print_info, release];
+            let () = msg_send![document, release];
+            return Err("Failed to create print operation".to_string());
+        }
+        let () = msg_send![operation, setShowsPrintPanel: YES];
+        let () = msg_send![operation, setShowsProgressPanel: YES];
+        let _: BOOL = msg_send![operation, runOperation];
+
+        let () = msg_send![print_info, release];
+        let () = msg_send![document, release];
+    }
+
+    Ok(())
+}
+
+/// The `print` shell verb can't carry a page range or copies, so they are
+/// refused rather than silently dropped.
+#[cfg(windows)]
+fn check_platform_options(options: &PrintOptions) -> Result<(), String> {
+    if options.first_page.is_some() || options.last_page.is_some() || options.copies.is_some() {
+        return Err(
+            "Page ranges and copies can't be preset on Windows; choose them in the print dialog"
+                .to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn check_platform_options(_options: &PrintOptions) -> Result<(), String> {
+    Ok(())
+}
+
+/// Opens the PDF with the `print` shell verb, which shows the default PDF
+/// handler's print dialog; see `check_platform_options`.
+#[cfg(windows)]
+fn show_print_dialog(path: &Path, _options: &PrintOptions) -> Result<(), String> {
+    crate::file_manager::shell_execute("print", path)
+        .map_err(|_| "No application is available to print PDFs".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn show_print_dialog(_path: &Path, _options: &PrintOptions) -> Result<(), String> {
+    Err("Printing is not supported on this platform; export a PDF instead".to_string())
+}
+
+/// Paginates the document with the PDF exporter and sends it to the system
+/// print dialog.
+#[tauri::command]
+pub fn print_screenplay(
+    content_json: String,
+    title_page_json: Option<String>,
+    document_title: String,
+    document_mode: String,
+    options: Option<PrintOptions>,
+) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    validate_options(&options)?;
+    check_platform_options(&options)?;
+
+    remove_stale_files();
+    let path = print_file_path();
+    pdf::generate_pdf(
+        &content_json,
+        title_page_json.as_deref(),
+        &path.to_string_lossy(),
+        &document_title,
+        &document_mode,
+    )?;
+
+    let result = show_print_dialog(&path, &options);
+    // On Windows the file is deleted by a later `remove_stale_files`.
+    if cfg!(not(windows)) {
+        let _ = fs::remove_file(&path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_print_options() {
+        assert!(validate_options(&PrintOptions::default()).is_ok());
+        assert!(validate_options(&PrintOptions {
+            first_page: Some(2),
+            last_page: Some(5),
+            copies: Some(3),
+        })
+        .is_ok());
+        assert!(validate_options(&PrintOptions {
+            first_page: Some(5),
+            last_page: Some(2),
+            copies: None,
+        })
+        .is_err());
+        assert!(validate_options(&PrintOptions {
+            copies: Some(0),
+            ..PrintOptions::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn removes_only_stale_print_files() {
+        let dir = std::env::temp_dir().join(format!("grainery-print-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("grainery-print-1.pdf"), b"%PDF").unwrap();
+        fs::write(dir.join("other.pdf"), b"%PDF").unwrap();
+
+        remove_stale_files_in(&dir, Duration::from_secs(3600));
+        assert!(dir.join("grainery-print-1.pdf").exists());
+
+        remove_stale_files_in(&dir, Duration::ZERO);
+        assert!(!dir.join("grainery-print-1.pdf").exists());
+        assert!(dir.join("other.pdf").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
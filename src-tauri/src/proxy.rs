@@ -0,0 +1,148 @@
+use crate::app_data::{load_json, save_json};
+use reqwest::{Client, ClientBuilder, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+const PROXY_SETTINGS_FILE: &str = "proxy-settings.json";
+const KEYCHAIN_SERVICE: &str = "com.grainery.proxy";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProxyMode {
+    /// Use the system configuration and `HTTPS_PROXY`/`NO_PROXY` variables.
+    #[default]
+    System,
+    Manual,
+    /// Connect directly, ignoring system and environment proxies.
+    Direct,
+}
+
+/// App-level proxy configuration. The password never touches disk; it is
+/// kept in the OS keychain under the username.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    #[serde(default)]
+    pub mode: ProxyMode,
+    #[serde(default)]
+    pub host: String,
+    #[serde(default)]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Hosts that bypass the manual proxy, in `NO_PROXY` syntax.
+    #[serde(default)]
+    pub bypass: Vec<String>,
+    /// Reported to the UI only; derived from the keychain.
+    #[serde(default, skip_deserializing)]
+    pub has_password: bool,
+}
+
+fn keychain_entry(username: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, username)
+        .map_err(|error| format!("Failed to open keychain: {}", error))
+}
+
+fn load_password(username: &str) -> Result<Option<String>, String> {
+    match keychain_entry(username)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(error) => Err(format!("Failed to read proxy password: {}", error)),
+    }
+}
+
+fn validate(settings: &ProxySettings) -> Result<(), String> {
+    if settings.mode != ProxyMode::Manual {
+        return Ok(());
+    }
+
+    let host = settings.host.trim();
+    if host.is_empty() || host.contains("://") || host.contains('/') {
+        return Err("Proxy host must be a host name or IP address".to_string());
+    }
+    if settings.port == 0 {
+        return Err("Proxy port is required".to_string());
+    }
+
+    Ok(())
+}
+
+/// A client builder with the configured proxy applied. Every outbound
+/// request (registry fetches, downloads, plugin host calls) starts here.
+pub fn client_builder(app: &AppHandle) -> Result<ClientBuilder, String> {
+    let settings = load_json::<ProxySettings>(app, PROXY_SETTINGS_FILE)?;
+    let builder = Client::builder();
+
+    match settings.mode {
+        ProxyMode::System => Ok(builder),
+        ProxyMode::Direct => Ok(builder.no_proxy()),
+        ProxyMode::Manual => {
+            validate(&settings)?;
+            let mut proxy =
+                Proxy::all(format!("http://{}:{}", settings.host.trim(), settings.port))
+                    .map_err(|error| format!("Invalid proxy configuration: {}", error))?
+                    .no_proxy(NoProxy::from_string(&settings.bypass.join(",")));
+
+            if let Some(username) = settings.username.as_deref().filter(|name| !name.is_empty()) {
+                let password = load_password(username)?.unwrap_or_default();
+                proxy = proxy.basic_auth(username, &password);
+            }
+
+            Ok(builder.proxy(proxy))
+        }
+    }
+}
+
+pub fn client(app: &AppHandle) -> Result<Client, String> {
+    client_builder(app)?
+        .build()
+        .map_err(|error| format!("Failed to create HTTP client: {}", error))
+}
+
+#[tauri::command]
+pub fn proxy_get_settings(app: AppHandle) -> Result<ProxySettings, String> {
+    let mut settings = load_json::<ProxySettings>(&app, PROXY_SETTINGS_FILE)?;
+    settings.has_password = match settings.username.as_deref() {
+        Some(username) if !username.is_empty() => load_password(username)?.is_some(),
+        _ => false,
+    };
+    Ok(settings)
+}
+
+/// Saves the settings. `password` replaces the stored password when given;
+/// an empty string removes it.
+#[tauri::command]
+pub fn proxy_set_settings(
+    app: AppHandle,
+    settings: ProxySettings,
+    password: Option<String>,
+) -> Result<ProxySettings, String> {
+    validate(&settings)?;
+
+    let previous = load_json::<ProxySettings>(&app, PROXY_SETTINGS_FILE)?;
+    let username = settings.username.clone().filter(|name| !name.is_empty());
+    if let Some(old) = previous
+        .username
+        .filter(|name| Some(name) != username.as_ref())
+    {
+        // Don't leave the old account's password behind in the keychain.
+        let _ = keychain_entry(&old)?.delete_credential();
+    }
+
+    if let (Some(username), Some(password)) = (username.as_deref(), password) {
+        let entry = keychain_entry(username)?;
+        if password.is_empty() {
+            match entry.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => {}
+                Err(error) => return Err(format!("Failed to remove proxy password: {}", error)),
+            }
+        } else {
+            entry
+                .set_password(&password)
+                .map_err(|error| format!("Failed to store proxy password: {}", error))?;
+        }
+    }
+
+    save_json(&app, PROXY_SETTINGS_FILE, &settings)?;
+    proxy_get_settings(app)
+}
@@ -0,0 +1,348 @@
+use crate::app_data;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+const RECENT_FILES_FILE: &str = "recent-files.json";
+const MAX_RECENT_FILES: usize = 8;
+/// Command line flag of the jump list's New Screenplay task.
+pub const NEW_SCREENPLAY_ARG: &str = "--new-screenplay";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFileEntry {
+    pub path: String,
+    pub filename: String,
+    pub last_opened_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentFilesStore {
+    #[serde(default)]
+    entries: Vec<RecentFileEntry>,
+}
+
+fn load_entries(app: &AppHandle) -> Result<Vec<RecentFileEntry>, String> {
+    let store: RecentFilesStore = app_data::load_json(app, RECENT_FILES_FILE)?;
+    Ok(store.entries)
+}
+
+/// Opens a recent file picked from the Dock menu the same way as a file
+/// opened from Finder.
+#[cfg(target_os = "macos")]
+fn open_recent(app: &AppHandle, path: String) {
+    use tauri::{Emitter, Manager};
+
+    let paths = vec![path];
+    let pending = app.state::<crate::PendingOpenFiles>();
+    pending.push_paths(paths.clone());
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("app-open-file", paths);
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod dock {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{class_addMethod, object_getClass, Class, Object, Sel};
+    use std::sync::{Mutex, OnceLock};
+    use tauri::{AppHandle, Emitter, Manager};
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+    static PATHS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static TARGET: OnceLock<usize> = OnceLock::new();
+
+    extern "C" fn open_recent(_this: &Object, _cmd: Sel, sender: id) {
+        let index: isize = unsafe { msg_send![sender, tag] };
+        let path = PATHS
+            .lock()
+            .ok()
+            .and_then(|paths| paths.get(index as usize).cloned());
+        if let (Some(app), Some(path)) = (APP.get(), path) {
+            super::open_recent(app, path);
+        }
+    }
+
+    extern "C" fn new_screenplay(_this: &Object, _cmd: Sel, _sender: id) {
+        if let Some(window) = APP.get().and_then(|app| app.get_webview_window("main")) {
+            let _ = window.emit("menu-event", "new");
+        }
+    }
+
+    /// Menu item target; AppKit needs an object that answers the actions.
+    fn target() -> id {
+        *TARGET.get_or_init(|| {
+            let mut decl = ClassDecl::new("GraineryDockMenuTarget", class!(NSObject))
+                .expect("dock menu target class already declared");
+            unsafe {
+                decl.add_method(
+                    sel!(openRecent:),
+                    open_recent as extern "C" fn(&Object, Sel, id),
+                );
+                decl.add_method(
+                    sel!(newScreenplay:),
+                    new_screenplay as extern "C" fn(&Object, Sel, id),
+                );
+            }
+            let class = decl.register();
+            let target: id = unsafe { msg_send![class, new] };
+            target as usize
+        }) as id
+    }
+
+    unsafe fn add_item(menu: id, title: &str, action: Sel, tag: isize) {
+        let title = NSString::alloc(nil).init_str(title);
+        let key = NSString::alloc(nil).init_str("");
+        let item: id = msg_send![class!(NSMenuItem), alloc];
+        let item: id = msg_send![item, initWithTitle: title action: action keyEquivalent: key];
+        let () = msg_send![item, setTarget: target()];
+        let () = msg_send![item, setTag: tag];
+        let () = msg_send![menu, addItem: item];
+        let () = msg_send![item, release];
+        let () = msg_send![title, release];
+        let () = msg_send![key, release];
+    }
+
+    /// `applicationDockMenu:`, called by AppKit each time the Dock menu opens.
+    extern "C" fn dock_menu(_this: &Object, _cmd: Sel, _sender: id) -> id {
+        let paths = PATHS.lock().map(|paths| paths.clone()).unwrap_or_default();
+        unsafe {
+            let menu: id = msg_send![class!(NSMenu), new];
+            let menu: id = msg_send![menu, autorelease];
+            for (index, path) in paths.iter().enumerate() {
+                let title = std::path::Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone());
+                add_item(menu, &title, sel!(openRecent:), index as isize);
+            }
+            if !paths.is_empty() {
+                let separator: id = msg_send![class!(NSMenuItem), separatorItem];
+                let () = msg_send![menu, addItem: separator];
+            }
+            add_item(menu, "New Screenplay", sel!(newScreenplay:), 0);
+            menu
+        }
+    }
+
+    /// Adds `applicationDockMenu:` to the app delegate installed by the
+    /// window runtime.
+    pub fn install(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+
+        unsafe {
+            let ns_app: id = msg_send![class!(NSApplication), sharedApplication];
+            let delegate: id = msg_send![ns_app, delegate];
+            if delegate == nil {
+                return;
+            }
+            let class = object_getClass(delegate as *const Object) as *mut Class;
+            let imp: extern "C" fn(&Object, Sel, id) -> id = dock_menu;
+            class_addMethod(
+                class,
+                sel!(applicationDockMenu:),
+                std::mem::transmute::<extern "C" fn(&Object, Sel, id) -> id, objc::runtime::Imp>(
+                    imp,
+                ),
+                c"@@:@".as_ptr(),
+            );
+        }
+    }
+
+    pub fn update(paths: Vec<String>) {
+        if let Ok(mut current) = PATHS.lock() {
+            *current = paths;
+        }
+    }
+}
+
+#[cfg(windows)]
+mod jump_list {
+    use super::{RecentFileEntry, NEW_SCREENPLAY_ARG};
+    use std::path::Path;
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::StructuredStorage::{
+        PROPVARIANT, PROPVARIANT_0, PROPVARIANT_0_0, PROPVARIANT_0_0_0,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::System::Variant::VT_LPWSTR;
+    use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW,
+        SHStrDupW, ShellLink,
+    };
+
+    const RECENT_CATEGORY: &str = "Recent";
+
+    /// A `VT_LPWSTR` value, the type the shell expects for `PKEY_Title`.
+    unsafe fn string_value(value: &str) -> windows::core::Result<PROPVARIANT> {
+        let text = SHStrDupW(&HSTRING::from(value))?;
+        Ok(PROPVARIANT {
+            Anonymous: PROPVARIANT_0 {
+                Anonymous: std::mem::ManuallyDrop::new(PROPVARIANT_0_0 {
+                    vt: VT_LPWSTR,
+                    wReserved1: 0,
+                    wReserved2: 0,
+                    wReserved3: 0,
+                    Anonymous: PROPVARIANT_0_0_0 { pwszVal: text },
+                }),
+            },
+        })
+    }
+
+    /// A shell link that starts Grainery with `arguments`, shown as `title`.
+    unsafe fn app_link(
+        exe: &HSTRING,
+        arguments: &str,
+        title: &str,
+        description: &str,
+    ) -> windows::core::Result<IShellLinkW> {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(exe)?;
+        link.SetArguments(&HSTRING::from(arguments))?;
+        link.SetIconLocation(exe, 0)?;
+        link.SetDescription(&HSTRING::from(description))?;
+        let properties: IPropertyStore = link.cast()?;
+        properties.SetValue(&PKEY_Title, &string_value(title)?)?;
+        properties.Commit()?;
+        Ok(link)
+    }
+
+    /// Arguments of the links the user removed from the jump list, which
+    /// the shell refuses to take back until they are used again.
+    unsafe fn removed_arguments(removed: &IObjectArray) -> Vec<String> {
+        let count = removed.GetCount().unwrap_or(0);
+        (0..count)
+            .filter_map(|index| removed.GetAt::<IShellLinkW>(index).ok())
+            .filter_map(|link| {
+                let mut buffer = [0u16; 1024];
+                link.GetArguments(&mut buffer).ok()?;
+                let length = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+                Some(String::from_utf16_lossy(&buffer[..length]))
+            })
+            .collect()
+    }
+
+    unsafe fn build(exe: &HSTRING, entries: &[RecentFileEntry]) -> windows::core::Result<()> {
+        let list: ICustomDestinationList =
+            CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+        let mut slots = 0u32;
+        let removed: IObjectArray = list.BeginList(&mut slots)?;
+        let removed = removed_arguments(&removed);
+
+        let recent: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        let mut count = 0;
+        for entry in entries {
+            let arguments = format!("\"{}\"", entry.path);
+            if removed.contains(&arguments) || count >= slots.saturating_sub(1) {
+                continue;
+            }
+            let title = Path::new(&entry.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| entry.filename.clone());
+            recent.AddObject(&app_link(exe, &arguments, &title, &entry.path)?)?;
+            count += 1;
+        }
+        if count > 0 {
+            list.AppendCategory(&HSTRING::from(RECENT_CATEGORY), &recent)?;
+        }
+
+        let tasks: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        tasks.AddObject(&app_link(
+            exe,
+            NEW_SCREENPLAY_ARG,
+            "New Screenplay",
+            "Start a new screenplay",
+        )?)?;
+        list.AddUserTasks(&tasks)?;
+
+        list.CommitList()
+    }
+
+    /// Rebuilds the taskbar jump list from scratch: a Recent category with
+    /// `entries`, newest first, and a New Screenplay task. Each file is a
+    /// link that starts Grainery with its path, so removing or clearing
+    /// recent files takes them off the list too.
+    pub fn update(entries: &[RecentFileEntry]) {
+        let exe = match std::env::current_exe() {
+            Ok(exe) => HSTRING::from(exe.as_os_str()),
+            Err(error) => {
+                tracing::warn!("Failed to update jump list: {}", error);
+                return;
+            }
+        };
+
+        unsafe {
+            // The main thread already runs COM; this only balances the count.
+            let initialized = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
+            if let Err(error) = build(&exe, entries) {
+                tracing::warn!("Failed to update jump list: {}", error);
+            }
+            if initialized {
+                CoUninitialize();
+            }
+        }
+    }
+}
+
+fn refresh_native(entries: &[RecentFileEntry]) {
+    #[cfg(target_os = "macos")]
+    dock::update(entries.iter().map(|entry| entry.path.clone()).collect());
+
+    #[cfg(windows)]
+    jump_list::update(entries);
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let _ = entries;
+}
+
+/// Installs the Dock menu and fills native recent-file lists from the
+/// saved store.
+pub fn init(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    dock::install(app);
+
+    if let Ok(entries) = load_entries(app) {
+        refresh_native(&entries);
+    }
+}
+
+#[tauri::command]
+pub fn recent_files_get(app: AppHandle) -> Result<Vec<RecentFileEntry>, String> {
+    load_entries(&app)
+}
+
+/// Replaces the stored list (newest first) and refreshes the Dock menu and
+/// jump list.
+#[tauri::command]
+pub fn recent_files_set(
+    app: AppHandle,
+    mut entries: Vec<RecentFileEntry>,
+) -> Result<Vec<RecentFileEntry>, String> {
+    entries.retain(|entry| !entry.path.trim().is_empty());
+    entries.truncate(MAX_RECENT_FILES);
+
+    app_data::save_json(
+        &app,
+        RECENT_FILES_FILE,
+        &RecentFilesStore {
+            entries: entries.clone(),
+        },
+    )?;
+    refresh_native(&entries);
+    Ok(entries)
+}
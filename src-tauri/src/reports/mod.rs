@@ -0,0 +1,123 @@
+use std::fs;
+
+mod pdf;
+mod xlsx;
+
+/// A tabular report that can be written as CSV, XLSX or a formatted PDF.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub sections: Vec<ReportSection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportSection {
+    pub heading: Option<String>,
+    pub columns: Vec<ReportColumn>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportColumn {
+    pub title: String,
+    /// Column width in characters for the PDF layout.
+    pub width: usize,
+}
+
+impl ReportColumn {
+    pub fn new(title: &str, width: usize) -> Self {
+        Self {
+            title: title.to_string(),
+            width,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Csv,
+    Pdf,
+    Xlsx,
+}
+
+impl ReportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "pdf" => Ok(Self::Pdf),
+            "xlsx" => Ok(Self::Xlsx),
+            _ => Err(format!("Unsupported report format '{}'", format)),
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(['"', ',', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_line(values: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    values
+        .into_iter()
+        .map(|value| csv_field(value.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Flattens every section into one table under the first section's header.
+/// Sections with a heading get it as a leading column so the rows stay
+/// sortable in a spreadsheet.
+fn table_rows(report: &Report) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let grouped = report
+        .sections
+        .iter()
+        .any(|section| section.heading.is_some());
+
+    if let Some(first) = report.sections.first() {
+        let mut header = Vec::new();
+        if grouped {
+            header.push("Group".to_string());
+        }
+        header.extend(first.columns.iter().map(|column| column.title.clone()));
+        rows.push(header);
+    }
+
+    for section in &report.sections {
+        for row in &section.rows {
+            let mut values = Vec::new();
+            if grouped {
+                values.push(section.heading.clone().unwrap_or_default());
+            }
+            values.extend(row.iter().cloned());
+            rows.push(values);
+        }
+    }
+
+    rows
+}
+
+pub fn to_csv(report: &Report) -> String {
+    table_rows(report)
+        .into_iter()
+        .map(|row| format!("{}\r\n", csv_line(row)))
+        .collect()
+}
+
+pub fn write_report(
+    report: &Report,
+    format: ReportFormat,
+    output_path: &str,
+) -> Result<(), String> {
+    match format {
+        ReportFormat::Csv => fs::write(output_path, to_csv(report))
+            .map_err(|error| format!("Failed to write CSV report: {}", error)),
+        ReportFormat::Pdf => pdf::write_pdf(report, output_path),
+        ReportFormat::Xlsx => fs::write(output_path, xlsx::to_xlsx(report, &table_rows(report))?)
+            .map_err(|error| format!("Failed to write XLSX report: {}", error)),
+    }
+}
@@ -0,0 +1,153 @@
+use super::Report;
+use printpdf::*;
+use std::fs::File;
+use std::io::BufWriter;
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 36.0;
+const TITLE_SIZE: f32 = 14.0;
+const BODY_SIZE: f32 = 9.0;
+const LINE_HEIGHT: f32 = 12.0;
+const CHAR_WIDTH: f32 = BODY_SIZE * 0.6; // Courier is monospaced at 0.6em
+
+struct ReportPdf {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    bold_font: IndirectFontRef,
+    y: f32,
+    page_number: u32,
+}
+
+impl ReportPdf {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(
+            title,
+            Mm::from(Pt(PAGE_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT)),
+            "Layer 1",
+        );
+        let font = doc
+            .add_builtin_font(BuiltinFont::Courier)
+            .map_err(|e| format!("Failed to add font: {}", e))?;
+        let bold_font = doc
+            .add_builtin_font(BuiltinFont::CourierBold)
+            .map_err(|e| format!("Failed to add font: {}", e))?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            font,
+            bold_font,
+            y: PAGE_HEIGHT - MARGIN,
+            page_number: 1,
+        })
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height >= MARGIN {
+            return;
+        }
+
+        let (page, layer) = self.doc.add_page(
+            Mm::from(Pt(PAGE_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT)),
+            "Layer 1",
+        );
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+        self.page_number += 1;
+
+        let label = format!("{}.", self.page_number);
+        self.layer.use_text(
+            &label,
+            BODY_SIZE,
+            Mm::from(Pt(PAGE_WIDTH - MARGIN - label.len() as f32 * CHAR_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT - MARGIN / 2.0)),
+            &self.font,
+        );
+    }
+
+    fn text(&mut self, text: &str, size: f32, bold: bool) {
+        self.ensure_space(LINE_HEIGHT.max(size));
+        let font = if bold { &self.bold_font } else { &self.font };
+        self.layer.use_text(
+            text,
+            size,
+            Mm::from(Pt(MARGIN)),
+            Mm::from(Pt(self.y - size)),
+            font,
+        );
+        self.y -= LINE_HEIGHT.max(size + 4.0);
+    }
+
+    fn gap(&mut self) {
+        self.y -= LINE_HEIGHT / 2.0;
+    }
+}
+
+/// Pads or truncates a cell to its column width so rows line up in Courier.
+fn fit_cell(value: &str, width: usize) -> String {
+    let value = value.replace(['\n', '\r'], " ");
+    let length = value.chars().count();
+    if length <= width {
+        return format!("{}{}", value, " ".repeat(width - length));
+    }
+
+    let mut truncated = value
+        .chars()
+        .take(width.saturating_sub(1))
+        .collect::<String>();
+    truncated.push('~');
+    truncated
+}
+
+fn table_line(values: &[String], widths: &[usize]) -> String {
+    values
+        .iter()
+        .zip(widths)
+        .map(|(value, width)| fit_cell(value, *width))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_string()
+}
+
+pub fn write_pdf(report: &Report, output_path: &str) -> Result<(), String> {
+    let mut pdf = ReportPdf::new(&report.title)?;
+    pdf.text(&report.title.to_uppercase(), TITLE_SIZE, true);
+    if let Some(subtitle) = &report.subtitle {
+        pdf.text(subtitle, BODY_SIZE, false);
+    }
+    pdf.gap();
+
+    for section in &report.sections {
+        if let Some(heading) = &section.heading {
+            pdf.gap();
+            pdf.text(heading, BODY_SIZE + 1.0, true);
+        }
+
+        let widths = section
+            .columns
+            .iter()
+            .map(|column| column.width)
+            .collect::<Vec<_>>();
+        let titles = section
+            .columns
+            .iter()
+            .map(|column| column.title.to_uppercase())
+            .collect::<Vec<_>>();
+        pdf.text(&table_line(&titles, &widths), BODY_SIZE, true);
+
+        for row in &section.rows {
+            pdf.text(&table_line(row, &widths), BODY_SIZE, false);
+        }
+    }
+
+    let file = File::create(output_path).map_err(|e| format!("Failed to create file: {}", e))?;
+    pdf.doc
+        .save(&mut BufWriter::new(file))
+        .map_err(|e| format!("Failed to save PDF: {}", e))
+}
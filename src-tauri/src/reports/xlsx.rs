@@ -0,0 +1,182 @@
+use super::Report;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const MAX_SHEET_NAME_CHARS: usize = 31;
+const MAX_COLUMN_WIDTH: usize = 60;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+/// Style 0 is plain text, style 1 the bold header row.
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs></styleSheet>"#;
+
+fn escape_xml(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+/// `0` -> `A`, `25` -> `Z`, `26` -> `AA`.
+fn column_name(mut index: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.insert(0, (b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            return name;
+        }
+        index = index / 26 - 1;
+    }
+}
+
+/// Plain integers and decimals become number cells so they sum and sort.
+/// Leading zeros mark codes rather than numbers and stay text.
+fn is_number(value: &str) -> bool {
+    let leading_zero = value.len() > 1 && value.starts_with('0') && !value.starts_with("0.");
+    !leading_zero
+        && value.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && value.parse::<f64>().is_ok()
+}
+
+fn sheet_name(title: &str) -> String {
+    let name = title
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(MAX_SHEET_NAME_CHARS)
+        .collect::<String>();
+    let name = name.trim();
+    if name.is_empty() {
+        "Report".to_string()
+    } else {
+        escape_xml(name)
+    }
+}
+
+fn worksheet(rows: &[Vec<String>]) -> String {
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>"#,
+    );
+
+    if column_count > 0 {
+        xml.push_str("<cols>");
+        for column in 0..column_count {
+            let width = rows
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|value| value.chars().count())
+                .max()
+                .unwrap_or(0)
+                .clamp(6, MAX_COLUMN_WIDTH)
+                + 2;
+            xml.push_str(&format!(
+                r#"<col min="{0}" max="{0}" width="{1}" customWidth="1"/>"#,
+                column + 1,
+                width
+            ));
+        }
+        xml.push_str("</cols>");
+    }
+
+    xml.push_str("<sheetData>");
+    for (row_index, row) in rows.iter().enumerate() {
+        xml.push_str(&format!(r#"<row r="{}">"#, row_index + 1));
+        for (column, value) in row.iter().enumerate() {
+            let reference = format!("{}{}", column_name(column), row_index + 1);
+            if row_index > 0 && is_number(value) {
+                xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, value));
+            } else {
+                xml.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"{}><is><t xml:space="preserve">{}</t></is></c>"#,
+                    reference,
+                    if row_index == 0 { r#" s="1""# } else { "" },
+                    escape_xml(value)
+                ));
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// Writes `rows` (header first) as a single-sheet workbook.
+pub fn to_xlsx(report: &Report, rows: &[Vec<String>]) -> Result<Vec<u8>, String> {
+    let workbook = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        sheet_name(&report.title)
+    );
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+        ("_rels/.rels", ROOT_RELS.to_string()),
+        ("xl/workbook.xml", workbook),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.to_string()),
+        ("xl/styles.xml", STYLES.to_string()),
+        ("xl/worksheets/sheet1.xml", worksheet(rows)),
+    ];
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in parts {
+        writer
+            .start_file(name, options)
+            .map_err(|error| format!("Failed to write XLSX report: {}", error))?;
+        writer
+            .write_all(contents.as_bytes())
+            .map_err(|error| format!("Failed to write XLSX report: {}", error))?;
+    }
+
+    writer
+        .finish()
+        .map(Cursor::into_inner)
+        .map_err(|error| format!("Failed to write XLSX report: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_columns_and_numbers() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+        assert!(is_number("12"));
+        assert!(is_number("1.5"));
+        assert!(is_number("0"));
+        assert!(!is_number("12A"));
+        assert!(!is_number("007"));
+        assert!(!is_number("1 3/8"));
+    }
+
+    #[test]
+    fn worksheet_escapes_text() {
+        let rows = vec![
+            vec!["Scene".to_string(), "Set".to_string()],
+            vec!["4".to_string(), "BAR & GRILL <EAST>".to_string()],
+        ];
+        let xml = worksheet(&rows);
+        assert!(xml.contains(r#"<c r="A2"><v>4</v></c>"#));
+        assert!(xml.contains("BAR &amp; GRILL &lt;EAST&gt;"));
+        assert!(xml.contains(r#"<c r="A1" t="inlineStr" s="1">"#));
+    }
+}
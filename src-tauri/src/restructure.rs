@@ -0,0 +1,693 @@
+//! Structural edits to the script that keep what points into it attached:
+//! scene numbers, outline cards (linked by `sceneId`), breakdown tags
+//! (anchored by block index) and comments (anchored by editor position).
+
+use crate::document::slugline;
+use crate::outline::{self, IndexCard};
+use crate::sidecar::{self, DocumentSidecar};
+use crate::{comments, document, scene_numbers};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Restructured {
+    pub document_json: String,
+    /// Outline cards in their updated order.
+    pub cards: Vec<IndexCard>,
+}
+
+/// Editor position where each block starts, plus the end of the document.
+fn block_starts(blocks: &[Value]) -> Vec<usize> {
+    let mut starts = Vec::with_capacity(blocks.len() + 1);
+    let mut position = 0;
+    for block in blocks {
+        starts.push(position);
+        position += document::node_size(block);
+    }
+    starts.push(position);
+    starts
+}
+
+/// The block holding editor position `position` and the text offset of the
+/// position within it. A range end on a block boundary belongs to the block
+/// it closes.
+fn locate(starts: &[usize], position: usize, range_end: bool) -> Option<(usize, usize)> {
+    let blocks = starts.len().checked_sub(1)?;
+    let block = (0..blocks).find(|&block| {
+        if range_end {
+            starts[block] < position && position <= starts[block + 1]
+        } else {
+            starts[block] <= position && position < starts[block + 1]
+        }
+    })?;
+    Some((block, position.saturating_sub(starts[block] + 1)))
+}
+
+fn text_length(block: &Value) -> usize {
+    document::node_text(block).encode_utf16().count()
+}
+
+/// Moves breakdown tags and comments to where their text went. `map` takes
+/// a block index and text offset in `old_blocks` to the same text in the
+/// document as it is now; its flag is set for the end of a range. Anchors
+/// it can't place are left as they were.
+fn remap_anchors(
+    document: &mut Value,
+    old_blocks: &[Value],
+    store: &mut DocumentSidecar,
+    map: impl Fn(usize, usize, bool) -> (usize, usize),
+) -> Result<(), String> {
+    let blocks = document::blocks(document);
+    for tag in &mut store.breakdown_tags {
+        if tag.anchor.block_index >= old_blocks.len() {
+            continue;
+        }
+        let (block, start) = map(tag.anchor.block_index, tag.anchor.start, false);
+        let (end_block, end) = map(tag.anchor.block_index, tag.anchor.end, true);
+        tag.anchor.block_index = block;
+        tag.anchor.start = start;
+        // A tag cut in two by a split keeps the part before the cut.
+        tag.anchor.end = if end_block == block {
+            end.max(start)
+        } else {
+            blocks.get(block).map(text_length).unwrap_or(start)
+        };
+    }
+
+    let old_starts = block_starts(old_blocks);
+    let new_starts = block_starts(document::blocks(document));
+    let to_position = |(block, offset): (usize, usize)| {
+        new_starts
+            .get(block)
+            .map(|start| start + 1 + offset)
+            .unwrap_or(new_starts[new_starts.len() - 1])
+    };
+    let mut comments = comments::read_comments(document)?;
+    if comments.is_empty() {
+        return Ok(());
+    }
+    for comment in &mut comments {
+        let from = locate(&old_starts, comment.anchor.from, false);
+        let to = locate(&old_starts, comment.anchor.to, true);
+        let (Some((from_block, from_offset)), Some((to_block, to_offset))) = (from, to) else {
+            continue;
+        };
+        comment.anchor.from = to_position(map(from_block, from_offset, false));
+        comment.anchor.to = to_position(map(to_block, to_offset, true)).max(comment.anchor.from);
+    }
+    comments.sort_by_key(|comment| comment.anchor.from);
+    comments::write_comments(document, &comments)
+}
+
+/// Renumbers unlocked scripts that use scene numbers. Locked numbers stay
+/// with their scenes, and new scenes get A/B numbers.
+fn update_scene_numbers(document: &mut Value) -> Result<(), String> {
+    let numbered = document::scenes(document)
+        .iter()
+        .any(|scene| scene.scene_number.is_some());
+    if !numbered {
+        return Ok(());
+    }
+    if scene_numbers::is_locked(document) {
+        scene_numbers::assign(document)?;
+    } else {
+        scene_numbers::renumber(document)?;
+    }
+    Ok(())
+}
+
+fn scene_ids(document: &Value) -> Vec<String> {
+    document::scenes(document)
+        .into_iter()
+        .filter_map(|scene| scene.scene_id)
+        .collect()
+}
+
+/// Old block index for each block after moving scenes `first..=last` in
+/// front of scene `target`, or to the end when `target` is the scene count.
+fn move_order(
+    blocks: &[Value],
+    first: usize,
+    last: usize,
+    target: usize,
+) -> Result<Vec<usize>, String> {
+    let scenes = document::block_scenes(blocks);
+    if first > last || last >= scenes.len() {
+        return Err(format!(
+            "Scenes {}-{} are out of range; the script has {} scenes",
+            first + 1,
+            last + 1,
+            scenes.len()
+        ));
+    }
+    if target > scenes.len() {
+        return Err(format!("Scene {} is out of range", target + 1));
+    }
+    if target > first && target <= last {
+        return Err("Scenes can't be moved inside themselves".to_string());
+    }
+
+    let moved = scenes[first].heading_block..scenes[last].end_block;
+    let destination = scenes
+        .get(target)
+        .map(|scene| scene.heading_block)
+        .unwrap_or(blocks.len());
+    let order = if destination <= moved.start {
+        (0..destination)
+            .chain(moved.clone())
+            .chain(destination..moved.start)
+            .chain(moved.end..blocks.len())
+            .collect()
+    } else {
+        (0..moved.start)
+            .chain(moved.end..destination)
+            .chain(moved.clone())
+            .chain(destination..blocks.len())
+            .collect()
+    };
+    Ok(order)
+}
+
+/// Moves scenes `first..=last` (zero-based, inclusive) in front of scene
+/// `target`, or to the end of the script when `target` is the scene count.
+pub fn move_scenes(
+    document: &mut Value,
+    store: &mut DocumentSidecar,
+    first: usize,
+    last: usize,
+    target: usize,
+) -> Result<(), String> {
+    let old_blocks = document::blocks(document).to_vec();
+    let order = move_order(&old_blocks, first, last, target)?;
+    let mut new_index = vec![0; order.len()];
+    for (new, old) in order.iter().enumerate() {
+        new_index[*old] = new;
+    }
+
+    let blocks =
+        document::blocks_mut(document).ok_or_else(|| "Document has no content".to_string())?;
+    *blocks = order.iter().map(|old| old_blocks[*old].clone()).collect();
+
+    update_scene_numbers(document)?;
+    remap_anchors(document, &old_blocks, store, |block, offset, _| {
+        (new_index[block], offset)
+    })?;
+    outline::follow_scenes(&mut store.index_cards, &scene_ids(document));
+    Ok(())
+}
+
+/// A heading for a scene split off `heading`, in the same place at the same
+/// time of day.
+fn inherited_heading(heading: &str) -> String {
+    let slugline = slugline::parse(heading);
+    let prefix = match slugline.int_ext.as_str() {
+        "INT/EXT" => "INT./EXT.".to_string(),
+        "" => String::new(),
+        int_ext => format!("{}.", int_ext),
+    };
+    let place = [prefix, slugline.location]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if slugline.time.is_empty() {
+        place
+    } else {
+        format!("{} - {}", place, slugline.time)
+    }
+}
+
+/// Splits inline content at a text offset in UTF-16 units, keeping marks on
+/// both halves. Inline nodes without text count as one unit.
+fn split_inline(content: &[Value], at: usize) -> (Vec<Value>, Vec<Value>) {
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    let mut offset = 0;
+    for node in content {
+        let Some(text) = node.get("text").and_then(Value::as_str) else {
+            if offset < at {
+                before.push(node.clone());
+            } else {
+                after.push(node.clone());
+            }
+            offset += 1;
+            continue;
+        };
+        let length = text.encode_utf16().count();
+        if offset + length <= at {
+            before.push(node.clone());
+        } else if offset >= at {
+            after.push(node.clone());
+        } else {
+            let mut units = offset;
+            let cut = text
+                .char_indices()
+                .find(|(_, character)| {
+                    let found = units >= at;
+                    units += character.len_utf16();
+                    found
+                })
+                .map(|(index, _)| index)
+                .unwrap_or(text.len());
+            for (part, half) in [(&text[..cut], &mut before), (&text[cut..], &mut after)] {
+                if !part.is_empty() {
+                    let mut piece = node.clone();
+                    piece["text"] = Value::String(part.to_string());
+                    half.push(piece);
+                }
+            }
+        }
+        offset += length;
+    }
+    (before, after)
+}
+
+/// Starts a new scene at editor position `position`, splitting the block
+/// there when the cursor is inside its text. The new heading repeats the
+/// location and time of the scene it came from.
+pub fn split_scene(
+    document: &mut Value,
+    store: &mut DocumentSidecar,
+    position: usize,
+) -> Result<(), String> {
+    let old_blocks = document::blocks(document).to_vec();
+    let starts = block_starts(&old_blocks);
+    let (block, offset) = locate(&starts, position, false)
+        .ok_or_else(|| "The cursor is outside the script".to_string())?;
+    let scene = document::block_scenes(&old_blocks)
+        .into_iter()
+        .find(|scene| scene.heading_block <= block && block < scene.end_block)
+        .ok_or_else(|| "The cursor is not inside a scene".to_string())?;
+    if block == scene.heading_block {
+        return Err("Place the cursor in the scene's text to split it".to_string());
+    }
+
+    let length = text_length(&old_blocks[block]);
+    // The block is cut in two only when the cursor is inside its text.
+    let cut =
+        offset > 0 && offset < length && document::node_type(&old_blocks[block]) != "pageBreak";
+    let insert_at = if offset == 0 { block } else { block + 1 };
+    let heading = outline::scene_heading(
+        &inherited_heading(&scene.heading),
+        &sidecar::next_id("scene"),
+    );
+
+    let blocks =
+        document::blocks_mut(document).ok_or_else(|| "Document has no content".to_string())?;
+    if cut {
+        let content = old_blocks[block]
+            .get("content")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let (before, after) = split_inline(&content, offset);
+        let mut tail = old_blocks[block].clone();
+        tail["content"] = Value::Array(after);
+        if tail
+            .get("attrs")
+            .is_some_and(|attrs| attrs.get("nodeId").is_some())
+        {
+            tail["attrs"]["nodeId"] = Value::Null;
+        }
+        blocks[block]["content"] = Value::Array(before);
+        blocks.insert(block + 1, tail);
+    }
+    blocks.insert(insert_at, heading);
+
+    update_scene_numbers(document)?;
+    remap_anchors(
+        document,
+        &old_blocks,
+        store,
+        |old, old_offset, range_end| {
+            if old == block && cut {
+                if old_offset < offset || (range_end && old_offset == offset) {
+                    (block, old_offset)
+                } else {
+                    (block + 2, old_offset - offset)
+                }
+            } else if old < insert_at {
+                (old, old_offset)
+            } else {
+                (old + 1 + usize::from(cut), old_offset)
+            }
+        },
+    )
+}
+
+/// Merges scene `scene` (zero-based) with the one after it by removing the
+/// second heading. Breakdown tags on that heading go with it; its outline
+/// card is folded into the first scene's.
+pub fn merge_scenes(
+    document: &mut Value,
+    store: &mut DocumentSidecar,
+    scene: usize,
+) -> Result<(), String> {
+    let old_blocks = document::blocks(document).to_vec();
+    let scenes = document::block_scenes(&old_blocks);
+    let (Some(kept), Some(removed)) = (scenes.get(scene), scenes.get(scene + 1)) else {
+        return Err(format!(
+            "Scene {} has no scene after it to merge with",
+            scene + 1
+        ));
+    };
+    let heading = removed.heading_block;
+
+    let blocks =
+        document::blocks_mut(document).ok_or_else(|| "Document has no content".to_string())?;
+    blocks.remove(heading);
+    match (&kept.scene_id, &removed.scene_id) {
+        (Some(kept_id), Some(removed_id)) => {
+            outline::merge_scene_cards(&mut store.index_cards, removed_id, kept_id)
+        }
+        // The first scene takes over the second one's id, and with it its card.
+        (None, Some(removed_id)) => {
+            blocks[kept.heading_block]["attrs"]["sceneId"] = Value::String(removed_id.clone())
+        }
+        _ => {}
+    }
+
+    store
+        .breakdown_tags
+        .retain(|tag| tag.anchor.block_index != heading);
+    update_scene_numbers(document)?;
+    // Comments on the removed heading close up where it was.
+    remap_anchors(document, &old_blocks, store, |old, offset, _| {
+        match old.cmp(&heading) {
+            Ordering::Less => (old, offset),
+            Ordering::Equal => (heading, 0),
+            Ordering::Greater => (old - 1, offset),
+        }
+    })
+}
+
+fn finish(document: &Value, store: &DocumentSidecar) -> Result<Restructured, String> {
+    let mut cards = store.index_cards.clone();
+    cards.sort_by_key(|card| card.order);
+    Ok(Restructured {
+        document_json: serde_json::to_string(document)
+            .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        cards,
+    })
+}
+
+/// Moves a scene or a run of scenes; see [`move_scenes`].
+#[tauri::command]
+pub fn restructure_move_scenes(
+    app: AppHandle,
+    document_id: String,
+    document_json: String,
+    first_scene: usize,
+    last_scene: usize,
+    target_scene: usize,
+) -> Result<Restructured, String> {
+    let mut document = document::parse_json(&document_json)?;
+    sidecar::update(&app, &document_id, |store| {
+        move_scenes(&mut document, store, first_scene, last_scene, target_scene)?;
+        finish(&document, store)
+    })
+}
+
+/// Splits the scene at the cursor; see [`split_scene`].
+#[tauri::command]
+pub fn restructure_split_scene(
+    app: AppHandle,
+    document_id: String,
+    document_json: String,
+    position: usize,
+) -> Result<Restructured, String> {
+    let mut document = document::parse_json(&document_json)?;
+    sidecar::update(&app, &document_id, |store| {
+        split_scene(&mut document, store, position)?;
+        finish(&document, store)
+    })
+}
+
+/// Merges a scene with the next one; see [`merge_scenes`].
+#[tauri::command]
+pub fn restructure_merge_scenes(
+    app: AppHandle,
+    document_id: String,
+    document_json: String,
+    scene: usize,
+) -> Result<Restructured, String> {
+    let mut document = document::parse_json(&document_json)?;
+    sidecar::update(&app, &document_id, |store| {
+        merge_scenes(&mut document, store, scene)?;
+        finish(&document, store)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breakdown::BreakdownTag;
+    use crate::comments::CommentAnchor;
+    use crate::document::block;
+    use crate::sidecar::TextAnchor;
+    use serde_json::json;
+
+    fn heading(text: &str, number: &str, scene_id: &str) -> Value {
+        json!({
+            "type": "sceneHeading",
+            "attrs": { "sceneNumber": number, "sceneId": scene_id },
+            "content": [{ "type": "text", "text": text }],
+        })
+    }
+
+    fn card(id: &str, order: u32, scene_id: Option<&str>) -> IndexCard {
+        IndexCard {
+            id: id.to_string(),
+            title: id.to_string(),
+            synopsis: String::new(),
+            color: None,
+            order,
+            scene_id: scene_id.map(str::to_string),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn tag(block_index: usize, text: &str) -> BreakdownTag {
+        BreakdownTag {
+            id: format!("tag-{}", block_index),
+            category: "props".to_string(),
+            name: text.to_string(),
+            anchor: TextAnchor {
+                block_index,
+                start: 0,
+                end: text.len(),
+                text: text.to_string(),
+            },
+            note: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    /// The text a comment anchor covers in `document`.
+    fn anchored_text(document: &Value, anchor: &CommentAnchor) -> String {
+        let blocks = document::blocks(document);
+        let starts = block_starts(blocks);
+        let (block, offset) = locate(&starts, anchor.from, false).unwrap();
+        let text = document::node_text(&blocks[block]);
+        text.chars()
+            .skip(offset)
+            .take(anchor.to - anchor.from)
+            .collect()
+    }
+
+    fn script() -> (Value, DocumentSidecar) {
+        let mut document = json!({
+            "document": { "type": "doc", "content": [
+                heading("INT. ONE - DAY", "1", "s1"),
+                block("action", "A gun on the table."),
+                heading("INT. TWO - DAY", "2", "s2"),
+                block("action", "Rain."),
+                heading("INT. THREE - DAY", "3", "s3"),
+                block("action", "A knife."),
+            ]},
+        });
+        let store = DocumentSidecar {
+            breakdown_tags: vec![tag(1, "gun"), tag(5, "knife")],
+            index_cards: vec![
+                card("c1", 0, Some("s1")),
+                card("loose", 1, None),
+                card("c2", 2, Some("s2")),
+                card("c3", 3, Some("s3")),
+            ],
+            ..DocumentSidecar::default()
+        };
+
+        let blocks = document::blocks(&document).to_vec();
+        let knife_start = block_starts(&blocks)[5] + 1 + "A ".len();
+        comments::write_comments(
+            &mut document,
+            &[comments::new_comment(
+                CommentAnchor {
+                    from: knife_start,
+                    to: knife_start + "knife".len(),
+                    quote: "knife".to_string(),
+                },
+                "Ann",
+                "Sharper?",
+            )],
+        )
+        .unwrap();
+        (document, store)
+    }
+
+    #[test]
+    fn moves_scenes_with_their_numbers_cards_tags_and_comments() {
+        let (mut document, mut store) = script();
+
+        move_scenes(&mut document, &mut store, 2, 2, 0).unwrap();
+
+        let scenes = document::scenes(&document);
+        let headings = scenes
+            .iter()
+            .map(|scene| (scene.heading.as_str(), scene.scene_number.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headings,
+            [
+                ("INT. THREE - DAY", Some("1")),
+                ("INT. ONE - DAY", Some("2")),
+                ("INT. TWO - DAY", Some("3")),
+            ]
+        );
+
+        let cards = store
+            .index_cards
+            .iter()
+            .map(|card| card.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(cards, ["c3", "loose", "c1", "c2"]);
+
+        let tagged = store
+            .breakdown_tags
+            .iter()
+            .map(|tag| document::node_text(&document::blocks(&document)[tag.anchor.block_index]))
+            .collect::<Vec<_>>();
+        assert_eq!(tagged, ["A gun on the table.", "A knife."]);
+
+        let comment = &comments::read_comments(&document).unwrap()[0];
+        assert_eq!(anchored_text(&document, &comment.anchor), "knife");
+    }
+
+    #[test]
+    fn keeps_locked_numbers_and_rejects_bad_ranges() {
+        let (mut document, mut store) = script();
+        scene_numbers::lock(&mut document, true).unwrap();
+
+        move_scenes(&mut document, &mut store, 0, 1, 3).unwrap();
+        let numbers = document::scenes(&document)
+            .into_iter()
+            .map(|scene| scene.scene_number.unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, ["3", "1", "2"]);
+
+        assert!(move_scenes(&mut document, &mut store, 0, 1, 1).is_err());
+        assert!(move_scenes(&mut document, &mut store, 2, 3, 0).is_err());
+        assert!(move_scenes(&mut document, &mut store, 0, 0, 4).is_err());
+    }
+
+    #[test]
+    fn splits_a_scene_at_the_cursor() {
+        let (mut document, mut store) = script();
+        let mut spanning = tag(1, "gun on");
+        spanning.anchor.start = "A ".len();
+        spanning.anchor.end = "A gun on".len();
+        store.breakdown_tags.push(spanning);
+        let cursor = block_starts(document::blocks(&document))[1] + 1 + "A gun".len();
+
+        split_scene(&mut document, &mut store, cursor).unwrap();
+
+        let blocks = document::blocks(&document);
+        let texts = blocks.iter().map(document::node_text).collect::<Vec<_>>();
+        assert_eq!(
+            texts[..4],
+            [
+                "INT. ONE - DAY",
+                "A gun",
+                "INT. ONE - DAY",
+                " on the table."
+            ]
+        );
+        let numbers = document::scenes(&document)
+            .into_iter()
+            .map(|scene| scene.scene_number.unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, ["1", "2", "3", "4"]);
+        assert!(blocks[2]["attrs"]["sceneId"].is_string());
+
+        let anchors = store
+            .breakdown_tags
+            .iter()
+            .map(|tag| (tag.anchor.block_index, tag.anchor.start, tag.anchor.end))
+            .collect::<Vec<_>>();
+        assert_eq!(anchors, [(1, 0, 3), (7, 0, 5), (1, 2, 5)]);
+        let comment = &comments::read_comments(&document).unwrap()[0];
+        assert_eq!(anchored_text(&document, &comment.anchor), "knife");
+
+        let (mut locked, mut store) = script();
+        scene_numbers::lock(&mut locked, true).unwrap();
+        let cursor = block_starts(document::blocks(&locked))[3] + 1;
+        split_scene(&mut locked, &mut store, cursor).unwrap();
+        let numbers = document::scenes(&locked)
+            .into_iter()
+            .map(|scene| scene.scene_number.unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, ["1", "2", "2A", "3"]);
+        assert_eq!(document::scenes(&locked)[2].heading, "INT. TWO - DAY");
+        assert!(split_scene(&mut locked, &mut store, 0).is_err());
+    }
+
+    #[test]
+    fn merges_a_scene_into_the_one_before() {
+        let (mut document, mut store) = script();
+        store.index_cards[0].synopsis = "Tom finds a gun.".to_string();
+        store.index_cards[2].synopsis = "It rains.".to_string();
+
+        merge_scenes(&mut document, &mut store, 0).unwrap();
+
+        let texts = document::blocks(&document)
+            .iter()
+            .map(document::node_text)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            [
+                "INT. ONE - DAY",
+                "A gun on the table.",
+                "Rain.",
+                "INT. THREE - DAY",
+                "A knife."
+            ]
+        );
+        let numbers = document::scenes(&document)
+            .into_iter()
+            .map(|scene| scene.scene_number.unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, ["1", "2"]);
+
+        let cards = store
+            .index_cards
+            .iter()
+            .map(|card| (card.id.as_str(), card.order))
+            .collect::<Vec<_>>();
+        assert_eq!(cards, [("c1", 0), ("loose", 1), ("c3", 2)]);
+        assert_eq!(
+            store.index_cards[0].synopsis,
+            "Tom finds a gun.\n\nIt rains."
+        );
+        assert_eq!(store.breakdown_tags[1].anchor.block_index, 4);
+        let comment = &comments::read_comments(&document).unwrap()[0];
+        assert_eq!(anchored_text(&document, &comment.anchor), "knife");
+
+        assert!(merge_scenes(&mut document, &mut store, 1).is_err());
+    }
+}
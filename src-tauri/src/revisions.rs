@@ -0,0 +1,334 @@
+use crate::compare::{self, ChangeKind};
+use crate::{document, reports};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Industry-standard revision page colors, in the order they are issued. The
+/// original draft is White, so the first revision is Blue.
+const REVISION_COLORS: [&str; 9] = [
+    "White",
+    "Blue",
+    "Pink",
+    "Yellow",
+    "Green",
+    "Goldenrod",
+    "Buff",
+    "Salmon",
+    "Cherry",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision {
+    pub id: String,
+    /// Display name such as "Blue 3/12".
+    pub name: String,
+    pub color: String,
+    pub started_at: String,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+}
+
+/// Stored on the document under `revisions`; `settings.revision` mirrors the
+/// active revision's name for the header and title page.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionState {
+    #[serde(default)]
+    pub active_id: Option<String>,
+    #[serde(default)]
+    pub history: Vec<Revision>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionSummaryEntry {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub started_at: String,
+    pub active: bool,
+    pub changed_blocks: usize,
+    /// Numbers of the scenes that contain at least one revised block.
+    pub scenes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionSummary {
+    pub revisions: Vec<RevisionSummaryEntry>,
+}
+
+fn read_state(document: &Value) -> Result<RevisionState, String> {
+    match document.get("revisions") {
+        Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+            .map_err(|error| format!("Failed to parse revisions: {}", error)),
+        _ => Ok(RevisionState::default()),
+    }
+}
+
+fn write_state(document: &mut Value, state: &RevisionState) -> Result<(), String> {
+    let Some(object) = document.as_object_mut() else {
+        return Err("Revisions require a full document".to_string());
+    };
+
+    let active_name = state
+        .active_id
+        .as_ref()
+        .and_then(|id| state.history.iter().find(|revision| &revision.id == id))
+        .map(|revision| Value::String(revision.name.clone()))
+        .unwrap_or(Value::Null);
+    let value = serde_json::to_value(state)
+        .map_err(|error| format!("Failed to serialize revisions: {}", error))?;
+    object.insert("revisions".to_string(), value);
+
+    if let Some(settings) = object.get_mut("settings").and_then(Value::as_object_mut) {
+        settings.insert("revision".to_string(), active_name);
+    }
+
+    Ok(())
+}
+
+fn block_revision(block: &Value) -> Option<&str> {
+    document::node_attr_str(block, "revision").filter(|revision| !revision.is_empty())
+}
+
+fn to_json(document: &Value) -> Result<String, String> {
+    serde_json::to_string(document)
+        .map_err(|error| format!("Failed to serialize document: {}", error))
+}
+
+pub fn start(document: &mut Value, name: &str, color: Option<String>) -> Result<Revision, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Revision name is required".to_string());
+    }
+
+    let mut state = read_state(document)?;
+    let now = Utc::now().to_rfc3339();
+    if let Some(active) = state
+        .active_id
+        .as_ref()
+        .and_then(|id| state.history.iter_mut().find(|revision| &revision.id == id))
+    {
+        active.ended_at = Some(now.clone());
+    }
+
+    let revision = Revision {
+        id: format!("rev-{}", state.history.len() + 1),
+        name: name.to_string(),
+        color: color
+            .filter(|color| !color.trim().is_empty())
+            .unwrap_or_else(|| {
+                REVISION_COLORS[(state.history.len() + 1) % REVISION_COLORS.len()].to_string()
+            }),
+        started_at: now,
+        ended_at: None,
+    };
+    state.active_id = Some(revision.id.clone());
+    state.history.push(revision.clone());
+    write_state(document, &state)?;
+
+    Ok(revision)
+}
+
+/// Tags every block added or edited since `baseline` with the active
+/// revision. Blocks that already carry an older revision are re-tagged.
+pub fn record_changes(document: &mut Value, baseline: &Value) -> Result<Vec<usize>, String> {
+    let state = read_state(document)?;
+    let active_id = state
+        .active_id
+        .ok_or_else(|| "No revision is active".to_string())?;
+
+    let changeset = compare::compare(baseline, document);
+    let Some(blocks) = document::blocks_mut(document) else {
+        return Ok(Vec::new());
+    };
+
+    let mut marked = Vec::new();
+    for change in changeset.elements {
+        if change.kind == ChangeKind::Removed {
+            continue;
+        }
+        let Some(block) = change.new_index.and_then(|index| blocks.get_mut(index)) else {
+            continue;
+        };
+
+        if !block.get("attrs").map(Value::is_object).unwrap_or(false) {
+            block["attrs"] = Value::Object(serde_json::Map::new());
+        }
+        block["attrs"]["revision"] = Value::String(active_id.clone());
+        marked.extend(change.new_index);
+    }
+
+    Ok(marked)
+}
+
+pub fn summarize(document: &Value) -> Result<RevisionSummary, String> {
+    let state = read_state(document)?;
+    let blocks = document::blocks(document);
+    let scenes = document::scenes(document);
+    let scene_by_block = document::scene_index_by_block(&scenes, blocks.len());
+
+    let revisions = state
+        .history
+        .iter()
+        .map(|revision| {
+            let mut changed_blocks = 0;
+            let mut scene_numbers = Vec::new();
+            for (index, block) in blocks.iter().enumerate() {
+                if block_revision(block) != Some(revision.id.as_str()) {
+                    continue;
+                }
+                changed_blocks += 1;
+                if let Some(scene) = scene_by_block[index].map(|scene| &scenes[scene]) {
+                    let number = scene
+                        .scene_number
+                        .clone()
+                        .unwrap_or_else(|| (scene.index + 1).to_string());
+                    if !scene_numbers.contains(&number) {
+                        scene_numbers.push(number);
+                    }
+                }
+            }
+
+            RevisionSummaryEntry {
+                id: revision.id.clone(),
+                name: revision.name.clone(),
+                color: revision.color.clone(),
+                started_at: revision.started_at.clone(),
+                active: state.active_id.as_ref() == Some(&revision.id),
+                changed_blocks,
+                scenes: scene_numbers,
+            }
+        })
+        .collect();
+
+    Ok(RevisionSummary { revisions })
+}
+
+fn to_report(summary: &RevisionSummary, title: &str) -> reports::Report {
+    reports::Report {
+        title: format!("{} - Revision Summary", title),
+        subtitle: None,
+        sections: vec![reports::ReportSection {
+            heading: None,
+            columns: vec![
+                reports::ReportColumn::new("Revision", 18),
+                reports::ReportColumn::new("Color", 10),
+                reports::ReportColumn::new("Started", 10),
+                reports::ReportColumn::new("Blocks", 6),
+                reports::ReportColumn::new("Scenes", 40),
+            ],
+            rows: summary
+                .revisions
+                .iter()
+                .map(|revision| {
+                    vec![
+                        revision.name.clone(),
+                        revision.color.clone(),
+                        revision.started_at.chars().take(10).collect(),
+                        revision.changed_blocks.to_string(),
+                        revision.scenes.join(", "),
+                    ]
+                })
+                .collect(),
+        }],
+    }
+}
+
+/// Enters a named revision; returns the updated document JSON.
+#[tauri::command]
+pub fn revision_start(
+    document_json: String,
+    name: String,
+    color: Option<String>,
+) -> Result<String, String> {
+    let mut document = document::parse_json(&document_json)?;
+    start(&mut document, &name, color)?;
+    to_json(&document)
+}
+
+/// Leaves revision mode. Existing marks stay on the blocks.
+#[tauri::command]
+pub fn revision_end(document_json: String) -> Result<String, String> {
+    let mut document = document::parse_json(&document_json)?;
+    let mut state = read_state(&document)?;
+    let now = Utc::now().to_rfc3339();
+    if let Some(active) = state
+        .active_id
+        .take()
+        .and_then(|id| state.history.iter_mut().find(|revision| revision.id == id))
+    {
+        active.ended_at = Some(now);
+    }
+    write_state(&mut document, &state)?;
+    to_json(&document)
+}
+
+/// Marks blocks changed since `baseline_json` (typically the last saved
+/// draft) with the active revision; returns the updated document JSON.
+#[tauri::command]
+pub fn revision_record_changes(
+    document_json: String,
+    baseline_json: String,
+) -> Result<String, String> {
+    let mut document = document::parse_json(&document_json)?;
+    let baseline = document::parse_json(&baseline_json)?;
+    record_changes(&mut document, &baseline)?;
+    to_json(&document)
+}
+
+#[tauri::command]
+pub fn revision_summary(
+    document_json: String,
+    document_title: Option<String>,
+    format: Option<String>,
+    output_path: Option<String>,
+) -> Result<RevisionSummary, String> {
+    let document = document::parse_json(&document_json)?;
+    let summary = summarize(&document)?;
+
+    if let (Some(format), Some(output_path)) = (format, output_path) {
+        let format = reports::ReportFormat::parse(&format)?;
+        let title = document_title.unwrap_or_else(|| "Untitled".to_string());
+        reports::write_report(&to_report(&summary, &title), format, &output_path)?;
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    #[test]
+    fn records_changed_blocks_under_the_active_revision() {
+        let baseline = json!({
+            "settings": { "revision": null },
+            "document": { "type": "doc", "content": [
+                block("sceneHeading", "INT. HALL - DAY"),
+                block("action", "Quiet."),
+            ]}
+        });
+        let mut document = baseline.clone();
+        document["document"]["content"][1] = block("action", "Loud.");
+
+        let revision = start(&mut document, "Blue 3/12", None).unwrap();
+        assert_eq!(revision.color, "Blue");
+        assert_eq!(document["settings"]["revision"], "Blue 3/12");
+
+        assert_eq!(record_changes(&mut document, &baseline).unwrap(), vec![1]);
+        assert_eq!(
+            document["document"]["content"][1]["attrs"]["revision"],
+            "rev-1"
+        );
+
+        let summary = summarize(&document).unwrap();
+        assert_eq!(summary.revisions[0].changed_blocks, 1);
+        assert_eq!(summary.revisions[0].scenes, vec!["1".to_string()]);
+    }
+}
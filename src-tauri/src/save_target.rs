@@ -0,0 +1,139 @@
+//! Save As overwrite checks. The native dialog only says a file exists; this
+//! says whose screenplay it is, so replacing a different draft gets a proper
+//! warning while re-saving over the document's own file does not.
+
+use crate::document;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveTargetConflict {
+    pub path: String,
+    /// Title page title of the file that would be replaced.
+    pub title: Option<String>,
+    /// When that file was last written, as RFC 3339.
+    pub modified_at: Option<String>,
+    /// False when the file isn't a Grainery document at all.
+    pub is_document: bool,
+}
+
+fn title_of(value: &Value) -> Option<String> {
+    value
+        .get("titlePage")
+        .and_then(|page| page.get("title"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_string)
+}
+
+/// `None` when `path` is free or already holds the document with
+/// `document_id`.
+pub fn conflict(
+    path: &Path,
+    document_id: Option<&str>,
+) -> Result<Option<SaveTargetConflict>, String> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(format!(
+                "Failed to read {}: {}",
+                path.to_string_lossy(),
+                error
+            ))
+        }
+    };
+
+    let existing = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| document::parse_json(&content).ok())
+        .filter(|value| value.get("document").is_some_and(Value::is_object));
+    let existing_id = existing
+        .as_ref()
+        .and_then(|value| value.get("meta"))
+        .and_then(|meta| meta.get("id"))
+        .and_then(Value::as_str);
+    if existing_id.is_some() && existing_id == document_id {
+        return Ok(None);
+    }
+
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .map(|modified| DateTime::<Utc>::from(modified).to_rfc3339())
+        .or_else(|| {
+            existing
+                .as_ref()
+                .and_then(|value| value.get("meta"))
+                .and_then(|meta| meta.get("modifiedAt"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        });
+
+    Ok(Some(SaveTargetConflict {
+        path: path.to_string_lossy().to_string(),
+        title: existing.as_ref().and_then(title_of),
+        modified_at,
+        is_document: existing.is_some(),
+    }))
+}
+
+/// Checks a Save As target picked for the document with `document_id`.
+#[tauri::command]
+pub fn check_save_target(
+    path: String,
+    document_id: Option<String>,
+) -> Result<Option<SaveTargetConflict>, String> {
+    // Picker URLs can't be inspected; the system picker confirms replacing.
+    #[cfg(mobile)]
+    if crate::mobile_files::is_document_uri(&path) {
+        return Ok(None);
+    }
+
+    conflict(Path::new(&path), document_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flags_other_screenplays_but_not_the_same_document() {
+        let dir = std::env::temp_dir().join(format!("grainery-save-target-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let draft = dir.join("draft.gwx");
+        fs::write(
+            &draft,
+            json!({
+                "meta": { "id": "doc-1", "modifiedAt": "2026-01-01T00:00:00Z" },
+                "titlePage": { "title": " Nightfall " },
+                "document": { "type": "doc", "content": [] },
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let notes = dir.join("notes.gwx");
+        fs::write(&notes, "just some notes").unwrap();
+
+        let same = conflict(&draft, Some("doc-1"));
+        let other = conflict(&draft, Some("doc-2"));
+        let foreign = conflict(&notes, Some("doc-1"));
+        let free = conflict(&dir.join("new.gwx"), None);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(same.unwrap(), None);
+        let other = other.unwrap().unwrap();
+        assert_eq!(other.title.as_deref(), Some("Nightfall"));
+        assert!(other.is_document && other.modified_at.is_some());
+        let foreign = foreign.unwrap().unwrap();
+        assert!(!foreign.is_document && foreign.title.is_none());
+        assert_eq!(free.unwrap(), None);
+    }
+}
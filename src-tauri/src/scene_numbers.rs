@@ -0,0 +1,259 @@
+use crate::document;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Document setting that freezes scene numbers for production drafts.
+const LOCKED_SETTING: &str = "sceneNumbersLocked";
+
+pub fn is_locked(document: &Value) -> bool {
+    document
+        .get("settings")
+        .and_then(|settings| settings.get(LOCKED_SETTING))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn set_locked(document: &mut Value, locked: bool) -> Result<(), String> {
+    let Some(object) = document.as_object_mut() else {
+        return Err("Scene numbers require a full document".to_string());
+    };
+    let settings = object
+        .entry("settings")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !settings.is_object() {
+        *settings = Value::Object(Map::new());
+    }
+    settings[LOCKED_SETTING] = Value::Bool(locked);
+    Ok(())
+}
+
+pub fn heading_number(block: &Value) -> Option<String> {
+    match block.get("attrs").and_then(|attrs| attrs.get("sceneNumber")) {
+        Some(Value::String(number)) => Some(number.trim().to_string()),
+        Some(Value::Number(number)) => Some(number.to_string()),
+        _ => None,
+    }
+    .filter(|number| !number.is_empty())
+}
+
+fn set_heading_number(block: &mut Value, number: Option<String>) {
+    if !block.get("attrs").map(Value::is_object).unwrap_or(false) {
+        block["attrs"] = Value::Object(Map::new());
+    }
+    block["attrs"]["sceneNumber"] = number.map(Value::String).unwrap_or(Value::Null);
+}
+
+/// `A`, `B`, ... `Z`, `AA`, `AB`, ... for a zero-based index.
+fn letters(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+/// The number for a scene inserted after `previous`: `5` becomes `5A`, `5A`
+/// becomes `5B`, and `5Z` becomes `5ZA`. When that is already used by a
+/// later scene, letters are appended instead (`5` then `5AA`).
+fn insert_after(previous: &str, taken: &HashSet<String>) -> String {
+    let next = match previous.chars().last() {
+        Some(last) if last.is_ascii_uppercase() && last != 'Z' => {
+            let mut next = previous[..previous.len() - 1].to_string();
+            next.push((last as u8 + 1) as char);
+            next
+        }
+        _ => format!("{}A", previous),
+    };
+    if !taken.contains(&next) {
+        return next;
+    }
+    let mut candidate = format!("{}A", previous);
+    while taken.contains(&candidate) {
+        candidate.push('A');
+    }
+    candidate
+}
+
+/// Gives every scene heading without a number one, leaving existing numbers
+/// alone. A script with no numbers at all is numbered from 1; otherwise new
+/// scenes get A/B numbers from the scene before them, and scenes ahead of
+/// the first numbered one are lettered in front of it (`A1`, `B1`).
+/// Returns the block indices that were numbered.
+pub fn assign(document: &mut Value) -> Result<Vec<usize>, String> {
+    let Some(blocks) = document::blocks_mut(document) else {
+        return Ok(Vec::new());
+    };
+    let headings = blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| document::node_type(block) == "sceneHeading")
+        .map(|(index, block)| (index, heading_number(block)))
+        .collect::<Vec<_>>();
+
+    let mut taken = headings
+        .iter()
+        .filter_map(|(_, number)| number.clone())
+        .collect::<HashSet<_>>();
+    if taken.is_empty() {
+        for (position, (index, _)) in headings.iter().enumerate() {
+            set_heading_number(&mut blocks[*index], Some((position + 1).to_string()));
+        }
+        return Ok(headings.iter().map(|(index, _)| *index).collect());
+    }
+
+    let first_numbered = headings
+        .iter()
+        .position(|(_, number)| number.is_some())
+        .unwrap_or_default();
+    let mut previous: Option<String> = None;
+    let mut numbered = Vec::new();
+    for (position, (index, number)) in headings.iter().enumerate() {
+        if let Some(number) = number {
+            previous = Some(number.clone());
+            continue;
+        }
+        let next = match &previous {
+            Some(previous) => insert_after(previous, &taken),
+            None => {
+                let first = headings[first_numbered].1.clone().unwrap_or_default();
+                format!("{}{}", letters(position), first)
+            }
+        };
+        taken.insert(next.clone());
+        set_heading_number(&mut blocks[*index], Some(next.clone()));
+        previous = Some(next);
+        numbered.push(*index);
+    }
+
+    Ok(numbered)
+}
+
+/// Numbers every scene heading 1, 2, 3... Locked scripts keep their numbers.
+pub fn renumber(document: &mut Value) -> Result<(), String> {
+    if is_locked(document) {
+        return Err("Scene numbers are locked. Unlock them to renumber.".to_string());
+    }
+    let Some(blocks) = document::blocks_mut(document) else {
+        return Ok(());
+    };
+    let mut count = 0;
+    for block in blocks.iter_mut() {
+        if document::node_type(block) == "sceneHeading" {
+            count += 1;
+            set_heading_number(block, Some(count.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Locking first numbers any unnumbered scenes so every heading keeps its
+/// number from then on.
+pub fn lock(document: &mut Value, locked: bool) -> Result<(), String> {
+    if locked {
+        assign(document)?;
+    }
+    set_locked(document, locked)
+}
+
+fn to_json(document: &Value) -> Result<String, String> {
+    serde_json::to_string(document)
+        .map_err(|error| format!("Failed to serialize document: {}", error))
+}
+
+/// Numbers scene headings that don't have a number yet, using A/B numbers
+/// between existing ones.
+#[tauri::command]
+pub fn scene_numbers_assign(document_json: String) -> Result<String, String> {
+    let mut document = document::parse_json(&document_json)?;
+    assign(&mut document)?;
+    to_json(&document)
+}
+
+#[tauri::command]
+pub fn scene_numbers_renumber(document_json: String) -> Result<String, String> {
+    let mut document = document::parse_json(&document_json)?;
+    renumber(&mut document)?;
+    to_json(&document)
+}
+
+#[tauri::command]
+pub fn scene_numbers_lock(document_json: String, locked: bool) -> Result<String, String> {
+    let mut document = document::parse_json(&document_json)?;
+    lock(&mut document, locked)?;
+    to_json(&document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn heading(number: Option<&str>) -> Value {
+        json!({
+            "type": "sceneHeading",
+            "attrs": { "sceneNumber": number },
+            "content": [{ "type": "text", "text": "INT. ROOM - DAY" }]
+        })
+    }
+
+    fn numbers(document: &Value) -> Vec<Option<String>> {
+        document::blocks(document)
+            .iter()
+            .filter(|block| document::node_type(block) == "sceneHeading")
+            .map(heading_number)
+            .collect()
+    }
+
+    fn script(headings: Vec<Value>) -> Value {
+        json!({
+            "settings": { "showSceneNumbers": true },
+            "document": { "type": "doc", "content": headings }
+        })
+    }
+
+    #[test]
+    fn inserts_a_b_numbers_around_locked_scenes() {
+        let mut document = script(vec![
+            heading(None),
+            heading(Some("1")),
+            heading(None),
+            heading(None),
+            heading(Some("2")),
+            heading(Some("3")),
+            heading(None),
+            heading(Some("3A")),
+        ]);
+        lock(&mut document, true).unwrap();
+
+        let expected = ["A1", "1", "1A", "1B", "2", "3", "3AA", "3A"];
+        assert_eq!(
+            numbers(&document),
+            expected.map(|number| Some(number.to_string()))
+        );
+        assert!(is_locked(&document));
+        assert!(renumber(&mut document).unwrap_err().contains("locked"));
+    }
+
+    #[test]
+    fn renumbers_unlocked_scripts() {
+        let mut document = script(vec![heading(Some("4B")), heading(None), heading(Some("9"))]);
+        renumber(&mut document).unwrap();
+        assert_eq!(
+            numbers(&document),
+            ["1", "2", "3"].map(|number| Some(number.to_string()))
+        );
+
+        let mut fresh = script(vec![heading(None), heading(None)]);
+        assert_eq!(assign(&mut fresh).unwrap(), [0, 1]);
+        assert_eq!(
+            numbers(&fresh),
+            ["1", "2"].map(|number| Some(number.to_string()))
+        );
+        assert_eq!(letters(0), "A");
+        assert_eq!(letters(27), "AB");
+    }
+}
@@ -0,0 +1,201 @@
+use crate::app_data;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+const SCRATCHPAD_DIR: &str = "scratchpads";
+const CAPTURE_WINDOW: &str = "capture";
+/// Scratchpad used when nothing has been opened in the main window yet.
+const INBOX_ID: &str = "inbox";
+const MAX_CAPTURE_CHARS: usize = 20_000;
+
+/// The document quick captures are filed under, keyed by `meta.id` like the
+/// document sidecars so the scratchpad follows the script across renames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScratchpadProject {
+    pub document_id: String,
+    pub title: String,
+}
+
+#[derive(Default)]
+pub struct ActiveScratchpad {
+    project: Mutex<Option<ScratchpadProject>>,
+}
+
+impl ActiveScratchpad {
+    fn current(&self) -> ScratchpadProject {
+        self.project
+            .lock()
+            .ok()
+            .and_then(|project| project.clone())
+            .unwrap_or_else(|| ScratchpadProject {
+                document_id: INBOX_ID.to_string(),
+                title: "Inbox".to_string(),
+            })
+    }
+}
+
+fn validate_document_id(document_id: &str) -> Result<(), String> {
+    if document_id.is_empty()
+        || !document_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid document id '{}'", document_id));
+    }
+
+    Ok(())
+}
+
+fn scratchpad_path(app: &AppHandle, document_id: &str) -> Result<PathBuf, String> {
+    validate_document_id(document_id)?;
+    Ok(app_data::app_data_dir(app, SCRATCHPAD_DIR)?.join(format!("{}.md", document_id)))
+}
+
+/// Formats one capture as a timestamped Markdown entry.
+fn format_entry(text: &str, timestamp: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Nothing to capture".to_string());
+    }
+    if text.chars().count() > MAX_CAPTURE_CHARS {
+        return Err(format!(
+            "Captures are limited to {} characters",
+            MAX_CAPTURE_CHARS
+        ));
+    }
+
+    Ok(format!(
+        "## {}\n\n{}\n\n",
+        timestamp,
+        text.replace("\r\n", "\n")
+    ))
+}
+
+/// Shows the quick-capture window, creating it on first use.
+pub fn show_capture_window(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(CAPTURE_WINDOW) {
+        #[cfg(desktop)]
+        let _ = window.center();
+        window
+            .show()
+            .and_then(|_| window.set_focus())
+            .map_err(|error| format!("Failed to show quick capture: {}", error))?;
+        let _ = window.emit("quick-capture-opened", ());
+        return Ok(());
+    }
+
+    let builder = WebviewWindowBuilder::new(app, CAPTURE_WINDOW, WebviewUrl::default())
+        .title("Quick Capture")
+        .inner_size(460.0, 180.0)
+        .resizable(false);
+    // A floating panel only makes sense on desktop; elsewhere it is a plain window
+    #[cfg(desktop)]
+    let builder = builder
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true);
+    builder
+        .build()
+        .map_err(|error| format!("Failed to open quick capture: {}", error))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn quick_capture_open(app: AppHandle) -> Result<(), String> {
+    show_capture_window(&app)
+}
+
+/// Hides rather than closes so the next capture opens instantly.
+#[tauri::command]
+pub fn quick_capture_close(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(CAPTURE_WINDOW) {
+        window
+            .hide()
+            .map_err(|error| format!("Failed to hide quick capture: {}", error))?;
+    }
+    Ok(())
+}
+
+/// Called by the main window whenever the open document changes.
+#[tauri::command]
+pub fn scratchpad_set_project(
+    active: State<'_, ActiveScratchpad>,
+    project: Option<ScratchpadProject>,
+) -> Result<(), String> {
+    if let Some(project) = &project {
+        validate_document_id(&project.document_id)?;
+    }
+
+    let mut current = active
+        .project
+        .lock()
+        .map_err(|_| "Scratchpad state is unavailable".to_string())?;
+    *current = project;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn scratchpad_get_project(active: State<'_, ActiveScratchpad>) -> ScratchpadProject {
+    active.current()
+}
+
+/// Appends `text` to the active document's scratchpad and returns the
+/// project it was filed under.
+#[tauri::command]
+pub fn scratchpad_append(
+    app: AppHandle,
+    active: State<'_, ActiveScratchpad>,
+    text: String,
+) -> Result<ScratchpadProject, String> {
+    let project = active.current();
+    let entry = format_entry(&text, &Local::now().format("%Y-%m-%d %H:%M").to_string())?;
+    let path = scratchpad_path(&app, &project.document_id)?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| format!("Failed to open scratchpad: {}", error))?;
+    file.write_all(entry.as_bytes())
+        .map_err(|error| format!("Failed to write scratchpad: {}", error))?;
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("scratchpad-updated", &project.document_id);
+    }
+
+    Ok(project)
+}
+
+#[tauri::command]
+pub fn scratchpad_read(app: AppHandle, document_id: String) -> Result<String, String> {
+    let path = scratchpad_path(&app, &document_id)?;
+    if !path.exists() {
+        return Ok(String::new());
+    }
+
+    fs::read_to_string(&path).map_err(|error| format!("Failed to read scratchpad: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_capture_entries() {
+        assert_eq!(
+            format_entry("  What if the dog talks?\r\nKeep it.  ", "2026-10-15 09:30").unwrap(),
+            "## 2026-10-15 09:30\n\nWhat if the dog talks?\nKeep it.\n\n"
+        );
+        assert!(format_entry("   \n", "2026-10-15 09:30").is_err());
+        assert!(validate_document_id("../notes").is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use crate::app_data::{app_data_root, save_json};
+#[cfg(desktop)]
+use crate::shortcuts;
+use crate::title_page_presets::{self, TitlePagePreset};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, State};
+
+const SETTINGS_FILE: &str = "settings.json";
+/// Bump together with a new entry in `MIGRATIONS`.
+const SETTINGS_VERSION: u32 = 1;
+const AUTOSAVE_INTERVALS_MS: [u64; 4] = [15_000, 30_000, 60_000, 300_000];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Pdf,
+    Fountain,
+    Fdx,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    pub interval_ms: u64,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportDefaults {
+    pub format: ExportFormat,
+    pub include_title_page: bool,
+}
+
+impl Default for ExportDefaults {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Pdf,
+            include_title_page: true,
+        }
+    }
+}
+
+/// A user shortcut override. `accelerator: None` unbinds the command;
+/// `global` registers it with the OS so it fires while the app is in the
+/// background. Only desktop builds apply them; see `shortcuts`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutOverride {
+    pub accelerator: Option<String>,
+    #[serde(default)]
+    pub global: bool,
+}
+
+/// App-wide preferences shared by every window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    /// A theme name, or `system` to follow the OS appearance.
+    pub theme: String,
+    pub autosave: AutosaveSettings,
+    pub export: ExportDefaults,
+    pub backup_directory: Option<String>,
+    /// Registry URLs offered when browsing for plugins.
+    pub plugin_registry_urls: Vec<String>,
+    /// User shortcut overrides by command id; see `shortcuts`.
+    pub shortcuts: BTreeMap<String, ShortcutOverride>,
+    /// Reusable title page blocks; see `title_page_presets`.
+    pub title_page_presets: Vec<TitlePagePreset>,
+    /// Preset applied to new documents.
+    pub default_title_page_preset: Option<String>,
+    /// Smart quotes, em-dashes and ellipses applied to documents as they are
+    /// saved and exported; see `typography`.
+    pub smart_typography: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            theme: "grainery".to_string(),
+            autosave: AutosaveSettings::default(),
+            export: ExportDefaults::default(),
+            backup_directory: None,
+            plugin_registry_urls: Vec::new(),
+            shortcuts: BTreeMap::new(),
+            title_page_presets: Vec::new(),
+            default_title_page_preset: None,
+            smart_typography: false,
+        }
+    }
+}
+
+/// Version 0 is the layout the webview kept in localStorage before settings
+/// moved to the backend: `{ theme, autoSavePreferences: { enabled, intervalMs } }`.
+fn migrate_v0(settings: &mut Map<String, Value>) {
+    if let Some(autosave) = settings.remove("autoSavePreferences") {
+        settings.insert("autosave".to_string(), autosave);
+    }
+}
+
+/// Migration `n` upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); SETTINGS_VERSION as usize] = [migrate_v0];
+
+/// Upgrades a stored settings document to the current schema. Documents
+/// without a `version` are treated as version 0.
+fn migrate_value(value: Value) -> Result<Map<String, Value>, String> {
+    let Value::Object(mut settings) = value else {
+        return Err("Settings must be a JSON object".to_string());
+    };
+
+    let version = settings.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+    if version > SETTINGS_VERSION as usize {
+        return Err(format!(
+            "Settings version {} is newer than this version of Grainery supports",
+            version
+        ));
+    }
+    for migration in &MIGRATIONS[version..] {
+        migration(&mut settings);
+    }
+    settings.insert("version".to_string(), json!(SETTINGS_VERSION));
+    Ok(settings)
+}
+
+fn migrate(value: Value) -> Result<Settings, String> {
+    serde_json::from_value(Value::Object(migrate_value(value)?))
+        .map_err(|error| format!("Failed to parse settings: {}", error))
+}
+
+fn validate(settings: &Settings) -> Result<(), String> {
+    if settings.theme.is_empty()
+        || !settings
+            .theme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!("Invalid theme '{}'", settings.theme));
+    }
+    if !AUTOSAVE_INTERVALS_MS.contains(&settings.autosave.interval_ms) {
+        return Err(format!(
+            "Unsupported autosave interval {}ms",
+            settings.autosave.interval_ms
+        ));
+    }
+    if let Some(directory) = &settings.backup_directory {
+        if !std::path::Path::new(directory).is_absolute() {
+            return Err("Backup directory must be an absolute path".to_string());
+        }
+    }
+    #[cfg(desktop)]
+    for binding in settings.shortcuts.values() {
+        if let Some(accelerator) = &binding.accelerator {
+            shortcuts::validate_accelerator(accelerator)?;
+        }
+    }
+    title_page_presets::validate(
+        &settings.title_page_presets,
+        settings.default_title_page_preset.as_deref(),
+    )?;
+    for url in &settings.plugin_registry_urls {
+        let valid =
+            tauri::Url::parse(url).is_ok_and(|parsed| matches!(parsed.scheme(), "https" | "http"));
+        if !valid {
+            return Err(format!("Invalid plugin registry URL '{}'", url));
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies a JSON merge patch (RFC 7386): objects merge recursively and
+/// `null` clears a field back to its default.
+fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            merge_patch(target.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Settings plus the channels subscribed through `settings_subscribe`.
+pub struct SettingsStore {
+    settings: Mutex<Settings>,
+    subscribers: Mutex<HashMap<u32, Channel<Settings>>>,
+    next_subscriber: AtomicU32,
+    /// False until settings are saved, so the legacy import runs only once.
+    persisted: AtomicBool,
+}
+
+impl SettingsStore {
+    /// Loads and migrates `settings.json`. Unreadable settings fall back to
+    /// the defaults without overwriting the file.
+    pub fn load(app: &AppHandle) -> Self {
+        let stored = app_data_root(app).ok().and_then(|root| {
+            let content = fs::read_to_string(root.join(SETTINGS_FILE)).ok()?;
+            Some(
+                serde_json::from_str::<Value>(&content)
+                    .map_err(|error| format!("Failed to parse settings: {}", error))
+                    .and_then(migrate),
+            )
+        });
+
+        let persisted = stored.is_some();
+        let mut settings = match stored {
+            Some(Ok(settings)) => settings,
+            Some(Err(error)) => {
+                tracing::warn!("{}", error);
+                Settings::default()
+            }
+            None => Settings::default(),
+        };
+
+        // Shortcut overrides used to live in their own file.
+        #[cfg(desktop)]
+        if let Some(overrides) = shortcuts::legacy_overrides(app) {
+            for (command, binding) in overrides {
+                settings.shortcuts.entry(command).or_insert(binding);
+            }
+            match save_json(app, SETTINGS_FILE, &settings) {
+                Ok(()) => shortcuts::remove_legacy_overrides(app),
+                Err(error) => tracing::warn!("{}", error),
+            }
+        }
+
+        Self {
+            settings: Mutex::new(settings),
+            subscribers: Mutex::new(HashMap::new()),
+            next_subscriber: AtomicU32::new(1),
+            persisted: AtomicBool::new(persisted),
+        }
+    }
+
+    /// Applies `change` to the current settings and saves them, notifying
+    /// every window like `settings_set`.
+    pub fn update(
+        &self,
+        app: &AppHandle,
+        change: impl FnOnce(&mut Settings),
+    ) -> Result<Settings, String> {
+        let mut next = self.current()?;
+        change(&mut next);
+        self.replace(app, next)
+    }
+
+    pub fn current(&self) -> Result<Settings, String> {
+        self.settings
+            .lock()
+            .map(|settings| settings.clone())
+            .map_err(|_| "Settings are unavailable".to_string())
+    }
+
+    fn replace(&self, app: &AppHandle, next: Settings) -> Result<Settings, String> {
+        validate(&next)?;
+        {
+            let mut settings = self
+                .settings
+                .lock()
+                .map_err(|_| "Settings are unavailable".to_string())?;
+            save_json(app, SETTINGS_FILE, &next)?;
+            *settings = next.clone();
+        }
+        self.persisted.store(true, Ordering::Relaxed);
+
+        let _ = app.emit("settings-changed", &next);
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            // Channels of closed windows fail to send and are dropped.
+            subscribers.retain(|_, channel| channel.send(next.clone()).is_ok());
+        }
+
+        Ok(next)
+    }
+}
+
+#[tauri::command]
+pub fn settings_get(store: State<'_, SettingsStore>) -> Result<Settings, String> {
+    store.current()
+}
+
+/// Merges `patch` into the current settings, validates, saves and notifies
+/// every window.
+#[tauri::command]
+pub fn settings_set(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    patch: Value,
+) -> Result<Settings, String> {
+    let mut value = serde_json::to_value(store.current()?)
+        .map_err(|error| format!("Failed to serialize settings: {}", error))?;
+    merge_patch(&mut value, patch);
+    let mut next = serde_json::from_value::<Settings>(value)
+        .map_err(|error| format!("Invalid settings: {}", error))?;
+    next.version = SETTINGS_VERSION;
+
+    store.replace(&app, next)
+}
+
+/// Sends the settings to `on_change` after every change. Returns an id for
+/// `settings_unsubscribe`.
+#[tauri::command]
+pub fn settings_subscribe(
+    store: State<'_, SettingsStore>,
+    on_change: Channel<Settings>,
+) -> Result<u32, String> {
+    let id = store.next_subscriber.fetch_add(1, Ordering::Relaxed);
+    store
+        .subscribers
+        .lock()
+        .map_err(|_| "Settings are unavailable".to_string())?
+        .insert(id, on_change);
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn settings_unsubscribe(store: State<'_, SettingsStore>, id: u32) -> Result<(), String> {
+    store
+        .subscribers
+        .lock()
+        .map_err(|_| "Settings are unavailable".to_string())?
+        .remove(&id);
+    Ok(())
+}
+
+/// Imports the preferences the webview stored before settings moved to the
+/// backend. Ignored once settings have been saved.
+#[tauri::command]
+pub fn settings_import_legacy(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    legacy: Value,
+) -> Result<Settings, String> {
+    if store.persisted.load(Ordering::Relaxed) {
+        return store.current();
+    }
+
+    let mut legacy = migrate_value(legacy)?;
+    legacy.remove("version");
+    let mut value = serde_json::to_value(store.current()?)
+        .map_err(|error| format!("Failed to serialize settings: {}", error))?;
+    merge_patch(&mut value, Value::Object(legacy));
+    let imported = serde_json::from_value::<Settings>(value)
+        .map_err(|error| format!("Invalid settings: {}", error))?;
+
+    store.replace(&app, imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_legacy_settings() {
+        let settings = migrate(json!({
+            "theme": "dracula",
+            "autoSavePreferences": { "enabled": false, "intervalMs": 60000 }
+        }))
+        .unwrap();
+
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.theme, "dracula");
+        assert_eq!(
+            settings.autosave,
+            AutosaveSettings {
+                enabled: false,
+                interval_ms: 60_000
+            }
+        );
+        assert_eq!(settings.export, ExportDefaults::default());
+        assert!(migrate(json!({ "version": SETTINGS_VERSION + 1 })).is_err());
+    }
+
+    #[test]
+    fn merges_patches() {
+        let mut value = serde_json::to_value(Settings {
+            backup_directory: Some("/backups".to_string()),
+            ..Settings::default()
+        })
+        .unwrap();
+        merge_patch(
+            &mut value,
+            json!({ "autosave": { "intervalMs": 15000 }, "backupDirectory": null }),
+        );
+
+        let settings = serde_json::from_value::<Settings>(value).unwrap();
+        assert!(settings.autosave.enabled);
+        assert_eq!(settings.autosave.interval_ms, 15_000);
+        assert_eq!(settings.backup_directory, None);
+    }
+}
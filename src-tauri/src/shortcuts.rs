@@ -0,0 +1,473 @@
+use crate::app_data::{app_data_root, load_json};
+use crate::plugins;
+use crate::scratchpad;
+use crate::settings::{SettingsStore, ShortcutOverride};
+use crate::view_settings;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tauri::menu::MenuItemKind;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Where overrides were stored before they moved to the settings store.
+const LEGACY_SHORTCUTS_FILE: &str = "shortcuts.json";
+const PLUGIN_COMMAND_PREFIX: &str = "plugin:command:";
+
+/// Built-in menu commands and their default accelerators. Ids match the
+/// menu item ids and the `menu-event` payloads the frontend handles.
+const APP_SHORTCUTS: [(&str, &str); 18] = [
+    ("new", "CmdOrCtrl+N"),
+    ("open", "CmdOrCtrl+O"),
+    ("save", "CmdOrCtrl+S"),
+    ("save_as", "CmdOrCtrl+Shift+S"),
+    ("export_fountain", "CmdOrCtrl+Shift+E"),
+    ("export_pdf", "CmdOrCtrl+Shift+P"),
+    ("print", "CmdOrCtrl+P"),
+    ("settings", "CmdOrCtrl+,"),
+    ("find", "CmdOrCtrl+F"),
+    ("find_next", "CmdOrCtrl+G"),
+    ("find_prev", "CmdOrCtrl+Shift+G"),
+    ("replace", "CmdOrCtrl+H"),
+    ("zoom_in", "CmdOrCtrl+="),
+    ("zoom_out", "CmdOrCtrl+-"),
+    ("zoom_reset", "CmdOrCtrl+0"),
+    ("focus_mode", "CmdOrCtrl+Shift+F"),
+    ("typewriter_scrolling", "CmdOrCtrl+Shift+T"),
+    ("toggle_sidebar", "CmdOrCtrl+\\"),
+];
+
+/// Opens the quick-capture window instead of emitting a `menu-event`.
+const QUICK_CAPTURE_COMMAND: &str = "quick_capture";
+
+/// Built-in commands registered with the OS by default, since they are
+/// meant to be used while another application has focus.
+const GLOBAL_APP_SHORTCUTS: [(&str, &str); 1] = [(QUICK_CAPTURE_COMMAND, "CmdOrCtrl+Alt+Space")];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyShortcutStore {
+    #[serde(default)]
+    overrides: BTreeMap<String, ShortcutOverride>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    /// `menu-event` payload: an app menu id or `plugin:command:<plugin>:<command>`.
+    pub command: String,
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+    pub global: bool,
+    pub overridden: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutConflict {
+    pub accelerator: String,
+    pub commands: Vec<String>,
+    /// `binding` when two commands share it, `os` when the global shortcut
+    /// couldn't be registered, usually because another application holds it,
+    /// and `invalid` when the accelerator doesn't parse.
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutOverview {
+    pub bindings: Vec<ShortcutBinding>,
+    pub conflicts: Vec<ShortcutConflict>,
+}
+
+/// Maps registered OS shortcut ids to the command they trigger.
+#[derive(Default)]
+pub struct ShortcutRegistry {
+    active: Mutex<HashMap<u32, String>>,
+}
+
+/// Canonical form used for conflict checks: lower-case, aliases folded,
+/// modifiers sorted ahead of the key.
+pub fn normalize_accelerator(accelerator: &str) -> String {
+    let mut modifiers = Vec::new();
+    let mut keys = Vec::new();
+    for part in accelerator
+        .split('+')
+        .map(|part| part.trim().to_ascii_lowercase())
+    {
+        let part = match part.as_str() {
+            "cmdorctrl" | "commandorcontrol" | "mod" => "cmdorctrl".to_string(),
+            "cmd" | "command" | "meta" | "super" => "super".to_string(),
+            "ctrl" | "control" => "ctrl".to_string(),
+            "option" | "alt" => "alt".to_string(),
+            "" => continue,
+            _ => part,
+        };
+        match part.as_str() {
+            "cmdorctrl" | "super" | "ctrl" | "alt" | "shift" => modifiers.push(part),
+            _ => keys.push(part),
+        }
+    }
+    modifiers.sort();
+    modifiers.dedup();
+    modifiers.extend(keys);
+    modifiers.join("+")
+}
+
+pub fn validate_accelerator(accelerator: &str) -> Result<(), String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map(|_| ())
+        .map_err(|error| format!("Invalid shortcut '{}': {}", accelerator, error))
+}
+
+/// Overrides from the pre-settings `shortcuts.json`, if it still exists.
+pub fn legacy_overrides(app: &AppHandle) -> Option<BTreeMap<String, ShortcutOverride>> {
+    let exists = app_data_root(app)
+        .map(|root| root.join(LEGACY_SHORTCUTS_FILE).exists())
+        .unwrap_or(false);
+    if !exists {
+        return None;
+    }
+
+    load_json::<LegacyShortcutStore>(app, LEGACY_SHORTCUTS_FILE)
+        .map(|store| store.overrides)
+        .ok()
+}
+
+pub fn remove_legacy_overrides(app: &AppHandle) {
+    if let Ok(root) = app_data_root(app) {
+        let _ = std::fs::remove_file(root.join(LEGACY_SHORTCUTS_FILE));
+    }
+}
+
+fn user_overrides(app: &AppHandle) -> Result<BTreeMap<String, ShortcutOverride>, String> {
+    Ok(app.state::<SettingsStore>().current()?.shortcuts)
+}
+
+pub fn find_conflicts(bindings: &[ShortcutBinding]) -> Vec<ShortcutConflict> {
+    let mut by_accelerator: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for binding in bindings {
+        if let Some(accelerator) = &binding.accelerator {
+            by_accelerator
+                .entry(normalize_accelerator(accelerator))
+                .or_default()
+                .push(binding.command.clone());
+        }
+    }
+
+    by_accelerator
+        .into_iter()
+        .filter(|(_, commands)| commands.len() > 1)
+        .map(|(accelerator, commands)| ShortcutConflict {
+            accelerator,
+            commands,
+            kind: "binding".to_string(),
+        })
+        .collect()
+}
+
+/// Default `(command, accelerator, global)` bindings.
+fn default_bindings(app: &AppHandle) -> Result<Vec<(String, String, bool)>, String> {
+    let mut defaults = APP_SHORTCUTS
+        .iter()
+        .map(|(command, accelerator)| (command, accelerator, false))
+        .chain(
+            GLOBAL_APP_SHORTCUTS
+                .iter()
+                .map(|(command, accelerator)| (command, accelerator, true)),
+        )
+        .map(|(command, accelerator, global)| {
+            (command.to_string(), accelerator.to_string(), global)
+        })
+        .collect::<Vec<_>>();
+
+    for (command, accelerator) in plugins::contributed_shortcuts(app)? {
+        defaults.push((
+            format!("{}{}", PLUGIN_COMMAND_PREFIX, command),
+            accelerator,
+            false,
+        ));
+    }
+
+    Ok(defaults)
+}
+
+fn effective_bindings(
+    app: &AppHandle,
+    overrides: &BTreeMap<String, ShortcutOverride>,
+) -> Result<Vec<ShortcutBinding>, String> {
+    let mut bindings = default_bindings(app)?
+        .into_iter()
+        .map(|(command, accelerator, default_global)| {
+            let user = overrides.get(&command);
+            ShortcutBinding {
+                accelerator: match user {
+                    Some(user) => user.accelerator.clone(),
+                    None => Some(accelerator.clone()),
+                },
+                default_accelerator: Some(accelerator),
+                global: user.map_or(default_global, |user| user.global),
+                overridden: user.is_some(),
+                command,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // User bindings for commands without a default.
+    for (command, user) in overrides {
+        if !bindings.iter().any(|binding| &binding.command == command) {
+            bindings.push(ShortcutBinding {
+                command: command.clone(),
+                accelerator: user.accelerator.clone(),
+                default_accelerator: None,
+                global: user.global,
+                overridden: true,
+            });
+        }
+    }
+
+    Ok(bindings)
+}
+
+/// Shows the effective accelerators on the native menu items. Global
+/// bindings are left off the menu so they don't fire twice.
+fn apply_menu_accelerators(app: &AppHandle, bindings: &[ShortcutBinding]) {
+    let Some(menu) = app.menu() else {
+        return;
+    };
+    let accelerators = bindings
+        .iter()
+        .map(|binding| {
+            let accelerator = binding.accelerator.as_deref().filter(|_| !binding.global);
+            (binding.command.as_str(), accelerator)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut pending = menu.items().unwrap_or_default();
+    while let Some(item) = pending.pop() {
+        let result = match &item {
+            MenuItemKind::Submenu(submenu) => {
+                pending.extend(submenu.items().unwrap_or_default());
+                continue;
+            }
+            MenuItemKind::MenuItem(item) => match accelerators.get(item.id().0.as_str()) {
+                Some(accelerator) => item.set_accelerator(*accelerator),
+                None => continue,
+            },
+            MenuItemKind::Check(item) => match accelerators.get(item.id().0.as_str()) {
+                Some(accelerator) => item.set_accelerator(*accelerator),
+                None => continue,
+            },
+            _ => continue,
+        };
+        if let Err(error) = result {
+            tracing::warn!("Failed to update menu accelerator: {}", error);
+        }
+    }
+}
+
+/// Re-registers every global binding with the OS and updates the menu
+/// accelerators. Bindings that conflict with another command, don't parse
+/// or can't be registered are skipped and reported one by one, so a single
+/// bad shortcut doesn't keep the others from working.
+fn apply(app: &AppHandle, registry: &ShortcutRegistry) -> Result<ShortcutOverview, String> {
+    let bindings = effective_bindings(app, &user_overrides(app)?)?;
+    let mut conflicts = find_conflicts(&bindings);
+    apply_menu_accelerators(app, &bindings);
+
+    let shortcuts = app.global_shortcut();
+    if let Err(error) = shortcuts.unregister_all() {
+        tracing::warn!("Failed to clear global shortcuts: {}", error);
+    }
+    let mut active = registry
+        .active
+        .lock()
+        .map_err(|_| "Shortcut registry is unavailable".to_string())?;
+    active.clear();
+
+    for binding in bindings.iter().filter(|binding| binding.global) {
+        let Some(accelerator) = &binding.accelerator else {
+            continue;
+        };
+        let normalized = normalize_accelerator(accelerator);
+        if conflicts
+            .iter()
+            .any(|conflict| conflict.accelerator == normalized)
+        {
+            continue;
+        }
+
+        let shortcut = match accelerator.parse::<Shortcut>() {
+            Ok(shortcut) => shortcut,
+            Err(error) => {
+                tracing::warn!(
+                    "Skipping global shortcut '{}' for {}: {}",
+                    accelerator,
+                    binding.command,
+                    error
+                );
+                conflicts.push(ShortcutConflict {
+                    accelerator: accelerator.clone(),
+                    commands: vec![binding.command.clone()],
+                    kind: "invalid".to_string(),
+                });
+                continue;
+            }
+        };
+        match shortcuts.register(shortcut) {
+            Ok(()) => {
+                active.insert(shortcut.id(), binding.command.clone());
+            }
+            Err(error) => {
+                tracing::warn!(
+                    "Failed to register global shortcut '{}' for {}: {}",
+                    accelerator,
+                    binding.command,
+                    error
+                );
+                conflicts.push(ShortcutConflict {
+                    accelerator: normalized,
+                    commands: vec![binding.command.clone()],
+                    kind: "os".to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(ShortcutOverview {
+        bindings,
+        conflicts,
+    })
+}
+
+/// Global shortcut handler: forwards presses as `menu-event`s, except quick
+/// capture, which must work while the main window is hidden or unfocused.
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, state: ShortcutState) {
+    if state != ShortcutState::Pressed {
+        return;
+    }
+
+    let command = app
+        .state::<ShortcutRegistry>()
+        .active
+        .lock()
+        .ok()
+        .and_then(|active| active.get(&shortcut.id()).cloned());
+    if command.as_deref() == Some(QUICK_CAPTURE_COMMAND) {
+        if let Err(error) = scratchpad::show_capture_window(app) {
+            tracing::warn!("{}", error);
+        }
+        return;
+    }
+
+    if command
+        .as_deref()
+        .is_some_and(|command| view_settings::handle_menu_event(app, command))
+    {
+        return;
+    }
+
+    if let (Some(command), Some(window)) = (command, app.get_webview_window("main")) {
+        let _ = window.emit("menu-event", command);
+    }
+}
+
+/// Applies the saved bindings at startup, before the frontend loads.
+pub fn refresh(app: &AppHandle) {
+    if let Err(error) = apply(app, &app.state::<ShortcutRegistry>()) {
+        tracing::warn!("{}", error);
+    }
+}
+
+#[tauri::command]
+pub fn shortcuts_apply(
+    app: AppHandle,
+    registry: State<'_, ShortcutRegistry>,
+) -> Result<ShortcutOverview, String> {
+    apply(&app, &registry)
+}
+
+/// Binds `command` to `binding`. Fails when the accelerator is already
+/// used by another command.
+#[tauri::command]
+pub fn shortcuts_set(
+    app: AppHandle,
+    registry: State<'_, ShortcutRegistry>,
+    settings: State<'_, SettingsStore>,
+    command: String,
+    binding: ShortcutOverride,
+) -> Result<ShortcutOverview, String> {
+    if let Some(accelerator) = &binding.accelerator {
+        validate_accelerator(accelerator)?;
+
+        let mut overrides = user_overrides(&app)?;
+        overrides.insert(command.clone(), binding.clone());
+        let bindings = effective_bindings(&app, &overrides)?;
+        if let Some(conflict) = find_conflicts(&bindings)
+            .into_iter()
+            .find(|conflict| conflict.commands.contains(&command))
+        {
+            let others = conflict
+                .commands
+                .iter()
+                .filter(|other| **other != command)
+                .cloned()
+                .collect::<Vec<_>>();
+            return Err(format!(
+                "{} is already used by {}",
+                accelerator,
+                others.join(", ")
+            ));
+        }
+    }
+
+    settings.update(&app, |settings| {
+        settings.shortcuts.insert(command, binding);
+    })?;
+    apply(&app, &registry)
+}
+
+#[tauri::command]
+pub fn shortcuts_reset(
+    app: AppHandle,
+    registry: State<'_, ShortcutRegistry>,
+    settings: State<'_, SettingsStore>,
+    command: String,
+) -> Result<ShortcutOverview, String> {
+    settings.update(&app, |settings| {
+        settings.shortcuts.remove(&command);
+    })?;
+    apply(&app, &registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(command: &str, accelerator: &str) -> ShortcutBinding {
+        ShortcutBinding {
+            command: command.to_string(),
+            accelerator: Some(accelerator.to_string()),
+            default_accelerator: None,
+            global: false,
+            overridden: false,
+        }
+    }
+
+    #[test]
+    fn detects_conflicts_across_aliases() {
+        assert_eq!(
+            normalize_accelerator("Shift+CommandOrControl+K"),
+            normalize_accelerator("mod+shift+k")
+        );
+
+        let conflicts = find_conflicts(&[
+            binding("save", "CmdOrCtrl+S"),
+            binding("plugin:command:demo:sync", "Shift+CmdOrCtrl+K"),
+            binding("plugin:command:other:run", "cmdorctrl+shift+k"),
+        ]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].commands.len(), 2);
+    }
+}
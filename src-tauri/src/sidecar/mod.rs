@@ -0,0 +1,128 @@
+use crate::app_data;
+use crate::breakdown::BreakdownTag;
+use crate::document;
+use crate::export_presets::ExportPreset;
+use crate::outline::IndexCard;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tauri::AppHandle;
+
+const SIDECAR_DIR: &str = "documents";
+
+/// A text range inside a top-level block, in character offsets. `text` keeps
+/// the anchored text so anchors can be re-found after edits elsewhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextAnchor {
+    pub block_index: usize,
+    pub start: usize,
+    pub end: usize,
+    #[serde(default)]
+    pub text: String,
+}
+
+impl TextAnchor {
+    /// Where the anchor falls in `blocks` now. When its text is no longer at
+    /// `block_index`/`start..end`, the nearest block holding it is used, at
+    /// the occurrence closest to `start`. `None` once the text is gone.
+    /// Anchors saved without text are taken as they are.
+    pub fn resolve(&self, blocks: &[Value]) -> Option<TextAnchor> {
+        if self.text.is_empty() {
+            return (self.block_index < blocks.len()).then(|| self.clone());
+        }
+
+        let mut nearest = (0..blocks.len()).collect::<Vec<_>>();
+        nearest.sort_by_key(|index| index.abs_diff(self.block_index));
+        nearest.into_iter().find_map(|index| {
+            let text = document::node_text(&blocks[index]);
+            let start = text
+                .match_indices(self.text.as_str())
+                .map(|(byte, _)| text[..byte].chars().count())
+                .min_by_key(|start| start.abs_diff(self.start))?;
+            Some(TextAnchor {
+                block_index: index,
+                start,
+                end: start + self.text.chars().count(),
+                text: self.text.clone(),
+            })
+        })
+    }
+}
+
+/// Per-document metadata kept by the backend, keyed by the document's
+/// `meta.id` so it follows the script across renames and Save As.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSidecar {
+    #[serde(default)]
+    pub breakdown_tags: Vec<BreakdownTag>,
+    #[serde(default)]
+    pub export_presets: Vec<ExportPreset>,
+    #[serde(default)]
+    pub index_cards: Vec<IndexCard>,
+}
+
+fn validate_document_id(document_id: &str) -> Result<(), String> {
+    if document_id.is_empty()
+        || !document_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid document id '{}'", document_id));
+    }
+
+    Ok(())
+}
+
+fn sidecar_path(app: &AppHandle, document_id: &str) -> Result<PathBuf, String> {
+    validate_document_id(document_id)?;
+    Ok(app_data::app_data_dir(app, SIDECAR_DIR)?.join(format!("{}.json", document_id)))
+}
+
+pub fn load(app: &AppHandle, document_id: &str) -> Result<DocumentSidecar, String> {
+    let path = sidecar_path(app, document_id)?;
+    if !path.exists() {
+        return Ok(DocumentSidecar::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read document metadata: {}", error))?;
+
+    serde_json::from_str::<DocumentSidecar>(&content)
+        .map_err(|error| format!("Failed to parse document metadata: {}", error))
+}
+
+pub fn save(app: &AppHandle, document_id: &str, sidecar: &DocumentSidecar) -> Result<(), String> {
+    let path = sidecar_path(app, document_id)?;
+    let payload = serde_json::to_string_pretty(sidecar)
+        .map_err(|error| format!("Failed to serialize document metadata: {}", error))?;
+
+    fs::write(path, payload).map_err(|error| format!("Failed to save document metadata: {}", error))
+}
+
+/// Loads the sidecar, applies `update`, and saves it back.
+pub fn update<T>(
+    app: &AppHandle,
+    document_id: &str,
+    update: impl FnOnce(&mut DocumentSidecar) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut sidecar = load(app, document_id)?;
+    let output = update(&mut sidecar)?;
+    save(app, document_id, &sidecar)?;
+    Ok(output)
+}
+
+pub fn next_id(prefix: &str) -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let sequence = COUNTER.fetch_add(1, Ordering::Relaxed) & 0xffff;
+    format!(
+        "{}-{:x}{:04x}",
+        prefix,
+        Utc::now().timestamp_millis(),
+        sequence
+    )
+}
@@ -0,0 +1,202 @@
+//! Sides for auditions and table reads: for each actor, a PDF of only the
+//! scenes their character speaks in, with their cues and dialogue
+//! highlighted and everyone else's dialogue dimmed.
+
+use crate::pdf::{self, PdfOptions};
+use crate::{dialogs, document, jobs, scene_numbers};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidesResult {
+    pub character: String,
+    /// `None` when the character has no dialogue and no file was written.
+    pub path: Option<String>,
+    /// Scene numbers included, as printed.
+    pub scenes: Vec<String>,
+}
+
+fn speaks_in(blocks: &[Value], character: &str) -> bool {
+    blocks.iter().any(|block| {
+        document::node_type(block) == "character"
+            && document::character_cue_name(&document::node_text(block)) == character
+    })
+}
+
+/// A copy of the script with only the scenes `character` speaks in. Each
+/// heading keeps the number it has in the full script, and a page break
+/// separates scenes that aren't consecutive there. Returns the document and
+/// the scene numbers it contains.
+pub fn extract(root: &Value, character: &str) -> (Value, Vec<String>) {
+    let character = document::character_cue_name(character);
+    let blocks = document::blocks(root);
+    let mut content = Vec::new();
+    let mut numbers = Vec::new();
+    let mut previous: Option<usize> = None;
+
+    for scene in document::block_scenes(blocks) {
+        let scene_blocks = &blocks[scene.heading_block..scene.end_block];
+        if !speaks_in(scene_blocks, &character) {
+            continue;
+        }
+        if previous.is_some_and(|index| index + 1 != scene.index) {
+            content.push(json!({ "type": "pageBreak" }));
+        }
+        let number = scene_numbers::heading_number(&scene_blocks[0])
+            .unwrap_or_else(|| (scene.index + 1).to_string());
+        let mut heading = scene_blocks[0].clone();
+        if !heading.get("attrs").is_some_and(Value::is_object) {
+            heading["attrs"] = json!({});
+        }
+        heading["attrs"]["sceneNumber"] = Value::String(number.clone());
+        content.push(heading);
+        content.extend(scene_blocks[1..].iter().cloned());
+        numbers.push(number);
+        previous = Some(scene.index);
+    }
+
+    (json!({ "type": "doc", "content": content }), numbers)
+}
+
+/// Writes one sides PDF per character into `output_dir`, named
+/// `<title> - <CHARACTER> Sides.pdf`. Scene numbers are always printed so
+/// actors can find their place in the full script.
+#[tauri::command]
+pub async fn export_sides(
+    app: AppHandle,
+    content_json: String,
+    title_page_json: Option<String>,
+    characters: Vec<String>,
+    output_dir: String,
+    document_title: String,
+    options: Option<PdfOptions>,
+) -> Result<Vec<SidesResult>, String> {
+    jobs::run(&app, "export", "Exporting sides", move |job| {
+        let root = document::parse_json(&content_json)?;
+        let mut results = Vec::new();
+
+        for (position, character) in characters.iter().enumerate() {
+            job.check_cancelled()?;
+            let character = document::character_cue_name(character);
+            job.progress(
+                Some(position as f32 / characters.len() as f32),
+                format!("Rendering sides for {}", character),
+            );
+
+            let (sides, scenes) = extract(&root, &character);
+            if scenes.is_empty() {
+                results.push(SidesResult {
+                    character,
+                    path: None,
+                    scenes,
+                });
+                continue;
+            }
+
+            let options = PdfOptions {
+                scene_numbers: true,
+                highlight_character: Some(character.clone()),
+                ..options.clone().unwrap_or_default()
+            };
+            let file_name =
+                dialogs::sanitize_file_stem(&format!("{} - {} Sides", document_title, character));
+            let path = Path::new(&output_dir).join(format!("{}.pdf", file_name));
+            let path = path.to_string_lossy().to_string();
+            let sides_json = serde_json::to_string(&sides)
+                .map_err(|error| format!("Failed to serialize sides: {}", error))?;
+            pdf::generate_pdf_with_options(
+                &sides_json,
+                title_page_json.as_deref(),
+                &path,
+                &document_title,
+                "screenplay",
+                &options,
+            )?;
+            results.push(SidesResult {
+                character,
+                path: Some(path),
+                scenes,
+            });
+        }
+
+        Ok(results)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+
+    fn script() -> Value {
+        json!({
+            "type": "doc",
+            "content": [
+                block("sceneHeading", "INT. KITCHEN - DAY"),
+                block("character", "JANE"),
+                block("dialogue", "Morning."),
+                block("sceneHeading", "EXT. YARD - DAY"),
+                block("character", "TOM"),
+                block("dialogue", "Is she up?"),
+                { "type": "sceneHeading", "attrs": { "sceneNumber": "2A" }, "content": [{ "type": "text", "text": "INT. HALL - DAY" }] },
+                block("character", "Jane (V.O.)"),
+                block("dialogue", "Always."),
+            ],
+        })
+    }
+
+    #[test]
+    fn keeps_only_scenes_the_character_speaks_in() {
+        let (sides, scenes) = extract(&script(), "jane");
+        assert_eq!(scenes, ["1", "2A"]);
+
+        let types = document::blocks(&sides)
+            .iter()
+            .map(document::node_type)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                "sceneHeading",
+                "character",
+                "dialogue",
+                "pageBreak",
+                "sceneHeading",
+                "character",
+                "dialogue"
+            ]
+        );
+        assert_eq!(
+            scene_numbers::heading_number(&document::blocks(&sides)[0]),
+            Some("1".to_string())
+        );
+
+        let (_, none) = extract(&script(), "BOB");
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn renders_highlighted_sides() {
+        let (sides, _) = extract(&script(), "TOM");
+        let path = std::env::temp_dir().join(format!("grainery-sides-{}.pdf", std::process::id()));
+        let options = PdfOptions {
+            highlight_character: Some("TOM".to_string()),
+            ..PdfOptions::default()
+        };
+        pdf::generate_pdf_with_options(
+            &sides.to_string(),
+            None,
+            &path.to_string_lossy(),
+            "Sides",
+            "screenplay",
+            &options,
+        )
+        .unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,379 @@
+use crate::app_data::{load_json, save_json};
+use crate::sidecar;
+use chrono::{Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::AppHandle;
+
+const SNIPPETS_FILE: &str = "snippets.json";
+const SNIPPET_SET_FORMAT_VERSION: u32 = 1;
+const DEFAULT_SET: &str = "Default";
+const MAX_TRIGGER_CHARS: usize = 32;
+const CURSOR_PLACEHOLDER: &str = "{CURSOR}";
+
+/// Expands `trigger` to `expansion` in the editor. The expansion may use the
+/// placeholders filled by `expand`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: String,
+    pub trigger: String,
+    pub expansion: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Named group the snippet is imported and exported with.
+    #[serde(default = "default_set")]
+    pub set: String,
+}
+
+fn default_set() -> String {
+    DEFAULT_SET.to_string()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnippetStore {
+    #[serde(default)]
+    snippets: Vec<Snippet>,
+}
+
+/// A new snippet, or changes to the one with `id`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub trigger: String,
+    pub expansion: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub set: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetSetEntry {
+    pub trigger: String,
+    pub expansion: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A portable snippet set file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetSet {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub name: String,
+    pub snippets: Vec<SnippetSetEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetImportReport {
+    pub set: String,
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Editor state used to fill placeholders.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpansionContext {
+    /// The last character cue above the cursor.
+    #[serde(default)]
+    pub character: Option<String>,
+    /// The current scene heading.
+    #[serde(default)]
+    pub scene: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Expansion {
+    pub text: String,
+    /// Where to place the cursor, in characters from the start of `text`.
+    /// `None` leaves it after the expansion.
+    pub cursor: Option<usize>,
+}
+
+fn validate_trigger(trigger: &str) -> Result<(), String> {
+    if trigger.is_empty() {
+        return Err("Snippet trigger is required".to_string());
+    }
+    if trigger.chars().any(char::is_whitespace) {
+        return Err(format!(
+            "Snippet trigger '{}' can't contain spaces",
+            trigger
+        ));
+    }
+    if trigger.chars().count() > MAX_TRIGGER_CHARS {
+        return Err(format!(
+            "Snippet triggers are limited to {} characters",
+            MAX_TRIGGER_CHARS
+        ));
+    }
+    Ok(())
+}
+
+fn set_name(set: Option<&str>) -> String {
+    set.map(str::trim)
+        .filter(|set| !set.is_empty())
+        .unwrap_or(DEFAULT_SET)
+        .to_string()
+}
+
+/// Fills `{CHARACTER}`, `{SCENE}`, `{TITLE}`, `{DATE}` and `{TIME}`, and
+/// removes `{CURSOR}`, reporting its position. Placeholders without a value
+/// are left as typed so the writer can see what was missing.
+pub fn expand(
+    template: &str,
+    context: &ExpansionContext,
+    now: chrono::DateTime<Local>,
+) -> Expansion {
+    let values = [
+        ("{CHARACTER}", context.character.clone()),
+        ("{SCENE}", context.scene.clone()),
+        ("{TITLE}", context.title.clone()),
+        ("{DATE}", Some(now.format("%B %-d, %Y").to_string())),
+        ("{TIME}", Some(now.format("%-I:%M %p").to_string())),
+    ];
+
+    let mut text = template.to_string();
+    for (placeholder, value) in values {
+        if let Some(value) = value.map(|value| value.trim().to_string()) {
+            if !value.is_empty() {
+                text = text.replace(placeholder, &value);
+            }
+        }
+    }
+
+    let cursor = text
+        .find(CURSOR_PLACEHOLDER)
+        .map(|index| text[..index].chars().count());
+    Expansion {
+        text: text.replace(CURSOR_PLACEHOLDER, ""),
+        cursor,
+    }
+}
+
+fn load_store(app: &AppHandle) -> Result<SnippetStore, String> {
+    load_json(app, SNIPPETS_FILE)
+}
+
+fn save_store(app: &AppHandle, store: &SnippetStore) -> Result<(), String> {
+    save_json(app, SNIPPETS_FILE, store)
+}
+
+#[tauri::command]
+pub fn snippets_list(app: AppHandle) -> Result<Vec<Snippet>, String> {
+    Ok(load_store(&app)?.snippets)
+}
+
+#[tauri::command]
+pub fn snippets_save(app: AppHandle, snippet: SnippetInput) -> Result<Snippet, String> {
+    let trigger = snippet.trigger.trim().to_string();
+    validate_trigger(&trigger)?;
+
+    let mut store = load_store(&app)?;
+    if let Some(existing) = store.snippets.iter().find(|existing| {
+        existing.trigger == trigger && snippet.id.as_deref() != Some(existing.id.as_str())
+    }) {
+        return Err(format!(
+            "'{}' is already used by another snippet in {}",
+            trigger, existing.set
+        ));
+    }
+
+    let saved = Snippet {
+        id: snippet
+            .id
+            .clone()
+            .unwrap_or_else(|| sidecar::next_id("snippet")),
+        trigger,
+        expansion: snippet.expansion,
+        description: snippet
+            .description
+            .map(|description| description.trim().to_string())
+            .filter(|description| !description.is_empty()),
+        set: set_name(snippet.set.as_deref()),
+    };
+
+    match snippet.id.as_deref() {
+        Some(id) => {
+            let existing = store
+                .snippets
+                .iter_mut()
+                .find(|existing| existing.id == id)
+                .ok_or_else(|| format!("Snippet '{}' not found", id))?;
+            *existing = saved.clone();
+        }
+        None => store.snippets.push(saved.clone()),
+    }
+
+    save_store(&app, &store)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn snippets_delete(app: AppHandle, id: String) -> Result<(), String> {
+    let mut store = load_store(&app)?;
+    let before = store.snippets.len();
+    store.snippets.retain(|snippet| snippet.id != id);
+    if store.snippets.len() == before {
+        return Err(format!("Snippet '{}' not found", id));
+    }
+    save_store(&app, &store)
+}
+
+/// Looks up the snippet for the word before the cursor. Returns `None` when
+/// `trigger` isn't a snippet, so the editor can handle the key normally.
+#[tauri::command]
+pub fn snippets_expand(
+    app: AppHandle,
+    trigger: String,
+    context: Option<ExpansionContext>,
+) -> Result<Option<Expansion>, String> {
+    let store = load_store(&app)?;
+    Ok(store
+        .snippets
+        .iter()
+        .find(|snippet| snippet.trigger == trigger)
+        .map(|snippet| {
+            expand(
+                &snippet.expansion,
+                &context.unwrap_or_default(),
+                Local::now(),
+            )
+        }))
+}
+
+/// Writes one set to a file that `snippets_import` can read on another
+/// machine. Returns the number of snippets exported.
+#[tauri::command]
+pub fn snippets_export(
+    app: AppHandle,
+    output_path: String,
+    set: Option<String>,
+) -> Result<usize, String> {
+    let name = set_name(set.as_deref());
+    let snippets = load_store(&app)?
+        .snippets
+        .into_iter()
+        .filter(|snippet| snippet.set == name)
+        .map(|snippet| SnippetSetEntry {
+            trigger: snippet.trigger,
+            expansion: snippet.expansion,
+            description: snippet.description,
+        })
+        .collect::<Vec<_>>();
+    if snippets.is_empty() {
+        return Err(format!("Snippet set '{}' is empty", name));
+    }
+
+    let count = snippets.len();
+    let file = SnippetSet {
+        format_version: SNIPPET_SET_FORMAT_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        name,
+        snippets,
+    };
+    let payload = serde_json::to_string_pretty(&file)
+        .map_err(|error| format!("Failed to serialize snippets: {}", error))?;
+    fs::write(&output_path, payload)
+        .map_err(|error| format!("Failed to write snippets: {}", error))?;
+
+    Ok(count)
+}
+
+/// Imports a snippet set file. Snippets replace ones in the same set with
+/// the same trigger; triggers used by another set are skipped.
+#[tauri::command]
+pub fn snippets_import(
+    app: AppHandle,
+    input_path: String,
+    set: Option<String>,
+) -> Result<SnippetImportReport, String> {
+    let content = fs::read_to_string(&input_path)
+        .map_err(|error| format!("Failed to read snippets: {}", error))?;
+    let file = serde_json::from_str::<SnippetSet>(&content)
+        .map_err(|error| format!("Failed to parse snippets: {}", error))?;
+    if file.format_version != SNIPPET_SET_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported snippet set format {}",
+            file.format_version
+        ));
+    }
+
+    let mut store = load_store(&app)?;
+    let mut report = SnippetImportReport {
+        set: set_name(set.as_deref().or(Some(file.name.as_str()))),
+        ..SnippetImportReport::default()
+    };
+
+    for entry in file.snippets {
+        let trigger = entry.trigger.trim().to_string();
+        if validate_trigger(&trigger).is_err() {
+            report.skipped.push(entry.trigger);
+            continue;
+        }
+
+        match store
+            .snippets
+            .iter_mut()
+            .find(|snippet| snippet.trigger == trigger)
+        {
+            Some(existing) if existing.set == report.set => {
+                existing.expansion = entry.expansion;
+                existing.description = entry.description;
+                report.replaced.push(trigger);
+            }
+            Some(_) => report.skipped.push(trigger),
+            None => {
+                store.snippets.push(Snippet {
+                    id: sidecar::next_id("snippet"),
+                    trigger: trigger.clone(),
+                    expansion: entry.expansion,
+                    description: entry.description,
+                    set: report.set.clone(),
+                });
+                report.added.push(trigger);
+            }
+        }
+    }
+
+    save_store(&app, &store)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fills_placeholders_and_cursor() {
+        let context = ExpansionContext {
+            character: Some("JANE".to_string()),
+            scene: None,
+            title: Some("Nightfall".to_string()),
+        };
+        let now = Local.with_ymd_and_hms(2026, 3, 12, 9, 5, 0).unwrap();
+
+        let expansion = expand("{CHARACTER} (CONT'D)\n{CURSOR}— {SCENE}", &context, now);
+        assert_eq!(expansion.text, "JANE (CONT'D)\n— {SCENE}");
+        assert_eq!(expansion.cursor, Some(14));
+
+        let dated = expand("{TITLE}, {DATE}", &context, now);
+        assert_eq!(dated.text, "Nightfall, March 12, 2026");
+        assert_eq!(dated.cursor, None);
+
+        assert!(validate_trigger("int").is_ok());
+        assert!(validate_trigger("in t").is_err());
+    }
+}
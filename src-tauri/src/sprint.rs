@@ -0,0 +1,176 @@
+use crate::sidecar;
+use crate::writing_stats::{self, WritingSession, WritingSessionInput};
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+const MAX_SPRINT_MINUTES: u32 = 240;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintInfo {
+    pub id: String,
+    pub document_id: String,
+    pub started_at: String,
+    pub duration_seconds: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_target: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SprintTick {
+    id: String,
+    elapsed_seconds: u32,
+    remaining_seconds: u32,
+}
+
+struct ActiveSprint {
+    info: SprintInfo,
+    stopped: Arc<AtomicBool>,
+}
+
+/// Holds the current sprint. Only one timer runs at a time; its thread emits
+/// `sprint-tick` every second and `sprint-finished` when time is up. A
+/// finished sprint stays here until `sprint_complete` records it.
+#[derive(Default)]
+pub struct SprintTimer {
+    active: Mutex<Option<ActiveSprint>>,
+}
+
+impl SprintTimer {
+    fn take(&self, sprint_id: &str) -> Result<SprintInfo, String> {
+        let mut active = self
+            .active
+            .lock()
+            .map_err(|_| "Sprint timer is unavailable".to_string())?;
+        match active.take() {
+            Some(sprint) if sprint.info.id == sprint_id => {
+                sprint.stopped.store(true, Ordering::Relaxed);
+                Ok(sprint.info)
+            }
+            other => {
+                *active = other;
+                Err(format!("Sprint '{}' is not running", sprint_id))
+            }
+        }
+    }
+}
+
+fn run_timer(app: AppHandle, info: SprintInfo, stopped: Arc<AtomicBool>) {
+    let started = Instant::now();
+    let total = Duration::from_secs(info.duration_seconds as u64);
+
+    loop {
+        thread::sleep(Duration::from_secs(1));
+        if stopped.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let elapsed = started.elapsed().min(total);
+        let _ = app.emit(
+            "sprint-tick",
+            SprintTick {
+                id: info.id.clone(),
+                elapsed_seconds: elapsed.as_secs() as u32,
+                remaining_seconds: (total - elapsed).as_secs() as u32,
+            },
+        );
+
+        if elapsed >= total {
+            stopped.store(true, Ordering::Relaxed);
+            let _ = app.emit("sprint-finished", info.clone());
+            return;
+        }
+    }
+}
+
+#[tauri::command]
+pub fn sprint_start(
+    app: AppHandle,
+    timer: State<'_, SprintTimer>,
+    document_id: String,
+    duration_minutes: u32,
+    word_target: Option<u32>,
+) -> Result<SprintInfo, String> {
+    if duration_minutes == 0 || duration_minutes > MAX_SPRINT_MINUTES {
+        return Err(format!(
+            "Sprint length must be between 1 and {} minutes",
+            MAX_SPRINT_MINUTES
+        ));
+    }
+
+    let mut active = timer
+        .active
+        .lock()
+        .map_err(|_| "Sprint timer is unavailable".to_string())?;
+    if let Some(sprint) = active.as_ref() {
+        if !sprint.stopped.load(Ordering::Relaxed) {
+            return Err("A sprint is already running".to_string());
+        }
+    }
+
+    let info = SprintInfo {
+        id: sidecar::next_id("sprint"),
+        document_id,
+        started_at: Utc::now().to_rfc3339(),
+        duration_seconds: duration_minutes * 60,
+        word_target,
+    };
+    let stopped = Arc::new(AtomicBool::new(false));
+    let thread_info = info.clone();
+    let thread_stopped = stopped.clone();
+    thread::spawn(move || run_timer(app, thread_info, thread_stopped));
+
+    *active = Some(ActiveSprint {
+        info: info.clone(),
+        stopped,
+    });
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn sprint_cancel(timer: State<'_, SprintTimer>, sprint_id: String) -> Result<(), String> {
+    timer.take(&sprint_id).map(|_| ())
+}
+
+/// Ends the sprint (early or after `sprint-finished`) and records its result
+/// as a writing session tagged with the sprint id.
+#[tauri::command]
+pub fn sprint_complete(
+    app: AppHandle,
+    timer: State<'_, SprintTimer>,
+    sprint_id: String,
+    words_added: u32,
+    words_removed: u32,
+    pages: u32,
+) -> Result<WritingSession, String> {
+    let info = timer.take(&sprint_id)?;
+    let ended_at = Utc::now().to_rfc3339();
+    let active_seconds = chrono::DateTime::parse_from_rfc3339(&info.started_at)
+        .map(|started| {
+            (Utc::now() - started.with_timezone(&Utc))
+                .num_seconds()
+                .max(0) as u32
+        })
+        .unwrap_or(0)
+        .min(info.duration_seconds);
+
+    writing_stats::record(
+        &app,
+        &info.document_id,
+        WritingSessionInput {
+            started_at: info.started_at,
+            ended_at,
+            words_added,
+            words_removed,
+            active_seconds,
+            pages,
+        },
+        Some(info.id),
+    )
+}
@@ -0,0 +1,347 @@
+//! Table reads: a rough audio read-through of the script by the system's
+//! speech engine, with one voice per character and a narrator for headings,
+//! action and transitions, written to a WAV file. Voice choices are kept in
+//! the document's `meta.tableReadVoices`; characters without one are given a
+//! voice from the narrator's language.
+
+use crate::{document, jobs};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+mod speech;
+mod wav;
+
+const VOICES_META: &str = "tableReadVoices";
+/// Language of the voices handed out when the narrator has no voice set.
+const DEFAULT_LANGUAGE: &str = "en";
+const LINE_PAUSE_MS: u32 = 400;
+const SCENE_PAUSE_MS: u32 = 1200;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Voice {
+    pub name: String,
+    /// Lowercase language tag, e.g. `en-us`.
+    pub language: String,
+}
+
+impl Voice {
+    fn new(name: &str, language: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            language: language.replace('_', "-").to_lowercase(),
+        }
+    }
+
+    fn primary_language(&self) -> &str {
+        self.language.split('-').next().unwrap_or_default()
+    }
+}
+
+/// Voice names as the speech engine knows them. Unset voices use the
+/// system default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VoiceMapping {
+    /// Reads scene headings, action and transitions.
+    pub narrator: Option<String>,
+    /// Voices by character cue name.
+    pub characters: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    /// Cue name of the character speaking, or `None` for the narrator.
+    pub speaker: Option<String>,
+    pub text: String,
+    pub scene_start: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableReadResult {
+    pub path: String,
+    pub lines: usize,
+    pub duration_seconds: f32,
+    /// The voices used, including ones handed out this time.
+    pub voices: VoiceMapping,
+}
+
+pub fn voice_mapping(document: &Value) -> VoiceMapping {
+    document
+        .get("meta")
+        .and_then(|meta| meta.get(VOICES_META))
+        .and_then(|voices| serde_json::from_value(voices.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn set_voice_mapping(document: &mut Value, mapping: &VoiceMapping) -> Result<(), String> {
+    let Some(object) = document.as_object_mut() else {
+        return Err("Table read voices require a full document".to_string());
+    };
+    let meta = object
+        .entry("meta")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !meta.is_object() {
+        *meta = Value::Object(Map::new());
+    }
+    meta[VOICES_META] = serde_json::to_value(mapping)
+        .map_err(|error| format!("Failed to serialize voices: {}", error))?;
+    Ok(())
+}
+
+/// What gets read aloud, in order. Parentheticals, notes and outline
+/// elements are skipped.
+pub fn lines(root: &Value) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut speaker: Option<String> = None;
+
+    for block in document::blocks(root) {
+        let node_type = document::node_type(block);
+        let text = document::node_text(block).trim().to_string();
+        let speaking = match node_type {
+            "character" => {
+                speaker = Some(document::character_cue_name(&text));
+                continue;
+            }
+            "parenthetical" => continue,
+            "dialogue" => speaker.clone(),
+            "sceneHeading" | "action" | "transition" => {
+                speaker = None;
+                None
+            }
+            _ => {
+                speaker = None;
+                continue;
+            }
+        };
+        if (node_type == "dialogue" && speaking.is_none()) || text.is_empty() {
+            continue;
+        }
+        lines.push(Line {
+            speaker: speaking,
+            text,
+            scene_start: node_type == "sceneHeading",
+        });
+    }
+
+    lines
+}
+
+/// Fills in a voice for every character that speaks and has none, cycling
+/// through the unclaimed voices in the narrator's language.
+pub fn assign_voices(lines: &[Line], mapping: &VoiceMapping, available: &[Voice]) -> VoiceMapping {
+    let language = mapping
+        .narrator
+        .as_ref()
+        .and_then(|narrator| available.iter().find(|voice| &voice.name == narrator))
+        .map(Voice::primary_language)
+        .unwrap_or(DEFAULT_LANGUAGE);
+    let in_language = available
+        .iter()
+        .filter(|voice| voice.primary_language() == language)
+        .collect::<Vec<_>>();
+    let claimed = |voice: &&&Voice| {
+        mapping.narrator.as_ref() == Some(&voice.name)
+            || mapping.characters.values().any(|name| *name == voice.name)
+    };
+    let unclaimed = in_language
+        .iter()
+        .filter(|voice| !claimed(voice))
+        .collect::<Vec<_>>();
+    let pool = if unclaimed.is_empty() {
+        in_language.iter().collect()
+    } else {
+        unclaimed
+    };
+
+    let mut resolved = mapping.clone();
+    let mut handed_out = 0;
+    for speaker in lines.iter().filter_map(|line| line.speaker.as_ref()) {
+        if resolved.characters.contains_key(speaker) || pool.is_empty() {
+            continue;
+        }
+        let voice = &pool[handed_out % pool.len()];
+        resolved
+            .characters
+            .insert(speaker.clone(), voice.name.clone());
+        handed_out += 1;
+    }
+    resolved
+}
+
+/// Voices the system speech engine offers.
+#[tauri::command]
+pub async fn table_read_voices() -> Result<Vec<Voice>, String> {
+    tauri::async_runtime::spawn_blocking(speech::voices)
+        .await
+        .map_err(|error| format!("Failed to list voices: {}", error))?
+}
+
+/// Stores the voice mapping in the document's metadata.
+#[tauri::command]
+pub fn table_read_set_voices(
+    document_json: String,
+    voices: VoiceMapping,
+) -> Result<String, String> {
+    let mut document = document::parse_json(&document_json)?;
+    set_voice_mapping(&mut document, &voices)?;
+    serde_json::to_string(&document)
+        .map_err(|error| format!("Failed to serialize document: {}", error))
+}
+
+/// Reads the script aloud into a WAV file at `output_path`, using the
+/// voices in the document's metadata.
+#[tauri::command]
+pub async fn export_table_read(
+    app: AppHandle,
+    document_json: String,
+    output_path: String,
+) -> Result<TableReadResult, String> {
+    jobs::run(&app, "export", "Recording table read", move |job| {
+        let document = document::parse_json(&document_json)?;
+        let lines = lines(&document);
+        if lines.is_empty() {
+            return Err("There is nothing to read aloud".to_string());
+        }
+        job.progress(None, "Choosing voices");
+        // An unlisted engine still reads everything in the default voice.
+        let available = speech::voices().unwrap_or_default();
+        let voices = assign_voices(&lines, &voice_mapping(&document), &available);
+
+        let scratch = std::env::temp_dir().join(format!(
+            "grainery-table-read-{}",
+            chrono::Utc::now().timestamp_millis()
+        ));
+        fs::create_dir_all(&scratch)
+            .map_err(|error| format!("Failed to create table read folder: {}", error))?;
+        let result = record(job, &lines, &voices, &scratch, Path::new(&output_path));
+        let _ = fs::remove_dir_all(&scratch);
+        let duration_seconds = result?;
+
+        Ok(TableReadResult {
+            path: output_path,
+            lines: lines.len(),
+            duration_seconds,
+            voices,
+        })
+    })
+    .await
+}
+
+fn record(
+    job: &jobs::Job,
+    lines: &[Line],
+    voices: &VoiceMapping,
+    scratch: &Path,
+    output: &Path,
+) -> Result<f32, String> {
+    let text_path = scratch.join("line.txt");
+    let clip_path = scratch.join("line.wav");
+    let mut track = wav::Track::default();
+
+    for (index, line) in lines.iter().enumerate() {
+        job.check_cancelled()?;
+        job.progress(
+            Some(index as f32 / lines.len() as f32),
+            format!("Reading line {} of {}", index + 1, lines.len()),
+        );
+        let voice = match &line.speaker {
+            Some(speaker) => voices.characters.get(speaker),
+            None => voices.narrator.as_ref(),
+        };
+        speech::speak(
+            &line.text,
+            voice.map(String::as_str),
+            &text_path,
+            &clip_path,
+        )?;
+        let bytes = fs::read(&clip_path)
+            .map_err(|error| format!("Failed to read spoken line: {}", error))?;
+
+        track.pause(if line.scene_start {
+            SCENE_PAUSE_MS
+        } else {
+            LINE_PAUSE_MS
+        });
+        track.push(wav::parse(&bytes)?)?;
+    }
+
+    job.progress(Some(1.0), "Writing audio");
+    track.write(output)?;
+    Ok(track.duration_seconds())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    fn script() -> Value {
+        json!({
+            "meta": { "id": "doc-1", "tableReadVoices": { "characters": { "TOM": "Fred" } } },
+            "document": {
+                "type": "doc",
+                "content": [
+                    block("sceneHeading", "INT. KITCHEN - DAY"),
+                    block("action", "Jane pours coffee."),
+                    block("character", "JANE (O.S.)"),
+                    block("parenthetical", "(yawning)"),
+                    block("dialogue", "Morning."),
+                    block("character", "TOM"),
+                    block("dialogue", "Is it?"),
+                    block("note", "Trim this."),
+                    block("dialogue", "Stray line."),
+                    block("transition", "CUT TO:"),
+                ],
+            },
+        })
+    }
+
+    #[test]
+    fn reads_dialogue_in_character_and_the_rest_as_narrator() {
+        let lines = lines(&script());
+        let read = lines
+            .iter()
+            .map(|line| (line.speaker.as_deref(), line.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            read,
+            [
+                (None, "INT. KITCHEN - DAY"),
+                (None, "Jane pours coffee."),
+                (Some("JANE"), "Morning."),
+                (Some("TOM"), "Is it?"),
+                (None, "CUT TO:"),
+            ]
+        );
+        assert!(lines[0].scene_start && !lines[1].scene_start);
+    }
+
+    #[test]
+    fn hands_out_unclaimed_voices_in_the_narrators_language() {
+        let document = script();
+        let available = [
+            Voice::new("Fred", "en_US"),
+            Voice::new("Amelie", "fr_CA"),
+            Voice::new("Daniel", "en_GB"),
+            Voice::new("Karen", "en-AU"),
+        ];
+        let mut mapping = voice_mapping(&document);
+        mapping.narrator = Some("Karen".to_string());
+
+        let voices = assign_voices(&lines(&document), &mapping, &available);
+        assert_eq!(voices.characters["TOM"], "Fred");
+        assert_eq!(voices.characters["JANE"], "Daniel");
+
+        let mut saved = document.clone();
+        set_voice_mapping(&mut saved, &voices).unwrap();
+        assert_eq!(voice_mapping(&saved), voices);
+        assert_eq!(saved["meta"]["id"], "doc-1");
+    }
+}
@@ -0,0 +1,132 @@
+//! The operating system's speech engine: `say` on macOS, System.Speech
+//! through PowerShell on Windows and espeak-ng elsewhere. Each line is
+//! spoken to its own 16-bit mono WAV file.
+
+use super::Voice;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn run(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|error| format!("Failed to launch {:?}: {}", command.get_program(), error))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} failed: {}",
+            command.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Speaks `text` with `voice`, or the system default, into `output`.
+/// `scratch` is a spare path for the text, which is passed by file so no
+/// line of dialogue is ever parsed as a command line option.
+#[cfg(target_os = "macos")]
+pub fn speak(text: &str, voice: Option<&str>, scratch: &Path, output: &Path) -> Result<(), String> {
+    fs::write(scratch, text).map_err(|error| format!("Failed to stage line: {}", error))?;
+    let mut command = Command::new("say");
+    if let Some(voice) = voice {
+        command.arg("-v").arg(voice);
+    }
+    command
+        .arg("--file-format=WAVE")
+        .arg("--data-format=LEI16@22050")
+        .arg("-o")
+        .arg(output)
+        .arg("-f")
+        .arg(scratch);
+    run(&mut command).map(|_| ())
+}
+
+/// `say -v ?` lists `Name   locale   # sample`, and names can have spaces.
+#[cfg(target_os = "macos")]
+pub fn voices() -> Result<Vec<Voice>, String> {
+    let listing = run(Command::new("say").arg("-v").arg("?"))?;
+    Ok(listing
+        .lines()
+        .filter_map(|line| {
+            let described = line.split('#').next()?.trim_end();
+            let (name, locale) = described.rsplit_once(char::is_whitespace)?;
+            Some(Voice::new(name.trim(), locale))
+        })
+        .filter(|voice| !voice.name.is_empty())
+        .collect())
+}
+
+#[cfg(windows)]
+const SPEAK_SCRIPT: &str = "Add-Type -AssemblyName System.Speech; \
+    $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+    if ($env:GRAINERY_VOICE) { $s.SelectVoice($env:GRAINERY_VOICE) }; \
+    $f = New-Object System.Speech.AudioFormat.SpeechAudioFormatInfo 22050, Sixteen, Mono; \
+    $s.SetOutputToWaveFile($env:GRAINERY_OUTPUT, $f); \
+    $s.Speak([IO.File]::ReadAllText($env:GRAINERY_TEXT)); \
+    $s.Dispose()";
+
+#[cfg(windows)]
+const VOICES_SCRIPT: &str = "Add-Type -AssemblyName System.Speech; \
+    (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+    Where-Object Enabled | \
+    ForEach-Object { $_.VoiceInfo.Name + [char]9 + $_.VoiceInfo.Culture.Name }";
+
+#[cfg(windows)]
+fn powershell(script: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+    // CREATE_NO_WINDOW, so a console doesn't flash up for every line.
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .creation_flags(0x0800_0000);
+    command
+}
+
+/// Paths and the voice go through the environment to avoid quoting them
+/// into the script.
+#[cfg(windows)]
+pub fn speak(text: &str, voice: Option<&str>, scratch: &Path, output: &Path) -> Result<(), String> {
+    fs::write(scratch, text).map_err(|error| format!("Failed to stage line: {}", error))?;
+    let mut command = powershell(SPEAK_SCRIPT);
+    command
+        .env("GRAINERY_VOICE", voice.unwrap_or_default())
+        .env("GRAINERY_OUTPUT", output)
+        .env("GRAINERY_TEXT", scratch);
+    run(&mut command).map(|_| ())
+}
+
+#[cfg(windows)]
+pub fn voices() -> Result<Vec<Voice>, String> {
+    let listing = run(&mut powershell(VOICES_SCRIPT))?;
+    Ok(listing
+        .lines()
+        .filter_map(|line| line.trim().split_once('\t'))
+        .map(|(name, culture)| Voice::new(name, culture))
+        .collect())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn speak(text: &str, voice: Option<&str>, scratch: &Path, output: &Path) -> Result<(), String> {
+    fs::write(scratch, text).map_err(|error| format!("Failed to stage line: {}", error))?;
+    let mut command = Command::new("espeak-ng");
+    if let Some(voice) = voice {
+        command.arg("-v").arg(voice);
+    }
+    command.arg("-w").arg(output).arg("-f").arg(scratch);
+    run(&mut command).map(|_| ())
+}
+
+/// `espeak-ng --voices` prints a table whose second column is the language
+/// code `-v` accepts, which doubles as the voice name.
+#[cfg(not(any(target_os = "macos", windows)))]
+pub fn voices() -> Result<Vec<Voice>, String> {
+    let listing = run(Command::new("espeak-ng").arg("--voices"))?;
+    let mut voices = listing
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|language| Voice::new(language, language))
+        .collect::<Vec<_>>();
+    voices.dedup();
+    Ok(voices)
+}
@@ -0,0 +1,194 @@
+//! Just enough of the WAV format to join the clips each voice speaks into
+//! one file. Every supported speech engine writes uncompressed PCM.
+
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Format {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+}
+
+impl Format {
+    fn block_align(&self) -> u32 {
+        self.channels as u32 * (self.bits_per_sample as u32 / 8)
+    }
+
+    fn byte_rate(&self) -> u32 {
+        self.sample_rate * self.block_align()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    pub format: Format,
+    pub samples: Vec<u8>,
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Reads a PCM WAV file's format and sample data.
+pub fn parse(bytes: &[u8]) -> Result<Clip, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("Speech output is not a WAV file".to_string());
+    }
+
+    let mut format = None;
+    let mut at = 12;
+    while at + 8 <= bytes.len() {
+        let id = &bytes[at..at + 4];
+        // Streaming writers leave sizes unset, so clamp to what's there.
+        let size = (read_u32(bytes, at + 4) as usize).min(bytes.len() - at - 8);
+        let body = &bytes[at + 8..at + 8 + size];
+        match id {
+            b"fmt " if size >= 16 => {
+                if read_u16(body, 0) != 1 {
+                    return Err("Speech output is not uncompressed PCM".to_string());
+                }
+                format = Some(Format {
+                    channels: read_u16(body, 2),
+                    sample_rate: read_u32(body, 4),
+                    bits_per_sample: read_u16(body, 14),
+                });
+            }
+            b"data" => {
+                let format = format.ok_or("Speech output has no format chunk")?;
+                return Ok(Clip {
+                    format,
+                    samples: body.to_vec(),
+                });
+            }
+            _ => {}
+        }
+        at += 8 + size + size % 2;
+    }
+
+    Err("Speech output has no audio data".to_string())
+}
+
+/// Clips and pauses joined end to end. The first clip sets the format.
+#[derive(Debug, Default)]
+pub struct Track {
+    format: Option<Format>,
+    samples: Vec<u8>,
+    /// Pause requested before the next clip.
+    pending_pause_ms: u32,
+}
+
+impl Track {
+    pub fn push(&mut self, clip: Clip) -> Result<(), String> {
+        match self.format {
+            Some(format) if format != clip.format => {
+                return Err(format!(
+                    "Voices produced different audio formats ({} Hz and {} Hz)",
+                    format.sample_rate, clip.format.sample_rate
+                ));
+            }
+            Some(format) => {
+                let length = format.byte_rate() as usize * self.pending_pause_ms as usize / 1000;
+                let length = length - length % format.block_align().max(1) as usize;
+                self.samples.resize(self.samples.len() + length, 0);
+            }
+            None => self.format = Some(clip.format),
+        }
+        self.pending_pause_ms = 0;
+        self.samples.extend_from_slice(&clip.samples);
+        Ok(())
+    }
+
+    /// Adds silence before the next clip. Pauses don't stack; the longest
+    /// one requested wins, and trailing pauses are dropped.
+    pub fn pause(&mut self, milliseconds: u32) {
+        if self.format.is_some() {
+            self.pending_pause_ms = self.pending_pause_ms.max(milliseconds);
+        }
+    }
+
+    pub fn duration_seconds(&self) -> f32 {
+        self.format
+            .map(|format| self.samples.len() as f32 / format.byte_rate().max(1) as f32)
+            .unwrap_or(0.0)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let format = self.format.ok_or("Nothing was read aloud")?;
+        let data_size = u32::try_from(self.samples.len())
+            .map_err(|_| "The table read is too long for a WAV file".to_string())?;
+
+        let mut bytes = Vec::with_capacity(44 + self.samples.len());
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&format.channels.to_le_bytes());
+        bytes.extend_from_slice(&format.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&format.byte_rate().to_le_bytes());
+        bytes.extend_from_slice(&(format.block_align() as u16).to_le_bytes());
+        bytes.extend_from_slice(&format.bits_per_sample.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_size.to_le_bytes());
+        bytes.extend_from_slice(&self.samples);
+        Ok(bytes)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_bytes()?)
+            .map_err(|error| format!("Failed to write table read: {}", error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONO: Format = Format {
+        channels: 1,
+        sample_rate: 1000,
+        bits_per_sample: 16,
+    };
+
+    fn clip(samples: &[u8]) -> Clip {
+        Clip {
+            format: MONO,
+            samples: samples.to_vec(),
+        }
+    }
+
+    #[test]
+    fn joins_clips_with_pauses_and_round_trips() {
+        let mut track = Track::default();
+        track.pause(500);
+        track.push(clip(&[1, 1, 2, 2])).unwrap();
+        track.pause(10);
+        track.pause(5);
+        track.push(clip(&[3, 3])).unwrap();
+        track.pause(1000);
+
+        // 10ms at 1000 Hz, 16-bit mono is 10 samples of 2 bytes.
+        assert_eq!(track.samples.len(), 4 + 20 + 2);
+        assert_eq!(track.duration_seconds(), 0.013);
+
+        let parsed = parse(&track.to_bytes().unwrap()).unwrap();
+        assert_eq!(parsed.format, MONO);
+        assert_eq!(parsed.samples, track.samples);
+
+        let stereo = Clip {
+            format: Format {
+                channels: 2,
+                ..MONO
+            },
+            samples: vec![0; 4],
+        };
+        assert!(track.push(stereo).is_err());
+        assert!(parse(b"not a wav").is_err());
+    }
+}
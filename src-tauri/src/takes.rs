@@ -0,0 +1,254 @@
+use crate::{document, sidecar};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// An alternative version of a dialogue block. `content` holds the block's
+/// inline nodes, so marks survive a promote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Take {
+    pub id: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub content: Vec<Value>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TakesUpdate {
+    pub document_json: String,
+    pub node_id: String,
+    pub takes: Vec<Take>,
+}
+
+/// Stored on the document under `takes`, keyed by the dialogue block's
+/// `attrs.nodeId`.
+type TakeStore = BTreeMap<String, Vec<Take>>;
+
+fn read_store(document: &Value) -> Result<TakeStore, String> {
+    match document.get("takes") {
+        Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+            .map_err(|error| format!("Failed to parse takes: {}", error)),
+        _ => Ok(TakeStore::new()),
+    }
+}
+
+fn write_store(document: &mut Value, store: &TakeStore) -> Result<(), String> {
+    let Some(object) = document.as_object_mut() else {
+        return Err("Takes require a full document".to_string());
+    };
+
+    if store.is_empty() {
+        object.remove("takes");
+        return Ok(());
+    }
+    let value = serde_json::to_value(store)
+        .map_err(|error| format!("Failed to serialize takes: {}", error))?;
+    object.insert("takes".to_string(), value);
+    Ok(())
+}
+
+fn block_content(block: &Value) -> Vec<Value> {
+    block
+        .get("content")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn find_block<'a>(document: &'a mut Value, node_id: &str) -> Result<&'a mut Value, String> {
+    document::blocks_mut(document)
+        .and_then(|blocks| {
+            blocks
+                .iter_mut()
+                .find(|block| document::node_attr_str(block, "nodeId") == Some(node_id))
+        })
+        .ok_or_else(|| format!("Dialogue block '{}' not found", node_id))
+}
+
+/// Returns the dialogue block's node id, assigning one if it has none.
+fn ensure_node_id(document: &mut Value, block_index: usize) -> Result<String, String> {
+    let block = document::blocks_mut(document)
+        .and_then(|blocks| blocks.get_mut(block_index))
+        .ok_or_else(|| format!("Block {} not found", block_index))?;
+    if document::node_type(block) != "dialogue" {
+        return Err("Takes can only be kept for dialogue".to_string());
+    }
+    if let Some(node_id) = document::node_attr_str(block, "nodeId") {
+        return Ok(node_id.to_string());
+    }
+
+    let node_id = sidecar::next_id("dialogue");
+    if !block.get("attrs").map(Value::is_object).unwrap_or(false) {
+        block["attrs"] = Value::Object(Map::new());
+    }
+    block["attrs"]["nodeId"] = Value::String(node_id.clone());
+    Ok(node_id)
+}
+
+/// Saves a take for the dialogue block at `block_index`: `content` when
+/// given, else a copy of the block's current text.
+pub fn add(
+    document: &mut Value,
+    block_index: usize,
+    content: Option<Vec<Value>>,
+    label: Option<String>,
+) -> Result<(String, Vec<Take>), String> {
+    let node_id = ensure_node_id(document, block_index)?;
+    let content = match content {
+        Some(content) => content,
+        None => block_content(&document::blocks(document)[block_index]),
+    };
+
+    let mut store = read_store(document)?;
+    let takes = store.entry(node_id.clone()).or_default();
+    takes.push(Take {
+        id: sidecar::next_id("take"),
+        label: label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty()),
+        content,
+        created_at: Utc::now().to_rfc3339(),
+    });
+    let takes = takes.clone();
+    write_store(document, &store)?;
+    Ok((node_id, takes))
+}
+
+/// Makes a take the block's text. The text it replaces becomes a take, so
+/// promoting never loses a version.
+pub fn promote(document: &mut Value, node_id: &str, take_id: &str) -> Result<Vec<Take>, String> {
+    let mut store = read_store(document)?;
+    let takes = store
+        .get_mut(node_id)
+        .ok_or_else(|| format!("Dialogue block '{}' has no takes", node_id))?;
+    let take = takes
+        .iter_mut()
+        .find(|take| take.id == take_id)
+        .ok_or_else(|| format!("Take '{}' not found", take_id))?;
+
+    let block = find_block(document, node_id)?;
+    let current = block_content(block);
+    block["content"] = Value::Array(std::mem::replace(&mut take.content, current));
+    take.created_at = Utc::now().to_rfc3339();
+    take.label = None;
+
+    let takes = takes.clone();
+    write_store(document, &store)?;
+    Ok(takes)
+}
+
+pub fn delete(document: &mut Value, node_id: &str, take_id: &str) -> Result<Vec<Take>, String> {
+    let mut store = read_store(document)?;
+    let takes = store
+        .get_mut(node_id)
+        .ok_or_else(|| format!("Dialogue block '{}' has no takes", node_id))?;
+    let before = takes.len();
+    takes.retain(|take| take.id != take_id);
+    if takes.len() == before {
+        return Err(format!("Take '{}' not found", take_id));
+    }
+
+    let takes = takes.clone();
+    if takes.is_empty() {
+        store.remove(node_id);
+    }
+    write_store(document, &store)?;
+    Ok(takes)
+}
+
+fn to_update(document: &Value, node_id: String, takes: Vec<Take>) -> Result<TakesUpdate, String> {
+    Ok(TakesUpdate {
+        document_json: serde_json::to_string(document)
+            .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        node_id,
+        takes,
+    })
+}
+
+#[tauri::command]
+pub fn takes_list(document_json: String, node_id: String) -> Result<Vec<Take>, String> {
+    let document = document::parse_json(&document_json)?;
+    Ok(read_store(&document)?.remove(&node_id).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn takes_add(
+    document_json: String,
+    block_index: usize,
+    content: Option<Vec<Value>>,
+    label: Option<String>,
+) -> Result<TakesUpdate, String> {
+    let mut document = document::parse_json(&document_json)?;
+    let (node_id, takes) = add(&mut document, block_index, content, label)?;
+    to_update(&document, node_id, takes)
+}
+
+#[tauri::command]
+pub fn takes_promote(
+    document_json: String,
+    node_id: String,
+    take_id: String,
+) -> Result<TakesUpdate, String> {
+    let mut document = document::parse_json(&document_json)?;
+    let takes = promote(&mut document, &node_id, &take_id)?;
+    to_update(&document, node_id, takes)
+}
+
+#[tauri::command]
+pub fn takes_delete(
+    document_json: String,
+    node_id: String,
+    take_id: String,
+) -> Result<TakesUpdate, String> {
+    let mut document = document::parse_json(&document_json)?;
+    let takes = delete(&mut document, &node_id, &take_id)?;
+    to_update(&document, node_id, takes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn promoting_a_take_keeps_the_replaced_text() {
+        let mut document = json!({
+            "formatVersion": "1.0",
+            "document": {
+                "type": "doc",
+                "content": [
+                    { "type": "character", "content": [{ "type": "text", "text": "JANE" }] },
+                    { "type": "dialogue", "content": [{ "type": "text", "text": "First joke." }] },
+                ],
+            },
+        });
+
+        assert!(add(&mut document, 0, None, None).is_err());
+        let (node_id, takes) = add(
+            &mut document,
+            1,
+            Some(vec![json!({ "type": "text", "text": "Better joke." })]),
+            Some("punch-up".to_string()),
+        )
+        .unwrap();
+        assert_eq!(takes.len(), 1);
+
+        let takes = promote(&mut document, &node_id, &takes[0].id).unwrap();
+        assert_eq!(
+            document::node_text(&document::blocks(&document)[1]),
+            "Better joke."
+        );
+        assert_eq!(
+            takes[0].content,
+            vec![json!({ "type": "text", "text": "First joke." })]
+        );
+
+        delete(&mut document, &node_id, &takes[0].id).unwrap();
+        assert!(document.get("takes").is_none());
+    }
+}
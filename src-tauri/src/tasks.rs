@@ -0,0 +1,297 @@
+use crate::document;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+const TODO_MARKER: &str = "TODO:";
+
+/// A `TODO:` item found in a note (`[[ ]]` or a note block) or a boneyard
+/// (`/* */`) span.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// Derived from the task text, so it survives edits elsewhere.
+    pub id: String,
+    pub text: String,
+    pub block_index: usize,
+    /// Character offset of the `TODO:` marker in the block's text.
+    pub offset: usize,
+    /// 1-based position of the scene the task is in, if any.
+    pub scene: Option<usize>,
+    pub scene_heading: Option<String>,
+    /// `note` or `boneyard`.
+    pub source: String,
+    pub done: bool,
+}
+
+/// Stored on the document under `tasks`; only completed tasks are recorded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletedTask {
+    id: String,
+    completed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TasksUpdate {
+    pub document_json: String,
+    pub tasks: Vec<Task>,
+}
+
+/// Note or boneyard text inside one block.
+struct Segment {
+    block_index: usize,
+    /// Character offset of the segment in the block's text.
+    offset: usize,
+    text: String,
+    source: &'static str,
+}
+
+/// Collects note and boneyard text. Boneyard spans may run across blocks.
+fn segments(blocks: &[Value]) -> Vec<Segment> {
+    let mut output = Vec::new();
+    let mut in_boneyard = false;
+
+    for (block_index, block) in blocks.iter().enumerate() {
+        let text = document::node_text(block);
+        if document::node_type(block) == "note" {
+            output.push(Segment {
+                block_index,
+                offset: 0,
+                text,
+                source: "note",
+            });
+            continue;
+        }
+
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut current: Option<(usize, &'static str)> = in_boneyard.then_some((0, "boneyard"));
+        let mut index = 0;
+        while index < chars.len() {
+            let pair = (chars[index], chars.get(index + 1).copied());
+            match current {
+                None if pair == ('[', Some('[')) => current = Some((index + 2, "note")),
+                None if pair == ('/', Some('*')) => current = Some((index + 2, "boneyard")),
+                Some((start, source))
+                    if (source == "note" && pair == (']', Some(']')))
+                        || (source == "boneyard" && pair == ('*', Some('/'))) =>
+                {
+                    output.push(Segment {
+                        block_index,
+                        offset: start,
+                        text: chars[start..index].iter().collect(),
+                        source,
+                    });
+                    current = None;
+                }
+                _ => {
+                    index += 1;
+                    continue;
+                }
+            }
+            index += 2;
+        }
+
+        // Notes close within their block; an open boneyard carries on.
+        in_boneyard = matches!(current, Some((_, "boneyard")));
+        if let Some((start, source)) = current {
+            output.push(Segment {
+                block_index,
+                offset: start,
+                text: chars[start..].iter().collect(),
+                source,
+            });
+        }
+    }
+
+    output
+}
+
+fn task_id(text: &str, occurrence: usize) -> String {
+    let hash = Sha256::digest(format!("{}:{}", occurrence, text.to_uppercase()).as_bytes());
+    format!(
+        "task-{}",
+        hash.iter()
+            .take(6)
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    )
+}
+
+fn read_completed(document: &Value) -> Result<Vec<CompletedTask>, String> {
+    match document.get("tasks") {
+        Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+            .map_err(|error| format!("Failed to parse tasks: {}", error)),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn write_completed(document: &mut Value, completed: &[CompletedTask]) -> Result<(), String> {
+    let Some(object) = document.as_object_mut() else {
+        return Err("Tasks require a full document".to_string());
+    };
+
+    if completed.is_empty() {
+        object.remove("tasks");
+        return Ok(());
+    }
+    let value = serde_json::to_value(completed)
+        .map_err(|error| format!("Failed to serialize tasks: {}", error))?;
+    object.insert("tasks".to_string(), value);
+    Ok(())
+}
+
+/// Every `TODO:` item in document order, with its done flag.
+pub fn extract(document: &Value) -> Result<Vec<Task>, String> {
+    let completed = read_completed(document)?;
+    let blocks = document::blocks(document);
+    let scenes = document::scenes(document);
+    let scene_by_block = document::scene_index_by_block(&scenes, blocks.len());
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let mut tasks = Vec::new();
+
+    for segment in segments(blocks) {
+        let starts = segment
+            .text
+            .char_indices()
+            .map(|(index, _)| index)
+            .filter(|index| {
+                segment
+                    .text
+                    .get(*index..index + TODO_MARKER.len())
+                    .is_some_and(|marker| marker.eq_ignore_ascii_case(TODO_MARKER))
+            })
+            .collect::<Vec<_>>();
+
+        for (position, start) in starts.iter().enumerate() {
+            let end = starts
+                .get(position + 1)
+                .copied()
+                .unwrap_or(segment.text.len());
+            let text = segment.text[start + TODO_MARKER.len()..end]
+                .trim()
+                .to_string();
+            if text.is_empty() {
+                continue;
+            }
+
+            let occurrence = occurrences.entry(text.to_uppercase()).or_default();
+            let id = task_id(&text, *occurrence);
+            *occurrence += 1;
+
+            let scene = scene_by_block[segment.block_index];
+            tasks.push(Task {
+                done: completed.iter().any(|item| item.id == id),
+                id,
+                text,
+                block_index: segment.block_index,
+                offset: segment.offset + segment.text[..*start].chars().count(),
+                scene: scene.map(|index| index + 1),
+                scene_heading: scene.map(|index| scenes[index].heading.clone()),
+                source: segment.source.to_string(),
+            });
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// Marks a task done or open. Completions for tasks no longer in the script
+/// are dropped.
+pub fn set_done(document: &mut Value, task_id: &str, done: bool) -> Result<Vec<Task>, String> {
+    let tasks = extract(document)?;
+    if !tasks.iter().any(|task| task.id == task_id) {
+        return Err(format!("Task '{}' not found", task_id));
+    }
+
+    let mut completed = read_completed(document)?;
+    completed.retain(|item| item.id != task_id && tasks.iter().any(|task| task.id == item.id));
+    if done {
+        completed.push(CompletedTask {
+            id: task_id.to_string(),
+            completed_at: Utc::now().to_rfc3339(),
+        });
+    }
+    write_completed(document, &completed)?;
+    extract(document)
+}
+
+/// Lists `TODO:` items; done ones only with `include_done`.
+#[tauri::command]
+pub fn get_tasks(document_json: String, include_done: Option<bool>) -> Result<Vec<Task>, String> {
+    let document = document::parse_json(&document_json)?;
+    let mut tasks = extract(&document)?;
+    if !include_done.unwrap_or(false) {
+        tasks.retain(|task| !task.done);
+    }
+    Ok(tasks)
+}
+
+#[tauri::command]
+pub fn tasks_set_done(
+    document_json: String,
+    task_id: String,
+    done: bool,
+) -> Result<TasksUpdate, String> {
+    let mut document = document::parse_json(&document_json)?;
+    let tasks = set_done(&mut document, &task_id, done)?;
+    Ok(TasksUpdate {
+        document_json: serde_json::to_string(&document)
+            .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        tasks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_tasks_and_persists_done_flags() {
+        let mut document = json!({
+            "formatVersion": "1.0",
+            "document": {
+                "type": "doc",
+                "content": [
+                    block("sceneHeading", "INT. OFFICE - DAY"),
+                    block("action", "Jane types. [[TODO: fix this scene later]]"),
+                    block("action", "/* cut? todo: trim the"),
+                    block("action", "speech */ Jane leaves."),
+                    block("note", "Todo: research lawyers TODO: name the firm"),
+                ],
+            },
+        });
+
+        let tasks = extract(&document).unwrap();
+        let texts = tasks
+            .iter()
+            .map(|task| task.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec![
+                "fix this scene later",
+                "trim the",
+                "research lawyers",
+                "name the firm"
+            ]
+        );
+        assert_eq!(tasks[0].block_index, 1);
+        assert_eq!(tasks[0].offset, 14);
+        assert_eq!(tasks[0].scene, Some(1));
+        assert_eq!(tasks[1].source, "boneyard");
+
+        let updated = set_done(&mut document, &tasks[0].id, true).unwrap();
+        assert!(updated[0].done);
+        assert!(document.get("tasks").is_some());
+
+        set_done(&mut document, &tasks[0].id, false).unwrap();
+        assert!(document.get("tasks").is_none());
+        assert!(set_done(&mut document, "task-missing", true).is_err());
+    }
+}
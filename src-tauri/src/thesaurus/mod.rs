@@ -0,0 +1,293 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::OnceLock;
+
+// Hand-curated list of common screenplay words in MyThes format, bundled so
+// lookups work offline; see README.md.
+const THESAURUS_DATA: &str = include_str!("th_en_US.dat");
+const ANTONYM_MARKER: &str = "(antonym)";
+
+/// Where Linux packages and LibreOffice install the full WordNet-derived
+/// MyThes thesaurus. The first one found is used on top of the bundled list.
+#[cfg(target_os = "linux")]
+const SYSTEM_THESAURI: &[&str] = &[
+    "/usr/share/mythes/th_en_US_v2.dat",
+    "/usr/share/myspell/dicts/th_en_US_v2.dat",
+    "/usr/lib/libreoffice/share/extensions/dict-en/th_en_US_v2.dat",
+    "/opt/libreoffice/share/extensions/dict-en/th_en_US_v2.dat",
+];
+#[cfg(target_os = "macos")]
+const SYSTEM_THESAURI: &[&str] =
+    &["/Applications/LibreOffice.app/Contents/Resources/extensions/dict-en/th_en_US_v2.dat"];
+#[cfg(windows)]
+const SYSTEM_THESAURI: &[&str] = &[
+    "C:\\Program Files\\LibreOffice\\share\\extensions\\dict-en\\th_en_US_v2.dat",
+    "C:\\Program Files (x86)\\LibreOffice\\share\\extensions\\dict-en\\th_en_US_v2.dat",
+];
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const SYSTEM_THESAURI: &[&str] = &[];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynonymGroup {
+    pub part_of_speech: String,
+    pub synonyms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThesaurusEntry {
+    pub word: String,
+    pub groups: Vec<SynonymGroup>,
+    pub antonyms: Vec<String>,
+}
+
+/// MyThes files name their encoding on the first line; older ones are
+/// ISO 8859-1.
+fn decode(bytes: &[u8]) -> String {
+    if bytes.starts_with(b"UTF-8") {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|byte| char::from(*byte)).collect()
+    }
+}
+
+fn system_thesaurus() -> Option<String> {
+    SYSTEM_THESAURI
+        .iter()
+        .find_map(|path| fs::read(path).ok())
+        .map(|bytes| decode(&bytes))
+}
+
+/// The bundled list, with a full system thesaurus taking precedence where
+/// one is installed. Parsed on first use, which takes a moment for the full
+/// thesaurus.
+fn thesaurus() -> &'static HashMap<String, ThesaurusEntry> {
+    static THESAURUS: OnceLock<HashMap<String, ThesaurusEntry>> = OnceLock::new();
+    THESAURUS.get_or_init(|| {
+        let mut entries = parse_mythes(THESAURUS_DATA);
+        if let Some(data) = system_thesaurus() {
+            entries.extend(parse_mythes(&data));
+        }
+        entries
+    })
+}
+
+/// Parses MyThes `.dat` content: a `word|count` header followed by `count`
+/// lines of `(pos)|term|term...`, where antonyms carry an `(antonym)` suffix.
+/// Other suffixes, such as WordNet's `(generic term)`, are dropped.
+fn parse_mythes(data: &str) -> HashMap<String, ThesaurusEntry> {
+    let mut entries = HashMap::new();
+    let mut lines = data.lines().skip(1);
+
+    while let Some(header) = lines.next() {
+        let Some((word, count)) = header.split_once('|') else {
+            continue;
+        };
+        let count = count.trim().parse::<usize>().unwrap_or(0);
+        let mut entry = ThesaurusEntry {
+            word: word.trim().to_string(),
+            groups: Vec::new(),
+            antonyms: Vec::new(),
+        };
+
+        for line in lines.by_ref().take(count) {
+            let mut parts = line.split('|');
+            let part_of_speech = parts
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .to_string();
+            let mut synonyms = Vec::new();
+
+            for term in parts.map(str::trim).filter(|term| !term.is_empty()) {
+                if let Some(antonym) = term.strip_suffix(ANTONYM_MARKER) {
+                    let antonym = antonym.trim().to_string();
+                    if !entry.antonyms.contains(&antonym) {
+                        entry.antonyms.push(antonym);
+                    }
+                } else {
+                    let term = match term.split_once(" (") {
+                        Some((word, _)) if term.ends_with(')') => word,
+                        _ => term,
+                    };
+                    if !synonyms.iter().any(|synonym| synonym == term) {
+                        synonyms.push(term.to_string());
+                    }
+                }
+            }
+
+            if !synonyms.is_empty() {
+                entry.groups.push(SynonymGroup {
+                    part_of_speech,
+                    synonyms,
+                });
+            }
+        }
+
+        entries.insert(entry.word.to_lowercase(), entry);
+    }
+
+    entries
+}
+
+/// Strips a doubled final consonant ("runn" -> "run", "stopp" -> "stop").
+fn undouble(stem: &str) -> Option<&str> {
+    let mut chars = stem.chars().rev();
+    match (chars.next(), chars.next()) {
+        (Some(last), Some(previous)) if last == previous && !"aeiou".contains(last) => {
+            Some(&stem[..stem.len() - last.len_utf8()])
+        }
+        _ => None,
+    }
+}
+
+/// Whether a stem ends consonant-vowel-consonant, like "star" from
+/// "stared". Such a stem has usually lost a final `e`, since the word
+/// without one would have doubled its consonant ("starred").
+fn ends_short_syllable(stem: &str) -> bool {
+    let is_vowel = |c: &char| "aeiou".contains(*c);
+    let last = stem.chars().rev().take(3).collect::<Vec<_>>();
+    matches!(
+        last.as_slice(),
+        [end, vowel, start]
+            if !is_vowel(end) && !"wxy".contains(*end) && is_vowel(vowel) && !is_vowel(start)
+    )
+}
+
+/// Candidate dictionary forms for an inflected word, most likely first.
+/// Where more than one is a word ("stare" and "star" for "stares"), the
+/// order decides which entry is shown.
+fn lookup_candidates(word: &str) -> Vec<String> {
+    let mut candidates = vec![word.to_string()];
+    let mut push = |candidate: String| {
+        if candidate.len() > 1 && !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    };
+
+    for suffix in ["ies", "ied"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            push(format!("{}y", stem));
+        }
+    }
+    for suffix in ["ing", "ed"] {
+        let Some(stem) = word.strip_suffix(suffix) else {
+            continue;
+        };
+        if let Some(undoubled) = undouble(stem) {
+            push(stem.to_string());
+            push(undoubled.to_string());
+        } else if ends_short_syllable(stem) {
+            push(format!("{}e", stem));
+            push(stem.to_string());
+        } else {
+            push(stem.to_string());
+            push(format!("{}e", stem));
+        }
+    }
+    if let Some(stem) = word.strip_suffix("es") {
+        // "boxes", "watches": the `e` only belongs to the ending after a
+        // hissing sound; "stares" is "stare" plus `s`.
+        if ["s", "x", "z", "ch", "sh"]
+            .iter()
+            .any(|ending| stem.ends_with(ending))
+        {
+            push(stem.to_string());
+        }
+    }
+    if let Some(stem) = word.strip_suffix('s') {
+        push(stem.to_string());
+    }
+    if let Some(stem) = word.strip_suffix("es") {
+        push(stem.to_string());
+    }
+    if let Some(stem) = word.strip_suffix("ly") {
+        push(stem.to_string());
+    }
+
+    candidates
+}
+
+fn lookup_in(entries: &HashMap<String, ThesaurusEntry>, word: &str) -> Option<ThesaurusEntry> {
+    let normalized = word
+        .trim()
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    if normalized.is_empty() {
+        return None;
+    }
+
+    lookup_candidates(&normalized)
+        .iter()
+        .find_map(|candidate| entries.get(candidate).cloned())
+}
+
+pub fn lookup(word: &str) -> Option<ThesaurusEntry> {
+    lookup_in(thesaurus(), word)
+}
+
+/// Runs off the main thread, since the first lookup loads the thesaurus.
+#[tauri::command]
+pub async fn thesaurus_lookup(word: String) -> Result<Option<ThesaurusEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || lookup(&word))
+        .await
+        .map_err(|error| format!("Failed to look up synonyms: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Looks up in the bundled list only, whatever is installed.
+    fn lookup(word: &str) -> Option<ThesaurusEntry> {
+        static BUNDLED: OnceLock<HashMap<String, ThesaurusEntry>> = OnceLock::new();
+        lookup_in(BUNDLED.get_or_init(|| parse_mythes(THESAURUS_DATA)), word)
+    }
+
+    #[test]
+    fn groups_synonyms_and_collects_antonyms() {
+        let entry = lookup("Angry").expect("angry should be bundled");
+        assert_eq!(entry.groups.len(), 2);
+        assert_eq!(entry.groups[0].part_of_speech, "adj");
+        assert!(entry.groups[0].synonyms.contains(&"furious".to_string()));
+        assert!(entry.antonyms.contains(&"calm".to_string()));
+        assert_eq!(
+            entry.antonyms.iter().filter(|item| *item == "calm").count(),
+            1
+        );
+    }
+
+    #[test]
+    fn falls_back_to_dictionary_forms() {
+        assert_eq!(lookup("running").unwrap().word, "run");
+        assert_eq!(lookup("walked").unwrap().word, "walk");
+        assert_eq!(lookup("stares.").unwrap().word, "stare");
+        assert_eq!(lookup("stared").unwrap().word, "stare");
+        assert_eq!(lookup("starred").unwrap().word, "star");
+        assert_eq!(lookup("cried").unwrap().word, "cry");
+        assert!(lookup("zzzz").is_none());
+    }
+
+    #[test]
+    fn orders_candidates_by_likely_base_form() {
+        assert_eq!(lookup_candidates("stares"), ["stares", "stare", "star"]);
+        assert_eq!(lookup_candidates("watches"), ["watches", "watch", "watche"]);
+        assert_eq!(lookup_candidates("hoping"), ["hoping", "hope", "hop"]);
+        assert_eq!(lookup_candidates("killed"), ["killed", "kill", "kil"]);
+    }
+
+    #[test]
+    fn reads_full_mythes_data() {
+        let data = decode(b"ISO8859-1\ncaf\xe9|1\n(noun)|coffee shop|bistro (similar term)|bistro|restaurant (generic term)\n");
+        let entries = parse_mythes(&data);
+        let entry = &entries["caf\u{e9}"];
+        assert_eq!(
+            entry.groups[0].synonyms,
+            ["coffee shop", "bistro", "restaurant"]
+        );
+    }
+}
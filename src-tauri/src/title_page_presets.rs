@@ -0,0 +1,207 @@
+use crate::settings::SettingsStore;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+const MAX_PRESET_NAME_CHARS: usize = 60;
+
+/// Title page fields that stay the same from script to script.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TitlePagePreset {
+    pub name: String,
+    pub credit: Option<String>,
+    pub author: Option<String>,
+    /// Multi-line contact block: agent, address, phone, email.
+    pub contact: Option<String>,
+    pub copyright: Option<String>,
+    pub wga_registration: Option<String>,
+}
+
+/// A title page in the document's `titlePage` shape.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitlePage {
+    pub title: String,
+    pub credit: String,
+    pub author: String,
+    pub contact: String,
+    pub copyright: String,
+}
+
+fn same_name(preset: &TitlePagePreset, name: &str) -> bool {
+    preset.name.eq_ignore_ascii_case(name.trim())
+}
+
+/// Checks the presets stored in settings: names are required and unique,
+/// and the default must name one of them.
+pub fn validate(presets: &[TitlePagePreset], default: Option<&str>) -> Result<(), String> {
+    for (index, preset) in presets.iter().enumerate() {
+        let name = preset.name.trim();
+        if name.is_empty() {
+            return Err("Title page preset name is required".to_string());
+        }
+        if name.chars().count() > MAX_PRESET_NAME_CHARS {
+            return Err(format!(
+                "Title page preset names are limited to {} characters",
+                MAX_PRESET_NAME_CHARS
+            ));
+        }
+        if presets[..index]
+            .iter()
+            .any(|other| same_name(other, &preset.name))
+        {
+            return Err(format!("Duplicate title page preset '{}'", name));
+        }
+    }
+    if let Some(default) = default {
+        if !presets.iter().any(|preset| same_name(preset, default)) {
+            return Err(format!("Title page preset '{}' not found", default));
+        }
+    }
+
+    Ok(())
+}
+
+/// Fills a new title page from a preset. The WGA registration number goes
+/// under the contact block, where readers expect it.
+pub fn title_page(preset: &TitlePagePreset, title: Option<&str>) -> TitlePage {
+    let field = |value: &Option<String>| value.as_deref().unwrap_or_default().trim().to_string();
+    let mut contact = field(&preset.contact);
+    let registration = field(&preset.wga_registration);
+    if !registration.is_empty() {
+        if !contact.is_empty() {
+            contact.push('\n');
+        }
+        contact.push_str(&format!("WGA Registered #{}", registration));
+    }
+
+    TitlePage {
+        title: title.unwrap_or_default().trim().to_string(),
+        credit: preset
+            .credit
+            .as_deref()
+            .map(str::trim)
+            .filter(|credit| !credit.is_empty())
+            .unwrap_or("Written by")
+            .to_string(),
+        author: field(&preset.author),
+        contact,
+        copyright: field(&preset.copyright),
+    }
+}
+
+#[tauri::command]
+pub fn title_page_presets_list(
+    store: State<'_, SettingsStore>,
+) -> Result<Vec<TitlePagePreset>, String> {
+    Ok(store.current()?.title_page_presets)
+}
+
+/// Adds a preset or replaces the one with the same name.
+#[tauri::command]
+pub fn title_page_presets_save(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    mut preset: TitlePagePreset,
+) -> Result<Vec<TitlePagePreset>, String> {
+    preset.name = preset.name.trim().to_string();
+    let settings = store.update(&app, |settings| {
+        match settings
+            .title_page_presets
+            .iter_mut()
+            .find(|existing| same_name(existing, &preset.name))
+        {
+            Some(existing) => *existing = preset,
+            None => settings.title_page_presets.push(preset),
+        }
+    })?;
+    Ok(settings.title_page_presets)
+}
+
+#[tauri::command]
+pub fn title_page_presets_delete(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    name: String,
+) -> Result<Vec<TitlePagePreset>, String> {
+    let settings = store.update(&app, |settings| {
+        settings
+            .title_page_presets
+            .retain(|preset| !same_name(preset, &name));
+        if settings
+            .default_title_page_preset
+            .as_deref()
+            .is_some_and(|default| default.eq_ignore_ascii_case(name.trim()))
+        {
+            settings.default_title_page_preset = None;
+        }
+    })?;
+    Ok(settings.title_page_presets)
+}
+
+/// Chooses the preset applied to new documents; `None` starts them without
+/// a title page.
+#[tauri::command]
+pub fn title_page_presets_set_default(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    name: Option<String>,
+) -> Result<Option<String>, String> {
+    let settings = store.update(&app, |settings| {
+        settings.default_title_page_preset = name.map(|name| name.trim().to_string());
+    })?;
+    Ok(settings.default_title_page_preset)
+}
+
+/// Builds a title page from the named preset, or from the default preset
+/// when `name` is omitted. Returns `None` when there is no default.
+#[tauri::command]
+pub fn title_page_presets_apply(
+    store: State<'_, SettingsStore>,
+    name: Option<String>,
+    title: Option<String>,
+) -> Result<Option<TitlePage>, String> {
+    let settings = store.current()?;
+    let Some(name) = name.or(settings.default_title_page_preset) else {
+        return Ok(None);
+    };
+
+    settings
+        .title_page_presets
+        .iter()
+        .find(|preset| same_name(preset, &name))
+        .map(|preset| Some(title_page(preset, title.as_deref())))
+        .ok_or_else(|| format!("Title page preset '{}' not found", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_title_page_from_preset() {
+        let preset = TitlePagePreset {
+            name: "Agency".to_string(),
+            author: Some("Jane Doe".to_string()),
+            contact: Some("Acme Literary\n555-0100".to_string()),
+            wga_registration: Some(" 1234567 ".to_string()),
+            ..TitlePagePreset::default()
+        };
+
+        let page = title_page(&preset, Some("Nightfall"));
+        assert_eq!(page.title, "Nightfall");
+        assert_eq!(page.credit, "Written by");
+        assert_eq!(
+            page.contact,
+            "Acme Literary\n555-0100\nWGA Registered #1234567"
+        );
+
+        let duplicate = TitlePagePreset {
+            name: "agency ".to_string(),
+            ..TitlePagePreset::default()
+        };
+        assert!(validate(std::slice::from_ref(&preset), Some("Agency")).is_ok());
+        assert!(validate(&[preset.clone(), duplicate], None).is_err());
+        assert!(validate(&[preset], Some("Other")).is_err());
+    }
+}
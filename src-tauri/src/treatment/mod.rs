@@ -0,0 +1,338 @@
+//! Treatments and beat sheets: the script's outline as prose, for sending to
+//! producers without the full script. Sections become headings, and each
+//! scene is summarised by its outline card's synopsis, the synopsis notes
+//! under its heading, or else its first line of action.
+
+use crate::outline::IndexCard;
+use crate::{document, jobs, scene_numbers, sidecar};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use tauri::AppHandle;
+
+mod pdf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreatmentStyle {
+    /// Scene headings with a paragraph of prose each.
+    Treatment,
+    /// A numbered list, one line per scene.
+    BeatSheet,
+}
+
+impl TreatmentStyle {
+    pub fn parse(style: &str) -> Result<Self, String> {
+        match style {
+            "treatment" => Ok(Self::Treatment),
+            "beatSheet" => Ok(Self::BeatSheet),
+            _ => Err(format!("Unsupported treatment style '{}'", style)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreatmentFormat {
+    Pdf,
+    Markdown,
+}
+
+impl TreatmentFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.to_ascii_lowercase().as_str() {
+            "pdf" => Ok(Self::Pdf),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(format!("Unsupported treatment format '{}'", format)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Beat {
+    pub number: String,
+    pub heading: String,
+    pub summary: String,
+}
+
+/// Scenes under one section heading. The part before the first section has
+/// no heading.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreatmentPart {
+    pub heading: Option<String>,
+    /// Section depth, 1 for acts.
+    pub level: u8,
+    /// Synopsis notes written under the section heading.
+    pub summary: String,
+    pub beats: Vec<Beat>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Treatment {
+    pub title: String,
+    pub parts: Vec<TreatmentPart>,
+}
+
+/// Summary sources for the scene being read, best first.
+#[derive(Default)]
+struct PendingBeat {
+    card: Option<String>,
+    synopses: Vec<String>,
+    first_action: Option<String>,
+}
+
+impl PendingBeat {
+    fn summary(self) -> String {
+        self.card
+            .or_else(|| (!self.synopses.is_empty()).then(|| self.synopses.join(" ")))
+            .or(self.first_action)
+            .unwrap_or_default()
+    }
+}
+
+fn new_part(heading: Option<String>, level: u8) -> TreatmentPart {
+    TreatmentPart {
+        heading,
+        level,
+        summary: String::new(),
+        beats: Vec::new(),
+    }
+}
+
+pub fn build(root: &Value, title: &str, cards: &[IndexCard]) -> Treatment {
+    let mut parts = vec![new_part(None, 1)];
+    let mut pending: Option<PendingBeat> = None;
+    let mut scene_count = 0;
+
+    let finish = |parts: &mut Vec<TreatmentPart>, pending: &mut Option<PendingBeat>| {
+        if let (Some(beat), Some(part)) = (pending.take(), parts.last_mut()) {
+            if let Some(last) = part.beats.last_mut() {
+                last.summary = beat.summary();
+            }
+        }
+    };
+
+    for block in document::blocks(root) {
+        let text = document::node_text(block).trim().to_string();
+        match document::node_type(block) {
+            "section" => {
+                finish(&mut parts, &mut pending);
+                let level = block
+                    .get("attrs")
+                    .and_then(|attrs| attrs.get("level"))
+                    .and_then(Value::as_u64)
+                    .unwrap_or(1)
+                    .clamp(1, 6) as u8;
+                parts.push(new_part(Some(text), level));
+            }
+            "sceneHeading" => {
+                finish(&mut parts, &mut pending);
+                scene_count += 1;
+                let card = document::node_attr_str(block, "sceneId").and_then(|scene_id| {
+                    cards
+                        .iter()
+                        .find(|card| card.scene_id.as_deref() == Some(scene_id))
+                        .map(|card| card.synopsis.trim().to_string())
+                        .filter(|synopsis| !synopsis.is_empty())
+                });
+                if let Some(part) = parts.last_mut() {
+                    part.beats.push(Beat {
+                        number: scene_numbers::heading_number(block)
+                            .unwrap_or_else(|| scene_count.to_string()),
+                        heading: text.to_uppercase(),
+                        summary: String::new(),
+                    });
+                }
+                pending = Some(PendingBeat {
+                    card,
+                    ..PendingBeat::default()
+                });
+            }
+            "synopsis" if !text.is_empty() => match pending.as_mut() {
+                Some(beat) => beat.synopses.push(text),
+                None => {
+                    if let Some(part) = parts.last_mut() {
+                        if !part.summary.is_empty() {
+                            part.summary.push(' ');
+                        }
+                        part.summary.push_str(&text);
+                    }
+                }
+            },
+            "action" if !text.is_empty() => {
+                if let Some(beat) = pending.as_mut() {
+                    beat.first_action.get_or_insert(text);
+                }
+            }
+            _ => {}
+        }
+    }
+    finish(&mut parts, &mut pending);
+
+    parts.retain(|part| part.heading.is_some() || !part.beats.is_empty());
+    Treatment {
+        title: title.to_string(),
+        parts,
+    }
+}
+
+pub fn to_markdown(treatment: &Treatment, style: TreatmentStyle) -> String {
+    let mut out = format!("# {}\n", treatment.title);
+    if style == TreatmentStyle::BeatSheet {
+        out.push_str("\n*Beat Sheet*\n");
+    }
+
+    for part in &treatment.parts {
+        if let Some(heading) = &part.heading {
+            out.push_str(&format!(
+                "\n{} {}\n",
+                "#".repeat(part.level as usize + 1),
+                heading
+            ));
+        }
+        if !part.summary.is_empty() {
+            out.push_str(&format!("\n{}\n", part.summary));
+        }
+        if style == TreatmentStyle::BeatSheet && !part.beats.is_empty() {
+            out.push('\n');
+        }
+        for beat in &part.beats {
+            match style {
+                TreatmentStyle::Treatment => {
+                    out.push_str(&format!("\n**{}. {}**\n", beat.number, beat.heading));
+                    if !beat.summary.is_empty() {
+                        out.push_str(&format!("\n{}\n", beat.summary));
+                    }
+                }
+                TreatmentStyle::BeatSheet if beat.summary.is_empty() => {
+                    out.push_str(&format!("- **{}** {}\n", beat.number, beat.heading));
+                }
+                TreatmentStyle::BeatSheet => {
+                    out.push_str(&format!(
+                        "- **{}** {} — {}\n",
+                        beat.number, beat.heading, beat.summary
+                    ));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Builds the treatment and writes it as PDF or Markdown to `output_path`.
+/// Outline cards come from the document's sidecar when `document_id` is set.
+#[tauri::command]
+pub async fn treatment_export(
+    app: AppHandle,
+    document_id: Option<String>,
+    content_json: String,
+    document_title: Option<String>,
+    style: Option<String>,
+    format: String,
+    output_path: String,
+) -> Result<Treatment, String> {
+    let handle = app.clone();
+    jobs::run(&app, "export", "Exporting treatment", move |job| {
+        let style = TreatmentStyle::parse(style.as_deref().unwrap_or("treatment"))?;
+        let format = TreatmentFormat::parse(&format)?;
+        let root = document::parse_json(&content_json)?;
+        let cards = match document_id {
+            Some(document_id) => sidecar::load(&handle, &document_id)?.index_cards,
+            None => Vec::new(),
+        };
+        let title = document_title.unwrap_or_else(|| "Untitled".to_string());
+        let treatment = build(&root, &title, &cards);
+
+        job.check_cancelled()?;
+        job.progress(None, "Writing treatment");
+        match format {
+            TreatmentFormat::Markdown => fs::write(&output_path, to_markdown(&treatment, style))
+                .map_err(|error| format!("Failed to write treatment: {}", error))?,
+            TreatmentFormat::Pdf => pdf::write_pdf(&treatment, style, &output_path)?,
+        }
+
+        Ok(treatment)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::block;
+    use serde_json::json;
+
+    fn card(scene_id: &str, synopsis: &str) -> IndexCard {
+        IndexCard {
+            id: "card-1".to_string(),
+            title: "Card".to_string(),
+            synopsis: synopsis.to_string(),
+            color: None,
+            order: 0,
+            scene_id: Some(scene_id.to_string()),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn script() -> Value {
+        json!({
+            "type": "doc",
+            "content": [
+                { "type": "section", "attrs": { "level": 1 }, "content": [{ "type": "text", "text": "Act One" }] },
+                block("synopsis", "Jane gets the job."),
+                { "type": "sceneHeading", "attrs": { "sceneId": "s1" }, "content": [{ "type": "text", "text": "int. office - day" }] },
+                block("action", "Jane types."),
+                { "type": "sceneHeading", "attrs": { "sceneNumber": "2A" }, "content": [{ "type": "text", "text": "EXT. STREET - NIGHT" }] },
+                block("synopsis", "She walks home."),
+                block("action", "Rain."),
+                block("sceneHeading", "INT. FLAT - NIGHT"),
+                block("action", "Jane sleeps."),
+                block("character", "JANE"),
+            ],
+        })
+    }
+
+    #[test]
+    fn summarises_scenes_from_cards_synopses_and_action() {
+        let treatment = build(&script(), "Nightly", &[card("s1", "Jane starts work.")]);
+
+        assert_eq!(treatment.parts.len(), 1);
+        let part = &treatment.parts[0];
+        assert_eq!(part.heading.as_deref(), Some("Act One"));
+        assert_eq!(part.summary, "Jane gets the job.");
+        let beats = part
+            .beats
+            .iter()
+            .map(|beat| (beat.number.as_str(), beat.summary.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            beats,
+            [
+                ("1", "Jane starts work."),
+                ("2A", "She walks home."),
+                ("3", "Jane sleeps.")
+            ]
+        );
+        assert_eq!(part.beats[0].heading, "INT. OFFICE - DAY");
+    }
+
+    #[test]
+    fn writes_markdown_in_both_styles() {
+        let treatment = build(&script(), "Nightly", &[]);
+
+        let prose = to_markdown(&treatment, TreatmentStyle::Treatment);
+        assert!(prose.starts_with("# Nightly\n\n## Act One\n\nJane gets the job.\n"));
+        assert!(prose.contains("\n**1. INT. OFFICE - DAY**\n\nJane types.\n"));
+
+        let beats = to_markdown(&treatment, TreatmentStyle::BeatSheet);
+        assert!(beats.contains("- **2A** EXT. STREET - NIGHT — She walks home.\n"));
+        assert_eq!(
+            TreatmentFormat::parse("md").unwrap(),
+            TreatmentFormat::Markdown
+        );
+        assert!(TreatmentStyle::parse("novel").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Treatment, TreatmentStyle};
+use crate::pagination::wrap;
+use printpdf::*;
+use std::fs::File;
+use std::io::BufWriter;
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 72.0;
+const FONT_SIZE: f32 = 12.0;
+const LINE_HEIGHT: f32 = 14.0;
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6; // Courier is monospaced at 0.6em
+/// Beat sheet text sits to the right of the scene number.
+const BEAT_INDENT: usize = 6;
+
+struct TreatmentPdf {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    bold_font: IndirectFontRef,
+    y: f32,
+    page_number: u32,
+}
+
+impl TreatmentPdf {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(
+            title,
+            Mm::from(Pt(PAGE_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT)),
+            "Layer 1",
+        );
+        let font = doc
+            .add_builtin_font(BuiltinFont::Courier)
+            .map_err(|e| format!("Failed to add font: {}", e))?;
+        let bold_font = doc
+            .add_builtin_font(BuiltinFont::CourierBold)
+            .map_err(|e| format!("Failed to add font: {}", e))?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            font,
+            bold_font,
+            y: PAGE_HEIGHT - MARGIN,
+            page_number: 1,
+        })
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height >= MARGIN {
+            return;
+        }
+
+        let (page, layer) = self.doc.add_page(
+            Mm::from(Pt(PAGE_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT)),
+            "Layer 1",
+        );
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+        self.page_number += 1;
+
+        let label = format!("{}.", self.page_number);
+        self.layer.use_text(
+            &label,
+            FONT_SIZE,
+            Mm::from(Pt(PAGE_WIDTH - MARGIN - label.len() as f32 * CHAR_WIDTH)),
+            Mm::from(Pt(PAGE_HEIGHT - MARGIN / 2.0)),
+            &self.font,
+        );
+    }
+
+    /// Writes `text` word-wrapped to the page width, `indent` characters in
+    /// from the margin.
+    fn paragraph(&mut self, text: &str, bold: bool, indent: usize) {
+        let max_chars = ((PAGE_WIDTH - 2.0 * MARGIN) / CHAR_WIDTH) as usize;
+        let chars = text.chars().map(|c| (c, ())).collect::<Vec<_>>();
+        for line in wrap(&chars, max_chars - indent, |_, _| ()) {
+            self.ensure_space(LINE_HEIGHT);
+            let font = if bold { &self.bold_font } else { &self.font };
+            self.layer.use_text(
+                line.iter().map(|(c, _)| *c).collect::<String>(),
+                FONT_SIZE,
+                Mm::from(Pt(MARGIN + indent as f32 * CHAR_WIDTH)),
+                Mm::from(Pt(self.y - FONT_SIZE)),
+                font,
+            );
+            self.y -= LINE_HEIGHT;
+        }
+    }
+
+    /// Writes `label` at the margin on the line the next paragraph starts.
+    fn label(&mut self, label: &str) {
+        self.ensure_space(LINE_HEIGHT);
+        self.layer.use_text(
+            label,
+            FONT_SIZE,
+            Mm::from(Pt(MARGIN)),
+            Mm::from(Pt(self.y - FONT_SIZE)),
+            &self.font,
+        );
+    }
+
+    fn gap(&mut self) {
+        self.y -= LINE_HEIGHT;
+    }
+}
+
+pub fn write_pdf(
+    treatment: &Treatment,
+    style: TreatmentStyle,
+    output_path: &str,
+) -> Result<(), String> {
+    let mut pdf = TreatmentPdf::new(&treatment.title)?;
+    pdf.paragraph(&treatment.title.to_uppercase(), true, 0);
+    if style == TreatmentStyle::BeatSheet {
+        pdf.paragraph("Beat Sheet", false, 0);
+    }
+
+    for part in &treatment.parts {
+        if let Some(heading) = &part.heading {
+            pdf.gap();
+            pdf.paragraph(&heading.to_uppercase(), true, 0);
+        }
+        if !part.summary.is_empty() {
+            pdf.gap();
+            pdf.paragraph(&part.summary, false, 0);
+        }
+        if style == TreatmentStyle::BeatSheet && !part.beats.is_empty() {
+            pdf.gap();
+        }
+
+        for beat in &part.beats {
+            match style {
+                TreatmentStyle::Treatment => {
+                    pdf.gap();
+                    pdf.paragraph(&format!("{}. {}", beat.number, beat.heading), true, 0);
+                    if !beat.summary.is_empty() {
+                        pdf.paragraph(&beat.summary, false, 0);
+                    }
+                }
+                TreatmentStyle::BeatSheet => {
+                    let line = if beat.summary.is_empty() {
+                        beat.heading.clone()
+                    } else {
+                        format!("{} - {}", beat.heading, beat.summary)
+                    };
+                    pdf.label(&beat.number);
+                    pdf.paragraph(&line, false, BEAT_INDENT);
+                }
+            }
+        }
+    }
+
+    let file = File::create(output_path).map_err(|e| format!("Failed to create file: {}", e))?;
+    pdf.doc
+        .save(&mut BufWriter::new(file))
+        .map_err(|e| format!("Failed to save PDF: {}", e))
+}
@@ -0,0 +1,191 @@
+use crate::document;
+use serde::Deserialize;
+use serde_json::Value;
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartTypographyOptions {
+    #[serde(default = "default_true")]
+    pub quotes: bool,
+    #[serde(default = "default_true")]
+    pub dashes: bool,
+    #[serde(default = "default_true")]
+    pub ellipses: bool,
+}
+
+impl Default for SmartTypographyOptions {
+    fn default() -> Self {
+        Self {
+            quotes: true,
+            dashes: true,
+            ellipses: true,
+        }
+    }
+}
+
+/// Carries context between the text runs of a single block so quotes that
+/// follow a marked run still pick the right direction.
+#[derive(Default)]
+struct SmartenState {
+    previous: Option<char>,
+    in_note: bool,
+    in_boneyard: bool,
+}
+
+fn opens_quote(previous: Option<char>) -> bool {
+    match previous {
+        None => true,
+        Some(c) => {
+            c.is_whitespace()
+                || matches!(
+                    c,
+                    '(' | '[' | '{' | '\u{2014}' | '\u{2013}' | '\u{201C}' | '\u{2018}'
+                )
+        }
+    }
+}
+
+/// Converts quotes, `--` and `...` in one text run. Fountain notes (`[[ ]]`)
+/// and boneyard (`/* */`) spans are copied through untouched.
+fn smarten_run(text: &str, options: &SmartTypographyOptions, state: &mut SmartenState) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(text.len());
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+
+        if state.in_boneyard || state.in_note {
+            let closes = if state.in_boneyard {
+                c == '*' && next == Some('/')
+            } else {
+                c == ']' && next == Some(']')
+            };
+            output.push(c);
+            if closes {
+                output.push(chars[index + 1]);
+                state.in_boneyard = false;
+                state.in_note = false;
+                index += 1;
+            }
+            state.previous = Some(chars[index]);
+            index += 1;
+            continue;
+        }
+
+        if (c == '/' && next == Some('*')) || (c == '[' && next == Some('[')) {
+            state.in_boneyard = c == '/';
+            state.in_note = c == '[';
+            output.push(c);
+            output.push(chars[index + 1]);
+            state.previous = next;
+            index += 2;
+            continue;
+        }
+
+        let replacement = if options.ellipses
+            && c == '.'
+            && next == Some('.')
+            && chars.get(index + 2) == Some(&'.')
+        {
+            index += 2;
+            '\u{2026}'
+        } else if options.dashes && c == '-' && next == Some('-') {
+            index += 1;
+            '\u{2014}'
+        } else if options.quotes && c == '"' {
+            if opens_quote(state.previous) {
+                '\u{201C}'
+            } else {
+                '\u{201D}'
+            }
+        } else if options.quotes && c == '\'' {
+            if opens_quote(state.previous) {
+                '\u{2018}'
+            } else {
+                '\u{2019}'
+            }
+        } else {
+            c
+        };
+
+        output.push(replacement);
+        state.previous = Some(replacement);
+        index += 1;
+    }
+
+    output
+}
+
+fn smarten_node(node: &mut Value, options: &SmartTypographyOptions, state: &mut SmartenState) {
+    if let Some(text) = node.get("text").and_then(Value::as_str) {
+        let next = smarten_run(text, options, state);
+        node["text"] = Value::String(next);
+        return;
+    }
+
+    if let Some(children) = node.get_mut("content").and_then(Value::as_array_mut) {
+        for child in children {
+            smarten_node(child, options, state);
+        }
+    }
+}
+
+pub fn smarten_document(root: &mut Value, options: &SmartTypographyOptions) {
+    let Some(blocks) = document::blocks_mut(root) else {
+        return;
+    };
+
+    for block in blocks {
+        let mut state = SmartenState::default();
+        smarten_node(block, options, &mut state);
+    }
+}
+
+/// Built-in text transform. With the `smartTypography` setting on, the
+/// editor runs it on the pre-save and pre-export path after the
+/// plugin-contributed transforms.
+#[tauri::command]
+pub fn apply_smart_typography(
+    content_json: String,
+    options: Option<SmartTypographyOptions>,
+) -> Result<String, String> {
+    let mut root = document::parse_json(&content_json)?;
+    smarten_document(&mut root, &options.unwrap_or_default());
+
+    serde_json::to_string(&root).map_err(|error| format!("Failed to serialize document: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn smarten(text: &str) -> String {
+        smarten_run(
+            text,
+            &SmartTypographyOptions::default(),
+            &mut SmartenState::default(),
+        )
+    }
+
+    #[test]
+    fn converts_quotes_dashes_and_ellipses() {
+        assert_eq!(
+            smarten(r#"She said "don't go--wait..." ('no')"#),
+            "She said \u{201C}don\u{2019}t go\u{2014}wait\u{2026}\u{201D} (\u{2018}no\u{2019})"
+        );
+    }
+
+    #[test]
+    fn leaves_fountain_notes_and_boneyard_alone() {
+        assert_eq!(
+            smarten(r#"[[fix "this" -- later]] "ok" /* 'cut' ... */"#),
+            "[[fix \"this\" -- later]] \u{201C}ok\u{201D} /* 'cut' ... */"
+        );
+    }
+}
@@ -0,0 +1,648 @@
+//! Version store. Every save of a document is kept under app data as
+//! `versions/<documentId>/<hash>.json` together with the hash of the version
+//! it was saved over, and `HEAD` names the newest one. The parent links give
+//! two copies of a script a common ancestor to merge from, which LAN sync
+//! uses; they also let the writer get an older draft back.
+
+use crate::app_data;
+use crate::compare::merge;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use tauri::AppHandle;
+
+const VERSIONS_DIR: &str = "versions";
+const HEAD_FILE: &str = "HEAD";
+/// Where the document was last saved on this machine, so synced changes can
+/// be written back to it.
+const PATH_FILE: &str = "PATH";
+/// Versions kept per document; older ones are deleted on the next save.
+const MAX_VERSIONS: usize = 200;
+
+/// Held while a save or a sync changes a store, so the two can't move
+/// `HEAD` under each other.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StoredVersion {
+    pub parent: Option<String>,
+    /// The other version a merge brought in; `None` for plain saves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged: Option<String>,
+    pub saved_at: String,
+    pub document: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionSummary {
+    pub hash: String,
+    pub parent: Option<String>,
+    pub merged: Option<String>,
+    pub saved_at: String,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedVersion {
+    pub version: VersionSummary,
+    /// Set when changes synced from another device arrived since the editor
+    /// loaded the document: the save merged with them, and the editor should
+    /// show this document instead of its own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merged_document_json: Option<String>,
+}
+
+/// A new `HEAD` brought in by a sync or a merge.
+pub(crate) struct HeadChange {
+    pub hash: String,
+    pub conflicts: usize,
+    pub document: Value,
+}
+
+pub(crate) fn lock() -> MutexGuard<'static, ()> {
+    STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn validate_document_id(document_id: &str) -> Result<(), String> {
+    if document_id.is_empty()
+        || !document_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid document id '{}'", document_id));
+    }
+
+    Ok(())
+}
+
+fn validate_hash(hash: &str) -> Result<(), String> {
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid version '{}'", hash));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn versions_dir(app: &AppHandle, document_id: &str) -> Result<PathBuf, String> {
+    validate_document_id(document_id)?;
+    let dir = app_data::app_data_dir(app, VERSIONS_DIR)?.join(document_id);
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("Failed to create versions directory: {}", error))?;
+    Ok(dir)
+}
+
+fn document_id(document: &Value) -> Result<String, String> {
+    document
+        .get("meta")
+        .and_then(|meta| meta.get("id"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "Document has no id".to_string())
+}
+
+/// The `HEAD` of every document in the store, by document id.
+pub(crate) fn heads(app: &AppHandle) -> Result<BTreeMap<String, String>, String> {
+    let root = app_data::app_data_dir(app, VERSIONS_DIR)?;
+    let entries =
+        fs::read_dir(&root).map_err(|error| format!("Failed to read versions: {}", error))?;
+    Ok(entries
+        .flatten()
+        .filter_map(|entry| {
+            let document_id = entry.file_name().to_string_lossy().to_string();
+            validate_document_id(&document_id).ok()?;
+            Some((document_id, head(&entry.path())?))
+        })
+        .collect())
+}
+
+/// Hash of a version: its parents and its document, so the same text saved
+/// again later is a new version with its own place in the history. Plain
+/// saves leave the merged parent out, which keeps their hashes as they were
+/// before merges existed.
+fn version_hash(parent: Option<&str>, merged: Option<&str>, document: &Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(parent.unwrap_or_default().as_bytes());
+    hasher.update(b"\n");
+    if let Some(merged) = merged {
+        hasher.update(merged.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.update(document.to_string().as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub(crate) fn head(dir: &Path) -> Option<String> {
+    fs::read_to_string(dir.join(HEAD_FILE))
+        .ok()
+        .map(|head| head.trim().to_string())
+        .filter(|head| validate_hash(head).is_ok())
+}
+
+pub(crate) fn read_version(dir: &Path, hash: &str) -> Result<StoredVersion, String> {
+    validate_hash(hash)?;
+    let content = fs::read_to_string(dir.join(format!("{}.json", hash)))
+        .map_err(|error| format!("Failed to read version {}: {}", hash, error))?;
+    serde_json::from_str(&content)
+        .map_err(|error| format!("Failed to parse version {}: {}", hash, error))
+}
+
+fn summary(hash: &str, version: &StoredVersion) -> VersionSummary {
+    VersionSummary {
+        hash: hash.to_string(),
+        parent: version.parent.clone(),
+        merged: version.merged.clone(),
+        saved_at: version.saved_at.clone(),
+        title: version
+            .document
+            .get("titlePage")
+            .and_then(|title_page| title_page.get("title"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+/// Versions from `HEAD` back through their parents, newest first. The walk
+/// stops at the first version that was pruned or can't be read.
+fn history(dir: &Path) -> Vec<VersionSummary> {
+    let mut versions = Vec::new();
+    let mut next = head(dir);
+    while let Some(hash) = next {
+        let Ok(version) = read_version(dir, &hash) else {
+            break;
+        };
+        next = version.parent.clone();
+        versions.push(summary(&hash, &version));
+    }
+    versions
+}
+
+/// `from` and every version it descends from through either parent, nearest
+/// first. Parents that were pruned are listed but not followed.
+fn ancestry(dir: &Path, from: &str) -> Vec<String> {
+    let mut found = vec![from.to_string()];
+    let mut seen = HashSet::from([from.to_string()]);
+    let mut index = 0;
+    while index < found.len() {
+        if let Ok(version) = read_version(dir, &found[index]) {
+            for parent in [version.parent, version.merged].into_iter().flatten() {
+                if seen.insert(parent.clone()) {
+                    found.push(parent);
+                }
+            }
+        }
+        index += 1;
+    }
+    found
+}
+
+/// Hashes of every version stored for the document.
+pub(crate) fn stored_hashes(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let hash = name.strip_suffix(".json")?;
+            validate_hash(hash).ok()?;
+            Some(hash.to_string())
+        })
+        .collect()
+}
+
+/// Deletes every stored version past the newest `keep` reachable from
+/// `HEAD`, along with versions no longer reachable at all.
+fn prune(dir: &Path, keep: usize) -> Result<(), String> {
+    let kept = head(dir)
+        .map(|head| ancestry(dir, &head))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|hash| dir.join(format!("{}.json", hash)).exists())
+        .take(keep)
+        .map(|hash| format!("{}.json", hash))
+        .collect::<Vec<_>>();
+    let entries =
+        fs::read_dir(dir).map_err(|error| format!("Failed to read versions: {}", error))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(".json") && !kept.contains(&name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Stores `document` as the newest version on top of `HEAD`. Saving the
+/// same document as `HEAD` again records nothing.
+fn record(dir: &Path, document: Value, saved_at: String) -> Result<VersionSummary, String> {
+    let parent = head(dir);
+    if let Some(parent) = &parent {
+        if let Ok(current) = read_version(dir, parent) {
+            if current.document == document {
+                return Ok(summary(parent, &current));
+            }
+        }
+    }
+
+    let version = StoredVersion {
+        parent,
+        merged: None,
+        saved_at,
+        document,
+    };
+    let hash = write_version(dir, &version)?;
+    // Written after the version itself, so a failed save leaves the old HEAD
+    set_head(dir, &hash)?;
+    prune(dir, MAX_VERSIONS)?;
+
+    Ok(summary(&hash, &version))
+}
+
+fn write_version(dir: &Path, version: &StoredVersion) -> Result<String, String> {
+    let hash = version_hash(
+        version.parent.as_deref(),
+        version.merged.as_deref(),
+        &version.document,
+    );
+    let payload = serde_json::to_string(version)
+        .map_err(|error| format!("Failed to serialize version: {}", error))?;
+    fs::write(dir.join(format!("{}.json", hash)), payload)
+        .map_err(|error| format!("Failed to save version: {}", error))?;
+    Ok(hash)
+}
+
+fn set_head(dir: &Path, hash: &str) -> Result<(), String> {
+    fs::write(dir.join(HEAD_FILE), hash)
+        .map_err(|error| format!("Failed to update version head: {}", error))
+}
+
+/// Stores a version received from another device, after checking that its
+/// contents hash to `hash`. `HEAD` is left alone; see [`merge_head`].
+pub(crate) fn import(dir: &Path, hash: &str, version: &StoredVersion) -> Result<(), String> {
+    validate_hash(hash)?;
+    let expected = version_hash(
+        version.parent.as_deref(),
+        version.merged.as_deref(),
+        &version.document,
+    );
+    if expected != hash {
+        return Err(format!("Version {} doesn't match its contents", hash));
+    }
+    if dir.join(format!("{}.json", hash)).exists() {
+        return Ok(());
+    }
+    write_version(dir, version).map(|_| ())
+}
+
+/// The nearest version both `a` and `b` descend from that is still stored.
+fn common_ancestor(dir: &Path, a: &str, b: &str) -> Option<String> {
+    let ours = ancestry(dir, a).into_iter().collect::<HashSet<_>>();
+    ancestry(dir, b)
+        .into_iter()
+        .find(|hash| ours.contains(hash) && dir.join(format!("{}.json", hash)).exists())
+}
+
+fn fast_forward(dir: &Path, hash: &str) -> Result<HeadChange, String> {
+    let version = read_version(dir, hash)?;
+    set_head(dir, hash)?;
+    Ok(HeadChange {
+        hash: hash.to_string(),
+        conflicts: 0,
+        document: version.document,
+    })
+}
+
+/// Brings the stored version `other` into `HEAD`. When one descends from the
+/// other, `HEAD` moves to the newer; otherwise the two are merged against
+/// their common ancestor, or what they share when they have none, into a
+/// new version with both as parents. The merge
+/// orders the two by hash, so both devices merging the same pair record the
+/// same version. Returns `None` when `HEAD` already contains `other`.
+pub(crate) fn merge_head(dir: &Path, other: &str) -> Result<Option<HeadChange>, String> {
+    let Some(current) = head(dir) else {
+        return fast_forward(dir, other).map(Some);
+    };
+    if ancestry(dir, &current).iter().any(|hash| hash == other) {
+        return Ok(None);
+    }
+    if ancestry(dir, other).contains(&current) {
+        return fast_forward(dir, other).map(Some);
+    }
+
+    let base = common_ancestor(dir, &current, other);
+    let (first, second) = if current.as_str() < other {
+        (current, other.to_string())
+    } else {
+        (other.to_string(), current)
+    };
+    let first_version = read_version(dir, &first)?;
+    let second_version = read_version(dir, &second)?;
+    let base = match base {
+        Some(base) => read_version(dir, &base)?.document,
+        // Copies of one file first saved separately on each device
+        None => merge::shared_base(&first_version.document, &second_version.document),
+    };
+    let saved_at = first_version
+        .saved_at
+        .clone()
+        .max(second_version.saved_at.clone());
+    let merged = merge::merge(
+        &base,
+        &first_version.document,
+        &second_version.document,
+        &saved_at,
+    );
+
+    let version = StoredVersion {
+        parent: Some(first),
+        merged: Some(second),
+        saved_at,
+        document: merged.document,
+    };
+    let hash = write_version(dir, &version)?;
+    set_head(dir, &hash)?;
+    prune(dir, MAX_VERSIONS)?;
+    Ok(Some(HeadChange {
+        hash,
+        conflicts: merged.conflicts,
+        document: version.document,
+    }))
+}
+
+/// Stores `document`, saved by an editor that loaded `base`, when `HEAD` has
+/// moved on since: the save becomes a child of `base` and is merged into
+/// `HEAD`. Falls back to [`record`] when there's nothing to merge.
+fn record_from(
+    dir: &Path,
+    base: &str,
+    document: Value,
+    saved_at: String,
+) -> Result<(VersionSummary, Option<Value>), String> {
+    let current = head(dir);
+    let Ok(base_version) = read_version(dir, base) else {
+        return record(dir, document, saved_at).map(|summary| (summary, None));
+    };
+    if current.as_deref() == Some(base) {
+        return record(dir, document, saved_at).map(|summary| (summary, None));
+    }
+    let Some(current) = current else {
+        return record(dir, document, saved_at).map(|summary| (summary, None));
+    };
+
+    let change = if base_version.document == document {
+        // Nothing was edited since `base`, so the editor just catches up
+        fast_forward(dir, &current)?
+    } else {
+        let saved = write_version(
+            dir,
+            &StoredVersion {
+                parent: Some(base.to_string()),
+                merged: None,
+                saved_at,
+                document,
+            },
+        )?;
+        match merge_head(dir, &saved)? {
+            Some(change) => change,
+            None => fast_forward(dir, &current)?,
+        }
+    };
+    let version = read_version(dir, &change.hash)?;
+    Ok((summary(&change.hash, &version), Some(change.document)))
+}
+
+fn record_path(dir: &Path, path: &str) {
+    if let Err(error) = fs::write(dir.join(PATH_FILE), path) {
+        tracing::warn!(
+            "Failed to remember where {} was saved: {}",
+            dir.display(),
+            error
+        );
+    }
+}
+
+/// Writes `document` over the file the document was last saved to on this
+/// machine, formatted the way the editor saves it. Does nothing when the
+/// document was never saved here or the file has since moved.
+pub(crate) fn write_saved_file(dir: &Path, document: &Value) -> Result<Option<PathBuf>, String> {
+    let Ok(path) = fs::read_to_string(dir.join(PATH_FILE)) else {
+        return Ok(None);
+    };
+    let path = PathBuf::from(path.trim());
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = serde_json::to_string_pretty(document)
+        .map_err(|error| format!("Failed to serialize document: {}", error))?;
+    fs::write(&path, content)
+        .map_err(|error| format!("Failed to write {}: {}", path.display(), error))?;
+    Ok(Some(path))
+}
+
+/// Records `document_json`, the document just written to `path`, as a new
+/// version of its document. `base` is the version the editor loaded; when
+/// a sync moved `HEAD` since, the save is merged with it and the merged
+/// document is written back to `path` and returned.
+#[tauri::command]
+pub fn versions_record(
+    app: AppHandle,
+    document_json: String,
+    path: Option<String>,
+    base: Option<String>,
+) -> Result<RecordedVersion, String> {
+    let document = serde_json::from_str::<Value>(&document_json)
+        .map_err(|error| format!("Failed to parse document: {}", error))?;
+    let dir = versions_dir(&app, &document_id(&document)?)?;
+
+    let recorded = {
+        let _guard = lock();
+        if let Some(path) = &path {
+            record_path(&dir, path);
+        }
+        let (version, merged) = match base {
+            Some(base) => record_from(&dir, &base, document, Utc::now().to_rfc3339())?,
+            None => (record(&dir, document, Utc::now().to_rfc3339())?, None),
+        };
+        if let Some(merged) = &merged {
+            write_saved_file(&dir, merged)?;
+        }
+        RecordedVersion {
+            version,
+            merged_document_json: merged
+                .map(|merged| serde_json::to_string(&merged))
+                .transpose()
+                .map_err(|error| format!("Failed to serialize document: {}", error))?,
+        }
+    };
+
+    #[cfg(desktop)]
+    crate::lan_sync::request_sync(&app);
+    Ok(recorded)
+}
+
+/// The newest version of `document_id`, which the editor passes back as the
+/// base of its next save.
+#[tauri::command]
+pub fn versions_head(app: AppHandle, document_id: String) -> Result<Option<String>, String> {
+    Ok(head(&versions_dir(&app, &document_id)?))
+}
+
+/// Saved versions of `document_id`, newest first.
+#[tauri::command]
+pub fn versions_list(app: AppHandle, document_id: String) -> Result<Vec<VersionSummary>, String> {
+    Ok(history(&versions_dir(&app, &document_id)?))
+}
+
+/// The document JSON of one stored version, as it was written to disk.
+#[tauri::command]
+pub fn versions_load(app: AppHandle, document_id: String, hash: String) -> Result<String, String> {
+    let version = read_version(&versions_dir(&app, &document_id)?, &hash)?;
+    serde_json::to_string(&version.document)
+        .map_err(|error| format!("Failed to serialize version: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("grainery-versions-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn draft(text: &str) -> Value {
+        json!({
+            "meta": { "id": "doc-1" },
+            "titlePage": { "title": "Night Shift" },
+            "document": { "type": "doc", "content": [{ "type": "action", "content": [{ "type": "text", "text": text }] }] },
+        })
+    }
+
+    #[test]
+    fn records_saves_as_a_chain_of_versions() {
+        let dir = temp_dir("chain");
+        let first = record(&dir, draft("One."), "2026-01-01T00:00:00Z".to_string()).unwrap();
+        let second = record(&dir, draft("Two."), "2026-01-02T00:00:00Z".to_string()).unwrap();
+        let unchanged = record(&dir, draft("Two."), "2026-01-03T00:00:00Z".to_string()).unwrap();
+        let third = record(&dir, draft("One."), "2026-01-04T00:00:00Z".to_string()).unwrap();
+
+        assert_eq!(first.parent, None);
+        assert_eq!(second.parent.as_deref(), Some(first.hash.as_str()));
+        assert_eq!(unchanged, second);
+        // The first draft again is a new version on top of the second
+        assert_ne!(third.hash, first.hash);
+        assert_eq!(third.parent.as_deref(), Some(second.hash.as_str()));
+        assert_eq!(third.title.as_deref(), Some("Night Shift"));
+
+        let hashes = history(&dir)
+            .into_iter()
+            .map(|version| version.hash)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, vec![third.hash, second.hash.clone(), first.hash]);
+        assert_eq!(
+            read_version(&dir, &second.hash).unwrap().document,
+            draft("Two.")
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn script(lines: &[&str]) -> Value {
+        let blocks = lines
+            .iter()
+            .map(|line| json!({ "type": "action", "content": [{ "type": "text", "text": line }] }))
+            .collect::<Vec<_>>();
+        json!({ "meta": { "id": "doc-1" }, "document": { "type": "doc", "content": blocks } })
+    }
+
+    fn copy_version(from: &Path, to: &Path, hash: &str) {
+        import(to, hash, &read_version(from, hash).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn merges_diverged_copies_the_same_way_on_both_devices() {
+        let laptop = temp_dir("laptop");
+        let desktop = temp_dir("desktop");
+        let base = record(&laptop, script(&["One.", "Two."]), "1".to_string()).unwrap();
+        copy_version(&laptop, &desktop, &base.hash);
+        assert!(merge_head(&desktop, &base.hash).unwrap().is_some());
+        assert_eq!(head(&desktop), Some(base.hash.clone()));
+
+        let ours = record(&laptop, script(&["One!", "Two."]), "2".to_string()).unwrap();
+        let theirs = record(&desktop, script(&["One.", "Two!"]), "3".to_string()).unwrap();
+        copy_version(&desktop, &laptop, &theirs.hash);
+        copy_version(&laptop, &desktop, &ours.hash);
+
+        let on_laptop = merge_head(&laptop, &theirs.hash).unwrap().unwrap();
+        let on_desktop = merge_head(&desktop, &ours.hash).unwrap().unwrap();
+        assert_eq!(on_laptop.hash, on_desktop.hash);
+        assert_eq!(on_laptop.conflicts, 0);
+        assert_eq!(on_laptop.document, script(&["One!", "Two!"]));
+        // Both heads are already part of the merge
+        assert!(merge_head(&laptop, &on_desktop.hash).unwrap().is_none());
+        assert!(merge_head(&laptop, &ours.hash).unwrap().is_none());
+
+        let mut forged = read_version(&laptop, &ours.hash).unwrap();
+        forged.document = script(&["Forged."]);
+        assert!(import(&desktop, &theirs.hash, &forged).is_err());
+        let _ = fs::remove_dir_all(&laptop);
+        let _ = fs::remove_dir_all(&desktop);
+    }
+
+    #[test]
+    fn merges_a_save_over_an_outdated_base() {
+        let dir = temp_dir("rebase");
+        let base = record(&dir, script(&["One.", "Two."]), "1".to_string()).unwrap();
+        // A sync moved HEAD while the editor still had `base` open
+        let synced = record(&dir, script(&["One.", "Two!"]), "2".to_string()).unwrap();
+
+        let (saved, merged) =
+            record_from(&dir, &base.hash, script(&["One!", "Two."]), "3".to_string()).unwrap();
+        assert_eq!(merged, Some(script(&["One!", "Two!"])));
+        assert_eq!(head(&dir), Some(saved.hash.clone()));
+        assert!([&saved.parent, &saved.merged].contains(&&Some(synced.hash)));
+
+        // Saving the untouched base again just catches the editor up
+        let (current, merged) =
+            record_from(&dir, &base.hash, script(&["One.", "Two."]), "4".to_string()).unwrap();
+        assert_eq!(current, saved);
+        assert_eq!(merged, Some(script(&["One!", "Two!"])));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prunes_the_oldest_versions() {
+        let dir = temp_dir("prune");
+        for index in 0..5 {
+            record(&dir, draft(&index.to_string()), index.to_string()).unwrap();
+        }
+        prune(&dir, 3).unwrap();
+
+        let versions = history(&dir);
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].saved_at, "4");
+        // The oldest kept version's parent was deleted, so the history ends there
+        assert!(versions[2].parent.is_some());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 4);
+        assert!(read_version(&dir, "../HEAD").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
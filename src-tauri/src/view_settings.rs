@@ -0,0 +1,215 @@
+use crate::app_data::{load_json, save_json};
+use crate::menu_locale::MenuStrings;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::menu::{
+    CheckMenuItem, CheckMenuItemBuilder, MenuItem, MenuItemBuilder, Submenu, SubmenuBuilder,
+};
+use tauri::{App, AppHandle, Emitter, Manager, State, Wry};
+
+const VIEW_SETTINGS_FILE: &str = "view-settings.json";
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 3.0;
+const ZOOM_STEP: f64 = 0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ViewSettings {
+    pub zoom: f64,
+    pub focus_mode: bool,
+    pub typewriter_scrolling: bool,
+    pub sidebar_visible: bool,
+}
+
+impl Default for ViewSettings {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            focus_mode: false,
+            typewriter_scrolling: false,
+            sidebar_visible: false,
+        }
+    }
+}
+
+/// Partial update sent by the frontend, e.g. when the sidebar is closed
+/// from its own button rather than the menu.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewSettingsPatch {
+    pub zoom: Option<f64>,
+    pub focus_mode: Option<bool>,
+    pub typewriter_scrolling: Option<bool>,
+    pub sidebar_visible: Option<bool>,
+}
+
+/// Persisted view settings plus the check items that mirror them.
+pub struct ViewState {
+    settings: Mutex<ViewSettings>,
+    focus_mode: CheckMenuItem<Wry>,
+    typewriter_scrolling: CheckMenuItem<Wry>,
+    sidebar: CheckMenuItem<Wry>,
+}
+
+/// Steps the zoom level, snapping to tenths and staying within bounds.
+fn step_zoom(current: f64, steps: i32) -> f64 {
+    let next = current + ZOOM_STEP * f64::from(steps);
+    ((next * 10.0).round() / 10.0).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// Builds the View submenu and registers its state. Returns the submenu for
+/// the app menu bar.
+pub fn build_menu(app: &App, labels: &MenuStrings) -> Result<Submenu<Wry>, String> {
+    let settings = load_json::<ViewSettings>(app.handle(), VIEW_SETTINGS_FILE)?;
+    let build_error = |error: tauri::Error| format!("Failed to build View menu: {}", error);
+
+    let item = |id: &str, accelerator: &str| -> Result<MenuItem<Wry>, String> {
+        MenuItemBuilder::with_id(id, labels.get(id))
+            .accelerator(accelerator)
+            .build(app)
+            .map_err(build_error)
+    };
+    let check =
+        |id: &str, accelerator: &str, checked: bool| -> Result<CheckMenuItem<Wry>, String> {
+            CheckMenuItemBuilder::with_id(id, labels.get(id))
+                .accelerator(accelerator)
+                .checked(checked)
+                .build(app)
+                .map_err(build_error)
+        };
+
+    let zoom_in = item("zoom_in", "CmdOrCtrl+=")?;
+    let zoom_out = item("zoom_out", "CmdOrCtrl+-")?;
+    let zoom_reset = item("zoom_reset", "CmdOrCtrl+0")?;
+    let focus_mode = check("focus_mode", "CmdOrCtrl+Shift+F", settings.focus_mode)?;
+    let typewriter_scrolling = check(
+        "typewriter_scrolling",
+        "CmdOrCtrl+Shift+T",
+        settings.typewriter_scrolling,
+    )?;
+    let sidebar = check("toggle_sidebar", "CmdOrCtrl+\\", settings.sidebar_visible)?;
+
+    let menu = SubmenuBuilder::new(app, labels.get("view_menu"))
+        .item(&zoom_in)
+        .item(&zoom_out)
+        .item(&zoom_reset)
+        .separator()
+        .item(&focus_mode)
+        .item(&typewriter_scrolling)
+        .item(&sidebar)
+        .build()
+        .map_err(build_error)?;
+
+    app.manage(ViewState {
+        settings: Mutex::new(settings),
+        focus_mode,
+        typewriter_scrolling,
+        sidebar,
+    });
+
+    Ok(menu)
+}
+
+/// Restores the saved zoom level on the main window.
+pub fn restore_zoom(app: &AppHandle) {
+    let zoom = app
+        .state::<ViewState>()
+        .settings
+        .lock()
+        .map(|settings| settings.zoom)
+        .unwrap_or(1.0);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_zoom(zoom);
+    }
+}
+
+fn update(
+    app: &AppHandle,
+    state: &ViewState,
+    change: impl FnOnce(&mut ViewSettings),
+) -> Result<ViewSettings, String> {
+    let mut settings = state
+        .settings
+        .lock()
+        .map_err(|_| "View settings are unavailable".to_string())?;
+    change(&mut settings);
+    settings.zoom = settings.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    save_json(app, VIEW_SETTINGS_FILE, &*settings)?;
+
+    let _ = state.focus_mode.set_checked(settings.focus_mode);
+    let _ = state
+        .typewriter_scrolling
+        .set_checked(settings.typewriter_scrolling);
+    let _ = state.sidebar.set_checked(settings.sidebar_visible);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_zoom(settings.zoom);
+        let _ = window.emit("view-settings-changed", &*settings);
+    }
+
+    Ok(settings.clone())
+}
+
+/// Handles View menu items. Returns `false` for ids that belong elsewhere.
+pub fn handle_menu_event(app: &AppHandle, id: &str) -> bool {
+    let change: fn(&mut ViewSettings) = match id {
+        "zoom_in" => |settings| settings.zoom = step_zoom(settings.zoom, 1),
+        "zoom_out" => |settings| settings.zoom = step_zoom(settings.zoom, -1),
+        "zoom_reset" => |settings| settings.zoom = 1.0,
+        "focus_mode" => |settings| settings.focus_mode = !settings.focus_mode,
+        "typewriter_scrolling" => {
+            |settings| settings.typewriter_scrolling = !settings.typewriter_scrolling
+        }
+        "toggle_sidebar" => |settings| settings.sidebar_visible = !settings.sidebar_visible,
+        _ => return false,
+    };
+
+    if let Err(error) = update(app, &app.state::<ViewState>(), change) {
+        tracing::warn!("{}", error);
+    }
+    true
+}
+
+#[tauri::command]
+pub fn view_settings_get(state: State<'_, ViewState>) -> Result<ViewSettings, String> {
+    state
+        .settings
+        .lock()
+        .map(|settings| settings.clone())
+        .map_err(|_| "View settings are unavailable".to_string())
+}
+
+#[tauri::command]
+pub fn view_settings_update(
+    app: AppHandle,
+    state: State<'_, ViewState>,
+    patch: ViewSettingsPatch,
+) -> Result<ViewSettings, String> {
+    update(&app, &state, |settings| {
+        if let Some(zoom) = patch.zoom.filter(|zoom| zoom.is_finite()) {
+            settings.zoom = zoom;
+        }
+        if let Some(focus_mode) = patch.focus_mode {
+            settings.focus_mode = focus_mode;
+        }
+        if let Some(typewriter_scrolling) = patch.typewriter_scrolling {
+            settings.typewriter_scrolling = typewriter_scrolling;
+        }
+        if let Some(sidebar_visible) = patch.sidebar_visible {
+            settings.sidebar_visible = sidebar_visible;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_zoom_within_bounds() {
+        assert_eq!(step_zoom(1.0, 1), 1.1);
+        assert_eq!(step_zoom(1.1, -2), 0.9);
+        assert_eq!(step_zoom(0.5, -1), MIN_ZOOM);
+        assert_eq!(step_zoom(2.95, 1), MAX_ZOOM);
+    }
+}
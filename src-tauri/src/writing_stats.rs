@@ -0,0 +1,342 @@
+use crate::app_data;
+use crate::sidecar;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tauri::AppHandle;
+
+const WRITING_STATS_FILE: &str = "writing-stats.json";
+const DEFAULT_HISTORY_DAYS: u32 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritingSession {
+    pub id: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub words_added: u32,
+    pub words_removed: u32,
+    /// Time spent actually typing, excluding idle stretches.
+    pub active_seconds: u32,
+    /// Page count at the end of the session.
+    pub pages: u32,
+    /// Set when the session was a timed sprint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritingSessionInput {
+    pub started_at: String,
+    pub ended_at: String,
+    pub words_added: u32,
+    pub words_removed: u32,
+    pub active_seconds: u32,
+    pub pages: u32,
+}
+
+/// Sessions per document id, persisted as one JSON file in app data.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritingStatsStore {
+    #[serde(default)]
+    pub documents: BTreeMap<String, Vec<WritingSession>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsBucket {
+    /// First day of the bucket, `YYYY-MM-DD` in local time.
+    pub start_date: String,
+    pub words_added: u32,
+    pub words_removed: u32,
+    pub net_words: i64,
+    pub active_seconds: u32,
+    pub sessions: u32,
+    /// Highest page count reached by a session in the bucket.
+    pub pages: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsSummary {
+    pub words_today: u32,
+    pub active_seconds_today: u32,
+    /// Consecutive days with writing, ending today (or yesterday if nothing
+    /// has been written yet today).
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|error| format!("Invalid timestamp '{}': {}", value, error))
+}
+
+fn local_date(value: &str) -> Option<NaiveDate> {
+    parse_time(value)
+        .ok()
+        .map(|time| time.with_timezone(&Local).date_naive())
+}
+
+pub fn load_store(app: &AppHandle) -> Result<WritingStatsStore, String> {
+    app_data::load_json(app, WRITING_STATS_FILE)
+}
+
+/// Appends a session for `document_id` and saves the store.
+pub fn record(
+    app: &AppHandle,
+    document_id: &str,
+    input: WritingSessionInput,
+    sprint_id: Option<String>,
+) -> Result<WritingSession, String> {
+    if document_id.trim().is_empty() {
+        return Err("Document id is required".to_string());
+    }
+    if parse_time(&input.ended_at)? < parse_time(&input.started_at)? {
+        return Err("Session end must not precede its start".to_string());
+    }
+
+    let session = WritingSession {
+        id: sidecar::next_id("session"),
+        started_at: input.started_at,
+        ended_at: input.ended_at,
+        words_added: input.words_added,
+        words_removed: input.words_removed,
+        active_seconds: input.active_seconds,
+        pages: input.pages,
+        sprint_id,
+    };
+
+    let mut store = load_store(app)?;
+    store
+        .documents
+        .entry(document_id.to_string())
+        .or_default()
+        .push(session.clone());
+    app_data::save_json(app, WRITING_STATS_FILE, &store)?;
+
+    Ok(session)
+}
+
+fn sessions<'a>(
+    store: &'a WritingStatsStore,
+    document_id: Option<&'a str>,
+) -> impl Iterator<Item = &'a WritingSession> {
+    store
+        .documents
+        .iter()
+        .filter(move |(id, _)| {
+            document_id
+                .map(|wanted| wanted == id.as_str())
+                .unwrap_or(true)
+        })
+        .flat_map(|(_, sessions)| sessions.iter())
+}
+
+fn bucket_start(date: NaiveDate, weekly: bool) -> NaiveDate {
+    if weekly {
+        date - Duration::days(date.weekday().num_days_from_monday() as i64)
+    } else {
+        date
+    }
+}
+
+/// Groups sessions into daily or weekly buckets covering the last `days`
+/// days, oldest first. Days without writing produce empty buckets so the
+/// frontend can chart the series directly.
+pub fn history(
+    store: &WritingStatsStore,
+    document_id: Option<&str>,
+    weekly: bool,
+    days: u32,
+    today: NaiveDate,
+) -> Vec<StatsBucket> {
+    let first_day = today - Duration::days(days.saturating_sub(1) as i64);
+    let mut buckets: BTreeMap<NaiveDate, StatsBucket> = BTreeMap::new();
+
+    let mut day = first_day;
+    while day <= today {
+        let start = bucket_start(day, weekly);
+        buckets.entry(start).or_insert_with(|| StatsBucket {
+            start_date: start.format("%Y-%m-%d").to_string(),
+            words_added: 0,
+            words_removed: 0,
+            net_words: 0,
+            active_seconds: 0,
+            sessions: 0,
+            pages: 0,
+        });
+        day += Duration::days(1);
+    }
+
+    for session in sessions(store, document_id) {
+        let Some(date) = local_date(&session.started_at) else {
+            continue;
+        };
+        if date < first_day || date > today {
+            continue;
+        }
+
+        if let Some(bucket) = buckets.get_mut(&bucket_start(date, weekly)) {
+            bucket.words_added += session.words_added;
+            bucket.words_removed += session.words_removed;
+            bucket.net_words += session.words_added as i64 - session.words_removed as i64;
+            bucket.active_seconds += session.active_seconds;
+            bucket.sessions += 1;
+            bucket.pages = bucket.pages.max(session.pages);
+        }
+    }
+
+    buckets.into_values().collect()
+}
+
+pub fn summary(
+    store: &WritingStatsStore,
+    document_id: Option<&str>,
+    today: NaiveDate,
+) -> StatsSummary {
+    let mut words_today = 0;
+    let mut active_seconds_today = 0;
+    let mut days = BTreeSet::new();
+
+    for session in sessions(store, document_id) {
+        let Some(date) = local_date(&session.started_at) else {
+            continue;
+        };
+        if session.words_added > 0 || session.active_seconds > 0 {
+            days.insert(date);
+        }
+        if date == today {
+            words_today += session.words_added;
+            active_seconds_today += session.active_seconds;
+        }
+    }
+
+    let mut longest_streak_days = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in &days {
+        run = match previous {
+            Some(previous) if *day - previous == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest_streak_days = longest_streak_days.max(run);
+        previous = Some(*day);
+    }
+
+    let mut current_streak_days = 0;
+    let mut cursor = if days.contains(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    while days.contains(&cursor) {
+        current_streak_days += 1;
+        cursor -= Duration::days(1);
+    }
+
+    StatsSummary {
+        words_today,
+        active_seconds_today,
+        current_streak_days,
+        longest_streak_days,
+    }
+}
+
+#[tauri::command]
+pub fn stats_record_session(
+    app: AppHandle,
+    document_id: String,
+    session: WritingSessionInput,
+) -> Result<WritingSession, String> {
+    record(&app, &document_id, session, None)
+}
+
+/// Daily (default) or weekly history; `document_id` of `None` aggregates
+/// every document.
+#[tauri::command]
+pub fn stats_history(
+    app: AppHandle,
+    document_id: Option<String>,
+    period: Option<String>,
+    days: Option<u32>,
+) -> Result<Vec<StatsBucket>, String> {
+    let weekly = match period.as_deref().unwrap_or("day") {
+        "day" | "daily" => false,
+        "week" | "weekly" => true,
+        other => return Err(format!("Unsupported stats period '{}'", other)),
+    };
+    let store = load_store(&app)?;
+
+    Ok(history(
+        &store,
+        document_id.as_deref(),
+        weekly,
+        days.unwrap_or(DEFAULT_HISTORY_DAYS).max(1),
+        Local::now().date_naive(),
+    ))
+}
+
+#[tauri::command]
+pub fn stats_summary(app: AppHandle, document_id: Option<String>) -> Result<StatsSummary, String> {
+    let store = load_store(&app)?;
+    Ok(summary(
+        &store,
+        document_id.as_deref(),
+        Local::now().date_naive(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(day: NaiveDate, words_added: u32) -> WritingSession {
+        let started_at = day
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+            .to_rfc3339();
+        WritingSession {
+            id: format!("session-{}", day),
+            ended_at: started_at.clone(),
+            started_at,
+            words_added,
+            words_removed: 10,
+            active_seconds: 600,
+            pages: 3,
+            sprint_id: None,
+        }
+    }
+
+    #[test]
+    fn computes_streaks_and_daily_history() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 12).unwrap();
+        let mut store = WritingStatsStore::default();
+        store.documents.insert(
+            "doc-1".to_string(),
+            vec![
+                session(today - Duration::days(5), 100),
+                session(today - Duration::days(2), 200),
+                session(today - Duration::days(1), 300),
+                session(today, 400),
+            ],
+        );
+
+        let totals = summary(&store, None, today);
+        assert_eq!(totals.words_today, 400);
+        assert_eq!(totals.current_streak_days, 3);
+        assert_eq!(totals.longest_streak_days, 3);
+
+        let daily = history(&store, Some("doc-1"), false, 3, today);
+        assert_eq!(daily.len(), 3);
+        assert_eq!(daily[0].net_words, 190);
+        assert!(history(&store, Some("doc-2"), false, 3, today)
+            .iter()
+            .all(|bucket| bucket.sessions == 0));
+    }
+}
@@ -6,6 +6,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { ask as askDialog, message as messageDialog } from '@tauri-apps/plugin-dialog';
 
+import { ActivityIndicator } from './components/Activity';
 import { RecentDocumentsPanel, ScreenplayEditor } from './components/Editor';
 import { SettingsModal } from './components/Settings';
 import { StartScreen } from './components/StartScreen';
@@ -16,11 +17,14 @@ import {
   importFdxFile,
   openFile,
   openFileAtPath,
+  parseSyncedDocument,
   saveFile,
   saveFileAs,
   exportAsFountain,
   exportAsPdf,
+  printScreenplay,
   exportAsFdx,
+  setMenuDocumentPath,
   confirmUnsavedChanges,
   updateWindowTitle,
 } from './lib/fileOps';
@@ -28,12 +32,34 @@ import {
   type AvailableAppUpdate,
   type UpdateDownloadProgress,
   checkForAppUpdate,
+  downloadAppUpdate,
   installAppUpdate,
   recordStartupUpdateCheck,
   relaunchApp,
   shouldRunStartupUpdateCheck,
 } from './lib/appUpdates';
-import { getRecentFiles, removeRecentFile } from './lib/recentFiles';
+import { dismissCrashReport, getPendingCrashReports, sendCrashReport } from './lib/crashReports';
+import {
+  canChooseAnotherLocation,
+  describeError,
+  errorMessage,
+  isCancelledError,
+} from './lib/errors';
+import { closeJournal, openJournal, recoverJournal } from './lib/journal';
+import { answerPairing, subscribePairingRequests, subscribeSyncedDocuments } from './lib/lanSync';
+import { getMigrationStatus } from './lib/migrations';
+import { getRecentFiles, removeRecentFile, syncRecentFiles } from './lib/recentFiles';
+import { openQuickCapture, setScratchpadProject } from './lib/scratchpad';
+import { getSettings, updateSettings, type AppSettings } from './lib/settings';
+import { applySmartTypography } from './lib/typography';
+import { applyTitlePagePreset } from './lib/titlePagePresets';
+import { loadVersionBase, setVersionBase } from './lib/versions';
+import {
+  DEFAULT_VIEW_SETTINGS,
+  getViewSettings,
+  updateViewSettings,
+  type ViewSettings,
+} from './lib/viewSettings';
 import {
   getDocumentSanitizationWarning,
   sanitizeEditorDocument,
@@ -61,7 +87,12 @@ import {
   type ElementLoopPreferences,
 } from './lib/elementLoopPreferences';
 import { PluginManager } from './plugins';
-import type { RenderedInlineAnnotation, RenderedStatusBadge } from './plugins';
+import type {
+  PluginDocumentSelection,
+  PluginSelectionRange,
+  RenderedInlineAnnotation,
+  RenderedStatusBadge,
+} from './plugins';
 import { PluginUIHost } from './components/PluginUI';
 import './styles/screenplay.css';
 
@@ -187,8 +218,83 @@ function storeElementLoopPreferences(preferences: ElementLoopPreferences): void
   localStorage.setItem(ELEMENT_LOOP_PREFERENCES_STORAGE_KEY, JSON.stringify(preferences));
 }
 
-function getErrorMessage(error: unknown): string {
-  return error instanceof Error ? error.message : String(error);
+/**
+ * Asks whether to send the newest report from an earlier crash. Only the
+ * newest is offered; older ones are dismissed with it.
+ */
+async function offerPendingCrashReports(): Promise<void> {
+  const [latest, ...older] = await getPendingCrashReports();
+  if (!latest) {
+    return;
+  }
+
+  const plugins = latest.pluginIds.length > 0 ? `\n\nPlugins running at the time: ${latest.pluginIds.join(', ')}.` : '';
+  const send = await askDialog(
+    `Grainery quit unexpectedly (${latest.message}).${plugins}\n\nSend a crash report? It contains the app version, a backtrace and recent actions, never your script. It opens in your browser so you can review it before submitting.`,
+    {
+      title: 'Crash Report',
+      kind: 'warning',
+      okLabel: 'Review and Send',
+      cancelLabel: "Don't Send",
+    }
+  );
+
+  if (send) {
+    await sendCrashReport(latest.id);
+  } else {
+    await dismissCrashReport(latest.id);
+  }
+  await Promise.all(older.map((report) => dismissCrashReport(report.id)));
+}
+
+async function reportMigrationFailure(): Promise<void> {
+  const { failure } = await getMigrationStatus();
+  if (!failure) {
+    return;
+  }
+
+  const backup = failure.backupDir ? `\n\nA copy of your data from before the upgrade is in ${failure.backupDir}.` : '';
+  await messageDialog(
+    `Grainery couldn't upgrade its saved data (${failure.description}: ${failure.error}). Your settings, plugins and statistics were left as they were, but some may not load correctly in this version.${backup}`,
+    { title: 'Data Upgrade Failed', kind: 'error', okLabel: 'OK' }
+  );
+}
+
+/**
+ * Runs a save or export, showing failures with what the user can do about
+ * them. Errors a different folder can fix offer to pick one, and `write` is
+ * then called again with `retry` set.
+ */
+async function writeWithRecovery(
+  title: string,
+  write: (retry: boolean) => Promise<void>
+): Promise<void> {
+  for (let retry = false; ; retry = true) {
+    try {
+      await write(retry);
+      return;
+    } catch (error) {
+      console.error(`${title}:`, error);
+      if (isCancelledError(error)) {
+        return;
+      }
+
+      if (!canChooseAnotherLocation(error)) {
+        await messageDialog(describeError(error), { title, kind: 'error', okLabel: 'OK' });
+        return;
+      }
+
+      const chooseAnother = await askDialog(describeError(error), {
+        title,
+        kind: 'error',
+        okLabel: 'Choose a Different Folder',
+        cancelLabel: 'Cancel',
+      });
+      if (!chooseAnother) {
+        return;
+      }
+    }
+  }
 }
 
 function App() {
@@ -214,12 +320,13 @@ function App() {
   const [availableUpdate, setAvailableUpdate] = useState<AvailableAppUpdate | null>(null);
   const [updateProgress, setUpdateProgress] = useState<UpdateDownloadProgress | null>(null);
   const [updateError, setUpdateError] = useState<string | null>(null);
-  const [isRecentDocumentsPanelOpen, setIsRecentDocumentsPanelOpen] = useState(false);
+  const [viewSettings, setViewSettings] = useState<ViewSettings>(DEFAULT_VIEW_SETTINGS);
 
   const editorRef = useRef<Editor | null>(null);
   const editorContentRef = useRef<JSONContent>(document.document);
   const autoSaveTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const performAutoSaveRef = useRef<() => Promise<void>>(async () => undefined);
+  const handleNewRef = useRef<(documentMode?: DocumentMode) => Promise<void>>(async () => undefined);
   const pluginDataRef = useRef<Record<string, unknown>>(document.pluginData ?? {});
   const pluginManagerRef = useRef<PluginManager | null>(null);
   const lastTextSelectionRef = useRef<{ from: number; to: number } | null>(null);
@@ -227,6 +334,8 @@ function App() {
   const isDirtyRef = useRef(isDirty);
   const showSettingsRef = useRef(showSettings);
   const isClosingRef = useRef(false);
+  /** Editor whose document the journal was last opened for. */
+  const journaledEditorRef = useRef<Editor | null>(null);
 
   const clearQueuedAutoSave = useCallback(() => {
     if (autoSaveTimerRef.current) {
@@ -352,10 +461,70 @@ function App() {
     [document.documentMode, document.meta.filename, isDirty, queueAutoSave, showDocumentCompatibilityWarning]
   );
 
+  /** Shows a saved document that LAN sync merged with another device's copy. */
+  const showSyncedDocument = useCallback((doc: ScreenplayDocument) => {
+    const editor = editorRef.current;
+    editorContentRef.current = doc.document;
+    setDocument(doc);
+    if (editor) {
+      editor.commands.setContent(doc.document, { emitUpdate: false });
+      // The journal indexes into the editor's blocks, which were just replaced
+      void openJournal({ ...doc, document: editor.getJSON() }).catch((error) => {
+        console.error('Failed to open the document journal:', error);
+      });
+    }
+    setEditorVersion((prev) => prev + 1);
+  }, []);
+
+  /** Takes a saved document; a save that merged synced changes returns other content. */
+  const applySavedDocument = useCallback(
+    (savedDoc: ScreenplayDocument, editorContent: JSONContent) => {
+      if (savedDoc.document === editorContent) {
+        setDocument(savedDoc);
+      } else {
+        showSyncedDocument(savedDoc);
+      }
+    },
+    [showSyncedDocument]
+  );
+
+  const getSelectionForPlugin = useCallback((): PluginDocumentSelection | null => {
+    const editor = editorRef.current;
+    if (!editor) {
+      return null;
+    }
+
+    // Plugin commands usually run after focus moved to a menu or palette,
+    // so fall back to the last text selection like the editor adapter does.
+    const { from, to } = editor.state.selection;
+    const range =
+      from === to && !editor.isFocused && lastTextSelectionRef.current
+        ? lastTextSelectionRef.current
+        : { from, to };
+    const max = editor.state.doc.content.size;
+    if (range.to > max) {
+      return null;
+    }
+
+    return { ...range, text: editor.state.doc.textBetween(range.from, range.to, '\n') };
+  }, []);
+
+  const replaceSelectionFromPlugin = useCallback((range: PluginSelectionRange, text: string) => {
+    const editor = editorRef.current;
+    if (!editor) {
+      throw new Error('Editor is not available.');
+    }
+
+    editor.view.dispatch(editor.state.tr.insertText(text, range.from, range.to));
+    lastTextSelectionRef.current = null;
+  }, []);
+
   if (!pluginManagerRef.current) {
     pluginManagerRef.current = new PluginManager({
       getDocument: () => editorContentRef.current,
       replaceDocument: applyDocumentFromPlugin,
+      getSelection: getSelectionForPlugin,
+      replaceSelection: replaceSelectionFromPlugin,
       getPluginData: getPluginDataForPlugin,
       setPluginData: setPluginDataFromPlugin,
     });
@@ -501,13 +670,24 @@ function App() {
 
   const runTransformHook = useCallback(
     async (hook: 'post-open' | 'pre-save' | 'pre-export', content: JSONContent) => {
+      let transformed = content;
       try {
-        return await pluginManager.runDocumentTransforms(hook, content, {
+        transformed = await pluginManager.runDocumentTransforms(hook, content, {
           filename: document.meta.filename,
         });
       } catch (error) {
         console.error(`[Plugins] Transform hook failed (${hook})`, error);
-        return content;
+      }
+
+      if (hook === 'post-open') {
+        return transformed;
+      }
+      try {
+        const { smartTypography } = await getSettings();
+        return smartTypography ? await applySmartTypography(transformed) : transformed;
+      } catch (error) {
+        console.error(`[Typography] Smart typography failed (${hook})`, error);
+        return transformed;
       }
     },
     [document.meta.filename, pluginManager]
@@ -517,6 +697,10 @@ function App() {
     setRecentFiles(getRecentFiles());
   }, []);
 
+  useEffect(() => {
+    syncRecentFiles();
+  }, []);
+
   const prepareDocumentForEditor = useCallback(
     async (doc: ScreenplayDocument): Promise<PreparedDocument> => {
       const transformed = await runTransformHook('post-open', doc.document);
@@ -534,15 +718,38 @@ function App() {
   );
 
   const openDocumentInEditor = useCallback(
-    async ({ doc, report }: PreparedDocument) => {
+    async ({ doc: opened, report }: PreparedDocument) => {
+      let doc = opened;
+      let recovered = false;
+      try {
+        const recovery = await recoverJournal(opened.meta.id);
+        if (recovery) {
+          recovered = await askDialog(
+            `${opened.meta.filename} has ${recovery.editCount} unsaved edits from ${new Date(recovery.lastEditAt).toLocaleString()} that were not saved before Grainery closed. Recover them?`,
+            {
+              title: 'Recover Unsaved Changes',
+              kind: 'warning',
+              okLabel: 'Recover',
+              cancelLabel: 'Discard',
+            }
+          );
+          if (recovered) {
+            doc = { ...recovery.document, meta: { ...recovery.document.meta, ...opened.meta } };
+          }
+        }
+      } catch (error) {
+        console.error('Failed to read the document journal:', error);
+      }
+
       setDocument(doc);
       editorContentRef.current = doc.document;
-      setIsDirty(false);
-      setIsRecentDocumentsPanelOpen(false);
+      setIsDirty(recovered);
+      void loadVersionBase(doc.meta.id);
+      updateViewSettings({ sidebarVisible: false });
       setView('editor');
       setStartScreenError(null);
       refreshRecentFiles();
-      await updateWindowTitle(doc.meta.filename);
+      await updateWindowTitle(doc.meta.filename, recovered);
       showDocumentCompatibilityWarning(report);
     },
     [refreshRecentFiles, showDocumentCompatibilityWarning]
@@ -573,7 +780,7 @@ function App() {
       } catch (error) {
         console.error('Failed to open file at path:', error);
         if (showStartError && viewRef.current === 'start') {
-          const message = error instanceof Error ? error.message : String(error);
+          const message = errorMessage(error);
           setStartScreenError(`${errorPrefix} ${message}`);
         }
         return false;
@@ -591,14 +798,14 @@ function App() {
 
       const savedDoc = await saveFile(document, transformed);
       if (savedDoc) {
-        setDocument(savedDoc);
+        applySavedDocument(savedDoc, transformed);
         setIsDirty(false);
         await updateWindowTitle(savedDoc.meta.filename);
       }
     } catch (error) {
       console.error('Auto-save failed:', error);
     }
-  }, [document, isDirty, runTransformHook]);
+  }, [applySavedDocument, document, isDirty, runTransformHook]);
 
   useEffect(() => {
     performAutoSaveRef.current = performAutoSave;
@@ -626,15 +833,26 @@ function App() {
     }
 
     const nextDoc = createNewDocument(documentMode);
+    if (documentMode === 'screenplay') {
+      try {
+        nextDoc.titlePage = await applyTitlePagePreset();
+      } catch (error) {
+        console.error('Failed to apply title page preset:', error);
+      }
+    }
     setDocument(nextDoc);
     editorContentRef.current = nextDoc.document;
     setIsDirty(false);
-    setIsRecentDocumentsPanelOpen(false);
+    updateViewSettings({ sidebarVisible: false });
     setView('editor');
     setStartScreenError(null);
     await updateWindowTitle(null);
   }, [isDirty, view]);
 
+  useEffect(() => {
+    handleNewRef.current = handleNew;
+  }, [handleNew]);
+
   const handleOpen = useCallback(async () => {
     if (view === 'editor' && isDirty) {
       const discard = await confirmUnsavedChanges();
@@ -651,7 +869,7 @@ function App() {
     } catch (error) {
       console.error('Failed to open file:', error);
       if (view === 'start') {
-        const message = error instanceof Error ? error.message : String(error);
+        const message = errorMessage(error);
         setStartScreenError(`Failed to open file. ${message}`);
       }
     }
@@ -665,9 +883,13 @@ function App() {
 
     setView('start');
     setIsDirty(false);
-    setIsRecentDocumentsPanelOpen(false);
+    updateViewSettings({ sidebarVisible: false });
     setStartScreenError(null);
     editorRef.current = null;
+    journaledEditorRef.current = null;
+    void closeJournal().catch((error) => {
+      console.error('Failed to close the document journal:', error);
+    });
     refreshRecentFiles();
     await updateWindowTitle(null);
   }, [isDirty, refreshRecentFiles, view]);
@@ -688,7 +910,7 @@ function App() {
     } catch (error) {
       console.error('Failed to import Final Draft file:', error);
       if (view === 'start') {
-        const message = error instanceof Error ? error.message : String(error);
+        const message = errorMessage(error);
         setStartScreenError(`Failed to import Final Draft file. ${message}`);
       }
     }
@@ -714,7 +936,7 @@ function App() {
         });
       } catch (error) {
         console.error('Failed to open recent file:', error);
-        const message = error instanceof Error ? error.message : String(error);
+        const message = errorMessage(error);
         setStartScreenError(`Failed to open recent file. ${message}`);
       }
     },
@@ -743,11 +965,11 @@ function App() {
         });
 
         if (opened) {
-          setIsRecentDocumentsPanelOpen(false);
+          updateViewSettings({ sidebarVisible: false });
         }
       } catch (error) {
         console.error('Failed to open recent file:', error);
-        const message = error instanceof Error ? error.message : String(error);
+        const message = errorMessage(error);
         await askDialog(`Failed to open recent file. ${message}`, {
           title: 'Open Failed',
           kind: 'error',
@@ -759,21 +981,19 @@ function App() {
   );
 
   const handleSave = useCallback(async () => {
-    try {
+    await writeWithRecovery('Save Failed', async (retry) => {
       const transformed = await runTransformHook('pre-save', editorContentRef.current);
       editorContentRef.current = transformed;
 
-      const savedDoc = await saveFile(document, transformed);
+      const savedDoc = await (retry ? saveFileAs : saveFile)(document, transformed);
       if (savedDoc) {
-        setDocument(savedDoc);
+        applySavedDocument(savedDoc, transformed);
         setIsDirty(false);
         refreshRecentFiles();
         await updateWindowTitle(savedDoc.meta.filename);
       }
-    } catch (error) {
-      console.error('Failed to save file:', error);
-    }
-  }, [document, refreshRecentFiles, runTransformHook]);
+    });
+  }, [applySavedDocument, document, refreshRecentFiles, runTransformHook]);
 
   const saveCurrentDocument = useCallback(async (): Promise<boolean> => {
     try {
@@ -785,7 +1005,7 @@ function App() {
         return false;
       }
 
-      setDocument(savedDoc);
+      applySavedDocument(savedDoc, transformed);
       setIsDirty(false);
       refreshRecentFiles();
       await updateWindowTitle(savedDoc.meta.filename);
@@ -794,7 +1014,7 @@ function App() {
       console.error('Failed to save file:', error);
       return false;
     }
-  }, [document, refreshRecentFiles, runTransformHook]);
+  }, [applySavedDocument, document, refreshRecentFiles, runTransformHook]);
 
   const confirmQuitWithUnsavedChanges = useCallback(async (): Promise<boolean> => {
     if (!(viewRef.current === 'editor' && isDirtyRef.current)) {
@@ -889,13 +1109,22 @@ function App() {
         return;
       }
 
+      if (silent) {
+        try {
+          await downloadAppUpdate(nextUpdate, setUpdateProgress);
+        } catch (error) {
+          // Fall back to downloading when the user chooses to install.
+          console.error('Background update download failed:', error);
+        }
+      }
+
       setAvailableUpdate(nextUpdate);
       setUpdateProgress(null);
       setUpdateError(null);
       setUpdateDialogStatus('available');
       setIsUpdateDialogOpen(true);
     } catch (error) {
-      const message = getErrorMessage(error);
+      const message = errorMessage(error);
       if (silent) {
         console.error('Startup update check failed:', error);
         return;
@@ -934,34 +1163,32 @@ function App() {
       setUpdateDialogStatus('installed');
       await relaunchApp();
     } catch (error) {
-      setUpdateError(getErrorMessage(error));
+      setUpdateError(errorMessage(error));
       setUpdateDialogStatus('error');
     }
   }, [availableUpdate, confirmInstallUpdateWithUnsavedChanges]);
 
   const handleRelaunchAfterUpdate = useCallback(() => {
     void relaunchApp().catch((error) => {
-      setUpdateError(getErrorMessage(error));
+      setUpdateError(errorMessage(error));
       setUpdateDialogStatus('error');
     });
   }, []);
 
   const handleSaveAs = useCallback(async () => {
-    try {
+    await writeWithRecovery('Save Failed', async () => {
       const transformed = await runTransformHook('pre-save', editorContentRef.current);
       editorContentRef.current = transformed;
 
       const savedDoc = await saveFileAs(document, transformed);
       if (savedDoc) {
-        setDocument(savedDoc);
+        applySavedDocument(savedDoc, transformed);
         setIsDirty(false);
         refreshRecentFiles();
         await updateWindowTitle(savedDoc.meta.filename);
       }
-    } catch (error) {
-      console.error('Failed to save file:', error);
-    }
-  }, [document, refreshRecentFiles, runTransformHook]);
+    });
+  }, [applySavedDocument, document, refreshRecentFiles, runTransformHook]);
 
   const handleExportFountain = useCallback(async () => {
     if (document.documentMode !== 'screenplay') {
@@ -973,20 +1200,30 @@ function App() {
       return;
     }
 
-    try {
+    await writeWithRecovery('Fountain Export Failed', async () => {
       const transformed = await runTransformHook('pre-export', editorContentRef.current);
-      await exportAsFountain(transformed, document.titlePage, document.meta.filename);
-    } catch (error) {
-      console.error('Failed to export as Fountain:', error);
-    }
-  }, [document.documentMode, document.meta.filename, document.titlePage, runTransformHook]);
+      await exportAsFountain(transformed, document.titlePage, document.meta.filePath ?? document.meta.filename);
+    });
+  }, [document.documentMode, document.meta.filePath, document.meta.filename, document.titlePage, runTransformHook]);
 
   const handleExportPdf = useCallback(async () => {
+    await writeWithRecovery('PDF Export Failed', async () => {
+      const transformed = await runTransformHook('pre-export', editorContentRef.current);
+      await exportAsPdf(transformed, document.titlePage, document.meta.filePath ?? document.meta.filename, document.documentMode);
+    });
+  }, [document.documentMode, document.meta.filePath, document.meta.filename, document.titlePage, runTransformHook]);
+
+  const handlePrint = useCallback(async () => {
     try {
       const transformed = await runTransformHook('pre-export', editorContentRef.current);
-      await exportAsPdf(transformed, document.titlePage, document.meta.filename, document.documentMode);
+      await printScreenplay(transformed, document.titlePage, document.meta.filename, document.documentMode);
     } catch (error) {
-      console.error('Failed to export as PDF:', error);
+      console.error('Failed to print:', error);
+      await messageDialog(describeError(error), {
+        title: 'Print Failed',
+        kind: 'error',
+        okLabel: 'OK',
+      });
     }
   }, [document.documentMode, document.meta.filename, document.titlePage, runTransformHook]);
 
@@ -1000,13 +1237,11 @@ function App() {
       return;
     }
 
-    try {
+    await writeWithRecovery('Final Draft Export Failed', async () => {
       const transformed = await runTransformHook('pre-export', editorContentRef.current);
-      await exportAsFdx(transformed, document.titlePage, document.meta.filename);
-    } catch (error) {
-      console.error('Failed to export as Final Draft:', error);
-    }
-  }, [document.documentMode, document.meta.filename, document.titlePage, runTransformHook]);
+      await exportAsFdx(transformed, document.titlePage, document.meta.filePath ?? document.meta.filename);
+    });
+  }, [document.documentMode, document.meta.filePath, document.meta.filename, document.titlePage, runTransformHook]);
 
   const handleFind = useCallback(() => {
     const editor = editorRef.current;
@@ -1035,7 +1270,7 @@ function App() {
     localStorage.setItem(RECENT_DOCUMENTS_PANEL_STORAGE_KEY, String(enabled));
 
     if (!enabled) {
-      setIsRecentDocumentsPanelOpen(false);
+      updateViewSettings({ sidebarVisible: false });
     }
   }, []);
 
@@ -1046,6 +1281,9 @@ function App() {
         storeAutoSavePreferences(next);
         return next;
       });
+      void updateSettings({ autosave: { enabled } }).catch((error) => {
+        console.error('Failed to save autosave settings:', error);
+      });
 
       if (!enabled) {
         clearQueuedAutoSave();
@@ -1064,6 +1302,9 @@ function App() {
       storeAutoSavePreferences(next);
       return next;
     });
+    void updateSettings({ autosave: { intervalMs } }).catch((error) => {
+      console.error('Failed to save autosave settings:', error);
+    });
   }, []);
 
   const handleElementLoopPreferencesChange = useCallback((preferences: ElementLoopPreferences) => {
@@ -1160,10 +1401,19 @@ function App() {
     pluginManager.updateDocumentAccess({
       getDocument: () => editorContentRef.current,
       replaceDocument: applyDocumentFromPlugin,
+      getSelection: getSelectionForPlugin,
+      replaceSelection: replaceSelectionFromPlugin,
       getPluginData: getPluginDataForPlugin,
       setPluginData: setPluginDataFromPlugin,
     });
-  }, [applyDocumentFromPlugin, getPluginDataForPlugin, pluginManager, setPluginDataFromPlugin]);
+  }, [
+    applyDocumentFromPlugin,
+    getPluginDataForPlugin,
+    getSelectionForPlugin,
+    pluginManager,
+    replaceSelectionFromPlugin,
+    setPluginDataFromPlugin,
+  ]);
 
   useEffect(() => {
     let mounted = true;
@@ -1253,6 +1503,22 @@ function App() {
     };
   }, [document.meta.filename, document.meta.id, editorVersion, pluginManager, pluginStateVersion]);
 
+  useEffect(() => {
+    void setMenuDocumentPath(view === 'editor' ? document.meta.filePath : null).catch((error) => {
+      console.error('Failed to update File menu:', error);
+    });
+  }, [document.meta.filePath, view]);
+
+  // File quick captures under whichever document is open.
+  useEffect(() => {
+    void setScratchpadProject({
+      documentId: document.meta.id,
+      title: document.meta.filename ?? 'Untitled',
+    }).catch((error) => {
+      console.error('Failed to update scratchpad project:', error);
+    });
+  }, [document.meta.filename, document.meta.id]);
+
   useEffect(() => {
     const onKeyDown = (event: KeyboardEvent) => {
       void pluginManager.maybeExecuteShortcut(event);
@@ -1327,6 +1593,17 @@ function App() {
     void checkForUpdates(true);
   }, [checkForUpdates]);
 
+  useEffect(() => {
+    void reportMigrationFailure()
+      .catch((error) => {
+        console.error('Failed to check data migrations:', error);
+      })
+      .then(offerPendingCrashReports)
+      .catch((error) => {
+        console.error('Failed to check for crash reports:', error);
+      });
+  }, []);
+
   // Listen for native menu events
   useEffect(() => {
     const unlisten = listen<string>('menu-event', (event) => {
@@ -1354,6 +1631,9 @@ function App() {
         case 'start_screen':
           void handleShowStartScreen();
           break;
+        case 'quick_capture':
+          void openQuickCapture();
+          break;
         case 'save':
           void handleSave();
           break;
@@ -1369,6 +1649,9 @@ function App() {
         case 'export_fdx':
           void handleExportFdx();
           break;
+        case 'print':
+          void handlePrint();
+          break;
         case 'find':
           handleFind();
           break;
@@ -1400,6 +1683,7 @@ function App() {
     handleExportFdx,
     handleExportFountain,
     handleExportPdf,
+    handlePrint,
     handleFind,
     handleFindNext,
     handleFindPrevious,
@@ -1415,6 +1699,52 @@ function App() {
     pluginManager,
   ]);
 
+  // View menu state lives in the backend so the menu check marks survive restarts.
+  useEffect(() => {
+    void getViewSettings()
+      .then(setViewSettings)
+      .catch((error) => {
+        console.error('Failed to load view settings:', error);
+      });
+
+    const unlisten = listen<ViewSettings>('view-settings-changed', (event) => {
+      setViewSettings(event.payload);
+    });
+
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Autosave preferences live in the backend settings store; localStorage
+  // only caches them for the first render.
+  useEffect(() => {
+    const applySettings = (settings: AppSettings) => {
+      const next: AutoSavePreferences = {
+        enabled: settings.autosave.enabled,
+        intervalMs: isValidAutoSaveInterval(settings.autosave.intervalMs)
+          ? settings.autosave.intervalMs
+          : DEFAULT_AUTO_SAVE_INTERVAL_MS,
+      };
+      storeAutoSavePreferences(next);
+      setAutoSavePreferences(next);
+    };
+
+    void getSettings()
+      .then(applySettings)
+      .catch((error) => {
+        console.error('Failed to load settings:', error);
+      });
+
+    const unlisten = listen<AppSettings>('settings-changed', (event) => {
+      applySettings(event.payload);
+    });
+
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     const unlisten = listen('app-quit-requested', () => {
       void requestAppExit();
@@ -1425,7 +1755,49 @@ function App() {
     };
   }, [requestAppExit]);
 
-  // Open files when the app is launched via file association / OS open-file events.
+  // LAN sync already rewrote the file. Unsaved edits are merged with it on
+  // their next save, so only a clean editor picks the synced copy up now.
+  useEffect(() => {
+    const unlisten = subscribeSyncedDocuments((synced) => {
+      if (viewRef.current !== 'editor' || isDirtyRef.current || synced.documentId !== document.meta.id) {
+        return;
+      }
+
+      setVersionBase(synced.documentId, synced.hash);
+      showSyncedDocument(parseSyncedDocument(synced.documentJson, document.meta.filePath ?? null));
+      if (synced.conflicts > 0) {
+        void messageDialog(
+          `${synced.conflicts} ${synced.conflicts === 1 ? 'passage was' : 'passages were'} changed here and on "${synced.deviceName}". Both versions were kept and marked with a comment.`,
+          { title: 'Synced Changes', kind: 'info', okLabel: 'OK' }
+        );
+      }
+    });
+
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, [document.meta.filePath, document.meta.id, showSyncedDocument]);
+
+  useEffect(() => {
+    const unlisten = subscribePairingRequests((request) => {
+      void (async () => {
+        const accepted = await askDialog(
+          `Pair with "${request.deviceName}"? Only continue if it shows the same code: ${request.code}`,
+          { title: 'Pair Device', kind: 'info', okLabel: 'Pair', cancelLabel: 'Cancel' }
+        );
+        await answerPairing(request.pairingId, accepted);
+      })().catch((error) => {
+        console.error('Failed to answer the pairing request:', error);
+      });
+    });
+
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Open files when the app is launched via file association / OS open-file events,
+  // or dropped onto the window.
   useEffect(() => {
     const openIncomingPaths = async (paths: string[]) => {
       const firstPath = paths.find((path) => typeof path === 'string' && path.length > 0);
@@ -1448,10 +1820,26 @@ function App() {
       void openIncomingPaths(event.payload);
     });
 
+    // Files dropped on the window that Grainery can't open.
+    const unlistenRejected = listen<string[]>('app-open-file-rejected', (event) => {
+      if (!Array.isArray(event.payload) || event.payload.length === 0) {
+        return;
+      }
+
+      void messageDialog(
+        `Grainery can open .gwx, .fdx and .fountain files. Skipped: ${event.payload.join(', ')}`,
+        { title: 'Unsupported File', kind: 'info', okLabel: 'OK' }
+      );
+    });
+
     void (async () => {
       try {
         const pending = await invoke<string[]>('consume_pending_open_files');
         await openIncomingPaths(Array.isArray(pending) ? pending : []);
+        // Launched from the New Screenplay task in the Windows jump list.
+        if (await invoke<boolean>('consume_new_screenplay_request')) {
+          await handleNewRef.current('screenplay');
+        }
       } catch (error) {
         console.error('Failed to consume pending open files:', error);
       } finally {
@@ -1461,12 +1849,19 @@ function App() {
 
     return () => {
       void unlisten.then((fn) => fn());
+      void unlistenRejected.then((fn) => fn());
     };
   }, [openPathIntoEditor]);
 
   return (
     <ThemeProvider>
-      <div className="app-container">
+      <div
+        className={[
+          'app-container',
+          viewSettings.focusMode ? 'focus-mode' : '',
+          viewSettings.typewriterScrolling ? 'typewriter-scrolling' : '',
+        ].filter(Boolean).join(' ')}
+      >
         {view === 'start' ? (
           isResolvingInitialOpen ? null : (
           <StartScreen
@@ -1499,9 +1894,9 @@ function App() {
               <RecentDocumentsPanel
                 recentFiles={recentFiles}
                 currentFilePath={document.meta.filePath}
-                isOpen={isRecentDocumentsPanelOpen}
-                onToggle={() => setIsRecentDocumentsPanelOpen((prev) => !prev)}
-                onClose={() => setIsRecentDocumentsPanelOpen(false)}
+                isOpen={viewSettings.sidebarVisible}
+                onToggle={() => updateViewSettings({ sidebarVisible: !viewSettings.sidebarVisible })}
+                onClose={() => updateViewSettings({ sidebarVisible: false })}
                 onCreateDocument={() => {
                   void handleShowStartScreen();
                 }}
@@ -1527,8 +1922,16 @@ function App() {
               }}
               onEditorReady={(editor) => {
                 editorRef.current = editor;
+                if (editor && editor !== journaledEditorRef.current) {
+                  journaledEditorRef.current = editor;
+                  // Journal the blocks as the editor holds them, since edits index into them
+                  void openJournal({ ...document, document: editor.getJSON() }).catch((error) => {
+                    console.error('Failed to open the document journal:', error);
+                  });
+                }
               }}
-              showKeymapHint={keymapHintsEnabled}
+              showKeymapHint={keymapHintsEnabled && !viewSettings.focusMode}
+              typewriterScrolling={viewSettings.typewriterScrolling}
             />
 
             <PluginUIHost
@@ -1573,6 +1976,8 @@ function App() {
           />
         )}
 
+        <ActivityIndicator />
+
         {isUpdateDialogOpen && (
           <UpdateDialog
             status={updateDialogStatus}
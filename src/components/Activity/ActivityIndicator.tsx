@@ -0,0 +1,52 @@
+import { useEffect, useState } from 'react';
+import { cancelJob, isActiveJob, subscribeJobs, type JobInfo } from '../../lib/jobs';
+
+function describe(job: JobInfo): string {
+  if (job.state === 'queued') return `${job.label} (waiting)`;
+  const percent = job.progress === null ? '' : ` ${Math.round(job.progress * 100)}%`;
+  return `${job.message ?? job.label}${percent}`;
+}
+
+/** Shows running and queued backend jobs, each with a cancel button. */
+export function ActivityIndicator() {
+  const [jobs, setJobs] = useState<JobInfo[]>([]);
+
+  useEffect(() => {
+    let stop: (() => void) | undefined;
+    let cancelled = false;
+    void subscribeJobs(setJobs).then((unsubscribe) => {
+      if (cancelled) unsubscribe();
+      else stop = unsubscribe;
+    });
+    return () => {
+      cancelled = true;
+      stop?.();
+    };
+  }, []);
+
+  const active = jobs.filter(isActiveJob);
+  if (active.length === 0) return null;
+
+  return (
+    <div className="activity-indicator" role="status" aria-live="polite">
+      {active.map((job) => (
+        <div key={job.id} className="activity-job" title={job.label}>
+          <span className="activity-job-spinner" aria-hidden="true" />
+          <span className="activity-job-label">{describe(job)}</span>
+          <button
+            type="button"
+            className="activity-job-cancel"
+            aria-label={`Cancel ${job.label}`}
+            onClick={() => {
+              void cancelJob(job.id).catch((error) => {
+                console.error('[Jobs] Failed to cancel job', error);
+              });
+            }}
+          >
+            ×
+          </button>
+        </div>
+      ))}
+    </div>
+  );
+}
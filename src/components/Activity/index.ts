@@ -0,0 +1 @@
+export { ActivityIndicator } from './ActivityIndicator';
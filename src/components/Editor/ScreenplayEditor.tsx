@@ -7,7 +7,7 @@ import Bold from '@tiptap/extension-bold';
 import Italic from '@tiptap/extension-italic';
 import Underline from '@tiptap/extension-underline';
 import Strike from '@tiptap/extension-strike';
-import { useEffect, useMemo, useState } from 'react';
+import { useEffect, useMemo, useRef, useState } from 'react';
 
 import {
   SceneHeading,
@@ -27,6 +27,9 @@ import {
   BulletItem,
   NumberedItem,
   ScreenplayKeymap,
+  SnippetExpansion,
+  SmartPaste,
+  LiveDocumentSync,
   PaginationExtension,
   FindReplaceExtension,
   getFindReplaceState,
@@ -64,13 +67,16 @@ interface ScreenplayEditorProps {
   onEditorReady?: (editor: Editor | null) => void;
   showKeymapHint?: boolean;
   documentMode?: DocumentMode;
+  /** Keep the caret pinned to the middle of the viewport. */
+  typewriterScrolling?: boolean;
 }
 
 const VIEWPORT_TARGET_RATIO = 0.45;
 const VIEWPORT_UPPER_TRIGGER_RATIO = 0.28;
 const VIEWPORT_LOWER_TRIGGER_RATIO = 0.62;
+const TYPEWRITER_TARGET_RATIO = 0.5;
 
-function keepCaretNearViewportCenter(editor: Editor): void {
+function keepCaretNearViewportCenter(editor: Editor, typewriter = false): void {
   const { view } = editor;
   const { from, empty } = view.state.selection;
   if (!empty) {
@@ -91,12 +97,13 @@ function keepCaretNearViewportCenter(editor: Editor): void {
       const upperTrigger = viewportHeight * VIEWPORT_UPPER_TRIGGER_RATIO;
       const lowerTrigger = viewportHeight * VIEWPORT_LOWER_TRIGGER_RATIO;
 
-      if (caretYInViewport >= upperTrigger && caretYInViewport <= lowerTrigger) {
+      if (!typewriter && caretYInViewport >= upperTrigger && caretYInViewport <= lowerTrigger) {
         return;
       }
 
+      const targetRatio = typewriter ? TYPEWRITER_TARGET_RATIO : VIEWPORT_TARGET_RATIO;
       const targetScrollTop =
-        scrollContainer.scrollTop + caretYInViewport - viewportHeight * VIEWPORT_TARGET_RATIO;
+        scrollContainer.scrollTop + caretYInViewport - viewportHeight * targetRatio;
 
       scrollContainer.scrollTo({
         top: Math.max(0, targetScrollTop),
@@ -129,6 +136,7 @@ export function ScreenplayEditor({
   onEditorReady,
   showKeymapHint = true,
   documentMode = 'screenplay',
+  typewriterScrolling = false,
 }: ScreenplayEditorProps) {
   const [currentElement, setCurrentElement] = useState<ScreenplayElementType | null>(
     DEFAULT_ELEMENT_BY_MODE[documentMode]
@@ -137,6 +145,8 @@ export function ScreenplayEditor({
   const [previousElement, setPreviousElement] = useState<string | null>(null);
   const [isCurrentElementEmpty, setIsCurrentElementEmpty] = useState(true);
   const [isFindOpen, setIsFindOpen] = useState(false);
+  const typewriterScrollingRef = useRef(typewriterScrolling);
+  typewriterScrollingRef.current = typewriterScrolling;
   const scriptDocument = useMemo(
     () =>
       Document.extend({
@@ -196,6 +206,9 @@ export function ScreenplayEditor({
       FindReplaceExtension,
       PluginAnnotationsExtension,
       InactiveSelectionExtension,
+      SnippetExpansion,
+      ...(documentMode === 'screenplay' ? [SmartPaste] : []),
+      LiveDocumentSync,
       ScreenplayKeymap.configure({
         documentMode,
         resolveElementLoop,
@@ -216,11 +229,14 @@ export function ScreenplayEditor({
     content: initialContent || getDefaultContent(documentMode),
     onUpdate: ({ editor }) => {
       onChange?.(editor.getJSON());
-      keepCaretNearViewportCenter(editor);
+      keepCaretNearViewportCenter(editor, typewriterScrollingRef.current);
       syncElementContext(editor);
     },
     onSelectionUpdate: ({ editor }) => {
       syncElementContext(editor);
+      if (typewriterScrollingRef.current) {
+        keepCaretNearViewportCenter(editor, true);
+      }
       onSelectionChange?.();
     },
     editorProps: {
@@ -267,6 +283,12 @@ export function ScreenplayEditor({
     };
   }, [editor]);
 
+  useEffect(() => {
+    if (editor && typewriterScrolling) {
+      keepCaretNearViewportCenter(editor, true);
+    }
+  }, [editor, typewriterScrolling]);
+
   useEffect(() => {
     if (!editor) {
       return;
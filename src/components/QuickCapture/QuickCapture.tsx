@@ -0,0 +1,99 @@
+import { useCallback, useEffect, useRef, useState } from 'react';
+import type { KeyboardEvent } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { getStoredThemePreference, resolveTheme } from '../../contexts/ThemeContext';
+import {
+  appendToScratchpad,
+  closeQuickCapture,
+  getScratchpadProject,
+  type ScratchpadProject,
+} from '../../lib/scratchpad';
+
+/**
+ * Content of the always-on-top capture window. Enter (or Cmd/Ctrl+Enter)
+ * files the note, Shift+Enter adds a line, Escape dismisses.
+ */
+export function QuickCapture() {
+  const [text, setText] = useState('');
+  const [project, setProject] = useState<ScratchpadProject | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [saving, setSaving] = useState(false);
+  const textareaRef = useRef<HTMLTextAreaElement>(null);
+
+  const refresh = useCallback(() => {
+    document.documentElement.setAttribute(
+      'data-theme',
+      resolveTheme(
+        getStoredThemePreference(),
+        window.matchMedia('(prefers-color-scheme: dark)').matches,
+      ),
+    );
+    setError(null);
+    textareaRef.current?.focus();
+    void getScratchpadProject()
+      .then(setProject)
+      .catch(() => setProject(null));
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen('quick-capture-opened', refresh);
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, [refresh]);
+
+  const dismiss = useCallback(() => {
+    setError(null);
+    void closeQuickCapture();
+  }, []);
+
+  const submit = useCallback(async () => {
+    if (saving || !text.trim()) {
+      return;
+    }
+
+    setSaving(true);
+    try {
+      await appendToScratchpad(text);
+      setText('');
+      await closeQuickCapture();
+    } catch (captureError) {
+      setError(String(captureError));
+    } finally {
+      setSaving(false);
+    }
+  }, [saving, text]);
+
+  const handleKeyDown = (event: KeyboardEvent<HTMLTextAreaElement>) => {
+    if (event.key === 'Escape') {
+      event.preventDefault();
+      dismiss();
+    } else if (event.key === 'Enter' && !event.shiftKey) {
+      event.preventDefault();
+      void submit();
+    }
+  };
+
+  return (
+    <div className="flex h-screen flex-col gap-2 bg-base-100 p-3 text-base-content" data-tauri-drag-region>
+      <div className="flex items-center justify-between text-xs opacity-70" data-tauri-drag-region>
+        <span data-tauri-drag-region>
+          Scratchpad: {project?.title ?? 'Inbox'}
+        </span>
+        <span data-tauri-drag-region>Enter to save · Esc to close</span>
+      </div>
+      <textarea
+        ref={textareaRef}
+        className="textarea textarea-bordered w-full flex-1 resize-none text-sm"
+        value={text}
+        onChange={(event) => setText(event.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder="Jot an idea..."
+        autoFocus
+        disabled={saving}
+      />
+      {error && <p className="text-xs text-error">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1 @@
+export { QuickCapture } from './QuickCapture';
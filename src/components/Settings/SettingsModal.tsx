@@ -1,5 +1,5 @@
 import { useEffect, useMemo, useState } from 'react';
-import { confirm, open } from '@tauri-apps/plugin-dialog';
+import { confirm, open, save } from '@tauri-apps/plugin-dialog';
 import { useTheme, THEMES, Theme } from '../../contexts/ThemeContext';
 import { TitlePagePreviewPage } from '../TitlePage';
 import {
@@ -15,10 +15,35 @@ import {
 import type { DocumentMode, ScreenplayElementType, TitlePageData } from '../../lib/types';
 import type {
   OptionalPermission,
+  PluginChannel,
+  PluginDiskUsage,
   PluginPermissionGrant,
 } from '../../plugins';
 import { PluginManager } from '../../plugins';
 import { PERMISSION_DESCRIPTIONS } from '../../plugins/permissions';
+import { backupAppData, restoreAppData } from '../../lib/appBackup';
+import { relaunchApp } from '../../lib/appUpdates';
+import { errorMessage } from '../../lib/errors';
+import {
+  getLanSyncStatus,
+  pairDevice,
+  renameSyncDevice,
+  setLanSyncEnabled,
+  subscribeLanSyncStatus,
+  syncNow,
+  unpairDevice,
+  type LanSyncStatus,
+} from '../../lib/lanSync';
+import { getRecentLogLines, openLogFolder } from '../../lib/logs';
+import { getMenuLocale, setMenuLocale, type MenuLocaleInfo } from '../../lib/menuLocale';
+import { getSettings, updateSettings } from '../../lib/settings';
+import {
+  applyShortcuts,
+  resetShortcut,
+  setShortcut,
+  type ShortcutBinding,
+  type ShortcutOverview,
+} from '../../lib/shortcuts';
 
 interface SettingsModalProps {
   onClose: () => void;
@@ -39,7 +64,38 @@ interface SettingsModalProps {
   onElementLoopPreferencesChange: (preferences: ElementLoopPreferences) => void;
 }
 
-type SettingsTab = 'theme' | 'editor' | 'smart-loop' | 'title-page' | 'plugins';
+type SettingsTab = 'theme' | 'editor' | 'smart-loop' | 'title-page' | 'shortcuts' | 'plugins';
+
+const SHORTCUT_LABELS: Record<string, string> = {
+  new: 'New',
+  open: 'Open',
+  save: 'Save',
+  save_as: 'Save As',
+  export_fountain: 'Export as Fountain',
+  export_pdf: 'Export as PDF',
+  print: 'Print',
+  settings: 'Settings',
+  find: 'Find',
+  find_next: 'Find Next',
+  find_prev: 'Find Previous',
+  replace: 'Replace',
+  zoom_in: 'Zoom In',
+  zoom_out: 'Zoom Out',
+  zoom_reset: 'Actual Size',
+  focus_mode: 'Focus Mode',
+  typewriter_scrolling: 'Typewriter Scrolling',
+  toggle_sidebar: 'Toggle Sidebar',
+  quick_capture: 'Quick Capture',
+};
+
+const PLUGIN_COMMAND_PREFIX = 'plugin:command:';
+
+function getShortcutLabel(command: string): string {
+  if (command.startsWith(PLUGIN_COMMAND_PREFIX)) {
+    return command.slice(PLUGIN_COMMAND_PREFIX.length);
+  }
+  return SHORTCUT_LABELS[command] ?? command;
+}
 
 const EMPTY_TITLE_PAGE: TitlePageData = {
   title: '',
@@ -69,6 +125,16 @@ function capitalizeTheme(theme: string): string {
   return theme.charAt(0).toUpperCase() + theme.slice(1);
 }
 
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) {
+    return `${bytes} B`;
+  }
+  if (bytes < 1024 * 1024) {
+    return `${(bytes / 1024).toFixed(1)} KB`;
+  }
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 function normalizeTitlePage(titlePage: TitlePageData): TitlePageData | null {
   if (titlePage.title.trim() || titlePage.author.trim()) {
     return titlePage;
@@ -77,7 +143,17 @@ function normalizeTitlePage(titlePage: TitlePageData): TitlePageData | null {
   return null;
 }
 
-function ThemeCard({ t, isSelected, onClick }: { t: Theme; isSelected: boolean; onClick: () => void }) {
+function ThemeCard({
+  t,
+  label,
+  isSelected,
+  onClick,
+}: {
+  t: Theme;
+  label?: string;
+  isSelected: boolean;
+  onClick: () => void;
+}) {
   return (
     <button
       type="button"
@@ -91,7 +167,7 @@ function ThemeCard({ t, isSelected, onClick }: { t: Theme; isSelected: boolean;
         <span className="bg-accent" />
       </div>
       <div className="settings-theme-card-label">
-        {capitalizeTheme(t)}
+        {label ?? capitalizeTheme(t)}
       </div>
     </button>
   );
@@ -170,12 +246,23 @@ export function SettingsModal({
   elementLoopPreferences,
   onElementLoopPreferencesChange,
 }: SettingsModalProps) {
-  const { theme, setTheme } = useTheme();
+  const { theme, preference, systemTheme, setTheme } = useTheme();
   const [activeTab, setActiveTab] = useState<SettingsTab>('theme');
   const [titlePageForm, setTitlePageForm] = useState<TitlePageData>(titlePage || EMPTY_TITLE_PAGE);
   const [selectedLoopMode, setSelectedLoopMode] = useState<DocumentMode>(documentMode);
   const [isBusy, setIsBusy] = useState(false);
   const [pluginError, setPluginError] = useState<string | null>(null);
+  const [diskUsage, setDiskUsage] = useState<Record<string, PluginDiskUsage>>({});
+  const [menuLocale, setMenuLocaleInfo] = useState<MenuLocaleInfo | null>(null);
+  const [smartTypography, setSmartTypography] = useState(false);
+  const [shortcutOverview, setShortcutOverview] = useState<ShortcutOverview | null>(null);
+  const [shortcutDrafts, setShortcutDrafts] = useState<Record<string, string>>({});
+  const [shortcutError, setShortcutError] = useState<string | null>(null);
+  const [logStatus, setLogStatus] = useState<string | null>(null);
+  const [backupStatus, setBackupStatus] = useState<string | null>(null);
+  const [lanSync, setLanSync] = useState<LanSyncStatus | null>(null);
+  const [deviceNameDraft, setDeviceNameDraft] = useState('');
+  const [lanSyncError, setLanSyncError] = useState<string | null>(null);
   const isScreenplayDocument = documentMode === 'screenplay';
 
   const plugins = useMemo(() => pluginManager.getInstalledPlugins(), [pluginManager, pluginStateVersion]);
@@ -189,6 +276,231 @@ export function SettingsModal({
     setTitlePageForm(titlePage || EMPTY_TITLE_PAGE);
   }, [titlePage]);
 
+  useEffect(() => {
+    let cancelled = false;
+    void getMenuLocale()
+      .then((info) => {
+        if (!cancelled) {
+          setMenuLocaleInfo(info);
+        }
+      })
+      .catch((error) => {
+        console.error('[SettingsModal] Failed to load menu language', error);
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  useEffect(() => {
+    let cancelled = false;
+    void getSettings()
+      .then((settings) => {
+        if (!cancelled) {
+          setSmartTypography(settings.smartTypography);
+        }
+      })
+      .catch((error) => {
+        console.error('[SettingsModal] Failed to load settings', error);
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  // LAN sync only exists on desktop; elsewhere the section stays hidden
+  useEffect(() => {
+    let cancelled = false;
+    const applyStatus = (status: LanSyncStatus) => {
+      if (!cancelled) {
+        setLanSync(status);
+        setDeviceNameDraft(status.deviceName);
+      }
+    };
+
+    void getLanSyncStatus()
+      .then(applyStatus)
+      .catch((error) => {
+        console.error('[SettingsModal] Failed to load LAN sync status', error);
+      });
+    const unlisten = subscribeLanSyncStatus(applyStatus);
+
+    return () => {
+      cancelled = true;
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const runLanSync = (action: () => Promise<LanSyncStatus | void>) => {
+    setLanSyncError(null);
+    void action()
+      .then((status) => {
+        if (status) {
+          setLanSync(status);
+          setDeviceNameDraft(status.deviceName);
+        }
+      })
+      .catch((error) => {
+        setLanSyncError(errorMessage(error));
+      });
+  };
+
+  const handleDeviceNameCommit = () => {
+    if (!lanSync || deviceNameDraft.trim() === lanSync.deviceName) {
+      return;
+    }
+    runLanSync(() => renameSyncDevice(deviceNameDraft));
+  };
+
+  const handleSmartTypographyChange = (enabled: boolean) => {
+    setSmartTypography(enabled);
+    void updateSettings({ smartTypography: enabled }).catch((error) => {
+      console.error('[SettingsModal] Failed to save smart typography', error);
+      setSmartTypography(!enabled);
+    });
+  };
+
+  const handleMenuLocaleChange = (locale: string | null) => {
+    void setMenuLocale(locale)
+      .then(setMenuLocaleInfo)
+      .catch((error) => {
+        console.error('[SettingsModal] Failed to save menu language', error);
+      });
+  };
+
+  const handleCopyLog = () => {
+    void getRecentLogLines()
+      .then(async (lines) => {
+        await navigator.clipboard.writeText(lines.join('\n'));
+        setLogStatus(`Copied ${lines.length} log lines.`);
+      })
+      .catch((error) => {
+        setLogStatus(`Could not copy the log. ${errorMessage(error)}`);
+      });
+  };
+
+  const handleOpenLogFolder = () => {
+    void openLogFolder().catch((error) => {
+      setLogStatus(`Could not open the log folder. ${errorMessage(error)}`);
+    });
+  };
+
+  const handleBackupAppData = async () => {
+    const date = new Date().toISOString().slice(0, 10);
+    const path = await save({
+      defaultPath: `Grainery Backup ${date}.zip`,
+      filters: [{ name: 'Grainery Backup', extensions: ['zip'] }],
+    });
+    if (!path) {
+      return;
+    }
+
+    try {
+      const manifest = await backupAppData(path);
+      setBackupStatus(`Backed up ${manifest.files} files.`);
+    } catch (error) {
+      setBackupStatus(`Could not back up app data. ${errorMessage(error)}`);
+    }
+  };
+
+  const handleRestoreAppData = async () => {
+    const path = await open({
+      multiple: false,
+      directory: false,
+      filters: [{ name: 'Grainery Backup', extensions: ['zip'] }],
+    });
+    if (typeof path !== 'string') {
+      return;
+    }
+
+    const confirmed = await confirm(
+      'Replace your settings, plugins and saved data with this backup? Your current data is saved to the backups folder first, and Grainery will restart.',
+      { title: 'Restore App Data', kind: 'warning', okLabel: 'Restore', cancelLabel: 'Cancel' }
+    );
+    if (!confirmed) {
+      return;
+    }
+
+    try {
+      await restoreAppData(path);
+      await relaunchApp();
+    } catch (error) {
+      setBackupStatus(`Could not restore app data. ${errorMessage(error)}`);
+    }
+  };
+
+  useEffect(() => {
+    if (activeTab !== 'shortcuts') {
+      return;
+    }
+
+    let cancelled = false;
+    void applyShortcuts()
+      .then((overview) => {
+        if (!cancelled) {
+          setShortcutOverview(overview);
+        }
+      })
+      .catch((error) => {
+        console.error('[SettingsModal] Failed to load shortcuts', error);
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [activeTab, pluginStateVersion]);
+
+  const runShortcutUpdate = (command: string, update: () => Promise<ShortcutOverview>) => {
+    void update()
+      .then((overview) => {
+        setShortcutOverview(overview);
+        setShortcutError(null);
+        setShortcutDrafts((drafts) => {
+          const next = { ...drafts };
+          delete next[command];
+          return next;
+        });
+      })
+      .catch((error) => {
+        setShortcutError(String(error));
+      });
+  };
+
+  const handleShortcutCommit = (binding: ShortcutBinding) => {
+    const draft = shortcutDrafts[binding.command];
+    if (draft === undefined || draft.trim() === (binding.accelerator ?? '')) {
+      return;
+    }
+    const accelerator = draft.trim() || null;
+    runShortcutUpdate(binding.command, () =>
+      setShortcut(binding.command, accelerator, binding.global)
+    );
+  };
+
+  useEffect(() => {
+    if (activeTab !== 'plugins') {
+      return;
+    }
+
+    let cancelled = false;
+    void pluginManager
+      .getDiskUsage()
+      .then((usage) => {
+        if (!cancelled) {
+          setDiskUsage(Object.fromEntries(usage.map((item) => [item.pluginId, item])));
+        }
+      })
+      .catch((error) => {
+        console.error('[SettingsModal] Failed to load plugin disk usage', error);
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [activeTab, pluginManager, pluginStateVersion]);
+
   const handleTitlePageFieldChange = (field: keyof TitlePageData, value: string) => {
     setTitlePageForm((prev) => {
       const next = { ...prev, [field]: value };
@@ -286,7 +598,7 @@ export function SettingsModal({
     try {
       await task();
     } catch (error) {
-      setPluginError(error instanceof Error ? error.message : String(error));
+      setPluginError(errorMessage(error));
     } finally {
       setIsBusy(false);
     }
@@ -308,7 +620,27 @@ export function SettingsModal({
         return;
       }
 
-      await pluginManager.installFromFile(path);
+      try {
+        await pluginManager.installFromFile(path);
+      } catch (error) {
+        const message = errorMessage(error);
+        if (!message.startsWith('Plugin archive is not signed')) {
+          throw error;
+        }
+
+        const approved = await confirm(
+          'This plugin is not signed, so its contents cannot be verified. Install it anyway?',
+          {
+            title: 'Unsigned Plugin',
+            kind: 'warning',
+            okLabel: 'Install',
+            cancelLabel: 'Cancel',
+          }
+        );
+        if (approved) {
+          await pluginManager.installFromFile(path, true);
+        }
+      }
     });
   };
 
@@ -318,6 +650,30 @@ export function SettingsModal({
     });
   };
 
+  const handleUpdateChannelChange = (pluginId: string, channel: PluginChannel) => {
+    void runBusy(async () => {
+      await pluginManager.setUpdateChannel(pluginId, channel);
+    });
+  };
+
+  const handleClearData = (pluginId: string) => {
+    void runBusy(async () => {
+      const approved = await confirm(`Delete all stored data for plugin ${pluginId}?`, {
+        title: 'Clear Plugin Data',
+        kind: 'warning',
+        okLabel: 'Clear Data',
+        cancelLabel: 'Cancel',
+      });
+
+      if (!approved) {
+        return;
+      }
+
+      const usage = await pluginManager.clearPluginData(pluginId);
+      setDiskUsage((current) => ({ ...current, [pluginId]: usage }));
+    });
+  };
+
   const handleUninstall = (pluginId: string) => {
     void runBusy(async () => {
       const approved = await confirm(`Uninstall plugin ${pluginId}?`, {
@@ -356,6 +712,7 @@ export function SettingsModal({
     ...(isScreenplayDocument
       ? [{ id: 'title-page' as const, label: 'Title Page', icon: '▤' }]
       : []),
+    { id: 'shortcuts', label: 'Shortcuts', icon: '⌘' },
     { id: 'plugins', label: 'Plugins', icon: '⊕' },
   ];
 
@@ -416,8 +773,14 @@ export function SettingsModal({
                 </p>
                 <p className="settings-section-label">Surface</p>
                 <div className="settings-theme-grid">
+                  <ThemeCard
+                    t={systemTheme}
+                    label="System"
+                    isSelected={preference === 'system'}
+                    onClick={() => setTheme('system')}
+                  />
                   {THEMES.map((t) => (
-                    <ThemeCard key={t} t={t} isSelected={theme === t} onClick={() => setTheme(t)} />
+                    <ThemeCard key={t} t={t} isSelected={preference === t} onClick={() => setTheme(t)} />
                   ))}
                 </div>
               </div>
@@ -507,6 +870,197 @@ export function SettingsModal({
                   )}
                 </div>
 
+                <div className="settings-editor-group">
+                  <p className="settings-section-label">Typography</p>
+
+                  <label className="settings-editor-option">
+                    <span className="settings-editor-option-copy">
+                      <span>Smart punctuation</span>
+                      <small>Curl quotes and turn -- and ... into dashes and ellipses when saving and exporting.</small>
+                    </span>
+                    <span className="settings-editor-option-control">
+                      <span aria-hidden="true">{smartTypography ? 'On' : 'Off'}</span>
+                      <input
+                        type="checkbox"
+                        className="toggle toggle-sm"
+                        checked={smartTypography}
+                        onChange={(event) => handleSmartTypographyChange(event.target.checked)}
+                      />
+                    </span>
+                  </label>
+                </div>
+
+                {menuLocale && (
+                  <div className="settings-editor-group">
+                    <p className="settings-section-label">Language</p>
+
+                    <label className="settings-editor-option settings-editor-option-select">
+                      <span className="settings-editor-option-copy">
+                        <span>Menu language</span>
+                        <small>Used for the menu bar. Applies the next time Grainery starts.</small>
+                      </span>
+                      <span className="settings-editor-option-control">
+                        <select
+                          className="settings-editor-select"
+                          value={menuLocale.overrideLocale ?? ''}
+                          onChange={(event) => handleMenuLocaleChange(event.target.value || null)}
+                        >
+                          <option value="">System</option>
+                          {menuLocale.available.map((option) => (
+                            <option key={option.code} value={option.code}>
+                              {option.name}
+                            </option>
+                          ))}
+                        </select>
+                      </span>
+                    </label>
+                  </div>
+                )}
+
+                <div className="settings-editor-group">
+                  <p className="settings-section-label">Diagnostics</p>
+
+                  <div className="settings-editor-option">
+                    <span className="settings-editor-option-copy">
+                      <span>Log file</span>
+                      <small>
+                        {logStatus ?? 'Attach recent log lines to a bug report to show what went wrong.'}
+                      </small>
+                    </span>
+                    <span className="settings-editor-option-control">
+                      <button type="button" className="settings-inline-button" onClick={handleCopyLog}>
+                        Copy Recent Log
+                      </button>
+                      <button type="button" className="settings-inline-button" onClick={handleOpenLogFolder}>
+                        Open Log Folder
+                      </button>
+                    </span>
+                  </div>
+                </div>
+
+                <div className="settings-editor-group">
+                  <p className="settings-section-label">App Data</p>
+
+                  <div className="settings-editor-option">
+                    <span className="settings-editor-option-copy">
+                      <span>Backup</span>
+                      <small>
+                        {backupStatus ??
+                          'Settings, plugins, snapshots, presets and dictionaries in one file, for moving to another computer.'}
+                      </small>
+                    </span>
+                    <span className="settings-editor-option-control">
+                      <button type="button" className="settings-inline-button" onClick={() => void handleBackupAppData()}>
+                        Back Up…
+                      </button>
+                      <button type="button" className="settings-inline-button" onClick={() => void handleRestoreAppData()}>
+                        Restore…
+                      </button>
+                    </span>
+                  </div>
+                </div>
+
+                {lanSync && (
+                  <div className="settings-editor-group">
+                    <p className="settings-section-label">Sync</p>
+
+                    <label className="settings-editor-option">
+                      <span className="settings-editor-option-copy">
+                        <span>Sync over the local network</span>
+                        <small>
+                          {lanSyncError ??
+                            lanSync.lastError ??
+                            'Keep screenplays in step with your other computers on this network, with no cloud service. Save a screenplay once on each computer to start syncing it.'}
+                        </small>
+                      </span>
+                      <span className="settings-editor-option-control">
+                        <span aria-hidden="true">{lanSync.enabled ? 'On' : 'Off'}</span>
+                        <input
+                          type="checkbox"
+                          className="toggle toggle-sm"
+                          checked={lanSync.enabled}
+                          onChange={(event) => runLanSync(() => setLanSyncEnabled(event.target.checked))}
+                        />
+                      </span>
+                    </label>
+
+                    <label className="settings-editor-option settings-editor-option-select">
+                      <span className="settings-editor-option-copy">
+                        <span>This computer</span>
+                        <small>
+                          {lanSync.fingerprint
+                            ? `Shown to other devices. Key fingerprint ${lanSync.fingerprint}.`
+                            : 'Shown to other devices.'}
+                        </small>
+                      </span>
+                      <span className="settings-editor-option-control">
+                        <input
+                          type="text"
+                          className="settings-editor-select"
+                          value={deviceNameDraft}
+                          maxLength={60}
+                          onChange={(event) => setDeviceNameDraft(event.target.value)}
+                          onBlur={handleDeviceNameCommit}
+                          onKeyDown={(event) => {
+                            if (event.key === 'Enter') {
+                              handleDeviceNameCommit();
+                            }
+                          }}
+                        />
+                      </span>
+                    </label>
+
+                    {lanSync.paired.map((device) => (
+                      <div key={device.deviceId} className="settings-editor-option">
+                        <span className="settings-editor-option-copy">
+                          <span>{device.name}</span>
+                          <small>
+                            {device.online ? 'On this network' : 'Not on this network'}
+                            {device.lastSyncedAt
+                              ? `. Last synced ${new Date(device.lastSyncedAt).toLocaleString()}.`
+                              : '. Not synced yet.'}
+                          </small>
+                        </span>
+                        <span className="settings-editor-option-control">
+                          <button
+                            type="button"
+                            className="settings-inline-button"
+                            disabled={!lanSync.enabled || !device.online}
+                            onClick={() => runLanSync(syncNow)}
+                          >
+                            Sync Now
+                          </button>
+                          <button
+                            type="button"
+                            className="settings-inline-button"
+                            onClick={() => runLanSync(() => unpairDevice(device.deviceId))}
+                          >
+                            Forget
+                          </button>
+                        </span>
+                      </div>
+                    ))}
+
+                    {lanSync.enabled &&
+                      lanSync.nearby.map((device) => (
+                        <div key={device.deviceId} className="settings-editor-option">
+                          <span className="settings-editor-option-copy">
+                            <span>{device.name}</span>
+                            <small>Not paired. Key fingerprint {device.fingerprint}.</small>
+                          </span>
+                          <span className="settings-editor-option-control">
+                            <button
+                              type="button"
+                              className="settings-inline-button"
+                              onClick={() => runLanSync(() => pairDevice(device.deviceId))}
+                            >
+                              Pair…
+                            </button>
+                          </span>
+                        </div>
+                      ))}
+                  </div>
+                )}
               </div>
             )}
 
@@ -795,6 +1349,70 @@ export function SettingsModal({
               </div>
             )}
 
+            {activeTab === 'shortcuts' && (
+              <div className="settings-panel">
+                <p className="settings-panel-intro">
+                  Type a shortcut such as CmdOrCtrl+Shift+K. Leave it empty to unbind the command.
+                </p>
+
+                {shortcutError && <div className="alert alert-error py-2 text-sm">{shortcutError}</div>}
+
+                {shortcutOverview?.conflicts.map((conflict) => (
+                  <div key={`${conflict.kind}:${conflict.accelerator}`} className="alert alert-warning py-2 text-sm">
+                    {conflict.kind === 'os'
+                      ? `${conflict.accelerator} is already used by another application.`
+                      : conflict.kind === 'invalid'
+                        ? `${conflict.accelerator} is not a valid shortcut for ${conflict.commands.map(getShortcutLabel).join(', ')}.`
+                        : `${conflict.accelerator} is bound to ${conflict.commands.map(getShortcutLabel).join(', ')}.`}
+                  </div>
+                ))}
+
+                <div className="settings-editor-group">
+                  <p className="settings-section-label">Commands</p>
+
+                  {shortcutOverview?.bindings.map((binding) => (
+                    <label key={binding.command} className="settings-editor-option settings-editor-option-select">
+                      <span className="settings-editor-option-copy">
+                        <span>{getShortcutLabel(binding.command)}</span>
+                        {binding.defaultAccelerator && <small>Default: {binding.defaultAccelerator}</small>}
+                      </span>
+                      <span className="settings-editor-option-control">
+                        <input
+                          type="text"
+                          className="input input-sm settings-editor-select"
+                          value={shortcutDrafts[binding.command] ?? binding.accelerator ?? ''}
+                          placeholder="None"
+                          onChange={(event) =>
+                            setShortcutDrafts((drafts) => ({
+                              ...drafts,
+                              [binding.command]: event.target.value,
+                            }))
+                          }
+                          onBlur={() => handleShortcutCommit(binding)}
+                          onKeyDown={(event) => {
+                            if (event.key === 'Enter') {
+                              event.currentTarget.blur();
+                            }
+                          }}
+                        />
+                        {binding.overridden && (
+                          <button
+                            type="button"
+                            className="settings-inline-button"
+                            onClick={() =>
+                              runShortcutUpdate(binding.command, () => resetShortcut(binding.command))
+                            }
+                          >
+                            Reset
+                          </button>
+                        )}
+                      </span>
+                    </label>
+                  ))}
+                </div>
+              </div>
+            )}
+
             {activeTab === 'plugins' && (
               <div className="settings-panel settings-plugins-panel">
                 <p className="settings-panel-intro">
@@ -842,6 +1460,17 @@ export function SettingsModal({
                                 {plugin.name} <span>{plugin.version}</span>
                               </div>
                               {plugin.description && <small>{plugin.description}</small>}
+                              {diskUsage[plugin.id] && (
+                                <small>
+                                  {formatBytes(diskUsage[plugin.id].totalBytes)} of{' '}
+                                  {formatBytes(diskUsage[plugin.id].quotaBytes)} used
+                                </small>
+                              )}
+                              {plugin.revocation && (
+                                <small className="settings-plugin-revoked">
+                                  Revoked for security reasons: {plugin.revocation.reason}
+                                </small>
+                              )}
                             </div>
                             <label className="settings-plugin-enabled">
                               <span>{plugin.enabled ? 'On' : 'Off'}</span>
@@ -849,6 +1478,7 @@ export function SettingsModal({
                                 type="checkbox"
                                 className="toggle toggle-sm"
                                 checked={plugin.enabled}
+                                disabled={Boolean(plugin.revocation)}
                                 onChange={(event) =>
                                   handleToggleEnabled(plugin.id, event.target.checked)
                                 }
@@ -857,6 +1487,35 @@ export function SettingsModal({
                           </div>
 
                           <div className="settings-plugin-actions">
+                            {plugin.installSource === 'registry' && (
+                              <select
+                                className="settings-editor-select"
+                                aria-label="Update channel"
+                                value={plugin.updateChannel ?? 'stable'}
+                                disabled={isBusy}
+                                onChange={(event) =>
+                                  handleUpdateChannelChange(
+                                    plugin.id,
+                                    event.target.value as PluginChannel
+                                  )
+                                }
+                              >
+                                <option value="stable">Stable updates</option>
+                                <option value="beta">Beta updates</option>
+                              </select>
+                            )}
+                            <button
+                              type="button"
+                              className="settings-inline-button"
+                              disabled={
+                                isBusy ||
+                                !diskUsage[plugin.id] ||
+                                diskUsage[plugin.id].dataBytes + diskUsage[plugin.id].storageBytes === 0
+                              }
+                              onClick={() => handleClearData(plugin.id)}
+                            >
+                              Clear Data
+                            </button>
                             <button
                               type="button"
                               className="settings-inline-button settings-inline-button-danger"
@@ -1,5 +1,7 @@
 import { createContext, useContext, useState, useEffect, ReactNode } from 'react';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { syncNativeTitlebarTheme } from '../lib/nativeTheme';
+import { loadSettings, subscribeSettings, updateSettings, type AppSettings } from '../lib/settings';
 
 export const THEMES = [
   'grainery',
@@ -13,38 +15,155 @@ export const THEMES = [
 
 export type Theme = typeof THEMES[number];
 
+/** A concrete theme, or `system` to follow the OS light/dark appearance. */
+export type ThemePreference = Theme | 'system';
+
+export const SYSTEM_LIGHT_THEME: Theme = 'grainery';
+export const SYSTEM_DARK_THEME: Theme = 'dark';
+
 interface ThemeContextType {
+  /** The theme currently applied, with `system` resolved. */
   theme: Theme;
-  setTheme: (theme: Theme) => void;
+  preference: ThemePreference;
+  /** What `system` currently resolves to. */
+  systemTheme: Theme;
+  setTheme: (theme: ThemePreference) => void;
 }
 
 const ThemeContext = createContext<ThemeContextType | undefined>(undefined);
 
 const STORAGE_KEY = 'grainery-theme';
 
-export function ThemeProvider({ children }: { children: ReactNode }) {
-  const [theme, setThemeState] = useState<Theme>(() => {
-    const stored = localStorage.getItem(STORAGE_KEY) as Theme;
-    if (stored && THEMES.includes(stored)) {
-      return stored;
+function isThemePreference(value: string | null): value is ThemePreference {
+  return value === 'system' || THEMES.includes(value as Theme);
+}
+
+/**
+ * The last known preference, cached in localStorage so the first paint uses
+ * the right theme before the backend settings load.
+ */
+export function getStoredThemePreference(): ThemePreference {
+  const stored = localStorage.getItem(STORAGE_KEY);
+  return isThemePreference(stored) ? stored : 'grainery';
+}
+
+export function resolveTheme(preference: ThemePreference, systemDark: boolean): Theme {
+  if (preference !== 'system') {
+    return preference;
+  }
+  return systemDark ? SYSTEM_DARK_THEME : SYSTEM_LIGHT_THEME;
+}
+
+function prefersDarkMedia(): boolean {
+  return window.matchMedia('(prefers-color-scheme: dark)').matches;
+}
+
+function isTauriRuntime(): boolean {
+  return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
+}
+
+/**
+ * Tracks the OS appearance through the native window theme, falling back to
+ * the media query outside Tauri. Both report the window's appearance, which
+ * is pinned while a concrete theme is selected; choosing `system` unpins it
+ * and the resulting change event corrects any stale value.
+ */
+function useSystemDark(): boolean {
+  const [systemDark, setSystemDark] = useState(prefersDarkMedia);
+
+  useEffect(() => {
+    const media = window.matchMedia('(prefers-color-scheme: dark)');
+    const onMediaChange = (event: MediaQueryListEvent) => setSystemDark(event.matches);
+    media.addEventListener('change', onMediaChange);
+
+    let unlistenNative: (() => void) | null = null;
+    let disposed = false;
+    if (isTauriRuntime()) {
+      const appWindow = getCurrentWindow();
+      void appWindow.theme().then((theme) => {
+        if (!disposed && theme) {
+          setSystemDark(theme === 'dark');
+        }
+      });
+      void appWindow
+        .onThemeChanged(({ payload }) => setSystemDark(payload === 'dark'))
+        .then((unlisten) => {
+          if (disposed) {
+            unlisten();
+          } else {
+            unlistenNative = unlisten;
+          }
+        });
     }
-    return 'grainery';
-    // TODO: Consider using system preference as default if no stored theme is found, need to pick a dark theme
-    // return window.matchMedia('(prefers-color-scheme: dark)').matches ? 'dark' : 'grainery';
-  });
 
-  const setTheme = (newTheme: Theme) => {
-    setThemeState(newTheme);
+    return () => {
+      disposed = true;
+      media.removeEventListener('change', onMediaChange);
+      unlistenNative?.();
+    };
+  }, []);
+
+  return systemDark;
+}
+
+export function ThemeProvider({ children }: { children: ReactNode }) {
+  const [preference, setPreference] = useState<ThemePreference>(getStoredThemePreference);
+  const systemDark = useSystemDark();
+  const theme = resolveTheme(preference, systemDark);
+  const systemTheme = resolveTheme('system', systemDark);
+
+  const setTheme = (newTheme: ThemePreference) => {
+    setPreference(newTheme);
     localStorage.setItem(STORAGE_KEY, newTheme);
+    if (isTauriRuntime()) {
+      void updateSettings({ theme: newTheme }).catch((error) => {
+        console.error('Failed to save theme:', error);
+      });
+    }
   };
 
+  // Settings are shared by every window, so a theme picked in one applies
+  // to all of them.
+  useEffect(() => {
+    if (!isTauriRuntime()) {
+      return;
+    }
+
+    const applySettings = (settings: AppSettings) => {
+      if (isThemePreference(settings.theme)) {
+        setPreference(settings.theme);
+        localStorage.setItem(STORAGE_KEY, settings.theme);
+      }
+    };
+
+    let unsubscribe: (() => void) | null = null;
+    let disposed = false;
+    void loadSettings().then((settings) => {
+      if (!disposed) {
+        applySettings(settings);
+      }
+    });
+    void subscribeSettings(applySettings).then((unsubscribeSettings) => {
+      if (disposed) {
+        unsubscribeSettings();
+      } else {
+        unsubscribe = unsubscribeSettings;
+      }
+    });
+
+    return () => {
+      disposed = true;
+      unsubscribe?.();
+    };
+  }, []);
+
   useEffect(() => {
     document.documentElement.setAttribute('data-theme', theme);
-    void syncNativeTitlebarTheme();
-  }, [theme]);
+    void syncNativeTitlebarTheme({ followSystem: preference === 'system' });
+  }, [preference, theme]);
 
   return (
-    <ThemeContext.Provider value={{ theme, setTheme }}>
+    <ThemeContext.Provider value={{ theme, preference, systemTheme, setTheme }}>
       {children}
     </ThemeContext.Provider>
   );
@@ -0,0 +1,73 @@
+import { Extension } from '@tiptap/core';
+import type { Node as ProseMirrorNode } from '@tiptap/pm/model';
+import type { JSONContent } from '@tiptap/react';
+import { recordJournalEdits } from '../lib/journal';
+import { applyBlockEdits, loadLiveDocument, type BlockEdit } from '../lib/liveDocument';
+
+/**
+ * The top-level blocks that differ between two documents. ProseMirror reuses
+ * unchanged nodes, so the shared prefix and suffix compare by identity.
+ */
+function diffBlocks(before: ProseMirrorNode, after: ProseMirrorNode): BlockEdit | null {
+  const limit = Math.min(before.childCount, after.childCount);
+  let start = 0;
+  while (start < limit && before.child(start) === after.child(start)) {
+    start += 1;
+  }
+
+  let endBefore = before.childCount;
+  let endAfter = after.childCount;
+  while (
+    endBefore > start &&
+    endAfter > start &&
+    before.child(endBefore - 1) === after.child(endAfter - 1)
+  ) {
+    endBefore -= 1;
+    endAfter -= 1;
+  }
+
+  if (start === endBefore && start === endAfter) {
+    return null;
+  }
+
+  const insert: JSONContent[] = [];
+  for (let index = start; index < endAfter; index += 1) {
+    insert.push(after.child(index).toJSON());
+  }
+  return { from: start, deleteCount: endBefore - start, insert };
+}
+
+/**
+ * Streams block-level edits to the backend document mirror, which keeps the
+ * character index and other lookups current without reserializing the script,
+ * and to the crash journal.
+ */
+export const LiveDocumentSync = Extension.create({
+  name: 'liveDocumentSync',
+
+  onCreate() {
+    void loadLiveDocument(this.editor.getJSON()).catch((error) => {
+      console.error('[LiveDocumentSync] Failed to load document', error);
+    });
+  },
+
+  onTransaction({ transaction }) {
+    if (!transaction.docChanged) {
+      return;
+    }
+
+    const edit = diffBlocks(transaction.before, transaction.doc);
+    if (!edit) {
+      return;
+    }
+
+    recordJournalEdits([edit]);
+
+    const editor = this.editor;
+    void applyBlockEdits([edit]).catch(() => {
+      void loadLiveDocument(editor.getJSON()).catch((error) => {
+        console.error('[LiveDocumentSync] Failed to reload document', error);
+      });
+    });
+  },
+});
@@ -1,9 +1,13 @@
 import { Extension } from '@tiptap/core';
 import { Plugin, PluginKey } from '@tiptap/pm/state';
 import { Decoration, DecorationSet } from '@tiptap/pm/view';
-import { computePagination, type PageBreakInfo } from '../lib/computePagination';
+import { getPagination } from '../lib/liveDocument';
+import { pageBreaks, type PageBreakInfo, type Pagination, type PaperSize } from '../lib/pagination';
 import type { DocumentMode } from '../lib/types';
 
+// Typing settles before the backend repaginates.
+const REPAGINATE_DELAY_MS = 150;
+
 export interface PaginationStorage {
   breaks: PageBreakInfo[];
   totalPages: number;
@@ -17,14 +21,21 @@ export const paginationPluginKey = new PluginKey<{
 
 export interface PaginationOptions {
   documentMode: DocumentMode;
+  paperSize: PaperSize;
 }
 
+/**
+ * Shows page breaks where the PDF export will put them. Layout comes from the
+ * backend's pagination engine via the live document mirror, so the page view,
+ * scene list and export always agree.
+ */
 export const PaginationExtension = Extension.create<PaginationOptions>({
   name: 'pagination',
 
   addOptions() {
     return {
       documentMode: 'screenplay',
+      paperSize: 'letter',
     };
   },
 
@@ -43,33 +54,34 @@ export const PaginationExtension = Extension.create<PaginationOptions>({
         key: paginationPluginKey,
 
         state: {
-          init(_, { doc }) {
-            const { breaks, totalPages } = computePagination(doc, extension.options.documentMode);
-            const decorations = createPageBreakDecorations(breaks);
+          init() {
             return {
-              decorationSet: DecorationSet.create(doc, decorations),
-              breaks,
-              totalPages,
+              decorationSet: DecorationSet.empty,
+              breaks: [],
+              totalPages: 1,
             };
           },
 
           apply(tr, prev, _oldState, newState) {
-            // Only recompute if document changed
-            if (!tr.docChanged) {
-              // Map decorations through the transaction
+            const pagination = tr.getMeta(paginationPluginKey) as Pagination | undefined;
+            if (pagination) {
+              const breaks = pageBreaks(newState.doc, pagination);
               return {
-                ...prev,
-                decorationSet: prev.decorationSet.map(tr.mapping, newState.doc),
+                decorationSet: DecorationSet.create(newState.doc, createPageBreakDecorations(breaks)),
+                breaks,
+                totalPages: pagination.totalPages,
               };
             }
 
-            const { breaks, totalPages } = computePagination(newState.doc, extension.options.documentMode);
-            const decorations = createPageBreakDecorations(breaks);
+            if (!tr.docChanged) {
+              return prev;
+            }
 
+            // Keep the last layout in place until the backend answers
             return {
-              decorationSet: DecorationSet.create(newState.doc, decorations),
-              breaks,
-              totalPages,
+              decorationSet: prev.decorationSet.map(tr.mapping, newState.doc),
+              breaks: prev.breaks.map((br) => ({ ...br, pos: tr.mapping.map(br.pos) })),
+              totalPages: prev.totalPages,
             };
           },
         },
@@ -82,6 +94,10 @@ export const PaginationExtension = Extension.create<PaginationOptions>({
         },
 
         view(editorView) {
+          let timer: ReturnType<typeof setTimeout> | undefined;
+          let requestId = 0;
+          let destroyed = false;
+
           const updateStorage = () => {
             const pluginState = paginationPluginKey.getState(editorView.state);
             if (pluginState) {
@@ -90,11 +106,41 @@ export const PaginationExtension = Extension.create<PaginationOptions>({
             }
           };
 
+          const repaginate = () => {
+            const id = ++requestId;
+            void getPagination(extension.options.paperSize)
+              .then((pagination) => {
+                // Drop answers overtaken by a newer request
+                if (destroyed || id !== requestId) return;
+                editorView.dispatch(
+                  editorView.state.tr
+                    .setMeta(paginationPluginKey, pagination)
+                    .setMeta('addToHistory', false)
+                );
+              })
+              .catch((error) => {
+                console.error('[Pagination] Failed to paginate document', error);
+              });
+          };
+
+          const scheduleRepaginate = () => {
+            clearTimeout(timer);
+            timer = setTimeout(repaginate, REPAGINATE_DELAY_MS);
+          };
+
           updateStorage();
+          scheduleRepaginate();
 
           return {
-            update() {
+            update(view, prevState) {
               updateStorage();
+              if (view.state.doc !== prevState.doc) {
+                scheduleRepaginate();
+              }
+            },
+            destroy() {
+              destroyed = true;
+              clearTimeout(timer);
             },
           };
         },
@@ -114,17 +160,17 @@ function createPageBreakDecorations(breaks: PageBreakInfo[]): Decoration[] {
           el.className = 'page-break-auto';
           el.dataset.page = String(br.page);
           el.setAttribute('contenteditable', 'false');
-          
+
           const label = document.createElement('span');
           label.className = 'page-break-label';
           label.textContent = `Page ${br.page}`;
           el.appendChild(label);
-          
+
           return el;
         },
         {
           side: -1, // Insert before the node at this position
-          key: `page-break-${br.pos}`,
+          key: `page-break-${br.page}-${br.pos}`,
         }
       );
     });
@@ -0,0 +1,48 @@
+import { Extension } from '@tiptap/core';
+import { Plugin, PluginKey } from '@tiptap/pm/state';
+import { classifyClipboardText } from '../lib/fileOps';
+
+export const smartPastePluginKey = new PluginKey('smartPaste');
+
+/**
+ * Pastes multi-line plain text as screenplay elements: Fountain and FDX keep
+ * their scene headings, cues and dialogue, and wrapped prose becomes one
+ * action block per paragraph. Rich pastes, including copies from the editor
+ * itself, are left to the default handler.
+ */
+export const SmartPaste = Extension.create({
+  name: 'smartPaste',
+
+  addProseMirrorPlugins() {
+    const editor = this.editor;
+
+    return [
+      new Plugin({
+        key: smartPastePluginKey,
+        props: {
+          handlePaste: (_view, event) => {
+            const data = event.clipboardData;
+            if (!data || data.getData('text/html')) {
+              return false;
+            }
+            const text = data.getData('text/plain');
+            if (!text.includes('\n')) {
+              return false;
+            }
+
+            const { from, to } = editor.state.selection;
+            void classifyClipboardText(text)
+              .then((paste) => {
+                editor.chain().focus().insertContentAt({ from, to }, paste.nodes).run();
+              })
+              .catch((error) => {
+                console.error('[SmartPaste] Failed to classify pasted text', error);
+                editor.chain().focus().insertContentAt({ from, to }, text).run();
+              });
+            return true;
+          },
+        },
+      }),
+    ];
+  },
+});
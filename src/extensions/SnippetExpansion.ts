@@ -0,0 +1,95 @@
+import { Extension } from '@tiptap/core';
+import type { Editor } from '@tiptap/core';
+import { expandSnippet, listSnippets } from '../lib/snippets';
+
+interface SnippetExpansionStorage {
+  triggers: Set<string>;
+}
+
+function loadTriggers(storage: SnippetExpansionStorage) {
+  void listSnippets()
+    .then((snippets) => {
+      storage.triggers = new Set(snippets.map((snippet) => snippet.trigger));
+    })
+    .catch((error) => {
+      console.error('[SnippetExpansion] Failed to load snippets', error);
+    });
+}
+
+/** The last character cue and scene heading before `pos`. */
+function getExpansionContext(editor: Editor, pos: number) {
+  let character: string | null = null;
+  let scene: string | null = null;
+  editor.state.doc.nodesBetween(0, pos, (node) => {
+    if (node.type.name === 'character') {
+      character = node.textContent.trim();
+    } else if (node.type.name === 'sceneHeading') {
+      scene = node.textContent.trim();
+    }
+    return !node.isTextblock;
+  });
+  return { character, scene };
+}
+
+/**
+ * Expands the word before the cursor on Tab when it is a snippet trigger.
+ * Runs ahead of the screenplay keymap, so Tab still cycles elements otherwise.
+ */
+export const SnippetExpansion = Extension.create<Record<string, never>, SnippetExpansionStorage>({
+  name: 'snippetExpansion',
+  priority: 1000,
+
+  addStorage() {
+    return {
+      triggers: new Set<string>(),
+    };
+  },
+
+  onCreate() {
+    loadTriggers(this.storage);
+  },
+
+  // Picks up snippets edited in Settings while the editor was open.
+  onFocus() {
+    loadTriggers(this.storage);
+  },
+
+  addKeyboardShortcuts() {
+    return {
+      Tab: ({ editor }) => {
+        const { selection } = editor.state;
+        if (!selection.empty) {
+          return false;
+        }
+
+        const { $from } = selection;
+        const textBefore = $from.parent.textBetween(0, $from.parentOffset);
+        const trigger = /(\S+)$/.exec(textBefore)?.[1];
+        if (!trigger || !this.storage.triggers.has(trigger)) {
+          return false;
+        }
+
+        const to = $from.pos;
+        const from = to - trigger.length;
+        void expandSnippet(trigger, getExpansionContext(editor, from))
+          .then((expansion) => {
+            // Skip if the trigger was edited while the lookup ran.
+            if (!expansion || editor.state.doc.textBetween(from, to) !== trigger) {
+              return;
+            }
+            const cursor = from + (expansion.cursor ?? expansion.text.length);
+            editor
+              .chain()
+              .focus()
+              .insertContentAt({ from, to }, expansion.text)
+              .setTextSelection(cursor)
+              .run();
+          })
+          .catch((error) => {
+            console.error('[SnippetExpansion] Failed to expand snippet', error);
+          });
+        return true;
+      },
+    };
+  },
+});
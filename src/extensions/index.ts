@@ -3,6 +3,9 @@ export * from './marks';
 export { BlockAlignment } from './BlockAlignment';
 export type { TextAlignment } from './BlockAlignment';
 export { ScreenplayKeymap } from './ScreenplayKeymap';
+export { SnippetExpansion } from './SnippetExpansion';
+export { SmartPaste, smartPastePluginKey } from './SmartPaste';
+export { LiveDocumentSync } from './LiveDocumentSync';
 export { PaginationExtension, paginationPluginKey } from './PaginationExtension';
 export type { PaginationStorage } from './PaginationExtension';
 export {
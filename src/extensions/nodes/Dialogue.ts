@@ -25,6 +25,16 @@ export const Dialogue = Node.create<DialogueOptions>({
     };
   },
 
+  addAttributes() {
+    return {
+      // Keys the block's alternate takes; not rendered.
+      nodeId: {
+        default: null,
+        rendered: false,
+      },
+    };
+  },
+
   parseHTML() {
     return [{ tag: 'div[data-type="dialogue"]' }];
   },
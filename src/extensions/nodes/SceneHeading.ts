@@ -33,6 +33,11 @@ export const SceneHeading = Node.create<SceneHeadingOptions>({
       sceneNumber: {
         default: null,
       },
+      // Links the scene to its outline card; not rendered.
+      sceneId: {
+        default: null,
+        rendered: false,
+      },
     };
   },
 
@@ -0,0 +1,27 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface BackupManifest {
+  appVersion: string;
+  createdAt: string;
+  dataVersion: number;
+  files: number;
+}
+
+export interface RestoreResult {
+  manifest: BackupManifest;
+  /** Archive of the data the restore replaced. */
+  previousDataPath: string;
+}
+
+/** Zips settings, plugins, snapshots, presets and dictionaries to `path`. */
+export async function backupAppData(path: string): Promise<BackupManifest> {
+  return invoke<BackupManifest>('backup_app_data', { path });
+}
+
+/**
+ * Replaces app data with a backup. The current data is saved first, and the
+ * app must be relaunched for the restored settings and plugins to load.
+ */
+export async function restoreAppData(path: string): Promise<RestoreResult> {
+  return invoke<RestoreResult>('restore_app_data', { path });
+}
@@ -13,6 +13,8 @@ export interface AvailableAppUpdate {
   date?: string;
   body?: string;
   update: Update;
+  /** Set once the package has been downloaded and verified in the background. */
+  downloaded?: boolean;
 }
 
 export interface UpdateDownloadProgress {
@@ -91,14 +93,13 @@ export async function checkForAppUpdate(): Promise<AvailableAppUpdate | null> {
   };
 }
 
-export async function installAppUpdate(
-  availableUpdate: AvailableAppUpdate,
+function createDownloadEventHandler(
   onProgress: UpdateDownloadProgressHandler
-): Promise<void> {
+): (event: DownloadEvent) => void {
   let downloadedBytes = 0;
   let contentLength: number | null = null;
 
-  await availableUpdate.update.downloadAndInstall((event: DownloadEvent) => {
+  return (event: DownloadEvent) => {
     switch (event.event) {
       case 'Started':
         downloadedBytes = 0;
@@ -113,7 +114,31 @@ export async function installAppUpdate(
         onProgress({ downloadedBytes, contentLength, finished: true });
         break;
     }
-  });
+  };
+}
+
+/**
+ * Downloads and verifies the update package without installing it, so the
+ * startup check can fetch it in the background and install on confirmation.
+ */
+export async function downloadAppUpdate(
+  availableUpdate: AvailableAppUpdate,
+  onProgress: UpdateDownloadProgressHandler
+): Promise<void> {
+  await availableUpdate.update.download(createDownloadEventHandler(onProgress));
+  availableUpdate.downloaded = true;
+}
+
+export async function installAppUpdate(
+  availableUpdate: AvailableAppUpdate,
+  onProgress: UpdateDownloadProgressHandler
+): Promise<void> {
+  if (availableUpdate.downloaded) {
+    await availableUpdate.update.install();
+    return;
+  }
+
+  await availableUpdate.update.downloadAndInstall(createDownloadEventHandler(onProgress));
 }
 
 export async function relaunchApp(): Promise<void> {
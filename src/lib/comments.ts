@@ -0,0 +1,81 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { CommentAnchor, ScreenplayDocument, ScriptComment } from './types';
+
+export interface CommentsUpdate {
+  document: ScreenplayDocument;
+  comments: ScriptComment[];
+}
+
+async function editComments(
+  command: string,
+  document: ScreenplayDocument,
+  args: Record<string, unknown>
+): Promise<CommentsUpdate> {
+  const result = await invoke<{ documentJson: string; comments: ScriptComment[] }>(command, {
+    documentJson: JSON.stringify(document),
+    ...args,
+  });
+  return {
+    document: JSON.parse(result.documentJson) as ScreenplayDocument,
+    comments: result.comments,
+  };
+}
+
+/** Comments in document order. */
+export async function listComments(document: ScreenplayDocument): Promise<ScriptComment[]> {
+  return invoke<ScriptComment[]>('comments_list', { documentJson: JSON.stringify(document) });
+}
+
+export async function addComment(
+  document: ScreenplayDocument,
+  anchor: CommentAnchor,
+  author: string,
+  body: string
+): Promise<CommentsUpdate> {
+  return editComments('comments_add', document, { anchor, author, body });
+}
+
+/** Replying reopens a resolved thread. */
+export async function replyToComment(
+  document: ScreenplayDocument,
+  commentId: string,
+  author: string,
+  body: string
+): Promise<CommentsUpdate> {
+  return editComments('comments_reply', document, { commentId, author, body });
+}
+
+/** Edits a comment's text, or one of its replies when `replyId` is given. */
+export async function updateComment(
+  document: ScreenplayDocument,
+  commentId: string,
+  body: string,
+  replyId?: string
+): Promise<CommentsUpdate> {
+  return editComments('comments_update', document, { commentId, replyId: replyId ?? null, body });
+}
+
+export async function moveComment(
+  document: ScreenplayDocument,
+  commentId: string,
+  anchor: CommentAnchor
+): Promise<CommentsUpdate> {
+  return editComments('comments_move', document, { commentId, anchor });
+}
+
+export async function setCommentResolved(
+  document: ScreenplayDocument,
+  commentId: string,
+  resolved: boolean
+): Promise<CommentsUpdate> {
+  return editComments('comments_set_resolved', document, { commentId, resolved });
+}
+
+/** Deletes a whole thread, or one reply when `replyId` is given. */
+export async function deleteComment(
+  document: ScreenplayDocument,
+  commentId: string,
+  replyId?: string
+): Promise<CommentsUpdate> {
+  return editComments('comments_delete', document, { commentId, replyId: replyId ?? null });
+}
@@ -0,0 +1,28 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** A crash report saved by an earlier run. Reports never hold script text. */
+export interface CrashReportSummary {
+  id: string;
+  createdAt: string;
+  appVersion: string;
+  message: string;
+  /** Plugins that had host calls in progress when the crash happened. */
+  pluginIds: string[];
+}
+
+/** Saved reports the user hasn't sent or dismissed, newest first. */
+export async function getPendingCrashReports(): Promise<CrashReportSummary[]> {
+  return invoke<CrashReportSummary[]>('crash_reports_pending');
+}
+
+/**
+ * Opens a prefilled GitHub issue for the report in the browser, where it can
+ * be read before submitting, and removes the saved copy.
+ */
+export async function sendCrashReport(id: string): Promise<void> {
+  await invoke('crash_reports_send', { id });
+}
+
+export async function dismissCrashReport(id: string): Promise<void> {
+  await invoke('crash_reports_dismiss', { id });
+}
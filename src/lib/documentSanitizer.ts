@@ -322,6 +322,24 @@ function sanitizeNodeAttrs(
     } else if (sceneNumber !== null && sceneNumber !== undefined) {
       removeAttribute(report, 'sceneNumber');
     }
+
+    allowedAttrs.add('sceneId');
+    const sceneId = attrs.sceneId;
+    if (typeof sceneId === 'string' && sceneId) {
+      nextAttrs.sceneId = sceneId;
+    } else if (sceneId !== null && sceneId !== undefined) {
+      removeAttribute(report, 'sceneId');
+    }
+  }
+
+  if (type === 'dialogue') {
+    allowedAttrs.add('nodeId');
+    const nodeId = attrs.nodeId;
+    if (typeof nodeId === 'string' && nodeId) {
+      nextAttrs.nodeId = nodeId;
+    } else if (nodeId !== null && nodeId !== undefined) {
+      removeAttribute(report, 'nodeId');
+    }
   }
 
   for (const key of Object.keys(attrs)) {
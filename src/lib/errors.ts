@@ -0,0 +1,60 @@
+export type CommandErrorKind =
+  | 'notFound'
+  | 'permissionDenied'
+  | 'diskFull'
+  | 'readOnly'
+  | 'invalidDocument'
+  | 'network'
+  | 'cancelled'
+  | 'other';
+
+/** What save, load, export and plugin commands reject with. */
+export interface CommandError {
+  kind: CommandErrorKind;
+  message: string;
+  /** File the command was reading or writing, when there is one. */
+  path: string | null;
+  /** Whether the same action can succeed after the user does something. */
+  recoverable: boolean;
+  suggestion: string | null;
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as CommandError).kind === 'string' &&
+    typeof (error as CommandError).message === 'string'
+  );
+}
+
+/** Readable message for anything a command or a promise rejected with. */
+export function errorMessage(error: unknown): string {
+  if (error instanceof Error) {
+    return error.message;
+  }
+  if (isCommandError(error)) {
+    return error.message;
+  }
+  return String(error);
+}
+
+/** The message followed by what the user can do about it, if anything. */
+export function describeError(error: unknown): string {
+  const message = errorMessage(error);
+  return isCommandError(error) && error.suggestion ? `${message}\n\n${error.suggestion}` : message;
+}
+
+export function isCancelledError(error: unknown): boolean {
+  return isCommandError(error) && error.kind === 'cancelled';
+}
+
+/** Whether saving or exporting somewhere else could get past the error. */
+export function canChooseAnotherLocation(error: unknown): boolean {
+  return (
+    isCommandError(error) &&
+    error.recoverable &&
+    error.path !== null &&
+    ['notFound', 'permissionDenied', 'diskFull', 'readOnly'].includes(error.kind)
+  );
+}
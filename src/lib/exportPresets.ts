@@ -0,0 +1,68 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { JSONContent } from '@tiptap/react';
+import type { ExportFormat } from './settings';
+import type { DocumentMode, TitlePageData } from './types';
+import { exportAsFdx, exportAsFountain, exportAsPdf, type PdfOptions } from './fileOps';
+
+export interface ExportPreset {
+  name: string;
+  format: ExportFormat;
+  includeTitlePage: boolean;
+  /** Page setup for PDF exports; ignored by the text formats. */
+  pdf: PdfOptions;
+}
+
+export interface ExportPresetEntry {
+  scope: 'document' | 'global';
+  preset: ExportPreset;
+}
+
+/**
+ * Presets for a document (stored with its metadata) followed by the global
+ * ones. Omit `documentId` for the global presets only.
+ */
+export async function listExportPresets(documentId: string | null): Promise<ExportPresetEntry[]> {
+  return invoke<ExportPresetEntry[]>('export_presets_list', { documentId });
+}
+
+/** Saves a preset for the document, or globally when `documentId` is null. */
+export async function saveExportPreset(
+  preset: ExportPreset,
+  documentId: string | null
+): Promise<ExportPresetEntry[]> {
+  return invoke<ExportPresetEntry[]>('export_presets_save', { documentId, preset });
+}
+
+export async function deleteExportPreset(
+  name: string,
+  scope: ExportPresetEntry['scope'],
+  documentId: string | null
+): Promise<ExportPresetEntry[]> {
+  return invoke<ExportPresetEntry[]>('export_presets_delete', { documentId, scope, name });
+}
+
+interface PresetExportSource {
+  documentId: string | null;
+  editorContent: JSONContent;
+  titlePage: TitlePageData | null;
+  currentPath: string | null;
+  documentMode: DocumentMode;
+}
+
+/** Runs an export with a saved preset. Returns the exported path, or null when cancelled. */
+export async function exportWithPreset(name: string, source: PresetExportSource): Promise<string | null> {
+  const preset = await invoke<ExportPreset>('export_presets_apply', {
+    documentId: source.documentId,
+    name,
+  });
+  const titlePage = preset.includeTitlePage ? source.titlePage : null;
+
+  switch (preset.format) {
+    case 'pdf':
+      return exportAsPdf(source.editorContent, titlePage, source.currentPath, source.documentMode, preset.pdf);
+    case 'fountain':
+      return exportAsFountain(source.editorContent, titlePage, source.currentPath);
+    case 'fdx':
+      return exportAsFdx(source.editorContent, titlePage, source.currentPath);
+  }
+}
@@ -189,8 +189,17 @@ function renderFdxTextNodes(node: JSONContent, options: { uppercase?: boolean }
     .join('\n      ');
 }
 
+/** Scene headings keep their assigned number, e.g. ` Number="12A"`. */
+function sceneNumberAttr(node: JSONContent): string {
+  const number = node.attrs?.sceneNumber;
+  if ((typeof number !== 'string' && typeof number !== 'number') || String(number).trim() === '') {
+    return '';
+  }
+  return ` Number="${escapeXml(String(number).trim())}"`;
+}
+
 function paragraphOpen(node: JSONContent, type: string): string {
-  return `<Paragraph Type="${type}" id="${generateUUID()}" Alignment="${paragraphAlignment(node)}"${paragraphSpacingAttrs(node)}>`;
+  return `<Paragraph Type="${type}"${sceneNumberAttr(node)} id="${generateUUID()}" Alignment="${paragraphAlignment(node)}"${paragraphSpacingAttrs(node)}>`;
 }
 
 function formatTitlePage(tp: TitlePageData): string {
@@ -1,12 +1,15 @@
-import { invoke } from '@tauri-apps/api/core';
-import { open, save, ask } from '@tauri-apps/plugin-dialog';
+import { Channel, invoke } from '@tauri-apps/api/core';
+import { ask, message } from '@tauri-apps/plugin-dialog';
 import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import type { DocumentMode, ScreenplayDocument, TitlePageData } from './types';
 import type { JSONContent } from '@tiptap/react';
-import { exportToFountain } from './fountain';
+import { exportToFountain, parseFountain } from './fountain';
 import { exportToFdx, importFromFdx } from './fdx';
 import { recordRecentFile } from './recentFiles';
 import { getDefaultContent } from './elementConfig';
+import type { CommandError } from './errors';
+import { saveWithJournal } from './journal';
+import { recordVersion } from './versions';
 import {
   prepareDocumentForCompatibilitySave,
   restoreCompatibleTextStyles,
@@ -19,12 +22,15 @@ export async function updateWindowTitle(filename: string | null, isDirty: boolea
 
 const FILE_EXTENSION = 'gwx';
 const FDX_EXTENSION = 'fdx';
+const FOUNTAIN_EXTENSION = 'fountain';
 const APP_NAME = 'Grainery';
 const APP_VERSION = '1.5.9';
+/** `.gwx` layout version; bump alongside `document::FORMAT_VERSION`. */
+const FORMAT_VERSION = '1.0';
 
 export function createNewDocument(documentMode: ScreenplayDocument['documentMode'] = 'screenplay'): ScreenplayDocument {
   return {
-    formatVersion: '1.0',
+    formatVersion: FORMAT_VERSION,
     documentMode,
     application: {
       name: APP_NAME,
@@ -62,7 +68,7 @@ function populateDocumentMetaFromPath(doc: ScreenplayDocument, filePath: string)
     meta: {
       ...doc.meta,
       filePath,
-      filename: filePath.split(/[\\/]/).pop() || null,
+      filename: getFilenameFromPath(filePath),
     },
   };
 }
@@ -74,7 +80,14 @@ function getPathExtension(path: string): string {
 }
 
 function getFilenameFromPath(path: string): string {
-  return path.split(/[\\/]/).pop() || 'untitled';
+  const filename = path.split(/[\\/]/).pop() || 'untitled';
+  // Mobile document pickers return percent-encoded URLs instead of paths.
+  if (!path.includes('://')) return filename;
+  try {
+    return decodeURIComponent(filename);
+  } catch {
+    return filename;
+  }
 }
 
 function replacePathExtension(filename: string, extension: string): string {
@@ -104,8 +117,86 @@ function createImportedDocument(
   };
 }
 
-export async function openFileAtPath(path: string): Promise<ScreenplayDocument> {
-  const content = await invoke<string>('load_screenplay', { path });
+export type LoadStage = 'reading' | 'validating' | 'sending';
+
+export interface LoadProgress {
+  stage: LoadStage;
+  loadedBytes: number;
+  totalBytes: number | null;
+}
+
+/** A change made so a damaged document could open. */
+export interface DocumentRepair {
+  /** Index of the repaired block, or null for the document as a whole. */
+  block: number | null;
+  message: string;
+}
+
+/** A document written by a newer build, opened as well as this one can. */
+export interface NewerFormat {
+  formatVersion: string;
+  appVersion: string | null;
+}
+
+type LoadEvent =
+  | ({ kind: 'progress' } & LoadProgress)
+  | ({ kind: 'newerFormat' } & NewerFormat)
+  | { kind: 'repaired'; repairs: DocumentRepair[] }
+  | { kind: 'chunk'; data: string }
+  | { kind: 'done' };
+
+/**
+ * Reads a file's text through the backend, which streams it in chunks and
+ * rejects a malformed native document before sending any of it. A document
+ * with recoverable problems arrives repaired, with the repairs reported to
+ * `onRepaired` first; one from a newer build is reported to `onNewerFormat`.
+ */
+export async function loadScreenplay(
+  path: string,
+  onProgress?: (progress: LoadProgress) => void,
+  onRepaired?: (repairs: DocumentRepair[]) => void,
+  onNewerFormat?: (newer: NewerFormat) => void
+): Promise<string> {
+  const chunks: string[] = [];
+  const onEvent = new Channel<LoadEvent>();
+  // Channel messages can trail the command's result, so wait for `done`
+  const received = new Promise<void>((resolve) => {
+    onEvent.onmessage = (event) => {
+      if (event.kind === 'chunk') {
+        chunks.push(event.data);
+      } else if (event.kind === 'progress') {
+        onProgress?.(event);
+      } else if (event.kind === 'repaired') {
+        onRepaired?.(event.repairs);
+      } else if (event.kind === 'newerFormat') {
+        onNewerFormat?.(event);
+      } else {
+        resolve();
+      }
+    };
+  });
+
+  await invoke('load_screenplay', { path, onEvent });
+  await received;
+  return chunks.join('');
+}
+
+export async function openFileAtPath(
+  path: string,
+  onProgress?: (progress: LoadProgress) => void
+): Promise<ScreenplayDocument> {
+  let repairs: DocumentRepair[] = [];
+  let newer: NewerFormat | null = null;
+  const content = await loadScreenplay(
+    path,
+    onProgress,
+    (reported) => {
+      repairs = reported;
+    },
+    (reported) => {
+      newer = reported;
+    }
+  );
 
   if (getPathExtension(path) === FDX_EXTENSION) {
     const doc = createImportedDocument(importFromFdx(content), path);
@@ -113,62 +204,137 @@ export async function openFileAtPath(path: string): Promise<ScreenplayDocument>
     return doc;
   }
 
+  if (getPathExtension(path) === FOUNTAIN_EXTENSION) {
+    const parsed = parseFountain(content);
+    const doc = createImportedDocument({ titlePage: parsed.titlePage, document: parsed.content }, path);
+    recordRecentFile(path, doc.documentMode);
+    return doc;
+  }
+
   const doc = JSON.parse(content) as ScreenplayDocument;
   const normalized = restoreCompatibleTextStyles(
     populateDocumentMetaFromPath(normalizeDocument(doc), path)
   );
   recordRecentFile(path, normalized.documentMode);
+  if (newer) {
+    await showNewerFormat(normalized.meta.filename ?? path, newer);
+  } else if (repairs.length > 0) {
+    await showRepairs(normalized.meta.filename ?? path, repairs);
+  }
   return normalized;
 }
 
-export async function openFile(): Promise<ScreenplayDocument | null> {
-  const filePath = await open({
-    multiple: false,
-    filters: [
-      {
-        name: 'Screenplay',
-        extensions: [FILE_EXTENSION, FDX_EXTENSION],
-      },
-      {
-        name: 'Grainery',
-        extensions: [FILE_EXTENSION],
-      },
-      {
-        name: 'Final Draft',
-        extensions: [FDX_EXTENSION],
-      },
-      {
-        name: 'All Files',
-        extensions: ['*'],
-      },
-    ],
+function showNewerFormat(filename: string, newer: NewerFormat): Promise<void> {
+  const writer = newer.appVersion ? `Grainery ${newer.appVersion}` : 'a newer version of Grainery';
+  return message(
+    `"${filename}" was created by ${writer}. Some of it may be missing or shown differently here, and saving over it could lose that content. Update Grainery to edit it safely.`,
+    { title: 'Created by a Newer Version', kind: 'warning' }
+  );
+}
+
+/** Whether `version` is a `.gwx` layout newer than this build writes. */
+function isNewerFormat(version: string | undefined): boolean {
+  const parse = (value: string) => value.split('.').map((part) => Number(part));
+  const [major, minor = 0] = parse(version ?? FORMAT_VERSION);
+  const [currentMajor, currentMinor] = parse(FORMAT_VERSION);
+  if (Number.isNaN(major) || Number.isNaN(minor)) return false;
+  return major > currentMajor || (major === currentMajor && minor > currentMinor);
+}
+
+/** Records the layout and app version that wrote the document. */
+function stampDocument(doc: ScreenplayDocument): ScreenplayDocument {
+  return {
+    ...doc,
+    formatVersion: FORMAT_VERSION,
+    application: { name: APP_NAME, version: APP_VERSION },
+  };
+}
+
+const MAX_LISTED_REPAIRS = 10;
+
+function showRepairs(filename: string, repairs: DocumentRepair[]): Promise<void> {
+  const lines = repairs
+    .slice(0, MAX_LISTED_REPAIRS)
+    .map((repair) =>
+      repair.block === null ? `• ${repair.message}` : `• Element ${repair.block + 1}: ${repair.message}`
+    );
+  if (repairs.length > MAX_LISTED_REPAIRS) {
+    lines.push(`…and ${repairs.length - MAX_LISTED_REPAIRS} more`);
+  }
+  return message(
+    `"${filename}" was damaged and has been repaired so it can open. Check these places before saving:\n\n${lines.join('\n')}`,
+    { title: 'Document Repaired', kind: 'warning' }
+  );
+}
+
+type OpenDialogOperation = 'open' | 'import_fdx' | 'import_feedback';
+type SaveDialogOperation =
+  | 'save'
+  | 'export_fountain'
+  | 'export_pdf'
+  | 'export_fdx'
+  | 'export_feedback';
+
+/**
+ * Native open dialog with the operation's file filters, starting in the
+ * directory last used for that operation.
+ */
+function pickOpenPath(operation: OpenDialogOperation): Promise<string | null> {
+  return invoke<string | null>('dialog_open_path', { operation, currentPath: null });
+}
+
+/**
+ * Native save dialog with the operation's file filter. The suggested name is
+ * derived from the title page title (or the current file name for saves).
+ */
+function pickSavePath(
+  operation: SaveDialogOperation,
+  titlePage: TitlePageData | null,
+  currentPath: string | null
+): Promise<string | null> {
+  return invoke<string | null>('dialog_save_path', {
+    operation,
+    suggestion: { title: titlePage?.title ?? null, currentPath },
   });
+}
+
+function getBaseName(path: string | null): string {
+  return path ? getFilenameFromPath(path).replace(/\.[^.]+$/, '') : 'untitled';
+}
 
+export async function openFile(): Promise<ScreenplayDocument | null> {
+  const filePath = await pickOpenPath('open');
   if (!filePath) return null;
 
   return openFileAtPath(filePath);
 }
 
 export async function importFdxFile(): Promise<ScreenplayDocument | null> {
-  const filePath = await open({
-    multiple: false,
-    filters: [
-      {
-        name: 'Final Draft',
-        extensions: [FDX_EXTENSION],
-      },
-      {
-        name: 'All Files',
-        extensions: ['*'],
-      },
-    ],
-  });
-
+  const filePath = await pickOpenPath('import_fdx');
   if (!filePath) return null;
 
   return openFileAtPath(filePath);
 }
 
+/** Reads a document the backend merged with synced changes and wrote to `path`. */
+export function parseSyncedDocument(documentJson: string, path: string | null): ScreenplayDocument {
+  const doc = normalizeDocument(JSON.parse(documentJson) as ScreenplayDocument);
+  return restoreCompatibleTextStyles(path ? populateDocumentMetaFromPath(doc, path) : doc);
+}
+
+/**
+ * Records a save as a version. When a sync brought in changes since the
+ * document was opened, returns the merged document written over the save.
+ */
+async function recordSavedVersion(
+  compatible: ReturnType<typeof prepareDocumentForCompatibilitySave>,
+  path: string
+): Promise<ScreenplayDocument> {
+  const recorded = await recordVersion(compatible.diskDocument, path);
+  if (!recorded?.mergedDocumentJson) return compatible.appDocument;
+  return parseSyncedDocument(recorded.mergedDocumentJson, path);
+}
+
 export async function saveFile(
   doc: ScreenplayDocument,
   editorContent: JSONContent
@@ -177,8 +343,21 @@ export async function saveFile(
     return saveFileAs(doc, editorContent);
   }
 
+  if (isNewerFormat(doc.formatVersion)) {
+    const overwrite = await ask(
+      'This document was created by a newer version of Grainery. Saving over it with this version may lose content it doesn\'t support.',
+      {
+        title: 'Created by a Newer Version',
+        kind: 'warning',
+        okLabel: 'Save Anyway',
+        cancelLabel: 'Cancel',
+      }
+    );
+    if (!overwrite) return null;
+  }
+
   const updatedDoc: ScreenplayDocument = {
-    ...doc,
+    ...stampDocument(doc),
     document: editorContent,
     meta: {
       ...doc.meta,
@@ -187,50 +366,85 @@ export async function saveFile(
   };
   const compatible = prepareDocumentForCompatibilitySave(updatedDoc);
 
-  await invoke('save_screenplay', {
-    path: doc.meta.filePath,
-    content: JSON.stringify(compatible.diskDocument, null, 2),
+  const saved = await saveWithJournal(async () => {
+    await invoke('save_screenplay', {
+      path: doc.meta.filePath,
+      content: JSON.stringify(compatible.diskDocument, null, 2),
+    });
+    return recordSavedVersion(compatible, doc.meta.filePath);
   });
 
   recordRecentFile(doc.meta.filePath, compatible.appDocument.documentMode);
-  return compatible.appDocument;
+  return saved;
+}
+
+export interface SaveTargetConflict {
+  path: string;
+  title: string | null;
+  modifiedAt: string | null;
+  /** False when the existing file isn't a Grainery document. */
+  isDocument: boolean;
+}
+
+/**
+ * Warns before Save As replaces a different screenplay. Re-saving over the
+ * document's own file goes ahead without asking.
+ */
+async function confirmSaveTarget(path: string, documentId: string): Promise<boolean> {
+  const conflict = await invoke<SaveTargetConflict | null>('check_save_target', {
+    path,
+    documentId,
+  });
+  if (!conflict) return true;
+
+  const name = getFilenameFromPath(conflict.path);
+  const what = conflict.isDocument
+    ? conflict.title
+      ? `"${name}" is a different screenplay, "${conflict.title}".`
+      : `"${name}" is a different screenplay.`
+    : `"${name}" is not a Grainery document.`;
+  const when = conflict.modifiedAt
+    ? ` It was last modified ${new Date(conflict.modifiedAt).toLocaleString()}.`
+    : '';
+  return ask(`${what}${when} Replacing it cannot be undone.`, {
+    title: 'Replace Existing File?',
+    kind: 'warning',
+    okLabel: 'Replace',
+    cancelLabel: 'Cancel',
+  });
 }
 
 export async function saveFileAs(
   doc: ScreenplayDocument,
   editorContent: JSONContent
 ): Promise<ScreenplayDocument | null> {
-  const filePath = await save({
-    filters: [
-      {
-        name: 'Grainery Document',
-        extensions: [FILE_EXTENSION],
-      },
-    ],
-    defaultPath: doc.meta.filename || 'untitled.gwx',
-  });
+  const filePath = await pickSavePath('save', doc.titlePage, doc.meta.filePath ?? doc.meta.filename);
 
   if (!filePath) return null;
+  if (!(await confirmSaveTarget(filePath, doc.meta.id))) return null;
 
   const updatedDoc: ScreenplayDocument = {
-    ...doc,
+    ...stampDocument(doc),
     document: editorContent,
     meta: {
       ...doc.meta,
       filePath,
-      filename: filePath.split(/[\\/]/).pop() || null,
+      filename: getFilenameFromPath(filePath),
       modifiedAt: new Date().toISOString(),
     },
   };
   const compatible = prepareDocumentForCompatibilitySave(updatedDoc);
 
-  await invoke('save_screenplay', {
-    path: filePath,
-    content: JSON.stringify(compatible.diskDocument, null, 2),
+  const saved = await saveWithJournal(async () => {
+    await invoke('save_screenplay', {
+      path: filePath,
+      content: JSON.stringify(compatible.diskDocument, null, 2),
+    });
+    return recordSavedVersion(compatible, filePath);
   });
 
   recordRecentFile(filePath, compatible.appDocument.documentMode);
-  return compatible.appDocument;
+  return saved;
 }
 
 export async function confirmUnsavedChanges(): Promise<boolean> {
@@ -245,24 +459,11 @@ export async function confirmUnsavedChanges(): Promise<boolean> {
 export async function exportAsFountain(
   editorContent: JSONContent,
   titlePage: TitlePageData | null,
-  currentFilename: string | null
-): Promise<boolean> {
-  // Generate default filename from current file or 'untitled'
-  const baseName = currentFilename
-    ? currentFilename.replace(/\.[^.]+$/, '')
-    : 'untitled';
-
-  const filePath = await save({
-    filters: [
-      {
-        name: 'Fountain',
-        extensions: ['fountain'],
-      },
-    ],
-    defaultPath: `${baseName}.fountain`,
-  });
+  currentPath: string | null
+): Promise<string | null> {
+  const filePath = await pickSavePath('export_fountain', titlePage, currentPath);
 
-  if (!filePath) return false;
+  if (!filePath) return null;
 
   const fountainContent = exportToFountain(editorContent, titlePage);
 
@@ -271,62 +472,180 @@ export async function exportAsFountain(
     content: fountainContent,
   });
 
-  return true;
+  await recordExport(filePath);
+  return filePath;
+}
+
+export interface PdfOptions {
+  paperSize: 'letter' | 'a4';
+  watermark: string | null;
+  sceneNumbers: boolean;
+  revisionMarks: boolean;
+  /** Highlights this character's dialogue and dims everyone else's. */
+  highlightCharacter?: string | null;
 }
 
 export async function exportAsPdf(
   editorContent: JSONContent,
   titlePage: TitlePageData | null,
-  currentFilename: string | null,
-  documentMode: DocumentMode
-): Promise<boolean> {
-  const baseName = currentFilename
-    ? currentFilename.replace(/\.[^.]+$/, '')
-    : 'untitled';
-
-  const filePath = await save({
-    filters: [
-      {
-        name: 'PDF',
-        extensions: ['pdf'],
-      },
-    ],
-    defaultPath: `${baseName}.pdf`,
-  });
+  currentPath: string | null,
+  documentMode: DocumentMode,
+  options?: PdfOptions
+): Promise<string | null> {
+  const filePath = await pickSavePath('export_pdf', titlePage, currentPath);
 
-  if (!filePath) return false;
+  if (!filePath) return null;
 
   await invoke('export_pdf', {
     contentJson: JSON.stringify(editorContent),
     titlePageJson: titlePage ? JSON.stringify(titlePage) : null,
     outputPath: filePath,
-    documentTitle: baseName,
+    documentTitle: getBaseName(currentPath),
     documentMode,
+    options: options ?? null,
   });
 
-  return true;
+  await recordExport(filePath);
+  return filePath;
 }
 
-export async function exportAsFdx(
+/**
+ * Copies the document to the clipboard as Fountain text. Pass a selection
+ * as its own `doc` to copy just that.
+ */
+export async function copyAsFountain(
+  editorContent: JSONContent,
+  titlePage: TitlePageData | null = null
+): Promise<void> {
+  await invoke('copy_as_fountain', {
+    contentJson: JSON.stringify(editorContent),
+    titlePageJson: titlePage ? JSON.stringify(titlePage) : null,
+  });
+}
+
+/** Copies with screenplay formatting (RTF and HTML) for word processors and email. */
+export async function copyAsFormattedText(editorContent: JSONContent): Promise<void> {
+  await invoke('copy_as_formatted_text', {
+    contentJson: JSON.stringify(editorContent),
+  });
+}
+
+export interface ClassifiedPaste {
+  kind: 'fountain' | 'fdx' | 'prose';
+  /** Block nodes to insert at the cursor. */
+  nodes: JSONContent[];
+  titlePage: TitlePageData | null;
+}
+
+/** Detects Fountain, FDX or prose in pasted text and converts it to nodes. */
+export async function classifyClipboardText(text: string): Promise<ClassifiedPaste> {
+  return invoke<ClassifiedPaste>('classify_clipboard_text', { text });
+}
+
+export type ChangeKind = 'added' | 'removed' | 'changed';
+
+export interface ElementChange {
+  kind: ChangeKind;
+  elementType: string;
+  oldIndex?: number;
+  newIndex?: number;
+  oldText?: string;
+  newText?: string;
+  character?: string;
+}
+
+export interface Changeset {
+  elements: ElementChange[];
+  scenes: { kind: ChangeKind; heading: string; oldNumber?: string; newNumber?: string }[];
+  dialogueByCharacter: { name: string; added: number; removed: number; changed: number }[];
+  unchangedElements: number;
+}
+
+/** Lists what the unsaved buffer changes relative to the file at `path`. */
+export async function diffAgainstDisk(path: string, content: JSONContent): Promise<Changeset> {
+  return invoke<Changeset>('diff_against_disk', { path, contentJson: JSON.stringify(content) });
+}
+
+export interface SidesResult {
+  character: string;
+  /** Null when the character has no dialogue and no file was written. */
+  path: string | null;
+  scenes: string[];
+}
+
+/** Writes one PDF of highlighted sides per character into `outputDir`. */
+export async function exportSides(
   editorContent: JSONContent,
   titlePage: TitlePageData | null,
-  currentFilename: string | null
-): Promise<boolean> {
+  currentPath: string | null,
+  characters: string[],
+  outputDir: string,
+  options?: PdfOptions
+): Promise<SidesResult[]> {
+  return invoke<SidesResult[]>('export_sides', {
+    contentJson: JSON.stringify(editorContent),
+    titlePageJson: titlePage ? JSON.stringify(titlePage) : null,
+    characters,
+    outputDir,
+    documentTitle: getBaseName(currentPath),
+    options: options ?? null,
+  });
+}
+
+export interface PrintOptions {
+  firstPage?: number;
+  lastPage?: number;
+  copies?: number;
+}
+
+export async function printScreenplay(
+  editorContent: JSONContent,
+  titlePage: TitlePageData | null,
+  currentFilename: string | null,
+  documentMode: DocumentMode,
+  options?: PrintOptions
+): Promise<void> {
   const baseName = currentFilename
     ? currentFilename.replace(/\.[^.]+$/, '')
     : 'untitled';
 
-  const filePath = await save({
-    filters: [
-      {
-        name: 'Final Draft',
-        extensions: ['fdx'],
-      },
-    ],
-    defaultPath: `${baseName}.fdx`,
+  await invoke('print_screenplay', {
+    contentJson: JSON.stringify(editorContent),
+    titlePageJson: titlePage ? JSON.stringify(titlePage) : null,
+    documentTitle: baseName,
+    documentMode,
+    options: options ?? null,
   });
+}
 
-  if (!filePath) return false;
+/**
+ * Exports a PDF and opens a draft in the default mail client with it
+ * attached, using the title page title as the subject.
+ */
+export async function shareViaEmail(
+  editorContent: JSONContent,
+  titlePage: TitlePageData | null,
+  currentFilename: string | null,
+  documentMode: DocumentMode,
+  options?: PdfOptions
+): Promise<void> {
+  await invoke('share_via_email', {
+    contentJson: JSON.stringify(editorContent),
+    titlePageJson: titlePage ? JSON.stringify(titlePage) : null,
+    documentTitle: getBaseName(currentFilename),
+    documentMode,
+    options: options ?? null,
+  });
+}
+
+export async function exportAsFdx(
+  editorContent: JSONContent,
+  titlePage: TitlePageData | null,
+  currentPath: string | null
+): Promise<string | null> {
+  const filePath = await pickSavePath('export_fdx', titlePage, currentPath);
+
+  if (!filePath) return null;
 
   const fdxContent = exportToFdx(editorContent, titlePage);
 
@@ -335,5 +654,118 @@ export async function exportAsFdx(
     content: fdxContent,
   });
 
-  return true;
+  await recordExport(filePath);
+  return filePath;
+}
+
+export type ExportFormat = 'pdf' | 'fdx' | 'fountain';
+
+export interface ExportTarget {
+  format: ExportFormat;
+  path: string;
+  pdfOptions?: PdfOptions;
+}
+
+export interface ExportResult {
+  format: ExportFormat;
+  path: string;
+  /** `null` when the file was written. */
+  error: CommandError | null;
+}
+
+/**
+ * Writes several outputs in one background job, e.g. PDF, FDX and Fountain
+ * together or watermarked PDF copies for each recipient. One failed output
+ * doesn't stop the rest; check each result's `error`.
+ */
+export async function exportBatch(
+  editorContent: JSONContent,
+  titlePage: TitlePageData | null,
+  currentPath: string | null,
+  documentMode: DocumentMode,
+  targets: ExportTarget[]
+): Promise<ExportResult[]> {
+  const results = await invoke<ExportResult[]>('export_batch', {
+    contentJson: JSON.stringify(editorContent),
+    titlePageJson: titlePage ? JSON.stringify(titlePage) : null,
+    documentTitle: getBaseName(currentPath),
+    documentMode,
+    targets,
+  });
+
+  for (const result of results) {
+    if (!result.error) {
+      await recordExport(result.path);
+    }
+  }
+  return results;
+}
+
+/**
+ * Exports a self-contained HTML copy of the script for readers. Their notes
+ * are downloaded as a JSON file that `importFeedback` turns into comments.
+ */
+export async function exportForFeedback(
+  doc: ScreenplayDocument,
+  editorContent: JSONContent,
+  currentPath: string | null
+): Promise<string | null> {
+  const filePath = await pickSavePath('export_feedback', doc.titlePage, currentPath);
+
+  if (!filePath) return null;
+
+  await invoke('export_feedback_bundle', {
+    documentJson: JSON.stringify({ ...doc, document: editorContent }),
+    outputPath: filePath,
+    documentTitle: doc.titlePage?.title || getBaseName(currentPath),
+  });
+
+  await recordExport(filePath);
+  return filePath;
+}
+
+export interface FeedbackImport {
+  document: ScreenplayDocument;
+  imported: number;
+  /** Notes already imported, or pointing past the end of the script. */
+  skipped: number;
+}
+
+/** Adds the notes from a reader's feedback file to the document as comments. */
+export async function importFeedback(doc: ScreenplayDocument): Promise<FeedbackImport | null> {
+  const inputPath = await pickOpenPath('import_feedback');
+  if (!inputPath) return null;
+
+  const result = await invoke<{ documentJson: string; imported: number; skipped: number }>(
+    'import_feedback',
+    { documentJson: JSON.stringify(doc), inputPath }
+  );
+  return {
+    document: JSON.parse(result.documentJson) as ScreenplayDocument,
+    imported: result.imported,
+    skipped: result.skipped,
+  };
+}
+
+/** Shows the file selected in Finder/Explorer/the desktop file manager. */
+export async function revealInFileManager(path: string): Promise<void> {
+  await invoke('reveal_in_file_manager', { path });
+}
+
+export async function openWithDefaultApp(path: string): Promise<void> {
+  await invoke('open_with_default_app', { path });
+}
+
+/** Points the File menu's "Reveal in ..." and "Open in Default App" items at the open document. */
+export async function setMenuDocumentPath(path: string | null): Promise<void> {
+  await invoke('file_targets_set_document', { path });
+}
+
+/** Enables the File menu's last-export items after a successful export. */
+async function recordExport(path: string): Promise<void> {
+  try {
+    await invoke('file_targets_set_export', { path });
+  } catch (error) {
+    console.error('Failed to record export:', error);
+  }
 }
@@ -0,0 +1,52 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type JobState = 'queued' | 'running' | 'succeeded' | 'failed' | 'cancelled';
+
+/** A long-running backend task: an export, report, analysis or download. */
+export interface JobInfo {
+  id: number;
+  kind: string;
+  label: string;
+  state: JobState;
+  /** 0 to 1, or null while the job can't tell how far along it is. */
+  progress: number | null;
+  message: string | null;
+  error: string | null;
+}
+
+export function isActiveJob(job: JobInfo): boolean {
+  return job.state === 'queued' || job.state === 'running';
+}
+
+export async function listJobs(): Promise<JobInfo[]> {
+  return invoke<JobInfo[]>('jobs_list');
+}
+
+export async function cancelJob(id: number): Promise<JobInfo> {
+  return invoke<JobInfo>('jobs_cancel', { id });
+}
+
+/**
+ * Calls `onChange` with the full job list now and after every job update.
+ * Returns a function that stops listening.
+ */
+export async function subscribeJobs(onChange: (jobs: JobInfo[]) => void): Promise<() => void> {
+  let jobs: JobInfo[] = [];
+  const unlisten = await listen<JobInfo>('job-changed', (event) => {
+    const job = event.payload;
+    const index = jobs.findIndex((existing) => existing.id === job.id);
+    jobs = index === -1 ? [...jobs, job] : jobs.map((existing, i) => (i === index ? job : existing));
+    onChange(jobs);
+  });
+
+  const listed = await listJobs();
+  // Keep updates that arrived while the list was loading
+  const updated = new Map(jobs.map((job) => [job.id, job]));
+  jobs = [
+    ...listed.map((job) => updated.get(job.id) ?? job),
+    ...jobs.filter((job) => !listed.some((existing) => existing.id === job.id)),
+  ];
+  onChange(jobs);
+  return unlisten;
+}
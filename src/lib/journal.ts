@@ -0,0 +1,109 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BlockEdit } from './liveDocument';
+import type { ScreenplayDocument } from './types';
+
+// Edits typed within this window reach the backend together
+const SEND_DELAY_MS = 300;
+
+/** Unsaved work replayed from the journal a crash left behind. */
+export interface JournalRecovery {
+  document: ScreenplayDocument;
+  editCount: number;
+  lastEditAt: string;
+}
+
+let buffered: BlockEdit[] = [];
+let timer: ReturnType<typeof setTimeout> | undefined;
+/** Set while a save runs; edits wait until the journal is compacted. */
+let held: BlockEdit[] | null = null;
+
+function sendBufferedEdits(): void {
+  clearTimeout(timer);
+  timer = undefined;
+  if (buffered.length === 0) return;
+
+  const edits = buffered;
+  buffered = [];
+  void invoke('journal_append', { edits }).catch((error) => {
+    console.error('[Journal] Failed to record edits', error);
+  });
+}
+
+/** Starts journaling `doc`, whose blocks must match the editor's. */
+export async function openJournal(doc: ScreenplayDocument): Promise<void> {
+  clearTimeout(timer);
+  timer = undefined;
+  buffered = [];
+  held = null;
+  await invoke('journal_open', { documentJson: JSON.stringify(doc) });
+}
+
+/** Queues block edits, in the order the editor made them, for the journal. */
+export function recordJournalEdits(edits: BlockEdit[]): void {
+  if (held) {
+    held.push(...edits);
+    return;
+  }
+
+  buffered.push(...edits);
+  timer ??= setTimeout(sendBufferedEdits, SEND_DELAY_MS);
+}
+
+/**
+ * Runs `save` and compacts the journal to the document it wrote. Edits made
+ * while the save runs are journaled on top of that document afterwards.
+ */
+export async function saveWithJournal(
+  save: () => Promise<ScreenplayDocument | null>
+): Promise<ScreenplayDocument | null> {
+  clearTimeout(timer);
+  timer = undefined;
+  // Edits so far are in the document being saved
+  const unsent = buffered;
+  buffered = [];
+  held = [];
+  let compacted = false;
+
+  try {
+    const saved = await save();
+    if (saved) {
+      await invoke('journal_compact', { documentJson: JSON.stringify(saved) })
+        .then(() => {
+          compacted = true;
+        })
+        .catch((error) => {
+          console.error('[Journal] Failed to compact journal', error);
+        });
+    }
+    return saved;
+  } finally {
+    // Without compaction the journal still needs the edits it hasn't seen
+    buffered = compacted ? (held ?? []) : [...unsent, ...(held ?? [])];
+    held = null;
+    sendBufferedEdits();
+  }
+}
+
+/** Unsaved edits to `documentId` left by a crash, or null. */
+export async function recoverJournal(documentId: string): Promise<JournalRecovery | null> {
+  const recovery = await invoke<{ documentJson: string; editCount: number; lastEditAt: string } | null>(
+    'journal_recover',
+    { documentId }
+  );
+  if (!recovery) return null;
+
+  return {
+    document: JSON.parse(recovery.documentJson) as ScreenplayDocument,
+    editCount: recovery.editCount,
+    lastEditAt: recovery.lastEditAt,
+  };
+}
+
+/** Deletes the journal once the document is saved or its changes discarded. */
+export async function closeJournal(): Promise<void> {
+  clearTimeout(timer);
+  timer = undefined;
+  buffered = [];
+  held = null;
+  await invoke('journal_close');
+}
@@ -0,0 +1,99 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+/** A device this one has paired with. */
+export interface PairedDevice {
+  deviceId: string;
+  name: string;
+  publicKey: string;
+  pairedAt: string;
+  lastSyncedAt: string | null;
+  fingerprint: string;
+  online: boolean;
+}
+
+/** An unpaired Grainery device heard on the local network. */
+export interface NearbyDevice {
+  deviceId: string;
+  name: string;
+  fingerprint: string;
+}
+
+export interface LanSyncStatus {
+  enabled: boolean;
+  deviceId: string;
+  deviceName: string;
+  /** This device's key fingerprint; known once sync has been turned on. */
+  fingerprint: string | null;
+  nearby: NearbyDevice[];
+  paired: PairedDevice[];
+  lastError: string | null;
+}
+
+/** Asks the writer to compare `code` with the one shown on the other device. */
+export interface PairingRequest {
+  pairingId: string;
+  deviceName: string;
+  code: string;
+  /** True on the device where pairing was started. */
+  initiated: boolean;
+}
+
+/** A document a sync changed. Its file was already rewritten. */
+export interface SyncedDocument {
+  documentId: string;
+  hash: string;
+  documentJson: string;
+  /** Blocks changed on both devices, kept twice and marked with a comment. */
+  conflicts: number;
+  deviceName: string;
+  path: string | null;
+}
+
+export async function getLanSyncStatus(): Promise<LanSyncStatus> {
+  return invoke<LanSyncStatus>('lan_sync_status');
+}
+
+export async function setLanSyncEnabled(enabled: boolean): Promise<LanSyncStatus> {
+  return invoke<LanSyncStatus>('lan_sync_set_enabled', { enabled });
+}
+
+export async function renameSyncDevice(name: string): Promise<LanSyncStatus> {
+  return invoke<LanSyncStatus>('lan_sync_rename_device', { name });
+}
+
+/** Starts pairing with a nearby device; both sides then show a code to confirm. */
+export async function pairDevice(deviceId: string): Promise<void> {
+  await invoke('lan_sync_pair', { deviceId });
+}
+
+export async function answerPairing(pairingId: string, accepted: boolean): Promise<void> {
+  await invoke('lan_sync_answer_pairing', { pairingId, accepted });
+}
+
+export async function unpairDevice(deviceId: string): Promise<LanSyncStatus> {
+  return invoke<LanSyncStatus>('lan_sync_unpair', { deviceId });
+}
+
+export async function syncNow(): Promise<void> {
+  await invoke('lan_sync_now');
+}
+
+/** Calls `onChange` whenever devices come and go or a sync fails. */
+export async function subscribeLanSyncStatus(
+  onChange: (status: LanSyncStatus) => void
+): Promise<() => void> {
+  return listen<LanSyncStatus>('lan-sync-status', (event) => onChange(event.payload));
+}
+
+export async function subscribePairingRequests(
+  onRequest: (request: PairingRequest) => void
+): Promise<() => void> {
+  return listen<PairingRequest>('lan-sync-pairing', (event) => onRequest(event.payload));
+}
+
+export async function subscribeSyncedDocuments(
+  onSynced: (synced: SyncedDocument) => void
+): Promise<() => void> {
+  return listen<SyncedDocument>('lan-sync-document', (event) => onSynced(event.payload));
+}
@@ -0,0 +1,103 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { JSONContent } from '@tiptap/react';
+import type { Pagination, PaperSize } from './pagination';
+
+/** Replaces `deleteCount` top-level blocks at `from` with `insert`. */
+export interface BlockEdit {
+  from: number;
+  deleteCount: number;
+  insert: JSONContent[];
+}
+
+export interface CharacterSuggestion {
+  name: string;
+  speakCount: number;
+  /** Block index of the character's last cue. */
+  lastBlock: number;
+}
+
+/** Settles once every load and edit sent so far has reached the mirror. */
+let pendingSync: Promise<unknown> = Promise.resolve();
+
+function trackSync<T>(request: Promise<T>): Promise<T> {
+  pendingSync = Promise.allSettled([pendingSync, request]);
+  return request;
+}
+
+/** Replaces the backend mirror of the open document. */
+export async function loadLiveDocument(content: JSONContent): Promise<void> {
+  await trackSync(invoke('live_document_load', { contentJson: JSON.stringify(content) }));
+}
+
+/** Fails when the mirror is out of step; reload it with `loadLiveDocument`. */
+export async function applyBlockEdits(edits: BlockEdit[]): Promise<void> {
+  await trackSync(invoke('live_document_apply', { edits }));
+}
+
+/** Character names starting with `prefix`, most-spoken first. */
+export async function getCharacterSuggestions(
+  prefix: string,
+  limit?: number
+): Promise<CharacterSuggestion[]> {
+  return invoke<CharacterSuggestion[]>('get_character_suggestions', {
+    prefix,
+    limit: limit ?? null,
+  });
+}
+
+export interface LocationSuggestion {
+  location: string;
+  /** Empty when the slugline had no time of day. */
+  time: string;
+  useCount: number;
+}
+
+export interface TimeSuggestion {
+  time: string;
+  useCount: number;
+}
+
+/**
+ * Location and time pairs already used in the script that match a partly
+ * typed heading, e.g. `INT. KI` or `INT. KITCHEN - N`.
+ */
+export async function getLocationSuggestions(
+  prefix: string,
+  limit?: number
+): Promise<LocationSuggestion[]> {
+  return invoke<LocationSuggestion[]>('get_location_suggestions', {
+    prefix,
+    limit: limit ?? null,
+  });
+}
+
+/** Times of day already used in the script, most used first. */
+export async function getTimeSuggestions(prefix: string, limit?: number): Promise<TimeSuggestion[]> {
+  return invoke<TimeSuggestion[]>('get_time_suggestions', { prefix, limit: limit ?? null });
+}
+
+export interface SceneListEntry {
+  sceneId: string | null;
+  sceneNumber: string | null;
+  slugline: string;
+  blockIndex: number;
+  /** Characters with a cue in the scene, in order of first appearance. */
+  characters: string[];
+  /** Page the scene starts on, on Letter paper. */
+  page: number;
+  /** Length in eighths of a page, on Letter paper. */
+  eighths: number;
+  /** Color of the linked outline card. */
+  color: string | null;
+}
+
+/** Scenes of the open document; pass `documentId` to include card colors. */
+export async function getSceneList(documentId?: string | null): Promise<SceneListEntry[]> {
+  return invoke<SceneListEntry[]>('get_scene_list', { documentId: documentId ?? null });
+}
+
+/** Page layout of the open document, once edits sent so far have landed. */
+export async function getPagination(paperSize?: PaperSize): Promise<Pagination> {
+  await pendingSync;
+  return invoke<Pagination>('get_pagination', { paperSize: paperSize ?? null });
+}
@@ -0,0 +1,10 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** The newest lines of the diagnostics log, oldest first. */
+export async function getRecentLogLines(limit?: number): Promise<string[]> {
+  return invoke<string[]>('logs_recent', { limit: limit ?? null });
+}
+
+export async function openLogFolder(): Promise<void> {
+  await invoke('logs_open_folder');
+}
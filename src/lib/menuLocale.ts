@@ -0,0 +1,23 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface MenuLocaleOption {
+  code: string;
+  name: string;
+}
+
+/** Native menu language, persisted by the backend in `menu-locale.json`. */
+export interface MenuLocaleInfo {
+  active: string;
+  overrideLocale: string | null;
+  systemLocale: string | null;
+  available: MenuLocaleOption[];
+}
+
+export async function getMenuLocale(): Promise<MenuLocaleInfo> {
+  return invoke<MenuLocaleInfo>('menu_locale_get');
+}
+
+/** Saves the menu language; `null` follows the system. Applies on next launch. */
+export async function setMenuLocale(locale: string | null): Promise<MenuLocaleInfo> {
+  return invoke<MenuLocaleInfo>('menu_locale_set', { locale });
+}
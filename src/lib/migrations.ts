@@ -0,0 +1,22 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** A startup migration step that failed; app data was put back as it was. */
+export interface MigrationFailure {
+  version: number;
+  description: string;
+  error: string;
+  /** Copies of the files from before the migration, if one was made. */
+  backupDir: string | null;
+}
+
+export interface MigrationStatus {
+  version: number;
+  /** Steps applied this launch. */
+  applied: string[];
+  failure: MigrationFailure | null;
+}
+
+/** The outcome of the app data migrations run at startup. */
+export async function getMigrationStatus(): Promise<MigrationStatus> {
+  return invoke<MigrationStatus>('migrations_status');
+}
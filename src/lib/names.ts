@@ -0,0 +1,47 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface NameCriteria {
+  origin?: string | null;
+  /** `classic`, `midcentury` or `contemporary`. */
+  era?: string | null;
+  /** `f` or `m`; unisex names match either. */
+  gender?: string | null;
+  initial?: string | null;
+  givenOnly?: boolean;
+  /** Keep names that share a word with a character already in the script. */
+  allowConflicts?: boolean;
+  count?: number | null;
+  seed?: number | null;
+}
+
+export interface NameCheck {
+  /** Characters in the script sharing a given name or surname. */
+  conflicts: string[];
+  /** Characters whose name starts with the same letter. */
+  sameInitial: string[];
+}
+
+export interface GeneratedName extends NameCheck {
+  fullName: string;
+  given: string;
+  surname: string | null;
+  origin: string;
+}
+
+export interface NameOptions {
+  origins: string[];
+  eras: string[];
+}
+
+export async function getNameOptions(): Promise<NameOptions> {
+  return invoke<NameOptions>('names_options');
+}
+
+/** Checks against the characters in the open script. */
+export async function generateNames(criteria: NameCriteria = {}): Promise<GeneratedName[]> {
+  return invoke<GeneratedName[]>('generate_names', { criteria });
+}
+
+export async function checkCharacterName(name: string): Promise<NameCheck> {
+  return invoke<NameCheck>('check_character_name', { name });
+}
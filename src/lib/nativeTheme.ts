@@ -8,7 +8,12 @@ type RgbColor = {
 
 const TITLEBAR_COLOR_PROPERTY = '--color-base-100';
 
-export async function syncNativeTitlebarTheme(): Promise<void> {
+interface NativeThemeOptions {
+  /** Let the window chrome keep tracking the OS appearance. */
+  followSystem?: boolean;
+}
+
+export async function syncNativeTitlebarTheme({ followSystem = false }: NativeThemeOptions = {}): Promise<void> {
   if (!isTauriRuntime()) {
     return;
   }
@@ -24,6 +29,7 @@ export async function syncNativeTitlebarTheme(): Promise<void> {
       green: color.green,
       blue: color.blue,
       dark: getRelativeLuminance(color) < 0.45,
+      followSystem,
     });
   } catch (error) {
     console.warn('Failed to sync native titlebar theme', error);
@@ -0,0 +1,74 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ScreenplayDocument } from './types';
+
+/** A beat or index card, linked to a scene heading through its `sceneId` attribute. */
+export interface IndexCard {
+  id: string;
+  title: string;
+  synopsis: string;
+  color: string | null;
+  order: number;
+  sceneId: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface IndexCardInput {
+  title: string;
+  synopsis?: string;
+  color?: string | null;
+  sceneId?: string | null;
+}
+
+export interface GeneratedScenes {
+  document: ScreenplayDocument;
+  cards: IndexCard[];
+  /** Ids of the cards that got a new scene heading. */
+  created: string[];
+}
+
+export async function listIndexCards(documentId: string): Promise<IndexCard[]> {
+  return invoke<IndexCard[]>('outline_list_cards', { documentId });
+}
+
+/** Adds a card at `position` in the outline, or at the end. */
+export async function addIndexCard(
+  documentId: string,
+  card: IndexCardInput,
+  position?: number
+): Promise<IndexCard> {
+  return invoke<IndexCard>('outline_add_card', { documentId, card, position: position ?? null });
+}
+
+export async function updateIndexCard(
+  documentId: string,
+  cardId: string,
+  card: IndexCardInput
+): Promise<IndexCard> {
+  return invoke<IndexCard>('outline_update_card', { documentId, cardId, card });
+}
+
+export async function deleteIndexCard(documentId: string, cardId: string): Promise<void> {
+  await invoke('outline_delete_card', { documentId, cardId });
+}
+
+/** `cardIds` must list every card of the document once, in the new order. */
+export async function reorderIndexCards(documentId: string, cardIds: string[]): Promise<IndexCard[]> {
+  return invoke<IndexCard[]>('outline_reorder_cards', { documentId, cardIds });
+}
+
+/** Adds scene headings for cards not yet linked to a scene, in card order. */
+export async function generateScenesFromCards(document: ScreenplayDocument): Promise<GeneratedScenes> {
+  const result = await invoke<{ documentJson: string; cards: IndexCard[]; created: string[] }>(
+    'outline_generate_scenes',
+    {
+      documentId: document.meta.id,
+      documentJson: JSON.stringify(document),
+    }
+  );
+  return {
+    document: JSON.parse(result.documentJson) as ScreenplayDocument,
+    cards: result.cards,
+    created: result.created,
+  };
+}
@@ -0,0 +1,77 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Node as ProseMirrorNode } from '@tiptap/pm/model';
+import type { JSONContent } from '@tiptap/react';
+
+export type PaperSize = 'letter' | 'a4';
+
+export interface LinePosition {
+  page: number;
+  /** 0-based line slot on the page. */
+  line: number;
+  /** Points below the top margin. */
+  top: number;
+}
+
+export interface BlockLayout {
+  /** Page the block's first line is on. */
+  page: number;
+  /** Line offset of the block's first line from the top of page one. */
+  line: number;
+  /** Empty for blank blocks and page breaks. */
+  lines: LinePosition[];
+}
+
+export interface PageBoundary {
+  page: number;
+  /** Block whose line opens the page; a long block can run on from the page before. */
+  firstBlock: number;
+  /** True when a page break block started the page. */
+  manual: boolean;
+}
+
+/** Page layout computed by the backend, exactly as the PDF export prints it. */
+export interface Pagination {
+  paperSize: PaperSize;
+  linesPerPage: number;
+  pages: PageBoundary[];
+  /** One entry per top-level block. */
+  blocks: BlockLayout[];
+  totalPages: number;
+  totalLines: number;
+}
+
+export interface PageBreakInfo {
+  pos: number; // ProseMirror position where we show a visual break (before node)
+  page: number; // page number *after* the break
+  isManual: boolean; // true if this is a manual page break node
+}
+
+/** Paginates a document without keeping it in the backend mirror. */
+export async function paginateDocument(
+  content: JSONContent,
+  paperSize?: PaperSize
+): Promise<Pagination> {
+  return invoke<Pagination>('paginate_document', {
+    contentJson: JSON.stringify(content),
+    paperSize: paperSize ?? null,
+  });
+}
+
+/**
+ * Positions in `doc` where each page after the first begins. A page that
+ * opens partway through a long block shows its break before that block.
+ */
+export function pageBreaks(doc: ProseMirrorNode, pagination: Pagination): PageBreakInfo[] {
+  const positions: number[] = [];
+  doc.forEach((_node, offset) => {
+    positions.push(offset + 1);
+  });
+
+  return pagination.pages
+    .filter((boundary) => boundary.page > 1 && boundary.firstBlock < positions.length)
+    .map((boundary) => ({
+      pos: positions[boundary.firstBlock],
+      page: boundary.page,
+      isManual: boundary.manual,
+    }));
+}
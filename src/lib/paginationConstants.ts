@@ -1,4 +1,5 @@
-// Pagination constants matching the Rust PDF generator (src-tauri/src/pdf/mod.rs)
+// Page geometry for the on-screen page view, matching the Rust pagination engine
+// (src-tauri/src/pagination/mod.rs), which decides where pages break.
 // Keep these in sync with the Rust constants!
 
 // Page dimensions in points (72 points = 1 inch)
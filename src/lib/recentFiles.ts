@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core';
 import type { DocumentMode, RecentFileEntry } from './types';
 
 const DOCUMENT_MODES: DocumentMode[] = ['screenplay', 'comic', 'freewrite'];
@@ -63,6 +64,21 @@ function writeRecentFiles(entries: RecentFileEntry[]): void {
   }
 
   window.localStorage.setItem(RECENT_FILES_STORAGE_KEY, JSON.stringify(entries));
+  pushToBackend(entries);
+}
+
+/**
+ * Mirrors the list into the backend store that feeds the macOS Dock menu and
+ * the Windows jump list.
+ */
+function pushToBackend(entries: RecentFileEntry[]): void {
+  void invoke('recent_files_set', { entries }).catch((error) => {
+    console.error('Failed to sync recent files:', error);
+  });
+}
+
+export function syncRecentFiles(): void {
+  pushToBackend(readRecentFiles());
 }
 
 export function getRecentFiles(): RecentFileEntry[] {
@@ -105,4 +121,5 @@ export function clearRecentFiles(): void {
   }
 
   window.localStorage.removeItem(RECENT_FILES_STORAGE_KEY);
+  pushToBackend([]);
 }
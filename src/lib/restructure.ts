@@ -0,0 +1,53 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { IndexCard } from './outline';
+import type { ScreenplayDocument } from './types';
+
+export interface Restructured {
+  document: ScreenplayDocument;
+  /** Outline cards in their updated order. */
+  cards: IndexCard[];
+}
+
+async function restructure(
+  command: string,
+  document: ScreenplayDocument,
+  args: Record<string, unknown>
+): Promise<Restructured> {
+  const result = await invoke<{ documentJson: string; cards: IndexCard[] }>(command, {
+    documentId: document.meta.id,
+    documentJson: JSON.stringify(document),
+    ...args,
+  });
+  return {
+    document: JSON.parse(result.documentJson) as ScreenplayDocument,
+    cards: result.cards,
+  };
+}
+
+/**
+ * Moves scenes `firstScene..=lastScene` (zero-based) in front of `targetScene`,
+ * or to the end when `targetScene` is the scene count. Scene numbers, outline
+ * cards, breakdown tags and comments follow the moved text.
+ */
+export async function moveScenes(
+  document: ScreenplayDocument,
+  firstScene: number,
+  lastScene: number,
+  targetScene: number
+): Promise<Restructured> {
+  return restructure('restructure_move_scenes', document, { firstScene, lastScene, targetScene });
+}
+
+/**
+ * Starts a new scene at editor position `position`, splitting the element
+ * there. The new heading repeats the location and time of the scene it was
+ * split from.
+ */
+export async function splitScene(document: ScreenplayDocument, position: number): Promise<Restructured> {
+  return restructure('restructure_split_scene', document, { position });
+}
+
+/** Merges scene `scene` (zero-based) with the scene after it. */
+export async function mergeScenes(document: ScreenplayDocument, scene: number): Promise<Restructured> {
+  return restructure('restructure_merge_scenes', document, { scene });
+}
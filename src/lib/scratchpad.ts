@@ -0,0 +1,33 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export const QUICK_CAPTURE_WINDOW_LABEL = 'capture';
+
+export interface ScratchpadProject {
+  documentId: string;
+  title: string;
+}
+
+/** Tells the backend which document quick captures should be filed under. */
+export async function setScratchpadProject(project: ScratchpadProject | null): Promise<void> {
+  await invoke('scratchpad_set_project', { project });
+}
+
+export async function getScratchpadProject(): Promise<ScratchpadProject> {
+  return invoke<ScratchpadProject>('scratchpad_get_project');
+}
+
+export async function appendToScratchpad(text: string): Promise<ScratchpadProject> {
+  return invoke<ScratchpadProject>('scratchpad_append', { text });
+}
+
+export async function readScratchpad(documentId: string): Promise<string> {
+  return invoke<string>('scratchpad_read', { documentId });
+}
+
+export async function openQuickCapture(): Promise<void> {
+  await invoke('quick_capture_open');
+}
+
+export async function closeQuickCapture(): Promise<void> {
+  await invoke('quick_capture_close');
+}
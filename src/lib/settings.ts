@@ -0,0 +1,99 @@
+import { Channel, invoke } from '@tauri-apps/api/core';
+
+export type ExportFormat = 'pdf' | 'fountain' | 'fdx';
+
+export interface TitlePagePreset {
+  name: string;
+  credit: string | null;
+  author: string | null;
+  contact: string | null;
+  copyright: string | null;
+  wgaRegistration: string | null;
+}
+
+/** App-wide settings, persisted and migrated by the backend in `settings.json`. */
+export interface AppSettings {
+  version: number;
+  /** A theme name, or `system` to follow the OS appearance. */
+  theme: string;
+  autosave: {
+    enabled: boolean;
+    intervalMs: number;
+  };
+  export: {
+    format: ExportFormat;
+    includeTitlePage: boolean;
+  };
+  backupDirectory: string | null;
+  pluginRegistryUrls: string[];
+  /** Shortcut overrides by command id; edit them through `lib/shortcuts`. */
+  shortcuts: Record<string, { accelerator: string | null; global: boolean }>;
+  /** Reusable title page blocks; edit them through `lib/titlePagePresets`. */
+  titlePagePresets: TitlePagePreset[];
+  /** Preset applied to new documents. */
+  defaultTitlePagePreset: string | null;
+  /** Smart quotes, em-dashes and ellipses applied on save and export. */
+  smartTypography: boolean;
+}
+
+type DeepPartial<T> = {
+  [K in keyof T]?: T[K] extends unknown[]
+    ? T[K] | null
+    : T[K] extends object
+      ? DeepPartial<T[K]> | null
+      : T[K] | null;
+};
+
+export type SettingsPatch = DeepPartial<Omit<AppSettings, 'version'>>;
+
+const LEGACY_THEME_STORAGE_KEY = 'grainery-theme';
+const LEGACY_AUTO_SAVE_STORAGE_KEY = 'grainery-autosave-preferences';
+
+export async function getSettings(): Promise<AppSettings> {
+  return invoke<AppSettings>('settings_get');
+}
+
+/**
+ * Merges `patch` into the settings (`null` resets a field). Every window is
+ * notified through `settings-changed` and `subscribeSettings`.
+ */
+export async function updateSettings(patch: SettingsPatch): Promise<AppSettings> {
+  return invoke<AppSettings>('settings_set', { patch });
+}
+
+export async function subscribeSettings(onChange: (settings: AppSettings) => void): Promise<() => void> {
+  const channel = new Channel<AppSettings>();
+  channel.onmessage = onChange;
+  const id = await invoke<number>('settings_subscribe', { onChange: channel });
+  return () => {
+    void invoke('settings_unsubscribe', { id });
+  };
+}
+
+/**
+ * Returns the settings, first importing the preferences this webview kept
+ * in localStorage before settings moved to the backend. The backend ignores
+ * the import once settings have been saved.
+ */
+export async function loadSettings(): Promise<AppSettings> {
+  const legacy: Record<string, unknown> = {};
+  const theme = localStorage.getItem(LEGACY_THEME_STORAGE_KEY);
+  if (theme) {
+    legacy.theme = theme;
+  }
+  try {
+    const autoSave = localStorage.getItem(LEGACY_AUTO_SAVE_STORAGE_KEY);
+    if (autoSave) {
+      legacy.autoSavePreferences = JSON.parse(autoSave);
+    }
+  } catch {
+    // Unreadable legacy values fall back to the defaults.
+  }
+
+  try {
+    return await invoke<AppSettings>('settings_import_legacy', { legacy });
+  } catch (error) {
+    console.error('Failed to import legacy settings:', error);
+    return getSettings();
+  }
+}
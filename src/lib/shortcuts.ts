@@ -0,0 +1,49 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ShortcutBinding {
+  /** Menu item id, or `plugin:command:<plugin>:<command>`. */
+  command: string;
+  accelerator: string | null;
+  defaultAccelerator: string | null;
+  global: boolean;
+  overridden: boolean;
+}
+
+export interface ShortcutConflict {
+  accelerator: string;
+  commands: string[];
+  /**
+   * `binding` when two commands share it, `os` when the OS refused the global
+   * shortcut (usually another application holds it), `invalid` when it doesn't parse.
+   */
+  kind: 'binding' | 'os' | 'invalid';
+}
+
+export interface ShortcutOverview {
+  bindings: ShortcutBinding[];
+  conflicts: ShortcutConflict[];
+}
+
+/** Re-applies the saved bindings to the menu and the OS, returning the result. */
+export async function applyShortcuts(): Promise<ShortcutOverview> {
+  return invoke<ShortcutOverview>('shortcuts_apply');
+}
+
+/**
+ * Binds a command; `accelerator: null` unbinds it. Rejects accelerators that
+ * are already used by another command.
+ */
+export async function setShortcut(
+  command: string,
+  accelerator: string | null,
+  global = false
+): Promise<ShortcutOverview> {
+  return invoke<ShortcutOverview>('shortcuts_set', {
+    command,
+    binding: { accelerator, global },
+  });
+}
+
+export async function resetShortcut(command: string): Promise<ShortcutOverview> {
+  return invoke<ShortcutOverview>('shortcuts_reset', { command });
+}
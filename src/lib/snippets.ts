@@ -0,0 +1,68 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface Snippet {
+  id: string;
+  trigger: string;
+  /** May use {CHARACTER}, {SCENE}, {TITLE}, {DATE}, {TIME} and {CURSOR}. */
+  expansion: string;
+  description: string | null;
+  set: string;
+}
+
+export interface SnippetInput {
+  /** Omit to create a snippet. */
+  id?: string;
+  trigger: string;
+  expansion: string;
+  description?: string | null;
+  set?: string | null;
+}
+
+export interface ExpansionContext {
+  character?: string | null;
+  scene?: string | null;
+  title?: string | null;
+}
+
+export interface SnippetExpansion {
+  text: string;
+  /** Cursor position in characters from the start of `text`, or null for the end. */
+  cursor: number | null;
+}
+
+export interface SnippetImportReport {
+  set: string;
+  added: string[];
+  replaced: string[];
+  skipped: string[];
+}
+
+export async function listSnippets(): Promise<Snippet[]> {
+  return invoke<Snippet[]>('snippets_list');
+}
+
+export async function saveSnippet(snippet: SnippetInput): Promise<Snippet> {
+  return invoke<Snippet>('snippets_save', { snippet });
+}
+
+export async function deleteSnippet(id: string): Promise<void> {
+  await invoke('snippets_delete', { id });
+}
+
+/** Resolves to null when `trigger` is not a snippet. */
+export async function expandSnippet(
+  trigger: string,
+  context: ExpansionContext
+): Promise<SnippetExpansion | null> {
+  return invoke<SnippetExpansion | null>('snippets_expand', { trigger, context });
+}
+
+/** Writes one snippet set to a file. Resolves to the number of snippets exported. */
+export async function exportSnippetSet(outputPath: string, set?: string): Promise<number> {
+  return invoke<number>('snippets_export', { outputPath, set: set ?? null });
+}
+
+/** Imports a snippet set file, optionally into a differently named set. */
+export async function importSnippetSet(inputPath: string, set?: string): Promise<SnippetImportReport> {
+  return invoke<SnippetImportReport>('snippets_import', { inputPath, set: set ?? null });
+}
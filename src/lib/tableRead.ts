@@ -0,0 +1,43 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ScreenplayDocument, TableReadVoices } from './types';
+
+export interface Voice {
+  name: string;
+  /** Lowercase language tag, e.g. `en-us`. */
+  language: string;
+}
+
+export interface TableReadResult {
+  path: string;
+  lines: number;
+  durationSeconds: number;
+  /** The voices used, including ones handed out to unmapped characters. */
+  voices: TableReadVoices;
+}
+
+export async function listVoices(): Promise<Voice[]> {
+  return invoke<Voice[]>('table_read_voices');
+}
+
+/** Stores the voice mapping in the document's metadata. */
+export async function setTableReadVoices(
+  document: ScreenplayDocument,
+  voices: TableReadVoices
+): Promise<ScreenplayDocument> {
+  const documentJson = await invoke<string>('table_read_set_voices', {
+    documentJson: JSON.stringify(document),
+    voices,
+  });
+  return JSON.parse(documentJson) as ScreenplayDocument;
+}
+
+/** Reads the script aloud with the system's voices into a WAV file. */
+export async function exportTableRead(
+  document: ScreenplayDocument,
+  outputPath: string
+): Promise<TableReadResult> {
+  return invoke<TableReadResult>('export_table_read', {
+    documentJson: JSON.stringify(document),
+    outputPath,
+  });
+}
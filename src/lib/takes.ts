@@ -0,0 +1,73 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { JSONContent } from '@tiptap/react';
+import type { DialogueTake, ScreenplayDocument } from './types';
+
+export interface TakesUpdate {
+  document: ScreenplayDocument;
+  nodeId: string;
+  takes: DialogueTake[];
+}
+
+interface RawTakesUpdate {
+  documentJson: string;
+  nodeId: string;
+  takes: DialogueTake[];
+}
+
+function toUpdate(result: RawTakesUpdate): TakesUpdate {
+  return {
+    document: JSON.parse(result.documentJson) as ScreenplayDocument,
+    nodeId: result.nodeId,
+    takes: result.takes,
+  };
+}
+
+export async function listTakes(document: ScreenplayDocument, nodeId: string): Promise<DialogueTake[]> {
+  return invoke<DialogueTake[]>('takes_list', { documentJson: JSON.stringify(document), nodeId });
+}
+
+/**
+ * Saves a take for the dialogue block at `blockIndex`. Without `content`, the
+ * block's current text is kept as the take. Assigns the block a `nodeId` if needed.
+ */
+export async function addTake(
+  document: ScreenplayDocument,
+  blockIndex: number,
+  content?: JSONContent[],
+  label?: string
+): Promise<TakesUpdate> {
+  const result = await invoke<RawTakesUpdate>('takes_add', {
+    documentJson: JSON.stringify(document),
+    blockIndex,
+    content: content ?? null,
+    label: label ?? null,
+  });
+  return toUpdate(result);
+}
+
+/** Swaps a take into the block; the replaced text is kept as a take. */
+export async function promoteTake(
+  document: ScreenplayDocument,
+  nodeId: string,
+  takeId: string
+): Promise<TakesUpdate> {
+  const result = await invoke<RawTakesUpdate>('takes_promote', {
+    documentJson: JSON.stringify(document),
+    nodeId,
+    takeId,
+  });
+  return toUpdate(result);
+}
+
+export async function deleteTake(
+  document: ScreenplayDocument,
+  nodeId: string,
+  takeId: string
+): Promise<TakesUpdate> {
+  const result = await invoke<RawTakesUpdate>('takes_delete', {
+    documentJson: JSON.stringify(document),
+    nodeId,
+    takeId,
+  });
+  return toUpdate(result);
+}
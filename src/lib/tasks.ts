@@ -0,0 +1,48 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ScreenplayDocument } from './types';
+
+/** A `TODO:` item from a `[[note]]`, a note block or a boneyard span. */
+export interface ScriptTask {
+  id: string;
+  text: string;
+  blockIndex: number;
+  /** Character offset of the `TODO:` marker in the block's text. */
+  offset: number;
+  /** 1-based scene position, or null before the first scene. */
+  scene: number | null;
+  sceneHeading: string | null;
+  source: 'note' | 'boneyard';
+  done: boolean;
+}
+
+export interface TasksUpdate {
+  document: ScreenplayDocument;
+  tasks: ScriptTask[];
+}
+
+/** Open tasks in document order; pass `includeDone` for the full checklist. */
+export async function getTasks(
+  document: ScreenplayDocument,
+  includeDone = false
+): Promise<ScriptTask[]> {
+  return invoke<ScriptTask[]>('get_tasks', {
+    documentJson: JSON.stringify(document),
+    includeDone,
+  });
+}
+
+export async function setTaskDone(
+  document: ScreenplayDocument,
+  taskId: string,
+  done: boolean
+): Promise<TasksUpdate> {
+  const result = await invoke<{ documentJson: string; tasks: ScriptTask[] }>('tasks_set_done', {
+    documentJson: JSON.stringify(document),
+    taskId,
+    done,
+  });
+  return {
+    document: JSON.parse(result.documentJson) as ScreenplayDocument,
+    tasks: result.tasks,
+  };
+}
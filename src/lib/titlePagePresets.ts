@@ -0,0 +1,37 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TitlePagePreset } from './settings';
+import type { TitlePageData } from './types';
+
+export type { TitlePagePreset };
+
+export async function listTitlePagePresets(): Promise<TitlePagePreset[]> {
+  return invoke<TitlePagePreset[]>('title_page_presets_list');
+}
+
+/** Adds a preset or replaces the one with the same name. */
+export async function saveTitlePagePreset(preset: TitlePagePreset): Promise<TitlePagePreset[]> {
+  return invoke<TitlePagePreset[]>('title_page_presets_save', { preset });
+}
+
+export async function deleteTitlePagePreset(name: string): Promise<TitlePagePreset[]> {
+  return invoke<TitlePagePreset[]>('title_page_presets_delete', { name });
+}
+
+/** Chooses the preset applied to new documents; `null` turns it off. */
+export async function setDefaultTitlePagePreset(name: string | null): Promise<string | null> {
+  return invoke<string | null>('title_page_presets_set_default', { name });
+}
+
+/**
+ * Builds a title page from the named preset, or from the default preset when
+ * `name` is omitted. Resolves to null when there is no default.
+ */
+export async function applyTitlePagePreset(
+  name?: string,
+  title?: string
+): Promise<TitlePageData | null> {
+  return invoke<TitlePageData | null>('title_page_presets_apply', {
+    name: name ?? null,
+    title: title ?? null,
+  });
+}
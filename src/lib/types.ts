@@ -39,16 +39,67 @@ export interface DocumentMeta {
   createdAt: string;
   modifiedAt: string;
   version: string;
+  tableReadVoices?: TableReadVoices;
+}
+
+/** System voice names for table reads. Unset voices use the default. */
+export interface TableReadVoices {
+  narrator?: string | null;
+  /** Voices by character cue name. */
+  characters: Record<string, string>;
 }
 
 export interface DocumentSettings {
   pageNumberStart: number;
   showSceneNumbers: boolean;
+  /** Scene numbers are frozen; new scenes get A/B numbers instead. */
+  sceneNumbersLocked?: boolean;
   revision: string | null;
 }
 
+export interface DialogueTake {
+  id: string;
+  label: string | null;
+  /** The block's inline content, marks included. */
+  content: JSONContent[];
+  createdAt: string;
+}
+
+export interface CommentAnchor {
+  from: number;
+  to: number;
+  /** The text the range covered when the comment was placed. */
+  quote: string;
+}
+
+export interface CommentReply {
+  id: string;
+  author: string;
+  body: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface ScriptComment {
+  id: string;
+  anchor: CommentAnchor;
+  author: string;
+  body: string;
+  resolved: boolean;
+  replies: CommentReply[];
+  createdAt: string;
+  updatedAt: string;
+}
+
+/** A `TODO:` item checked off in the task list. */
+export interface CompletedTask {
+  id: string;
+  completedAt: string;
+}
+
 export interface ScreenplayDocument {
-  formatVersion: '1.0';
+  /** `.gwx` layout version; newer builds may write a higher one. */
+  formatVersion: string;
   documentMode: DocumentMode;
   application: {
     name: string;
@@ -58,6 +109,10 @@ export interface ScreenplayDocument {
   titlePage: TitlePageData | null;
   document: JSONContent;
   settings: DocumentSettings;
+  /** Alternate dialogue takes, keyed by the dialogue block's `nodeId`. */
+  takes?: Record<string, DialogueTake[]>;
+  comments?: ScriptComment[];
+  tasks?: CompletedTask[];
   pluginData?: Record<string, unknown>;
 }
 
@@ -0,0 +1,13 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { JSONContent } from '@tiptap/react';
+
+/**
+ * Converts straight quotes, `--` and `...` in the editor content. Fountain
+ * notes and boneyard are left alone.
+ */
+export async function applySmartTypography(content: JSONContent): Promise<JSONContent> {
+  const contentJson = await invoke<string>('apply_smart_typography', {
+    contentJson: JSON.stringify(content),
+  });
+  return JSON.parse(contentJson) as JSONContent;
+}
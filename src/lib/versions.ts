@@ -0,0 +1,71 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ScreenplayDocument } from './types';
+
+/** One saved version of a document in the backend version store. */
+export interface VersionSummary {
+  hash: string;
+  parent: string | null;
+  /** Second parent when the version merged copies from two devices. */
+  merged: string | null;
+  savedAt: string;
+  title: string | null;
+}
+
+/** A recorded save, and the merged document when a sync moved on meanwhile. */
+export interface RecordedVersion {
+  version: VersionSummary;
+  mergedDocumentJson?: string;
+}
+
+// Version each open document was loaded from, so saves merge with synced ones
+const bases = new Map<string, string>();
+
+export function setVersionBase(documentId: string, hash: string): void {
+  bases.set(documentId, hash);
+}
+
+/** Bases the next save of `documentId` on its newest stored version. */
+export async function loadVersionBase(documentId: string): Promise<void> {
+  const head = await invoke<string | null>('versions_head', { documentId }).catch((error) => {
+    console.error('[Versions] Failed to read the newest version', error);
+    return null;
+  });
+  if (head) {
+    bases.set(documentId, head);
+  } else {
+    bases.delete(documentId);
+  }
+}
+
+/**
+ * Adds `saved`, as written to `path`, to its document's version history.
+ * Returns null when it couldn't be recorded.
+ */
+export async function recordVersion(
+  saved: ScreenplayDocument,
+  path: string | null
+): Promise<RecordedVersion | null> {
+  const documentId = saved.meta.id;
+  try {
+    const recorded = await invoke<RecordedVersion>('versions_record', {
+      documentJson: JSON.stringify(saved),
+      path,
+      base: bases.get(documentId) ?? null,
+    });
+    bases.set(documentId, recorded.version.hash);
+    return recorded;
+  } catch (error) {
+    console.error('[Versions] Failed to record version', error);
+    return null;
+  }
+}
+
+/** Saved versions of a document, newest first. */
+export async function listVersions(documentId: string): Promise<VersionSummary[]> {
+  return invoke<VersionSummary[]>('versions_list', { documentId });
+}
+
+export async function loadVersion(documentId: string, hash: string): Promise<ScreenplayDocument> {
+  const documentJson = await invoke<string>('versions_load', { documentId, hash });
+  return JSON.parse(documentJson) as ScreenplayDocument;
+}
@@ -0,0 +1,30 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** View menu state, persisted by the backend in `view-settings.json`. */
+export interface ViewSettings {
+  zoom: number;
+  focusMode: boolean;
+  typewriterScrolling: boolean;
+  sidebarVisible: boolean;
+}
+
+export const DEFAULT_VIEW_SETTINGS: ViewSettings = {
+  zoom: 1,
+  focusMode: false,
+  typewriterScrolling: false,
+  sidebarVisible: false,
+};
+
+export async function getViewSettings(): Promise<ViewSettings> {
+  return invoke<ViewSettings>('view_settings_get');
+}
+
+/**
+ * Applies a partial change. The backend re-emits the full settings as
+ * `view-settings-changed` and keeps the View menu check marks in sync.
+ */
+export function updateViewSettings(patch: Partial<ViewSettings>): void {
+  void invoke('view_settings_update', { patch }).catch((error) => {
+    console.error('Failed to update view settings:', error);
+  });
+}
@@ -1,12 +1,23 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import App from "./App";
+import { QuickCapture } from "./components/QuickCapture";
+import { QUICK_CAPTURE_WINDOW_LABEL } from "./lib/scratchpad";
+import { PLUGIN_PROCESS_WINDOW_LABEL } from "./plugins/process";
+import { runPluginProcess } from "./plugins/process-host";
 import "./styles/tailwind.css";
 import "./styles/global.css";
 import "./styles/plugin-ui.css";
 
-ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <React.StrictMode>
-    <App />
-  </React.StrictMode>,
-);
+const windowLabel = "__TAURI_INTERNALS__" in window ? getCurrentWindow().label : null;
+
+if (windowLabel === PLUGIN_PROCESS_WINDOW_LABEL) {
+  void runPluginProcess();
+} else {
+  ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+    <React.StrictMode>
+      {windowLabel === QUICK_CAPTURE_WINDOW_LABEL ? <QuickCapture /> : <App />}
+    </React.StrictMode>,
+  );
+}
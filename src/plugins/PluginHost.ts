@@ -5,28 +5,37 @@ import type {
   HostOperation,
   InstalledPlugin,
   OptionalPermission,
+  PluginDocumentSelection,
   PluginPermissionGrant,
+  PluginSelectionRange,
 } from './types';
 
 interface PluginHostOptions {
   getDocument: () => JSONContent;
   replaceDocument: (next: JSONContent) => void | Promise<void>;
+  getSelection: () => PluginDocumentSelection | null;
+  replaceSelection: (range: PluginSelectionRange, text: string) => void | Promise<void>;
   getPluginData: (pluginId: string) => unknown | null;
   setPluginData: (pluginId: string, value: unknown) => void | Promise<void>;
 }
 
 const MAX_PLUGIN_DATA_BYTES = 256 * 1024;
+const MAX_SELECTION_TEXT_BYTES = 256 * 1024;
 const GLOBAL_PLUGIN_DATA_PREFIX = 'grainery:plugin-global-data:';
 
 export class PluginHost {
   private getDocument: () => JSONContent;
   private replaceDocument: (next: JSONContent) => void | Promise<void>;
+  private getSelection: () => PluginDocumentSelection | null;
+  private replaceSelection: (range: PluginSelectionRange, text: string) => void | Promise<void>;
   private getPluginData: (pluginId: string) => unknown | null;
   private setPluginData: (pluginId: string, value: unknown) => void | Promise<void>;
 
   constructor(options: PluginHostOptions) {
     this.getDocument = options.getDocument;
     this.replaceDocument = options.replaceDocument;
+    this.getSelection = options.getSelection;
+    this.replaceSelection = options.replaceSelection;
     this.getPluginData = options.getPluginData;
     this.setPluginData = options.setPluginData;
   }
@@ -34,6 +43,8 @@ export class PluginHost {
   updateDocumentAccess(options: PluginHostOptions): void {
     this.getDocument = options.getDocument;
     this.replaceDocument = options.replaceDocument;
+    this.getSelection = options.getSelection;
+    this.replaceSelection = options.replaceSelection;
     this.getPluginData = options.getPluginData;
     this.setPluginData = options.setPluginData;
   }
@@ -64,6 +75,38 @@ export class PluginHost {
         return true;
       }
 
+      // `document:selection` scopes access to the current selection, so a
+      // plugin holding only that permission never sees the rest of the
+      // script. Whole-document permissions imply selection access.
+      case 'document:get-selection': {
+        if (
+          !hasPluginPermission(plugin, 'document:selection') &&
+          !hasPluginPermission(plugin, 'document:read')
+        ) {
+          throw new Error('Permission denied: document:selection');
+        }
+
+        return this.getSelection() ?? { from: 0, to: 0, text: '' };
+      }
+
+      case 'document:replace-selection': {
+        if (
+          !hasPluginPermission(plugin, 'document:selection') &&
+          !hasPluginPermission(plugin, 'document:write')
+        ) {
+          throw new Error('Permission denied: document:selection');
+        }
+
+        const { range, text } = extractSelectionReplacement(payload);
+        const current = this.getSelection();
+        if (!current || current.from !== range.from || current.to !== range.to) {
+          throw new Error('Selection changed since it was read.');
+        }
+
+        await this.replaceSelection(range, text);
+        return true;
+      }
+
       case 'document:get-plugin-data': {
         if (!hasPluginPermission(plugin, 'document:read')) {
           throw new Error('Permission denied: document:read');
@@ -124,6 +167,34 @@ export class PluginHost {
       return true;
     }
 
+    const allowed = this.confirmPermission(plugin, permission);
+
+    const nextGrants = this.withUpdatedGrant(plugin.grantedPermissions, permission, allowed);
+
+    await invoke('plugin_update_permissions', {
+      pluginId: plugin.id,
+      permissions: nextGrants,
+    });
+
+    this.applyGrant(plugin, permission, allowed);
+    return allowed;
+  }
+
+  /**
+   * Answers a consent prompt brokered by the backend, which persists the
+   * decision itself; only the in-memory grant is updated here.
+   */
+  answerBackendPermissionRequest(
+    plugin: InstalledPlugin,
+    permission: OptionalPermission
+  ): boolean {
+    const allowed = this.confirmPermission(plugin, permission);
+    this.applyGrant(plugin, permission, allowed);
+    return allowed;
+  }
+
+  private confirmPermission(plugin: InstalledPlugin, permission: OptionalPermission): boolean {
+    const existing = plugin.grantedPermissions.find((item) => item.permission === permission);
     const rationale = plugin.manifest.permissionRationales?.[permission];
     const message = [
       `${plugin.name} ${plugin.version}`,
@@ -142,15 +213,14 @@ export class PluginHost {
       'Allow this permission?',
     ].join('\n');
 
-    const allowed = window.confirm(message);
-
-    const nextGrants = this.withUpdatedGrant(plugin.grantedPermissions, permission, allowed);
-
-    await invoke('plugin_update_permissions', {
-      pluginId: plugin.id,
-      permissions: nextGrants,
-    });
+    return window.confirm(message);
+  }
 
+  private applyGrant(
+    plugin: InstalledPlugin,
+    permission: OptionalPermission,
+    allowed: boolean
+  ): void {
     const applied = plugin.grantedPermissions.find((item) => item.permission === permission);
     if (applied) {
       applied.granted = allowed;
@@ -162,8 +232,6 @@ export class PluginHost {
         grantedAt: allowed ? new Date().toISOString() : null,
       });
     }
-
-    return allowed;
   }
 
   private withUpdatedGrant(
@@ -219,6 +287,33 @@ export class PluginHost {
   }
 }
 
+function extractSelectionReplacement(payload: unknown): {
+  range: PluginSelectionRange;
+  text: string;
+} {
+  const value = (payload ?? {}) as Record<string, unknown>;
+  const { from, to, text } = value;
+  if (
+    typeof from !== 'number' ||
+    typeof to !== 'number' ||
+    !Number.isInteger(from) ||
+    !Number.isInteger(to) ||
+    from > to
+  ) {
+    throw new Error('Selection replacement requires integer from/to positions.');
+  }
+
+  if (typeof text !== 'string') {
+    throw new Error('Selection replacement requires string text.');
+  }
+
+  if (new TextEncoder().encode(text).length > MAX_SELECTION_TEXT_BYTES) {
+    throw new Error(`Selection text exceeds ${MAX_SELECTION_TEXT_BYTES} byte limit.`);
+  }
+
+  return { range: { from, to }, text };
+}
+
 function extractPluginDataPayload(payload: unknown): unknown {
   if (typeof payload !== 'object' || payload === null) {
     return payload;
@@ -1,9 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type { JSONContent } from '@tiptap/react';
 import type { ScreenplayElementType } from '../lib/types';
 import { hasPluginPermission } from './permissions';
 import { nextRequestId, parseWorkerMessage } from './rpc';
 import { PluginHost } from './PluginHost';
+import { PluginProcess, type PluginWorker } from './process';
 import { evaluateWhenClause } from './when';
 import {
   assertContributedId,
@@ -16,6 +18,8 @@ import {
 } from './validation';
 import type {
   ContributedTransform,
+  ContributedDictionary,
+  ContributedTheme,
   DocumentTransformContext,
   DocumentTransformHook,
   ElementLoopContext,
@@ -25,11 +29,20 @@ import type {
   InlineAnnotationContext,
   InstalledPlugin,
   OptionalPermission,
+  PluginChannel,
   PluginDiagnosticKind,
+  PluginDictionary,
+  PluginDictionaryWords,
+  PluginDiskUsage,
   PluginContributions,
+  PluginDocumentSelection,
   PluginLockRecord,
+  PluginPerformance,
+  PluginTheme,
+  PluginThemeTokens,
   PluginPermissionGrant,
   PluginRegistryEntry,
+  PluginSelectionRange,
   PluginStateSnapshot,
   RegisteredCommandMenu,
   RegisteredExporter,
@@ -40,6 +53,7 @@ import type {
   RegisteredStatusBadge,
   RegisteredUIControl,
   RegisteredUIPanel,
+  ScheduledTask,
   RegisteredInlineAnnotationProvider,
   RenderedInlineAnnotation,
   RenderedStatusBadge,
@@ -60,7 +74,7 @@ interface PendingInvoke {
 }
 
 interface WorkerSession {
-  worker: Worker;
+  worker: PluginWorker;
   pluginId: string;
   ready: boolean;
   shuttingDown: boolean;
@@ -69,6 +83,10 @@ interface WorkerSession {
     resolve: () => void;
     timeoutId: ReturnType<typeof setTimeout>;
   };
+  liveness?: {
+    requestId: string;
+    timeoutId: ReturnType<typeof setTimeout>;
+  };
 }
 
 interface RegisteredLoopProvider {
@@ -85,6 +103,35 @@ interface RegisteredTransform {
 
 type ActivationState = 'inactive' | 'activating' | 'active' | 'failed';
 
+interface CrashOutcome {
+  pluginId: string;
+  pluginName: string;
+  crashCount: number;
+  crashesInWindow: number;
+  disabled: boolean;
+  reenableAt?: string;
+}
+
+interface BackendPermissionRequest {
+  requestId: string;
+  pluginId: string;
+  permission: string;
+  operation: string;
+}
+
+interface ScheduledTaskEvent {
+  pluginId: string;
+  taskId: string;
+  scheduledAt: string;
+}
+
+interface BusEventDelivery {
+  pluginId: string;
+  source: string;
+  topic: string;
+  data: unknown;
+}
+
 interface ManifestContributionIndex {
   commands: Set<string>;
   exporters: Set<string>;
@@ -101,16 +148,19 @@ interface ManifestContributionIndex {
 interface PluginManagerOptions {
   getDocument: () => JSONContent;
   replaceDocument: (next: JSONContent) => void | Promise<void>;
+  getSelection: () => PluginDocumentSelection | null;
+  replaceSelection: (range: PluginSelectionRange, text: string) => void | Promise<void>;
   getPluginData: (pluginId: string) => unknown | null;
   setPluginData: (pluginId: string, value: unknown) => void | Promise<void>;
 }
 
-const MAX_CRASH_COUNT = 3;
 const INVOKE_TIMEOUT_MS = 8_000;
 const SHUTDOWN_TIMEOUT_MS = 2_000;
+const LIVENESS_TIMEOUT_MS = 1_000;
 
 export class PluginManager {
   private readonly pluginHost: PluginHost;
+  private readonly pluginProcess = new PluginProcess();
   private readonly sessions = new Map<string, WorkerSession>();
   private readonly listeners = new Set<() => void>();
   private readonly crashCounts = new Map<string, number>();
@@ -131,11 +181,17 @@ export class PluginManager {
   private activationStates = new Map<string, ActivationState>();
   private activationPromises = new Map<string, Promise<void>>();
   private contributionsByPlugin = new Map<string, ManifestContributionIndex>();
+  private permissionRequestUnlisten: UnlistenFn | null = null;
+  private autoDisableUnlisten: UnlistenFn | null = null;
+  private scheduledTaskUnlisten: UnlistenFn | null = null;
+  private busEventUnlisten: UnlistenFn | null = null;
 
   constructor(options: PluginManagerOptions) {
     this.pluginHost = new PluginHost({
       getDocument: options.getDocument,
       replaceDocument: options.replaceDocument,
+      getSelection: options.getSelection,
+      replaceSelection: options.replaceSelection,
       getPluginData: options.getPluginData,
       setPluginData: options.setPluginData,
     });
@@ -253,11 +309,85 @@ export class PluginManager {
   }
 
   async initialize(): Promise<void> {
+    if (!this.permissionRequestUnlisten) {
+      this.permissionRequestUnlisten = await listen<BackendPermissionRequest>(
+        'plugin-permission-request',
+        (event) => {
+          void this.handleBackendPermissionRequest(event.payload);
+        }
+      );
+    }
+    if (!this.autoDisableUnlisten) {
+      this.autoDisableUnlisten = await listen<CrashOutcome>('plugin-auto-disabled', (event) => {
+        const { pluginName, crashesInWindow, reenableAt } = event.payload;
+        const retry = reenableAt
+          ? ` It will be re-enabled after ${new Date(reenableAt).toLocaleTimeString()}.`
+          : '';
+        window.alert(
+          `${pluginName} was disabled after ${crashesInWindow} crashes in a short time.${retry}`
+        );
+      });
+    }
+    if (!this.scheduledTaskUnlisten) {
+      this.scheduledTaskUnlisten = await listen<ScheduledTaskEvent>(
+        'plugin-scheduled-task',
+        (event) => {
+          void this.runScheduledTask(event.payload).catch((error) => {
+            console.error('[PluginManager] Scheduled task failed', error);
+          });
+        }
+      );
+    }
+    if (!this.busEventUnlisten) {
+      this.busEventUnlisten = await listen<BusEventDelivery>('plugin-bus-event', (event) => {
+        const { pluginId, source, topic, data } = event.payload;
+        // Subscriptions belong to running workers; drop events for stopped ones.
+        if (!this.sessions.get(pluginId)?.ready) {
+          return;
+        }
+        void this.invokeWorker(pluginId, 'bus-event', topic, { topic, source, data }).catch(
+          (error) => {
+            console.error(`[PluginManager] Event delivery to ${pluginId} failed`, error);
+          }
+        );
+      });
+    }
+    try {
+      await invoke('plugin_check_revocations');
+    } catch (error) {
+      console.error('[PluginManager] Failed to check plugin revocations', error);
+    }
     await this.reloadInstalledPlugins();
   }
 
+  private async handleBackendPermissionRequest(request: BackendPermissionRequest): Promise<void> {
+    const plugin = this.getPluginById(request.pluginId);
+    const granted = plugin
+      ? this.pluginHost.answerBackendPermissionRequest(
+          plugin,
+          request.permission as OptionalPermission
+        )
+      : false;
+
+    await invoke('plugin_respond_permission_request', {
+      requestId: request.requestId,
+      granted,
+    });
+    if (plugin && !granted) {
+      await this.recordDiagnostic(
+        plugin.id,
+        'permission-denial',
+        `Permission denied: ${request.permission}`,
+        request.operation
+      );
+    }
+    this.notifyListeners();
+  }
+
   async reloadInstalledPlugins(): Promise<void> {
-    const installed = await invoke<InstalledPlugin[]>('plugin_list_installed');
+    const installed = await invoke<InstalledPlugin[]>('plugin_list_installed', {
+      locale: navigator.language,
+    });
 
     await this.disposeAllWorkers();
 
@@ -297,11 +427,19 @@ export class PluginManager {
       }
     }
 
+    // Plugin keybindings feed the OS-level shortcut registry.
+    void invoke('shortcuts_apply').catch((error) => {
+      console.error('[PluginManager] Failed to apply global shortcuts', error);
+    });
+
     this.notifyListeners();
   }
 
-  async installFromFile(path: string): Promise<InstalledPlugin> {
-    const plugin = await invoke<InstalledPlugin>('plugin_install_from_file', { path });
+  async installFromFile(path: string, allowUnsigned = false): Promise<InstalledPlugin> {
+    const plugin = await invoke<InstalledPlugin>('plugin_install_from_file', {
+      path,
+      allowUnsigned,
+    });
     await this.reloadInstalledPlugins();
     return plugin;
   }
@@ -335,6 +473,11 @@ export class PluginManager {
     await this.reloadInstalledPlugins();
   }
 
+  async setUpdateChannel(pluginId: string, channel: PluginChannel): Promise<void> {
+    await invoke<InstalledPlugin>('plugin_set_update_channel', { pluginId, channel });
+    await this.reloadInstalledPlugins();
+  }
+
   async updatePermissions(
     pluginId: string,
     permissions: PluginPermissionGrant[]
@@ -352,6 +495,37 @@ export class PluginManager {
     return invoke<PluginLockRecord[]>('plugin_get_lock_records');
   }
 
+  async getDiskUsage(): Promise<PluginDiskUsage[]> {
+    return invoke<PluginDiskUsage[]>('plugin_disk_usage');
+  }
+
+  async getPerformanceReport(): Promise<PluginPerformance[]> {
+    return invoke<PluginPerformance[]>('plugin_performance_report');
+  }
+
+  async listThemes(): Promise<PluginTheme[]> {
+    return invoke<PluginTheme[]>('plugin_list_themes', { locale: navigator.language });
+  }
+
+  async readTheme(pluginId: string, themeId: string): Promise<PluginThemeTokens> {
+    return invoke<PluginThemeTokens>('plugin_read_theme', { pluginId, themeId });
+  }
+
+  async listDictionaries(language?: string): Promise<PluginDictionary[]> {
+    return invoke<PluginDictionary[]>('plugin_list_dictionaries', {
+      language: language ?? null,
+      locale: navigator.language,
+    });
+  }
+
+  async readDictionary(pluginId: string, dictionaryId: string): Promise<PluginDictionaryWords> {
+    return invoke<PluginDictionaryWords>('plugin_read_dictionary', { pluginId, dictionaryId });
+  }
+
+  async clearPluginData(pluginId: string): Promise<PluginDiskUsage> {
+    return invoke<PluginDiskUsage>('plugin_clear_data', { pluginId });
+  }
+
   async clearDiagnostics(pluginId: string): Promise<void> {
     const updated = await invoke<InstalledPlugin>('plugin_clear_diagnostics', { pluginId });
     this.replaceInstalledPlugin(updated);
@@ -423,6 +597,24 @@ export class PluginManager {
     });
   }
 
+  async listScheduledTasks(pluginId?: string): Promise<ScheduledTask[]> {
+    return invoke<ScheduledTask[]>('plugin_list_scheduled_tasks', { pluginId: pluginId ?? null });
+  }
+
+  async cancelScheduledTask(pluginId: string, taskId: string): Promise<void> {
+    await invoke('plugin_cancel_scheduled_task', { pluginId, taskId });
+  }
+
+  private async runScheduledTask({ pluginId, taskId, scheduledAt }: ScheduledTaskEvent): Promise<void> {
+    const plugin = this.getPluginById(pluginId);
+    if (!plugin?.enabled) {
+      return;
+    }
+
+    await this.ensureActivated(pluginId, `onSchedule:${taskId}`);
+    await this.invokeWorker(pluginId, 'scheduled-task', taskId, { taskId, scheduledAt });
+  }
+
   async maybeExecuteShortcut(event: KeyboardEvent): Promise<boolean> {
     const shortcut = normalizeKeyboardShortcut(event);
     if (!shortcut) {
@@ -1017,12 +1209,24 @@ export class PluginManager {
 
     const promise = (async () => {
       this.activationStates.set(pluginId, 'activating');
-      if (!this.sessions.has(pluginId)) {
+      const startedAt = performance.now();
+      const startingWorker = !this.sessions.has(pluginId);
+      if (startingWorker) {
         this.startWorker(plugin);
       }
 
       await this.waitForWorkerReady(pluginId);
       this.activationStates.set(pluginId, 'active');
+
+      if (startingWorker) {
+        void invoke('plugin_record_activation', {
+          pluginId,
+          activationEvent,
+          durationMs: Math.round(performance.now() - startedAt),
+        }).catch((error) => {
+          console.error(`[PluginManager] Failed to record activation for ${pluginId}`, error);
+        });
+      }
     })();
 
     this.activationPromises.set(pluginId, promise);
@@ -1063,8 +1267,13 @@ export class PluginManager {
   }
 
   private startWorker(plugin: InstalledPlugin): void {
-    const worker = new Worker(new URL('./worker-runtime.ts', import.meta.url), {
-      type: 'module',
+    const worker = this.pluginProcess.spawn({
+      onMessage: (data) => {
+        void this.handleWorkerMessage(plugin.id, data);
+      },
+      onCrash: (reason) => {
+        void this.handleWorkerCrash(plugin.id, reason);
+      },
     });
 
     const session: WorkerSession = {
@@ -1075,18 +1284,6 @@ export class PluginManager {
       pending: new Map(),
     };
 
-    worker.onmessage = (event: MessageEvent<unknown>) => {
-      void this.handleWorkerMessage(plugin.id, event.data);
-    };
-
-    worker.onerror = (event) => {
-      void this.handleWorkerCrash(plugin.id, event.message || 'Worker crashed');
-    };
-
-    worker.onmessageerror = () => {
-      void this.handleWorkerCrash(plugin.id, 'Worker message error');
-    };
-
     this.sessions.set(plugin.id, session);
 
     const initMessage: HostToWorkerMessage = {
@@ -1491,6 +1688,17 @@ export class PluginManager {
         return;
       }
 
+      case 'worker:pong': {
+        const session = this.sessions.get(pluginId);
+        if (session?.liveness?.requestId !== message.requestId) {
+          return;
+        }
+
+        clearTimeout(session.liveness.timeoutId);
+        session.liveness = undefined;
+        return;
+      }
+
       case 'worker:shutdown-complete': {
         const session = this.sessions.get(pluginId);
         if (!session?.shutdown) {
@@ -1546,7 +1754,9 @@ export class PluginManager {
       | 'inline-annotations'
       | 'ui-control'
       | 'ui-panel-action'
-      | 'ui-evaluate',
+      | 'ui-evaluate'
+      | 'scheduled-task'
+      | 'bus-event',
     id: string,
     payload: unknown
   ): Promise<unknown> {
@@ -1566,6 +1776,7 @@ export class PluginManager {
         session.pending.delete(requestId);
         const message = `Plugin invocation timed out: ${pluginId}:${method}:${id}`;
         void this.recordDiagnostic(pluginId, 'invocation-timeout', message, `${method}:${id}`);
+        this.checkWorkerLiveness(pluginId);
         reject(new Error(message));
       }, INVOKE_TIMEOUT_MS);
 
@@ -1585,6 +1796,34 @@ export class PluginManager {
     });
   }
 
+  /**
+   * A timed-out invocation may just be slow async work. Ping the worker: if
+   * its event loop cannot answer, the plugin is stuck in synchronous code, so
+   * the worker is terminated and counted as a crash.
+   */
+  private checkWorkerLiveness(pluginId: string): void {
+    const session = this.sessions.get(pluginId);
+    if (!session || session.shuttingDown || session.liveness) {
+      return;
+    }
+
+    const requestId = nextRequestId(`host-ping-${pluginId}`);
+    const timeoutId = setTimeout(() => {
+      session.liveness = undefined;
+      if (this.sessions.get(pluginId) !== session) {
+        return;
+      }
+
+      void this.handleWorkerCrash(
+        pluginId,
+        `Worker unresponsive for ${LIVENESS_TIMEOUT_MS}ms after an invocation timeout`
+      );
+    }, LIVENESS_TIMEOUT_MS);
+
+    session.liveness = { requestId, timeoutId };
+    session.worker.postMessage({ type: 'host:ping', requestId } satisfies HostToWorkerMessage);
+  }
+
   private async handleWorkerCrash(pluginId: string, reason: string): Promise<void> {
     const session = this.sessions.get(pluginId);
     if (!session) {
@@ -1601,6 +1840,10 @@ export class PluginManager {
     session.worker.terminate();
     this.sessions.delete(pluginId);
     this.activationStates.set(pluginId, 'failed');
+    if (session.liveness) {
+      clearTimeout(session.liveness.timeoutId);
+      session.liveness = undefined;
+    }
 
     for (const [requestId, pending] of session.pending.entries()) {
       clearTimeout(pending.timeoutId);
@@ -1608,24 +1851,32 @@ export class PluginManager {
       session.pending.delete(requestId);
     }
 
-    const previousCrashes = this.crashCounts.get(pluginId) ?? 0;
-    await this.recordDiagnostic(pluginId, 'runtime-crash', reason, 'worker');
-
-    const crashes = this.crashCounts.get(pluginId) ?? previousCrashes + 1;
-    this.crashCounts.set(pluginId, crashes);
-
-    if (crashes >= MAX_CRASH_COUNT) {
-      try {
-        await invoke('plugin_enable_disable', {
-          pluginId,
-          enabled: false,
-        });
-      } catch (error) {
-        console.error(`[PluginManager] Failed to auto-disable plugin ${pluginId}`, error);
-      }
+    let outcome: CrashOutcome;
+    try {
+      outcome = await invoke<CrashOutcome>('plugin_report_crash', {
+        pluginId,
+        message: reason,
+      });
+    } catch (error) {
+      console.error(`[PluginManager] Failed to report crash for ${pluginId}`, error);
+      return;
+    }
 
+    this.crashCounts.set(pluginId, outcome.crashCount);
+    if (outcome.disabled) {
+      // The backend disabled the plugin and emitted plugin-auto-disabled.
       await this.reloadInstalledPlugins();
+      return;
+    }
+
+    const plugins = await invoke<InstalledPlugin[]>('plugin_list_installed', {
+      locale: navigator.language,
+    });
+    const updated = plugins.find((plugin) => plugin.id === pluginId);
+    if (updated) {
+      this.replaceInstalledPlugin(updated);
     }
+    this.notifyListeners();
   }
 
   private async disposeAllWorkers(): Promise<void> {
@@ -1634,6 +1885,10 @@ export class PluginManager {
 
     for (const session of sessions) {
       session.shuttingDown = true;
+      if (session.liveness) {
+        clearTimeout(session.liveness.timeoutId);
+        session.liveness = undefined;
+      }
 
       for (const [requestId, pending] of session.pending.entries()) {
         clearTimeout(pending.timeoutId);
@@ -1743,9 +1998,22 @@ export class PluginManager {
   }
 
   private replaceInstalledPlugin(updated: InstalledPlugin): void {
-    this.installedPlugins = this.installedPlugins.map((plugin) =>
-      plugin.id === updated.id ? updated : plugin
-    );
+    this.installedPlugins = this.installedPlugins.map((plugin) => {
+      if (plugin.id !== updated.id) {
+        return plugin;
+      }
+      // Only plugin_list_installed localizes; keep the resolved labels.
+      if (plugin.locale && plugin.version === updated.version) {
+        return {
+          ...updated,
+          name: plugin.name,
+          description: plugin.description,
+          manifest: plugin.manifest,
+          locale: plugin.locale,
+        };
+      }
+      return updated;
+    });
   }
 
   private getContributionIndex(pluginId: string): ManifestContributionIndex {
@@ -1790,6 +2058,8 @@ function normalizeManifestContributions(contributes: PluginContributions | undef
       uiControls: [],
       uiPanels: [],
       transforms: [],
+      themes: [],
+      dictionaries: [],
     };
   }
 
@@ -1805,6 +2075,29 @@ function normalizeManifestContributions(contributes: PluginContributions | undef
       })
     : [];
 
+  const themes = Array.isArray(contributes.themes)
+    ? contributes.themes.filter((theme): theme is ContributedTheme => {
+        return Boolean(
+          theme
+          && typeof theme.id === 'string'
+          && typeof theme.path === 'string'
+          && (theme.base === 'light' || theme.base === 'dark')
+        );
+      })
+    : [];
+
+  const dictionaries = Array.isArray(contributes.dictionaries)
+    ? contributes.dictionaries.filter((dictionary): dictionary is ContributedDictionary => {
+        return Boolean(
+          dictionary
+          && typeof dictionary.id === 'string'
+          && typeof dictionary.language === 'string'
+          && typeof dictionary.path === 'string'
+          && (dictionary.kind === 'language' || dictionary.kind === 'wordlist')
+        );
+      })
+    : [];
+
   return {
     commands: Array.isArray(contributes.commands) ? contributes.commands : [],
     menus: Array.isArray(contributes.menus) ? contributes.menus : [],
@@ -1824,6 +2117,8 @@ function normalizeManifestContributions(contributes: PluginContributions | undef
     uiControls: Array.isArray(contributes.uiControls) ? contributes.uiControls : [],
     uiPanels: Array.isArray(contributes.uiPanels) ? contributes.uiPanels : [],
     transforms,
+    themes,
+    dictionaries,
   };
 }
 
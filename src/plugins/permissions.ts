@@ -14,18 +14,32 @@ export const CORE_PERMISSIONS: CorePermission[] = [
 ];
 
 export const OPTIONAL_PERMISSIONS: OptionalPermission[] = [
+  'document:selection',
   'fs:pick-read',
   'fs:pick-write',
   'network:https',
+  'network:credentials',
+  'clipboard:read',
+  'clipboard:write',
+  'ui:notify',
+  'scheduler:tasks',
+  'events:bus',
   'ui:mount',
   'editor:annotations',
   'system:fonts',
 ];
 
 export const PERMISSION_DESCRIPTIONS: Record<OptionalPermission, string> = {
+  'document:selection': 'Read and replace only the text you have selected, not the whole script.',
   'fs:pick-read': 'Ask you to choose a local file the plugin can read.',
   'fs:pick-write': 'Ask you to choose a local destination the plugin can write.',
   'network:https': 'Make HTTPS requests to hosts declared in the plugin allowlist.',
+  'network:credentials': 'Send authorization headers or cookies with its HTTPS requests.',
+  'clipboard:read': 'Read text from your clipboard.',
+  'clipboard:write': 'Put text or formatted content on your clipboard.',
+  'ui:notify': 'Show system notifications, for example when a long task finishes.',
+  'scheduler:tasks': 'Run background tasks on a schedule while Grainery is open.',
+  'events:bus': 'Exchange events with other installed plugins.',
   'ui:mount': 'Render host-controlled toolbar controls or side panels.',
   'editor:annotations': 'Show host-rendered inline annotations in the editor.',
   'system:fonts': 'List installed font family names and variants.',
@@ -0,0 +1,80 @@
+import { emitTo } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+import {
+  EDITOR_WINDOW_LABEL,
+  FROM_PLUGIN_PROCESS_EVENT,
+  TO_PLUGIN_PROCESS_EVENT,
+  type FromPluginProcessMessage,
+  type ToPluginProcessMessage,
+} from './process';
+
+/**
+ * Entry point of the hidden plugin process window: starts one worker per
+ * plugin and relays messages between the workers and the editor window.
+ */
+export async function runPluginProcess(): Promise<void> {
+  const workers = new Map<string, Worker>();
+  let outbox: Promise<void> = Promise.resolve();
+
+  const send = (message: FromPluginProcessMessage) => {
+    outbox = outbox
+      .then(() => emitTo(EDITOR_WINDOW_LABEL, FROM_PLUGIN_PROCESS_EVENT, message))
+      .catch((error) => {
+        console.error(`[PluginProcess] Failed to send ${message.type}`, error);
+      });
+  };
+
+  const spawn = (workerId: string) => {
+    const worker = new Worker(new URL('./worker-runtime.ts', import.meta.url), {
+      type: 'module',
+    });
+
+    worker.onmessage = (event: MessageEvent<unknown>) => {
+      send({ type: 'message', workerId, data: event.data });
+    };
+
+    worker.onerror = (event) => {
+      send({ type: 'crash', workerId, reason: event.message || 'Worker crashed' });
+    };
+
+    worker.onmessageerror = () => {
+      send({ type: 'crash', workerId, reason: 'Worker message error' });
+    };
+
+    workers.set(workerId, worker);
+  };
+
+  const terminate = (workerId: string) => {
+    workers.get(workerId)?.terminate();
+    workers.delete(workerId);
+  };
+
+  await getCurrentWebviewWindow().listen<ToPluginProcessMessage>(
+    TO_PLUGIN_PROCESS_EVENT,
+    (event) => {
+      const message = event.payload;
+      switch (message.type) {
+        case 'reset':
+          for (const workerId of Array.from(workers.keys())) {
+            terminate(workerId);
+          }
+          send({ type: 'ready' });
+          return;
+        case 'ping':
+          send({ type: 'pong', requestId: message.requestId });
+          return;
+        case 'spawn':
+          spawn(message.workerId);
+          return;
+        case 'post':
+          workers.get(message.workerId)?.postMessage(message.message);
+          return;
+        case 'terminate':
+          terminate(message.workerId);
+          return;
+      }
+    }
+  );
+
+  send({ type: 'ready' });
+}
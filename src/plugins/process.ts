@@ -0,0 +1,219 @@
+import { invoke } from '@tauri-apps/api/core';
+import { emitTo, type UnlistenFn } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+import { nextRequestId } from './rpc';
+import type { HostToWorkerMessage } from './types';
+
+/** Hidden window whose web content process runs the plugin workers. */
+export const PLUGIN_PROCESS_WINDOW_LABEL = 'plugin-process';
+export const EDITOR_WINDOW_LABEL = 'main';
+
+export const TO_PLUGIN_PROCESS_EVENT = 'plugin-process:inbound';
+export const FROM_PLUGIN_PROCESS_EVENT = 'plugin-process:outbound';
+
+export type ToPluginProcessMessage =
+  | { type: 'reset' }
+  | { type: 'ping'; requestId: string }
+  | { type: 'spawn'; workerId: string }
+  | { type: 'post'; workerId: string; message: HostToWorkerMessage }
+  | { type: 'terminate'; workerId: string };
+
+export type FromPluginProcessMessage =
+  | { type: 'ready' }
+  | { type: 'pong'; requestId: string }
+  | { type: 'message'; workerId: string; data: unknown }
+  | { type: 'crash'; workerId: string; reason: string };
+
+/** The part of `Worker` the plugin manager uses. */
+export interface PluginWorker {
+  postMessage(message: HostToWorkerMessage): void;
+  terminate(): void;
+}
+
+export interface PluginWorkerHandlers {
+  onMessage: (data: unknown) => void;
+  onCrash: (reason: string) => void;
+}
+
+const CONNECT_TIMEOUT_MS = 15_000;
+const HEARTBEAT_INTERVAL_MS = 5_000;
+const HEARTBEAT_TIMEOUT_MS = 3_000;
+
+/**
+ * Editor side of the plugin process. Workers are spawned in the hidden
+ * `plugin-process` window and reached over Tauri events, so a plugin that
+ * exhausts memory or wedges its renderer takes down that process instead of
+ * the editor. While workers run, the process is pinged; when it stops
+ * answering it is killed and restarted, and its workers are reported as
+ * crashed.
+ */
+export class PluginProcess {
+  private readonly workers = new Map<string, PluginWorkerHandlers>();
+  private readonly pings = new Map<string, () => void>();
+  private unlisten: UnlistenFn | null = null;
+  private connection: Promise<void> | null = null;
+  private markReady: (() => void) | null = null;
+  private connected = false;
+  private outbox: Promise<void> = Promise.resolve();
+  private heartbeat: ReturnType<typeof setInterval> | null = null;
+  private restarting = false;
+
+  spawn(handlers: PluginWorkerHandlers): PluginWorker {
+    const workerId = nextRequestId('plugin-worker');
+    this.workers.set(workerId, handlers);
+    this.startHeartbeat();
+    this.send({ type: 'spawn', workerId });
+
+    return {
+      postMessage: (message) => {
+        if (this.workers.has(workerId)) {
+          this.send({ type: 'post', workerId, message });
+        }
+      },
+      terminate: () => {
+        if (this.workers.delete(workerId)) {
+          this.send({ type: 'terminate', workerId });
+        }
+        if (this.workers.size === 0) {
+          this.stopHeartbeat();
+        }
+      },
+    };
+  }
+
+  /** Whether the plugin process answers within `timeoutMs`. */
+  ping(timeoutMs: number): Promise<boolean> {
+    const requestId = nextRequestId('plugin-process-ping');
+    return new Promise<boolean>((resolve) => {
+      const timeoutId = setTimeout(() => {
+        this.pings.delete(requestId);
+        resolve(false);
+      }, timeoutMs);
+      this.pings.set(requestId, () => {
+        clearTimeout(timeoutId);
+        resolve(true);
+      });
+      this.send({ type: 'ping', requestId });
+    });
+  }
+
+  /** Kills the plugin process and reports every worker in it as crashed. */
+  async restart(reason: string): Promise<void> {
+    if (this.restarting) {
+      return;
+    }
+
+    this.restarting = true;
+    const crashed = Array.from(this.workers.values());
+    this.workers.clear();
+    this.stopHeartbeat();
+    this.connection = null;
+    this.connected = false;
+    try {
+      await invoke('plugin_process_restart');
+    } catch (error) {
+      console.error('[PluginProcess] Failed to restart plugin process', error);
+    } finally {
+      this.restarting = false;
+    }
+
+    for (const handlers of crashed) {
+      handlers.onCrash(reason);
+    }
+  }
+
+  private send(message: ToPluginProcessMessage): void {
+    this.outbox = this.outbox
+      .then(() => this.connect())
+      .then(() => emitTo(PLUGIN_PROCESS_WINDOW_LABEL, TO_PLUGIN_PROCESS_EVENT, message))
+      .catch((error) => {
+        console.error(`[PluginProcess] Failed to send ${message.type}`, error);
+      });
+  }
+
+  /**
+   * Starts the plugin process if needed and waits until it has dropped any
+   * workers left from an earlier editor session.
+   */
+  private connect(): Promise<void> {
+    if (this.connection) {
+      return this.connection;
+    }
+
+    const connection = (async () => {
+      if (!this.unlisten) {
+        this.unlisten = await getCurrentWebviewWindow().listen<FromPluginProcessMessage>(
+          FROM_PLUGIN_PROCESS_EVENT,
+          (event) => this.receive(event.payload)
+        );
+      }
+
+      const ready = new Promise<void>((resolve, reject) => {
+        const timeoutId = setTimeout(() => {
+          this.markReady = null;
+          reject(new Error('Plugin process did not start'));
+        }, CONNECT_TIMEOUT_MS);
+        this.markReady = () => {
+          clearTimeout(timeoutId);
+          this.markReady = null;
+          resolve();
+        };
+      });
+
+      await invoke('plugin_process_start');
+      await emitTo(PLUGIN_PROCESS_WINDOW_LABEL, TO_PLUGIN_PROCESS_EVENT, {
+        type: 'reset',
+      } satisfies ToPluginProcessMessage);
+      await ready;
+      this.connected = true;
+    })();
+
+    this.connection = connection.catch((error) => {
+      this.connection = null;
+      throw error;
+    });
+    return this.connection;
+  }
+
+  private receive(message: FromPluginProcessMessage): void {
+    switch (message.type) {
+      case 'ready':
+        this.markReady?.();
+        return;
+      case 'pong':
+        this.pings.get(message.requestId)?.();
+        this.pings.delete(message.requestId);
+        return;
+      case 'message':
+        this.workers.get(message.workerId)?.onMessage(message.data);
+        return;
+      case 'crash':
+        this.workers.get(message.workerId)?.onCrash(message.reason);
+        return;
+    }
+  }
+
+  private startHeartbeat(): void {
+    if (this.heartbeat) {
+      return;
+    }
+
+    this.heartbeat = setInterval(() => {
+      if (!this.connected || this.restarting) {
+        return;
+      }
+      void this.ping(HEARTBEAT_TIMEOUT_MS).then((alive) => {
+        if (!alive && this.workers.size > 0) {
+          void this.restart(`Plugin process unresponsive for ${HEARTBEAT_TIMEOUT_MS}ms`);
+        }
+      });
+    }, HEARTBEAT_INTERVAL_MS);
+  }
+
+  private stopHeartbeat(): void {
+    if (this.heartbeat) {
+      clearInterval(this.heartbeat);
+      this.heartbeat = null;
+    }
+  }
+}
@@ -46,6 +46,7 @@ export function parseWorkerMessage(value: unknown): WorkerToHostMessage | null {
     case 'worker:permission-request':
     case 'worker:response':
     case 'worker:shutdown-complete':
+    case 'worker:pong':
       return value as unknown as WorkerToHostMessage;
     default:
       return null;
@@ -62,6 +63,7 @@ export function parseHostMessage(value: unknown): HostToWorkerMessage | null {
     case 'host:invoke':
     case 'host:response':
     case 'host:shutdown':
+    case 'host:ping':
       return value as unknown as HostToWorkerMessage;
     default:
       return null;
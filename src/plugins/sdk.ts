@@ -6,11 +6,13 @@ export type {
   ContributedCommandMenu,
   ContributedConfiguration,
   ContributedConfigurationProperty,
+  ContributedDictionary,
   ContributedExporter,
   ContributedImporter,
   ContributedInlineAnnotationProvider,
   ContributedKeybinding,
   ContributedStatusBadge,
+  ContributedTheme,
   ContributedTransform,
   ContributedUIControl,
   ContributedUIPanel,
@@ -41,9 +43,11 @@ export type {
   PluginCommand,
   PluginCommandContext,
   PluginContributions,
+  PluginDocumentSelection,
   PluginManifest,
   PluginManifestEngine,
   PluginPermission,
+  PluginSelectionRange,
   PluginSignature,
   PluginStorage,
   ProposedPluginApi,
@@ -12,9 +12,16 @@ export type CorePermission =
   | 'export:register';
 
 export type OptionalPermission =
+  | 'document:selection'
   | 'fs:pick-read'
   | 'fs:pick-write'
   | 'network:https'
+  | 'network:credentials'
+  | 'clipboard:read'
+  | 'clipboard:write'
+  | 'ui:notify'
+  | 'scheduler:tasks'
+  | 'events:bus'
   | 'ui:mount'
   | 'editor:annotations'
   | 'system:fonts';
@@ -41,6 +48,7 @@ export type PluginActivationEvent =
   | `onUIPanel:${string}`
   | `onStatusBadge:${string}`
   | `onInlineAnnotations:${string}`
+  | `onSchedule:${string}`
   | `onTransform:${DocumentTransformHook}`;
 
 export interface ContributedCommand {
@@ -152,6 +160,25 @@ export interface ContributedTransform {
   priority?: number;
 }
 
+export type ThemeBase = 'light' | 'dark';
+
+export interface ContributedTheme {
+  id: string;
+  label: string;
+  base: ThemeBase;
+  path: string;
+}
+
+export type DictionaryKind = 'language' | 'wordlist';
+
+export interface ContributedDictionary {
+  id: string;
+  label: string;
+  language: string;
+  kind: DictionaryKind;
+  path: string;
+}
+
 export interface PluginContributions {
   commands: ContributedCommand[];
   menus: ContributedCommandMenu[];
@@ -164,10 +191,12 @@ export interface PluginContributions {
   uiControls: ContributedUIControl[];
   uiPanels: ContributedUIPanel[];
   transforms: ContributedTransform[];
+  themes: ContributedTheme[];
+  dictionaries: ContributedDictionary[];
 }
 
 export interface PluginManifest {
-  schemaVersion: 1;
+  schemaVersion: 1 | 2;
   id: string;
   name: string;
   version: string;
@@ -182,10 +211,12 @@ export interface PluginManifest {
   enabledApiProposals?: string[];
   permissionRationales?: Partial<Record<OptionalPermission, string>>;
   signature?: PluginSignature;
+  defaultLocale?: string;
 }
 
 export type PluginInstallSource = 'sideload' | 'registry';
 export type PluginTrustState = 'verified' | 'unverified';
+export type PluginChannel = 'stable' | 'beta';
 
 export interface PluginPermissionGrant {
   permission: OptionalPermission;
@@ -225,6 +256,71 @@ export interface InstalledPlugin {
   networkAllowlist: string[];
   manifest: PluginManifest;
   grantedPermissions: PluginPermissionGrant[];
+  autoDisableCount?: number;
+  reenableAt?: string | null;
+  revocation?: PluginRevocation | null;
+  updateChannel?: PluginChannel;
+  locale?: string | null;
+}
+
+export interface PluginDiskUsage {
+  pluginId: string;
+  installBytes: number;
+  dataBytes: number;
+  storageBytes: number;
+  totalBytes: number;
+  quotaBytes: number;
+}
+
+export interface PluginPerformance {
+  pluginId: string;
+  name: string;
+  activationCount: number;
+  lastActivationEvent: string | null;
+  lastActivationMs: number | null;
+  totalActivationMs: number;
+  hostCalls: number;
+  failedHostCalls: number;
+  hostCallMs: number;
+  networkBytesSent: number;
+  networkBytesReceived: number;
+}
+
+export interface PluginTheme {
+  pluginId: string;
+  id: string;
+  label: string;
+  base: ThemeBase;
+}
+
+export interface PluginThemeTokens {
+  pluginId: string;
+  id: string;
+  base: ThemeBase;
+  tokens: Record<string, string>;
+}
+
+export interface PluginDictionary {
+  pluginId: string;
+  id: string;
+  label: string;
+  language: string;
+  kind: DictionaryKind;
+}
+
+export interface PluginDictionaryWords {
+  pluginId: string;
+  id: string;
+  language: string;
+  kind: DictionaryKind;
+  words: string[];
+}
+
+export interface PluginRevocation {
+  pluginId: string;
+  versions: string[];
+  reason: string;
+  revokedAt?: string | null;
 }
 
 export interface PluginRegistryEntry {
@@ -237,6 +333,7 @@ export interface PluginRegistryEntry {
   sha256: string;
   signatureKeyId: string;
   signature: string;
+  channel?: PluginChannel;
 }
 
 export interface PluginLockRecord {
@@ -259,11 +356,33 @@ export type HostOperation =
   | 'document:replace'
   | 'document:get-plugin-data'
   | 'document:set-plugin-data'
+  | 'document:get-selection'
+  | 'document:replace-selection'
   | 'plugin:get-global-data'
   | 'plugin:set-global-data'
   | 'network:get_json'
   | 'network:get_text'
+  | 'network:request'
+  | 'network:download'
+  | 'clipboard:read'
+  | 'clipboard:write'
+  | 'ui:notify'
+  | 'scheduler:register'
+  | 'scheduler:cancel'
+  | 'scheduler:list'
+  | 'events:publish'
+  | 'events:subscribe'
+  | 'events:unsubscribe'
   | 'system:list_fonts'
+  | 'storage:get'
+  | 'storage:set'
+  | 'storage:delete'
+  | 'storage:list'
+  | 'fs:read'
+  | 'fs:write'
+  | 'fs:list'
+  | 'fs:pick-read'
+  | 'fs:pick-write'
   | 'audit:log';
 
 export type ElementLoopEvent = 'tab' | 'shift-tab' | 'enter' | 'escape';
@@ -677,6 +796,62 @@ export interface Disposable {
   dispose(): void | Promise<void>;
 }
 
+export interface ScheduledTaskContext {
+  taskId: string;
+  scheduledAt: string;
+}
+
+export interface ScheduledTaskDefinition {
+  id: string;
+  /** Run every N seconds (at least 60). */
+  intervalSeconds?: number;
+  /** Five-field cron expression in local time, e.g. `0 9 * * 1-5`. */
+  cron?: string;
+  handler: (context: ScheduledTaskContext) => void | Promise<void>;
+}
+
+export type TaskSchedule =
+  | { kind: 'interval'; seconds: number }
+  | { kind: 'cron'; expression: string };
+
+export interface ScheduledTask {
+  pluginId: string;
+  taskId: string;
+  schedule: TaskSchedule;
+  createdAt: string;
+  nextRunAt: string;
+  lastRunAt?: string | null;
+}
+
+export interface PluginBusEvent<T = unknown> {
+  /** Full topic, `<publisher plugin id>/<name>`. */
+  topic: string;
+  source: string;
+  data: T;
+}
+
+export interface PluginEventsApi {
+  /** Publishes `data` on `<this plugin id>/<name>` (requires `events:bus`). */
+  publish(name: string, data?: unknown): Promise<void>;
+  /**
+   * Subscribes to `<plugin id>/<name>` or `<plugin id>/*` (requires
+   * `events:bus`). Events are only delivered while this plugin is running.
+   */
+  subscribe<T = unknown>(
+    topic: string,
+    handler: (event: PluginBusEvent<T>) => void | Promise<void>
+  ): Promise<Disposable>;
+}
+
+export interface PluginSelectionRange {
+  from: number;
+  to: number;
+}
+
+export interface PluginDocumentSelection extends PluginSelectionRange {
+  text: string;
+}
+
 export interface PluginStorage<T = unknown> {
   get(): Promise<T>;
   set(value: T): Promise<void>;
@@ -709,11 +884,25 @@ export interface PluginApi {
   registerInlineAnnotationProvider(provider: InlineAnnotationProvider): Disposable;
   registerUIControl(control: UIControlDefinition): Disposable;
   registerUIPanel(panel: UIPanelDefinition): Disposable;
+  /**
+   * Persists a schedule with the host (requires `scheduler:tasks`). The
+   * schedule survives restarts; declare `onSchedule:<id>` so the plugin is
+   * activated when it fires. Disposing removes only the handler.
+   */
+  registerScheduledTask(task: ScheduledTaskDefinition): Promise<Disposable>;
   getDocument(): Promise<JSONContent>;
   replaceDocument(next: JSONContent): Promise<void>;
+  /** Requires `document:selection` or `document:read`. */
+  getSelection(): Promise<PluginDocumentSelection>;
+  /**
+   * Replaces the text of `selection`, which must still be the current
+   * selection. Requires `document:selection` or `document:write`.
+   */
+  replaceSelection(selection: PluginSelectionRange, text: string): Promise<void>;
   getPluginData<T = unknown>(): Promise<T | null>;
   setPluginData(value: unknown): Promise<void>;
   screenplay: ScreenplayMutationApi;
+  events: PluginEventsApi;
   requestPermission(permission: OptionalPermission): Promise<boolean>;
   hostCall<T>(operation: HostOperation, payload: unknown): Promise<T>;
   proposed?: ProposedPluginApi;
@@ -743,7 +932,9 @@ export interface HostInvokeMessage {
     | 'inline-annotations'
     | 'ui-control'
     | 'ui-panel-action'
-    | 'ui-evaluate';
+    | 'ui-evaluate'
+    | 'scheduled-task'
+    | 'bus-event';
   id: string;
   payload: unknown;
 }
@@ -760,11 +951,17 @@ export interface HostShutdownMessage {
   type: 'host:shutdown';
 }
 
+export interface HostPingMessage {
+  type: 'host:ping';
+  requestId: string;
+}
+
 export type HostToWorkerMessage =
   | HostInitMessage
   | HostInvokeMessage
   | HostResponseMessage
-  | HostShutdownMessage;
+  | HostShutdownMessage
+  | HostPingMessage;
 
 export interface WorkerReadyMessage {
   type: 'worker:ready';
@@ -880,6 +1077,12 @@ export interface WorkerShutdownCompleteMessage {
   error?: string;
 }
 
+export interface WorkerPongMessage {
+  type: 'worker:pong';
+  pluginId: string;
+  requestId: string;
+}
+
 export type WorkerToHostMessage =
   | WorkerReadyMessage
   | WorkerErrorMessage
@@ -896,7 +1099,8 @@ export type WorkerToHostMessage =
   | WorkerHostRequestMessage
   | WorkerPermissionRequestMessage
   | WorkerResponseMessage
-  | WorkerShutdownCompleteMessage;
+  | WorkerShutdownCompleteMessage
+  | WorkerPongMessage;
 
 export interface PluginStateSnapshot {
   installedPlugins: InstalledPlugin[];
@@ -21,11 +21,15 @@ import type {
   InlineAnnotationProvider,
   Importer,
   PluginApi,
+  PluginBusEvent,
+  PluginEventsApi,
   PluginStorage,
   PluginCommand,
   PluginCommandContext,
   PluginManifest,
   ProposedPluginApi,
+  ScheduledTaskContext,
+  ScheduledTaskDefinition,
   ScreenplayMutationApi,
   StatusBadge,
   StatusBadgeContext,
@@ -65,6 +69,8 @@ const uiControlActiveHandlers = new Map<
 >();
 const uiPanelActionHandlers = new Map<string, NonNullable<UIPanelDefinition['onAction']>>();
 const uiPanelRenderHandlers = new Map<string, NonNullable<UIPanelDefinition['onRender']>>();
+const scheduledTaskHandlers = new Map<string, ScheduledTaskDefinition['handler']>();
+const busEventHandlers = new Map<string, Set<(event: PluginBusEvent) => void | Promise<void>>>();
 
 const pendingHostRequests = new Map<
   string,
@@ -299,6 +305,35 @@ function createProposedApi(enabledApiProposals: string[] | undefined): ProposedP
   return {};
 }
 
+function busTopicMatches(pattern: string, topic: string): boolean {
+  return pattern.endsWith('/*') ? topic.startsWith(pattern.slice(0, -1)) : pattern === topic;
+}
+
+function createEventsApi(): PluginEventsApi {
+  return {
+    async publish(name, data) {
+      await requestHost('events:publish', { topic: name, data: data ?? null });
+    },
+    async subscribe(topic, handler) {
+      await requestHost('events:subscribe', { topic });
+      const handlers = busEventHandlers.get(topic) ?? new Set();
+      const listener = handler as (event: PluginBusEvent) => void | Promise<void>;
+      handlers.add(listener);
+      busEventHandlers.set(topic, handlers);
+
+      return {
+        async dispose() {
+          handlers.delete(listener);
+          if (handlers.size === 0 && busEventHandlers.get(topic) === handlers) {
+            busEventHandlers.delete(topic);
+            await requestHost('events:unsubscribe', { topic });
+          }
+        },
+      };
+    },
+  };
+}
+
 function createPluginApi(manifest: PluginManifest): PluginApi {
   const proposed = createProposedApi(manifest.enabledApiProposals);
   const screenplay = createScreenplayApi();
@@ -480,12 +515,36 @@ function createPluginApi(manifest: PluginManifest): PluginApi {
         uiPanelRenderHandlers.delete(panel.id);
       });
     },
+    async registerScheduledTask(task) {
+      throwIfInvalidPluginId();
+      await requestHost('scheduler:register', {
+        id: task.id,
+        intervalSeconds: task.intervalSeconds,
+        cron: task.cron,
+      });
+      scheduledTaskHandlers.set(task.id, task.handler);
+      return {
+        dispose() {
+          scheduledTaskHandlers.delete(task.id);
+        },
+      };
+    },
     getDocument() {
       return requestHost('document:get', null);
     },
     replaceDocument(next) {
       return requestHost('document:replace', next);
     },
+    getSelection() {
+      return requestHost('document:get-selection', null);
+    },
+    replaceSelection(selection, text) {
+      return requestHost('document:replace-selection', {
+        from: selection.from,
+        to: selection.to,
+        text,
+      }).then(() => undefined);
+    },
     getPluginData<T = unknown>() {
       return requestHost<T | null>('document:get-plugin-data', null);
     },
@@ -493,6 +552,7 @@ function createPluginApi(manifest: PluginManifest): PluginApi {
       return requestHost('document:set-plugin-data', { value }).then(() => undefined);
     },
     screenplay,
+    events: createEventsApi(),
     requestPermission(permission) {
       return requestPermission(permission);
     },
@@ -685,6 +745,26 @@ async function handleInvokeMessage(
         respond(true, result ?? { action: null } satisfies UIPanelActionResult);
         return;
       }
+      case 'scheduled-task': {
+        const handler = scheduledTaskHandlers.get(message.id);
+        if (!handler) {
+          throw new Error(`Scheduled task handler not found: ${message.id}`);
+        }
+        await handler(message.payload as ScheduledTaskContext);
+        respond(true, null);
+        return;
+      }
+      case 'bus-event': {
+        const event = message.payload as PluginBusEvent;
+        const listeners = [...busEventHandlers.entries()]
+          .filter(([pattern]) => busTopicMatches(pattern, event.topic))
+          .flatMap(([, handlers]) => [...handlers]);
+        for (const listener of listeners) {
+          await listener(event);
+        }
+        respond(true, null);
+        return;
+      }
       case 'ui-evaluate': {
         const payload = message.payload as {
           controlIds: string[];
@@ -776,6 +856,17 @@ self.onmessage = async (event: MessageEvent<unknown>) => {
     return;
   }
 
+  // Answered before any plugin code runs, so a missing pong means the
+  // worker's event loop is blocked.
+  if (message.type === 'host:ping') {
+    postWorkerMessage({
+      type: 'worker:pong',
+      pluginId: currentPluginId,
+      requestId: message.requestId,
+    });
+    return;
+  }
+
   try {
     switch (message.type) {
       case 'host:init':